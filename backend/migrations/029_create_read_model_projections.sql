@@ -0,0 +1,20 @@
+-- Create denormalized read-model projections for detail endpoints
+-- Migration: 029_create_read_model_projections.sql
+
+CREATE TABLE IF NOT EXISTS anchor_detail_projections (
+    anchor_id TEXT PRIMARY KEY,
+    stellar_account TEXT NOT NULL,
+    payload TEXT NOT NULL, -- JSON-encoded AnchorDetailResponse
+    version INTEGER NOT NULL DEFAULT 1,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS corridor_detail_projections (
+    corridor_key TEXT PRIMARY KEY,
+    payload TEXT NOT NULL, -- JSON-encoded CorridorDetailView
+    version INTEGER NOT NULL DEFAULT 1,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_anchor_detail_projections_account ON anchor_detail_projections(stellar_account);
+CREATE INDEX IF NOT EXISTS idx_corridor_detail_projections_updated ON corridor_detail_projections(updated_at DESC);
@@ -0,0 +1,11 @@
+-- Overflow storage for alert subscribers that fall behind
+-- Migration: 030_create_alert_spill.sql
+
+CREATE TABLE IF NOT EXISTS alert_spill (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    consumer TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_alert_spill_consumer_id ON alert_spill(consumer, id);
@@ -0,0 +1,39 @@
+-- Per-corridor SLA definitions, evaluation history and breach tracking
+-- Migration: 031_create_corridor_slas.sql
+
+CREATE TABLE IF NOT EXISTS corridor_slas (
+    corridor_key TEXT PRIMARY KEY,
+    min_success_rate REAL NOT NULL, -- percent, 0-100
+    max_p95_settlement_ms INTEGER NOT NULL,
+    evaluation_window_hours INTEGER NOT NULL DEFAULT 24,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS corridor_sla_evaluations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    corridor_key TEXT NOT NULL,
+    evaluated_at TEXT NOT NULL,
+    window_start TEXT NOT NULL,
+    total_transactions INTEGER NOT NULL,
+    success_rate REAL NOT NULL,
+    p95_settlement_ms REAL,
+    success_rate_met INTEGER NOT NULL,
+    settlement_time_met INTEGER NOT NULL,
+    compliant INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS corridor_sla_breaches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    corridor_key TEXT NOT NULL,
+    metric TEXT NOT NULL, -- 'success_rate' or 'p95_settlement_ms'
+    threshold REAL NOT NULL,
+    observed REAL NOT NULL,
+    started_at TEXT NOT NULL,
+    resolved_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_corridor_sla_evaluations_corridor ON corridor_sla_evaluations(corridor_key, evaluated_at DESC);
+CREATE INDEX IF NOT EXISTS idx_corridor_sla_breaches_corridor ON corridor_sla_breaches(corridor_key, started_at DESC);
+CREATE INDEX IF NOT EXISTS idx_corridor_sla_breaches_open ON corridor_sla_breaches(corridor_key, metric) WHERE resolved_at IS NULL;
@@ -0,0 +1,18 @@
+-- Mapping of historical snapshot anchors from a retired contract to its replacement
+-- Migration: 032_create_snapshot_reanchors.sql
+
+CREATE TABLE IF NOT EXISTS snapshot_reanchors (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source_contract_id TEXT NOT NULL,
+    target_contract_id TEXT NOT NULL,
+    epoch INTEGER NOT NULL,
+    hash TEXT NOT NULL,
+    status TEXT NOT NULL, -- 'verified', 'already_anchored' or 'failed'
+    target_transaction_hash TEXT,
+    target_ledger INTEGER,
+    error TEXT,
+    reanchored_at TEXT NOT NULL,
+    UNIQUE(source_contract_id, target_contract_id, epoch)
+);
+
+CREATE INDEX IF NOT EXISTS idx_snapshot_reanchors_target ON snapshot_reanchors(target_contract_id, epoch);
@@ -0,0 +1,16 @@
+-- Stored responses for requests sent with an Idempotency-Key header
+-- Migration: 033_create_idempotency_keys.sql
+
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    scope TEXT NOT NULL,              -- hash of the caller's credentials, or 'anonymous'
+    idempotency_key TEXT NOT NULL,
+    request_hash TEXT NOT NULL,       -- sha256 of method, path and body
+    status_code INTEGER,              -- NULL while the first request is still running
+    content_type TEXT,
+    response_body BLOB,
+    created_at TEXT NOT NULL,
+    expires_at TEXT NOT NULL,
+    PRIMARY KEY (scope, idempotency_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_expires ON idempotency_keys(expires_at);
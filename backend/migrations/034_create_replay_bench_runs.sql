@@ -0,0 +1,18 @@
+-- Results of replay engine benchmark runs (`replay --bench`)
+-- Migration: 034_create_replay_bench_runs.sql
+
+CREATE TABLE IF NOT EXISTS replay_bench_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    version TEXT NOT NULL,            -- crate version the run was built from
+    git_sha TEXT,
+    corpus_events INTEGER NOT NULL,
+    batch_size INTEGER NOT NULL,
+    duration_ms REAL NOT NULL,
+    events_per_sec REAL NOT NULL,
+    allocations INTEGER,              -- NULL when allocation tracking was unavailable
+    allocated_bytes INTEGER,
+    processor_timings TEXT NOT NULL,  -- JSON array of per-processor timings
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_replay_bench_runs_corpus ON replay_bench_runs(corpus_events, batch_size, created_at DESC);
@@ -0,0 +1,19 @@
+-- History of simulated Soroban resource fees, used to spot storage-driven fee growth
+-- Migration: 035_create_soroban_fee_estimates.sql
+
+CREATE TABLE IF NOT EXISTS soroban_fee_estimates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    target TEXT NOT NULL,             -- 'snapshot_submission' or 'contract_call'
+    contract_id TEXT NOT NULL,
+    function_name TEXT NOT NULL,
+    min_resource_fee INTEGER NOT NULL, -- stroops
+    instructions INTEGER,
+    read_bytes INTEGER,
+    write_bytes INTEGER,
+    footprint_entries INTEGER,
+    latest_ledger INTEGER,
+    estimated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_soroban_fee_estimates_call
+    ON soroban_fee_estimates(contract_id, function_name, estimated_at DESC);
@@ -0,0 +1,14 @@
+-- Operator-maintained country/region for anchors and assets, used to bucket corridors by geography
+-- Migration: 036_create_geography_mappings.sql
+
+CREATE TABLE IF NOT EXISTS geography_mappings (
+    entity_type TEXT NOT NULL,        -- 'anchor' or 'asset'
+    entity_key TEXT NOT NULL,         -- anchor id, or 'CODE:ISSUER' / 'native' for assets
+    country_code TEXT NOT NULL,       -- ISO 3166-1 alpha-2
+    region TEXT NOT NULL,             -- e.g. 'EU', 'West Africa'
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (entity_type, entity_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_geography_mappings_region
+    ON geography_mappings(region);
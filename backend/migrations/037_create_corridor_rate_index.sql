@@ -0,0 +1,20 @@
+-- Compressed, append-only blocks of sampled corridor exchange rates
+-- Migration: 037_create_corridor_rate_index.sql
+
+CREATE TABLE IF NOT EXISTS corridor_rate_blocks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    corridor_key TEXT NOT NULL,
+    start_ts INTEGER NOT NULL,        -- unix seconds of the first sample
+    end_ts INTEGER NOT NULL,          -- unix seconds of the last sample
+    sample_count INTEGER NOT NULL,
+    data BLOB NOT NULL,               -- delta-of-delta timestamps, XOR-encoded rates
+    sealed INTEGER NOT NULL DEFAULT 0, -- sealed blocks are never rewritten
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_corridor_rate_blocks_range
+    ON corridor_rate_blocks(corridor_key, start_ts);
+
+-- At most one block per corridor is still accepting samples
+CREATE UNIQUE INDEX IF NOT EXISTS idx_corridor_rate_blocks_open
+    ON corridor_rate_blocks(corridor_key) WHERE sealed = 0;
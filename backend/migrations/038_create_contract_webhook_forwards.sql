@@ -0,0 +1,13 @@
+-- Contract events already turned into webhook deliveries, so replayed ledgers don't notify twice
+-- Migration: 038_create_contract_webhook_forwards.sql
+
+CREATE TABLE IF NOT EXISTS contract_webhook_forwards (
+    event_id TEXT PRIMARY KEY,        -- Soroban RPC event id
+    contract_id TEXT NOT NULL,
+    event_type TEXT NOT NULL,         -- webhook event type, e.g. 'contract.snapshot_submitted'
+    ledger INTEGER NOT NULL,
+    forwarded_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_contract_webhook_forwards_ledger
+    ON contract_webhook_forwards(ledger);
@@ -0,0 +1,17 @@
+-- Leader leases for singleton background work and the instances competing for them
+-- Migration: 039_create_cluster_leases.sql
+
+CREATE TABLE IF NOT EXISTS cluster_leases (
+    role TEXT PRIMARY KEY,            -- e.g. 'ingestion', 'snapshot_submission', 'digests'
+    holder_id TEXT NOT NULL,          -- instance_id of the current leader
+    acquired_at INTEGER NOT NULL,     -- unix seconds; unchanged while the holder keeps renewing
+    renewed_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL       -- any instance may take the role once this has passed
+);
+
+CREATE TABLE IF NOT EXISTS cluster_instances (
+    instance_id TEXT PRIMARY KEY,
+    region TEXT,
+    started_at INTEGER NOT NULL,
+    last_heartbeat_at INTEGER NOT NULL
+);
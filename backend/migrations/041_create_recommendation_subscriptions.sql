@@ -0,0 +1,10 @@
+-- Telegram chats that opted in to the weekly "corridors to watch" digest
+-- Migration: 041_create_recommendation_subscriptions.sql
+
+CREATE TABLE IF NOT EXISTS recommendation_subscriptions (
+    chat_id INTEGER PRIMARY KEY,
+    region TEXT,                      -- only corridors with a side in this region
+    watchlist TEXT,                   -- comma-separated corridor keys
+    subscribed_at TEXT NOT NULL,
+    last_sent_at TEXT                 -- NULL until the first digest goes out
+);
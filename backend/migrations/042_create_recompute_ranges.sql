@@ -0,0 +1,19 @@
+-- Time ranges left stale by backfills, and progress recomputing their rollups
+-- Migration: 042_create_recompute_ranges.sql
+
+CREATE TABLE IF NOT EXISTS recompute_ranges (
+    id TEXT PRIMARY KEY,
+    source TEXT NOT NULL,                 -- comma-separated backfills that dirtied the range
+    range_start TEXT NOT NULL,            -- first dirty hour, inclusive
+    range_end TEXT NOT NULL,              -- end of the last dirty hour, exclusive
+    status TEXT NOT NULL,                 -- 'pending', 'running', 'completed' or 'failed'
+    hours_total INTEGER NOT NULL,
+    hours_done INTEGER NOT NULL DEFAULT 0, -- hours rebuilt so far; a restart resumes here
+    snapshots_redrafted INTEGER NOT NULL DEFAULT 0,
+    anchored_snapshots INTEGER NOT NULL DEFAULT 0, -- affected but already on-chain
+    error TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_recompute_ranges_status ON recompute_ranges(status, range_start);
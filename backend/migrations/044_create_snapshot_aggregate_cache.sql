@@ -0,0 +1,21 @@
+-- Per-entity snapshot aggregates carried between epochs, so each epoch only
+-- recomputes anchors and corridors whose source rows changed
+-- Migration: 044_create_snapshot_aggregate_cache.sql
+
+CREATE TABLE IF NOT EXISTS snapshot_entity_aggregates (
+    entity_type TEXT NOT NULL,            -- 'anchor' or 'corridor'
+    entity_key TEXT NOT NULL,             -- anchor id or corridor key
+    metrics TEXT NOT NULL,                -- JSON of the snapshot metrics for the entity
+    computed_at TEXT NOT NULL,
+    PRIMARY KEY (entity_type, entity_key)
+);
+
+-- Single row: where the last refresh left off
+CREATE TABLE IF NOT EXISTS snapshot_aggregate_state (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    window_start TEXT NOT NULL,           -- start of the corridor window at the last refresh
+    refreshed_at TEXT NOT NULL            -- rows updated at or after this are dirty
+);
+
+CREATE INDEX IF NOT EXISTS idx_corridor_metrics_hourly_bucket_key
+    ON corridor_metrics_hourly(hour_bucket, corridor_key);
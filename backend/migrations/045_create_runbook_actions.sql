@@ -0,0 +1,21 @@
+-- Operator remediation actions and their progress
+-- Migration: 045_create_runbook_actions.sql
+
+CREATE TABLE IF NOT EXISTS runbook_actions (
+    id TEXT PRIMARY KEY,
+    action TEXT NOT NULL,                 -- 'rebuild_corridor', 'resync_anchor', 'reanchor_epoch' or 'restart_ingestion'
+    target TEXT NOT NULL,                 -- corridor key, anchor id, epoch, or 'ingestion'
+    params TEXT NOT NULL,                 -- JSON of the request
+    status TEXT NOT NULL,                 -- 'pending', 'running', 'completed' or 'failed'
+    steps_total INTEGER NOT NULL,
+    steps_done INTEGER NOT NULL DEFAULT 0, -- a restart resumes at this step
+    current_step TEXT,
+    result TEXT,                          -- JSON left by the last step, if any
+    error TEXT,
+    requested_by TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_runbook_actions_status ON runbook_actions(status, created_at);
+CREATE INDEX IF NOT EXISTS idx_runbook_actions_target ON runbook_actions(action, target);
@@ -0,0 +1,5 @@
+-- Diff reports from `ReplayMode::Diff` sessions
+-- Migration: 046_add_replay_session_reports.sql
+
+-- JSON-encoded ReplayReport; NULL for every other mode
+ALTER TABLE replay_sessions ADD COLUMN report TEXT;
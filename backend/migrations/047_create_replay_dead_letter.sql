@@ -0,0 +1,21 @@
+-- Per-event-type replay metrics and events a replay could not process
+-- Migration: 047_create_replay_dead_letter.sql
+
+-- JSON map of event type to processed/failed/skipped counts
+ALTER TABLE replay_sessions ADD COLUMN event_types TEXT;
+
+CREATE TABLE IF NOT EXISTS replay_dead_letter (
+    session_id TEXT NOT NULL,
+    event_id TEXT NOT NULL,               -- ContractEvent::unique_id, as in processed_events
+    event_type TEXT NOT NULL,
+    ledger_sequence INTEGER NOT NULL,
+    event TEXT NOT NULL,                  -- JSON-encoded ContractEvent
+    error TEXT NOT NULL,                  -- error from the latest attempt
+    attempts INTEGER NOT NULL DEFAULT 1,  -- failed replays and retries so far
+    first_failed_at TIMESTAMP NOT NULL,
+    last_failed_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (session_id, event_id),
+    FOREIGN KEY (session_id) REFERENCES replay_sessions(session_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_replay_dead_letter_type ON replay_dead_letter(session_id, event_type);
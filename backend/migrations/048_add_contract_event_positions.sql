@@ -0,0 +1,9 @@
+-- Position of each replay event within its ledger, so events of one ledger
+-- replay in the order they were applied on chain
+-- Migration: 048_add_contract_event_positions.sql
+
+ALTER TABLE contract_events ADD COLUMN tx_index INTEGER NOT NULL DEFAULT 0;    -- transaction's apply order in the ledger
+ALTER TABLE contract_events ADD COLUMN event_index INTEGER NOT NULL DEFAULT 0; -- event's position in its transaction
+
+CREATE INDEX IF NOT EXISTS idx_contract_events_position
+    ON contract_events(ledger_sequence, tx_index, event_index);
@@ -0,0 +1,5 @@
+-- Integrity checks of replayed epochs against anchored snapshot hashes
+-- Migration: 049_add_replay_session_integrity.sql
+
+-- JSON-encoded IntegrityReport; NULL unless the session was checked against the chain
+ALTER TABLE replay_sessions ADD COLUMN integrity TEXT;
@@ -0,0 +1,39 @@
+-- Network of each replay session, checkpoint and state snapshot, so testnet and
+-- mainnet replays sharing one database never read each other's progress
+-- Migration: 050_add_replay_network.sql
+
+-- Rows from before this were not recorded per network. Sessions are attributed to
+-- the network their filter named, if any, and otherwise to mainnet, the default.
+ALTER TABLE replay_sessions ADD COLUMN network TEXT NOT NULL DEFAULT 'mainnet';
+UPDATE replay_sessions
+SET network = lower(json_extract(config, '$.filter.network'))
+WHERE lower(json_extract(config, '$.filter.network')) IN ('mainnet', 'testnet');
+
+CREATE INDEX IF NOT EXISTS idx_replay_sessions_network ON replay_sessions(network, started_at DESC);
+
+ALTER TABLE replay_checkpoints ADD COLUMN network TEXT NOT NULL DEFAULT 'mainnet';
+UPDATE replay_checkpoints
+SET network = (
+    SELECT network FROM replay_sessions
+    WHERE replay_sessions.session_id = replay_checkpoints.session_id
+)
+WHERE session_id IN (SELECT session_id FROM replay_sessions);
+
+-- State snapshots were keyed by ledger alone; rebuild the table keyed by network too
+CREATE TABLE replay_state_by_network (
+    network TEXT NOT NULL,
+    ledger INTEGER NOT NULL,
+    state_json TEXT NOT NULL, -- JSON-encoded ApplicationState
+    state_hash TEXT NOT NULL, -- SHA-256 hash for verification
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (network, ledger)
+);
+
+INSERT INTO replay_state_by_network (network, ledger, state_json, state_hash, updated_at)
+SELECT 'mainnet', ledger, state_json, state_hash, updated_at FROM replay_state;
+
+DROP TABLE replay_state;
+ALTER TABLE replay_state_by_network RENAME TO replay_state;
+
+CREATE INDEX IF NOT EXISTS idx_replay_state_hash ON replay_state(state_hash);
+CREATE INDEX IF NOT EXISTS idx_replay_state_updated ON replay_state(updated_at DESC);
@@ -0,0 +1,18 @@
+-- Replay state entries moved out of memory once a replay's state outgrows its
+-- memory budget
+-- Migration: 051_create_replay_state_entries.sql
+
+-- Snapshot and verification entries of a network's replayed state, each with the
+-- ledger it was applied at. A state persisted after its entries were moved here
+-- (external_entries = 1) keeps only its ledger and metadata in state_json; its
+-- entries are the rows applied at or below its ledger.
+CREATE TABLE IF NOT EXISTS replay_state_entries (
+    network TEXT NOT NULL,
+    kind TEXT NOT NULL, -- 'snapshot' or 'verification'
+    entry_key TEXT NOT NULL, -- epoch, or epoch:verifier
+    ledger INTEGER NOT NULL,
+    entry_json TEXT NOT NULL,
+    PRIMARY KEY (network, kind, entry_key)
+);
+
+ALTER TABLE replay_state ADD COLUMN external_entries BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,33 @@
+-- Scope of replay state snapshots, so a session only resumes from state rebuilt
+-- with the same contract and event type filter as its own
+-- Migration: 052_add_replay_state_scope.sql
+
+-- States persisted before this did not record their filter, so which sessions
+-- may resume from them is unknown. They are dropped along with their entries.
+CREATE TABLE replay_state_by_scope (
+    network TEXT NOT NULL,
+    scope TEXT NOT NULL, -- EventFilter::state_scope of the replay's filter
+    ledger INTEGER NOT NULL,
+    state_json TEXT NOT NULL, -- JSON-encoded ApplicationState
+    state_hash TEXT NOT NULL, -- SHA-256 hash for verification
+    external_entries BOOLEAN NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (network, scope, ledger)
+);
+
+DROP TABLE replay_state;
+ALTER TABLE replay_state_by_scope RENAME TO replay_state;
+
+CREATE INDEX IF NOT EXISTS idx_replay_state_hash ON replay_state(state_hash);
+CREATE INDEX IF NOT EXISTS idx_replay_state_updated ON replay_state(updated_at DESC);
+
+DROP TABLE replay_state_entries;
+CREATE TABLE replay_state_entries (
+    network TEXT NOT NULL,
+    scope TEXT NOT NULL,
+    kind TEXT NOT NULL, -- 'snapshot' or 'verification'
+    entry_key TEXT NOT NULL, -- epoch, or epoch:verifier
+    ledger INTEGER NOT NULL,
+    entry_json TEXT NOT NULL,
+    PRIMARY KEY (network, scope, kind, entry_key)
+);
@@ -0,0 +1,138 @@
+#!/usr/bin/env python3
+"""Verify a Stellar Insights snapshot audit bundle.
+
+Run from inside the extracted bundle directory:
+
+    tar -xf audit-bundle-epoch-<N>.tar && cd epoch-<N> && python3 verify.py
+
+Checks, using only the Python standard library:
+  1. Every file matches its entry in SHA256SUMS.
+  2. SHA-256 of snapshot.json equals the snapshot hash, and every recorded
+     on-chain anchoring carries that same hash.
+  3. Every Merkle leaf is a record of snapshot.json and its proof leads to the
+     published root; the root recomputed from all leaves matches.
+
+It does not contact the network. Look up the transactions listed in
+anchoring.json on a Stellar explorer or RPC node to confirm the contract call.
+"""
+
+import hashlib
+import json
+import sys
+
+LEAF_PREFIX = b"\x00"
+NODE_PREFIX = b"\x01"
+# Snapshot sections whose records are Merkle leaves; older snapshots may lack some.
+MERKLE_SECTIONS = ("anchor_metrics", "corridor_metrics", "region_pair_metrics")
+
+
+def sha256(data):
+    return hashlib.sha256(data).digest()
+
+
+def leaf_hash(record):
+    return sha256(LEAF_PREFIX + record.encode("utf-8"))
+
+
+def node_hash(left, right):
+    return sha256(NODE_PREFIX + left + right)
+
+
+def merkle_root(leaves):
+    if not leaves:
+        return sha256(b"")
+    level = leaves
+    while len(level) > 1:
+        nxt = []
+        for i in range(0, len(level), 2):
+            if i + 1 < len(level):
+                nxt.append(node_hash(level[i], level[i + 1]))
+            else:
+                nxt.append(level[i])
+        level = nxt
+    return level[0]
+
+
+def check_checksums(failures):
+    with open("SHA256SUMS", encoding="utf-8") as f:
+        for line in f:
+            if not line.strip():
+                continue
+            expected, name = line.split(None, 1)
+            name = name.strip()
+            with open(name, "rb") as target:
+                actual = hashlib.sha256(target.read()).hexdigest()
+            if actual != expected:
+                failures.append(f"{name}: checksum mismatch")
+
+
+def check_snapshot_hash(snapshot_bytes, anchoring, failures):
+    actual = hashlib.sha256(snapshot_bytes).hexdigest()
+    if actual != anchoring["snapshot_hash"]:
+        failures.append("snapshot.json does not hash to the snapshot hash")
+    if not anchoring["anchorings"]:
+        print("warning: no on-chain anchoring recorded for this epoch")
+    for anchor in anchoring["anchorings"]:
+        if anchor.get("hash") and anchor["hash"].lower() != actual:
+            failures.append(
+                f"anchoring {anchor['transaction_hash']} carries hash {anchor['hash']}"
+            )
+
+
+def check_merkle(snapshot, merkle, failures):
+    leaves = []
+    for leaf in merkle["leaves"]:
+        section, index = leaf["path"].split("/")
+        if json.loads(leaf["record"]) != snapshot[section][int(index)]:
+            failures.append(f"{leaf['path']}: record differs from snapshot.json")
+        current = leaf_hash(leaf["record"])
+        if current.hex() != leaf["leaf_hash"]:
+            failures.append(f"{leaf['path']}: leaf hash mismatch")
+        leaves.append(current)
+        for step in leaf["proof"]:
+            sibling = bytes.fromhex(step["hash"])
+            if step["position"] == "left":
+                current = node_hash(sibling, current)
+            else:
+                current = node_hash(current, sibling)
+        if current.hex() != merkle["root"]:
+            failures.append(f"{leaf['path']}: proof does not reach the Merkle root")
+
+    record_count = sum(len(snapshot.get(s, [])) for s in MERKLE_SECTIONS)
+    if len(leaves) != record_count:
+        failures.append("Merkle tree does not cover every snapshot record")
+    if merkle_root(leaves).hex() != merkle["root"]:
+        failures.append("recomputed Merkle root does not match")
+
+
+def main():
+    failures = []
+    check_checksums(failures)
+
+    with open("snapshot.json", "rb") as f:
+        snapshot_bytes = f.read()
+    snapshot = json.loads(snapshot_bytes)
+    with open("anchoring.json", encoding="utf-8") as f:
+        anchoring = json.load(f)
+    with open("merkle.json", encoding="utf-8") as f:
+        merkle = json.load(f)
+
+    check_snapshot_hash(snapshot_bytes, anchoring, failures)
+    check_merkle(snapshot, merkle, failures)
+
+    if failures:
+        for failure in failures:
+            print(f"FAIL: {failure}")
+        return 1
+
+    print(f"OK: epoch {anchoring['epoch']} snapshot {anchoring['snapshot_hash']}")
+    for anchor in anchoring["anchorings"]:
+        print(
+            f"  anchored by {anchor['transaction_hash']} "
+            f"(ledger {anchor['ledger']}, contract {anchor['contract_id']})"
+        )
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -0,0 +1,589 @@
+//! Bounded, per-consumer fan-out for alerts.
+//!
+//! Every subscriber gets its own queue, so a slow sink (e.g. Telegram's rate-limited
+//! delivery) only ever backs up itself. Durable subscribers created with
+//! [`AlertBus::subscribe_durable`] spill alerts to the `alert_spill` table once their
+//! in-memory queue is full and replay them in order as they catch up. Ephemeral
+//! subscribers (websocket clients) drop their oldest queued alert instead.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock, Weak};
+
+use serde::Serialize;
+use sqlx::{Row, SqlitePool};
+use tokio::sync::{mpsc, Notify};
+
+use crate::alerts::Alert;
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::observability::metrics;
+
+/// Alerts buffered in memory per subscriber before spilling or dropping.
+pub const DEFAULT_QUEUE_CAPACITY: usize = 256;
+
+/// Label used for ephemeral subscribers in metrics.
+const EPHEMERAL_CONSUMER: &str = "ephemeral";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubscriberStats {
+    pub consumer: String,
+    pub durable: bool,
+    /// Alerts buffered in memory.
+    pub queued: usize,
+    /// Alerts written to the spill table that have not been replayed yet.
+    pub spilled_pending: u64,
+    pub delivered: u64,
+    pub spilled_total: u64,
+    pub dropped_total: u64,
+}
+
+#[derive(Default)]
+struct QueueState {
+    queue: VecDeque<Alert>,
+    /// Set while the subscriber has alerts in the spill table; new alerts must follow
+    /// them there to preserve ordering.
+    spilling: bool,
+    spilled_pending: u64,
+    /// Durable subscribers check the spill table once on first receive to pick up
+    /// alerts left over from a previous process.
+    restored: bool,
+}
+
+struct Subscriber {
+    id: u64,
+    consumer: String,
+    durable: bool,
+    capacity: usize,
+    state: Mutex<QueueState>,
+    notify: Notify,
+    spill_tx: OnceLock<mpsc::UnboundedSender<Alert>>,
+    delivered: AtomicU64,
+    spilled_total: AtomicU64,
+    dropped_total: AtomicU64,
+}
+
+impl Subscriber {
+    fn metrics_label(&self) -> &str {
+        if self.durable {
+            &self.consumer
+        } else {
+            EPHEMERAL_CONSUMER
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, QueueState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Nothing left to replay from the spill table.
+    fn mark_restored(&self) {
+        let mut state = self.lock();
+        state.restored = true;
+        if state.spilled_pending == 0 {
+            state.spilling = false;
+        }
+    }
+
+    /// An alert handed to the spill writer could not be written: keep it in memory,
+    /// past capacity, rather than lose it or wait for a row that never appears.
+    fn requeue_unspilled(&self, alert: Alert) {
+        let mut state = self.lock();
+        state.spilled_pending = state.spilled_pending.saturating_sub(1);
+        state.queue.push_back(alert);
+        let depth = lag(&state);
+        drop(state);
+        metrics::set_alert_consumer_lag(self.metrics_label(), depth);
+        self.notify.notify_one();
+    }
+
+    fn stats(&self) -> SubscriberStats {
+        let state = self.lock();
+        SubscriberStats {
+            consumer: self.consumer.clone(),
+            durable: self.durable,
+            queued: state.queue.len(),
+            spilled_pending: state.spilled_pending,
+            delivered: self.delivered.load(Ordering::Relaxed),
+            spilled_total: self.spilled_total.load(Ordering::Relaxed),
+            dropped_total: self.dropped_total.load(Ordering::Relaxed),
+        }
+    }
+}
+
+struct BusInner {
+    subscribers: RwLock<Vec<Arc<Subscriber>>>,
+    spill_pool: OnceLock<SqlitePool>,
+    capacity: usize,
+    next_id: AtomicU64,
+    /// Set once every publishing handle is gone.
+    closed: AtomicBool,
+}
+
+/// Shared by all [`AlertBus`] clones; closes the bus when the last one is dropped.
+struct PublisherHandle {
+    inner: Arc<BusInner>,
+}
+
+impl Drop for PublisherHandle {
+    fn drop(&mut self) {
+        self.inner.closed.store(true, Ordering::Release);
+        for subscriber in self
+            .inner
+            .subscribers
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+        {
+            subscriber.notify.notify_one();
+        }
+    }
+}
+
+/// Fan-out of alerts to independently buffered subscribers.
+#[derive(Clone)]
+pub struct AlertBus {
+    inner: Arc<BusInner>,
+    _publisher: Arc<PublisherHandle>,
+}
+
+impl Default for AlertBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_QUEUE_CAPACITY)
+    }
+}
+
+impl AlertBus {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let inner = Arc::new(BusInner {
+            subscribers: RwLock::new(Vec::new()),
+            spill_pool: OnceLock::new(),
+            capacity: capacity.max(1),
+            next_id: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+        });
+        Self {
+            _publisher: Arc::new(PublisherHandle {
+                inner: Arc::clone(&inner),
+            }),
+            inner,
+        }
+    }
+
+    /// Enable spill-to-DB for durable subscribers. Only the first call has an effect.
+    pub fn set_spill_store(&self, pool: SqlitePool) {
+        let _ = self.inner.spill_pool.set(pool);
+    }
+
+    /// Subscribe a named sink whose alerts must not be lost (Telegram, Slack, ...).
+    #[must_use]
+    pub fn subscribe_durable(&self, consumer: &str) -> AlertSubscription {
+        self.register(consumer, true)
+    }
+
+    /// Subscribe a short-lived sink; on overflow its oldest alerts are dropped.
+    #[must_use]
+    pub fn subscribe(&self) -> AlertSubscription {
+        self.register(EPHEMERAL_CONSUMER, false)
+    }
+
+    fn register(&self, consumer: &str, durable: bool) -> AlertSubscription {
+        let subscriber = Arc::new(Subscriber {
+            id: self.inner.next_id.fetch_add(1, Ordering::Relaxed),
+            consumer: consumer.to_string(),
+            durable,
+            capacity: self.inner.capacity,
+            state: Mutex::new(QueueState {
+                // Nothing to restore for sinks that never spill.
+                restored: !durable,
+                ..QueueState::default()
+            }),
+            notify: Notify::new(),
+            spill_tx: OnceLock::new(),
+            delivered: AtomicU64::new(0),
+            spilled_total: AtomicU64::new(0),
+            dropped_total: AtomicU64::new(0),
+        });
+        self.inner
+            .subscribers
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(Arc::clone(&subscriber));
+
+        AlertSubscription {
+            bus: Arc::clone(&self.inner),
+            subscriber,
+        }
+    }
+
+    /// Deliver an alert to every subscriber without blocking the caller.
+    pub fn publish(&self, alert: &Alert) {
+        let subscribers = self
+            .inner
+            .subscribers
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone();
+
+        for subscriber in &subscribers {
+            self.enqueue(subscriber, alert.clone());
+        }
+    }
+
+    fn enqueue(&self, subscriber: &Arc<Subscriber>, alert: Alert) {
+        let label = subscriber.metrics_label();
+        let mut state = subscriber.lock();
+
+        if !state.spilling && state.queue.len() < subscriber.capacity {
+            state.queue.push_back(alert);
+            let depth = lag(&state);
+            drop(state);
+            metrics::set_alert_consumer_lag(label, depth);
+            subscriber.notify.notify_one();
+            return;
+        }
+
+        let spill_tx = if subscriber.durable {
+            self.inner.spill_pool.get().map(|pool| {
+                subscriber
+                    .spill_tx
+                    .get_or_init(|| spawn_spill_writer(pool.clone(), Arc::downgrade(subscriber)))
+                    .clone()
+            })
+        } else {
+            None
+        };
+
+        match spill_tx {
+            Some(tx) if tx.send(alert.clone()).is_ok() => {
+                state.spilling = true;
+                state.spilled_pending += 1;
+                subscriber.spilled_total.fetch_add(1, Ordering::Relaxed);
+                metrics::record_alert_spilled(label);
+            }
+            _ => {
+                if subscriber.durable {
+                    log_event!(
+                        error,
+                        Subsystem::Alerts,
+                        "alert_bus.alert_dropped",
+                        consumer = %subscriber.consumer,
+                        "Alert queue full and no spill store available; dropping oldest alert"
+                    );
+                }
+                state.queue.pop_front();
+                state.queue.push_back(alert);
+                subscriber.dropped_total.fetch_add(1, Ordering::Relaxed);
+                metrics::record_alert_dropped(label);
+            }
+        }
+        let depth = lag(&state);
+        drop(state);
+        metrics::set_alert_consumer_lag(label, depth);
+    }
+
+    /// Per-subscriber queue depth and delivery counters.
+    #[must_use]
+    pub fn stats(&self) -> Vec<SubscriberStats> {
+        self.inner
+            .subscribers
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .map(|s| s.stats())
+            .collect()
+    }
+}
+
+fn lag(state: &QueueState) -> i64 {
+    i64::try_from(state.queue.len() as u64 + state.spilled_pending).unwrap_or(i64::MAX)
+}
+
+/// Writes overflowed alerts for one subscriber to the spill table in arrival order.
+/// Alerts that cannot be written go back to the subscriber's in-memory queue.
+fn spawn_spill_writer(
+    pool: SqlitePool,
+    subscriber: Weak<Subscriber>,
+) -> mpsc::UnboundedSender<Alert> {
+    let (tx, mut rx) = mpsc::unbounded_channel::<Alert>();
+
+    tokio::spawn(async move {
+        while let Some(alert) = rx.recv().await {
+            let Some(subscriber) = subscriber.upgrade() else {
+                return;
+            };
+            if let Err(e) = write_spilled(&pool, &subscriber.consumer, &alert).await {
+                log_event!(
+                    error,
+                    Subsystem::Alerts,
+                    "alert_bus.spill_failed",
+                    consumer = %subscriber.consumer,
+                    error = format!("{e:#}"),
+                    "Failed to spill alert, keeping it in memory"
+                );
+                subscriber.requeue_unspilled(alert);
+            }
+        }
+    });
+
+    tx
+}
+
+async fn write_spilled(pool: &SqlitePool, consumer: &str, alert: &Alert) -> anyhow::Result<()> {
+    let payload = serde_json::to_string(alert)?;
+    sqlx::query("INSERT INTO alert_spill (consumer, payload) VALUES (?, ?)")
+        .bind(consumer)
+        .bind(payload)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Receiving half of a subscription. Dropping it unregisters the subscriber.
+pub struct AlertSubscription {
+    bus: Arc<BusInner>,
+    subscriber: Arc<Subscriber>,
+}
+
+impl AlertSubscription {
+    /// Wait for the next alert. Spilled alerts are replayed in order before newer ones.
+    /// Returns `None` once the bus is closed and everything queued has been delivered.
+    pub async fn recv(&mut self) -> Option<Alert> {
+        loop {
+            let notified = self.subscriber.notify.notified();
+
+            let needs_reload = {
+                let mut state = self.subscriber.lock();
+                if let Some(alert) = state.queue.pop_front() {
+                    let depth = lag(&state);
+                    drop(state);
+                    metrics::set_alert_consumer_lag(self.subscriber.metrics_label(), depth);
+                    self.subscriber.delivered.fetch_add(1, Ordering::Relaxed);
+                    return Some(alert);
+                }
+                state.spilling || !state.restored
+            };
+
+            if needs_reload && self.reload_spilled().await > 0 {
+                continue;
+            }
+
+            if needs_reload && self.subscriber.lock().spilled_pending > 0 {
+                // The spill writer has not flushed yet; poll again shortly.
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                continue;
+            }
+
+            if self.bus.closed.load(Ordering::Acquire) {
+                return None;
+            }
+            notified.await;
+        }
+    }
+
+    /// Move up to one queue's worth of spilled alerts back into memory.
+    async fn reload_spilled(&self) -> usize {
+        let Some(pool) = self.bus.spill_pool.get() else {
+            self.subscriber.mark_restored();
+            return 0;
+        };
+
+        let consumer = &self.subscriber.consumer;
+        let rows = match sqlx::query(
+            "SELECT id, payload FROM alert_spill WHERE consumer = ? ORDER BY id LIMIT ?",
+        )
+        .bind(consumer)
+        .bind(i64::try_from(self.subscriber.capacity).unwrap_or(i64::MAX))
+        .fetch_all(pool)
+        .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                log_event!(
+                    error,
+                    Subsystem::Alerts,
+                    "alert_bus.spill_load_failed",
+                    consumer = %consumer,
+                    error = %e,
+                    "Failed to load spilled alerts"
+                );
+                return 0;
+            }
+        };
+
+        let Some(max_id) = rows.last().map(|row| row.get::<i64, _>("id")) else {
+            self.subscriber.mark_restored();
+            return 0;
+        };
+
+        if let Err(e) = sqlx::query("DELETE FROM alert_spill WHERE consumer = ? AND id <= ?")
+            .bind(consumer)
+            .bind(max_id)
+            .execute(pool)
+            .await
+        {
+            log_event!(
+                error,
+                Subsystem::Alerts,
+                "alert_bus.spill_clear_failed",
+                consumer = %consumer,
+                error = %e,
+                "Failed to clear replayed spilled alerts"
+            );
+            return 0;
+        }
+
+        let alerts: Vec<Alert> = rows
+            .iter()
+            .filter_map(|row| serde_json::from_str(&row.get::<String, _>("payload")).ok())
+            .collect();
+
+        let mut state = self.subscriber.lock();
+        let from_this_run = if state.restored {
+            rows.len() as u64
+        } else {
+            // Rows left over from a previous process were never counted as pending.
+            (rows.len() as u64).min(state.spilled_pending)
+        };
+        state.restored = true;
+        state.spilled_pending = state.spilled_pending.saturating_sub(from_this_run);
+        if state.spilled_pending == 0 {
+            state.spilling = false;
+        }
+        let loaded = alerts.len();
+        state.queue.extend(alerts);
+        let depth = lag(&state);
+        drop(state);
+        metrics::set_alert_consumer_lag(self.subscriber.metrics_label(), depth);
+        loaded
+    }
+
+    #[must_use]
+    pub fn stats(&self) -> SubscriberStats {
+        self.subscriber.stats()
+    }
+}
+
+impl Drop for AlertSubscription {
+    fn drop(&mut self) {
+        let id = self.subscriber.id;
+        self.bus
+            .subscribers
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .retain(|s| s.id != id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::AlertType;
+    use crate::test_support::{self, migrations};
+
+    fn alert(n: u32) -> Alert {
+        Alert {
+            alert_type: AlertType::SuccessRateDrop,
+            corridor_id: Some(format!("corridor-{n}")),
+            anchor_id: None,
+            message: format!("alert {n}"),
+            old_value: 0.0,
+            new_value: f64::from(n),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    async fn spill_pool() -> SqlitePool {
+        test_support::sqlite_pool(&[migrations::ALERT_SPILL]).await
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_does_not_affect_others() {
+        let bus = AlertBus::new(2);
+        let mut fast = bus.subscribe();
+        let _slow = bus.subscribe();
+
+        for n in 0..3 {
+            bus.publish(&alert(n));
+            assert_eq!(fast.recv().await.unwrap().message, format!("alert {n}"));
+        }
+
+        let stats = bus.stats();
+        assert_eq!(stats[0].dropped_total, 0);
+        assert_eq!(stats[1].dropped_total, 1);
+        assert_eq!(stats[1].queued, 2);
+    }
+
+    #[tokio::test]
+    async fn test_durable_subscriber_spills_and_replays_in_order() {
+        let bus = AlertBus::new(2);
+        bus.set_spill_store(spill_pool().await);
+        let mut telegram = bus.subscribe_durable("telegram");
+
+        for n in 0..5 {
+            bus.publish(&alert(n));
+        }
+        assert_eq!(telegram.stats().spilled_total, 3);
+
+        for n in 0..5 {
+            let received = tokio::time::timeout(std::time::Duration::from_secs(2), telegram.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(received.message, format!("alert {n}"));
+        }
+        let stats = telegram.stats();
+        assert_eq!(stats.dropped_total, 0);
+        assert_eq!(stats.spilled_pending, 0);
+    }
+
+    #[tokio::test]
+    async fn test_failed_spill_writes_stay_in_memory() {
+        let bus = AlertBus::new(2);
+        // No alert_spill table: every spill write fails.
+        bus.set_spill_store(test_support::sqlite_pool(&[]).await);
+        let mut telegram = bus.subscribe_durable("telegram");
+
+        for n in 0..5 {
+            bus.publish(&alert(n));
+        }
+        for n in 0..5 {
+            let received = tokio::time::timeout(std::time::Duration::from_secs(2), telegram.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(received.message, format!("alert {n}"));
+        }
+        let stats = telegram.stats();
+        assert_eq!(stats.spilled_pending, 0);
+        assert_eq!(stats.dropped_total, 0);
+
+        // Nothing is left to wait for, so the subscription ends with the bus.
+        drop(bus);
+        let end = tokio::time::timeout(std::time::Duration::from_secs(2), telegram.recv()).await;
+        assert!(end.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dropping_subscription_unregisters() {
+        let bus = AlertBus::default();
+        let subscription = bus.subscribe();
+        assert_eq!(bus.stats().len(), 1);
+        drop(subscription);
+        assert!(bus.stats().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_recv_returns_none_after_bus_dropped() {
+        let bus = AlertBus::default();
+        let mut subscription = bus.subscribe();
+        bus.publish(&alert(1));
+        drop(bus);
+
+        assert!(subscription.recv().await.is_some());
+        assert!(subscription.recv().await.is_none());
+    }
+}
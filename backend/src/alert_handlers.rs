@@ -19,7 +19,7 @@ async fn handle_alert_socket(socket: WebSocket, alert_manager: Arc<AlertManager>
     let mut rx = alert_manager.subscribe();
 
     let mut send_task = tokio::spawn(async move {
-        while let Ok(alert) = rx.recv().await {
+        while let Some(alert) = rx.recv().await {
             if let Ok(msg) = serde_json::to_string(&alert) {
                 if sender
                     .send(axum::extract::ws::Message::Text(msg))
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
 use std::sync::Arc;
-use tokio::sync::broadcast;
+
+use crate::alert_bus::{AlertBus, AlertSubscription, SubscriberStats};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AlertType {
@@ -9,6 +11,9 @@ pub enum AlertType {
     LiquidityDecrease,
     AnchorStatusChange,
     AnchorMetricChange,
+    NetworkProtocolUpgrade,
+    SlaBreach,
+    StateDivergence,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,17 +28,18 @@ pub struct Alert {
 }
 
 pub struct AlertManager {
-    tx: broadcast::Sender<Alert>,
+    bus: AlertBus,
     webhook_event_service: Option<Arc<crate::services::webhook_event_service::WebhookEventService>>,
 }
 
 impl AlertManager {
     #[must_use]
-    pub fn new() -> (Self, broadcast::Receiver<Alert>) {
-        let (tx, rx) = broadcast::channel(100);
+    pub fn new() -> (Self, AlertSubscription) {
+        let bus = AlertBus::default();
+        let rx = bus.subscribe();
         (
             Self {
-                tx,
+                bus,
                 webhook_event_service: None,
             },
             rx,
@@ -43,17 +49,25 @@ impl AlertManager {
     #[must_use]
     pub fn new_with_webhooks(
         webhook_event_service: Arc<crate::services::webhook_event_service::WebhookEventService>,
-    ) -> (Self, broadcast::Receiver<Alert>) {
-        let (tx, rx) = broadcast::channel(100);
+    ) -> (Self, AlertSubscription) {
+        let bus = AlertBus::default();
+        let rx = bus.subscribe();
         (
             Self {
-                tx,
+                bus,
                 webhook_event_service: Some(webhook_event_service),
             },
             rx,
         )
     }
 
+    /// Persist alerts for durable subscribers that fall behind instead of dropping them.
+    #[must_use]
+    pub fn with_spill_store(self, pool: SqlitePool) -> Self {
+        self.bus.set_spill_store(pool);
+        self
+    }
+
     pub fn check_and_alert(
         &self,
         corridor_id: &str,
@@ -65,7 +79,7 @@ impl AlertManager {
         new_liquidity: f64,
     ) {
         if new_success < old_success - 10.0 {
-            let _ = self.tx.send(Alert {
+            self.bus.publish(&Alert {
                 alert_type: AlertType::SuccessRateDrop,
                 corridor_id: Some(corridor_id.to_string()),
                 anchor_id: None,
@@ -79,7 +93,7 @@ impl AlertManager {
         }
 
         if new_latency > old_latency * 1.5 {
-            let _ = self.tx.send(Alert {
+            self.bus.publish(&Alert {
                 alert_type: AlertType::LatencyIncrease,
                 corridor_id: Some(corridor_id.to_string()),
                 anchor_id: None,
@@ -91,7 +105,7 @@ impl AlertManager {
         }
 
         if new_liquidity < old_liquidity * 0.7 {
-            let _ = self.tx.send(Alert {
+            self.bus.publish(&Alert {
                 alert_type: AlertType::LiquidityDecrease,
                 corridor_id: Some(corridor_id.to_string()),
                 anchor_id: None,
@@ -105,9 +119,22 @@ impl AlertManager {
         }
     }
 
+    /// Subscribe a short-lived consumer (e.g. a websocket client).
+    #[must_use]
+    pub fn subscribe(&self) -> AlertSubscription {
+        self.bus.subscribe()
+    }
+
+    /// Subscribe a named sink that must not lose alerts when it falls behind.
+    #[must_use]
+    pub fn subscribe_durable(&self, consumer: &str) -> AlertSubscription {
+        self.bus.subscribe_durable(consumer)
+    }
+
+    /// Queue depth and delivery counters for every subscriber.
     #[must_use]
-    pub fn subscribe(&self) -> broadcast::Receiver<Alert> {
-        self.tx.subscribe()
+    pub fn subscriber_stats(&self) -> Vec<SubscriberStats> {
+        self.bus.stats()
     }
 
     pub fn send_anchor_alert(
@@ -128,7 +155,7 @@ impl AlertManager {
             timestamp: chrono::Utc::now().to_rfc3339(),
         };
 
-        let _ = self.tx.send(alert);
+        self.bus.publish(&alert);
 
         // Trigger webhook event for anchor status change
         if let Some(webhook_service) = &self.webhook_event_service {
@@ -166,4 +193,43 @@ impl AlertManager {
             });
         }
     }
+
+    /// Broadcast an alert for a single corridor.
+    pub fn send_corridor_alert(
+        &self,
+        alert_type: AlertType,
+        corridor_id: &str,
+        message: String,
+        old_value: f64,
+        new_value: f64,
+    ) {
+        self.bus.publish(&Alert {
+            alert_type,
+            corridor_id: Some(corridor_id.to_string()),
+            anchor_id: None,
+            message,
+            old_value,
+            new_value,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        });
+    }
+
+    /// Broadcast a network-wide alert that is not tied to a corridor or anchor.
+    pub fn send_network_alert(
+        &self,
+        alert_type: AlertType,
+        message: String,
+        old_value: f64,
+        new_value: f64,
+    ) {
+        self.bus.publish(&Alert {
+            alert_type,
+            corridor_id: None,
+            anchor_id: None,
+            message,
+            old_value,
+            new_value,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        });
+    }
 }
@@ -21,11 +21,16 @@ use crate::cache::keys;
 use crate::cache::CacheManager;
 use crate::database::Database;
 use crate::error::{ApiError, ApiResult};
+use crate::log_event;
+use crate::logging::Subsystem;
 use crate::models::corridor::Corridor;
 use crate::models::{AnchorDetailResponse, CreateAnchorRequest};
+use crate::projections::ProjectionStore;
+use crate::query_cost::QueryCostExceeded;
 use crate::rpc::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
 use crate::rpc::error::{with_retry, RetryConfig, RpcError};
 use crate::rpc::StellarRpcClient;
+use crate::services::anchor_flows::{AnchorFlowQuery, AnchorFlowService, AnchorFlows};
 use crate::services::price_feed::PriceFeedClient;
 use crate::state::AppState;
 use tracing::{error, info, warn};
@@ -78,15 +83,19 @@ pub async fn get_anchor(
     State(app_state): State<AppState>,
     Path(id): Path<Uuid>,
 ) -> ApiResult<Json<AnchorDetailResponse>> {
-    let anchor_detail = app_state.db.get_anchor_detail(id).await?.ok_or_else(|| {
-        let mut details = HashMap::new();
-        details.insert("anchor_id".to_string(), serde_json::json!(id.to_string()));
-        ApiError::not_found_with_details(
-            "ANCHOR_NOT_FOUND",
-            format!("Anchor with id {id} not found"),
-            details,
-        )
-    })?;
+    // Served from the denormalized projection; rebuilt from the normalized tables on miss.
+    let anchor_detail = ProjectionStore::new(app_state.db.clone())
+        .anchor_detail(id)
+        .await?
+        .ok_or_else(|| {
+            let mut details = HashMap::new();
+            details.insert("anchor_id".to_string(), serde_json::json!(id.to_string()));
+            ApiError::not_found_with_details(
+                "ANCHOR_NOT_FOUND",
+                format!("Anchor with id {id} not found"),
+                details,
+            )
+        })?;
 
     Ok(Json(anchor_detail))
 }
@@ -220,6 +229,8 @@ pub async fn update_anchor_metrics(
     // Broadcast the anchor update to WebSocket clients
     broadcast_anchor_update(&app_state.ws_state, &anchor);
 
+    refresh_anchor_projection(&app_state, id).await;
+
     Ok(Json(anchor))
 }
 
@@ -245,6 +256,48 @@ pub async fn get_anchor_assets(
     Ok(Json(assets))
 }
 
+/// GET /api/anchors/:id/flows - Inbound/outbound flows by counterparty and asset
+#[utoipa::path(
+    get,
+    path = "/api/anchors/{id}/flows",
+    params(
+        ("id" = String, Path, description = "Anchor UUID"),
+        AnchorFlowQuery
+    ),
+    responses(
+        (status = 200, description = "Sankey nodes and links for the anchor", body = AnchorFlows),
+        (status = 400, description = "Invalid window or pagination parameters"),
+        (status = 404, description = "Anchor not found"),
+        (status = 422, description = "Window too long for the anchor's issuer count"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Anchors"
+)]
+#[tracing::instrument(skip(app_state, query), fields(anchor_id = %id))]
+pub async fn get_anchor_flows(
+    State(app_state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<AnchorFlowQuery>,
+) -> ApiResult<Json<AnchorFlows>> {
+    let flows = AnchorFlowService::new(app_state.db.clone())
+        .flows(&id.to_string(), &query)
+        .await
+        .map_err(|e| {
+            if e.downcast_ref::<sqlx::Error>().is_some() {
+                ApiError::from(e)
+            } else if let Some(exceeded) = e.downcast_ref::<QueryCostExceeded>() {
+                ApiError::from(exceeded.clone())
+            } else {
+                ApiError::bad_request("INVALID_FLOW_QUERY", e.to_string())
+            }
+        })?
+        .ok_or_else(|| {
+            ApiError::not_found("ANCHOR_NOT_FOUND", format!("Anchor with id {id} not found"))
+        })?;
+
+    Ok(Json(flows))
+}
+
 /// POST /api/anchors/:id/assets - Add asset to anchor
 #[derive(Debug, Deserialize, validator::Validate)]
 pub struct CreateAssetRequest {
@@ -288,9 +341,29 @@ pub async fn create_anchor_asset(
         .create_asset(id, req.asset_code, req.asset_issuer)
         .await?;
 
+    refresh_anchor_projection(&app_state, id).await;
+
     Ok(Json(asset))
 }
 
+/// Keep the anchor detail read model in sync after a write; failures only delay the
+/// refresh until the projection ages out, so they are logged rather than surfaced.
+async fn refresh_anchor_projection(app_state: &AppState, anchor_id: Uuid) {
+    if let Err(e) = ProjectionStore::new(app_state.db.clone())
+        .refresh_anchor(anchor_id)
+        .await
+    {
+        log_event!(
+            warn,
+            Subsystem::Api,
+            "anchor_projection.refresh_failed",
+            anchor_id = %anchor_id,
+            error = %e,
+            "Failed to refresh anchor detail projection"
+        );
+    }
+}
+
 use crate::cache::helpers::cached_query;
 use crate::cache::keys;
 use crate::database::Database;
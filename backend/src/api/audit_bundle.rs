@@ -0,0 +1,57 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue},
+    routing::get,
+    Router,
+};
+use std::sync::Arc;
+
+use crate::error::{ApiError, ApiResult};
+use crate::services::audit_bundle::AuditBundleService;
+
+pub fn routes(service: Arc<AuditBundleService>) -> Router {
+    Router::new()
+        .route("/snapshots/:epoch/audit-bundle", get(get_audit_bundle))
+        .with_state(service)
+}
+
+/// GET /api/snapshots/:epoch/audit-bundle - Downloadable auditor bundle for an epoch
+#[utoipa::path(
+    get,
+    path = "/api/snapshots/{epoch}/audit-bundle",
+    params(
+        ("epoch" = u64, Path, description = "Snapshot epoch")
+    ),
+    responses(
+        (status = 200, description = "Tar archive with the canonical snapshot, Merkle proofs, on-chain anchoring, event manifest and verify.py", content_type = "application/x-tar"),
+        (status = 404, description = "No snapshot stored for this epoch"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Snapshots"
+)]
+pub async fn get_audit_bundle(
+    State(service): State<Arc<AuditBundleService>>,
+    Path(epoch): Path<u64>,
+) -> ApiResult<(HeaderMap, Vec<u8>)> {
+    let bundle = service.build(epoch).await?.ok_or_else(|| {
+        ApiError::not_found(
+            "SNAPSHOT_NOT_FOUND",
+            format!("No snapshot stored for epoch {epoch}"),
+        )
+    })?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/x-tar"),
+    );
+    let disposition = format!("attachment; filename=\"{}\"", bundle.file_name());
+    if let Ok(value) = HeaderValue::from_str(&disposition) {
+        headers.insert(header::CONTENT_DISPOSITION, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&bundle.snapshot_hash) {
+        headers.insert(HeaderName::from_static("x-snapshot-hash"), value);
+    }
+
+    Ok((headers, bundle.archive))
+}
@@ -0,0 +1,104 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use std::sync::Arc;
+
+use crate::error::{ApiError, ApiResult};
+use crate::services::backfill_coordinator::{
+    BackfillCoordinator, MarkDirtyRequest, RecomputeRange, RecomputeRangeQuery,
+};
+
+/// Nest under `/admin/recompute` behind auth.
+pub fn admin_routes(coordinator: Arc<BackfillCoordinator>) -> Router {
+    Router::new()
+        .route("/ranges", get(list_recompute_ranges).post(mark_dirty))
+        .route("/ranges/:id", get(get_recompute_range))
+        .with_state(coordinator)
+}
+
+fn invalid_range(e: anyhow::Error) -> ApiError {
+    if e.downcast_ref::<sqlx::Error>().is_some() {
+        ApiError::from(e)
+    } else {
+        ApiError::bad_request("INVALID_RECOMPUTE_RANGE", e.to_string())
+    }
+}
+
+/// POST /api/admin/recompute/ranges - Report a period a backfill inserted data into
+#[utoipa::path(
+    post,
+    path = "/api/admin/recompute/ranges",
+    request_body = MarkDirtyRequest,
+    responses(
+        (status = 202, description = "Range queued for recomputation, merged with any overlapping pending range", body = RecomputeRange),
+        (status = 400, description = "Empty, reversed or too long range"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn mark_dirty(
+    State(coordinator): State<Arc<BackfillCoordinator>>,
+    Json(request): Json<MarkDirtyRequest>,
+) -> ApiResult<(StatusCode, Json<RecomputeRange>)> {
+    let range = coordinator
+        .mark_dirty(&request, Utc::now())
+        .await
+        .map_err(invalid_range)?;
+
+    Ok((StatusCode::ACCEPTED, Json(range)))
+}
+
+/// GET /api/admin/recompute/ranges - Dirty ranges and recomputation progress
+#[utoipa::path(
+    get,
+    path = "/api/admin/recompute/ranges",
+    params(RecomputeRangeQuery),
+    responses(
+        (status = 200, description = "Ranges, newest first", body = Vec<RecomputeRange>),
+        (status = 400, description = "Invalid limit"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn list_recompute_ranges(
+    State(coordinator): State<Arc<BackfillCoordinator>>,
+    Query(query): Query<RecomputeRangeQuery>,
+) -> ApiResult<Json<Vec<RecomputeRange>>> {
+    let ranges = coordinator.list(&query).await.map_err(invalid_range)?;
+    Ok(Json(ranges))
+}
+
+/// GET /api/admin/recompute/ranges/:id - Progress of one range
+#[utoipa::path(
+    get,
+    path = "/api/admin/recompute/ranges/{id}",
+    params(
+        ("id" = String, Path, description = "Recompute range ID")
+    ),
+    responses(
+        (status = 200, description = "Range and progress", body = RecomputeRange),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "No such range"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn get_recompute_range(
+    State(coordinator): State<Arc<BackfillCoordinator>>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<RecomputeRange>> {
+    let range = coordinator.get(&id).await?.ok_or_else(|| {
+        ApiError::not_found(
+            "RECOMPUTE_RANGE_NOT_FOUND",
+            format!("No recompute range {id}"),
+        )
+    })?;
+
+    Ok(Json(range))
+}
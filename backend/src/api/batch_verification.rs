@@ -0,0 +1,41 @@
+use axum::{extract::State, routing::post, Json, Router};
+use std::sync::Arc;
+
+use crate::error::{ApiError, ApiResult};
+use crate::services::batch_verification::{
+    validate_items, BatchVerificationService, BatchVerifyRequest, BatchVerifyResponse,
+};
+
+pub fn routes(service: Arc<BatchVerificationService>) -> Router {
+    Router::new()
+        .route("/verify/batch", post(verify_batch))
+        .with_state(service)
+}
+
+/// POST /api/verify/batch - Verify many (epoch, hash) pairs against the snapshot contract
+#[utoipa::path(
+    post,
+    path = "/api/verify/batch",
+    request_body = BatchVerifyRequest,
+    responses(
+        (status = 200, description = "One verdict per item, in request order", body = BatchVerifyResponse),
+        (status = 400, description = "Empty or oversized batch, or a malformed epoch or hash"),
+        (status = 503, description = "Snapshot contract is not configured")
+    ),
+    tag = "Snapshots"
+)]
+pub async fn verify_batch(
+    State(service): State<Arc<BatchVerificationService>>,
+    Json(request): Json<BatchVerifyRequest>,
+) -> ApiResult<Json<BatchVerifyResponse>> {
+    let pairs = validate_items(&request.items)
+        .map_err(|e| ApiError::bad_request("INVALID_VERIFICATION_BATCH", e.to_string()))?;
+    if !service.has_contract() {
+        return Err(ApiError::service_unavailable(
+            "CONTRACT_NOT_CONFIGURED",
+            "Snapshot contract is not configured on this server",
+        ));
+    }
+
+    Ok(Json(service.verify_batch(&pairs).await))
+}
@@ -0,0 +1,29 @@
+use axum::{extract::State, routing::get, Json, Router};
+use std::sync::Arc;
+
+use crate::cluster::{ClusterView, LeaderElection};
+use crate::error::ApiResult;
+
+/// Nest under `/admin/cluster` behind auth.
+pub fn admin_routes(election: Arc<LeaderElection>) -> Router {
+    Router::new()
+        .route("/", get(get_cluster))
+        .with_state(election)
+}
+
+/// GET /api/admin/cluster - Which instance leads each singleton role
+#[utoipa::path(
+    get,
+    path = "/api/admin/cluster",
+    responses(
+        (status = 200, description = "Role assignment and live instances", body = ClusterView),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn get_cluster(
+    State(election): State<Arc<LeaderElection>>,
+) -> ApiResult<Json<ClusterView>> {
+    Ok(Json(election.view().await?))
+}
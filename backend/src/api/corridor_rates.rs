@@ -0,0 +1,67 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::error::{ApiError, ApiResult};
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::query_cost::QueryCostExceeded;
+use crate::rate_index::codec::CodecError;
+use crate::rate_index::{RateIndexStore, RateSeries, RateSeriesQuery};
+
+/// Uses the full path so it can be merged next to the cached `/corridors/:corridor_key` route.
+pub fn routes(store: Arc<RateIndexStore>) -> Router {
+    Router::new()
+        .route("/corridors/:corridor_key/rates", get(get_corridor_rates))
+        .with_state(store)
+}
+
+/// GET /api/corridors/:corridor_key/rates - Downsampled exchange-rate history
+#[utoipa::path(
+    get,
+    path = "/api/corridors/{corridor_key}/rates",
+    params(
+        ("corridor_key" = String, Path, description = "Corridor key, e.g. USDC:ISSUER->EURC:ISSUER"),
+        RateSeriesQuery
+    ),
+    responses(
+        (status = 200, description = "Open/high/low/close/mean rate per bucket", body = RateSeries),
+        (status = 400, description = "Invalid range, step or max_points"),
+        (status = 422, description = "Range too long or step too fine; the response suggests a coarser step_seconds"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Corridors"
+)]
+pub async fn get_corridor_rates(
+    State(store): State<Arc<RateIndexStore>>,
+    Path(corridor_key): Path<String>,
+    Query(query): Query<RateSeriesQuery>,
+) -> ApiResult<Json<RateSeries>> {
+    let series = store.series(&corridor_key, &query).await.map_err(|e| {
+        if e.downcast_ref::<sqlx::Error>().is_some() {
+            ApiError::from(e)
+        } else if let Some(exceeded) = e.downcast_ref::<QueryCostExceeded>() {
+            ApiError::from(exceeded.clone())
+        } else if e.downcast_ref::<CodecError>().is_some() {
+            log_event!(
+                error,
+                Subsystem::Api,
+                "corridor_rates.block_unreadable",
+                corridor_key = %corridor_key,
+                error = format!("{e:#}"),
+                "Unreadable rate block"
+            );
+            ApiError::internal(
+                "RATE_INDEX_CORRUPT",
+                "Stored rate history could not be decoded",
+            )
+        } else {
+            ApiError::bad_request("INVALID_RATE_QUERY", e.to_string())
+        }
+    })?;
+
+    Ok(Json(series))
+}
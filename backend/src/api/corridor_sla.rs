@@ -0,0 +1,134 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::error::{ApiError, ApiResult};
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::services::corridor_sla::{
+    CorridorSla, CorridorSlaService, CorridorSlaStatus, UpsertCorridorSlaRequest,
+};
+
+/// Public read-only SLA status. Uses the full corridor path so it can be merged next
+/// to the existing `/corridors/:corridor_key` route rather than nested under it.
+pub fn routes(service: Arc<CorridorSlaService>) -> Router {
+    Router::new()
+        .route("/corridors/:corridor_key/sla", get(get_corridor_sla))
+        .with_state(service)
+}
+
+/// Operator routes for defining and removing SLAs; nest under `/admin/corridors` behind auth.
+pub fn admin_routes(service: Arc<CorridorSlaService>) -> Router {
+    Router::new()
+        .route(
+            "/:corridor_key/sla",
+            axum::routing::put(upsert_corridor_sla).delete(delete_corridor_sla),
+        )
+        .with_state(service)
+}
+
+/// GET /api/corridors/:corridor_key/sla - SLA definition, breaches and monthly compliance
+#[utoipa::path(
+    get,
+    path = "/api/corridors/{corridor_key}/sla",
+    params(
+        ("corridor_key" = String, Path, description = "Corridor identifier (e.g., USDC:native->XLM:native)")
+    ),
+    responses(
+        (status = 200, description = "Current SLA status", body = CorridorSlaStatus),
+        (status = 404, description = "No SLA defined for this corridor"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Corridors"
+)]
+pub async fn get_corridor_sla(
+    State(service): State<Arc<CorridorSlaService>>,
+    Path(corridor_key): Path<String>,
+) -> ApiResult<Json<CorridorSlaStatus>> {
+    let status = service.status(&corridor_key).await?.ok_or_else(|| {
+        ApiError::not_found(
+            "SLA_NOT_FOUND",
+            format!("No SLA defined for corridor {corridor_key}"),
+        )
+    })?;
+
+    Ok(Json(status))
+}
+
+/// PUT /api/admin/corridors/:corridor_key/sla - Create or replace a corridor SLA
+#[utoipa::path(
+    put,
+    path = "/api/admin/corridors/{corridor_key}/sla",
+    params(
+        ("corridor_key" = String, Path, description = "Corridor identifier")
+    ),
+    request_body = UpsertCorridorSlaRequest,
+    responses(
+        (status = 200, description = "SLA stored", body = CorridorSla),
+        (status = 400, description = "Invalid SLA thresholds"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn upsert_corridor_sla(
+    State(service): State<Arc<CorridorSlaService>>,
+    Path(corridor_key): Path<String>,
+    Json(request): Json<UpsertCorridorSlaRequest>,
+) -> ApiResult<Json<CorridorSla>> {
+    let sla = service
+        .upsert_sla(&corridor_key, request)
+        .await
+        .map_err(|e| {
+            if e.downcast_ref::<sqlx::Error>().is_some() {
+                ApiError::from(e)
+            } else {
+                ApiError::bad_request("INVALID_SLA", e.to_string())
+            }
+        })?;
+
+    log_event!(
+        info,
+        Subsystem::Alerts,
+        "sla.updated",
+        corridor_key = %sla.corridor_key,
+        min_success_rate = sla.min_success_rate,
+        max_p95_settlement_ms = sla.max_p95_settlement_ms,
+        "Corridor SLA updated"
+    );
+
+    Ok(Json(sla))
+}
+
+/// DELETE /api/admin/corridors/:corridor_key/sla - Remove a corridor SLA
+#[utoipa::path(
+    delete,
+    path = "/api/admin/corridors/{corridor_key}/sla",
+    params(
+        ("corridor_key" = String, Path, description = "Corridor identifier")
+    ),
+    responses(
+        (status = 204, description = "SLA removed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "No SLA defined for this corridor"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn delete_corridor_sla(
+    State(service): State<Arc<CorridorSlaService>>,
+    Path(corridor_key): Path<String>,
+) -> ApiResult<StatusCode> {
+    if service.delete_sla(&corridor_key).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::not_found(
+            "SLA_NOT_FOUND",
+            format!("No SLA defined for corridor {corridor_key}"),
+        ))
+    }
+}
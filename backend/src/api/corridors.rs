@@ -4,10 +4,13 @@ use axum::{
     response::Response,
     Json,
 };
+use futures::future::join_all;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::{Arc, OnceLock};
-use tracing::{error, info, instrument, warn};
+use std::time::{Duration, Instant};
+use tracing::{info, instrument, warn};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
@@ -17,13 +20,16 @@ use crate::cache::keys;
 use crate::cache::CacheManager;
 use crate::database::Database;
 use crate::error::{ApiError, ApiResult};
+use crate::log_event;
+use crate::logging::Subsystem;
 use crate::models::corridor::{Corridor, CorridorMetrics};
 use crate::models::{CreateCorridorRequest, SortBy};
+use crate::projections::{CorridorDetailView, ProjectionStore};
 use crate::request_id::RequestId;
 use crate::rpc::{
     circuit_breaker::{CircuitBreaker, CircuitBreakerConfig},
     error::{with_retry, RetryConfig, RpcError},
-    StellarRpcClient,
+    Asset, OrderBook, StellarRpcClient,
 };
 use crate::services::analytics::{compute_corridor_metrics, CorridorPayment};
 use crate::services::price_feed::PriceFeedClient;
@@ -191,6 +197,83 @@ pub struct LiquidityDataPoint {
     pub volume_24h_usd: f64,
 }
 
+/// Time each source gets before the detail response goes out without it
+const DETAIL_METRICS_BUDGET: Duration = Duration::from_secs(10);
+const DETAIL_ORDER_BOOK_BUDGET: Duration = Duration::from_millis(1500);
+const DETAIL_SUMMARY_BUDGET: Duration = Duration::from_millis(500);
+const DETAIL_ORDER_BOOK_LIMIT: u32 = 20;
+const CORRIDOR_DETAIL_CACHE_TTL: usize = 300;
+
+/// Where a corridor detail section came from on this request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionFreshness {
+    /// Fetched from its source for this response
+    Fresh,
+    /// Served from the response cache
+    Cached,
+    /// Source failed or missed its latency budget; the section is omitted
+    Unavailable,
+    /// Not applicable to this corridor, e.g. no order book for a same-asset corridor
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DetailSection {
+    /// `metrics`, `order_book` or `summary`
+    #[schema(example = "order_book")]
+    pub name: String,
+    pub freshness: SectionFreshness,
+    pub duration_ms: u64,
+    /// Why the section is unavailable
+    pub message: Option<String>,
+}
+
+/// Top of the live DEX order book between the corridor's two assets
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrderBookDepth {
+    #[schema(example = 0.1185)]
+    pub best_bid: Option<f64>,
+    #[schema(example = 0.1191)]
+    pub best_ask: Option<f64>,
+    /// Gap between best ask and best bid, in basis points of the mid price
+    #[schema(example = 50.5)]
+    pub spread_bps: Option<f64>,
+    /// Amount offered across the returned bid levels
+    pub bid_depth: f64,
+    /// Amount offered across the returned ask levels
+    pub ask_depth: f64,
+}
+
+impl OrderBookDepth {
+    fn from_order_book(book: &OrderBook) -> Self {
+        let price = |entries: &[crate::rpc::OrderBookEntry]| {
+            entries.first().and_then(|e| e.price.parse::<f64>().ok())
+        };
+        let depth = |entries: &[crate::rpc::OrderBookEntry]| {
+            entries
+                .iter()
+                .filter_map(|e| e.amount.parse::<f64>().ok())
+                .sum()
+        };
+        let best_bid = price(&book.bids);
+        let best_ask = price(&book.asks);
+        let spread_bps = match (best_bid, best_ask) {
+            (Some(bid), Some(ask)) if bid + ask > 0.0 => {
+                Some((ask - bid) / f64::midpoint(ask, bid) * 10_000.0)
+            }
+            _ => None,
+        };
+        Self {
+            best_bid,
+            best_ask,
+            spread_bps,
+            bid_depth: depth(&book.bids),
+            ask_depth: depth(&book.asks),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CorridorDetailResponse {
     /// Corridor summary information
@@ -203,6 +286,15 @@ pub struct CorridorDetailResponse {
     pub liquidity_trends: Vec<LiquidityDataPoint>,
     /// Related corridors
     pub related_corridors: Option<Vec<CorridorResponse>>,
+    /// Live DEX order book; omitted when Horizon misses its latency budget
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order_book: Option<OrderBookDepth>,
+    /// Hourly rollups from the read model; omitted when unavailable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<CorridorDetailView>,
+    /// Freshness of each section of this response
+    #[serde(default)]
+    pub sections: Vec<DetailSection>,
 }
 
 /// Query parameters for listing corridors with filtering and pagination.
@@ -690,8 +782,11 @@ fn find_related_corridors(
 /// Get detailed corridor information
 ///
 /// Returns detailed metrics and historical data for a specific corridor.
+/// Payment metrics, the live order book and the hourly rollup summary are fetched
+/// concurrently, each within its own latency budget. Only the metrics are required;
+/// a slow order book or summary is left out and reported in `sections`.
 ///
-/// **DATA SOURCE: RPC**
+/// **DATA SOURCE: RPC (metrics cached for 5 minutes), Horizon order book, read-model projection**
 #[utoipa::path(
     get,
     path = "/api/corridors/{corridor_key}",
@@ -699,19 +794,20 @@ fn find_related_corridors(
         ("corridor_key" = String, Path, description = "Corridor identifier (e.g., USDC:native->XLM:native)")
     ),
     responses(
-        (status = 200, description = "Corridor details retrieved successfully", body = CorridorDetailResponse),
+        (status = 200, description = "Corridor details retrieved successfully; `sections` tells which parts are fresh, cached or missing", body = CorridorDetailResponse),
         (status = 404, description = "Corridor not found"),
-        (status = 500, description = "Internal server error")
+        (status = 500, description = "Internal server error"),
+        (status = 503, description = "Payment data could not be fetched within its latency budget")
     ),
     tag = "Corridors"
 )]
 #[tracing::instrument(
-    skip(_db, cache, rpc_client, price_feed),
+    skip(db, cache, rpc_client, price_feed),
     fields(request_id = %request_id.0, corridor_key = %corridor_key)
 )]
 pub async fn get_corridor_detail(
     Extension(request_id): Extension<RequestId>,
-    State((_db, cache, rpc_client, price_feed)): State<(
+    State((db, cache, rpc_client, price_feed)): State<(
         Arc<Database>,
         Arc<CacheManager>,
         Arc<StellarRpcClient>,
@@ -719,7 +815,6 @@ pub async fn get_corridor_detail(
     )>,
     Path(corridor_key): Path<String>,
 ) -> ApiResult<Json<CorridorDetailResponse>> {
-    use std::collections::HashMap;
     info!("Fetching corridor");
 
     // Validate corridor_key format
@@ -745,126 +840,222 @@ pub async fn get_corridor_detail(
         ));
     }
 
-    let cache_key = keys::corridor_detail(&corridor_key);
-    let response = cached_query(&cache, &cache_key, 300, || async {
-        // Fetch payments from RPC
-        let circuit_breaker = rpc_circuit_breaker();
-
-        let payments = with_retry(
-            || async {
-                rpc_client
-                    .fetch_all_payments(Some(5000))
-                    .await
-                    .map_err(|e| RpcError::categorize(&e.to_string()))
-            },
-            RetryConfig::default(),
-            circuit_breaker.clone(),
-        )
-        .await
-        .map_err(|e| {
-            error!(
-                error = %e,
-                "Failed to fetch payments from RPC"
-            );
-            anyhow::anyhow!("Failed to fetch payment data from RPC")
-        })?;
-
-        // Filter payments for this specific corridor
-        let mut corridor_payments = Vec::new();
-        let mut all_corridors = Vec::new();
-        let mut corridor_map: HashMap<String, Vec<&crate::rpc::Payment>> = HashMap::new();
-
-        for payment in &payments {
-            if let Some(asset_pair) = extract_asset_pair_from_payment(payment) {
-                let key = asset_pair.to_corridor_key();
-                corridor_map.entry(key.clone()).or_default().push(payment);
-
-                if key == corridor_key {
-                    corridor_payments.push(payment);
-                }
+    let (metrics, (order_book, order_book_section), (summary, summary_section)) = tokio::join!(
+        corridor_metrics(
+            &cache,
+            &rpc_client,
+            &price_feed,
+            &corridor_key,
+            source_parts[0],
+            dest_parts[0],
+        ),
+        within_budget("order_book", DETAIL_ORDER_BOOK_BUDGET, async {
+            if source_key == dest_key {
+                return Ok(None);
             }
-        }
+            let book = rpc_client
+                .fetch_order_book(
+                    &horizon_asset(source_key),
+                    &horizon_asset(dest_key),
+                    DETAIL_ORDER_BOOK_LIMIT,
+                )
+                .await?;
+            Ok::<_, RpcError>(Some(OrderBookDepth::from_order_book(&book)))
+        }),
+        within_budget("summary", DETAIL_SUMMARY_BUDGET, async {
+            ProjectionStore::new(db.clone())
+                .corridor_detail(&corridor_key)
+                .await
+        }),
+    );
+    let (mut response, metrics_section) = metrics?;
+    response.order_book = order_book;
+    response.summary = summary;
+    response.sections = vec![metrics_section, order_book_section, summary_section];
+
+    log_event!(
+        info,
+        Subsystem::Api,
+        "corridor_detail.served",
+        corridor_id = %response.corridor.id,
+        success_rate = response.corridor.success_rate,
+        "Corridor found"
+    );
+
+    Ok(Json(response))
+}
 
-        // If no payments found for this corridor, return 404
-        if corridor_payments.is_empty() {
-            return Err(anyhow::anyhow!(
-                "No payment data found for corridor: {corridor_key}"
+/// Payment metrics are the one required section: served from the response cache
+/// when possible, otherwise rebuilt from RPC within `DETAIL_METRICS_BUDGET`.
+async fn corridor_metrics(
+    cache: &Arc<CacheManager>,
+    rpc_client: &StellarRpcClient,
+    price_feed: &PriceFeedClient,
+    corridor_key: &str,
+    source_code: &str,
+    dest_code: &str,
+) -> ApiResult<(CorridorDetailResponse, DetailSection)> {
+    let start = Instant::now();
+    let cache_key = keys::corridor_detail(corridor_key);
+    match cache.get::<CorridorDetailResponse>(&cache_key).await {
+        Ok(Some(cached)) => {
+            return Ok((
+                cached,
+                detail_section("metrics", SectionFreshness::Cached, start, None),
             ));
         }
+        Ok(None) => {}
+        Err(e) => log_event!(
+            warn,
+            Subsystem::Api,
+            "corridor_detail.cache_read_failed",
+            key = %cache_key,
+            error = %e,
+            "Corridor detail cache read failed"
+        ),
+    }
 
-        // Build all corridor responses for related corridors lookup
-        for (key, corr_payments) in &corridor_map {
-            let total_attempts = corr_payments.len() as i64;
-            let successful_payments = total_attempts;
-            let failed_payments = 0;
-            let success_rate = 100.0; // All payments in Stellar stream are successful
-
-            let parts: Vec<&str> = key.split("->").collect();
-            if parts.len() != 2 {
-                continue;
-            }
+    let response = tokio::time::timeout(
+        DETAIL_METRICS_BUDGET,
+        compute_corridor_detail(rpc_client, price_feed, corridor_key, source_code, dest_code),
+    )
+    .await
+    .map_err(|_| {
+        ApiError::service_unavailable(
+            "CORRIDOR_DETAIL_TIMEOUT",
+            "Payment data for this corridor could not be fetched in time",
+        )
+        .with_retry_after(5)
+    })??;
 
-            let source_parts: Vec<&str> = parts[0].split(':').collect();
-            let dest_parts: Vec<&str> = parts[1].split(':').collect();
+    // Cache write is best-effort; live sections are never part of the cached value
+    if let Err(e) = cache
+        .set(&cache_key, &response, CORRIDOR_DETAIL_CACHE_TTL)
+        .await
+    {
+        log_event!(
+            warn,
+            Subsystem::Api,
+            "corridor_detail.cache_write_failed",
+            key = %cache_key,
+            error = %e,
+            "Failed to cache corridor detail"
+        );
+    }
 
-            if source_parts.len() != 2 || dest_parts.len() != 2 {
-                continue;
-            }
+    Ok((
+        response,
+        detail_section("metrics", SectionFreshness::Fresh, start, None),
+    ))
+}
 
-            // Calculate volume
-            let mut volume_usd = 0.0;
-            if let Ok(price) = price_feed.get_price(parts[0]).await {
-                for payment in corr_payments {
-                    if let Ok(amount) = payment.get_amount().parse::<f64>() {
-                        volume_usd += amount * price;
-                    }
-                }
-            } else {
-                volume_usd = corr_payments
-                    .iter()
-                    .filter_map(|p| p.get_amount().parse::<f64>().ok())
-                    .sum();
-            }
+async fn compute_corridor_detail(
+    rpc_client: &StellarRpcClient,
+    price_feed: &PriceFeedClient,
+    corridor_key: &str,
+    source_code: &str,
+    dest_code: &str,
+) -> anyhow::Result<CorridorDetailResponse> {
+    let source_key = corridor_key.split("->").next().unwrap_or_default();
 
-            let health_score = calculate_health_score(success_rate, total_attempts, volume_usd);
-            let liquidity_trend = get_liquidity_trend(volume_usd);
-            let avg_latency = 400.0 + (success_rate * 2.0);
+    // Fetch payments from RPC
+    let circuit_breaker = rpc_circuit_breaker();
 
-            all_corridors.push(CorridorResponse {
-                id: key.clone(),
-                source_asset: source_parts[0].to_string(),
-                destination_asset: dest_parts[0].to_string(),
-                success_rate,
-                total_attempts,
-                successful_payments,
-                failed_payments,
-                average_latency_ms: avg_latency,
-                median_latency_ms: avg_latency * 0.75,
-                p95_latency_ms: avg_latency * 2.5,
-                p99_latency_ms: avg_latency * 4.0,
-                liquidity_depth_usd: volume_usd,
-                liquidity_volume_24h_usd: volume_usd * 0.1,
-                liquidity_trend,
-                health_score,
-                last_updated: chrono::Utc::now().to_rfc3339(),
-            });
+    let payments = with_retry(
+        || async {
+            rpc_client
+                .fetch_all_payments(Some(5000))
+                .await
+                .map_err(|e| RpcError::categorize(&e.to_string()))
+        },
+        RetryConfig::default(),
+        circuit_breaker.clone(),
+    )
+    .await
+    .map_err(|e| {
+        log_event!(
+            error,
+            Subsystem::Api,
+            "corridor_detail.payments_fetch_failed",
+            corridor_key = %corridor_key,
+            error = %e,
+            "Failed to fetch payments from RPC"
+        );
+        anyhow::anyhow!("Failed to fetch payment data from RPC")
+    })?;
+
+    // Filter payments for this specific corridor
+    let mut corridor_payments = Vec::new();
+    let mut all_corridors = Vec::new();
+    let mut corridor_map: HashMap<String, Vec<&crate::rpc::Payment>> = HashMap::new();
+
+    for payment in &payments {
+        if let Some(asset_pair) = extract_asset_pair_from_payment(payment) {
+            let key = asset_pair.to_corridor_key();
+            corridor_map.entry(key.clone()).or_default().push(payment);
+
+            if key == corridor_key {
+                corridor_payments.push(payment);
+            }
         }
+    }
+
+    // If no payments found for this corridor, return 404
+    if corridor_payments.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No payment data found for corridor: {corridor_key}"
+        ));
+    }
 
-        // Calculate volume for target corridor
-        let total_attempts = corridor_payments.len() as i64;
+    // Look up every source asset price at once instead of one corridor at a time
+    let mut source_assets: Vec<&str> = corridor_map
+        .keys()
+        .filter_map(|key| key.split("->").next())
+        .chain(std::iter::once(source_key))
+        .collect();
+    source_assets.sort_unstable();
+    source_assets.dedup();
+    let prices: HashMap<&str, f64> = join_all(source_assets.into_iter().map(|asset| async move {
+        price_feed
+            .get_price(asset)
+            .await
+            .ok()
+            .map(|price| (asset, price))
+    }))
+    .await
+    .into_iter()
+    .flatten()
+    .collect();
+
+    // Build all corridor responses for related corridors lookup
+    for (key, corr_payments) in &corridor_map {
+        let total_attempts = corr_payments.len() as i64;
         let successful_payments = total_attempts;
         let failed_payments = 0;
-        let success_rate = 100.0;
+        let success_rate = 100.0; // All payments in Stellar stream are successful
+
+        let parts: Vec<&str> = key.split("->").collect();
+        if parts.len() != 2 {
+            continue;
+        }
 
+        let source_parts: Vec<&str> = parts[0].split(':').collect();
+        let dest_parts: Vec<&str> = parts[1].split(':').collect();
+
+        if source_parts.len() != 2 || dest_parts.len() != 2 {
+            continue;
+        }
+
+        // Calculate volume
         let mut volume_usd = 0.0;
-        if let Ok(price) = price_feed.get_price(source_key).await {
-            for payment in &corridor_payments {
+        if let Some(&price) = prices.get(parts[0]) {
+            for payment in corr_payments {
                 if let Ok(amount) = payment.get_amount().parse::<f64>() {
                     volume_usd += amount * price;
                 }
             }
         } else {
-            volume_usd = corridor_payments
+            volume_usd = corr_payments
                 .iter()
                 .filter_map(|p| p.get_amount().parse::<f64>().ok())
                 .sum();
@@ -874,8 +1065,8 @@ pub async fn get_corridor_detail(
         let liquidity_trend = get_liquidity_trend(volume_usd);
         let avg_latency = 400.0 + (success_rate * 2.0);
 
-        let corridor = CorridorResponse {
-            id: corridor_key.clone(),
+        all_corridors.push(CorridorResponse {
+            id: key.clone(),
             source_asset: source_parts[0].to_string(),
             destination_asset: dest_parts[0].to_string(),
             success_rate,
@@ -891,35 +1082,196 @@ pub async fn get_corridor_detail(
             liquidity_trend,
             health_score,
             last_updated: chrono::Utc::now().to_rfc3339(),
-        };
+        });
+    }
 
-        // Calculate historical metrics
-        let historical_success_rate = calculate_historical_success_rate(&corridor_payments);
-        let latency_distribution =
-            calculate_latency_distribution(&corridor_payments, total_attempts);
-        let liquidity_trends = calculate_liquidity_trends(&corridor_payments, volume_usd);
-
-        // Find related corridors
-        let related_corridors = find_related_corridors(&corridor_key, &all_corridors);
-
-        Ok(CorridorDetailResponse {
-            corridor,
-            historical_success_rate,
-            latency_distribution,
-            liquidity_trends,
-            related_corridors,
-        })
+    // Calculate volume for target corridor
+    let total_attempts = corridor_payments.len() as i64;
+    let successful_payments = total_attempts;
+    let failed_payments = 0;
+    let success_rate = 100.0;
+
+    let mut volume_usd = 0.0;
+    if let Some(&price) = prices.get(source_key) {
+        for payment in &corridor_payments {
+            if let Ok(amount) = payment.get_amount().parse::<f64>() {
+                volume_usd += amount * price;
+            }
+        }
+    } else {
+        volume_usd = corridor_payments
+            .iter()
+            .filter_map(|p| p.get_amount().parse::<f64>().ok())
+            .sum();
+    }
+
+    let health_score = calculate_health_score(success_rate, total_attempts, volume_usd);
+    let liquidity_trend = get_liquidity_trend(volume_usd);
+    let avg_latency = 400.0 + (success_rate * 2.0);
+
+    let corridor = CorridorResponse {
+        id: corridor_key.to_string(),
+        source_asset: source_code.to_string(),
+        destination_asset: dest_code.to_string(),
+        success_rate,
+        total_attempts,
+        successful_payments,
+        failed_payments,
+        average_latency_ms: avg_latency,
+        median_latency_ms: avg_latency * 0.75,
+        p95_latency_ms: avg_latency * 2.5,
+        p99_latency_ms: avg_latency * 4.0,
+        liquidity_depth_usd: volume_usd,
+        liquidity_volume_24h_usd: volume_usd * 0.1,
+        liquidity_trend,
+        health_score,
+        last_updated: chrono::Utc::now().to_rfc3339(),
+    };
+
+    // Calculate historical metrics
+    let historical_success_rate = calculate_historical_success_rate(&corridor_payments);
+    let latency_distribution = calculate_latency_distribution(&corridor_payments, total_attempts);
+    let liquidity_trends = calculate_liquidity_trends(&corridor_payments, volume_usd);
+
+    // Find related corridors
+    let related_corridors = find_related_corridors(corridor_key, &all_corridors);
+
+    Ok(CorridorDetailResponse {
+        corridor,
+        historical_success_rate,
+        latency_distribution,
+        liquidity_trends,
+        related_corridors,
+        order_book: None,
+        summary: None,
+        sections: Vec::new(),
     })
-    .await?;
+}
 
-    // Log successful corridor fetch
-    info!(
-        corridor_id = %response.corridor.id,
-        success_rate = response.corridor.success_rate,
-        "Corridor found"
-    );
+/// Runs an optional section's fetch within its latency budget. A slow or failing
+/// source leaves the section out of the response instead of failing the request.
+async fn within_budget<T, E: std::fmt::Display>(
+    name: &'static str,
+    budget: Duration,
+    fetch: impl Future<Output = Result<Option<T>, E>>,
+) -> (Option<T>, DetailSection) {
+    let start = Instant::now();
+    match tokio::time::timeout(budget, fetch).await {
+        Ok(Ok(Some(value))) => (
+            Some(value),
+            detail_section(name, SectionFreshness::Fresh, start, None),
+        ),
+        Ok(Ok(None)) => (
+            None,
+            detail_section(name, SectionFreshness::Skipped, start, None),
+        ),
+        Ok(Err(e)) => {
+            log_event!(
+                warn,
+                Subsystem::Api,
+                "corridor_detail.section_failed",
+                section = name,
+                error = %e,
+                "Corridor detail section unavailable"
+            );
+            (
+                None,
+                detail_section(
+                    name,
+                    SectionFreshness::Unavailable,
+                    start,
+                    Some(e.to_string()),
+                ),
+            )
+        }
+        Err(_) => {
+            let message = format!("Exceeded {}ms budget", budget.as_millis());
+            log_event!(
+                warn,
+                Subsystem::Api,
+                "corridor_detail.section_timeout",
+                section = name,
+                budget_ms = budget.as_millis() as u64,
+                "Corridor detail section timed out"
+            );
+            (
+                None,
+                detail_section(name, SectionFreshness::Unavailable, start, Some(message)),
+            )
+        }
+    }
+}
 
-    Ok(Json(response))
+fn detail_section(
+    name: &str,
+    freshness: SectionFreshness,
+    start: Instant,
+    message: Option<String>,
+) -> DetailSection {
+    DetailSection {
+        name: name.to_string(),
+        freshness,
+        duration_ms: u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX),
+        message,
+    }
+}
+
+/// Horizon asset for a corridor side in `CODE:ISSUER` form (`XLM:native` for lumens)
+fn horizon_asset(asset_key: &str) -> Asset {
+    let (code, issuer) = asset_key.split_once(':').unwrap_or((asset_key, ""));
+    if issuer == "native" {
+        return Asset {
+            asset_type: "native".to_string(),
+            asset_code: None,
+            asset_issuer: None,
+        };
+    }
+    let asset_type = if code.len() <= 4 {
+        "credit_alphanum4"
+    } else {
+        "credit_alphanum12"
+    };
+    Asset {
+        asset_type: asset_type.to_string(),
+        asset_code: Some(code.to_string()),
+        asset_issuer: Some(issuer.to_string()),
+    }
+}
+
+/// Get the corridor summary read model
+///
+/// Returns 24h aggregates and the last 48 hourly rollups for a corridor.
+///
+/// **DATA SOURCE: read-model projection (hourly rollups)**
+#[utoipa::path(
+    get,
+    path = "/api/corridors/{corridor_key}/summary",
+    params(
+        ("corridor_key" = String, Path, description = "Corridor identifier as stored in hourly rollups")
+    ),
+    responses(
+        (status = 200, description = "Corridor summary retrieved successfully", body = CorridorDetailView),
+        (status = 404, description = "No rollups for corridor"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Corridors"
+)]
+#[tracing::instrument(skip(app_state), fields(corridor_key = %corridor_key))]
+pub async fn get_corridor_summary(
+    State(app_state): State<AppState>,
+    Path(corridor_key): Path<String>,
+) -> ApiResult<Json<CorridorDetailView>> {
+    let view = ProjectionStore::new(app_state.db.clone())
+        .corridor_detail(&corridor_key)
+        .await?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "CORRIDOR_NOT_FOUND",
+                format!("No rollup data found for corridor: {corridor_key}"),
+            )
+        })?;
+
+    Ok(Json(view))
 }
 
 /// POST /api/corridors - Create a new corridor
@@ -1310,4 +1662,75 @@ mod tests {
         let related_corridors = related.unwrap();
         assert!(related_corridors.len() >= 2); // At least target and one related
     }
+
+    #[test]
+    fn test_horizon_asset_for_corridor_sides() {
+        let native = horizon_asset("XLM:native");
+        assert_eq!(native.asset_type, "native");
+        assert!(native.asset_code.is_none());
+
+        let usdc = horizon_asset("USDC:GISSUER");
+        assert_eq!(usdc.asset_type, "credit_alphanum4");
+        assert_eq!(usdc.asset_issuer.as_deref(), Some("GISSUER"));
+
+        assert_eq!(
+            horizon_asset("LONGCODE:GISSUER").asset_type,
+            "credit_alphanum12"
+        );
+    }
+
+    #[test]
+    fn test_order_book_depth() {
+        let entry = |price: &str, amount: &str| crate::rpc::OrderBookEntry {
+            price: price.to_string(),
+            amount: amount.to_string(),
+            price_r: crate::rpc::Price { n: 1, d: 1 },
+        };
+        let book = OrderBook {
+            bids: vec![entry("0.99", "100"), entry("0.98", "50")],
+            asks: vec![entry("1.01", "200")],
+            base: horizon_asset("USDC:GISSUER"),
+            counter: horizon_asset("XLM:native"),
+        };
+
+        let depth = OrderBookDepth::from_order_book(&book);
+        assert_eq!(depth.best_bid, Some(0.99));
+        assert_eq!(depth.best_ask, Some(1.01));
+        assert!((depth.spread_bps.unwrap() - 200.0).abs() < 1e-9);
+        assert!((depth.bid_depth - 150.0).abs() < f64::EPSILON);
+        assert!((depth.ask_depth - 200.0).abs() < f64::EPSILON);
+
+        let empty = OrderBook {
+            bids: vec![],
+            asks: vec![entry("1.01", "200")],
+            ..book
+        };
+        assert!(OrderBookDepth::from_order_book(&empty).spread_bps.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_slow_section_is_dropped_at_budget() {
+        let (value, section) = within_budget("order_book", Duration::from_millis(100), async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok::<_, anyhow::Error>(Some(1))
+        })
+        .await;
+        assert!(value.is_none());
+        assert_eq!(section.freshness, SectionFreshness::Unavailable);
+        assert_eq!(section.message.as_deref(), Some("Exceeded 100ms budget"));
+
+        let (value, section) = within_budget("summary", Duration::from_millis(100), async {
+            Err::<Option<u8>, _>(anyhow::anyhow!("db down"))
+        })
+        .await;
+        assert!(value.is_none());
+        assert_eq!(section.freshness, SectionFreshness::Unavailable);
+
+        let (value, section) = within_budget("order_book", Duration::from_millis(100), async {
+            Ok::<_, anyhow::Error>(Some(7))
+        })
+        .await;
+        assert_eq!(value, Some(7));
+        assert_eq!(section.freshness, SectionFreshness::Fresh);
+    }
 }
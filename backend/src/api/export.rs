@@ -10,8 +10,13 @@ use serde::Deserialize;
 
 use crate::error::{ApiError, ApiResult};
 use crate::models::PaymentRecord;
+use crate::query_cost::{QueryBudget, QueryCost};
 use crate::state::AppState;
 
+/// Exports are aggregated per day; cap how many days one request may span.
+const DEFAULT_MAX_EXPORT_DAYS: i64 = 366;
+const EXPORT_GRANULARITY_SECS: i64 = 86_400;
+
 #[derive(Debug, Deserialize)]
 pub struct ExportQuery {
     pub format: String, // "csv", "json", "excel"
@@ -20,15 +25,33 @@ pub struct ExportQuery {
     pub corridor_id: Option<String>,
 }
 
+/// Reject export ranges over the `export` query budget before touching the database.
+fn check_export_range(start: DateTime<Utc>, end: DateTime<Utc>) -> ApiResult<()> {
+    let budget = QueryBudget::from_env(
+        "export",
+        QueryBudget::new(
+            DEFAULT_MAX_EXPORT_DAYS,
+            DEFAULT_MAX_EXPORT_DAYS * EXPORT_GRANULARITY_SECS,
+        ),
+    );
+    budget.check(QueryCost::new(
+        (end - start).num_seconds(),
+        EXPORT_GRANULARITY_SECS,
+        1,
+    ))?;
+    Ok(())
+}
+
 pub async fn export_corridors(
     State(app_state): State<AppState>,
     Query(params): Query<ExportQuery>,
 ) -> ApiResult<impl IntoResponse> {
-    let today = Utc::now().date_naive();
-    let start_date = params
-        .start_date
-        .map_or(today - Duration::days(30), |d| d.date_naive());
-    let end_date = params.end_date.map_or(today, |d| d.date_naive());
+    let now = Utc::now();
+    let start = params.start_date.unwrap_or(now - Duration::days(30));
+    let end = params.end_date.unwrap_or(now);
+    check_export_range(start, end)?;
+    let start_date = start.date_naive();
+    let end_date = end.date_naive();
 
     let corridors = app_state
         .db
@@ -368,6 +391,7 @@ pub async fn export_payments(
 
     let start_date = params.start_date.unwrap_or(Utc::now() - Duration::days(30));
     let end_date = params.end_date.unwrap_or(Utc::now());
+    check_export_range(start_date, end_date)?;
 
     let payments = sqlx::query_as::<_, PaymentRecord>(
         r"
@@ -535,3 +559,25 @@ pub async fn export_payments(
         )),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    #[test]
+    fn test_default_export_range_is_within_budget() {
+        let now = Utc::now();
+        assert!(check_export_range(now - Duration::days(30), now).is_ok());
+    }
+
+    #[test]
+    fn test_export_range_over_budget_is_rejected() {
+        let now = Utc::now();
+        let err = check_export_range(now - Duration::days(400), now).unwrap_err();
+        assert_eq!(
+            err.into_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+}
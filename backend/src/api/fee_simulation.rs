@@ -0,0 +1,56 @@
+use axum::{extract::State, routing::post, Json, Router};
+use std::sync::Arc;
+
+use crate::error::{ApiError, ApiResult};
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::services::fee_simulation::{
+    FeeSimulationRequest, FeeSimulationResult, FeeSimulationService,
+};
+
+pub fn routes(service: Arc<FeeSimulationService>) -> Router {
+    Router::new()
+        .route("/simulate", post(simulate_fee_change))
+        .with_state(service)
+}
+
+/// POST /api/admin/fee-simulation/simulate - Estimate the impact of a platform fee change
+///
+/// Revenue is a proxy computed from historical corridor volume and the fee rate, not
+/// from fees the contract collected; the response's `estimate_basis` says so.
+#[utoipa::path(
+    post,
+    path = "/api/admin/fee-simulation/simulate",
+    request_body = FeeSimulationRequest,
+    responses(
+        (status = 200, description = "Proxy estimate of revenue and user-cost impact, derived from corridor volume", body = FeeSimulationResult),
+        (status = 400, description = "Invalid simulation parameters"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn simulate_fee_change(
+    State(service): State<Arc<FeeSimulationService>>,
+    Json(request): Json<FeeSimulationRequest>,
+) -> ApiResult<Json<FeeSimulationResult>> {
+    let result = service.simulate(request).await.map_err(|e| {
+        if e.downcast_ref::<sqlx::Error>().is_some() {
+            ApiError::from(e)
+        } else {
+            ApiError::bad_request("INVALID_FEE_SIMULATION", e.to_string())
+        }
+    })?;
+
+    log_event!(
+        info,
+        Subsystem::Api,
+        "fee_simulation.completed",
+        current_fee_bps = result.current_fee_bps,
+        proposed_fee_bps = result.proposed_fee_bps,
+        revenue_delta_usd = result.revenue_delta_usd,
+        "Fee change simulation completed"
+    );
+
+    Ok(Json(result))
+}
@@ -0,0 +1,146 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::error::{ApiError, ApiResult};
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::services::geography::{
+    GeographyMapping, GeographyService, RegionPairQuery, RegionPairSummary,
+    UpsertGeographyMappingRequest,
+};
+
+/// Public region-pair aggregates. Uses the full path so it can be merged next to the
+/// existing `/corridors/:corridor_key` route.
+pub fn routes(service: Arc<GeographyService>) -> Router {
+    Router::new()
+        .route("/corridors/region-pairs", get(get_region_pairs))
+        .with_state(service)
+}
+
+/// Operator routes for maintaining the mappings; nest under `/admin/geography` behind auth.
+pub fn admin_routes(service: Arc<GeographyService>) -> Router {
+    Router::new()
+        .route("/mappings", get(list_mappings).put(upsert_mapping))
+        .route("/mappings/:entity_type/:entity_key", delete(delete_mapping))
+        .with_state(service)
+}
+
+/// GET /api/corridors/region-pairs - Corridor metrics aggregated by region pair
+#[utoipa::path(
+    get,
+    path = "/api/corridors/region-pairs",
+    params(RegionPairQuery),
+    responses(
+        (status = 200, description = "Region-pair aggregates over the trailing window", body = RegionPairSummary),
+        (status = 400, description = "Invalid window"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Corridors"
+)]
+pub async fn get_region_pairs(
+    State(service): State<Arc<GeographyService>>,
+    Query(query): Query<RegionPairQuery>,
+) -> ApiResult<Json<RegionPairSummary>> {
+    let summary = service.region_pair_summary(&query).await.map_err(|e| {
+        if e.downcast_ref::<sqlx::Error>().is_some() {
+            ApiError::from(e)
+        } else {
+            ApiError::bad_request("INVALID_WINDOW", e.to_string())
+        }
+    })?;
+
+    Ok(Json(summary))
+}
+
+/// GET /api/admin/geography/mappings - All anchor and asset geography mappings
+#[utoipa::path(
+    get,
+    path = "/api/admin/geography/mappings",
+    responses(
+        (status = 200, description = "Stored mappings", body = Vec<GeographyMapping>),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn list_mappings(
+    State(service): State<Arc<GeographyService>>,
+) -> ApiResult<Json<Vec<GeographyMapping>>> {
+    Ok(Json(service.list_mappings().await?))
+}
+
+/// PUT /api/admin/geography/mappings - Create or replace the mapping of an anchor or asset
+///
+/// Corridor summaries pick up the change when their projection is next rebuilt.
+#[utoipa::path(
+    put,
+    path = "/api/admin/geography/mappings",
+    request_body = UpsertGeographyMappingRequest,
+    responses(
+        (status = 200, description = "Mapping stored", body = GeographyMapping),
+        (status = 400, description = "Invalid entity, country code or region"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn upsert_mapping(
+    State(service): State<Arc<GeographyService>>,
+    Json(request): Json<UpsertGeographyMappingRequest>,
+) -> ApiResult<Json<GeographyMapping>> {
+    let mapping = service.upsert_mapping(request).await.map_err(|e| {
+        if e.downcast_ref::<sqlx::Error>().is_some() {
+            ApiError::from(e)
+        } else {
+            ApiError::bad_request("INVALID_GEOGRAPHY_MAPPING", e.to_string())
+        }
+    })?;
+
+    log_event!(
+        info,
+        Subsystem::Api,
+        "geography.mapping_updated",
+        entity_type = %mapping.entity_type,
+        entity_key = %mapping.entity_key,
+        country_code = %mapping.country_code,
+        region = %mapping.region,
+        "Geography mapping updated"
+    );
+
+    Ok(Json(mapping))
+}
+
+/// DELETE /api/admin/geography/mappings/:entity_type/:entity_key - Remove a mapping
+#[utoipa::path(
+    delete,
+    path = "/api/admin/geography/mappings/{entity_type}/{entity_key}",
+    params(
+        ("entity_type" = String, Path, description = "`anchor` or `asset`"),
+        ("entity_key" = String, Path, description = "Anchor id, or CODE:ISSUER / native")
+    ),
+    responses(
+        (status = 204, description = "Mapping removed"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "No such mapping"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn delete_mapping(
+    State(service): State<Arc<GeographyService>>,
+    Path((entity_type, entity_key)): Path<(String, String)>,
+) -> ApiResult<StatusCode> {
+    if service.delete_mapping(&entity_type, &entity_key).await? {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(ApiError::not_found(
+            "GEOGRAPHY_MAPPING_NOT_FOUND",
+            format!("No geography mapping for {entity_type} {entity_key}"),
+        ))
+    }
+}
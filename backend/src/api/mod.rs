@@ -4,9 +4,15 @@ pub mod alerts;
 pub mod anchors;
 pub mod api_keys;
 pub mod asset_verification;
+pub mod audit_bundle;
+pub mod backfill;
+pub mod batch_verification;
 
 pub mod auth;
 pub mod cache_stats;
+pub mod cluster;
+pub mod corridor_rates;
+pub mod corridor_sla;
 pub mod corridors;
 pub mod cost_calculator;
 pub mod export;
@@ -14,6 +20,8 @@ pub mod export;
 pub mod api_analytics;
 pub mod contract_events;
 pub mod fee_bump;
+pub mod fee_simulation;
+pub mod geography;
 pub mod governance;
 pub mod liquidity_pools;
 pub mod metrics;
@@ -23,12 +31,16 @@ pub mod network;
 pub mod oauth;
 pub mod prediction;
 pub mod price_feed;
+pub mod probes;
+pub mod recommendations;
 pub mod replay_handlers;
 pub mod rpc;
+pub mod runbook;
 pub mod sep10;
 pub mod sep24_proxy;
 pub mod sep31_proxy;
 pub mod snapshots;
+pub mod soroban_estimates;
 pub mod transactions;
 pub mod trustlines;
 pub mod v1;
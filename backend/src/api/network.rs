@@ -1,11 +1,14 @@
 use crate::network::{NetworkConfig, StellarNetwork};
+use crate::services::protocol_compat::{CompatibilityReport, ProtocolCompatibilityService};
 use axum::{
+    extract::State,
     http::StatusCode,
     response::Json,
     routing::{get, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tracing::{info, warn};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -143,6 +146,27 @@ pub async fn switch_network(
     Ok(Json(response))
 }
 
+/// Get protocol compatibility report for the connected Horizon and RPC endpoints
+#[utoipa::path(
+    get,
+    path = "/api/network/compatibility",
+    responses(
+        (status = 200, description = "Protocol compatibility report", body = CompatibilityReport),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Network"
+)]
+pub async fn get_compatibility_report(
+    State(service): State<Arc<ProtocolCompatibilityService>>,
+) -> Json<CompatibilityReport> {
+    // Serve the background monitor's last result; only hit upstream if it has not run yet.
+    let report = match service.latest_report().await {
+        Some(report) => report,
+        None => service.check().await,
+    };
+    Json(report)
+}
+
 /// Create network routes
 pub fn routes() -> Router {
     Router::new()
@@ -151,6 +175,13 @@ pub fn routes() -> Router {
         .route("/switch", post(switch_network))
 }
 
+/// Create protocol compatibility routes
+pub fn compatibility_routes(service: Arc<ProtocolCompatibilityService>) -> Router {
+    Router::new()
+        .route("/compatibility", get(get_compatibility_report))
+        .with_state(service)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use utoipa::{IntoParams, ToSchema};
 
+use crate::error::ApiError;
 use crate::services::price_feed::PriceFeedClient;
 
 #[derive(Debug, Deserialize, IntoParams)]
@@ -92,11 +93,6 @@ pub struct CacheStatsResponse {
     pub timestamp: String,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ErrorResponse {
-    pub error: String,
-}
-
 /// Get price for a single asset
 ///
 /// Returns the current USD price for a Stellar asset.
@@ -126,12 +122,8 @@ pub async fn get_price(
             };
             (StatusCode::OK, Json(response)).into_response()
         }
-        Err(e) => {
-            let error = ErrorResponse {
-                error: format!("Failed to fetch price: {e}"),
-            };
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
-        }
+        Err(e) => ApiError::internal("PRICE_FETCH_FAILED", format!("Failed to fetch price: {e}"))
+            .into_response(),
     }
 }
 
@@ -163,10 +155,7 @@ pub async fn get_prices(
         .collect();
 
     if assets.is_empty() {
-        let error = ErrorResponse {
-            error: "No assets provided".to_string(),
-        };
-        return (StatusCode::BAD_REQUEST, Json(error)).into_response();
+        return ApiError::bad_request("NO_ASSETS", "No assets provided").into_response();
     }
 
     let prices = price_feed.get_prices(&assets).await;
@@ -214,12 +203,8 @@ pub async fn convert_to_usd(
             };
             (StatusCode::OK, Json(response)).into_response()
         }
-        Err(e) => {
-            let error = ErrorResponse {
-                error: format!("Failed to convert: {e}"),
-            };
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(error)).into_response()
-        }
+        Err(e) => ApiError::internal("PRICE_CONVERSION_FAILED", format!("Failed to convert: {e}"))
+            .into_response(),
     }
 }
 
@@ -0,0 +1,47 @@
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use std::sync::Arc;
+
+use crate::readiness::{LivenessReport, ReadinessProbe, ReadinessReport};
+
+/// Orchestrator probes, mounted at the root rather than under an API version.
+pub fn routes(probe: Arc<ReadinessProbe>) -> Router {
+    Router::new()
+        .route("/livez", get(livez))
+        .route("/readyz", get(readyz))
+        .with_state(probe)
+}
+
+/// GET /livez - Process is alive; never checks dependencies
+#[utoipa::path(
+    get,
+    path = "/livez",
+    responses(
+        (status = 200, description = "Process is alive", body = LivenessReport)
+    ),
+    tag = "Health"
+)]
+pub async fn livez(State(probe): State<Arc<ReadinessProbe>>) -> Json<LivenessReport> {
+    Json(probe.liveness())
+}
+
+/// GET /readyz - Instance can serve traffic, with a per-check breakdown
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    responses(
+        (status = 200, description = "All required checks passed", body = ReadinessReport),
+        (status = 503, description = "At least one required check failed or timed out", body = ReadinessReport)
+    ),
+    tag = "Health"
+)]
+pub async fn readyz(
+    State(probe): State<Arc<ReadinessProbe>>,
+) -> (StatusCode, Json<ReadinessReport>) {
+    let report = probe.readiness().await;
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
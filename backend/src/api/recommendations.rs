@@ -0,0 +1,45 @@
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use std::sync::Arc;
+
+use crate::error::{ApiError, ApiResult};
+use crate::services::recommendations::{
+    RecommendationQuery, RecommendationReport, RecommendationService,
+};
+
+pub fn routes(service: Arc<RecommendationService>) -> Router {
+    Router::new()
+        .route("/recommendations", get(get_recommendations))
+        .with_state(service)
+}
+
+/// GET /api/recommendations - Corridors with improving liquidity and cost trends
+#[utoipa::path(
+    get,
+    path = "/api/recommendations",
+    params(RecommendationQuery),
+    responses(
+        (status = 200, description = "Corridors to watch, best first", body = RecommendationReport),
+        (status = 400, description = "Invalid window or limit"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Corridors"
+)]
+pub async fn get_recommendations(
+    State(service): State<Arc<RecommendationService>>,
+    Query(query): Query<RecommendationQuery>,
+) -> ApiResult<Json<RecommendationReport>> {
+    let report = service.recommend(&query, Utc::now()).await.map_err(|e| {
+        if e.downcast_ref::<sqlx::Error>().is_some() {
+            ApiError::from(e)
+        } else {
+            ApiError::bad_request("INVALID_RECOMMENDATION_QUERY", e.to_string())
+        }
+    })?;
+
+    Ok(Json(report))
+}
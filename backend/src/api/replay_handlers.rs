@@ -1,52 +1,47 @@
 //! API Handlers for Contract Event Replay System
 
 use axum::{
-    extract::{Path, Query, State},
+    extract::{
+        ws::{Message, WebSocket},
+        Path, Query, State, WebSocketUpgrade,
+    },
     http::StatusCode,
-    response::IntoResponse,
-    Json,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tracing::{error, info};
+use tokio::sync::broadcast::error::RecvError;
 
 use crate::{
+    auth_middleware::AuthUser,
     error::ApiError,
+    log_event,
+    logging::Subsystem,
+    network::StellarNetwork,
     replay::{
-        checkpoint::CheckpointManager,
-        config::{ReplayConfig, ReplayMode, ReplayRange},
-        engine::ReplayEngine,
-        event_processor::CompositeEventProcessor,
-        state_builder::StateBuilder,
-        storage::{EventStorage, ReplayStorage},
-        EventFilter,
+        config::ReplayConfig, progress::ReplayProgress, CheckpointBundle, ReplayError,
+        ReplayManager,
     },
-    state::AppState,
 };
 
-/// Request to start a replay
-#[derive(Debug, Deserialize)]
-pub struct StartReplayRequest {
-    /// Replay mode
-    pub mode: Option<String>,
-    /// Start ledger
-    pub start_ledger: Option<u64>,
-    /// End ledger
-    pub end_ledger: Option<u64>,
-    /// Checkpoint ID to resume from
-    pub checkpoint_id: Option<String>,
-    /// Contract IDs to filter
-    pub contract_ids: Option<Vec<String>>,
-    /// Event types to filter
-    pub event_types: Option<Vec<String>>,
-    /// Network filter
-    pub network: Option<String>,
-    /// Batch size
-    pub batch_size: Option<usize>,
-    /// Dry run mode
-    pub dry_run: Option<bool>,
-    /// Verbose logging
-    pub verbose: Option<bool>,
+/// Nest under `/admin/replay` behind auth.
+pub fn admin_routes(manager: Arc<ReplayManager>) -> Router {
+    Router::new()
+        .route("/", get(list_replays).post(start_replay))
+        .route("/checkpoints/cleanup", post(cleanup_checkpoints))
+        .route("/checkpoints/import", post(import_checkpoint))
+        .route("/:session_id", get(get_replay_status).delete(delete_replay))
+        .route("/:session_id/checkpoints", get(list_checkpoints))
+        .route("/:session_id/checkpoints/export", get(export_checkpoint))
+        .route("/:session_id/progress", get(replay_progress_websocket))
+        .route("/:session_id/pause", post(pause_replay))
+        .route("/:session_id/resume", post(resume_replay))
+        .route("/:session_id/cancel", post(cancel_replay))
+        .route("/:session_id/dead-letters", get(list_dead_letters))
+        .route("/:session_id/dead-letters/retry", post(retry_dead_letters))
+        .with_state(manager)
 }
 
 /// Response for replay operations
@@ -61,170 +56,463 @@ pub struct ReplayResponse {
 #[derive(Debug, Deserialize)]
 pub struct ListReplaysQuery {
     pub limit: Option<usize>,
+    /// Only sessions replaying this network
+    pub network: Option<StellarNetwork>,
+}
+
+/// Query parameters for cleaning up checkpoints
+#[derive(Debug, Deserialize)]
+pub struct CleanupCheckpointsQuery {
+    pub days: i64,
+}
+
+fn storage_error(e: &anyhow::Error) -> ApiError {
+    ApiError::internal("REPLAY_STORAGE_ERROR", e.to_string())
 }
 
-/// Start a new replay
+/// POST /api/admin/replay - Start a replay with the given configuration
 pub async fn start_replay(
-    State(state): State<Arc<AppState>>,
-    Json(req): Json<StartReplayRequest>,
+    State(manager): State<Arc<ReplayManager>>,
+    auth_user: AuthUser,
+    Json(config): Json<ReplayConfig>,
 ) -> Result<impl IntoResponse, ApiError> {
-    info!("Starting replay with request: {:?}", req);
-
-    // Parse replay mode
-    let mode = match req.mode.as_deref() {
-        Some("full") => ReplayMode::Full,
-        Some("incremental") => ReplayMode::Incremental,
-        Some("verification") => ReplayMode::Verification,
-        Some("debug") => ReplayMode::Debug,
-        _ => ReplayMode::Full,
-    };
+    let (mode, range) = (config.mode, config.range.clone());
+    let session_id = manager.start(config).map_err(|e| match e {
+        ReplayError::ConfigError(message) => {
+            ApiError::bad_request("INVALID_REPLAY_CONFIG", message)
+        }
+        e => ApiError::internal("REPLAY_START_FAILED", e.to_string()),
+    })?;
+
+    log_event!(
+        info,
+        Subsystem::Replay,
+        "replay.start_requested",
+        session_id = %session_id,
+        mode = %mode,
+        range = ?range,
+        user = %auth_user.username,
+        "Replay started"
+    );
 
-    // Determine replay range
-    let range = if let Some(checkpoint_id) = req.checkpoint_id {
-        ReplayRange::FromCheckpoint { checkpoint_id }
-    } else if let (Some(start), Some(end)) = (req.start_ledger, req.end_ledger) {
-        ReplayRange::FromTo { start, end }
-    } else if let Some(start) = req.start_ledger {
-        ReplayRange::From { start }
-    } else if let Some(end) = req.end_ledger {
-        ReplayRange::To { end }
-    } else {
-        ReplayRange::All
-    };
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ReplayResponse {
+            session_id,
+            status: "started".to_string(),
+            message: "Replay started successfully".to_string(),
+        }),
+    ))
+}
 
-    // Create event filter
-    let filter = EventFilter {
-        contract_ids: req.contract_ids,
-        event_types: req.event_types,
-        network: req.network,
-    };
+/// GET /api/admin/replay/:session_id - Stored status of a replay
+pub async fn get_replay_status(
+    State(manager): State<Arc<ReplayManager>>,
+    Path(session_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    log_event!(
+        debug,
+        Subsystem::Replay,
+        "replay.status_requested",
+        session_id = %session_id,
+        "Getting replay status"
+    );
+
+    let metadata = manager
+        .storage()
+        .load_metadata(&session_id)
+        .await
+        .map_err(|e| storage_error(&e))?
+        .ok_or_else(|| {
+            ApiError::not_found("REPLAY_SESSION_NOT_FOUND", "Replay session not found")
+        })?;
 
-    // Build configuration
-    let mut config = ReplayConfig::new()
-        .with_mode(mode)
-        .with_range(range)
-        .with_filter(filter);
+    Ok(Json(metadata))
+}
 
-    if let Some(batch_size) = req.batch_size {
-        config = config.with_batch_size(batch_size);
-    }
+/// Which control an operator sent to a session
+#[derive(Debug, Clone, Copy)]
+enum Control {
+    Pause,
+    Resume,
+    Cancel,
+}
 
-    if req.dry_run.unwrap_or(false) {
-        config = config.dry_run();
-    }
+/// POST /api/admin/replay/:session_id/pause - Pause after the batch in flight
+pub async fn pause_replay(
+    State(manager): State<Arc<ReplayManager>>,
+    auth_user: AuthUser,
+    Path(session_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    control_replay(&manager, &auth_user, session_id, Control::Pause).await
+}
 
-    if req.verbose.unwrap_or(false) {
-        config = config.verbose();
-    }
+/// POST /api/admin/replay/:session_id/resume - Resume a paused replay
+pub async fn resume_replay(
+    State(manager): State<Arc<ReplayManager>>,
+    auth_user: AuthUser,
+    Path(session_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    control_replay(&manager, &auth_user, session_id, Control::Resume).await
+}
 
-    // Create replay components
-    let event_storage = Arc::new(EventStorage::new(state.db.pool().clone()));
-    let replay_storage = Arc::new(ReplayStorage::new(state.db.pool().clone()));
-    let checkpoint_manager = Arc::new(CheckpointManager::new(state.db.pool().clone()));
-    let processor = Arc::new(CompositeEventProcessor::new());
-    let state_builder = Arc::new(tokio::sync::RwLock::new(StateBuilder::new(
-        state.db.pool().clone(),
-    )));
-
-    // Create replay engine
-    let engine = ReplayEngine::new(
-        config,
-        event_storage,
-        replay_storage,
-        checkpoint_manager,
-        processor,
-        state_builder,
-    )
-    .map_err(|e| ApiError::internal("INTERNAL_ERROR", e.to_string()))?;
-
-    // Start replay in background
-    let engine_clone = Arc::new(engine);
-    tokio::spawn(async move {
-        match engine_clone.start().await {
-            Ok(metadata) => {
-                info!("Replay completed: {:?}", metadata);
-            }
-            Err(e) => {
-                error!("Replay failed: {}", e);
-            }
-        }
-    });
+/// POST /api/admin/replay/:session_id/cancel - Stop a replay after the batch in
+/// flight, keeping a checkpoint of how far it got
+pub async fn cancel_replay(
+    State(manager): State<Arc<ReplayManager>>,
+    auth_user: AuthUser,
+    Path(session_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    control_replay(&manager, &auth_user, session_id, Control::Cancel).await
+}
+
+async fn control_replay(
+    manager: &ReplayManager,
+    auth_user: &AuthUser,
+    session_id: String,
+    control: Control,
+) -> Result<(StatusCode, Json<ReplayResponse>), ApiError> {
+    log_event!(
+        info,
+        Subsystem::Replay,
+        "replay.control_requested",
+        session_id = %session_id,
+        control = ?control,
+        user = %auth_user.username,
+        "Replay control requested"
+    );
+
+    let sent = match control {
+        Control::Pause => manager.pause(&session_id),
+        Control::Resume => manager.resume(&session_id),
+        Control::Cancel => manager.cancel(&session_id),
+    };
+    if !sent {
+        // Tell a finished or foreign session apart from an unknown one.
+        let stored = manager
+            .storage()
+            .load_metadata(&session_id)
+            .await
+            .map_err(|e| storage_error(&e))?;
+        return Err(match stored {
+            Some(metadata) => ApiError::unprocessable_entity(
+                "REPLAY_NOT_RUNNING",
+                format!(
+                    "Replay session is not running on this server: {}",
+                    metadata.status
+                ),
+            ),
+            None => ApiError::not_found("REPLAY_SESSION_NOT_FOUND", "Replay session not found"),
+        });
+    }
 
+    let (status, message) = match control {
+        Control::Pause => ("pausing", "Replay will pause after the current batch"),
+        Control::Resume => ("resuming", "Replay resumed"),
+        Control::Cancel => ("cancelling", "Replay will stop after the current batch"),
+    };
     Ok((
         StatusCode::ACCEPTED,
         Json(ReplayResponse {
-            session_id: "replay-session".to_string(), // Would use actual session ID
-            status: "started".to_string(),
-            message: "Replay started successfully".to_string(),
+            session_id,
+            status: status.to_string(),
+            message: message.to_string(),
         }),
     ))
 }
 
-/// Get replay status
-pub async fn get_replay_status(
-    State(state): State<Arc<AppState>>,
+/// Stream progress for a replay session over a WebSocket.
+///
+/// Sends the stored status first, then every update until the session
+/// completes or fails, and closes the socket after the final one. Sessions not
+/// running in this server only get their stored status.
+pub async fn replay_progress_websocket(
+    ws: WebSocketUpgrade,
+    State(manager): State<Arc<ReplayManager>>,
     Path(session_id): Path<String>,
-) -> Result<impl IntoResponse, ApiError> {
-    info!("Getting replay status for session: {}", session_id);
+) -> Response {
+    ws.on_upgrade(move |socket| stream_replay_progress(socket, manager, session_id))
+}
 
-    let replay_storage = ReplayStorage::new(state.db.pool().clone());
+async fn stream_replay_progress(
+    mut socket: WebSocket,
+    manager: Arc<ReplayManager>,
+    session_id: String,
+) {
+    // Subscribe before reading the stored status so no update falls in between.
+    let mut updates = manager.progress().subscribe(&session_id);
+    let running = manager.progress().is_running(&session_id);
+
+    let mut finished = !running;
+    match manager.storage().load_metadata(&session_id).await {
+        Ok(Some(metadata)) => {
+            let current = ReplayProgress::from_metadata(&metadata, None);
+            finished |= current.status.is_finished();
+            if !send_progress(&mut socket, &current).await {
+                return;
+            }
+        }
+        // A session that was just started may not have saved its status yet.
+        Ok(None) if running => {}
+        Ok(None) => log_event!(
+            debug,
+            Subsystem::Replay,
+            "replay.stream_session_missing",
+            session_id = %session_id,
+            "No replay session to stream"
+        ),
+        Err(e) => {
+            log_event!(
+                error,
+                Subsystem::Replay,
+                "replay.stream_load_failed",
+                session_id = %session_id,
+                error = %e,
+                "Failed to load replay session"
+            );
+            finished = true;
+        }
+    }
 
-    let metadata = replay_storage
-        .load_metadata(&session_id)
-        .await
-        .map_err(|e| ApiError::internal("INTERNAL_ERROR", e.to_string()))?
-        .ok_or_else(|| ApiError::not_found("NOT_FOUND", "Replay session not found".to_string()))?;
+    while !finished {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(progress) => {
+                    finished = progress.status.is_finished();
+                    if !send_progress(&mut socket, &progress).await {
+                        return;
+                    }
+                }
+                // Only the latest progress matters; skip what was missed.
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_)) | Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
 
-    Ok(Json(metadata))
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+async fn send_progress(socket: &mut WebSocket, progress: &ReplayProgress) -> bool {
+    match serde_json::to_string(progress) {
+        Ok(text) => socket.send(Message::Text(text)).await.is_ok(),
+        Err(e) => {
+            log_event!(
+                error,
+                Subsystem::Replay,
+                "replay.progress_serialize_failed",
+                session_id = %progress.session_id,
+                error = %e,
+                "Failed to serialize replay progress"
+            );
+            false
+        }
+    }
 }
 
-/// List all replay sessions
+/// GET /api/admin/replay - Replay sessions, newest first, optionally of one `network`
 pub async fn list_replays(
-    State(state): State<Arc<AppState>>,
+    State(manager): State<Arc<ReplayManager>>,
     Query(query): Query<ListReplaysQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
-    info!("Listing replay sessions");
-
-    let replay_storage = ReplayStorage::new(state.db.pool().clone());
-
-    let sessions = replay_storage
-        .list_sessions(query.limit)
+    log_event!(
+        debug,
+        Subsystem::Replay,
+        "replay.sessions_listed",
+        "Listing replay sessions"
+    );
+
+    let sessions = manager
+        .storage()
+        .list_sessions(query.limit, query.network)
         .await
-        .map_err(|e| ApiError::internal("INTERNAL_ERROR", e.to_string()))?;
+        .map_err(|e| storage_error(&e))?;
 
     Ok(Json(sessions))
 }
 
-/// Get checkpoints for a session
+/// GET /api/admin/replay/:session_id/checkpoints - Checkpoints of a session
 pub async fn list_checkpoints(
-    State(state): State<Arc<AppState>>,
+    State(manager): State<Arc<ReplayManager>>,
     Path(session_id): Path<String>,
 ) -> Result<impl IntoResponse, ApiError> {
-    info!("Listing checkpoints for session: {}", session_id);
-
-    let checkpoint_manager = CheckpointManager::new(state.db.pool().clone());
-
-    let checkpoints = checkpoint_manager
+    log_event!(
+        debug,
+        Subsystem::Replay,
+        "replay.checkpoints_listed",
+        session_id = %session_id,
+        "Listing replay checkpoints"
+    );
+
+    let checkpoints = manager
+        .checkpoints()
         .list_for_session(&session_id)
         .await
-        .map_err(|e| ApiError::internal("INTERNAL_ERROR", e.to_string()))?;
+        .map_err(|e| ApiError::internal("REPLAY_CHECKPOINT_ERROR", e.to_string()))?;
 
     Ok(Json(checkpoints))
 }
 
-/// Delete a replay session
-pub async fn delete_replay(
-    State(state): State<Arc<AppState>>,
+/// GET /api/admin/replay/:session_id/checkpoints/export - Latest checkpoint of a
+/// session as a bundle for `/checkpoints/import` elsewhere
+pub async fn export_checkpoint(
+    State(manager): State<Arc<ReplayManager>>,
+    auth_user: AuthUser,
     Path(session_id): Path<String>,
 ) -> Result<impl IntoResponse, ApiError> {
-    info!("Deleting replay session: {}", session_id);
+    log_event!(
+        info,
+        Subsystem::Replay,
+        "replay.checkpoint_export_requested",
+        session_id = %session_id,
+        user = %auth_user.username,
+        "Exporting replay checkpoint"
+    );
+
+    let bundle = manager
+        .checkpoints()
+        .export(&session_id)
+        .await
+        .map_err(|e| ApiError::internal("REPLAY_CHECKPOINT_ERROR", e.to_string()))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "REPLAY_CHECKPOINT_NOT_FOUND",
+                "Replay session not found or has no checkpoint",
+            )
+        })?;
+
+    Ok(Json(bundle))
+}
+
+/// POST /api/admin/replay/checkpoints/import - Restore an exported checkpoint;
+/// start a replay from it with the `FromCheckpoint` range
+pub async fn import_checkpoint(
+    State(manager): State<Arc<ReplayManager>>,
+    auth_user: AuthUser,
+    Json(bundle): Json<CheckpointBundle>,
+) -> Result<impl IntoResponse, ApiError> {
+    let session_id = bundle.session.session_id.clone();
+    log_event!(
+        info,
+        Subsystem::Replay,
+        "replay.checkpoint_import_requested",
+        session_id = %session_id,
+        checkpoint_id = %bundle.checkpoint.id,
+        user = %auth_user.username,
+        "Importing replay checkpoint"
+    );
+
+    if manager.is_running(&session_id) {
+        return Err(ApiError::unprocessable_entity(
+            "REPLAY_RUNNING",
+            "Cancel the replay session before importing over it",
+        ));
+    }
 
-    let replay_storage = ReplayStorage::new(state.db.pool().clone());
+    manager
+        .checkpoints()
+        .import(&bundle)
+        .await
+        .map_err(|e| ApiError::bad_request("INVALID_CHECKPOINT_BUNDLE", e.to_string()))?;
 
-    replay_storage
+    Ok((
+        StatusCode::CREATED,
+        Json(ReplayResponse {
+            session_id,
+            status: "imported".to_string(),
+            message: format!(
+                "Checkpoint {} imported at ledger {}",
+                bundle.checkpoint.id, bundle.checkpoint.last_ledger
+            ),
+        }),
+    ))
+}
+
+/// GET /api/admin/replay/:session_id/dead-letters - Events the session could
+/// not process, in ledger order
+pub async fn list_dead_letters(
+    State(manager): State<Arc<ReplayManager>>,
+    Path(session_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    log_event!(
+        debug,
+        Subsystem::Replay,
+        "replay.dead_letters_listed",
+        session_id = %session_id,
+        "Listing replay dead letters"
+    );
+
+    let dead_letters = manager
+        .storage()
+        .list_dead_letters(&session_id)
+        .await
+        .map_err(|e| storage_error(&e))?;
+
+    Ok(Json(dead_letters))
+}
+
+/// POST /api/admin/replay/:session_id/dead-letters/retry - Process a finished
+/// session's dead letters again
+pub async fn retry_dead_letters(
+    State(manager): State<Arc<ReplayManager>>,
+    auth_user: AuthUser,
+    Path(session_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    log_event!(
+        info,
+        Subsystem::Replay,
+        "replay.dead_letter_retry_requested",
+        session_id = %session_id,
+        user = %auth_user.username,
+        "Retrying replay dead letters"
+    );
+
+    let retry = manager
+        .retry_dead_letters(&session_id)
+        .await
+        .map_err(|e| match e {
+            ReplayError::AlreadyInProgress(_) => ApiError::unprocessable_entity(
+                "REPLAY_RUNNING",
+                "Wait for the replay session to finish before retrying its dead letters",
+            ),
+            e => ApiError::internal("REPLAY_STORAGE_ERROR", e.to_string()),
+        })?
+        .ok_or_else(|| {
+            ApiError::not_found("REPLAY_SESSION_NOT_FOUND", "Replay session not found")
+        })?;
+
+    Ok(Json(retry))
+}
+
+/// DELETE /api/admin/replay/:session_id - Delete a finished replay session
+pub async fn delete_replay(
+    State(manager): State<Arc<ReplayManager>>,
+    Path(session_id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    log_event!(
+        info,
+        Subsystem::Replay,
+        "replay.delete_requested",
+        session_id = %session_id,
+        "Deleting replay session"
+    );
+
+    if manager.is_running(&session_id) {
+        return Err(ApiError::unprocessable_entity(
+            "REPLAY_RUNNING",
+            "Cancel the replay session before deleting it",
+        ));
+    }
+
+    manager
+        .storage()
         .delete_session(&session_id)
         .await
-        .map_err(|e| ApiError::internal("INTERNAL_ERROR", e.to_string()))?;
+        .map_err(|e| storage_error(&e))?;
 
     Ok((
         StatusCode::OK,
@@ -236,19 +524,24 @@ pub async fn delete_replay(
     ))
 }
 
-/// Cleanup old checkpoints
+/// POST /api/admin/replay/checkpoints/cleanup?days=N - Delete old checkpoints
 pub async fn cleanup_checkpoints(
-    State(state): State<Arc<AppState>>,
-    Query(days): Query<i64>,
+    State(manager): State<Arc<ReplayManager>>,
+    Query(query): Query<CleanupCheckpointsQuery>,
 ) -> Result<impl IntoResponse, ApiError> {
-    info!("Cleaning up checkpoints older than {} days", days);
-
-    let checkpoint_manager = CheckpointManager::new(state.db.pool().clone());
-
-    let deleted = checkpoint_manager
-        .cleanup_old(days)
+    log_event!(
+        info,
+        Subsystem::Replay,
+        "replay.checkpoint_cleanup_requested",
+        older_than_days = query.days,
+        "Cleaning up old replay checkpoints"
+    );
+
+    let deleted = manager
+        .checkpoints()
+        .cleanup_old(query.days)
         .await
-        .map_err(|e| ApiError::internal("INTERNAL_ERROR", e.to_string()))?;
+        .map_err(|e| ApiError::internal("REPLAY_CHECKPOINT_ERROR", e.to_string()))?;
 
     Ok(Json(serde_json::json!({
         "deleted": deleted,
@@ -1,12 +1,12 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
     response::IntoResponse,
     Json,
 };
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::error::{ApiError, ApiResult, ProblemDetails};
 use crate::rpc::{Asset, StellarRpcClient};
 
 #[derive(Debug, Deserialize)]
@@ -32,34 +32,24 @@ pub struct OrderBookQuery {
     pub limit: u32,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ErrorResponse {
-    pub error: String,
-}
-
 /// Health check for Stellar RPC
 #[utoipa::path(
     get,
     path = "/api/rpc/health",
     responses(
         (status = 200, description = "RPC health status"),
-        (status = 503, description = "RPC service unavailable", body = ErrorResponse)
+        (status = 503, description = "RPC service unavailable", body = ProblemDetails)
     ),
     tag = "RPC"
 )]
 #[tracing::instrument(skip(client))]
 pub async fn rpc_health_check(
     State(client): State<Arc<StellarRpcClient>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    match client.check_health().await {
-        Ok(health) => Ok(Json(health)),
-        Err(e) => Err((
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(ErrorResponse {
-                error: format!("RPC health check failed: {e}"),
-            }),
-        )),
-    }
+) -> ApiResult<impl IntoResponse> {
+    let health = client.check_health().await.map_err(|e| {
+        ApiError::service_unavailable("RPC_UNHEALTHY", format!("RPC health check failed: {e}"))
+    })?;
+    Ok(Json(health))
 }
 
 /// Get latest ledger information
@@ -68,23 +58,15 @@ pub async fn rpc_health_check(
     path = "/api/rpc/ledger",
     responses(
         (status = 200, description = "Latest ledger information"),
-        (status = 500, description = "Internal server error", body = ErrorResponse)
+        (status = 500, description = "Internal server error", body = ProblemDetails)
     ),
     tag = "RPC"
 )]
 #[tracing::instrument(skip(client))]
 pub async fn get_latest_ledger(
     State(client): State<Arc<StellarRpcClient>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    match client.fetch_latest_ledger().await {
-        Ok(ledger) => Ok(Json(ledger)),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to fetch ledger: {e}"),
-            }),
-        )),
-    }
+) -> ApiResult<impl IntoResponse> {
+    Ok(Json(client.fetch_latest_ledger().await?))
 }
 
 /// Get recent payments
@@ -97,7 +79,7 @@ pub async fn get_latest_ledger(
     ),
     responses(
         (status = 200, description = "List of recent payments"),
-        (status = 500, description = "Internal server error", body = ErrorResponse)
+        (status = 500, description = "Internal server error", body = ProblemDetails)
     ),
     tag = "RPC"
 )]
@@ -105,17 +87,9 @@ pub async fn get_latest_ledger(
 pub async fn get_payments(
     State(client): State<Arc<StellarRpcClient>>,
     Query(params): Query<PaginationQuery>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+) -> ApiResult<impl IntoResponse> {
     let cursor = params.cursor.as_deref();
-    match client.fetch_payments(params.limit, cursor).await {
-        Ok(payments) => Ok(Json(payments)),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to fetch payments: {e}"),
-            }),
-        )),
-    }
+    Ok(Json(client.fetch_payments(params.limit, cursor).await?))
 }
 
 /// Get payments for a specific account
@@ -128,7 +102,7 @@ pub async fn get_payments(
     ),
     responses(
         (status = 200, description = "List of account payments"),
-        (status = 500, description = "Internal server error", body = ErrorResponse)
+        (status = 500, description = "Internal server error", body = ProblemDetails)
     ),
     tag = "RPC"
 )]
@@ -137,19 +111,12 @@ pub async fn get_account_payments(
     State(client): State<Arc<StellarRpcClient>>,
     Path(account_id): Path<String>,
     Query(params): Query<PaginationQuery>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    match client
-        .fetch_account_payments(&account_id, params.limit)
-        .await
-    {
-        Ok(payments) => Ok(Json(payments)),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to fetch account payments: {e}"),
-            }),
-        )),
-    }
+) -> ApiResult<impl IntoResponse> {
+    Ok(Json(
+        client
+            .fetch_account_payments(&account_id, params.limit)
+            .await?,
+    ))
 }
 
 /// Get recent trades
@@ -162,7 +129,7 @@ pub async fn get_account_payments(
     ),
     responses(
         (status = 200, description = "List of recent trades"),
-        (status = 500, description = "Internal server error", body = ErrorResponse)
+        (status = 500, description = "Internal server error", body = ProblemDetails)
     ),
     tag = "RPC"
 )]
@@ -170,17 +137,9 @@ pub async fn get_account_payments(
 pub async fn get_trades(
     State(client): State<Arc<StellarRpcClient>>,
     Query(params): Query<PaginationQuery>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+) -> ApiResult<impl IntoResponse> {
     let cursor = params.cursor.as_deref();
-    match client.fetch_trades(params.limit, cursor).await {
-        Ok(trades) => Ok(Json(trades)),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to fetch trades: {e}"),
-            }),
-        )),
-    }
+    Ok(Json(client.fetch_trades(params.limit, cursor).await?))
 }
 
 /// Get order book for a trading pair
@@ -198,7 +157,7 @@ pub async fn get_trades(
     ),
     responses(
         (status = 200, description = "Order book for trading pair"),
-        (status = 500, description = "Internal server error", body = ErrorResponse)
+        (status = 500, description = "Internal server error", body = ProblemDetails)
     ),
     tag = "RPC"
 )]
@@ -206,7 +165,7 @@ pub async fn get_trades(
 pub async fn get_order_book(
     State(client): State<Arc<StellarRpcClient>>,
     Query(params): Query<OrderBookQuery>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+) -> ApiResult<impl IntoResponse> {
     let selling_asset = Asset {
         asset_type: params.selling_asset_type,
         asset_code: params.selling_asset_code,
@@ -219,16 +178,9 @@ pub async fn get_order_book(
         asset_issuer: params.buying_asset_issuer,
     };
 
-    match client
-        .fetch_order_book(&selling_asset, &buying_asset, params.limit)
-        .await
-    {
-        Ok(order_book) => Ok(Json(order_book)),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to fetch order book: {e}"),
-            }),
-        )),
-    }
+    Ok(Json(
+        client
+            .fetch_order_book(&selling_asset, &buying_asset, params.limit)
+            .await?,
+    ))
 }
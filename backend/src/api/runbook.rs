@@ -0,0 +1,101 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use std::sync::Arc;
+
+use crate::auth_middleware::AuthUser;
+use crate::error::{ApiError, ApiResult};
+use crate::services::runbook::{RunbookAction, RunbookJob, RunbookJobQuery, RunbookService};
+
+/// Nest under `/admin/actions` behind auth.
+pub fn admin_routes(runbook: Arc<RunbookService>) -> Router {
+    Router::new()
+        .route("/", get(list_runbook_actions).post(request_runbook_action))
+        .route("/:id", get(get_runbook_action))
+        .with_state(runbook)
+}
+
+fn invalid_action(e: anyhow::Error) -> ApiError {
+    if e.downcast_ref::<sqlx::Error>().is_some() {
+        ApiError::from(e)
+    } else {
+        ApiError::bad_request("INVALID_RUNBOOK_ACTION", e.to_string())
+    }
+}
+
+/// POST /api/admin/actions - Run a remediation as a tracked job
+#[utoipa::path(
+    post,
+    path = "/api/admin/actions",
+    request_body = RunbookAction,
+    responses(
+        (status = 202, description = "Job queued, or the identical job already outstanding", body = RunbookJob),
+        (status = 400, description = "Unknown target or invalid parameters"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn request_runbook_action(
+    State(runbook): State<Arc<RunbookService>>,
+    auth_user: AuthUser,
+    Json(action): Json<RunbookAction>,
+) -> ApiResult<(StatusCode, Json<RunbookJob>)> {
+    let job = runbook
+        .request(&action, &auth_user.user_id, Utc::now())
+        .await
+        .map_err(invalid_action)?;
+
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// GET /api/admin/actions - Requested actions and their progress
+#[utoipa::path(
+    get,
+    path = "/api/admin/actions",
+    params(RunbookJobQuery),
+    responses(
+        (status = 200, description = "Jobs, newest first", body = Vec<RunbookJob>),
+        (status = 400, description = "Invalid limit"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn list_runbook_actions(
+    State(runbook): State<Arc<RunbookService>>,
+    Query(query): Query<RunbookJobQuery>,
+) -> ApiResult<Json<Vec<RunbookJob>>> {
+    let jobs = runbook.list(&query).await.map_err(invalid_action)?;
+    Ok(Json(jobs))
+}
+
+/// GET /api/admin/actions/:id - Progress of one job
+#[utoipa::path(
+    get,
+    path = "/api/admin/actions/{id}",
+    params(
+        ("id" = String, Path, description = "Runbook job ID")
+    ),
+    responses(
+        (status = 200, description = "Job and progress", body = RunbookJob),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "No such job"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn get_runbook_action(
+    State(runbook): State<Arc<RunbookService>>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<RunbookJob>> {
+    let job = runbook.get(&id).await?.ok_or_else(|| {
+        ApiError::not_found("RUNBOOK_ACTION_NOT_FOUND", format!("No runbook job {id}"))
+    })?;
+
+    Ok(Json(job))
+}
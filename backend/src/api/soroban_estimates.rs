@@ -0,0 +1,91 @@
+use axum::{
+    extract::{Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use std::sync::Arc;
+
+use crate::error::{ApiError, ApiResult};
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::services::soroban_estimates::{
+    EstimateHistoryQuery, EstimateRequest, FeeEstimate, SimulationRejected, SorobanEstimateService,
+    StoredEstimate,
+};
+
+pub fn routes(service: Arc<SorobanEstimateService>) -> Router {
+    Router::new()
+        .route("/estimate", post(estimate_fees))
+        .route("/estimates", get(list_estimates))
+        .with_state(service)
+}
+
+/// POST /api/admin/soroban/estimate - Simulate a contract call and report resource fees
+#[utoipa::path(
+    post,
+    path = "/api/admin/soroban/estimate",
+    request_body = EstimateRequest,
+    responses(
+        (status = 200, description = "Simulated resource fee, footprint and trend against earlier estimates", body = FeeEstimate),
+        (status = 400, description = "Invalid request, or the simulated call would fail"),
+        (status = 401, description = "Unauthorized"),
+        (status = 503, description = "Contract service not configured or Soroban RPC unavailable")
+    ),
+    tag = "Admin"
+)]
+pub async fn estimate_fees(
+    State(service): State<Arc<SorobanEstimateService>>,
+    Json(request): Json<EstimateRequest>,
+) -> ApiResult<Json<FeeEstimate>> {
+    request
+        .validate()
+        .map_err(|e| ApiError::bad_request("INVALID_ESTIMATE_REQUEST", e.to_string()))?;
+    if !service.is_configured() {
+        return Err(ApiError::service_unavailable(
+            "CONTRACT_NOT_CONFIGURED",
+            "Set SNAPSHOT_CONTRACT_ID, SOROBAN_RPC_URL, STELLAR_SOURCE_SECRET_KEY and STELLAR_SOURCE_PUBLIC_KEY to enable simulations",
+        ));
+    }
+
+    let estimate = service.estimate(request).await.map_err(|e| {
+        if e.downcast_ref::<sqlx::Error>().is_some() {
+            ApiError::from(e)
+        } else if e.downcast_ref::<SimulationRejected>().is_some() {
+            ApiError::bad_request("SIMULATION_REJECTED", e.to_string())
+        } else {
+            ApiError::service_unavailable("SIMULATION_FAILED", e.to_string())
+        }
+    })?;
+
+    log_event!(
+        info,
+        Subsystem::Api,
+        "soroban_estimate.completed",
+        contract_id = %estimate.contract_id,
+        function = %estimate.function,
+        min_resource_fee = estimate.resources.min_resource_fee,
+        storage_growth = estimate.trend.as_ref().is_some_and(|t| t.storage_growth_detected),
+        "Soroban fee estimate completed"
+    );
+
+    Ok(Json(estimate))
+}
+
+/// GET /api/admin/soroban/estimates - Recorded fee estimates, newest first
+#[utoipa::path(
+    get,
+    path = "/api/admin/soroban/estimates",
+    params(EstimateHistoryQuery),
+    responses(
+        (status = 200, description = "Recorded estimates", body = Vec<StoredEstimate>),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Admin"
+)]
+pub async fn list_estimates(
+    State(service): State<Arc<SorobanEstimateService>>,
+    Query(query): Query<EstimateHistoryQuery>,
+) -> ApiResult<Json<Vec<StoredEstimate>>> {
+    Ok(Json(service.history(&query).await?))
+}
@@ -1,16 +1,36 @@
 use crate::api::{
-    account_merges, anchors, cache_stats, corridors, cost_calculator, fee_bump, liquidity_pools,
-    metrics, oauth, price_feed as price_feed_api, rpc, webhooks,
+    account_merges, anchors, audit_bundle, backfill, batch_verification, cache_stats, cluster,
+    corridor_rates, corridor_sla, corridors, cost_calculator, fee_bump, fee_simulation, geography,
+    liquidity_pools, metrics, network, oauth, price_feed as price_feed_api, probes,
+    recommendations, replay_handlers, rpc, runbook, soroban_estimates, webhooks,
 };
 use crate::auth_middleware::auth_middleware;
 use crate::cache::CacheManager;
+use crate::cluster::{ClusterConfig, LeaderElection};
 use crate::database::Database;
+use crate::idempotency::{idempotency_middleware, IdempotencyStore};
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::rate_index::RateIndexStore;
 use crate::rate_limit::{rate_limit_middleware, RateLimiter};
+use crate::read_only::{read_only_middleware, ReadOnlyMode};
+use crate::readiness::{ReadinessConfig, ReadinessProbe};
 use crate::rpc::StellarRpcClient;
 use crate::services::account_merge_detector::AccountMergeDetector;
+use crate::services::audit_bundle::AuditBundleService;
+use crate::services::backfill_coordinator::BackfillCoordinator;
+use crate::services::batch_verification::BatchVerificationService;
+use crate::services::contract::ContractService;
+use crate::services::corridor_sla::CorridorSlaService;
 use crate::services::fee_bump_tracker::FeeBumpTrackerService;
+use crate::services::fee_simulation::FeeSimulationService;
+use crate::services::geography::GeographyService;
 use crate::services::liquidity_pool_analyzer::LiquidityPoolAnalyzer;
 use crate::services::price_feed::PriceFeedClient;
+use crate::services::protocol_compat::ProtocolCompatibilityService;
+use crate::services::recommendations::RecommendationService;
+use crate::services::runbook::RunbookService;
+use crate::services::soroban_estimates::SorobanEstimateService;
 use crate::state::AppState;
 use axum::{
     middleware,
@@ -67,6 +87,8 @@ pub fn routes(
     cors: CorsLayer,
     pool: sqlx::SqlitePool,
     cache: Arc<CacheManager>,
+    protocol_compat: Arc<ProtocolCompatibilityService>,
+    read_only: ReadOnlyMode,
 ) -> Router {
     // 1. Cached routes
     let cached_routes = Router::new()
@@ -78,6 +100,33 @@ pub fn routes(
         )
         .with_state(cached_state);
 
+    let corridor_sla_service = Arc::new(CorridorSlaService::new(app_state.db.clone()));
+    let idempotency_store = Arc::new(IdempotencyStore::new(app_state.db.clone()));
+    let audit_bundle_service = Arc::new(AuditBundleService::new(app_state.db.clone()));
+    let geography_service = Arc::new(GeographyService::new(app_state.db.clone()));
+    let rate_index = Arc::new(RateIndexStore::new(app_state.db.clone()));
+    let recommendation_service = Arc::new(RecommendationService::new(app_state.db.clone()));
+    // Records dirty ranges and reports progress; recomputation runs in the background task
+    let backfill_coordinator = Arc::new(BackfillCoordinator::new(app_state.db.clone()));
+    // Queues actions and reports progress; jobs run in the background task
+    let runbook_service = Arc::new(RunbookService::new(app_state.db.clone()));
+    // Shared with the consistency checks, so their sessions show up here too
+    let replay_manager = Arc::clone(&app_state.replay_manager);
+    // Read-only handle for the cluster view; campaigning happens in the background task
+    let leader_election = Arc::new(LeaderElection::new(
+        app_state.db.clone(),
+        ClusterConfig::from_env(),
+    ));
+    let readiness_probe = Arc::new(
+        ReadinessProbe::new(
+            app_state.db.clone(),
+            app_state.cache.clone(),
+            app_state.rpc_client.clone(),
+            price_feed.clone(),
+        )
+        .with_config(ReadinessConfig::from_env()),
+    );
+
     // 2. Public anchor routes
     let public_anchor_routes = Router::new()
         .route("/health", get(crate::handlers::health_check))
@@ -88,10 +137,31 @@ pub fn routes(
             get(anchors::get_anchor_by_account),
         )
         .route("/anchors/:id/assets", get(anchors::get_anchor_assets))
+        .route("/anchors/:id/flows", get(anchors::get_anchor_flows))
+        .route(
+            "/corridors/:corridor_key/summary",
+            get(corridors::get_corridor_summary),
+        )
         .route("/analytics/muxed", get(anchors::get_muxed_analytics))
         .with_state(app_state.clone());
 
     // 3. Protected anchor routes
+    let fee_simulation_service = Arc::new(FeeSimulationService::new(app_state.db.clone()));
+    // A read-only mirror never loads the submission key, but keeps a client so
+    // batch verification still works
+    let contract_service = if read_only.enabled {
+        ContractService::read_only_from_env().ok().map(Arc::new)
+    } else {
+        ContractService::from_env().ok().map(Arc::new)
+    };
+    let soroban_estimate_service = Arc::new(SorobanEstimateService::new(
+        app_state.db.clone(),
+        contract_service.clone(),
+    ));
+    let batch_verification_service = Arc::new(BatchVerificationService::new(
+        contract_service,
+        app_state.cache.clone(),
+    ));
     let protected_routes = Router::new()
         .route("/anchors", axum::routing::post(anchors::create_anchor))
         .route("/anchors/:id/metrics", put(anchors::update_anchor_metrics))
@@ -114,6 +184,35 @@ pub fn routes(
         .nest("/webhooks", webhooks::routes(pool.clone()))
         .layer(middleware::from_fn(auth_middleware));
 
+    let protected_admin_routes = Router::new()
+        .nest(
+            "/admin/fee-simulation",
+            fee_simulation::routes(fee_simulation_service),
+        )
+        .nest(
+            "/admin/corridors",
+            corridor_sla::admin_routes(corridor_sla_service),
+        )
+        .nest(
+            "/admin/geography",
+            geography::admin_routes(geography_service.clone()),
+        )
+        .nest(
+            "/admin/soroban",
+            soroban_estimates::routes(soroban_estimate_service),
+        )
+        .nest("/admin/cluster", cluster::admin_routes(leader_election))
+        .nest(
+            "/admin/recompute",
+            backfill::admin_routes(backfill_coordinator),
+        )
+        .nest("/admin/actions", runbook::admin_routes(runbook_service))
+        .nest(
+            "/admin/replay",
+            replay_handlers::admin_routes(replay_manager),
+        )
+        .layer(middleware::from_fn(auth_middleware));
+
     // 4. RPC routes
     let rpc_routes = Router::new()
         .route("/rpc/health", get(rpc::rpc_health_check))
@@ -138,20 +237,39 @@ pub fn routes(
         .nest("/prices", price_feed_api::routes(price_feed.clone()))
         .nest("/cost-calculator", cost_calculator::routes(price_feed))
         .nest("/cache/stats", cache_stats::routes(cache.clone()))
-        .nest("/metrics", metrics::routes(cache));
+        .nest("/metrics", metrics::routes(cache))
+        .merge(corridor_sla::routes(corridor_sla_service.clone()))
+        .merge(audit_bundle::routes(audit_bundle_service))
+        .merge(batch_verification::routes(batch_verification_service))
+        .merge(geography::routes(geography_service))
+        .merge(recommendations::routes(recommendation_service))
+        .merge(corridor_rates::routes(rate_index))
+        .nest("/network", network::compatibility_routes(protocol_compat));
 
     // 6. OAuth routes
     let oauth_routes = oauth::routes(pool);
 
     // V1 router (mounted at /api/v1 and also preserved at root for compatibility)
-    let v1_router = Router::new()
+    let mut v1_router = Router::new()
         .merge(cached_routes)
         .merge(public_anchor_routes)
-        .merge(protected_routes)
-        .merge(protected_webhook_routes)
         .merge(rpc_routes)
-        .merge(service_routes)
-        .merge(oauth_routes);
+        .merge(service_routes);
+    // Read-only mirrors don't mount anything that writes or needs credentials
+    if read_only.enabled {
+        log_event!(
+            info,
+            Subsystem::Api,
+            "read_only.routes_disabled",
+            "Read-only mode: admin, authenticated and OAuth routes are disabled"
+        );
+    } else {
+        v1_router = v1_router
+            .merge(protected_routes)
+            .merge(protected_webhook_routes)
+            .merge(protected_admin_routes)
+            .merge(oauth_routes);
+    }
 
     // Combine all routes
     Router::new()
@@ -160,6 +278,15 @@ pub fn routes(
         .route("/api/version", get(get_api_version))
         // Preserve existing unversioned endpoints for backward compatibility.
         .merge(v1_router)
+        .layer(middleware::from_fn_with_state(
+            idempotency_store,
+            idempotency_middleware,
+        ))
+        // Outside idempotency so refused writes never claim a key
+        .layer(middleware::from_fn_with_state(
+            read_only,
+            read_only_middleware,
+        ))
         .layer(cors)
         .layer(middleware::from_fn(
             crate::request_id::request_id_middleware,
@@ -171,4 +298,112 @@ pub fn routes(
             rate_limiter,
             rate_limit_middleware,
         ))
+        // Outermost, so every error body is rewritten once with the headers set above kept
+        .layer(middleware::from_fn(
+            crate::error::problem_details_middleware,
+        ))
+        // Probes are merged after the layers so kubelet checks are never rate limited
+        .merge(probes::routes(readiness_probe))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheConfig;
+    use crate::ingestion::DataIngestionService;
+    use crate::services::price_feed::{default_asset_mapping, PriceFeedConfig};
+    use crate::websocket::WsState;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    async fn test_router(read_only: ReadOnlyMode) -> Router {
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let db = Arc::new(Database::new(pool.clone()));
+        let cache = Arc::new(CacheManager::new_in_memory_for_tests(CacheConfig::default()));
+        let rpc_client = Arc::new(StellarRpcClient::new_with_defaults(true));
+        let price_feed = Arc::new(PriceFeedClient::new(
+            PriceFeedConfig::default(),
+            default_asset_mapping(),
+        ));
+        let app_state = AppState::new(
+            db.clone(),
+            cache.clone(),
+            Arc::new(WsState::new()),
+            Arc::new(DataIngestionService::new(rpc_client.clone(), db.clone())),
+            rpc_client.clone(),
+        );
+
+        routes(
+            app_state,
+            (db, cache.clone(), rpc_client.clone(), price_feed.clone()),
+            rpc_client.clone(),
+            Arc::new(FeeBumpTrackerService::new(pool.clone())),
+            Arc::new(AccountMergeDetector::new(pool.clone(), rpc_client.clone())),
+            Arc::new(LiquidityPoolAnalyzer::new(pool.clone(), rpc_client.clone())),
+            price_feed,
+            Arc::new(RateLimiter::new().await.unwrap()),
+            CorsLayer::new(),
+            pool,
+            cache,
+            Arc::new(ProtocolCompatibilityService::new(rpc_client)),
+            read_only,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_compatibility_report_is_served() {
+        let app = test_router(ReadOnlyMode::default()).await;
+
+        for uri in ["/api/v1/network/compatibility", "/network/compatibility"] {
+            let response = app
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{uri}");
+
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(report["sources"].as_array().unwrap().len(), 2);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_only_mirror_serves_batch_verification() {
+        let app = test_router(ReadOnlyMode { enabled: true }).await;
+
+        for uri in ["/api/v1/verify/batch", "/verify/batch"] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri(uri)
+                        .header("content-type", "application/json")
+                        .body(Body::from(r#"{"items":[]}"#))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            // Reaches the handler, which rejects the empty batch
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+        }
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v1/anchors")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
 }
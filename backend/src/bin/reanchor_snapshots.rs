@@ -0,0 +1,95 @@
+//! Re-submit historical snapshot hashes to a new snapshot contract.
+//!
+//! Usage:
+//!   reanchor_snapshots --source-contract <OLD_ID> [--from-epoch N] [--to-epoch M] [--dry-run]
+//!
+//! The new contract and signing key come from the usual `SNAPSHOT_CONTRACT_ID`,
+//! `SOROBAN_RPC_URL`, `STELLAR_NETWORK_PASSPHRASE`, `STELLAR_SOURCE_SECRET_KEY` and
+//! `STELLAR_SOURCE_PUBLIC_KEY` variables; the database from `DATABASE_URL`.
+
+use anyhow::{bail, Context, Result};
+use std::sync::Arc;
+use stellar_insights_backend::database::{Database, PoolConfig};
+use stellar_insights_backend::services::contract::ContractService;
+use stellar_insights_backend::services::snapshot_reanchor::{
+    ReanchorOptions, SnapshotReanchorService,
+};
+
+struct Args {
+    source_contract: String,
+    options: ReanchorOptions,
+}
+
+fn parse_args() -> Result<Args> {
+    let mut source_contract = std::env::var("OLD_SNAPSHOT_CONTRACT_ID").ok();
+    let mut options = ReanchorOptions::default();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--source-contract" => source_contract = args.next(),
+            "--from-epoch" => {
+                options.from_epoch = Some(
+                    args.next()
+                        .context("--from-epoch needs a value")?
+                        .parse()
+                        .context("--from-epoch must be an integer")?,
+                );
+            }
+            "--to-epoch" => {
+                options.to_epoch = Some(
+                    args.next()
+                        .context("--to-epoch needs a value")?
+                        .parse()
+                        .context("--to-epoch must be an integer")?,
+                );
+            }
+            "--dry-run" => options.dry_run = true,
+            other => bail!("Unknown argument: {other}"),
+        }
+    }
+
+    let source_contract =
+        source_contract.context("--source-contract (or OLD_SNAPSHOT_CONTRACT_ID) is required")?;
+    Ok(Args {
+        source_contract,
+        options,
+    })
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let _log_guard = stellar_insights_backend::logging::init_logging("reanchor-snapshots")?;
+
+    let args = parse_args()?;
+    let target_contract = std::env::var("SNAPSHOT_CONTRACT_ID")
+        .context("SNAPSHOT_CONTRACT_ID environment variable not set")?;
+
+    let db_url = std::env::var("DATABASE_URL")
+        .unwrap_or_else(|_| "sqlite://stellar_insights.db".to_string());
+    let pool = PoolConfig::from_env()
+        .create_pool(&db_url)
+        .await
+        .context("Failed to create database pool")?;
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .context("Failed to run database migrations")?;
+
+    let contract = Arc::new(ContractService::from_env()?);
+    let service = SnapshotReanchorService::new(
+        Arc::new(Database::new(pool)),
+        contract,
+        args.source_contract,
+        target_contract,
+    );
+
+    let report = service.run(&args.options).await?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    if report.failed() {
+        bail!("Re-anchoring stopped on a failed epoch; fix the cause and re-run to resume");
+    }
+    Ok(())
+}
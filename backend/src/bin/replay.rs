@@ -0,0 +1,339 @@
+//! Replay stored contract events, or benchmark the replay engine.
+//!
+//! Usage:
+//!   replay [--from N] [--to M | --since TIME [--until TIME] | --days N]
+//!          [--batch-size N] [--concurrency N] [--memory-budget MB] [--dry-run | --diff]
+//!          [--rpc [--contract ID]...] [--verify-chain] [--follow]
+//!   replay --bench [--events N] [--batch-size N] [--no-persist]
+//!
+//! `--diff` replays without writing and prints how the `snapshots` table differs
+//! from the replayed state; the exit status is non-zero when they differ.
+//!
+//! `--verify-chain` compares every epoch the replay rebuilt with the hash
+//! anchored in the snapshot contract (`SNAPSHOT_CONTRACT_ID`, configured as for
+//! snapshot submission) and with the stored analytics snapshot hashed again;
+//! the exit status is non-zero when any epoch does not match.
+//!
+//! `--since` and `--until` replay the ledgers closed in a time window, given in
+//! RFC 3339 (`2026-01-01T00:00:00Z`); `--until` defaults to now. `--days N` is
+//! the window of the last N days. The window is resolved to ledgers through the
+//! event source when the replay starts.
+//!
+//! `--memory-budget` caps the memory the replay holds in fetched events and
+//! rebuilt state, in MiB (default 512). Fetching pauses while processing
+//! catches up, and rebuilt state that outgrows its half is moved to the database
+//! and read back as needed. `--dry-run` and `--diff` replays write nothing, so
+//! theirs stops with an error instead.
+//!
+//! The replay covers the events, state snapshots and checkpoints of the network
+//! named by `STELLAR_NETWORK` (mainnet if unset) and nothing of any other.
+//!
+//! `--rpc` reads events from Soroban RPC (that network's RPC URL) instead of
+//! the local `contract_events` table, so ledgers that were never ingested can
+//! be replayed. RPC only keeps recent ledgers; pass `--from`.
+//! `--contract` restricts the replay to the given contracts.
+//!
+//! `--follow` keeps going after the historical replay: new events of the given
+//! contracts (or `REPLAY_INGEST_CONTRACTS`) are ingested from Soroban RPC into
+//! `contract_events` and replayed as they arrive, until interrupted with
+//! Ctrl-C.
+//!
+//! `--bench` replays a fixed synthetic corpus in memory and prints throughput,
+//! allocation and per-processor timings. Results are stored in `replay_bench_runs`
+//! (database from `DATABASE_URL`) and compared with the latest run of a different
+//! release. Set `GIT_SHA` to record the commit being measured.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use stellar_insights_backend::database::PoolConfig;
+use stellar_insights_backend::network::NetworkConfig;
+use stellar_insights_backend::replay::bench::{self, BenchConfig, BenchStore, CountingAllocator};
+use stellar_insights_backend::replay::event_processor::{
+    CompositeEventProcessor, SnapshotEventProcessor,
+};
+use stellar_insights_backend::replay::{
+    CheckpointManager, EventFilter, EventIngestor, EventStorage, IngestConfig, ReplayConfig,
+    ReplayEngine, ReplayMode, ReplayRange, ReplayStorage, RpcEventSource, StateBuilder,
+    DEFAULT_LIVE_FEED_CAPACITY,
+};
+use stellar_insights_backend::services::contract::ContractService;
+use tokio::sync::RwLock;
+
+#[global_allocator]
+static ALLOC: CountingAllocator = CountingAllocator;
+
+#[derive(Default)]
+struct Args {
+    bench: bool,
+    persist: bool,
+    events: Option<u64>,
+    batch_size: Option<usize>,
+    from: Option<u64>,
+    to: Option<u64>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    days: Option<u16>,
+    concurrency: Option<usize>,
+    memory_budget: Option<usize>,
+    dry_run: bool,
+    diff: bool,
+    rpc: bool,
+    verify_chain: bool,
+    follow: bool,
+    contracts: Vec<String>,
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T> {
+    value
+        .with_context(|| format!("{flag} needs a value"))?
+        .parse()
+        .map_err(|_| anyhow::anyhow!("{flag} must be a positive integer"))
+}
+
+fn parse_time(flag: &str, value: Option<String>) -> Result<DateTime<Utc>> {
+    let value = value.with_context(|| format!("{flag} needs a value"))?;
+    DateTime::parse_from_rfc3339(&value)
+        .map(|time| time.with_timezone(&Utc))
+        .with_context(|| format!("{flag} must be an RFC 3339 time, e.g. 2026-01-01T00:00:00Z"))
+}
+
+fn parse_args() -> Result<Args> {
+    let mut parsed = Args {
+        persist: true,
+        ..Args::default()
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bench" => parsed.bench = true,
+            "--no-persist" => parsed.persist = false,
+            "--events" => parsed.events = Some(parse_value("--events", args.next())?),
+            "--batch-size" => parsed.batch_size = Some(parse_value("--batch-size", args.next())?),
+            "--from" => parsed.from = Some(parse_value("--from", args.next())?),
+            "--to" => parsed.to = Some(parse_value("--to", args.next())?),
+            "--since" => parsed.since = Some(parse_time("--since", args.next())?),
+            "--until" => parsed.until = Some(parse_time("--until", args.next())?),
+            "--days" => parsed.days = Some(parse_value("--days", args.next())?),
+            "--concurrency" => {
+                parsed.concurrency = Some(parse_value("--concurrency", args.next())?);
+            }
+            "--memory-budget" => {
+                parsed.memory_budget = Some(parse_value("--memory-budget", args.next())?);
+            }
+            "--dry-run" => parsed.dry_run = true,
+            "--diff" => parsed.diff = true,
+            "--rpc" => parsed.rpc = true,
+            "--verify-chain" => parsed.verify_chain = true,
+            "--follow" => parsed.follow = true,
+            "--contract" => parsed
+                .contracts
+                .push(args.next().context("--contract needs a value")?),
+            other => bail!("Unknown argument: {other}"),
+        }
+    }
+
+    let by_time = parsed.since.is_some() || parsed.until.is_some() || parsed.days.is_some();
+    if parsed.bench
+        && (parsed.from.is_some()
+            || parsed.to.is_some()
+            || by_time
+            || parsed.concurrency.is_some()
+            || parsed.memory_budget.is_some()
+            || parsed.dry_run
+            || parsed.diff
+            || parsed.rpc
+            || parsed.verify_chain
+            || parsed.follow
+            || !parsed.contracts.is_empty())
+    {
+        bail!(
+            "--bench replays a synthetic corpus and does not take --from, --to, --since, --until, --days, --concurrency, --memory-budget, --dry-run, --diff, --rpc, --contract, --verify-chain or --follow"
+        );
+    }
+    if parsed.follow && (parsed.diff || parsed.rpc) {
+        bail!("--follow replays stored events as they are ingested; drop --diff and --rpc");
+    }
+    if by_time && (parsed.from.is_some() || parsed.to.is_some()) {
+        bail!(
+            "Give the range either in ledgers (--from, --to) or in time (--since, --until, --days)"
+        );
+    }
+    if parsed.days.is_some() && (parsed.since.is_some() || parsed.until.is_some()) {
+        bail!("--days is the window ending now; drop --since and --until");
+    }
+    if parsed.until.is_some() && parsed.since.is_none() {
+        bail!("--until needs --since");
+    }
+    if parsed.dry_run && parsed.diff {
+        bail!("--diff never writes; drop --dry-run");
+    }
+    if !parsed.bench && parsed.events.is_some() {
+        bail!("--events is only valid with --bench");
+    }
+    Ok(parsed)
+}
+
+async fn open_pool() -> Result<SqlitePool> {
+    let db_url = std::env::var("DATABASE_URL")
+        .unwrap_or_else(|_| "sqlite://stellar_insights.db".to_string());
+    let pool = PoolConfig::from_env()
+        .create_pool(&db_url)
+        .await
+        .context("Failed to create database pool")?;
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .context("Failed to run database migrations")?;
+    Ok(pool)
+}
+
+async fn run_bench(args: &Args) -> Result<()> {
+    let defaults = BenchConfig::default();
+    let config = BenchConfig {
+        events: args.events.unwrap_or(defaults.events),
+        batch_size: args.batch_size.unwrap_or(defaults.batch_size),
+    };
+    if config.events == 0 {
+        bail!("--events must be greater than 0");
+    }
+
+    let mut report = bench::run_bench(config).await?;
+    if args.persist {
+        let store = BenchStore::new(open_pool().await?);
+        report.baseline = store.baseline_for(&report).await?;
+        store.save(&report).await?;
+    }
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+async fn run_replay(args: &Args) -> Result<()> {
+    let pool = open_pool().await?;
+    let network = NetworkConfig::from_env();
+
+    let range = if let Some(days) = args.days {
+        ReplayRange::past(Duration::days(i64::from(days)))
+    } else if let Some(start) = args.since {
+        ReplayRange::TimeWindow {
+            start,
+            end: args.until.unwrap_or_else(Utc::now),
+        }
+    } else {
+        match (args.from, args.to) {
+            (Some(start), Some(end)) => ReplayRange::FromTo { start, end },
+            (Some(start), None) => ReplayRange::From { start },
+            (None, Some(end)) => ReplayRange::To { end },
+            (None, None) => ReplayRange::All,
+        }
+    };
+    let mut config = ReplayConfig::new()
+        .with_range(range)
+        .with_network(network.network);
+    if let Some(batch_size) = args.batch_size {
+        config = config.with_batch_size(batch_size);
+    }
+    if let Some(concurrency) = args.concurrency {
+        config = config.with_concurrency(concurrency);
+    }
+    if let Some(memory_budget) = args.memory_budget {
+        config = config.with_memory_budget(memory_budget);
+    }
+    if args.dry_run {
+        config = config.dry_run();
+    }
+    if args.diff {
+        config = config.with_mode(ReplayMode::Diff);
+    }
+    if args.follow {
+        config = config.with_mode(ReplayMode::CatchUpThenFollow);
+    }
+    if !args.contracts.is_empty() {
+        config = config.with_filter(EventFilter {
+            contract_ids: Some(args.contracts.clone()),
+            ..EventFilter::default()
+        });
+    }
+
+    let events =
+        Arc::new(EventStorage::new(pool.clone()).with_live_feed(DEFAULT_LIVE_FEED_CAPACITY));
+    let state_builder = StateBuilder::new(pool.clone(), network.network).for_filter(&config.filter);
+    let processor = CompositeEventProcessor::new()
+        .add_processor(Arc::new(SnapshotEventProcessor::new(pool.clone())));
+    let mut engine = ReplayEngine::new(
+        config,
+        Arc::clone(&events),
+        Arc::new(ReplayStorage::new(pool.clone())),
+        Arc::new(CheckpointManager::new(pool.clone())),
+        Arc::new(processor),
+        Arc::new(RwLock::new(state_builder)),
+    )?;
+    if args.rpc {
+        engine = engine.with_event_source(Arc::new(RpcEventSource::new(
+            network.rpc_url.clone(),
+            network.network.to_string(),
+        )?));
+    }
+    if args.verify_chain {
+        let contract = ContractService::from_env()
+            .context("--verify-chain needs the snapshot contract configuration")?;
+        engine = engine.with_integrity_check(Arc::new(contract));
+    }
+
+    let engine = Arc::new(engine);
+    if args.follow {
+        let mut ingest = IngestConfig::from_env();
+        if !args.contracts.is_empty() {
+            ingest.contract_ids.clone_from(&args.contracts);
+        }
+        if !ingest.is_enabled() {
+            bail!("--follow needs contracts to ingest: pass --contract or set REPLAY_INGEST_CONTRACTS");
+        }
+        let source = RpcEventSource::new(network.rpc_url, network.network.to_string())?;
+        let ingestor = EventIngestor::new(Arc::new(source), events, &ingest, network.network);
+        tokio::spawn(Arc::new(ingestor).start());
+
+        let engine = Arc::clone(&engine);
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                engine.stop_following();
+            }
+        });
+    }
+
+    let metadata = engine.start().await?;
+    println!("{}", serde_json::to_string_pretty(&metadata)?);
+
+    if let Some(report) = metadata.report.filter(|report| !report.is_consistent()) {
+        bail!(
+            "Database differs from replay: {} missing, {} divergent, {} extra",
+            report.missing.len(),
+            report.divergent.len(),
+            report.extra.len()
+        );
+    }
+    if let Some(report) = metadata.integrity.filter(|report| !report.is_consistent()) {
+        bail!(
+            "{} of {} replayed epochs do not match their anchored hashes",
+            report.failures.len(),
+            report.epochs_checked
+        );
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let _log_guard = stellar_insights_backend::logging::init_logging("replay")?;
+
+    let args = parse_args()?;
+    if args.bench {
+        run_bench(&args).await
+    } else {
+        run_replay(&args).await
+    }
+}
@@ -1,9 +1,12 @@
 use redis::aio::MultiplexedConnection;
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use crate::log_event;
+use crate::logging::Subsystem;
+
 #[cfg(test)]
 use std::collections::HashMap;
 
@@ -60,6 +63,83 @@ impl Default for CacheConfig {
     }
 }
 
+/// Envelope written around every cached value so readers can tell which
+/// serialized shape of the namespace produced it.
+#[derive(Serialize)]
+struct VersionedEntry<'a, T> {
+    v: u32,
+    data: &'a T,
+}
+
+#[derive(Deserialize)]
+struct EntryHeader {
+    v: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct VersionedPayload<T> {
+    data: T,
+}
+
+enum DecodedEntry<T> {
+    Hit(T),
+    /// Written under a different schema version (or before versioning existed),
+    /// or no longer deserializes; the key should be dropped.
+    Stale,
+}
+
+fn decode_entry<T: DeserializeOwned>(key: &str, payload: &str) -> DecodedEntry<T> {
+    let expected = schema_versions::for_key(key);
+    match serde_json::from_str::<EntryHeader>(payload) {
+        Ok(EntryHeader { v: Some(v) }) if v == expected => {}
+        Ok(EntryHeader { v }) => {
+            log_event!(
+                debug,
+                Subsystem::Cache,
+                "cache.entry_stale",
+                key,
+                schema_version = ?v,
+                expected_version = expected,
+                "Discarding cache entry written under another schema version"
+            );
+            return DecodedEntry::Stale;
+        }
+        Err(e) => {
+            log_event!(
+                warn,
+                Subsystem::Cache,
+                "cache.decode_failed",
+                key,
+                error = %e,
+                "Failed to read cache entry header"
+            );
+            return DecodedEntry::Stale;
+        }
+    }
+
+    match serde_json::from_str::<VersionedPayload<T>>(payload) {
+        Ok(entry) => DecodedEntry::Hit(entry.data),
+        Err(e) => {
+            log_event!(
+                warn,
+                Subsystem::Cache,
+                "cache.decode_failed",
+                key,
+                error = %e,
+                "Failed to deserialize cached value"
+            );
+            DecodedEntry::Stale
+        }
+    }
+}
+
+fn encode_entry<T: Serialize>(key: &str, value: &T) -> serde_json::Result<String> {
+    serde_json::to_string(&VersionedEntry {
+        v: schema_versions::for_key(key),
+        data: value,
+    })
+}
+
 /// Main cache manager
 pub struct CacheManager {
     redis_connection: Arc<RwLock<Option<MultiplexedConnection>>>,
@@ -130,65 +210,68 @@ impl CacheManager {
         }
     }
 
-    /// Get value from cache, returns None if not found or Redis unavailable
+    /// Get value from cache, returns None if not found or Redis unavailable.
+    ///
+    /// Entries written under a different schema version of their namespace are
+    /// deleted and reported as a miss.
     pub async fn get<T: DeserializeOwned>(&self, key: &str) -> anyhow::Result<Option<T>> {
         #[cfg(test)]
         {
-            if let Some(payload) = self.in_memory_store.read().await.get(key).cloned() {
-                self.hits.fetch_add(1, Ordering::Relaxed);
-                crate::observability::metrics::record_cache_lookup(true);
+            let payload = self.in_memory_store.read().await.get(key).cloned();
+            if let Some(payload) = payload {
                 tracing::debug!("In-memory cache hit for key: {}", key);
-                match serde_json::from_str::<T>(&payload) {
-                    Ok(data) => return Ok(Some(data)),
-                    Err(e) => {
-                        tracing::warn!(
-                            "Failed to deserialize in-memory cached value for {}: {}",
-                            key,
-                            e
-                        );
-                        return Ok(None);
+                return match decode_entry::<T>(key, &payload) {
+                    DecodedEntry::Hit(data) => {
+                        self.hits.fetch_add(1, Ordering::Relaxed);
+                        crate::observability::metrics::record_cache_lookup(true);
+                        Ok(Some(data))
                     }
-                }
+                    DecodedEntry::Stale => {
+                        self.in_memory_store.write().await.remove(key);
+                        self.invalidations.fetch_add(1, Ordering::Relaxed);
+                        self.misses.fetch_add(1, Ordering::Relaxed);
+                        crate::observability::metrics::record_cache_lookup(false);
+                        Ok(None)
+                    }
+                };
             }
         }
 
-        if let Some(conn) = self.redis_connection.read().await.as_ref() {
+        let stale = if let Some(conn) = self.redis_connection.read().await.as_ref() {
             let mut conn = conn.clone();
             match redis::cmd("GET")
                 .arg(key)
                 .query_async::<_, Option<String>>(&mut conn)
                 .await
             {
-                Ok(Some(value)) => {
-                    self.hits.fetch_add(1, Ordering::Relaxed);
-                    crate::observability::metrics::record_cache_lookup(true);
-                    tracing::debug!("Cache hit for key: {}", key);
-                    match serde_json::from_str::<T>(&value) {
-                        Ok(data) => Ok(Some(data)),
-                        Err(e) => {
-                            tracing::warn!("Failed to deserialize cached value for {}: {}", key, e);
-                            Ok(None)
-                        }
+                Ok(Some(value)) => match decode_entry::<T>(key, &value) {
+                    DecodedEntry::Hit(data) => {
+                        self.hits.fetch_add(1, Ordering::Relaxed);
+                        crate::observability::metrics::record_cache_lookup(true);
+                        tracing::debug!("Cache hit for key: {}", key);
+                        return Ok(Some(data));
                     }
-                }
+                    DecodedEntry::Stale => true,
+                },
                 Ok(None) => {
-                    self.misses.fetch_add(1, Ordering::Relaxed);
-                    crate::observability::metrics::record_cache_lookup(false);
                     tracing::debug!("Cache miss for key: {}", key);
-                    Ok(None)
+                    false
                 }
                 Err(e) => {
                     tracing::warn!("Redis GET error for {}: {}", key, e);
-                    self.misses.fetch_add(1, Ordering::Relaxed);
-                    crate::observability::metrics::record_cache_lookup(false);
-                    Ok(None)
+                    false
                 }
             }
         } else {
-            self.misses.fetch_add(1, Ordering::Relaxed);
-            crate::observability::metrics::record_cache_lookup(false);
-            Ok(None)
+            false
+        };
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        crate::observability::metrics::record_cache_lookup(false);
+        if stale {
+            self.delete(key).await?;
         }
+        Ok(None)
     }
 
     /// Set value in cache with TTL
@@ -201,7 +284,7 @@ impl CacheManager {
         #[cfg(test)]
         {
             if self.redis_connection.read().await.is_none() {
-                match serde_json::to_string(value) {
+                match encode_entry(key, value) {
                     Ok(serialized) => {
                         self.in_memory_store
                             .write()
@@ -223,7 +306,7 @@ impl CacheManager {
 
         if let Some(conn) = self.redis_connection.read().await.as_ref() {
             let mut conn = conn.clone();
-            match serde_json::to_string(value) {
+            match encode_entry(key, value) {
                 Ok(serialized) => {
                     match redis::cmd("SETEX")
                         .arg(key)
@@ -254,6 +337,11 @@ impl CacheManager {
 
     /// Delete a cache key
     pub async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        #[cfg(test)]
+        {
+            self.in_memory_store.write().await.remove(key);
+        }
+
         if let Some(conn) = self.redis_connection.read().await.as_ref() {
             let mut conn = conn.clone();
             match redis::cmd("DEL")
@@ -279,6 +367,17 @@ impl CacheManager {
     /// Delete multiple cache keys matching a pattern
     /// Uses SCAN instead of KEYS to avoid blocking Redis
     pub async fn delete_pattern(&self, pattern: &str) -> anyhow::Result<usize> {
+        #[cfg(test)]
+        {
+            if self.redis_connection.read().await.is_none() {
+                let prefix = pattern.trim_end_matches('*');
+                let mut store = self.in_memory_store.write().await;
+                let before = store.len();
+                store.retain(|key, _| !key.starts_with(prefix));
+                return Ok(before - store.len());
+            }
+        }
+
         if let Some(conn) = self.redis_connection.read().await.as_ref() {
             let mut conn = conn.clone();
             let mut cursor: u64 = 0;
@@ -403,6 +502,32 @@ impl CacheManager {
     }
 }
 
+/// Schema version of the values stored under each key namespace (the key prefix
+/// before the first `:`). Bump the relevant constant whenever the serialized shape
+/// of a cached type changes, e.g. `CorridorResponse` or `AnchorMetricsResponse`;
+/// entries written by the previous deploy are then discarded on read instead of
+/// failing to deserialize or being served with the wrong shape.
+pub mod schema_versions {
+    pub const ANCHOR: u32 = 1;
+    pub const CORRIDOR: u32 = 1;
+    pub const DASHBOARD: u32 = 1;
+    pub const METRICS: u32 = 1;
+    /// Namespaces without a dedicated version.
+    pub const DEFAULT: u32 = 1;
+
+    /// Current schema version for the namespace `key` belongs to.
+    #[must_use]
+    pub fn for_key(key: &str) -> u32 {
+        match key.split(':').next().unwrap_or_default() {
+            "anchor" => ANCHOR,
+            "corridor" => CORRIDOR,
+            "dashboard" => DASHBOARD,
+            "metrics" => METRICS,
+            _ => DEFAULT,
+        }
+    }
+}
+
 /// Cache key builders for consistency
 pub mod keys {
     #[must_use]
@@ -445,6 +570,18 @@ pub mod keys {
         "metrics:overview".to_string()
     }
 
+    /// On-chain verification result for one `(epoch, hash)` pair
+    #[must_use]
+    pub fn snapshot_verdict(epoch: u64, hash: &str) -> String {
+        format!("snapshot:verdict:{epoch}:{hash}")
+    }
+
+    /// Pattern for invalidating the verdicts of one epoch's hashes
+    #[must_use]
+    pub fn snapshot_verdict_pattern(epoch: u64) -> String {
+        format!("snapshot:verdict:{epoch}:*")
+    }
+
     /// Pattern for invalidating all anchor-related caches
     #[must_use]
     pub fn anchor_pattern() -> String {
@@ -500,5 +637,68 @@ mod tests {
         assert_eq!(keys::corridor_pattern(), "corridor:*");
         assert_eq!(keys::dashboard_stats(), "dashboard:stats");
         assert_eq!(keys::anchor_pattern(), "anchor:*");
+        assert_eq!(keys::snapshot_verdict(12, "ab"), "snapshot:verdict:12:ab");
+        assert_eq!(keys::snapshot_verdict_pattern(12), "snapshot:verdict:12:*");
+    }
+
+    #[tokio::test]
+    async fn test_versioned_round_trip() {
+        let cache = CacheManager::new_in_memory_for_tests(CacheConfig::default());
+        cache
+            .set("corridor:detail:abc", &vec![1, 2, 3], 60)
+            .await
+            .unwrap();
+
+        let value: Option<Vec<i32>> = cache.get("corridor:detail:abc").await.unwrap();
+        assert_eq!(value, Some(vec![1, 2, 3]));
+    }
+
+    #[tokio::test]
+    async fn test_stale_schema_version_is_invalidated() {
+        let cache = CacheManager::new_in_memory_for_tests(CacheConfig::default());
+        let stale = format!(
+            r#"{{"v":{},"data":[1,2,3]}}"#,
+            schema_versions::CORRIDOR + 1
+        );
+        cache
+            .in_memory_store
+            .write()
+            .await
+            .insert("corridor:detail:abc".to_string(), stale);
+
+        let value: Option<Vec<i32>> = cache.get("corridor:detail:abc").await.unwrap();
+        assert!(value.is_none());
+        assert!(!cache
+            .in_memory_store
+            .read()
+            .await
+            .contains_key("corridor:detail:abc"));
+        assert_eq!(cache.get_stats().invalidations, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unversioned_legacy_entry_is_invalidated() {
+        let cache = CacheManager::new_in_memory_for_tests(CacheConfig::default());
+        cache
+            .in_memory_store
+            .write()
+            .await
+            .insert("anchor:detail:1".to_string(), "[1,2,3]".to_string());
+
+        let value: Option<Vec<i32>> = cache.get("anchor:detail:1").await.unwrap();
+        assert!(value.is_none());
+        assert!(cache.in_memory_store.read().await.is_empty());
+    }
+
+    #[test]
+    fn test_schema_version_namespace_lookup() {
+        assert_eq!(
+            schema_versions::for_key("anchor:list:50:0"),
+            schema_versions::ANCHOR
+        );
+        assert_eq!(
+            schema_versions::for_key("unknown"),
+            schema_versions::DEFAULT
+        );
     }
 }
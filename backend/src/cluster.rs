@@ -0,0 +1,519 @@
+//! Leader election for singleton background work.
+//!
+//! Every instance serves reads, but ingestion, snapshot submission and digests must
+//! run exactly once per deployment. Each of these roles is a lease row in the shared
+//! database: the holder renews it on an interval, and once a holder stops renewing
+//! (crash, partition, shutdown) any other instance takes the role over as soon as
+//! the lease expires. A graceful shutdown releases its leases so failover is
+//! immediate.
+//!
+//! Lease expiry is judged by each instance's own clock, so clock skew between
+//! regions must stay well below `lease_ttl - renew_interval`.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::Row;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use utoipa::ToSchema;
+
+use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
+
+pub const ROLE_INGESTION: &str = "ingestion";
+pub const ROLE_SNAPSHOT_SUBMISSION: &str = "snapshot_submission";
+pub const ROLE_DIGESTS: &str = "digests";
+
+/// Roles every instance campaigns for.
+pub const SINGLETON_ROLES: [&str; 3] = [ROLE_INGESTION, ROLE_SNAPSHOT_SUBMISSION, ROLE_DIGESTS];
+
+/// Identity of this instance and lease timing.
+#[derive(Debug, Clone)]
+pub struct ClusterConfig {
+    pub instance_id: String,
+    pub region: Option<String>,
+    /// How long a lease stays valid without renewal.
+    pub lease_ttl: Duration,
+    /// How often leases are renewed and free roles are campaigned for.
+    pub renew_interval: Duration,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        Self {
+            instance_id: default_instance_id(),
+            region: None,
+            lease_ttl: Duration::from_secs(30),
+            renew_interval: Duration::from_secs(10),
+        }
+    }
+}
+
+impl ClusterConfig {
+    /// Reads `INSTANCE_ID` (falling back to `HOSTNAME`), `DEPLOY_REGION`,
+    /// `LEADER_LEASE_TTL_SECS` and `LEADER_RENEW_INTERVAL_SECS`. The renew interval
+    /// is capped at a third of the TTL so a single missed renewal never drops a lease.
+    #[must_use]
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let instance_id = std::env::var("INSTANCE_ID")
+            .ok()
+            .filter(|id| !id.trim().is_empty())
+            .unwrap_or(default.instance_id);
+        let region = std::env::var("DEPLOY_REGION")
+            .ok()
+            .filter(|r| !r.trim().is_empty());
+        let lease_ttl = std::env::var("LEADER_LEASE_TTL_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .filter(|secs| *secs >= 3)
+            .map_or(default.lease_ttl, Duration::from_secs);
+        let renew_interval = std::env::var("LEADER_RENEW_INTERVAL_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .map_or(default.renew_interval, Duration::from_secs)
+            .min(lease_ttl / 3);
+
+        Self {
+            instance_id,
+            region,
+            lease_ttl,
+            renew_interval,
+        }
+    }
+}
+
+fn default_instance_id() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .filter(|h| !h.trim().is_empty())
+        .unwrap_or_else(|| format!("instance-{}", std::process::id()))
+}
+
+/// Current holder of a singleton role.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RoleAssignment {
+    pub role: String,
+    /// `None` when no instance has claimed the role yet.
+    pub leader: Option<String>,
+    pub leader_region: Option<String>,
+    pub acquired_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// The lease has lapsed and the role is up for grabs.
+    pub expired: bool,
+    pub held_by_self: bool,
+}
+
+/// An instance that has recently heartbeated.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ClusterMember {
+    pub instance_id: String,
+    pub region: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub last_heartbeat_at: Option<DateTime<Utc>>,
+    /// Heartbeat is within the lease TTL.
+    pub alive: bool,
+    pub roles: Vec<String>,
+}
+
+/// Role assignment across the deployment, as seen by the responding instance.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ClusterView {
+    pub instance_id: String,
+    pub region: Option<String>,
+    pub lease_ttl_seconds: u64,
+    pub roles: Vec<RoleAssignment>,
+    pub instances: Vec<ClusterMember>,
+}
+
+pub struct LeaderElection {
+    db: Arc<Database>,
+    config: ClusterConfig,
+    started_at: i64,
+    /// Roles this instance believes it leads; only ever a subset of what the
+    /// database says, since failed renewals drop the role here first.
+    held: RwLock<BTreeSet<String>>,
+}
+
+impl LeaderElection {
+    #[must_use]
+    pub fn new(db: Arc<Database>, config: ClusterConfig) -> Self {
+        Self {
+            db,
+            config,
+            started_at: Utc::now().timestamp(),
+            held: RwLock::new(BTreeSet::new()),
+        }
+    }
+
+    #[must_use]
+    pub fn instance_id(&self) -> &str {
+        &self.config.instance_id
+    }
+
+    /// Whether this instance currently leads `role`. Singleton tasks check this
+    /// before every run.
+    #[must_use]
+    pub fn is_leader(&self, role: &str) -> bool {
+        self.held.read().is_ok_and(|held| held.contains(role))
+    }
+
+    /// Acquire `role` if it is free or expired, or renew it if already held.
+    pub async fn campaign(&self, role: &str) -> Result<bool> {
+        let now = Utc::now().timestamp();
+        let expires_at = now + lease_seconds(self.config.lease_ttl);
+
+        let won = sqlx::query(
+            r"
+            INSERT INTO cluster_leases (role, holder_id, acquired_at, renewed_at, expires_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(role) DO UPDATE SET
+                acquired_at = CASE
+                    WHEN cluster_leases.holder_id = excluded.holder_id THEN cluster_leases.acquired_at
+                    ELSE excluded.acquired_at
+                END,
+                holder_id = excluded.holder_id,
+                renewed_at = excluded.renewed_at,
+                expires_at = excluded.expires_at
+            WHERE cluster_leases.holder_id = excluded.holder_id
+               OR cluster_leases.expires_at <= excluded.renewed_at
+            ",
+        )
+        .bind(role)
+        .bind(&self.config.instance_id)
+        .bind(now)
+        .bind(now)
+        .bind(expires_at)
+        .execute(self.db.pool())
+        .await
+        .with_context(|| format!("Failed to campaign for role '{role}'"))?
+        .rows_affected()
+            > 0;
+
+        self.set_held(role, won);
+        Ok(won)
+    }
+
+    /// Heartbeat, then campaign for every singleton role. A failed renewal drops the
+    /// role locally: it is safer to pause a task than to risk two leaders.
+    pub async fn tick(&self) {
+        if let Err(e) = self.heartbeat().await {
+            log_event!(
+                warn,
+                Subsystem::Cluster,
+                "cluster.heartbeat_failed",
+                error = format!("{e:#}"),
+                "Cluster heartbeat failed"
+            );
+        }
+        for role in SINGLETON_ROLES {
+            if let Err(e) = self.campaign(role).await {
+                log_event!(
+                    warn,
+                    Subsystem::Cluster,
+                    "cluster.election_failed",
+                    role,
+                    error = format!("{e:#}"),
+                    "Leader election failed"
+                );
+                self.set_held(role, false);
+            }
+        }
+    }
+
+    /// Give up all roles so another instance can take over without waiting for expiry.
+    pub async fn release_all(&self) -> Result<()> {
+        if let Ok(mut held) = self.held.write() {
+            held.clear();
+        }
+        sqlx::query("DELETE FROM cluster_leases WHERE holder_id = ?")
+            .bind(&self.config.instance_id)
+            .execute(self.db.pool())
+            .await
+            .context("Failed to release leader leases")?;
+        sqlx::query("DELETE FROM cluster_instances WHERE instance_id = ?")
+            .bind(&self.config.instance_id)
+            .execute(self.db.pool())
+            .await
+            .context("Failed to deregister instance")?;
+        log_event!(
+            info,
+            Subsystem::Cluster,
+            "cluster.leases_released",
+            instance_id = %self.config.instance_id,
+            "Released leader leases"
+        );
+        Ok(())
+    }
+
+    pub async fn start(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(self.config.renew_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        log_event!(
+            info,
+            Subsystem::Cluster,
+            "cluster.election_started",
+            instance_id = %self.config.instance_id,
+            region = self.config.region.as_deref().unwrap_or("-"),
+            lease_ttl_secs = self.config.lease_ttl.as_secs(),
+            "Leader election started"
+        );
+
+        loop {
+            interval.tick().await;
+            self.tick().await;
+        }
+    }
+
+    /// Lease holders and live instances for `/api/admin/cluster`.
+    pub async fn view(&self) -> Result<ClusterView> {
+        let now = Utc::now().timestamp();
+        let ttl = lease_seconds(self.config.lease_ttl);
+
+        let instance_rows = sqlx::query(
+            "SELECT instance_id, region, started_at, last_heartbeat_at FROM cluster_instances ORDER BY instance_id",
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+        let lease_rows = sqlx::query(
+            "SELECT role, holder_id, acquired_at, expires_at FROM cluster_leases ORDER BY role",
+        )
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let regions: BTreeMap<String, Option<String>> = instance_rows
+            .iter()
+            .map(|row| (row.get("instance_id"), row.get("region")))
+            .collect();
+
+        let mut leases: BTreeMap<String, RoleAssignment> = lease_rows
+            .iter()
+            .map(|row| {
+                let role: String = row.get("role");
+                let holder: String = row.get("holder_id");
+                let expires_at: i64 = row.get("expires_at");
+                let assignment = RoleAssignment {
+                    role: role.clone(),
+                    leader_region: regions.get(&holder).cloned().flatten(),
+                    held_by_self: holder == self.config.instance_id && expires_at > now,
+                    leader: Some(holder),
+                    acquired_at: from_unix(row.get("acquired_at")),
+                    expires_at: from_unix(expires_at),
+                    expired: expires_at <= now,
+                };
+                (role, assignment)
+            })
+            .collect();
+        for role in SINGLETON_ROLES {
+            leases
+                .entry(role.to_string())
+                .or_insert_with(|| RoleAssignment {
+                    role: role.to_string(),
+                    leader: None,
+                    leader_region: None,
+                    acquired_at: None,
+                    expires_at: None,
+                    expired: true,
+                    held_by_self: false,
+                });
+        }
+
+        let instances = instance_rows
+            .iter()
+            .map(|row| {
+                let instance_id: String = row.get("instance_id");
+                let last_heartbeat: i64 = row.get("last_heartbeat_at");
+                let roles = leases
+                    .values()
+                    .filter(|lease| {
+                        !lease.expired && lease.leader.as_deref() == Some(instance_id.as_str())
+                    })
+                    .map(|lease| lease.role.clone())
+                    .collect();
+                ClusterMember {
+                    region: row.get("region"),
+                    started_at: from_unix(row.get("started_at")),
+                    last_heartbeat_at: from_unix(last_heartbeat),
+                    alive: now - last_heartbeat <= ttl,
+                    roles,
+                    instance_id,
+                }
+            })
+            .collect();
+
+        Ok(ClusterView {
+            instance_id: self.config.instance_id.clone(),
+            region: self.config.region.clone(),
+            lease_ttl_seconds: self.config.lease_ttl.as_secs(),
+            roles: leases.into_values().collect(),
+            instances,
+        })
+    }
+
+    async fn heartbeat(&self) -> Result<()> {
+        sqlx::query(
+            r"
+            INSERT INTO cluster_instances (instance_id, region, started_at, last_heartbeat_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(instance_id) DO UPDATE SET
+                region = excluded.region,
+                started_at = excluded.started_at,
+                last_heartbeat_at = excluded.last_heartbeat_at
+            ",
+        )
+        .bind(&self.config.instance_id)
+        .bind(&self.config.region)
+        .bind(self.started_at)
+        .bind(Utc::now().timestamp())
+        .execute(self.db.pool())
+        .await
+        .context("Failed to record cluster heartbeat")?;
+        Ok(())
+    }
+
+    fn set_held(&self, role: &str, leading: bool) {
+        let Ok(mut held) = self.held.write() else {
+            return;
+        };
+        let changed = if leading {
+            held.insert(role.to_string())
+        } else {
+            held.remove(role)
+        };
+        if changed && leading {
+            log_event!(
+                info,
+                Subsystem::Cluster,
+                "cluster.leadership_acquired",
+                role,
+                instance_id = %self.config.instance_id,
+                "Acquired leadership"
+            );
+        } else if changed {
+            log_event!(
+                warn,
+                Subsystem::Cluster,
+                "cluster.leadership_lost",
+                role,
+                instance_id = %self.config.instance_id,
+                "Lost leadership"
+            );
+        }
+    }
+}
+
+fn lease_seconds(ttl: Duration) -> i64 {
+    i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX)
+}
+
+const fn from_unix(secs: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(secs, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations};
+
+    async fn setup_db() -> Arc<Database> {
+        test_support::sqlite_db(&[migrations::CLUSTER_LEASES]).await
+    }
+
+    fn election(db: &Arc<Database>, instance_id: &str, region: &str) -> LeaderElection {
+        LeaderElection::new(
+            Arc::clone(db),
+            ClusterConfig {
+                instance_id: instance_id.to_string(),
+                region: Some(region.to_string()),
+                ..ClusterConfig::default()
+            },
+        )
+    }
+
+    async fn expire_leases(db: &Database) {
+        sqlx::query("UPDATE cluster_leases SET expires_at = expires_at - 3600")
+            .execute(db.pool())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_single_leader_per_role() {
+        let db = setup_db().await;
+        let eu = election(&db, "eu-1", "eu-west");
+        let us = election(&db, "us-1", "us-east");
+
+        assert!(eu.campaign(ROLE_INGESTION).await.unwrap());
+        assert!(!us.campaign(ROLE_INGESTION).await.unwrap());
+        assert!(us.campaign(ROLE_DIGESTS).await.unwrap());
+
+        // Renewal by the holder keeps the lease
+        assert!(eu.campaign(ROLE_INGESTION).await.unwrap());
+        assert!(eu.is_leader(ROLE_INGESTION));
+        assert!(!us.is_leader(ROLE_INGESTION));
+        assert!(!eu.is_leader(ROLE_DIGESTS));
+    }
+
+    #[tokio::test]
+    async fn test_failover_after_expiry_and_release() {
+        let db = setup_db().await;
+        let eu = election(&db, "eu-1", "eu-west");
+        let us = election(&db, "us-1", "us-east");
+
+        eu.tick().await;
+        us.tick().await;
+        assert!(SINGLETON_ROLES.iter().all(|role| eu.is_leader(role)));
+
+        // eu-1 stops renewing; us-1 takes over and eu-1 steps down on its next try
+        expire_leases(&db).await;
+        us.tick().await;
+        assert!(SINGLETON_ROLES.iter().all(|role| us.is_leader(role)));
+        eu.tick().await;
+        assert!(!eu.is_leader(ROLE_INGESTION));
+
+        // A graceful shutdown hands roles over without waiting for the TTL
+        us.release_all().await.unwrap();
+        assert!(!us.is_leader(ROLE_INGESTION));
+        assert!(eu.campaign(ROLE_INGESTION).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_cluster_view() {
+        let db = setup_db().await;
+        let eu = election(&db, "eu-1", "eu-west");
+        let us = election(&db, "us-1", "us-east");
+        eu.tick().await;
+        us.tick().await;
+
+        let view = us.view().await.unwrap();
+        assert_eq!(view.instance_id, "us-1");
+        assert_eq!(view.roles.len(), SINGLETON_ROLES.len());
+        let ingestion = view
+            .roles
+            .iter()
+            .find(|r| r.role == ROLE_INGESTION)
+            .unwrap();
+        assert_eq!(ingestion.leader.as_deref(), Some("eu-1"));
+        assert_eq!(ingestion.leader_region.as_deref(), Some("eu-west"));
+        assert!(!ingestion.held_by_self);
+        assert!(!ingestion.expired);
+
+        assert_eq!(view.instances.len(), 2);
+        assert!(view.instances.iter().all(|i| i.alive));
+        let eu_member = view
+            .instances
+            .iter()
+            .find(|i| i.instance_id == "eu-1")
+            .unwrap();
+        assert_eq!(eu_member.roles.len(), SINGLETON_ROLES.len());
+
+        expire_leases(&db).await;
+        let view = eu.view().await.unwrap();
+        assert!(view.roles.iter().all(|r| r.expired && !r.held_by_self));
+        assert!(view.instances.iter().all(|i| i.roles.is_empty()));
+    }
+}
@@ -1265,6 +1265,20 @@ impl Database {
         .await
     }
 
+    pub async fn delete_hourly_metrics_by_timerange(
+        &self,
+        start_time: chrono::DateTime<chrono::Utc>,
+        end_time: chrono::DateTime<chrono::Utc>,
+    ) -> Result<u64> {
+        self.execute_with_timing("delete_hourly_metrics_by_timerange", async {
+            self.aggregation_db()
+                .delete_hourly_metrics_by_timerange(start_time, end_time)
+                .await
+                .context("Failed to delete hourly metrics by timerange")
+        })
+        .await
+    }
+
     pub async fn create_aggregation_job(&self, job_id: &str, job_type: &str) -> Result<()> {
         self.execute_with_timing("create_aggregation_job", async {
             self.aggregation_db()
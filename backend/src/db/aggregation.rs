@@ -238,6 +238,29 @@ impl AggregationDb {
         Ok(metrics)
     }
 
+    /// Delete hourly metrics with a bucket in `[start_time, end_time)`, returning how
+    /// many rows were removed. Upserts are additive, so a bucket has to be cleared
+    /// before it is rebuilt from payments.
+    pub async fn delete_hourly_metrics_by_timerange(
+        &self,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    ) -> Result<u64> {
+        let result = sqlx::query(
+            r"
+            DELETE FROM corridor_metrics_hourly
+            WHERE hour_bucket >= ? AND hour_bucket < ?
+            ",
+        )
+        .bind(start_time.to_rfc3339())
+        .bind(end_time.to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .context("Failed to delete hourly metrics by timerange")?;
+
+        Ok(result.rows_affected())
+    }
+
     /// Create aggregation job record
     pub async fn create_aggregation_job(&self, job_id: &str, job_type: &str) -> Result<()> {
         let now = Utc::now().to_rfc3339();
@@ -6,6 +6,7 @@ use tokio::time::{interval, Duration};
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use crate::cache::CacheManager;
+use crate::cluster::{LeaderElection, ROLE_DIGESTS};
 use crate::email::report::{generate_html_report, AnchorSummary, CorridorSummary, DigestReport};
 use crate::email::service::EmailService;
 use crate::rpc::StellarRpcClient;
@@ -17,6 +18,7 @@ pub struct DigestScheduler {
     cache: Arc<CacheManager>,
     rpc_client: Arc<StellarRpcClient>,
     recipients: Vec<String>,
+    leader: Option<Arc<LeaderElection>>,
 }
 
 #[cfg(test)]
@@ -35,14 +37,28 @@ impl DigestScheduler {
             cache,
             rpc_client,
             recipients,
+            leader: None,
         }
     }
 
+    /// Only send scheduled digests while this instance leads the digests role, so a
+    /// multi-instance deployment does not email everyone once per instance.
+    #[must_use]
+    pub fn with_leader_election(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
     pub async fn start(self: Arc<Self>) {
         let mut ticker = interval(Duration::from_secs(3600)); // Check hourly
 
         loop {
             ticker.tick().await;
+            if let Some(leader) = &self.leader {
+                if !leader.is_leader(ROLE_DIGESTS) {
+                    continue;
+                }
+            }
             let now = Utc::now();
 
             // Weekly: Monday at 9 AM
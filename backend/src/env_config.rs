@@ -87,6 +87,9 @@ pub fn log_env_config() {
     // CORS
     log_var("CORS_ALLOWED_ORIGINS");
 
+    // Deployment mode
+    log_var("READ_ONLY_MODE");
+
     // Slack Bot
     if let Ok(slack_url) = env::var("SLACK_WEBHOOK_URL") {
         let sanitized = sanitize_url(&slack_url);
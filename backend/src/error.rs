@@ -1,11 +1,23 @@
 use axum::{
+    body::Body,
     extract::Request,
-    http::StatusCode,
+    http::{header, response::Parts, HeaderValue, StatusCode},
+    middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use utoipa::ToSchema;
+
+/// Media type for RFC 7807 problem details.
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Prefix of the `type` URI; the error code is appended in kebab case.
+const PROBLEM_TYPE_PREFIX: &str = "urn:stellar-insights:problem:";
+
+/// Plain-text and JSON error bodies larger than this are not folded into problem details.
+const MAX_ERROR_BODY: usize = 16 * 1024;
 
 /// Domain-specific errors for business logic and validation rules.
 #[derive(Debug, thiserror::Error, Clone)]
@@ -50,6 +62,98 @@ pub struct ErrorDetail {
     pub stack_trace: Option<String>,
 }
 
+/// RFC 7807 problem details, the body of every API error.
+///
+/// Beyond the standard members, `code` is a stable machine-readable identifier,
+/// `request_id` correlates the response with server logs and `retryable` tells
+/// clients whether the same request may succeed later.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProblemDetails {
+    /// URI identifying the problem type, derived from `code`
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// Short summary of the problem type (the HTTP reason phrase)
+    pub title: String,
+    pub status: u16,
+    /// Explanation specific to this occurrence
+    pub detail: String,
+    /// Request path that produced the problem
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    pub code: String,
+    pub retryable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_seconds: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<HashMap<String, serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack_trace: Option<String>,
+}
+
+impl ProblemDetails {
+    /// Problem for a status with no more specific information, e.g. a framework rejection.
+    #[must_use]
+    pub fn from_status(status: StatusCode, detail: impl Into<String>) -> Self {
+        let code = status
+            .canonical_reason()
+            .unwrap_or("Error")
+            .to_uppercase()
+            .replace([' ', '-'], "_");
+        Self::new(status, code, detail.into())
+    }
+
+    /// Problem with an application error code, for errors raised outside `ApiError`.
+    #[must_use]
+    pub fn new(status: StatusCode, code: String, detail: String) -> Self {
+        Self {
+            problem_type: format!(
+                "{PROBLEM_TYPE_PREFIX}{}",
+                code.to_lowercase().replace('_', "-")
+            ),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail,
+            instance: None,
+            code,
+            retryable: matches!(
+                status,
+                StatusCode::REQUEST_TIMEOUT
+                    | StatusCode::TOO_MANY_REQUESTS
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ),
+            retry_after_seconds: None,
+            request_id: None,
+            details: None,
+            stack_trace: None,
+        }
+    }
+
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+impl IntoResponse for ProblemDetails {
+    fn into_response(self) -> Response {
+        let status = self.status_code();
+        let retry_after = self.retry_after_seconds;
+        let mut response = (status, Json(&self)).into_response();
+        let headers = response.headers_mut();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON));
+        if let Some(seconds) = retry_after {
+            headers.insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        // Kept so `problem_details_middleware` can add the correlation ID without reparsing
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
 /// Main API error type with structured error codes
 #[derive(Debug)]
 pub enum ApiError {
@@ -78,6 +182,12 @@ pub enum ApiError {
         code: String,
         message: String,
         details: Option<HashMap<String, serde_json::Value>>,
+        retry_after_seconds: Option<u64>,
+    },
+    UnprocessableEntity {
+        code: String,
+        message: String,
+        details: Option<HashMap<String, serde_json::Value>>,
     },
 }
 
@@ -151,6 +261,29 @@ impl ApiError {
             code: code.into(),
             message: message.into(),
             details: None,
+            retry_after_seconds: None,
+        }
+    }
+
+    /// Suggest how long clients should wait before retrying a `ServiceUnavailable` error
+    #[must_use]
+    pub const fn with_retry_after(mut self, seconds: u64) -> Self {
+        if let Self::ServiceUnavailable {
+            retry_after_seconds,
+            ..
+        } = &mut self
+        {
+            *retry_after_seconds = Some(seconds);
+        }
+        self
+    }
+
+    /// Create an `UnprocessableEntity` error for well-formed requests that cannot be served
+    pub fn unprocessable_entity(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::UnprocessableEntity {
+            code: code.into(),
+            message: message.into(),
+            details: None,
         }
     }
 
@@ -162,7 +295,8 @@ impl ApiError {
             | Self::BadRequest { details: d, .. }
             | Self::InternalError { details: d, .. }
             | Self::Unauthorized { details: d, .. }
-            | Self::ServiceUnavailable { details: d, .. } => {
+            | Self::ServiceUnavailable { details: d, .. }
+            | Self::UnprocessableEntity { details: d, .. } => {
                 *d = Some(details);
             }
         }
@@ -177,6 +311,7 @@ impl ApiError {
             Self::InternalError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
             Self::Unauthorized { .. } => StatusCode::UNAUTHORIZED,
             Self::ServiceUnavailable { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            Self::UnprocessableEntity { .. } => StatusCode::UNPROCESSABLE_ENTITY,
         }
     }
 
@@ -216,6 +351,12 @@ impl ApiError {
                 code,
                 message,
                 details,
+                ..
+            }
+            | Self::UnprocessableEntity {
+                code,
+                message,
+                details,
             } => (code.clone(), message.clone(), details.clone(), None),
         };
 
@@ -229,25 +370,181 @@ impl ApiError {
             },
         }
     }
+
+    /// Whether the same request may succeed if retried later
+    #[must_use]
+    pub const fn is_retryable(&self) -> bool {
+        matches!(self, Self::ServiceUnavailable { .. })
+    }
+
+    /// Convert to RFC 7807 problem details with optional request ID
+    #[must_use]
+    pub fn to_problem(&self, request_id: Option<String>) -> ProblemDetails {
+        let retry_after_seconds = match self {
+            Self::ServiceUnavailable {
+                retry_after_seconds,
+                ..
+            } => *retry_after_seconds,
+            _ => None,
+        };
+        let error = self.to_error_response(request_id).error;
+
+        let mut problem = ProblemDetails::new(self.status_code(), error.code, error.message);
+        problem.retryable = self.is_retryable();
+        problem.retry_after_seconds = retry_after_seconds;
+        problem.request_id = error.request_id;
+        problem.details = error.details;
+        problem.stack_trace = error.stack_trace;
+        problem
+    }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let status = self.status_code();
-        let error_response = self.to_error_response(None);
-        (status, Json(error_response)).into_response()
+        self.to_problem(None).into_response()
     }
 }
 
 /// Extract request ID from request extensions and create error response
 pub fn error_response_with_request_id(error: ApiError, req: &Request) -> Response {
-    let status = error.status_code();
     let request_id = req
         .extensions()
         .get::<crate::request_id::RequestId>()
         .map(|id| id.0.clone());
-    let error_response = error.to_error_response(request_id);
-    (status, Json(error_response)).into_response()
+    let mut problem = error.to_problem(request_id);
+    problem.instance = Some(req.uri().path().to_string());
+    problem.into_response()
+}
+
+/// Give every error response the same problem+json envelope.
+///
+/// Problems raised by handlers get the request's correlation ID and path filled in.
+/// Plain-text or empty error responses, such as extractor rejections, unmatched
+/// routes and timeouts, are rewritten as problems keyed by their status. So are
+/// JSON error bodies from handlers that build their own, such as `{"error": "..."}`
+/// or the `ErrorResponse` envelope. Error bodies in other formats are left
+/// untouched. The correlation ID is read from the request extensions or, when
+/// layered outside `request_id_middleware`, from the `X-Request-ID` response header.
+///
+/// Only the body and its content headers are replaced; headers set by inner layers
+/// (CORS, `Allow`, rate limits, `WWW-Authenticate`, `X-Request-ID`) are kept.
+pub async fn problem_details_middleware(req: Request, next: Next) -> Response {
+    let request_id = req
+        .extensions()
+        .get::<crate::request_id::RequestId>()
+        .map(|id| id.0.clone());
+    let path = req.uri().path().to_string();
+
+    let mut response = next.run(req).await;
+    let status = response.status();
+    if !(status.is_client_error() || status.is_server_error()) {
+        return response;
+    }
+    let raised = response.extensions_mut().remove::<ProblemDetails>();
+    let (parts, body) = response.into_parts();
+    let request_id = request_id.or_else(|| {
+        parts
+            .headers
+            .get("X-Request-ID")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    });
+
+    let mut problem = if let Some(problem) = raised {
+        problem
+    } else {
+        let content_type = parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let is_json = content_type
+            .as_deref()
+            .is_some_and(|value| value.starts_with("application/json"));
+        let is_plain = content_type
+            .as_deref()
+            .is_none_or(|value| value.starts_with("text/plain"));
+        if !(is_json || is_plain) {
+            return Response::from_parts(parts, body);
+        }
+        let Ok(bytes) = axum::body::to_bytes(body, MAX_ERROR_BODY).await else {
+            let mut parts = parts;
+            // The original length no longer describes the (dropped) body
+            parts.headers.remove(header::CONTENT_LENGTH);
+            return Response::from_parts(parts, Body::empty());
+        };
+        let json_problem = if is_json {
+            match serde_json::from_slice(&bytes) {
+                Ok(body) => Some(json_error_problem(status, &body)),
+                Err(_) => return Response::from_parts(parts, Body::from(bytes)),
+            }
+        } else {
+            None
+        };
+        let mut problem = json_problem.unwrap_or_else(|| {
+            let text = String::from_utf8_lossy(&bytes).trim().to_string();
+            let detail = if text.is_empty() {
+                status.canonical_reason().unwrap_or("Error").to_string()
+            } else {
+                text
+            };
+            ProblemDetails::from_status(status, detail)
+        });
+        if let Some(seconds) = parts
+            .headers
+            .get(header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok())
+        {
+            problem.retry_after_seconds = Some(seconds);
+        }
+        problem
+    };
+
+    problem.request_id = problem.request_id.or(request_id);
+    problem.instance = problem.instance.or(Some(path));
+    with_problem_body(parts, problem)
+}
+
+/// Puts `problem` in place of the original body, keeping the original status and headers.
+fn with_problem_body(mut parts: Parts, problem: ProblemDetails) -> Response {
+    let (problem_parts, body) = problem.into_response().into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    for name in [header::CONTENT_TYPE, header::RETRY_AFTER] {
+        if let Some(value) = problem_parts.headers.get(&name) {
+            parts.headers.insert(name, value.clone());
+        }
+    }
+    parts.extensions.extend(problem_parts.extensions);
+    Response::from_parts(parts, body)
+}
+
+/// Problem for a JSON error body a handler built itself. Understands the
+/// `ErrorResponse` envelope and the flat `{"error": "...", "message": "..."}`
+/// shapes; anything else is reported by status alone.
+fn json_error_problem(status: StatusCode, body: &serde_json::Value) -> ProblemDetails {
+    let field = |value: &serde_json::Value, name: &str| {
+        value
+            .get(name)
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string)
+    };
+    let error = body.get("error").filter(|error| error.is_object());
+    let envelope = error.unwrap_or(body);
+
+    let detail = field(envelope, "message")
+        .or_else(|| field(body, "error"))
+        .or_else(|| field(body, "detail"))
+        .unwrap_or_else(|| status.canonical_reason().unwrap_or("Error").to_string());
+    let mut problem = match field(envelope, "code") {
+        Some(code) => ProblemDetails::new(status, code, detail),
+        None => ProblemDetails::from_status(status, detail),
+    };
+    problem.details = envelope
+        .get("details")
+        .and_then(|details| serde_json::from_value(details.clone()).ok());
+    problem.request_id = field(envelope, "request_id");
+    problem
 }
 
 /// Convert from `anyhow::Error`
@@ -294,6 +591,7 @@ impl From<sqlx::Error> for ApiError {
                 code,
                 message,
                 details: None,
+                retry_after_seconds: None,
             };
         }
 
@@ -338,13 +636,67 @@ impl From<DomainError> for ApiError {
 }
 
 /// Convert RPC errors into API errors so handlers can use `?` consistently.
+/// Failures the RPC client would itself retry are surfaced as retryable 503s.
 impl From<crate::rpc::error::RpcError> for ApiError {
     fn from(err: crate::rpc::error::RpcError) -> Self {
-        Self::InternalError {
-            code: "RPC_ERROR".to_string(),
-            message: "External service error".to_string(),
-            details: None,
-            source: Some(err.to_string()),
+        use crate::rpc::error::RpcError;
+
+        match &err {
+            RpcError::RateLimitError { retry_after } => Self::ServiceUnavailable {
+                code: "RPC_RATE_LIMITED".to_string(),
+                message: "External service is rate limiting requests".to_string(),
+                details: None,
+                retry_after_seconds: retry_after.map(|delay| delay.as_secs()),
+            },
+            RpcError::CircuitBreakerOpen => Self::ServiceUnavailable {
+                code: "RPC_UNAVAILABLE".to_string(),
+                message: "External service is temporarily unavailable".to_string(),
+                details: None,
+                retry_after_seconds: None,
+            },
+            _ if err.is_retryable() => Self::ServiceUnavailable {
+                code: "RPC_UNAVAILABLE".to_string(),
+                message: "External service is temporarily unavailable".to_string(),
+                details: None,
+                retry_after_seconds: None,
+            },
+            _ => Self::InternalError {
+                code: "RPC_ERROR".to_string(),
+                message: "External service error".to_string(),
+                details: None,
+                source: Some(err.to_string()),
+            },
+        }
+    }
+}
+
+/// Over-budget analytics queries become 422s carrying the estimate and alternatives.
+impl From<crate::query_cost::QueryCostExceeded> for ApiError {
+    fn from(err: crate::query_cost::QueryCostExceeded) -> Self {
+        let details = HashMap::from([
+            ("estimated_cells".to_string(), err.estimated_cells.into()),
+            ("max_cells".to_string(), err.budget.max_cells.into()),
+            ("range_seconds".to_string(), err.cost.range_seconds.into()),
+            (
+                "max_range_seconds".to_string(),
+                err.max_range_at_granularity.into(),
+            ),
+            (
+                "granularity_seconds".to_string(),
+                err.cost.granularity_seconds.into(),
+            ),
+            ("entities".to_string(), err.cost.entities.into()),
+            (
+                "suggested_granularity_seconds".to_string(),
+                err.suggested_granularity_seconds.into(),
+            ),
+            ("suggestions".to_string(), err.suggestions().into()),
+        ]);
+
+        Self::UnprocessableEntity {
+            code: "QUERY_TOO_EXPENSIVE".to_string(),
+            message: err.to_string(),
+            details: Some(details),
         }
     }
 }
@@ -429,6 +781,161 @@ mod tests {
         assert!(response.error.details.is_some());
     }
 
+    #[test]
+    fn test_query_cost_exceeded_is_unprocessable() {
+        use crate::query_cost::{QueryBudget, QueryCost};
+
+        let err = QueryBudget::new(100, 86_400)
+            .check(QueryCost::new(86_400, 60, 1).with_granularity_param("step_seconds"))
+            .unwrap_err();
+        let error = ApiError::from(err);
+        assert_eq!(error.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let response = error.to_error_response(None);
+        assert_eq!(response.error.code, "QUERY_TOO_EXPENSIVE");
+        let details = response.error.details.unwrap();
+        assert_eq!(details["estimated_cells"], 1_440);
+        assert_eq!(details["suggested_granularity_seconds"], 900);
+        assert!(details["suggestions"].is_array());
+    }
+
+    #[test]
+    fn test_problem_details_envelope() {
+        let problem = ApiError::not_found("CORRIDOR_NOT_FOUND", "Corridor not found")
+            .to_problem(Some("req-1".to_string()));
+        let json = serde_json::to_value(&problem).unwrap();
+
+        assert_eq!(
+            json["type"],
+            "urn:stellar-insights:problem:corridor-not-found"
+        );
+        assert_eq!(json["title"], "Not Found");
+        assert_eq!(json["status"], 404);
+        assert_eq!(json["detail"], "Corridor not found");
+        assert_eq!(json["code"], "CORRIDOR_NOT_FOUND");
+        assert_eq!(json["retryable"], false);
+        assert_eq!(json["request_id"], "req-1");
+        assert!(json.get("retry_after_seconds").is_none());
+    }
+
+    #[test]
+    fn test_service_unavailable_is_retryable() {
+        let error =
+            ApiError::service_unavailable("DB_POOL_EXHAUSTED", "Try again").with_retry_after(5);
+        assert!(error.is_retryable());
+
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], PROBLEM_JSON);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "5");
+        let problem = response.extensions().get::<ProblemDetails>().unwrap();
+        assert!(problem.retryable);
+        assert_eq!(problem.retry_after_seconds, Some(5));
+    }
+
+    #[test]
+    fn test_rpc_errors_carry_retryability() {
+        use crate::rpc::error::RpcError;
+        use std::time::Duration;
+
+        let limited = ApiError::from(RpcError::RateLimitError {
+            retry_after: Some(Duration::from_secs(30)),
+        });
+        let problem = limited.to_problem(None);
+        assert_eq!(problem.code, "RPC_RATE_LIMITED");
+        assert!(problem.retryable);
+        assert_eq!(problem.retry_after_seconds, Some(30));
+
+        let parse = ApiError::from(RpcError::ParseError("bad xdr".to_string()));
+        assert_eq!(parse.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!parse.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_problem_rewrite_keeps_inner_layer_headers() {
+        use crate::read_only::{read_only_middleware, ReadOnlyMode};
+        use axum::{middleware, routing::get, Router};
+        use tower::ServiceExt;
+        use tower_http::cors::{AllowOrigin, CorsLayer};
+
+        let origin = HeaderValue::from_static("https://app.example.com");
+        let app = Router::new()
+            .route("/anchors", get(|| async { "list" }))
+            .layer(middleware::from_fn_with_state(
+                ReadOnlyMode { enabled: true },
+                read_only_middleware,
+            ))
+            .layer(CorsLayer::new().allow_origin(AllowOrigin::exact(origin.clone())))
+            .layer(middleware::from_fn(
+                crate::request_id::request_id_middleware,
+            ))
+            .layer(middleware::from_fn(problem_details_middleware));
+        let request = |method: &str, uri: &str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(header::ORIGIN, origin.clone())
+                .header("X-Request-ID", "req-7")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let refused = app
+            .clone()
+            .oneshot(request("POST", "/anchors"))
+            .await
+            .unwrap();
+        assert_eq!(refused.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(refused.headers()[header::ALLOW], "GET, HEAD, OPTIONS");
+
+        let missing = app.oneshot(request("GET", "/missing")).await.unwrap();
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+        for response in [refused, missing] {
+            let headers = response.headers();
+            assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], origin);
+            assert_eq!(headers["X-Request-ID"], "req-7");
+            assert_eq!(headers[header::CONTENT_TYPE], PROBLEM_JSON);
+            let body = axum::body::to_bytes(response.into_body(), MAX_ERROR_BODY)
+                .await
+                .unwrap();
+            let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+            assert_eq!(problem.request_id.as_deref(), Some("req-7"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oversized_error_body_drops_content_length() {
+        use axum::{middleware, routing::get, Router};
+        use tower::ServiceExt;
+
+        let oversized = "x".repeat(MAX_ERROR_BODY + 1);
+        let length = oversized.len().to_string();
+        let app = Router::new()
+            .route(
+                "/dump",
+                get(move || async move {
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        [(header::CONTENT_LENGTH, length)],
+                        oversized,
+                    )
+                }),
+            )
+            .layer(middleware::from_fn(problem_details_middleware));
+
+        let response = app
+            .oneshot(Request::builder().uri("/dump").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response.headers().get(header::CONTENT_LENGTH).is_none());
+        let body = axum::body::to_bytes(response.into_body(), MAX_ERROR_BODY)
+            .await
+            .unwrap();
+        assert!(body.is_empty());
+    }
+
     #[test]
     fn test_from_anyhow_error() {
         let anyhow_err = anyhow::anyhow!("Test error");
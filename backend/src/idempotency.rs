@@ -0,0 +1,708 @@
+//! `Idempotency-Key` support for mutating endpoints.
+//!
+//! A POST carrying an `Idempotency-Key` header is executed once per caller and key.
+//! The response is stored for 24 hours and replayed verbatim for retries with the same
+//! body, so a client that lost the first response can safely resend. Reusing a key with
+//! a different request, or retrying while the first request is still running, is
+//! rejected. Only successes and client errors the same request would hit again are
+//! stored; server errors and transient refusals such as rate limiting leave the key
+//! free for another attempt. Anonymous callers are told apart by client address.
+//!
+//! A running request holds its key on a short lease. The claim is released as soon
+//! as the request is abandoned, and the lease frees the key if the process dies first.
+
+use anyhow::{Context, Result};
+use axum::{
+    body::{Body, Bytes},
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use crate::database::Database;
+use crate::error::ProblemDetails;
+use crate::log_event;
+use crate::logging::Subsystem;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+/// Set on responses served from the idempotency store.
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "Idempotent-Replayed";
+
+const DEFAULT_TTL_HOURS: i64 = 24;
+/// How long an unfinished request holds its key; longer than any request may run.
+const DEFAULT_LEASE_MINUTES: i64 = 5;
+const MAX_KEY_LENGTH: usize = 255;
+const MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// A completed response kept for replay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// Outcome of claiming a key for a new request.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Claim {
+    /// First use: run the handler and call `complete` or `release`.
+    Acquired,
+    Replay(StoredResponse),
+    InProgress,
+    /// The key was already used for a different request.
+    Mismatch,
+}
+
+pub struct IdempotencyStore {
+    db: Arc<Database>,
+    ttl: Duration,
+    lease: Duration,
+}
+
+impl IdempotencyStore {
+    #[must_use]
+    pub const fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            ttl: Duration::hours(DEFAULT_TTL_HOURS),
+            lease: Duration::minutes(DEFAULT_LEASE_MINUTES),
+        }
+    }
+
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    pub async fn claim(
+        &self,
+        scope: &str,
+        key: &str,
+        request_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Claim> {
+        // An expired record or lapsed lease no longer reserves the key.
+        sqlx::query(
+            "DELETE FROM idempotency_keys WHERE scope = ? AND idempotency_key = ? AND expires_at <= ?",
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(now.to_rfc3339())
+        .execute(self.db.pool())
+        .await
+        .context("Failed to expire idempotency key")?;
+
+        let inserted = sqlx::query(
+            r"
+            INSERT OR IGNORE INTO idempotency_keys
+                (scope, idempotency_key, request_hash, created_at, expires_at)
+            VALUES (?, ?, ?, ?, ?)
+            ",
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(request_hash)
+        .bind(now.to_rfc3339())
+        .bind((now + self.lease).to_rfc3339())
+        .execute(self.db.pool())
+        .await
+        .context("Failed to claim idempotency key")?;
+        if inserted.rows_affected() == 1 {
+            return Ok(Claim::Acquired);
+        }
+
+        let row = sqlx::query(
+            r"
+            SELECT request_hash, status_code, content_type, response_body
+            FROM idempotency_keys
+            WHERE scope = ? AND idempotency_key = ?
+            ",
+        )
+        .bind(scope)
+        .bind(key)
+        .fetch_optional(self.db.pool())
+        .await
+        .context("Failed to load idempotency key")?;
+
+        // Released between our insert and select; the client may simply retry.
+        let Some(row) = row else {
+            return Ok(Claim::InProgress);
+        };
+        if row.get::<String, _>("request_hash") != request_hash {
+            return Ok(Claim::Mismatch);
+        }
+        let status: Option<i64> = row.get("status_code");
+        Ok(status.map_or(Claim::InProgress, |status| {
+            Claim::Replay(StoredResponse {
+                status: u16::try_from(status).unwrap_or(500),
+                content_type: row.get("content_type"),
+                body: row
+                    .get::<Option<Vec<u8>>, _>("response_body")
+                    .unwrap_or_default(),
+            })
+        }))
+    }
+
+    /// Store the response and keep it for the full TTL from `now`.
+    pub async fn complete(
+        &self,
+        scope: &str,
+        key: &str,
+        response: &StoredResponse,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r"
+            UPDATE idempotency_keys
+            SET status_code = ?, content_type = ?, response_body = ?, expires_at = ?
+            WHERE scope = ? AND idempotency_key = ?
+            ",
+        )
+        .bind(i64::from(response.status))
+        .bind(&response.content_type)
+        .bind(&response.body)
+        .bind((now + self.ttl).to_rfc3339())
+        .bind(scope)
+        .bind(key)
+        .execute(self.db.pool())
+        .await
+        .context("Failed to store idempotent response")?;
+        Ok(())
+    }
+
+    /// Drop an in-flight claim so the key can be retried.
+    pub async fn release(&self, scope: &str, key: &str) -> Result<()> {
+        sqlx::query("DELETE FROM idempotency_keys WHERE scope = ? AND idempotency_key = ?")
+            .bind(scope)
+            .bind(key)
+            .execute(self.db.pool())
+            .await
+            .context("Failed to release idempotency key")?;
+        Ok(())
+    }
+
+    pub async fn purge_expired(&self) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at <= ?")
+            .bind(Utc::now().to_rfc3339())
+            .execute(self.db.pool())
+            .await
+            .context("Failed to purge expired idempotency keys")?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// Middleware applying [`IdempotencyStore`] to POST requests that carry the header.
+pub async fn idempotency_middleware(
+    State(store): State<Arc<IdempotencyStore>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if req.method() != Method::POST {
+        return next.run(req).await;
+    }
+    let Some(key) = req.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return next.run(req).await;
+    };
+    let key = match key.to_str() {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LENGTH => key.to_string(),
+        _ => return IdempotencyError::InvalidKey.into_response(),
+    };
+
+    let Some(scope) = caller_scope(&req) else {
+        return IdempotencyError::UnknownCaller.into_response();
+    };
+    let (parts, body) = req.into_parts();
+    let Ok(body) = axum::body::to_bytes(body, MAX_BODY_SIZE).await else {
+        return IdempotencyError::BodyTooLarge.into_response();
+    };
+    let path = parts
+        .uri
+        .path_and_query()
+        .map_or_else(|| parts.uri.path(), |p| p.as_str());
+    let request_hash = request_hash(&parts.method, path, &body);
+
+    match store.claim(&scope, &key, &request_hash, Utc::now()).await {
+        Ok(Claim::Acquired) => {}
+        Ok(Claim::Replay(stored)) => return replay(stored),
+        Ok(Claim::InProgress) => return IdempotencyError::InProgress.into_response(),
+        Ok(Claim::Mismatch) => return IdempotencyError::KeyReused.into_response(),
+        Err(e) => {
+            // Fail open: losing duplicate protection beats rejecting the request.
+            log_event!(
+                warn,
+                Subsystem::Api,
+                "idempotency.store_unavailable",
+                error = %e,
+                "Idempotency store unavailable, running request without it"
+            );
+            return next.run(Request::from_parts(parts, Body::from(body))).await;
+        }
+    }
+
+    // Dropped with this future if the client goes away or the handler panics
+    let mut guard = ClaimGuard {
+        store: Arc::clone(&store),
+        scope: scope.clone(),
+        key: key.clone(),
+        settled: false,
+    };
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let (parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            log_event!(
+                warn,
+                Subsystem::Api,
+                "idempotency.buffer_failed",
+                error = %e,
+                "Failed to buffer response for idempotency key"
+            );
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    guard.settled = true;
+    let outcome = if is_final(parts.status) {
+        let stored = StoredResponse {
+            status: parts.status.as_u16(),
+            content_type: parts
+                .headers
+                .get(header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string),
+            body: body.to_vec(),
+        };
+        store.complete(&scope, &key, &stored, Utc::now()).await
+    } else {
+        store.release(&scope, &key).await
+    };
+    if let Err(e) = outcome {
+        log_event!(
+            warn,
+            Subsystem::Api,
+            "idempotency.record_failed",
+            error = %e,
+            "Failed to record idempotent response"
+        );
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// Releases a claim unless the request reached an outcome, so a request that is
+/// cancelled or panics leaves its key free for the retry.
+struct ClaimGuard {
+    store: Arc<IdempotencyStore>,
+    scope: String,
+    key: String,
+    settled: bool,
+}
+
+impl Drop for ClaimGuard {
+    fn drop(&mut self) {
+        if self.settled {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let store = Arc::clone(&self.store);
+        let scope = std::mem::take(&mut self.scope);
+        let key = std::mem::take(&mut self.key);
+        runtime.spawn(async move {
+            if let Err(e) = store.release(&scope, &key).await {
+                log_event!(
+                    warn,
+                    Subsystem::Api,
+                    "idempotency.release_failed",
+                    error = %e,
+                    "Failed to release abandoned idempotency key"
+                );
+            }
+        });
+    }
+}
+
+/// Whether a response settles its request for good: successes, and client errors the
+/// same request would hit again. Authentication failures, conflicts and rate limiting
+/// may clear up on a retry, so like server errors they are not stored.
+fn is_final(status: StatusCode) -> bool {
+    status.is_success()
+        || (status.is_client_error()
+            && !matches!(
+                status,
+                StatusCode::UNAUTHORIZED
+                    | StatusCode::FORBIDDEN
+                    | StatusCode::REQUEST_TIMEOUT
+                    | StatusCode::CONFLICT
+                    | StatusCode::LOCKED
+                    | StatusCode::TOO_EARLY
+                    | StatusCode::TOO_MANY_REQUESTS
+            ))
+}
+
+/// Keys are per caller: a hash of whatever credential the request presents or, for
+/// anonymous requests, the client address. `None` if there is neither.
+fn caller_scope(req: &Request) -> Option<String> {
+    let headers = req.headers();
+    if let Some(credential) = headers
+        .get(header::AUTHORIZATION)
+        .or_else(|| headers.get("X-API-Key"))
+    {
+        return Some(hex::encode(Sha256::digest(credential.as_bytes())));
+    }
+    req.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| format!("ip:{}", addr.ip()))
+}
+
+fn request_hash(method: &Method, path: &str, body: &Bytes) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(path.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let status = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let mut response = (status, stored.body).into_response();
+    let headers = response.headers_mut();
+    if let Some(content_type) = stored
+        .content_type
+        .and_then(|c| HeaderValue::from_str(&c).ok())
+    {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+#[derive(Debug)]
+enum IdempotencyError {
+    InvalidKey,
+    UnknownCaller,
+    BodyTooLarge,
+    InProgress,
+    KeyReused,
+}
+
+impl IntoResponse for IdempotencyError {
+    fn into_response(self) -> Response {
+        let (status, code, message) = match self {
+            Self::InvalidKey => (
+                StatusCode::BAD_REQUEST,
+                "INVALID_IDEMPOTENCY_KEY",
+                "Idempotency-Key must be 1 to 255 visible ASCII characters",
+            ),
+            Self::UnknownCaller => (
+                StatusCode::BAD_REQUEST,
+                "IDEMPOTENCY_CALLER_UNKNOWN",
+                "Idempotency-Key needs credentials or an identifiable client address",
+            ),
+            Self::BodyTooLarge => (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "PAYLOAD_TOO_LARGE",
+                "Request body too large",
+            ),
+            Self::InProgress => (
+                StatusCode::CONFLICT,
+                "IDEMPOTENCY_REQUEST_IN_PROGRESS",
+                "A request with this Idempotency-Key is still being processed",
+            ),
+            Self::KeyReused => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "IDEMPOTENCY_KEY_REUSED",
+                "Idempotency-Key was already used for a different request",
+            ),
+        };
+        ProblemDetails::new(status, code.to_string(), message.to_string()).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations};
+    use axum::{middleware, routing::post, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    async fn store() -> Arc<IdempotencyStore> {
+        Arc::new(IdempotencyStore::new(
+            test_support::sqlite_db(&[migrations::IDEMPOTENCY_KEYS]).await,
+        ))
+    }
+
+    fn app(store: Arc<IdempotencyStore>, calls: Arc<AtomicUsize>) -> Router {
+        Router::new()
+            .route(
+                "/rules",
+                post(move |body: String| {
+                    let calls = Arc::clone(&calls);
+                    async move {
+                        let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                        match body.as_str() {
+                            "fail" => return (StatusCode::SERVICE_UNAVAILABLE, "down".to_string()),
+                            "busy" => {
+                                return (StatusCode::TOO_MANY_REQUESTS, "slow down".to_string())
+                            }
+                            "invalid" => return (StatusCode::BAD_REQUEST, "bad rule".to_string()),
+                            // Only the first attempt stalls, as if its client gave up
+                            "stall" if n == 1 => std::future::pending::<()>().await,
+                            _ => {}
+                        }
+                        (StatusCode::CREATED, format!("rule-{n}"))
+                    }
+                }),
+            )
+            .layer(middleware::from_fn_with_state(
+                store,
+                idempotency_middleware,
+            ))
+    }
+
+    fn post_with_key(key: &str, body: &str) -> Request {
+        post_from([127, 0, 0, 1], key, body)
+    }
+
+    fn post_from(ip: [u8; 4], key: &str, body: &str) -> Request {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/rules")
+            .header(IDEMPOTENCY_KEY_HEADER, key)
+            .extension(ConnectInfo(SocketAddr::from((ip, 40_000))))
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    async fn body_text(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_retry_replays_stored_response() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(store().await, Arc::clone(&calls));
+
+        let first = app
+            .clone()
+            .oneshot(post_with_key("k1", "{}"))
+            .await
+            .unwrap();
+        assert_eq!(first.status(), StatusCode::CREATED);
+        assert_eq!(body_text(first).await, "rule-1");
+
+        let retry = app.oneshot(post_with_key("k1", "{}")).await.unwrap();
+        assert_eq!(retry.status(), StatusCode::CREATED);
+        assert_eq!(
+            retry.headers().get(IDEMPOTENT_REPLAYED_HEADER).unwrap(),
+            "true"
+        );
+        assert_eq!(body_text(retry).await, "rule-1");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_key_reuse_with_different_body_is_rejected() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(store().await, Arc::clone(&calls));
+
+        app.clone()
+            .oneshot(post_with_key("k1", "{}"))
+            .await
+            .unwrap();
+        let reused = app
+            .oneshot(post_with_key("k1", r#"{"other":1}"#))
+            .await
+            .unwrap();
+        assert_eq!(reused.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_not_stored() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(store().await, Arc::clone(&calls));
+
+        let failed = app
+            .clone()
+            .oneshot(post_with_key("k1", "fail"))
+            .await
+            .unwrap();
+        assert_eq!(failed.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let retry = app.oneshot(post_with_key("k1", "fail")).await.unwrap();
+        assert!(retry.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_only_final_responses_are_stored() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(store().await, Arc::clone(&calls));
+
+        // A rate-limited request is run again on retry...
+        for _ in 0..2 {
+            let response = app
+                .clone()
+                .oneshot(post_with_key("k1", "busy"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+            assert!(response.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // ...while a rejected one would be rejected again, so it is replayed.
+        for _ in 0..2 {
+            let response = app
+                .clone()
+                .oneshot(post_with_key("k2", "invalid"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_request_releases_its_key() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(store().await, Arc::clone(&calls));
+
+        let abandoned = tokio::time::timeout(
+            std::time::Duration::from_millis(50),
+            app.clone().oneshot(post_with_key("k1", "stall")),
+        )
+        .await;
+        assert!(abandoned.is_err());
+
+        // The release runs in the background once the request is dropped.
+        let mut retry = None;
+        for _ in 0..20 {
+            let response = app
+                .clone()
+                .oneshot(post_with_key("k1", "stall"))
+                .await
+                .unwrap();
+            if response.status() != StatusCode::CONFLICT {
+                retry = Some(response);
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let retry = retry.unwrap();
+        assert_eq!(retry.status(), StatusCode::CREATED);
+        assert_eq!(body_text(retry).await, "rule-2");
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_callers_are_scoped_by_address() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(store().await, Arc::clone(&calls));
+
+        for ip in [[10, 0, 0, 1], [10, 0, 0, 2]] {
+            let response = app
+                .clone()
+                .oneshot(post_from(ip, "k1", "{}"))
+                .await
+                .unwrap();
+            assert!(response.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let unidentified = Request::builder()
+            .method(Method::POST)
+            .uri("/rules")
+            .header(IDEMPOTENCY_KEY_HEADER, "k1")
+            .body(Body::from("{}"))
+            .unwrap();
+        let response = app.oneshot(unidentified).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_requests_without_key_pass_through() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let app = app(store().await, Arc::clone(&calls));
+
+        for _ in 0..2 {
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri("/rules")
+                .body(Body::from("{}"))
+                .unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_claims_are_scoped_and_expire() {
+        let store = store().await;
+        let now = Utc::now();
+
+        assert_eq!(
+            store.claim("caller-a", "k", "h", now).await.unwrap(),
+            Claim::Acquired
+        );
+        assert_eq!(
+            store.claim("caller-a", "k", "h", now).await.unwrap(),
+            Claim::InProgress
+        );
+        assert_eq!(
+            store.claim("caller-b", "k", "h", now).await.unwrap(),
+            Claim::Acquired
+        );
+
+        // An unfinished claim only holds the key for its lease...
+        let lapsed = now + Duration::minutes(DEFAULT_LEASE_MINUTES) + Duration::seconds(1);
+        assert_eq!(
+            store.claim("caller-a", "k", "h", lapsed).await.unwrap(),
+            Claim::Acquired
+        );
+
+        // ...while a stored response is kept for the full TTL.
+        let response = StoredResponse {
+            status: 201,
+            content_type: None,
+            body: b"rule-1".to_vec(),
+        };
+        store
+            .complete("caller-a", "k", &response, lapsed)
+            .await
+            .unwrap();
+        assert_eq!(
+            store
+                .claim("caller-a", "k", "h", lapsed + Duration::hours(1))
+                .await
+                .unwrap(),
+            Claim::Replay(response)
+        );
+        let later = lapsed + Duration::hours(DEFAULT_TTL_HOURS) + Duration::seconds(1);
+        assert_eq!(
+            store.claim("caller-a", "k", "other", later).await.unwrap(),
+            Claim::Acquired
+        );
+    }
+}
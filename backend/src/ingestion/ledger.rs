@@ -2,8 +2,9 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, TimeZone, Utc};
 use sqlx::SqlitePool;
 use std::sync::Arc;
-use tracing::{info, warn};
 
+use crate::log_event;
+use crate::logging::Subsystem;
 use crate::rpc::{GetLedgersResult, RpcLedger, StellarRpcClient};
 use crate::services::account_merge_detector::AccountMergeDetector;
 use crate::services::fee_bump_tracker::FeeBumpTrackerService;
@@ -78,9 +79,13 @@ impl LedgerIngestionService {
             Some(health.oldest_ledger)
         };
 
-        info!(
-            "Starting ingestion from ledger {:?}, cursor: {:?}",
-            start_ledger, cursor
+        log_event!(
+            info,
+            Subsystem::Ingestion,
+            "ingestion.batch_started",
+            start_ledger = ?start_ledger,
+            cursor = ?cursor,
+            "Starting ledger ingestion"
         );
 
         let result = self
@@ -106,7 +111,14 @@ impl LedgerIngestionService {
 
         for ledger in &result.ledgers {
             if let Err(e) = self.persist_ledger(ledger).await {
-                warn!("Failed to persist ledger {}: {}", ledger.sequence, e);
+                log_event!(
+                    warn,
+                    Subsystem::Ingestion,
+                    "ingestion.ledger_persist_failed",
+                    ledger = ledger.sequence,
+                    error = %e,
+                    "Failed to persist ledger"
+                );
                 continue;
             }
 
@@ -132,14 +144,26 @@ impl LedgerIngestionService {
                         };
 
                         if let Err(e) = self.persist_payment(&extracted).await {
-                            warn!("Failed to persist payment: {}", e);
+                            log_event!(
+                                warn,
+                                Subsystem::Ingestion,
+                                "ingestion.payment_persist_failed",
+                                ledger = ledger.sequence,
+                                tx_hash = %extracted.transaction_hash,
+                                error = %e,
+                                "Failed to persist payment"
+                            );
                         }
                     }
                 }
                 Err(e) => {
-                    warn!(
-                        "Failed to fetch payments for ledger {}: {}",
-                        ledger.sequence, e
+                    log_event!(
+                        warn,
+                        Subsystem::Ingestion,
+                        "ingestion.payments_fetch_failed",
+                        ledger = ledger.sequence,
+                        error = %e,
+                        "Failed to fetch payments for ledger"
                     );
                     // Non-fatal, continue ingesting ledgers
                 }
@@ -157,13 +181,24 @@ impl LedgerIngestionService {
                         .process_transactions(&transactions)
                         .await
                     {
-                        warn!("Failed to process transactions for fee bumps: {}", e);
+                        log_event!(
+                            warn,
+                            Subsystem::Ingestion,
+                            "ingestion.fee_bumps_failed",
+                            ledger = ledger.sequence,
+                            error = %e,
+                            "Failed to process transactions for fee bumps"
+                        );
                     }
                 }
                 Err(e) => {
-                    warn!(
-                        "Failed to fetch transactions for ledger {}: {}",
-                        ledger.sequence, e
+                    log_event!(
+                        warn,
+                        Subsystem::Ingestion,
+                        "ingestion.transactions_fetch_failed",
+                        ledger = ledger.sequence,
+                        error = %e,
+                        "Failed to fetch transactions for ledger"
                     );
                 }
             }
@@ -173,16 +208,26 @@ impl LedgerIngestionService {
                 .process_ledger_operations(ledger.sequence)
                 .await
             {
-                warn!(
-                    "Failed to process account merge operations for ledger {}: {}",
-                    ledger.sequence, e
+                log_event!(
+                    warn,
+                    Subsystem::Ingestion,
+                    "ingestion.account_merges_failed",
+                    ledger = ledger.sequence,
+                    error = %e,
+                    "Failed to process account merge operations for ledger"
                 );
             }
 
             count += 1;
         }
 
-        info!("Processed {} ledgers", count);
+        log_event!(
+            info,
+            Subsystem::Ingestion,
+            "ingestion.batch_completed",
+            ledgers = count,
+            "Processed ledger batch"
+        );
         Ok(count)
     }
 
@@ -274,7 +319,13 @@ impl LedgerIngestionService {
                         )
                         .await
                     {
-                        tracing::error!("Failed to trigger payment created webhook: {}", e);
+                        log_event!(
+                            error,
+                            Subsystem::Webhooks,
+                            "webhooks.payment_created_failed",
+                            error = %e,
+                            "Failed to trigger payment created webhook"
+                        );
                     }
                 });
             }
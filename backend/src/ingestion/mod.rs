@@ -3,49 +3,144 @@ pub mod ledger;
 
 use anyhow::Result;
 use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tracing::{info, warn};
+use tokio::sync::Mutex;
 
 use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::projections::{ProjectionEvent, ProjectionPublisher};
 use crate::rpc::StellarRpcClient;
 
 pub struct DataIngestionService {
     rpc_client: Arc<StellarRpcClient>,
     db: Arc<Database>,
+    projections: Option<ProjectionPublisher>,
+    /// Set while drained; scheduled syncs are skipped until resumed
+    paused: AtomicBool,
+    /// Held for the duration of a full sync so a drain can wait for it
+    sync_in_progress: Mutex<()>,
 }
 
 impl DataIngestionService {
     #[must_use]
-    pub const fn new(rpc_client: Arc<StellarRpcClient>, db: Arc<Database>) -> Self {
-        Self { rpc_client, db }
+    pub fn new(rpc_client: Arc<StellarRpcClient>, db: Arc<Database>) -> Self {
+        Self {
+            rpc_client,
+            db,
+            projections: None,
+            paused: AtomicBool::new(false),
+            sync_in_progress: Mutex::new(()),
+        }
+    }
+
+    /// Publish read-model projection events whenever anchor metrics are ingested
+    #[must_use]
+    pub fn with_projections(mut self, publisher: ProjectionPublisher) -> Self {
+        self.projections = Some(publisher);
+        self
     }
 
     /// Sync all metrics from Stellar network
     pub async fn sync_all_metrics(&self) -> Result<()> {
-        info!("Starting metrics synchronization");
+        let _sync = self.sync_in_progress.lock().await;
+        if self.is_paused() {
+            log_event!(
+                info,
+                Subsystem::Ingestion,
+                "ingestion.sync_skipped",
+                "Ingestion is drained, skipping metrics synchronization"
+            );
+            return Ok(());
+        }
+        log_event!(
+            info,
+            Subsystem::Ingestion,
+            "ingestion.sync_started",
+            "Starting metrics synchronization"
+        );
 
         self.sync_anchor_metrics().await?;
 
-        info!("Metrics synchronization completed");
+        log_event!(
+            info,
+            Subsystem::Ingestion,
+            "ingestion.sync_completed",
+            "Metrics synchronization completed"
+        );
         Ok(())
     }
 
     /// Fetch and process anchor metrics from RPC
     pub async fn sync_anchor_metrics(&self) -> Result<()> {
-        info!("Syncing anchor metrics from Stellar network");
+        log_event!(
+            info,
+            Subsystem::Ingestion,
+            "ingestion.anchor_sync_started",
+            "Syncing anchor metrics from Stellar network"
+        );
 
         let anchors = self.db.list_anchors(0, 100).await?;
 
         for anchor in anchors {
             match self.process_anchor_metrics(&anchor.stellar_account).await {
-                Ok(()) => info!("Updated metrics for anchor: {}", anchor.name),
-                Err(e) => warn!("Failed to update anchor {}: {}", anchor.name, e),
+                Ok(()) => log_event!(
+                    info,
+                    Subsystem::Ingestion,
+                    "ingestion.anchor_metrics_updated",
+                    anchor_id = %anchor.id,
+                    anchor_name = %anchor.name,
+                    "Updated anchor metrics"
+                ),
+                Err(e) => log_event!(
+                    warn,
+                    Subsystem::Ingestion,
+                    "ingestion.anchor_metrics_failed",
+                    anchor_id = %anchor.id,
+                    anchor_name = %anchor.name,
+                    error = %e,
+                    "Failed to update anchor metrics"
+                ),
             }
         }
 
         Ok(())
     }
 
+    /// Stop further syncs and wait for the one in progress, if any, to finish
+    pub async fn drain(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+        drop(self.sync_in_progress.lock().await);
+        log_event!(
+            info,
+            Subsystem::Ingestion,
+            "ingestion.drained",
+            "Ingestion drained"
+        );
+    }
+
+    /// Let syncs run again after [`Self::drain`]
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+        log_event!(
+            info,
+            Subsystem::Ingestion,
+            "ingestion.resumed",
+            "Ingestion resumed"
+        );
+    }
+
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Re-fetch one anchor's metrics from RPC, outside the scheduled sync
+    pub async fn sync_anchor(&self, stellar_account: &str) -> Result<()> {
+        self.process_anchor_metrics(stellar_account).await
+    }
+
     /// Process metrics for a single anchor
     async fn process_anchor_metrics(&self, account_id: &str) -> Result<()> {
         let payments = self
@@ -106,6 +201,10 @@ impl DataIngestionService {
             })
             .await?;
 
+        if let Some(projections) = &self.projections {
+            projections.publish(ProjectionEvent::AnchorAccountChanged(account_id.to_string()));
+        }
+
         Ok(())
     }
 
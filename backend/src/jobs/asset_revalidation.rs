@@ -3,8 +3,9 @@ use chrono::{Duration, Utc};
 use sqlx::SqlitePool;
 use std::sync::Arc;
 use tokio::time::{interval, Duration as TokioDuration};
-use tracing::{error, info, warn};
 
+use crate::log_event;
+use crate::logging::Subsystem;
 use crate::models::asset_verification::VerifiedAsset;
 use crate::services::asset_verifier::AssetVerifier;
 
@@ -48,13 +49,23 @@ impl AssetRevalidationJob {
     /// Start the revalidation job
     pub async fn start(self: Arc<Self>) {
         if !self.config.enabled {
-            info!("Asset revalidation job is disabled");
+            log_event!(
+                info,
+                Subsystem::Jobs,
+                "jobs.asset_revalidation_disabled",
+                "Asset revalidation job is disabled"
+            );
             return;
         }
 
-        info!(
-            "Starting asset revalidation job (interval: {}h, batch_size: {}, max_age: {}d)",
-            self.config.interval_hours, self.config.batch_size, self.config.max_age_days
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "jobs.asset_revalidation_started",
+            interval_hours = self.config.interval_hours,
+            batch_size = self.config.batch_size,
+            max_age_days = self.config.max_age_days,
+            "Starting asset revalidation job"
         );
 
         let mut ticker = interval(TokioDuration::from_secs(self.config.interval_hours * 3600));
@@ -63,14 +74,25 @@ impl AssetRevalidationJob {
             ticker.tick().await;
 
             if let Err(e) = self.run_revalidation().await {
-                error!("Asset revalidation job failed: {}", e);
+                log_event!(
+                    error,
+                    Subsystem::Jobs,
+                    "jobs.asset_revalidation_failed",
+                    error = %e,
+                    "Asset revalidation job failed"
+                );
             }
         }
     }
 
     /// Run a single revalidation cycle
     async fn run_revalidation(&self) -> Result<()> {
-        info!("Starting asset revalidation cycle");
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "jobs.asset_revalidation_cycle_started",
+            "Starting asset revalidation cycle"
+        );
 
         let cutoff_date = Utc::now() - Duration::days(self.config.max_age_days);
 
@@ -89,11 +111,22 @@ impl AssetRevalidationJob {
         .await?;
 
         if assets.is_empty() {
-            info!("No assets need revalidation");
+            log_event!(
+                info,
+                Subsystem::Jobs,
+                "jobs.asset_revalidation_idle",
+                "No assets need revalidation"
+            );
             return Ok(());
         }
 
-        info!("Revalidating {} assets", assets.len());
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "jobs.asset_revalidation_batch",
+            assets = assets.len(),
+            "Revalidating assets"
+        );
 
         let verifier = AssetVerifier::new(self.pool.clone())?;
         let mut success_count = 0;
@@ -106,16 +139,25 @@ impl AssetRevalidationJob {
             {
                 Ok(_) => {
                     success_count += 1;
-                    info!(
-                        "Revalidated asset: {}-{}",
-                        asset.asset_code, asset.asset_issuer
+                    log_event!(
+                        info,
+                        Subsystem::Jobs,
+                        "jobs.asset_revalidated",
+                        asset_code = %asset.asset_code,
+                        asset_issuer = %asset.asset_issuer,
+                        "Revalidated asset"
                     );
                 }
                 Err(e) => {
                     failure_count += 1;
-                    warn!(
-                        "Failed to revalidate asset {}-{}: {}",
-                        asset.asset_code, asset.asset_issuer, e
+                    log_event!(
+                        warn,
+                        Subsystem::Jobs,
+                        "jobs.asset_revalidation_asset_failed",
+                        asset_code = %asset.asset_code,
+                        asset_issuer = %asset.asset_issuer,
+                        error = %e,
+                        "Failed to revalidate asset"
                     );
                 }
             }
@@ -124,9 +166,13 @@ impl AssetRevalidationJob {
             tokio::time::sleep(TokioDuration::from_millis(100)).await;
         }
 
-        info!(
-            "Revalidation cycle complete: {} succeeded, {} failed",
-            success_count, failure_count
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "jobs.asset_revalidation_cycle_completed",
+            succeeded = success_count,
+            failed = failure_count,
+            "Revalidation cycle complete"
         );
 
         Ok(())
@@ -134,17 +180,25 @@ impl AssetRevalidationJob {
 
     /// Manually trigger revalidation for a specific asset
     pub async fn revalidate_asset(&self, asset_code: &str, asset_issuer: &str) -> Result<()> {
-        info!(
-            "Manually revalidating asset: {}-{}",
-            asset_code, asset_issuer
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "jobs.asset_revalidation_requested",
+            asset_code,
+            asset_issuer,
+            "Manually revalidating asset"
         );
 
         let verifier = AssetVerifier::new(self.pool.clone())?;
         verifier.verify_asset(asset_code, asset_issuer).await?;
 
-        info!(
-            "Successfully revalidated asset: {}-{}",
-            asset_code, asset_issuer
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "jobs.asset_revalidated",
+            asset_code,
+            asset_issuer,
+            "Revalidated asset"
         );
 
         Ok(())
@@ -1,9 +1,10 @@
 use anyhow::Result;
 use std::sync::Arc;
 use tokio::time::{interval, Duration as TokioDuration};
-use tracing::{debug, error, info};
 
 use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
 use crate::services::contract_listener::ListenerConfig;
 use crate::services::event_indexer::EventIndexer;
 
@@ -54,14 +55,24 @@ impl ContractEventListenerJob {
     /// Start the event listener job
     pub async fn start(self: Arc<Self>) {
         if !self.config.enabled {
-            info!("Contract event listener job is disabled");
+            log_event!(
+                info,
+                Subsystem::Jobs,
+                "jobs.contract_listener_disabled",
+                "Contract event listener job is disabled"
+            );
             return;
         }
 
-        info!("Starting contract event listener job");
-        info!("Contract ID: {}", self.config.contract_id);
-        info!("RPC URL: {}", self.config.rpc_url);
-        info!("Interval: {} seconds", self.config.interval_seconds);
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "jobs.contract_listener_started",
+            contract_id = %self.config.contract_id,
+            rpc_url = %self.config.rpc_url,
+            interval_seconds = self.config.interval_seconds,
+            "Starting contract event listener job"
+        );
 
         let mut interval = interval(TokioDuration::from_secs(self.config.interval_seconds));
         interval.tick().await; // Skip first immediate tick
@@ -87,11 +98,23 @@ impl ContractEventListenerJob {
             {
                 Ok(events_processed) => {
                     if events_processed > 0 {
-                        info!("Processed {} missed contract events", events_processed);
+                        log_event!(
+                            info,
+                            Subsystem::Jobs,
+                            "jobs.contract_events_recovered",
+                            events = events_processed,
+                            "Processed missed contract events"
+                        );
                     }
                 }
                 Err(e) => {
-                    error!("Error checking for missed events: {}", e);
+                    log_event!(
+                        error,
+                        Subsystem::Jobs,
+                        "jobs.contract_events_check_failed",
+                        error = %e,
+                        "Error checking for missed events"
+                    );
                     // Continue running despite errors
                 }
             }
@@ -121,9 +144,13 @@ impl ContractEventListenerJob {
         // Current behavior uses: listener_config.rpc_url and listener_config.poll_interval_secs
 
         // For now, we'll just log that we're checking
-        debug!(
-            "Checking for events since ledger {} with poll interval {} seconds",
-            start_ledger, listener_config.poll_interval_secs
+        log_event!(
+            debug,
+            Subsystem::Jobs,
+            "jobs.contract_events_check",
+            start_ledger,
+            poll_interval_seconds = listener_config.poll_interval_secs,
+            "Checking for missed contract events"
         );
 
         // Return 0 events processed for now
@@ -178,7 +205,12 @@ pub async fn start_contract_event_listener_job(
         job_clone.start().await;
     });
 
-    info!("Contract event listener job started");
+    log_event!(
+        info,
+        Subsystem::Jobs,
+        "jobs.contract_listener_spawned",
+        "Contract event listener job started"
+    );
     Ok(job)
 }
 
@@ -0,0 +1,212 @@
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Datelike, Duration, DurationRound, TimeZone, Timelike, Utc};
+use std::fmt;
+use std::str::FromStr;
+
+/// How far ahead to look for the next run. Long enough for the rarest valid
+/// schedule, February 29th, across a skipped leap year.
+const SEARCH_DAYS: i64 = 366 * 9;
+
+/// A five-field cron expression — minute, hour, day of month, month, day of
+/// week — evaluated in UTC.
+///
+/// A field is `*`, a value, a range `a-b`, a step `*/n`, `a-b/n` or `a/n`, or
+/// a comma-separated list of those. Sunday is day 0, or 7. As in cron, a time
+/// whose day of month and day of week are both restricted matches either one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Both day fields are restricted
+    either_day: bool,
+}
+
+impl CronSchedule {
+    /// First time strictly after `after` the schedule fires, to the minute
+    #[must_use]
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut at = after.duration_trunc(Duration::minutes(1)).ok()? + Duration::minutes(1);
+        let limit = at + Duration::days(SEARCH_DAYS);
+
+        while at < limit {
+            if !has(self.months, at.month()) {
+                let (year, month) = if at.month() == 12 {
+                    (at.year() + 1, 1)
+                } else {
+                    (at.year(), at.month() + 1)
+                };
+                at = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+            } else if !self.matches_day(at) {
+                at = Utc.from_utc_datetime(&at.date_naive().succ_opt()?.and_hms_opt(0, 0, 0)?);
+            } else if !has(self.hours, at.hour()) {
+                at = at.duration_trunc(Duration::hours(1)).ok()? + Duration::hours(1);
+            } else if !has(self.minutes, at.minute()) {
+                at += Duration::minutes(1);
+            } else {
+                return Some(at);
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, at: DateTime<Utc>) -> bool {
+        let day = has(self.days, at.day());
+        let weekday = has(self.weekdays, at.weekday().num_days_from_sunday());
+        if self.either_day {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = anyhow::Error;
+
+    fn from_str(expression: &str) -> Result<Self> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minutes, hours, days, months, weekdays] = fields[..] else {
+            bail!(
+                "Cron expression '{expression}' needs 5 fields, found {}",
+                fields.len()
+            );
+        };
+
+        let mut weekday_mask = parse_field(weekdays, 0, 7)?;
+        if has(weekday_mask, 7) {
+            weekday_mask |= 1;
+        }
+        let schedule = Self {
+            expression: fields.join(" "),
+            minutes: parse_field(minutes, 0, 59)?,
+            hours: parse_field(hours, 0, 23)?,
+            days: parse_field(days, 1, 31)?,
+            months: parse_field(months, 1, 12)?,
+            weekdays: weekday_mask,
+            either_day: days != "*" && weekdays != "*",
+        };
+        if schedule.next_after(DateTime::UNIX_EPOCH).is_none() {
+            bail!("Cron expression '{expression}' never fires");
+        }
+        Ok(schedule)
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+const fn has(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+/// Bit mask of the values a field selects within `min..=max`
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64> {
+    let mut mask = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .with_context(|| format!("Invalid step in cron field '{part}'"))?;
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+
+        let value = |s: &str| {
+            s.parse::<u32>()
+                .with_context(|| format!("Invalid value in cron field '{part}'"))
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (value(start)?, value(end)?)
+        } else {
+            let start = value(range)?;
+            // `a/n` steps from `a` to the end of the field.
+            (start, if step.is_some() { max } else { start })
+        };
+        if start < min || end > max || start > end {
+            bail!("Cron field '{part}' is outside {min}-{max}");
+        }
+
+        for value in (start..=end).step_by(step.unwrap_or(1) as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(expression: &str, after: &str) -> DateTime<Utc> {
+        expression
+            .parse::<CronSchedule>()
+            .unwrap()
+            .next_after(at(after))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_next_run() {
+        assert_eq!(
+            next("* * * * *", "2026-03-01T10:15:30Z"),
+            at("2026-03-01T10:16:00Z")
+        );
+        assert_eq!(
+            next("0 */6 * * *", "2026-03-01T12:00:00Z"),
+            at("2026-03-01T18:00:00Z")
+        );
+        assert_eq!(
+            next("30 2 * * *", "2026-12-31T03:00:00Z"),
+            at("2027-01-01T02:30:00Z")
+        );
+        assert_eq!(
+            next("0 0 1-7 */3 *", "2026-02-10T00:00:00Z"),
+            at("2026-04-01T00:00:00Z")
+        );
+        // 2026-03-01 is a Sunday; with both day fields set, either matches.
+        assert_eq!(
+            next("0 9 15 * 7", "2026-02-28T12:00:00Z"),
+            at("2026-03-01T09:00:00Z")
+        );
+        assert_eq!(
+            next("0 0 29 2 *", "2097-01-01T00:00:00Z"),
+            at("2104-02-29T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_expressions() {
+        for expression in [
+            "* * * *",
+            "60 * * * *",
+            "* * 0 * *",
+            "5-1 * * * *",
+            "*/0 * * * *",
+            "a * * * *",
+            "0 0 30 2 *",
+        ] {
+            assert!(expression.parse::<CronSchedule>().is_err(), "{expression}");
+        }
+        assert_eq!(
+            "0  */6 * * *".parse::<CronSchedule>().unwrap().to_string(),
+            "0 */6 * * *"
+        );
+    }
+}
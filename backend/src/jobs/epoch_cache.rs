@@ -0,0 +1,290 @@
+use anyhow::{Context, Result};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::Instrument;
+
+use crate::log_event;
+use crate::logging::{correlation_span, Subsystem};
+
+use crate::cache::{keys, CacheManager};
+use crate::database::Database;
+use crate::services::batch_verification::BatchVerificationService;
+
+type WarmFn = Box<dyn Fn(u64) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> + Send + Sync>;
+
+/// Rotates the cached views of the latest snapshot epoch when a newer one is stored.
+///
+/// Invalidation runs before the warmers, so a rollover never serves a mix of
+/// previous-epoch and current-epoch entries.
+pub struct EpochCacheScheduler {
+    cache: Arc<CacheManager>,
+    db: Arc<Database>,
+    poll_interval: Duration,
+    warmers: Vec<(String, WarmFn)>,
+}
+
+impl EpochCacheScheduler {
+    #[must_use]
+    pub fn new(cache: Arc<CacheManager>, db: Arc<Database>, poll_interval: Duration) -> Self {
+        Self {
+            cache,
+            db,
+            poll_interval,
+            warmers: Vec::new(),
+        }
+    }
+
+    /// Register a warmer that runs after invalidation with the epoch that just started.
+    #[must_use]
+    pub fn with_warmer<F>(mut self, name: &str, warmer: F) -> Self
+    where
+        F: Fn(u64) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> + Send + Sync + 'static,
+    {
+        self.warmers.push((name.to_string(), Box::new(warmer)));
+        self
+    }
+
+    /// Verify the new epoch's snapshot hash on chain, caching the verdict that
+    /// bulk verification requests for it read.
+    #[must_use]
+    pub fn with_verdict_warmer(self, verifier: Arc<BatchVerificationService>) -> Self {
+        let db = Arc::clone(&self.db);
+        self.with_warmer("snapshot-verdict", move |epoch| {
+            let db = Arc::clone(&db);
+            let verifier = Arc::clone(&verifier);
+            Box::pin(async move {
+                let hash: Option<String> =
+                    sqlx::query_scalar("SELECT hash FROM snapshots WHERE epoch = $1")
+                        .bind(i64::try_from(epoch)?)
+                        .fetch_optional(db.pool())
+                        .await
+                        .context("Failed to load the snapshot hash")?
+                        .flatten();
+                if let Some(hash) = hash {
+                    verifier
+                        .verify_batch(&[(epoch, hash.to_ascii_lowercase())])
+                        .await;
+                }
+                Ok(())
+            })
+        })
+    }
+
+    /// Latest epoch with a stored snapshot
+    pub async fn latest_epoch(&self) -> Result<Option<u64>> {
+        let latest: Option<i64> = sqlx::query_scalar("SELECT MAX(epoch) FROM snapshots")
+            .fetch_one(self.db.pool())
+            .await
+            .context("Failed to load latest snapshot epoch")?;
+        Ok(latest.and_then(|epoch| u64::try_from(epoch).ok()))
+    }
+
+    /// Handle a single epoch rollover: drop the views of the previous epoch, then warm.
+    pub async fn on_epoch_boundary(&self, epoch: u64) -> Result<()> {
+        log_event!(
+            info,
+            Subsystem::Cache,
+            "cache.epoch_rotation_started",
+            epoch,
+            "Snapshot epoch rolled over, rotating epoch-scoped caches"
+        );
+
+        // Anchor and corridor rankings and the metrics overview summarize the
+        // latest epoch. Verdicts are fixed per (epoch, hash), except the new
+        // epoch's: one cached before its snapshot was anchored is stale now.
+        let mut invalidated = 0;
+        for pattern in [
+            keys::anchor_pattern(),
+            keys::corridor_pattern(),
+            keys::snapshot_verdict_pattern(epoch),
+        ] {
+            invalidated += self.cache.invalidate_pattern(&pattern).await?;
+        }
+        self.cache.delete(&keys::metrics_overview()).await?;
+
+        for (name, warmer) in &self.warmers {
+            if let Err(e) = warmer(epoch).await {
+                log_event!(
+                    warn,
+                    Subsystem::Cache,
+                    "cache.prewarm_failed",
+                    epoch,
+                    warmer = %name,
+                    error = %e,
+                    "Cache pre-warm failed"
+                );
+            }
+        }
+
+        log_event!(
+            info,
+            Subsystem::Cache,
+            "cache.epoch_rotation_completed",
+            epoch,
+            invalidated,
+            warmers = self.warmers.len(),
+            "Epoch cache rotation complete"
+        );
+        Ok(())
+    }
+
+    /// Poll the latest snapshot epoch and rotate the caches whenever it advances.
+    #[must_use]
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            log_event!(
+                info,
+                Subsystem::Jobs,
+                "jobs.epoch_cache_started",
+                poll_interval_seconds = self.poll_interval.as_secs(),
+                "Epoch cache scheduler started"
+            );
+
+            let mut seen = None;
+            let mut interval = tokio::time::interval(self.poll_interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                interval.tick().await;
+                let latest = match self.latest_epoch().await {
+                    Ok(latest) => latest,
+                    Err(e) => {
+                        log_event!(
+                            warn,
+                            Subsystem::Jobs,
+                            "jobs.epoch_cache_poll_failed",
+                            error = %e,
+                            "Failed to load the latest snapshot epoch"
+                        );
+                        continue;
+                    }
+                };
+                // The first poll only records where the epochs stand.
+                let Some(previous) = seen.replace(latest) else {
+                    continue;
+                };
+                let Some(epoch) = latest.filter(|_| latest > previous) else {
+                    continue;
+                };
+
+                let span = correlation_span(Subsystem::Jobs, &format!("epoch-cache-{epoch}"));
+                if let Err(e) = self.on_epoch_boundary(epoch).instrument(span).await {
+                    log_event!(
+                        error,
+                        Subsystem::Cache,
+                        "cache.epoch_rotation_failed",
+                        epoch,
+                        error = %e,
+                        "Epoch cache rotation failed"
+                    );
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheConfig;
+    use crate::services::batch_verification::Verdict;
+    use crate::test_support::{self, migrations};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    async fn scheduler() -> EpochCacheScheduler {
+        EpochCacheScheduler::new(
+            Arc::new(CacheManager::new_in_memory_for_tests(CacheConfig::default())),
+            test_support::sqlite_db(&[migrations::METRICS_CORRIDORS_SNAPSHOTS]).await,
+            Duration::from_secs(1),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_latest_epoch_follows_stored_snapshots() {
+        let scheduler = scheduler().await;
+        assert_eq!(scheduler.latest_epoch().await.unwrap(), None);
+
+        for epoch in [3, 5, 4] {
+            sqlx::query(
+                "INSERT INTO snapshots (id, entity_id, entity_type, data, hash, epoch, timestamp)
+                 VALUES ($1, 'all', 'analytics', '{}', 'ab', $2, '2026-01-01T00:00:00Z')",
+            )
+            .bind(format!("snapshot-{epoch}"))
+            .bind(epoch)
+            .execute(scheduler.db.pool())
+            .await
+            .unwrap();
+        }
+        assert_eq!(scheduler.latest_epoch().await.unwrap(), Some(5));
+    }
+
+    #[tokio::test]
+    async fn test_boundary_rotates_the_keys_handlers_read() {
+        let scheduler = scheduler().await;
+        let cache = &scheduler.cache;
+        for key in [
+            keys::anchor_list(50, 0),
+            keys::corridor_detail("USDC:issuer->XLM:native"),
+            keys::metrics_overview(),
+        ] {
+            cache
+                .set(&key, &"previous epoch".to_string(), 300)
+                .await
+                .unwrap();
+        }
+        for epoch in [7, 8] {
+            cache
+                .set(
+                    &keys::snapshot_verdict(epoch, "ab"),
+                    &Verdict::NotVerified,
+                    300,
+                )
+                .await
+                .unwrap();
+        }
+
+        scheduler.on_epoch_boundary(8).await.unwrap();
+
+        for key in [
+            keys::anchor_list(50, 0),
+            keys::corridor_detail("USDC:issuer->XLM:native"),
+            keys::metrics_overview(),
+        ] {
+            assert_eq!(cache.get::<String>(&key).await.unwrap(), None, "{key}");
+        }
+        assert_eq!(
+            cache
+                .get::<Verdict>(&keys::snapshot_verdict(8, "ab"))
+                .await
+                .unwrap(),
+            None
+        );
+        // Verdicts of closed epochs still hold.
+        assert_eq!(
+            cache
+                .get::<Verdict>(&keys::snapshot_verdict(7, "ab"))
+                .await
+                .unwrap(),
+            Some(Verdict::NotVerified)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_boundary_runs_warmers_with_new_epoch() {
+        let warmed_epoch = Arc::new(AtomicU64::new(0));
+        let observed = Arc::clone(&warmed_epoch);
+
+        let scheduler = scheduler().await.with_warmer("test", move |epoch| {
+            let observed = Arc::clone(&observed);
+            Box::pin(async move {
+                observed.store(epoch, Ordering::SeqCst);
+                Ok(())
+            })
+        });
+
+        scheduler.on_epoch_boundary(7).await.unwrap();
+        assert_eq!(warmed_epoch.load(Ordering::SeqCst), 7);
+    }
+}
@@ -1,5 +1,7 @@
 pub mod asset_revalidation;
 pub mod contract_event_listener;
+pub mod cron;
+pub mod epoch_cache;
 pub mod scheduler;
 
 pub use asset_revalidation::{AssetRevalidationJob, RevalidationConfig, RevalidationStats};
@@ -7,4 +9,6 @@ pub use contract_event_listener::{
     start_contract_event_listener_job, ContractEventListenerConfig, ContractEventListenerJob,
     ContractEventListenerStats,
 };
+pub use cron::CronSchedule;
+pub use epoch_cache::EpochCacheScheduler;
 pub use scheduler::{JobConfig, JobScheduler};
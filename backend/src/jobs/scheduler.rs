@@ -2,12 +2,18 @@ use anyhow::Result;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::task::JoinHandle;
-use tracing::{error, info};
 
 use crate::cache::CacheManager;
+use crate::cluster::{LeaderElection, ROLE_INGESTION};
 use crate::database::Database;
+use crate::idempotency::IdempotencyStore;
 use crate::ingestion::DataIngestionService;
+use crate::jobs::epoch_cache::EpochCacheScheduler;
+use crate::log_event;
+use crate::logging::Subsystem;
 use crate::rpc::StellarRpcClient;
+use crate::services::batch_verification::BatchVerificationService;
+use crate::services::contract::ContractService;
 use crate::services::price_feed::PriceFeedClient;
 
 #[derive(Clone)]
@@ -40,6 +46,7 @@ impl JobConfig {
 
 pub struct JobScheduler {
     handles: Vec<JoinHandle<()>>,
+    leader: Option<Arc<LeaderElection>>,
 }
 
 impl Default for JobScheduler {
@@ -53,35 +60,106 @@ impl JobScheduler {
     pub const fn new() -> Self {
         Self {
             handles: Vec::new(),
+            leader: None,
         }
     }
 
+    /// Run singleton jobs only while this instance leads their role. Without it,
+    /// singleton jobs run unconditionally (single-instance deployments).
+    #[must_use]
+    pub fn with_leader_election(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
     pub fn add_job<F>(&mut self, config: JobConfig, job_fn: F)
+    where
+        F: Fn() -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send>>
+            + Send
+            + 'static,
+    {
+        self.spawn_job(config, None, job_fn);
+    }
+
+    /// Schedule a job that must run on exactly one instance of the deployment.
+    pub fn add_singleton_job<F>(&mut self, role: &'static str, config: JobConfig, job_fn: F)
+    where
+        F: Fn() -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send>>
+            + Send
+            + 'static,
+    {
+        self.spawn_job(config, Some(role), job_fn);
+    }
+
+    fn spawn_job<F>(&mut self, config: JobConfig, role: Option<&'static str>, job_fn: F)
     where
         F: Fn() -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send>>
             + Send
             + 'static,
     {
         if !config.enabled {
-            info!("Job '{}' is disabled, skipping", config.name);
+            log_event!(
+                info,
+                Subsystem::Jobs,
+                "jobs.disabled",
+                job = %config.name,
+                "Job is disabled, skipping"
+            );
             return;
         }
 
-        info!(
-            "Scheduling job '{}' to run every {} seconds",
-            config.name, config.interval_seconds
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "jobs.scheduled",
+            job = %config.name,
+            interval_seconds = config.interval_seconds,
+            "Scheduling job"
         );
 
+        let leader = role.and(self.leader.clone());
         let handle = tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_secs(config.interval_seconds));
             interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
 
             loop {
                 interval.tick().await;
-                info!("Running job '{}'", config.name);
+                if let (Some(role), Some(leader)) = (role, leader.as_ref()) {
+                    if !leader.is_leader(role) {
+                        log_event!(
+                            debug,
+                            Subsystem::Jobs,
+                            "jobs.skipped_not_leader",
+                            job = %config.name,
+                            role,
+                            "Skipping job: this instance is not the leader"
+                        );
+                        continue;
+                    }
+                }
+                log_event!(
+                    info,
+                    Subsystem::Jobs,
+                    "jobs.run_started",
+                    job = %config.name,
+                    "Running job"
+                );
                 match job_fn().await {
-                    Ok(()) => info!("Job '{}' completed successfully", config.name),
-                    Err(e) => error!("Job '{}' failed: {}", config.name, e),
+                    Ok(()) => log_event!(
+                        info,
+                        Subsystem::Jobs,
+                        "jobs.run_completed",
+                        job = %config.name,
+                        "Job completed successfully"
+                    ),
+                    Err(e) => log_event!(
+                        error,
+                        Subsystem::Jobs,
+                        "jobs.run_failed",
+                        job = %config.name,
+                        error = %e,
+                        "Job failed"
+                    ),
                 }
             }
         });
@@ -95,14 +173,18 @@ impl JobScheduler {
         rpc: Arc<StellarRpcClient>,
         ingestion: Arc<DataIngestionService>,
         price_feed: Arc<PriceFeedClient>,
+        leader: Option<Arc<LeaderElection>>,
     ) -> Self {
         let mut scheduler = Self::new();
+        if let Some(leader) = leader {
+            scheduler = scheduler.with_leader_election(leader);
+        }
 
-        // Corridor refresh job
+        // Corridor refresh job: ingests from the network, so one instance does it
         let config = JobConfig::from_env("corridor-refresh", 300);
         let cache_clone = Arc::clone(&cache);
         let ingestion_clone = Arc::clone(&ingestion);
-        scheduler.add_job(config, move || {
+        scheduler.add_singleton_job(ROLE_INGESTION, config, move || {
             let cache = Arc::clone(&cache_clone);
             let ingestion = Arc::clone(&ingestion_clone);
             Box::pin(async move {
@@ -134,6 +216,32 @@ impl JobScheduler {
             })
         });
 
+        // Epoch rollover: polls for a newer snapshot epoch rather than rotating on
+        // every run
+        let config = JobConfig::from_env("epoch-cache", 60);
+        if config.enabled {
+            let verifier = Arc::new(BatchVerificationService::new(
+                ContractService::from_env().ok().map(Arc::new),
+                Arc::clone(&cache),
+            ));
+            let handle = EpochCacheScheduler::new(
+                Arc::clone(&cache),
+                Arc::clone(&db),
+                Duration::from_secs(config.interval_seconds.max(1)),
+            )
+            .with_verdict_warmer(verifier)
+            .spawn();
+            scheduler.handles.push(handle);
+        } else {
+            log_event!(
+                info,
+                Subsystem::Jobs,
+                "jobs.disabled",
+                job = %config.name,
+                "Job is disabled, skipping"
+            );
+        }
+
         // Cache cleanup job
         let config = JobConfig::from_env("cache-cleanup", 3600);
         let cache_clone = Arc::clone(&cache);
@@ -145,11 +253,27 @@ impl JobScheduler {
             })
         });
 
+        // Expired Idempotency-Key records
+        let config = JobConfig::from_env("idempotency-cleanup", 3600);
+        let idempotency = Arc::new(IdempotencyStore::new(Arc::clone(&db)));
+        scheduler.add_job(config, move || {
+            let idempotency = Arc::clone(&idempotency);
+            Box::pin(async move {
+                idempotency.purge_expired().await?;
+                Ok(())
+            })
+        });
+
         scheduler
     }
 
     pub async fn shutdown(self) {
-        info!("Shutting down job scheduler");
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "jobs.scheduler_stopped",
+            "Shutting down job scheduler"
+        );
         for handle in self.handles {
             handle.abort();
         }
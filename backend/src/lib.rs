@@ -1,4 +1,5 @@
 pub mod admin_audit_log;
+pub mod alert_bus;
 pub mod alerts;
 pub mod analytics;
 pub mod api;
@@ -12,6 +13,7 @@ pub mod backup;
 pub mod broadcast;
 pub mod cache;
 pub mod cache_invalidation;
+pub mod cluster;
 // cache_middleware removed in favor of cache helper APIs
 pub mod crypto;
 pub mod database;
@@ -22,6 +24,7 @@ pub mod env_config;
 pub mod error;
 pub mod handlers; // Core handlers (pool_metrics, health_check, ingestion_status)
 pub mod http_cache; // HTTP caching layer (ETag/conditional responses)
+pub mod idempotency;
 pub mod ingestion;
 pub mod ip_whitelist_middleware;
 pub mod jobs;
@@ -34,7 +37,12 @@ pub mod request_signing_middleware;
 pub mod network;
 pub mod observability;
 pub mod openapi;
+pub mod projections;
+pub mod query_cost;
+pub mod rate_index;
 pub mod rate_limit;
+pub mod read_only;
+pub mod readiness;
 pub mod replay;
 pub mod request_id;
 pub mod services;
@@ -50,3 +58,5 @@ pub mod rpc;
 
 #[cfg(test)]
 mod ml_tests;
+#[cfg(test)]
+mod test_support;
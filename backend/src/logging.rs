@@ -1,4 +1,7 @@
+pub mod loki;
 pub mod redaction;
+pub mod schema;
+pub mod sinks;
 
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
@@ -6,28 +9,53 @@ pub use redaction::{
     redact_account, redact_amount, redact_email, redact_hash, redact_ip, redact_token,
     redact_user_id, Redacted,
 };
+pub use schema::{correlation_span, Subsystem};
 
-/// Initializes logging to stdout only. No file output or rotation.
-/// Initialize logging with Logstash integration
-pub fn init_logging() -> anyhow::Result<()> {
+/// Initialize logging without OpenTelemetry, for tools and binaries other than the
+/// server. Uses the same sinks as [`crate::observability::tracing::init_tracing`].
+pub fn init_logging(
+    service_name: &str,
+) -> anyhow::Result<Option<tracing_appender::non_blocking::WorkerGuard>> {
     let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let sinks = sinks::LogSinksConfig::from_env(service_name)?.build_layers()?;
 
-    // Create console layer for local development
-    let console_layer = tracing_subscriber::fmt::layer()
-        .with_target(true)
-        .with_thread_ids(true)
-        .with_line_number(true)
-        .json();
-
-    // Build subscriber with console output.
     tracing_subscriber::registry()
         .with(env_filter)
-        .with(console_layer)
+        .with(sinks.layers)
         .init();
 
-    tracing::info!("Logging initialized with redaction support");
+    if let Some(pusher) = sinks.loki_pusher {
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(pusher.run());
+        }
+    }
+
+    Ok(sinks.file_guard)
+}
 
-    Ok(())
+/// Emit a structured event following the log schema in [`schema`].
+///
+/// `event` is a stable dot-separated name and `subsystem` a [`Subsystem`]; everything
+/// after them is passed to the `tracing` macro unchanged, so fields use the usual
+/// `%`/`?` sigils and the message comes last. Entity fields should use the names in
+/// [`schema::entity`].
+///
+/// ```ignore
+/// log_event!(info, Subsystem::Snapshot, "snapshot.submitted",
+///     epoch = epoch,
+///     tx_hash = %result.transaction_hash,
+///     "Snapshot anchored on-chain"
+/// );
+/// ```
+#[macro_export]
+macro_rules! log_event {
+    ($level:ident, $subsystem:expr, $event:expr, $($rest:tt)+) => {
+        tracing::$level!(
+            event = $event,
+            subsystem = $crate::logging::Subsystem::as_str($subsystem),
+            $($rest)+
+        )
+    };
 }
 
 /// Log HTTP request with structured fields
@@ -35,11 +63,13 @@ pub fn init_logging() -> anyhow::Result<()> {
 macro_rules! log_request {
     ($method:expr, $path:expr, $status:expr, $duration:expr, $request_id:expr) => {
         tracing::info!(
+            event = "http.request_completed",
+            subsystem = "api",
             http_method = %$method,
             http_path = %$path,
             http_status = $status,
             response_time_ms = $duration,
-            request_id = %$request_id,
+            correlation_id = %$request_id,
             "HTTP request completed"
         );
     };
@@ -50,6 +80,8 @@ macro_rules! log_request {
 macro_rules! log_rpc_call {
     ($method:expr, $duration:expr, $success:expr) => {
         tracing::info!(
+            event = "rpc.call_completed",
+            subsystem = "rpc",
             rpc_method = %$method,
             response_time_ms = $duration,
             success = $success,
@@ -63,6 +95,8 @@ macro_rules! log_rpc_call {
 macro_rules! log_query {
     ($query:expr, $duration:expr) => {
         tracing::debug!(
+            event = "db.query_executed",
+            subsystem = "database",
             query = %$query,
             query_time_ms = $duration,
             "Database query executed"
@@ -75,6 +109,7 @@ macro_rules! log_query {
 macro_rules! log_error {
     ($err:expr, $context:expr) => {
         tracing::error!(
+            event = "error",
             error = %$err,
             context = $context,
             "Error occurred"
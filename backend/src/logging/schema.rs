@@ -0,0 +1,80 @@
+//! Field names shared by every structured log line.
+//!
+//! Each event carries an `event` name (dot-separated, e.g. `snapshot.submitted`), the
+//! `subsystem` that emitted it and, for request-scoped work, the `correlation_id` of the
+//! enclosing span. Entity identifiers use the field names below so logs can be joined
+//! across subsystems without parsing messages.
+
+use std::fmt;
+
+pub const EVENT: &str = "event";
+pub const SUBSYSTEM: &str = "subsystem";
+pub const CORRELATION_ID: &str = "correlation_id";
+
+/// Canonical names for entity identifiers.
+pub mod entity {
+    pub const ANCHOR_ID: &str = "anchor_id";
+    pub const CORRIDOR_KEY: &str = "corridor_key";
+    pub const EPOCH: &str = "epoch";
+    pub const ACCOUNT: &str = "account";
+    pub const TX_HASH: &str = "tx_hash";
+    pub const CONTRACT_ID: &str = "contract_id";
+    pub const USER_ID: &str = "user_id";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Subsystem {
+    Api,
+    Alerts,
+    Cache,
+    Cluster,
+    Contract,
+    Database,
+    Ingestion,
+    Jobs,
+    Replay,
+    Rpc,
+    Snapshot,
+    Telegram,
+    Webhooks,
+}
+
+impl Subsystem {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Api => "api",
+            Self::Alerts => "alerts",
+            Self::Cache => "cache",
+            Self::Cluster => "cluster",
+            Self::Contract => "contract",
+            Self::Database => "database",
+            Self::Ingestion => "ingestion",
+            Self::Jobs => "jobs",
+            Self::Replay => "replay",
+            Self::Rpc => "rpc",
+            Self::Snapshot => "snapshot",
+            Self::Telegram => "telegram",
+            Self::Webhooks => "webhooks",
+        }
+    }
+}
+
+impl fmt::Display for Subsystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Span that tags everything logged inside it with a correlation ID.
+///
+/// HTTP requests get one from the request ID middleware; background jobs should open
+/// one per unit of work (e.g. per epoch or per ingestion batch).
+#[must_use]
+pub fn correlation_span(subsystem: Subsystem, correlation_id: &str) -> tracing::Span {
+    tracing::info_span!(
+        "correlated",
+        subsystem = subsystem.as_str(),
+        correlation_id = correlation_id
+    )
+}
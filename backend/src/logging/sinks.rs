@@ -0,0 +1,265 @@
+//! Output sinks for the tracing subscriber.
+//!
+//! Configured through the environment:
+//! - `LOG_SINKS`: comma-separated list of `stdout`, `file`, `loki`. When unset, stdout
+//!   is always on, `file` is enabled by `LOG_DIR` and `loki` by `LOKI_URL`.
+//! - `LOG_FORMAT`: `json` (default) or `text`, stdout only. File and Loki sinks always
+//!   write JSON so the log schema stays machine-readable.
+//! - `LOG_DIR`, `LOG_ROTATION` (`minutely`, `hourly`, `daily`, `never`), `LOG_MAX_FILES`
+//! - `LOKI_URL`, `LOKI_LABELS` (`key=value,...`), `LOKI_BATCH_SIZE`,
+//!   `LOKI_FLUSH_INTERVAL_MS`
+
+use anyhow::{bail, Result};
+use std::time::Duration;
+use tracing::Subscriber;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use super::loki::{self, LokiConfig, LokiPusher};
+
+const DEFAULT_MAX_LOG_FILES: usize = 30;
+const DEFAULT_LOKI_BATCH_SIZE: usize = 500;
+const DEFAULT_LOKI_FLUSH_MS: u64 = 2_000;
+const LOKI_QUEUE_CAPACITY: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileRotation {
+    Minutely,
+    Hourly,
+    Daily,
+    Never,
+}
+
+impl FileRotation {
+    fn parse(raw: &str) -> Result<Self> {
+        Ok(match raw.to_ascii_lowercase().as_str() {
+            "minutely" => Self::Minutely,
+            "hourly" => Self::Hourly,
+            "daily" => Self::Daily,
+            "never" => Self::Never,
+            other => bail!("Unknown LOG_ROTATION '{other}'"),
+        })
+    }
+
+    const fn as_rotation(self) -> Rotation {
+        match self {
+            Self::Minutely => Rotation::MINUTELY,
+            Self::Hourly => Rotation::HOURLY,
+            Self::Daily => Rotation::DAILY,
+            Self::Never => Rotation::NEVER,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FileSinkConfig {
+    pub dir: String,
+    pub rotation: FileRotation,
+    pub max_files: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogSinksConfig {
+    pub stdout: bool,
+    pub stdout_json: bool,
+    pub file: Option<FileSinkConfig>,
+    pub loki: Option<LokiConfig>,
+}
+
+/// Layers for the enabled sinks plus the handles that must outlive them.
+pub struct SinkLayers<S> {
+    pub layers: Vec<Box<dyn Layer<S> + Send + Sync>>,
+    /// Flushes the file sink on drop; hold it for the process lifetime.
+    pub file_guard: Option<WorkerGuard>,
+    /// Must be spawned on a Tokio runtime for the Loki sink to deliver anything.
+    pub loki_pusher: Option<LokiPusher>,
+}
+
+impl LogSinksConfig {
+    pub fn from_env(service_name: &str) -> Result<Self> {
+        Self::from_lookup(service_name, |key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(service_name: &str, var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let log_dir = var("LOG_DIR");
+        let loki_url = var("LOKI_URL");
+
+        let (stdout, file, loki) = match var("LOG_SINKS") {
+            Some(list) => {
+                let mut enabled = (false, false, false);
+                for sink in list.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                    match sink {
+                        "stdout" => enabled.0 = true,
+                        "file" => enabled.1 = true,
+                        "loki" => enabled.2 = true,
+                        other => bail!("Unknown log sink '{other}' in LOG_SINKS"),
+                    }
+                }
+                enabled
+            }
+            None => (true, log_dir.is_some(), loki_url.is_some()),
+        };
+
+        let file = if file {
+            let Some(dir) = log_dir else {
+                bail!("LOG_SINKS includes 'file' but LOG_DIR is not set");
+            };
+            Some(FileSinkConfig {
+                dir,
+                rotation: var("LOG_ROTATION")
+                    .map_or(Ok(FileRotation::Daily), |r| FileRotation::parse(&r))?,
+                max_files: var("LOG_MAX_FILES")
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(DEFAULT_MAX_LOG_FILES),
+            })
+        } else {
+            None
+        };
+
+        let loki = if loki {
+            let Some(url) = loki_url else {
+                bail!("LOG_SINKS includes 'loki' but LOKI_URL is not set");
+            };
+            let mut labels = var("LOKI_LABELS")
+                .map(|raw| loki::parse_labels(&raw))
+                .unwrap_or_default();
+            labels
+                .entry("service".to_string())
+                .or_insert_with(|| service_name.to_string());
+            Some(LokiConfig {
+                url,
+                labels,
+                batch_size: var("LOKI_BATCH_SIZE")
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(DEFAULT_LOKI_BATCH_SIZE),
+                flush_interval: Duration::from_millis(
+                    var("LOKI_FLUSH_INTERVAL_MS")
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(DEFAULT_LOKI_FLUSH_MS),
+                ),
+                queue_capacity: LOKI_QUEUE_CAPACITY,
+            })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            stdout,
+            stdout_json: var("LOG_FORMAT").is_none_or(|f| f.eq_ignore_ascii_case("json")),
+            file,
+            loki,
+        })
+    }
+
+    /// Build one `fmt` layer per enabled sink.
+    pub fn build_layers<S>(&self) -> Result<SinkLayers<S>>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+    {
+        let mut layers = Vec::new();
+        let mut file_guard = None;
+        let mut loki_pusher = None;
+
+        if self.stdout {
+            layers.push(if self.stdout_json {
+                json_layer(std::io::stdout)
+            } else {
+                tracing_subscriber::fmt::layer()
+                    .with_target(true)
+                    .with_level(true)
+                    .boxed()
+            });
+        }
+
+        if let Some(file) = &self.file {
+            std::fs::create_dir_all(&file.dir)?;
+            let appender = RollingFileAppender::builder()
+                .rotation(file.rotation.as_rotation())
+                .filename_prefix("stellar-insights")
+                .filename_suffix("log")
+                .max_log_files(file.max_files)
+                .build(&file.dir)?;
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            layers.push(json_layer(writer));
+            file_guard = Some(guard);
+        }
+
+        if let Some(config) = &self.loki {
+            let (writer, pusher) = loki::channel(config.clone());
+            layers.push(json_layer(writer));
+            loki_pusher = Some(pusher);
+        }
+
+        Ok(SinkLayers {
+            layers,
+            file_guard,
+            loki_pusher,
+        })
+    }
+}
+
+/// JSON lines with event fields flattened to the top level, so `event`, `subsystem`
+/// and entity IDs sit next to `level` and `timestamp`. The span list carries the
+/// `correlation_id` of enclosing request or job spans.
+fn json_layer<S, W>(writer: W) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+    W: for<'w> tracing_subscriber::fmt::MakeWriter<'w> + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt::layer()
+        .json()
+        .flatten_event(true)
+        .with_span_list(true)
+        .with_target(true)
+        .with_level(true)
+        .with_writer(writer)
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(vars: &[(&str, &str)]) -> Result<LogSinksConfig> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect();
+        LogSinksConfig::from_lookup("backend", |key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn test_defaults_follow_legacy_env() {
+        let c = config(&[]).unwrap();
+        assert!(c.stdout && c.stdout_json);
+        assert!(c.file.is_none() && c.loki.is_none());
+
+        let c = config(&[("LOG_DIR", "/tmp/logs"), ("LOKI_URL", "http://loki:3100")]).unwrap();
+        assert_eq!(c.file.unwrap().rotation, FileRotation::Daily);
+        assert_eq!(c.loki.unwrap().labels["service"], "backend");
+    }
+
+    #[test]
+    fn test_explicit_sink_list() {
+        let c = config(&[
+            ("LOG_SINKS", "file"),
+            ("LOG_DIR", "/tmp/logs"),
+            ("LOG_ROTATION", "hourly"),
+            ("LOKI_URL", "http://loki:3100"),
+        ])
+        .unwrap();
+        assert!(!c.stdout);
+        assert!(c.loki.is_none());
+        assert_eq!(c.file.unwrap().rotation, FileRotation::Hourly);
+    }
+
+    #[test]
+    fn test_invalid_sink_config_is_rejected() {
+        assert!(config(&[("LOG_SINKS", "syslog")]).is_err());
+        assert!(config(&[("LOG_SINKS", "file")]).is_err());
+        assert!(config(&[("LOG_DIR", "/tmp"), ("LOG_ROTATION", "weekly")]).is_err());
+    }
+}
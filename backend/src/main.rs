@@ -49,6 +49,7 @@ use stellar_insights_backend::auth::AuthService;
 use stellar_insights_backend::auth_middleware::auth_middleware;
 use stellar_insights_backend::cache::{CacheConfig, CacheManager};
 use stellar_insights_backend::cache_invalidation::CacheInvalidationService;
+use stellar_insights_backend::cluster::{ClusterConfig, LeaderElection};
 use stellar_insights_backend::database::Database;
 use stellar_insights_backend::gdpr::{GdprService, handlers as gdpr_handlers};
 use stellar_insights_backend::handlers::*;
@@ -58,19 +59,35 @@ use stellar_insights_backend::ip_whitelist_middleware::{ip_whitelist_middleware,
 use stellar_insights_backend::jobs::JobScheduler;
 use stellar_insights_backend::network::NetworkConfig;
 use stellar_insights_backend::openapi::ApiDoc;
+use stellar_insights_backend::projections::{self, ProjectionStore};
 use stellar_insights_backend::observability::{metrics as obs_metrics, tracing as obs_tracing};
 use stellar_insights_backend::observability::tracing::trace_propagation_middleware;
+use stellar_insights_backend::rate_index::{RateIndexStore, RateSampler};
 use stellar_insights_backend::rate_limit::{rate_limit_middleware, RateLimitConfig, RateLimiter};
+use stellar_insights_backend::read_only::ReadOnlyMode;
+use stellar_insights_backend::log_event;
+use stellar_insights_backend::logging::Subsystem;
+use stellar_insights_backend::replay::{
+    ConsistencyCheckConfig, ConsistencyMonitor, EventIngestor, IngestConfig, RpcEventSource,
+};
 use stellar_insights_backend::request_id::request_id_middleware;
 use stellar_insights_backend::rpc::StellarRpcClient;
 use stellar_insights_backend::rpc_handlers;
 use stellar_insights_backend::services::account_merge_detector::AccountMergeDetector;
+use stellar_insights_backend::services::aggregation::{AggregationConfig, AggregationService};
+use stellar_insights_backend::services::backfill_coordinator::BackfillCoordinator;
+use stellar_insights_backend::services::contract::ContractService;
+use stellar_insights_backend::services::corridor_sla::CorridorSlaService;
 use stellar_insights_backend::services::fee_bump_tracker::FeeBumpTrackerService;
 use stellar_insights_backend::services::liquidity_pool_analyzer::LiquidityPoolAnalyzer;
+use stellar_insights_backend::services::protocol_compat::ProtocolCompatibilityService;
 use stellar_insights_backend::services::price_feed::{
     default_asset_mapping, PriceFeedClient, PriceFeedConfig,
 };
 use stellar_insights_backend::services::realtime_broadcaster::RealtimeBroadcaster;
+use stellar_insights_backend::services::recommendations::RecommendationService;
+use stellar_insights_backend::services::runbook::RunbookService;
+use stellar_insights_backend::services::snapshot::SnapshotService;
 use stellar_insights_backend::services::trustline_analyzer::TrustlineAnalyzer;
 use stellar_insights_backend::services::webhook_dispatcher::WebhookDispatcher;
 use stellar_insights_backend::alerts::AlertManager;
@@ -118,6 +135,15 @@ async fn main() -> anyhow::Result<()> {
 
     let db = Arc::new(Database::new(pool.clone()));
 
+    // Every instance serves reads; ingestion, snapshot submission and digests only run
+    // on the instance holding the lease for their role.
+    let leader_election = Arc::new(LeaderElection::new(
+        Arc::clone(&db),
+        ClusterConfig::from_env(),
+    ));
+    leader_election.tick().await;
+    tokio::spawn(Arc::clone(&leader_election).start());
+
     let pool_metrics_db = Arc::clone(&db);
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(DB_POOL_LOG_INTERVAL);
@@ -180,20 +206,80 @@ async fn main() -> anyhow::Result<()> {
 
     let rpc_client = Arc::new(StellarRpcClient::new_with_defaults(true));
 
+    // Alert fan-out: each sink gets its own bounded queue; durable sinks that fall
+    // behind spill to the database instead of losing alerts. Sinks subscribe on their
+    // own, so the default subscription is dropped rather than left to fill up unread.
+    let (alert_manager, _) = AlertManager::new();
+    let alert_manager = Arc::new(alert_manager.with_spill_store(pool.clone()));
+
+    // Protocol compatibility: checked once at startup, then periodically so operators
+    // hear about upcoming network upgrades before XDR parsing breaks.
+    let protocol_compat = Arc::new(
+        ProtocolCompatibilityService::new(rpc_client.clone())
+            .with_alerts(Arc::clone(&alert_manager)),
+    );
+    let startup_report = protocol_compat.check().await;
+    log_event!(
+        info,
+        Subsystem::Rpc,
+        "protocol_compat.startup_checked",
+        status = ?startup_report.status,
+        "Network protocol compatibility checked"
+    );
+    tokio::spawn(Arc::clone(&protocol_compat).start());
+
     let price_feed_config = PriceFeedConfig::default();
     let price_feed = Arc::new(PriceFeedClient::new(
         price_feed_config,
         default_asset_mapping(),
     ));
+    // `/readyz` holds traffic back until the price cache has been filled once
+    {
+        let price_feed = Arc::clone(&price_feed);
+        tokio::spawn(async move {
+            if let Err(e) = price_feed.warm_cache().await {
+                log_event!(
+                    warn,
+                    Subsystem::Cache,
+                    "price_cache.warm_up_failed",
+                    error = %e,
+                    "Initial price cache warm-up failed"
+                );
+            }
+        });
+    }
+
+    // Record corridor cross rates into the compressed rate index for long-range charts
+    let rate_sampler = Arc::new(
+        RateSampler::new(
+            Arc::clone(&db),
+            Arc::new(RateIndexStore::new(Arc::clone(&db))),
+            Arc::clone(&price_feed),
+        )
+        .with_leader_election(Arc::clone(&leader_election)),
+    );
+    tokio::spawn(rate_sampler.start());
 
     let ws_state = Arc::new(WsState::new());
-    let ingestion = Arc::new(DataIngestionService::new(rpc_client.clone(), db.clone()));
+    // Read-model projections: ingestion publishes change events, a background task
+    // rebuilds the denormalized anchor/corridor detail views.
+    let (projection_publisher, projection_events) =
+        projections::channel(projections::DEFAULT_CHANNEL_CAPACITY);
+    projections::spawn_projection_updater(
+        Arc::new(ProjectionStore::new(db.clone())),
+        projection_events,
+    );
+
+    let ingestion = Arc::new(
+        DataIngestionService::new(rpc_client.clone(), db.clone())
+            .with_projections(projection_publisher.clone()),
+    );
 
     let app_state = AppState::new(
         db.clone(),
         cache.clone(),
         ws_state,
-        ingestion,
+        Arc::clone(&ingestion),
         rpc_client.clone(),
     );
     let cached_state = (
@@ -221,14 +307,125 @@ async fn main() -> anyhow::Result<()> {
             .context("Failed to initialize rate limiter")?,
     );
 
-    // Start webhook dispatcher as a background task
-    let webhook_pool = pool.clone();
-    tokio::spawn(async move {
-        let dispatcher = WebhookDispatcher::new(webhook_pool);
-        if let Err(e) = dispatcher.run().await {
-            tracing::error!("Webhook dispatcher stopped: {}", e);
-        }
+    // Evaluate operator-defined corridor SLAs and alert when a target is breached
+    let corridor_sla = Arc::new(
+        CorridorSlaService::new(Arc::clone(&db))
+            .with_alerts(Arc::clone(&alert_manager))
+            .with_leader_election(Arc::clone(&leader_election)),
+    );
+    tokio::spawn(corridor_sla.start());
+
+    // Replay the most recent ledgers on a schedule and alert when the state rebuilt
+    // from their events no longer matches the stored snapshots
+    let consistency_config = ConsistencyCheckConfig::from_env(NetworkConfig::from_env().network);
+    let consistency_checks: Option<JoinHandle<()>> = consistency_config.enabled.then(|| {
+        let monitor = ConsistencyMonitor::new(
+            Arc::clone(&app_state.replay_manager),
+            Arc::clone(&alert_manager),
+            consistency_config,
+        )
+        .with_leader_election(Arc::clone(&leader_election));
+        Arc::new(monitor).spawn()
     });
+
+    // Tail contract events into the replay event store; follow-mode replays
+    // receive them through its live feed
+    let ingest_config = IngestConfig::from_env();
+    if ingest_config.is_enabled() {
+        let network = NetworkConfig::from_env();
+        match RpcEventSource::new(network.rpc_url, network.network.to_string()) {
+            Ok(source) => {
+                let ingestor = EventIngestor::new(
+                    Arc::new(source),
+                    Arc::clone(&app_state.replay_events),
+                    &ingest_config,
+                    network.network,
+                )
+                .with_leader_election(Arc::clone(&leader_election));
+                tokio::spawn(Arc::new(ingestor).start());
+            }
+            Err(e) => log_event!(
+                warn,
+                Subsystem::Replay,
+                "replay.ingestion_disabled",
+                error = %e,
+                "Replay event ingestion disabled"
+            ),
+        }
+    }
+
+    // A public mirror runs no subsystem that writes outside its own analytics tables
+    // or needs credentials; the router gets the same mode and refuses the matching
+    // endpoints as well.
+    let read_only = ReadOnlyMode::from_env();
+    if read_only.enabled {
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "read_only.background_disabled",
+            "Read-only mode: digests, recomputation and webhook delivery disabled"
+        );
+    }
+
+    // Weekly "corridors to watch" digest for Telegram chats that opted in
+    if let Some(token) = std::env::var("TELEGRAM_BOT_TOKEN")
+        .ok()
+        .filter(|_| !read_only.enabled)
+    {
+        let recommendations = RecommendationService::new(Arc::clone(&db))
+            .with_telegram(Arc::new(
+                stellar_insights_backend::telegram::TelegramClient::new(&token),
+            ))
+            .with_leader_election(Arc::clone(&leader_election));
+        tokio::spawn(Arc::new(recommendations).start());
+    }
+
+    // Rebuild rollups and unanchored snapshot drafts for periods backfills reported
+    let backfill_coordinator = BackfillCoordinator::new(Arc::clone(&db))
+        .with_rollups(Arc::new(AggregationService::new(
+            Arc::clone(&db),
+            AggregationConfig::default(),
+        )))
+        .with_snapshots(Arc::new(SnapshotService::new(Arc::clone(&db), None, None)))
+        .with_leader_election(Arc::clone(&leader_election));
+    // Operator remediations requested through /admin/actions
+    let mut runbook = RunbookService::new(Arc::clone(&db))
+        .with_rollups(Arc::new(AggregationService::new(
+            Arc::clone(&db),
+            AggregationConfig::default(),
+        )))
+        .with_read_models(Arc::new(ProjectionStore::new(Arc::clone(&db))))
+        .with_ingestion(ingestion)
+        .with_leader_election(Arc::clone(&leader_election));
+    match ContractService::from_env() {
+        Ok(contract) => runbook = runbook.with_contract(Arc::new(contract)),
+        Err(e) => log_event!(
+            warn,
+            Subsystem::Jobs,
+            "runbook.reanchoring_disabled",
+            error = %e,
+            "Epoch re-anchoring disabled for runbook actions"
+        ),
+    }
+    if !read_only.enabled {
+        tokio::spawn(Arc::new(backfill_coordinator).start());
+        tokio::spawn(Arc::new(runbook).start());
+
+        // Start webhook dispatcher as a background task
+        let webhook_pool = pool.clone();
+        tokio::spawn(async move {
+            let dispatcher = WebhookDispatcher::new(webhook_pool);
+            if let Err(e) = dispatcher.run().await {
+                log_event!(
+                    error,
+                    Subsystem::Webhooks,
+                    "webhooks.dispatcher_stopped",
+                    error = %e,
+                    "Webhook dispatcher stopped"
+                );
+            }
+        });
+    }
     let allowed_origins = std::env::var("CORS_ALLOWED_ORIGINS")
         .unwrap_or_else(|_| "http://localhost:3000".to_string());
 
@@ -520,6 +717,8 @@ async fn main() -> anyhow::Result<()> {
         cors,
         pool,
         cache,
+        protocol_compat,
+        read_only,
     )
     .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
     .layer(TimeoutLayer::new(Duration::from_secs(timeout_seconds)))
@@ -545,10 +744,29 @@ async fn main() -> anyhow::Result<()> {
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     let start_shutdown = std::time::Instant::now();
-    axum::serve(listener, app)
-        .with_graceful_shutdown(stellar_insights_backend::shutdown::wait_for_signal())
-        .await?;
+    // Client addresses feed rate limiting and anonymous idempotency scopes
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown(stellar_insights_backend::shutdown::wait_for_signal())
+    .await?;
+
+    // Stop scheduling consistency replays before giving up the ingestion lease
+    if let Some(consistency_checks) = consistency_checks {
+        consistency_checks.abort();
+    }
     
+    // Hand singleton roles to another instance right away instead of after lease expiry
+    if let Err(e) = leader_election.release_all().await {
+        log_event!(
+            warn,
+            Subsystem::Cluster,
+            "cluster.lease_release_failed",
+            error = format!("{e:#}"),
+            "Failed to release leader leases"
+        );
+    }
     stellar_insights_backend::shutdown::log_shutdown_summary(start_shutdown);
     tracing::info!("Server shutdown complete");
     stellar_insights_backend::observability::tracing::shutdown_tracing();
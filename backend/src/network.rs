@@ -1,9 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum StellarNetwork {
+    /// Default, as when `STELLAR_NETWORK` is unset
+    #[default]
     Mainnet,
     Testnet,
 }
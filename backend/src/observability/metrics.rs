@@ -9,8 +9,9 @@ use axum::{
 };
 use lazy_static::lazy_static;
 use prometheus::{
-    register_counter, register_gauge, register_histogram, Counter, Encoder, Gauge, Histogram,
-    HistogramOpts, Registry, TextEncoder,
+    register_counter, register_counter_vec, register_gauge, register_gauge_vec,
+    register_histogram, Counter, CounterVec, Encoder, Gauge, GaugeVec, Histogram, HistogramOpts,
+    Registry, TextEncoder,
 };
 
 lazy_static! {
@@ -94,6 +95,27 @@ lazy_static! {
         &REGISTRY
     )
     .unwrap();
+    pub static ref ALERT_CONSUMER_LAG: GaugeVec = register_gauge_vec!(
+        "alert_consumer_lag",
+        "Alerts queued or spilled but not yet delivered, per consumer",
+        &["consumer"],
+        &REGISTRY
+    )
+    .unwrap();
+    pub static ref ALERTS_SPILLED_TOTAL: CounterVec = register_counter_vec!(
+        "alerts_spilled_total",
+        "Alerts written to the spill table because a consumer fell behind",
+        &["consumer"],
+        &REGISTRY
+    )
+    .unwrap();
+    pub static ref ALERTS_DROPPED_TOTAL: CounterVec = register_counter_vec!(
+        "alerts_dropped_total",
+        "Alerts discarded because a consumer queue overflowed",
+        &["consumer"],
+        &REGISTRY
+    )
+    .unwrap();
 }
 
 pub fn init_metrics() {
@@ -186,6 +208,20 @@ pub fn set_pool_active(count: i64) {
     DB_POOL_ACTIVE.set(count as f64);
 }
 
+pub fn set_alert_consumer_lag(consumer: &str, lag: i64) {
+    ALERT_CONSUMER_LAG
+        .with_label_values(&[consumer])
+        .set(lag as f64);
+}
+
+pub fn record_alert_spilled(consumer: &str) {
+    ALERTS_SPILLED_TOTAL.with_label_values(&[consumer]).inc();
+}
+
+pub fn record_alert_dropped(consumer: &str) {
+    ALERTS_DROPPED_TOTAL.with_label_values(&[consumer]).inc();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -5,12 +5,11 @@ use opentelemetry::sdk::propagation::TraceContextPropagator;
 use opentelemetry::sdk::{trace as sdktrace, Resource};
 use opentelemetry::{global, KeyValue};
 use opentelemetry_otlp::WithExportConfig;
-use tracing_opentelemetry::OpenTelemetrySpanExt;
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-const MAX_LOG_FILES: usize = 30;
+use crate::logging::sinks::LogSinksConfig;
 
 fn init_otel_tracer(service_name: &str) -> Result<sdktrace::Tracer> {
     let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
@@ -32,9 +31,9 @@ fn init_otel_tracer(service_name: &str) -> Result<sdktrace::Tracer> {
     Ok(tracer)
 }
 
-/// Initialize tracing. When `LOG_DIR` is set, logs are also written to a rotating file
-/// (daily rotation, up to 30 files retained). The returned guard must be held for the
-/// process lifetime so that file logs are flushed; drop it only at shutdown.
+/// Initialize tracing with the sinks configured in [`LogSinksConfig::from_env`]
+/// (stdout, rotating file, Loki). The returned guard flushes the file sink and must be
+/// held for the process lifetime; drop it only at shutdown.
 pub fn init_tracing(service_name: &str) -> Result<Option<WorkerGuard>> {
     // Register W3C TraceContext as the global propagator so that
     // `traceparent` / `tracestate` headers are used for context propagation.
@@ -45,106 +44,38 @@ pub fn init_tracing(service_name: &str) -> Result<Option<WorkerGuard>> {
     let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| "backend=info,tower_http=info".into());
 
-    let log_format = std::env::var("LOG_FORMAT").unwrap_or_else(|_| "json".to_string());
-    let use_json = log_format.eq_ignore_ascii_case("json");
-
     let otel_enabled = std::env::var("OTEL_ENABLED")
         .map(|v| v.eq_ignore_ascii_case("true"))
         .unwrap_or(true);
 
-    // Optional rotating file appender
-    let log_dir = std::env::var("LOG_DIR").ok();
-    let (file_writer, file_guard) = if let Some(ref dir) = log_dir {
-        std::fs::create_dir_all(dir)?;
-        let appender = RollingFileAppender::builder()
-            .rotation(Rotation::DAILY)
-            .filename_prefix("stellar-insights")
-            .filename_suffix("log")
-            .max_log_files(MAX_LOG_FILES)
-            .build(dir)?;
-        let (nb, guard) = tracing_appender::non_blocking(appender);
-        (Some(nb), Some(guard))
-    } else {
-        (None, None)
-    };
-
     // otel_layer must be added directly on registry() (before other layers) so that
     // the S: LookupSpan bound is satisfied.
-    if otel_enabled {
-        let otel_layer =
-            tracing_opentelemetry::layer().with_tracer(init_otel_tracer(service_name)?);
-        let base = tracing_subscriber::registry()
-            .with(otel_layer)
-            .with(env_filter);
+    let otel_layer = if otel_enabled {
+        Some(tracing_opentelemetry::layer().with_tracer(init_otel_tracer(service_name)?))
+    } else {
+        None
+    };
 
-        if use_json {
-            let fmt = tracing_subscriber::fmt::layer()
-                .json()
-                .with_target(true)
-                .with_level(true);
-            match file_writer {
-                Some(w) => {
-                    let file = tracing_subscriber::fmt::layer()
-                        .json()
-                        .with_writer(w)
-                        .with_target(true)
-                        .with_level(true);
-                    base.with(fmt).with(file).init();
-                }
-                None => base.with(fmt).init(),
-            }
-        } else {
-            let fmt = tracing_subscriber::fmt::layer()
-                .with_target(true)
-                .with_level(true);
-            match file_writer {
-                Some(w) => {
-                    let file = tracing_subscriber::fmt::layer()
-                        .with_writer(w)
-                        .with_target(true)
-                        .with_level(true);
-                    base.with(fmt).with(file).init();
-                }
-                None => base.with(fmt).init(),
+    let sinks = LogSinksConfig::from_env(service_name)?.build_layers()?;
+    tracing_subscriber::registry()
+        .with(otel_layer)
+        .with(env_filter)
+        .with(sinks.layers)
+        .init();
+
+    if let Some(pusher) = sinks.loki_pusher {
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(pusher.run());
             }
+            Err(_) => tracing::warn!("Loki sink configured outside a Tokio runtime; disabled"),
         }
+    }
+    if otel_enabled {
         tracing::info!("OpenTelemetry tracing enabled");
-    } else {
-        let base = tracing_subscriber::registry().with(env_filter);
-        if use_json {
-            let fmt = tracing_subscriber::fmt::layer()
-                .json()
-                .with_target(true)
-                .with_level(true);
-            match file_writer {
-                Some(w) => {
-                    let file = tracing_subscriber::fmt::layer()
-                        .json()
-                        .with_writer(w)
-                        .with_target(true)
-                        .with_level(true);
-                    base.with(fmt).with(file).init();
-                }
-                None => base.with(fmt).init(),
-            }
-        } else {
-            let fmt = tracing_subscriber::fmt::layer()
-                .with_target(true)
-                .with_level(true);
-            match file_writer {
-                Some(w) => {
-                    let file = tracing_subscriber::fmt::layer()
-                        .with_writer(w)
-                        .with_target(true)
-                        .with_level(true);
-                    base.with(fmt).with(file).init();
-                }
-                None => base.with(fmt).init(),
-            }
-        }
     }
 
-    Ok(file_guard)
+    Ok(sinks.file_guard)
 }
 
 pub fn shutdown_tracing() {
@@ -190,9 +121,7 @@ pub async fn trace_propagation_middleware(req: Request<Body>, next: Next) -> Res
 /// ```rust
 /// let response = inject_trace_context(client.get(&url)).send().await?;
 /// ```
-pub fn inject_trace_context(
-    builder: reqwest::RequestBuilder,
-) -> reqwest::RequestBuilder {
+pub fn inject_trace_context(builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
     let mut carrier = std::collections::HashMap::new();
     let propagator = TraceContextPropagator::new();
     let cx = opentelemetry::Context::current();
@@ -224,12 +153,7 @@ mod tests {
             .layer(middleware::from_fn(trace_propagation_middleware));
 
         let response = app
-            .oneshot(
-                Request::builder()
-                    .uri("/ping")
-                    .body(Body::empty())
-                    .unwrap(),
-            )
+            .oneshot(Request::builder().uri("/ping").body(Body::empty()).unwrap())
             .await
             .unwrap();
 
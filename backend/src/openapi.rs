@@ -23,11 +23,31 @@ use utoipa::OpenApi;
         // Anchors
         crate::api::anchors::get_anchor,
         crate::api::anchors::get_anchor_by_account,
+        crate::api::anchors::get_anchor_flows,
         crate::api::anchors::get_anchors,
         crate::api::anchors::get_muxed_analytics,
         // Corridors
         crate::api::corridors::list_corridors,
         crate::api::corridors::get_corridor_detail,
+        crate::api::corridors::get_corridor_summary,
+        crate::api::corridor_sla::get_corridor_sla,
+        crate::api::corridor_sla::upsert_corridor_sla,
+        crate::api::corridor_sla::delete_corridor_sla,
+        crate::api::geography::get_region_pairs,
+        crate::api::recommendations::get_recommendations,
+        crate::api::corridor_rates::get_corridor_rates,
+        crate::api::geography::list_mappings,
+        crate::api::geography::upsert_mapping,
+        crate::api::geography::delete_mapping,
+        crate::api::cluster::get_cluster,
+        crate::api::backfill::mark_dirty,
+        crate::api::backfill::list_recompute_ranges,
+        crate::api::backfill::get_recompute_range,
+        crate::api::runbook::request_runbook_action,
+        crate::api::runbook::list_runbook_actions,
+        crate::api::runbook::get_runbook_action,
+        crate::api::audit_bundle::get_audit_bundle,
+        crate::api::batch_verification::verify_batch,
         // Price Feed
         crate::api::price_feed::get_price,
         crate::api::price_feed::get_prices,
@@ -61,6 +81,10 @@ use utoipa::OpenApi;
         // Fee Bumps
         crate::api::fee_bump::get_fee_bump_stats,
         crate::api::fee_bump::get_recent_fee_bumps,
+        // Fee Simulation
+        crate::api::fee_simulation::simulate_fee_change,
+        crate::api::soroban_estimates::estimate_fees,
+        crate::api::soroban_estimates::list_estimates,
         // Liquidity Pools
         crate::api::liquidity_pools::list_pools,
         crate::api::liquidity_pools::get_pool_stats,
@@ -77,8 +101,12 @@ use utoipa::OpenApi;
         crate::api::network::get_network_info,
         crate::api::network::get_available_networks,
         crate::api::network::switch_network,
+        crate::api::network::get_compatibility_report,
         // Prediction
         crate::api::prediction::predict_success,
+        // Probes
+        crate::api::probes::livez,
+        crate::api::probes::readyz,
         // RPC
         crate::api::rpc::rpc_health_check,
         crate::api::rpc::get_latest_ledger,
@@ -165,13 +193,23 @@ use utoipa::OpenApi;
     ),
     components(
         schemas(
+            crate::error::ProblemDetails,
             crate::api::anchors::AnchorsResponse,
             crate::api::anchors::AnchorMetricsResponse,
+            crate::services::anchor_flows::AnchorFlows,
+            crate::services::anchor_flows::FlowNode,
+            crate::services::anchor_flows::FlowLink,
+            crate::services::anchor_flows::FlowDirection,
             crate::api::corridors::CorridorResponse,
             crate::api::corridors::CorridorDetailResponse,
+            crate::api::corridors::DetailSection,
+            crate::api::corridors::SectionFreshness,
+            crate::api::corridors::OrderBookDepth,
             crate::api::corridors::SuccessRateDataPoint,
             crate::api::corridors::LatencyDataPoint,
             crate::api::corridors::LiquidityDataPoint,
+            crate::projections::CorridorDetailView,
+            crate::projections::CorridorHourlyPoint,
             crate::api::price_feed::PriceResponse,
             crate::api::price_feed::PricesResponse,
             crate::api::price_feed::ConvertResponse,
@@ -182,9 +220,55 @@ use utoipa::OpenApi;
             crate::api::cost_calculator::RouteEstimate,
             crate::api::cost_calculator::CostCalculationResponse,
             crate::api::cost_calculator::ErrorResponse,
+            crate::services::fee_simulation::FeeSimulationRequest,
+            crate::services::fee_simulation::FeeSimulationResult,
+            crate::services::fee_simulation::CorridorFeeImpact,
+            crate::services::soroban_estimates::EstimateRequest,
+            crate::services::soroban_estimates::FeeEstimate,
+            crate::services::soroban_estimates::SimulatedResources,
+            crate::services::soroban_estimates::Footprint,
+            crate::services::soroban_estimates::FootprintEntry,
+            crate::services::soroban_estimates::FeeTrend,
+            crate::services::soroban_estimates::StoredEstimate,
+            crate::services::corridor_sla::CorridorSla,
+            crate::services::corridor_sla::UpsertCorridorSlaRequest,
+            crate::services::corridor_sla::SlaEvaluation,
+            crate::services::corridor_sla::SlaBreach,
+            crate::services::corridor_sla::MonthlyCompliance,
+            crate::services::corridor_sla::CorridorSlaStatus,
+            crate::services::geography::GeographyMapping,
+            crate::services::geography::UpsertGeographyMappingRequest,
+            crate::services::geography::RegionPairMetrics,
+            crate::services::geography::RegionPairSummary,
+            crate::services::recommendations::CorridorRecommendation,
+            crate::services::recommendations::RecommendationReport,
+            crate::services::batch_verification::BatchVerifyRequest,
+            crate::services::batch_verification::BatchVerifyItem,
+            crate::services::batch_verification::BatchVerifyResponse,
+            crate::services::batch_verification::BatchVerifyResult,
+            crate::services::batch_verification::Verdict,
+            crate::rate_index::RateSeries,
+            crate::rate_index::RateBucket,
+            crate::services::backfill_coordinator::MarkDirtyRequest,
+            crate::services::backfill_coordinator::RecomputeRange,
+            crate::services::backfill_coordinator::RecomputeStatus,
+            crate::services::runbook::RunbookAction,
+            crate::services::runbook::RunbookJob,
+            crate::services::runbook::RunbookStatus,
+            crate::cluster::ClusterView,
+            crate::cluster::RoleAssignment,
+            crate::cluster::ClusterMember,
+            crate::services::protocol_compat::CompatibilityReport,
+            crate::services::protocol_compat::SourceCompatibility,
+            crate::services::protocol_compat::CompatibilityStatus,
+            crate::readiness::LivenessReport,
+            crate::readiness::ReadinessReport,
+            crate::readiness::CheckResult,
+            crate::readiness::CheckStatus,
         )
     ),
     tags(
+        (name = "Admin", description = "Operator and administrative endpoints"),
         (name = "Alerts", description = "Alert management and notification endpoints"),
         (name = "Analytics", description = "API analytics endpoints"),
         (name = "Anchors", description = "Anchor management and metrics endpoints"),
@@ -192,6 +276,7 @@ use utoipa::OpenApi;
         (name = "Contract Events", description = "Smart contract event tracking"),
         (name = "Corridors", description = "Payment corridor analytics endpoints"),
         (name = "Fee Bumps", description = "Fee bump transaction tracking"),
+        (name = "Health", description = "Liveness and readiness probes"),
         (name = "Liquidity Pools", description = "Liquidity pool analytics"),
         (name = "Metrics", description = "System metrics and monitoring"),
         (name = "ML", description = "Machine learning prediction endpoints"),
@@ -201,6 +286,7 @@ use utoipa::OpenApi;
         (name = "Cost Calculator", description = "Cross-border payment cost estimation and route comparison"),
         (name = "RPC", description = "Stellar RPC integration endpoints"),
         (name = "SEP-31", description = "SEP-31 cross-border payment endpoints"),
+        (name = "Snapshots", description = "Anchored analytics snapshots and audit bundles"),
         (name = "Transactions", description = "Transaction management endpoints"),
         (name = "Trustlines", description = "Trustline analytics endpoints"),
         (name = "Webhooks", description = "Webhook management endpoints"),
@@ -0,0 +1,449 @@
+//! Denormalized read models for detail endpoints.
+//!
+//! Detail views are materialized into projection tables whenever ingestion or
+//! aggregation touches the underlying entities, so read paths only need a single
+//! primary-key lookup instead of re-joining anchors, assets, metrics history and
+//! hourly rollups on every request.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::models::AnchorDetailResponse;
+use crate::services::geography::GeographyService;
+
+/// Bump when the serialized shape of a projection payload changes so stale rows
+/// are rebuilt instead of deserialized.
+pub const PROJECTION_VERSION: i64 = 2;
+
+/// Projections older than this are rebuilt on read even without an ingestion event.
+const MAX_PROJECTION_AGE_SECS: i64 = 600;
+
+/// Hours of hourly rollups embedded in the corridor detail view.
+const CORRIDOR_SERIES_HOURS: i64 = 48;
+
+/// Default capacity of the projection event channel.
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Ingestion-side notification that a projection source has changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectionEvent {
+    AnchorChanged(Uuid),
+    AnchorAccountChanged(String),
+    CorridorChanged(String),
+}
+
+/// Cheap, cloneable handle used by ingestion services to publish projection events.
+#[derive(Clone)]
+pub struct ProjectionPublisher {
+    sender: mpsc::Sender<ProjectionEvent>,
+}
+
+impl ProjectionPublisher {
+    /// Publish without blocking ingestion. A full channel only delays the refresh,
+    /// since stale projections are rebuilt on read once they exceed the max age.
+    pub fn publish(&self, event: ProjectionEvent) {
+        if let Err(e) = self.sender.try_send(event) {
+            log_event!(
+                warn,
+                Subsystem::Jobs,
+                "projection.event_dropped",
+                error = %e,
+                "Dropped projection event"
+            );
+        }
+    }
+}
+
+/// Create a publisher and the receiver consumed by [`spawn_projection_updater`].
+#[must_use]
+pub fn channel(capacity: usize) -> (ProjectionPublisher, mpsc::Receiver<ProjectionEvent>) {
+    let (sender, receiver) = mpsc::channel(capacity);
+    (ProjectionPublisher { sender }, receiver)
+}
+
+/// Single hour of rollup data in the corridor detail view.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CorridorHourlyPoint {
+    pub hour_bucket: String,
+    pub total_transactions: i64,
+    pub success_rate: f64,
+    pub volume_usd: f64,
+    pub avg_settlement_latency_ms: Option<i64>,
+    pub liquidity_depth_usd: f64,
+}
+
+/// Denormalized corridor detail read model built from hourly rollups.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CorridorDetailView {
+    pub corridor_key: String,
+    pub asset_a_code: String,
+    pub asset_a_issuer: String,
+    pub asset_b_code: String,
+    pub asset_b_issuer: String,
+    /// Region of the source asset from the geography mappings, `None` when unmapped.
+    pub source_region: Option<String>,
+    /// Region of the destination asset from the geography mappings, `None` when unmapped.
+    pub destination_region: Option<String>,
+    pub total_transactions_24h: i64,
+    pub successful_transactions_24h: i64,
+    pub success_rate_24h: f64,
+    pub volume_usd_24h: f64,
+    pub avg_settlement_latency_ms_24h: Option<f64>,
+    pub liquidity_depth_usd: f64,
+    pub hourly: Vec<CorridorHourlyPoint>,
+    pub projected_at: DateTime<Utc>,
+}
+
+pub struct ProjectionStore {
+    db: Arc<Database>,
+}
+
+impl ProjectionStore {
+    #[must_use]
+    pub const fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Read the anchor detail projection, rebuilding it when missing or stale.
+    pub async fn anchor_detail(&self, anchor_id: Uuid) -> Result<Option<AnchorDetailResponse>> {
+        let row = sqlx::query(
+            "SELECT payload, version, updated_at FROM anchor_detail_projections WHERE anchor_id = ?",
+        )
+        .bind(anchor_id.to_string())
+        .fetch_optional(self.db.pool())
+        .await
+        .context("Failed to read anchor detail projection")?;
+
+        if let Some(row) = row {
+            if let Some(detail) = decode_fresh::<AnchorDetailResponse>(&row) {
+                return Ok(Some(detail));
+            }
+        }
+
+        self.refresh_anchor(anchor_id).await
+    }
+
+    /// Rebuild and persist the anchor detail projection from the normalized tables.
+    pub async fn refresh_anchor(&self, anchor_id: Uuid) -> Result<Option<AnchorDetailResponse>> {
+        let Some(detail) = self.db.get_anchor_detail(anchor_id).await? else {
+            sqlx::query("DELETE FROM anchor_detail_projections WHERE anchor_id = ?")
+                .bind(anchor_id.to_string())
+                .execute(self.db.pool())
+                .await
+                .context("Failed to delete orphaned anchor detail projection")?;
+            return Ok(None);
+        };
+
+        sqlx::query(
+            r"
+            INSERT INTO anchor_detail_projections (anchor_id, stellar_account, payload, version, updated_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(anchor_id) DO UPDATE SET
+                stellar_account = excluded.stellar_account,
+                payload = excluded.payload,
+                version = excluded.version,
+                updated_at = excluded.updated_at
+            ",
+        )
+        .bind(anchor_id.to_string())
+        .bind(&detail.anchor.stellar_account)
+        .bind(serde_json::to_string(&detail)?)
+        .bind(PROJECTION_VERSION)
+        .bind(Utc::now().to_rfc3339())
+        .execute(self.db.pool())
+        .await
+        .context("Failed to write anchor detail projection")?;
+
+        Ok(Some(detail))
+    }
+
+    /// Rebuild the projection for the anchor owning `stellar_account`.
+    pub async fn refresh_anchor_by_account(&self, stellar_account: &str) -> Result<()> {
+        if let Some(anchor) = self
+            .db
+            .get_anchor_by_stellar_account(stellar_account)
+            .await?
+        {
+            let anchor_id = Uuid::parse_str(&anchor.id)
+                .with_context(|| format!("Invalid anchor id: {}", anchor.id))?;
+            self.refresh_anchor(anchor_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Read the corridor detail projection, rebuilding it when missing or stale.
+    pub async fn corridor_detail(&self, corridor_key: &str) -> Result<Option<CorridorDetailView>> {
+        let row = sqlx::query(
+            "SELECT payload, version, updated_at FROM corridor_detail_projections WHERE corridor_key = ?",
+        )
+        .bind(corridor_key)
+        .fetch_optional(self.db.pool())
+        .await
+        .context("Failed to read corridor detail projection")?;
+
+        if let Some(row) = row {
+            if let Some(view) = decode_fresh::<CorridorDetailView>(&row) {
+                return Ok(Some(view));
+            }
+        }
+
+        self.refresh_corridor(corridor_key).await
+    }
+
+    /// Rebuild and persist the corridor detail projection from hourly rollups.
+    pub async fn refresh_corridor(&self, corridor_key: &str) -> Result<Option<CorridorDetailView>> {
+        let now = Utc::now();
+        let rows = sqlx::query(
+            r"
+            SELECT asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer, hour_bucket,
+                   total_transactions, successful_transactions, success_rate, volume_usd,
+                   avg_settlement_latency_ms, liquidity_depth_usd
+            FROM corridor_metrics_hourly
+            WHERE corridor_key = ? AND hour_bucket >= ?
+            ORDER BY hour_bucket ASC
+            ",
+        )
+        .bind(corridor_key)
+        .bind((now - Duration::hours(CORRIDOR_SERIES_HOURS)).to_rfc3339())
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to load hourly rollups for corridor projection")?;
+
+        let Some(first) = rows.first() else {
+            return Ok(None);
+        };
+
+        let mut view = build_corridor_view(
+            corridor_key,
+            [
+                first.get("asset_a_code"),
+                first.get("asset_a_issuer"),
+                first.get("asset_b_code"),
+                first.get("asset_b_issuer"),
+            ],
+            rows.iter()
+                .map(|row| CorridorHourlyPoint {
+                    hour_bucket: row.get("hour_bucket"),
+                    total_transactions: row.get("total_transactions"),
+                    success_rate: row.get("success_rate"),
+                    volume_usd: row.get("volume_usd"),
+                    avg_settlement_latency_ms: row.get("avg_settlement_latency_ms"),
+                    liquidity_depth_usd: row.get("liquidity_depth_usd"),
+                })
+                .collect(),
+            rows.iter()
+                .map(|row| row.get("successful_transactions"))
+                .collect(),
+            now,
+        );
+
+        let directory = GeographyService::new(self.db.clone()).directory().await?;
+        view.source_region = directory
+            .region_of(&view.asset_a_code, &view.asset_a_issuer)
+            .map(str::to_string);
+        view.destination_region = directory
+            .region_of(&view.asset_b_code, &view.asset_b_issuer)
+            .map(str::to_string);
+
+        sqlx::query(
+            r"
+            INSERT INTO corridor_detail_projections (corridor_key, payload, version, updated_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(corridor_key) DO UPDATE SET
+                payload = excluded.payload,
+                version = excluded.version,
+                updated_at = excluded.updated_at
+            ",
+        )
+        .bind(corridor_key)
+        .bind(serde_json::to_string(&view)?)
+        .bind(PROJECTION_VERSION)
+        .bind(now.to_rfc3339())
+        .execute(self.db.pool())
+        .await
+        .context("Failed to write corridor detail projection")?;
+
+        Ok(Some(view))
+    }
+
+    /// Apply a single ingestion event to the projections.
+    pub async fn apply(&self, event: &ProjectionEvent) -> Result<()> {
+        match event {
+            ProjectionEvent::AnchorChanged(anchor_id) => {
+                self.refresh_anchor(*anchor_id).await?;
+            }
+            ProjectionEvent::AnchorAccountChanged(account) => {
+                self.refresh_anchor_by_account(account).await?;
+            }
+            ProjectionEvent::CorridorChanged(corridor_key) => {
+                self.refresh_corridor(corridor_key).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Spawn the background task that keeps projections in sync with ingestion events.
+pub fn spawn_projection_updater(
+    store: Arc<ProjectionStore>,
+    mut receiver: mpsc::Receiver<ProjectionEvent>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "projection.updater_started",
+            "Read-model projection updater started"
+        );
+        while let Some(event) = receiver.recv().await {
+            if let Err(e) = store.apply(&event).await {
+                log_event!(
+                    warn,
+                    Subsystem::Jobs,
+                    "projection.refresh_failed",
+                    ?event,
+                    error = %e,
+                    "Failed to refresh projection"
+                );
+            }
+        }
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "projection.updater_stopped",
+            "Read-model projection updater stopped"
+        );
+    })
+}
+
+fn decode_fresh<T: serde::de::DeserializeOwned>(row: &sqlx::sqlite::SqliteRow) -> Option<T> {
+    let version: i64 = row.get("version");
+    if version != PROJECTION_VERSION {
+        return None;
+    }
+    let updated_at: String = row.get("updated_at");
+    let updated_at = DateTime::parse_from_rfc3339(&updated_at).ok()?;
+    if Utc::now().signed_duration_since(updated_at) > Duration::seconds(MAX_PROJECTION_AGE_SECS) {
+        return None;
+    }
+    let payload: String = row.get("payload");
+    serde_json::from_str(&payload).ok()
+}
+
+fn build_corridor_view(
+    corridor_key: &str,
+    [asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer]: [String; 4],
+    hourly: Vec<CorridorHourlyPoint>,
+    successful: Vec<i64>,
+    now: DateTime<Utc>,
+) -> CorridorDetailView {
+    let cutoff = (now - Duration::hours(24)).to_rfc3339();
+    let mut total_transactions_24h = 0;
+    let mut successful_transactions_24h = 0;
+    let mut volume_usd_24h = 0.0;
+    let mut latency_sum = 0.0;
+    let mut latency_samples = 0_u32;
+
+    for (point, successful) in hourly.iter().zip(&successful) {
+        if point.hour_bucket < cutoff {
+            continue;
+        }
+        total_transactions_24h += point.total_transactions;
+        successful_transactions_24h += successful;
+        volume_usd_24h += point.volume_usd;
+        if let Some(latency) = point.avg_settlement_latency_ms {
+            latency_sum += latency as f64;
+            latency_samples += 1;
+        }
+    }
+
+    CorridorDetailView {
+        corridor_key: corridor_key.to_string(),
+        asset_a_code,
+        asset_a_issuer,
+        asset_b_code,
+        asset_b_issuer,
+        source_region: None,
+        destination_region: None,
+        total_transactions_24h,
+        successful_transactions_24h,
+        success_rate_24h: if total_transactions_24h > 0 {
+            successful_transactions_24h as f64 / total_transactions_24h as f64 * 100.0
+        } else {
+            0.0
+        },
+        volume_usd_24h,
+        avg_settlement_latency_ms_24h: (latency_samples > 0)
+            .then(|| latency_sum / f64::from(latency_samples)),
+        liquidity_depth_usd: hourly.last().map_or(0.0, |p| p.liquidity_depth_usd),
+        hourly,
+        projected_at: now,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(hour_bucket: DateTime<Utc>, total: i64, volume: f64) -> CorridorHourlyPoint {
+        CorridorHourlyPoint {
+            hour_bucket: hour_bucket.to_rfc3339(),
+            total_transactions: total,
+            success_rate: 100.0,
+            volume_usd: volume,
+            avg_settlement_latency_ms: Some(400),
+            liquidity_depth_usd: volume * 2.0,
+        }
+    }
+
+    #[test]
+    fn test_corridor_view_only_sums_last_24_hours() {
+        let now = Utc::now();
+        let hourly = vec![
+            point(now - Duration::hours(30), 50, 5_000.0),
+            point(now - Duration::hours(2), 10, 1_000.0),
+            point(now - Duration::hours(1), 10, 2_000.0),
+        ];
+
+        let mut view = build_corridor_view(
+            "USDC:GA->XLM:native",
+            [
+                "USDC".to_string(),
+                "GA".to_string(),
+                "XLM".to_string(),
+                "native".to_string(),
+            ],
+            hourly,
+            vec![50, 9, 10],
+            now,
+        );
+
+        assert_eq!(view.total_transactions_24h, 20);
+        assert_eq!(view.successful_transactions_24h, 19);
+        assert!((view.success_rate_24h - 95.0).abs() < 1e-9);
+        assert!((view.volume_usd_24h - 3_000.0).abs() < 1e-9);
+        assert!((view.liquidity_depth_usd - 4_000.0).abs() < 1e-9);
+        assert_eq!(view.hourly.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_publisher_delivers_events() {
+        let (publisher, mut receiver) = channel(4);
+        publisher.publish(ProjectionEvent::CorridorChanged("A->B".to_string()));
+
+        assert_eq!(
+            receiver.recv().await,
+            Some(ProjectionEvent::CorridorChanged("A->B".to_string()))
+        );
+    }
+}
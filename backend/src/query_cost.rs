@@ -0,0 +1,259 @@
+//! Cost guardrails for analytics range queries.
+//!
+//! Before running, a range query is priced as the number of cells it would touch:
+//! `ceil(range / granularity) × entities`. Requests over their endpoint's budget are
+//! rejected with 422 and concrete alternatives (a coarser granularity or a shorter
+//! range that fits) rather than being allowed to scan the database unbounded.
+
+use std::fmt;
+
+/// Granularities offered as suggestions, smallest first.
+pub const SUGGESTED_GRANULARITIES: [i64; 8] = [
+    60,
+    300,
+    900,
+    3_600,
+    6 * 3_600,
+    86_400,
+    7 * 86_400,
+    30 * 86_400,
+];
+
+/// Estimated size of one range query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryCost {
+    pub range_seconds: i64,
+    pub granularity_seconds: i64,
+    pub entities: i64,
+    /// Query parameter that sets the granularity, when the caller controls it.
+    pub granularity_param: Option<&'static str>,
+}
+
+impl QueryCost {
+    #[must_use]
+    pub const fn new(range_seconds: i64, granularity_seconds: i64, entities: i64) -> Self {
+        Self {
+            range_seconds,
+            granularity_seconds,
+            entities,
+            granularity_param: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_granularity_param(mut self, param: &'static str) -> Self {
+        self.granularity_param = Some(param);
+        self
+    }
+
+    /// Buckets × entities, saturating instead of overflowing on absurd ranges.
+    #[must_use]
+    pub fn cells(&self) -> i64 {
+        buckets(self.range_seconds, self.granularity_seconds).saturating_mul(self.entities.max(1))
+    }
+}
+
+fn buckets(range_seconds: i64, granularity_seconds: i64) -> i64 {
+    let granularity = granularity_seconds.max(1);
+    range_seconds.max(0).saturating_add(granularity - 1) / granularity
+}
+
+/// Hard caps for one endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryBudget {
+    pub max_cells: i64,
+    pub max_range_seconds: i64,
+}
+
+impl QueryBudget {
+    #[must_use]
+    pub const fn new(max_cells: i64, max_range_seconds: i64) -> Self {
+        Self {
+            max_cells,
+            max_range_seconds,
+        }
+    }
+
+    /// Override the defaults with `QUERY_BUDGET_<NAME>_MAX_CELLS` and
+    /// `QUERY_BUDGET_<NAME>_MAX_RANGE_DAYS`.
+    #[must_use]
+    pub fn from_env(name: &str, default: Self) -> Self {
+        let prefix = format!("QUERY_BUDGET_{}", name.to_uppercase().replace('-', "_"));
+        let max_cells = std::env::var(format!("{prefix}_MAX_CELLS"))
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|cells| *cells > 0)
+            .unwrap_or(default.max_cells);
+        let max_range_seconds = std::env::var(format!("{prefix}_MAX_RANGE_DAYS"))
+            .ok()
+            .and_then(|s| s.parse::<i64>().ok())
+            .filter(|days| *days > 0)
+            .map_or(default.max_range_seconds, |days| {
+                days.saturating_mul(86_400)
+            });
+
+        Self {
+            max_cells,
+            max_range_seconds,
+        }
+    }
+
+    /// Reject `cost` if it exceeds either cap, explaining how to bring it under.
+    pub fn check(&self, cost: QueryCost) -> Result<(), QueryCostExceeded> {
+        let estimated_cells = cost.cells();
+        if cost.range_seconds <= self.max_range_seconds && estimated_cells <= self.max_cells {
+            return Ok(());
+        }
+
+        // Longest range that fits at the requested granularity
+        let buckets_per_entity = self.max_cells / cost.entities.max(1);
+        let max_range_at_granularity = buckets_per_entity
+            .saturating_mul(cost.granularity_seconds.max(1))
+            .min(self.max_range_seconds);
+
+        // Coarsest change needed to keep the requested range, if the caller can make it
+        let suggested_granularity_seconds = cost
+            .granularity_param
+            .filter(|_| cost.range_seconds <= self.max_range_seconds)
+            .and_then(|_| {
+                SUGGESTED_GRANULARITIES.into_iter().find(|granularity| {
+                    *granularity > cost.granularity_seconds
+                        && buckets(cost.range_seconds, *granularity)
+                            .saturating_mul(cost.entities.max(1))
+                            <= self.max_cells
+                })
+            });
+
+        Err(QueryCostExceeded {
+            cost,
+            estimated_cells,
+            budget: *self,
+            max_range_at_granularity,
+            suggested_granularity_seconds,
+        })
+    }
+}
+
+/// A query priced over its budget, with the alternatives that would fit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryCostExceeded {
+    pub cost: QueryCost,
+    pub estimated_cells: i64,
+    pub budget: QueryBudget,
+    /// Longest range (seconds) allowed at the requested granularity and entity count.
+    pub max_range_at_granularity: i64,
+    pub suggested_granularity_seconds: Option<i64>,
+}
+
+impl QueryCostExceeded {
+    /// Human-readable ways to make the query fit, most useful first.
+    #[must_use]
+    pub fn suggestions(&self) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        if let (Some(param), Some(granularity)) = (
+            self.cost.granularity_param,
+            self.suggested_granularity_seconds,
+        ) {
+            suggestions.push(format!(
+                "Downsample: set {param}={granularity} ({}) or coarser",
+                format_duration(granularity)
+            ));
+        }
+        if self.max_range_at_granularity > 0 {
+            suggestions.push(format!(
+                "Shorten the range to at most {} at the current granularity",
+                format_duration(self.max_range_at_granularity)
+            ));
+        }
+        if self.cost.entities > 1 {
+            suggestions.push("Query fewer entities per request".to_string());
+        }
+        suggestions
+    }
+}
+
+impl fmt::Display for QueryCostExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.cost.range_seconds > self.budget.max_range_seconds {
+            write!(
+                f,
+                "Requested range of {} exceeds the maximum of {}",
+                format_duration(self.cost.range_seconds),
+                format_duration(self.budget.max_range_seconds)
+            )
+        } else {
+            write!(
+                f,
+                "Query would read about {} data points, above the limit of {}",
+                self.estimated_cells, self.budget.max_cells
+            )
+        }
+    }
+}
+
+impl std::error::Error for QueryCostExceeded {}
+
+fn format_duration(seconds: i64) -> String {
+    match seconds {
+        s if s >= 86_400 && s % 86_400 == 0 => format!("{}d", s / 86_400),
+        s if s >= 3_600 && s % 3_600 == 0 => format!("{}h", s / 3_600),
+        s if s >= 60 && s % 60 == 0 => format!("{}m", s / 60),
+        s => format!("{s}s"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+
+    #[test]
+    fn test_within_budget() {
+        let budget = QueryBudget::new(1_000, 30 * DAY);
+        assert!(budget.check(QueryCost::new(DAY, 300, 3)).is_ok());
+        assert_eq!(QueryCost::new(DAY, 300, 3).cells(), 864);
+    }
+
+    #[test]
+    fn test_suggests_downsampling_and_shorter_range() {
+        let budget = QueryBudget::new(1_000, 365 * DAY);
+        let cost = QueryCost::new(30 * DAY, 60, 1).with_granularity_param("step_seconds");
+
+        let err = budget.check(cost).unwrap_err();
+        assert_eq!(err.estimated_cells, 43_200);
+        // 30 days fit in 1000 buckets only at hourly steps or coarser
+        assert_eq!(err.suggested_granularity_seconds, Some(3_600));
+        assert_eq!(err.max_range_at_granularity, 60_000);
+        let suggestions = err.suggestions();
+        assert_eq!(
+            suggestions[0],
+            "Downsample: set step_seconds=3600 (1h) or coarser"
+        );
+        assert!(suggestions[1].contains("1000m"));
+    }
+
+    #[test]
+    fn test_range_cap_and_fixed_granularity() {
+        let budget = QueryBudget::new(100_000, 90 * DAY);
+
+        // Over the range cap: no granularity suggestion, only a shorter range
+        let err = budget
+            .check(QueryCost::new(400 * DAY, 3_600, 2).with_granularity_param("step"))
+            .unwrap_err();
+        assert_eq!(err.suggested_granularity_seconds, None);
+        assert_eq!(err.max_range_at_granularity, 90 * DAY);
+        assert!(err.to_string().contains("400d exceeds the maximum of 90d"));
+
+        // Hourly rollups cannot be coarsened by the caller
+        let err = budget
+            .check(QueryCost::new(60 * DAY, 3_600, 100))
+            .unwrap_err();
+        assert_eq!(err.suggested_granularity_seconds, None);
+        assert_eq!(err.max_range_at_granularity, 1_000 * 3_600);
+        assert_eq!(
+            err.suggestions().last().map(String::as_str),
+            Some("Query fewer entities per request")
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! Block codec for rate samples.
+//!
+//! Timestamps are stored as delta-of-deltas, so a corridor sampled on a fixed
+//! interval costs one bit per timestamp. Rates are XOR-ed with the previous value
+//! and only the meaningful bits are kept, which collapses unchanged or slowly
+//! drifting rates to a handful of bits. This is the layout popularised by
+//! Facebook's Gorilla paper, with symmetric two's-complement buckets.
+//!
+//! Block layout: first timestamp (64 bits), first rate (64 bits), then for every
+//! further sample a timestamp code followed by a value code. The sample count is
+//! stored next to the block, so there is no end marker.
+
+use super::RateSample;
+
+/// `(control bits, control length, payload length)` for delta-of-delta buckets,
+/// tried in order. A final `1111` control carries the full 64-bit value.
+const DOD_BUCKETS: [(u64, u32, u32); 3] = [(0b10, 2, 7), (0b110, 3, 9), (0b1110, 4, 12)];
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum CodecError {
+    #[error("samples must have strictly increasing timestamps ({previous} then {next})")]
+    OutOfOrder { previous: i64, next: i64 },
+    #[error("block ended after {decoded} of {expected} samples")]
+    Truncated { decoded: usize, expected: usize },
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    bit_len: usize,
+}
+
+impl BitWriter {
+    /// Append the low `count` bits of `value`, most significant first.
+    fn write(&mut self, value: u64, count: u32) {
+        for shift in (0..count).rev() {
+            if self.bit_len.is_multiple_of(8) {
+                self.bytes.push(0);
+            }
+            if (value >> shift) & 1 == 1 {
+                if let Some(last) = self.bytes.last_mut() {
+                    *last |= 0x80 >> (self.bit_len % 8);
+                }
+            }
+            self.bit_len += 1;
+        }
+    }
+
+    fn bit(&mut self, set: bool) {
+        self.write(u64::from(set), 1);
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    const fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn read(&mut self, count: u32) -> Option<u64> {
+        let mut value = 0_u64;
+        for _ in 0..count {
+            let byte = self.bytes.get(self.position / 8)?;
+            let bit = (byte >> (7 - self.position % 8)) & 1;
+            value = (value << 1) | u64::from(bit);
+            self.position += 1;
+        }
+        Some(value)
+    }
+
+    fn bit(&mut self) -> Option<bool> {
+        self.read(1).map(|b| b == 1)
+    }
+}
+
+/// Leading/trailing zero window of the previous non-zero XOR.
+#[derive(Clone, Copy)]
+struct XorWindow {
+    leading: u32,
+    trailing: u32,
+}
+
+impl XorWindow {
+    const fn meaningful_bits(self) -> u32 {
+        64 - self.leading - self.trailing
+    }
+}
+
+/// Encode samples into a block. Timestamps must be strictly increasing.
+// Timestamps and deltas are written as raw two's-complement bits
+#[allow(clippy::cast_sign_loss)]
+pub fn encode(samples: &[RateSample]) -> Result<Vec<u8>, CodecError> {
+    let Some(first) = samples.first() else {
+        return Ok(Vec::new());
+    };
+
+    let mut writer = BitWriter::default();
+    writer.write(first.timestamp as u64, 64);
+    writer.write(first.rate.to_bits(), 64);
+
+    let mut previous = *first;
+    let mut previous_delta = 0_i64;
+    let mut window: Option<XorWindow> = None;
+
+    for sample in &samples[1..] {
+        if sample.timestamp <= previous.timestamp {
+            return Err(CodecError::OutOfOrder {
+                previous: previous.timestamp,
+                next: sample.timestamp,
+            });
+        }
+
+        let delta = sample.timestamp - previous.timestamp;
+        write_delta_of_delta(&mut writer, delta.wrapping_sub(previous_delta));
+        previous_delta = delta;
+
+        let xor = sample.rate.to_bits() ^ previous.rate.to_bits();
+        if xor == 0 {
+            writer.bit(false);
+        } else {
+            writer.bit(true);
+            // Leading zeros are stored in 5 bits, so cap them at 31
+            let current = XorWindow {
+                leading: xor.leading_zeros().min(31),
+                trailing: xor.trailing_zeros(),
+            };
+            match window {
+                Some(w) if current.leading >= w.leading && current.trailing >= w.trailing => {
+                    writer.bit(false);
+                    writer.write(xor >> w.trailing, w.meaningful_bits());
+                }
+                _ => {
+                    writer.bit(true);
+                    writer.write(u64::from(current.leading), 5);
+                    writer.write(u64::from(current.meaningful_bits() - 1), 6);
+                    writer.write(xor >> current.trailing, current.meaningful_bits());
+                    window = Some(current);
+                }
+            }
+        }
+
+        previous = *sample;
+    }
+
+    Ok(writer.bytes)
+}
+
+/// Decode `count` samples from a block produced by [`encode`].
+#[allow(clippy::cast_possible_wrap)]
+pub fn decode(data: &[u8], count: usize) -> Result<Vec<RateSample>, CodecError> {
+    let mut samples = Vec::with_capacity(count);
+    if count == 0 {
+        return Ok(samples);
+    }
+    let truncated = |decoded| CodecError::Truncated {
+        decoded,
+        expected: count,
+    };
+
+    let mut reader = BitReader::new(data);
+    let timestamp = reader.read(64).ok_or_else(|| truncated(0))? as i64;
+    let rate = f64::from_bits(reader.read(64).ok_or_else(|| truncated(0))?);
+    samples.push(RateSample { timestamp, rate });
+
+    let mut previous_delta = 0_i64;
+    let mut window: Option<XorWindow> = None;
+
+    while samples.len() < count {
+        let decoded = samples.len();
+        let previous = samples[decoded - 1];
+
+        let dod = read_delta_of_delta(&mut reader).ok_or_else(|| truncated(decoded))?;
+        let delta = previous_delta.wrapping_add(dod);
+        previous_delta = delta;
+
+        let bits = if reader.bit().ok_or_else(|| truncated(decoded))? {
+            if reader.bit().ok_or_else(|| truncated(decoded))? {
+                let leading = reader.read(5).ok_or_else(|| truncated(decoded))?;
+                let meaningful = reader.read(6).ok_or_else(|| truncated(decoded))? + 1;
+                // Both fit in 7 bits, so the conversions cannot fail
+                let leading = u32::try_from(leading).unwrap_or(0);
+                let meaningful = u32::try_from(meaningful).unwrap_or(64);
+                window = Some(XorWindow {
+                    leading,
+                    trailing: 64 - leading - meaningful,
+                });
+            }
+            let w = window.ok_or_else(|| truncated(decoded))?;
+            let xor = reader
+                .read(w.meaningful_bits())
+                .ok_or_else(|| truncated(decoded))?
+                << w.trailing;
+            previous.rate.to_bits() ^ xor
+        } else {
+            previous.rate.to_bits()
+        };
+
+        samples.push(RateSample {
+            timestamp: previous.timestamp.wrapping_add(delta),
+            rate: f64::from_bits(bits),
+        });
+    }
+
+    Ok(samples)
+}
+
+#[allow(clippy::cast_sign_loss)]
+fn write_delta_of_delta(writer: &mut BitWriter, dod: i64) {
+    if dod == 0 {
+        writer.bit(false);
+        return;
+    }
+    for (control, control_len, payload_len) in DOD_BUCKETS {
+        let limit = 1_i64 << (payload_len - 1);
+        if (-limit..limit).contains(&dod) {
+            writer.write(control, control_len);
+            writer.write(dod as u64 & ((1 << payload_len) - 1), payload_len);
+            return;
+        }
+    }
+    writer.write(0b1111, 4);
+    writer.write(dod as u64, 64);
+}
+
+#[allow(clippy::cast_possible_wrap)]
+fn read_delta_of_delta(reader: &mut BitReader<'_>) -> Option<i64> {
+    if !reader.bit()? {
+        return Some(0);
+    }
+    for (_, _, payload_len) in DOD_BUCKETS {
+        if !reader.bit()? {
+            let raw = reader.read(payload_len)?;
+            // Sign-extend the two's-complement payload
+            let shift = 64 - payload_len;
+            return Some(((raw << shift) as i64) >> shift);
+        }
+    }
+    reader.read(64).map(|raw| raw as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp: i64, rate: f64) -> RateSample {
+        RateSample { timestamp, rate }
+    }
+
+    #[test]
+    fn test_round_trip_irregular_samples() {
+        let samples = vec![
+            sample(1_700_000_000, 0.9213),
+            sample(1_700_000_060, 0.9213),
+            sample(1_700_000_120, 0.921_35),
+            sample(1_700_000_185, 0.9198),
+            sample(1_700_000_186, 1_250.5),
+            sample(1_700_003_000, 1e-9),
+            sample(1_800_000_000, 0.0),
+            sample(1_800_000_001, -3.25),
+        ];
+
+        let encoded = encode(&samples).unwrap();
+        assert_eq!(decode(&encoded, samples.len()).unwrap(), samples);
+    }
+
+    #[test]
+    fn test_regular_unchanged_samples_cost_two_bits() {
+        let samples: Vec<_> = (0..1_000)
+            .map(|i| sample(1_700_000_000 + i * 60, 1.0842))
+            .collect();
+
+        let encoded = encode(&samples).unwrap();
+        // 16 header bytes, then one bit for the timestamp and one for the value.
+        // The second sample pays for the initial 60s delta.
+        assert!(encoded.len() <= 16 + 2 + 999 * 2 / 8 + 1);
+        assert_eq!(decode(&encoded, samples.len()).unwrap(), samples);
+    }
+
+    #[test]
+    fn test_drifting_rates_compress_below_row_storage() {
+        let samples: Vec<_> = (0..1_440)
+            .map(|i| {
+                sample(
+                    1_700_000_000 + i * 60,
+                    ((i % 37) as f64).mul_add(0.0001, 0.92),
+                )
+            })
+            .collect();
+
+        let encoded = encode(&samples).unwrap();
+        // An (INTEGER, REAL) row is at least 16 bytes of payload alone
+        assert!(encoded.len() < samples.len() * 8);
+        assert_eq!(decode(&encoded, samples.len()).unwrap(), samples);
+    }
+
+    #[test]
+    fn test_rejects_out_of_order_and_truncated_blocks() {
+        assert_eq!(
+            encode(&[sample(10, 1.0), sample(10, 1.1)]),
+            Err(CodecError::OutOfOrder {
+                previous: 10,
+                next: 10
+            })
+        );
+
+        // Zero padding in the last byte can read as unchanged samples, so ask for
+        // well past what the block could hold
+        let encoded = encode(&[sample(10, 1.0), sample(20, 2.0)]).unwrap();
+        assert!(matches!(
+            decode(&encoded, 50),
+            Err(CodecError::Truncated { expected: 50, .. })
+        ));
+        assert!(decode(&encoded[..8], 1).is_err());
+    }
+}
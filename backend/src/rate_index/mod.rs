@@ -0,0 +1,25 @@
+//! Append-only time series of sampled corridor exchange rates.
+//!
+//! Samples are packed into compressed blocks (see [`codec`]) so a month of
+//! minute-level rates for a corridor is a few dozen rows instead of tens of
+//! thousands. Only the newest block of a corridor is ever rewritten; once it
+//! reaches its sample or time-span limit it is sealed. Reads decode the blocks
+//! overlapping the requested range and downsample them into OHLC buckets.
+
+pub mod codec;
+pub mod sampler;
+pub mod store;
+
+use serde::{Deserialize, Serialize};
+
+pub use sampler::RateSampler;
+pub use store::{RateBucket, RateIndexStore, RateSeries, RateSeriesQuery};
+
+/// A corridor exchange rate observed at a point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateSample {
+    /// Unix seconds.
+    pub timestamp: i64,
+    /// Units of destination asset per unit of source asset.
+    pub rate: f64,
+}
@@ -0,0 +1,150 @@
+use crate::cluster::{LeaderElection, ROLE_INGESTION};
+use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::services::price_feed::PriceFeedClient;
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+
+use super::{RateIndexStore, RateSample};
+
+const DEFAULT_SAMPLE_INTERVAL_SECS: u64 = 60;
+/// Corridors with hourly rollups in this trailing window are sampled.
+const ACTIVE_CORRIDOR_WINDOW_HOURS: i64 = 24;
+
+/// Periodically records the cross rate of every active corridor, derived from the
+/// USD prices of its two assets.
+pub struct RateSampler {
+    db: Arc<Database>,
+    store: Arc<RateIndexStore>,
+    price_feed: Arc<PriceFeedClient>,
+    leader: Option<Arc<LeaderElection>>,
+}
+
+impl RateSampler {
+    #[must_use]
+    pub const fn new(
+        db: Arc<Database>,
+        store: Arc<RateIndexStore>,
+        price_feed: Arc<PriceFeedClient>,
+    ) -> Self {
+        Self {
+            db,
+            store,
+            price_feed,
+            leader: None,
+        }
+    }
+
+    /// Only sample on the replica holding the ingestion lease, so each
+    /// corridor's samples come from one writer.
+    #[must_use]
+    pub fn with_leader_election(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    /// Sample every active corridor once; returns how many samples were stored.
+    pub async fn sample_once(&self) -> Result<usize> {
+        let since = Utc::now() - Duration::hours(ACTIVE_CORRIDOR_WINDOW_HOURS);
+        let corridor_keys: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT corridor_key FROM corridor_metrics_hourly WHERE hour_bucket >= ?",
+        )
+        .bind(since.to_rfc3339())
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let corridors: Vec<(String, &str, &str)> = corridor_keys
+            .iter()
+            .filter_map(|key| {
+                let (source, destination) = key.split_once("->")?;
+                Some((key.clone(), source, destination))
+            })
+            .collect();
+        if corridors.is_empty() {
+            return Ok(0);
+        }
+
+        let assets: Vec<String> = corridors
+            .iter()
+            .flat_map(|(_, source, destination)| [source.to_string(), destination.to_string()])
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let prices = self.price_feed.get_prices(&assets).await;
+        let timestamp = Utc::now().timestamp();
+
+        let mut stored = 0;
+        for (corridor_key, source, destination) in &corridors {
+            let Some(rate) = cross_rate(&prices, source, destination) else {
+                continue;
+            };
+            match self
+                .store
+                .append(corridor_key, &[RateSample { timestamp, rate }])
+                .await
+            {
+                Ok(()) => stored += 1,
+                Err(e) => log_event!(
+                    warn,
+                    Subsystem::Jobs,
+                    "rate_index.sample_store_failed",
+                    corridor_key,
+                    error = %e,
+                    "Failed to store rate sample"
+                ),
+            }
+        }
+
+        Ok(stored)
+    }
+
+    pub async fn start(self: Arc<Self>) {
+        let secs = std::env::var("RATE_SAMPLE_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_SAMPLE_INTERVAL_SECS);
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(secs));
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "rate_index.sampler_started",
+            interval_secs = secs,
+            "Corridor rate sampler started"
+        );
+
+        loop {
+            interval.tick().await;
+            if let Some(leader) = &self.leader {
+                if !leader.is_leader(ROLE_INGESTION) {
+                    continue;
+                }
+            }
+            match self.sample_once().await {
+                Ok(stored) => log_event!(
+                    debug,
+                    Subsystem::Jobs,
+                    "rate_index.sampling_completed",
+                    stored,
+                    "Corridor rate sampling complete"
+                ),
+                Err(e) => log_event!(
+                    error,
+                    Subsystem::Jobs,
+                    "rate_index.sampling_failed",
+                    error = %e,
+                    "Corridor rate sampling failed"
+                ),
+            }
+        }
+    }
+}
+
+/// Destination units per source unit, when both legs have a usable USD price.
+fn cross_rate(prices: &HashMap<String, f64>, source: &str, destination: &str) -> Option<f64> {
+    let source_usd = *prices.get(source)?;
+    let destination_usd = *prices.get(destination)?;
+    (source_usd > 0.0 && destination_usd > 0.0).then(|| source_usd / destination_usd)
+}
@@ -0,0 +1,473 @@
+use crate::database::Database;
+use crate::query_cost::{QueryBudget, QueryCost};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+use super::codec;
+use super::RateSample;
+
+/// A block is sealed once it holds this many samples (12 hours at one-minute sampling)...
+const BLOCK_MAX_SAMPLES: usize = 720;
+/// ...or once it spans this many seconds, so sparse corridors still get bounded blocks.
+const BLOCK_MAX_SPAN_SECS: i64 = 86_400;
+
+const DEFAULT_RANGE_SECS: i64 = 86_400;
+const DEFAULT_MAX_POINTS: i64 = 500;
+const MAX_POINTS_LIMIT: i64 = 10_000;
+/// Longest range a single series request may decode, overridable per deployment.
+const DEFAULT_MAX_RANGE_SECS: i64 = 366 * 86_400;
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RateSeriesQuery {
+    /// Range start in unix seconds, defaults to 24 hours before `to`.
+    pub from: Option<i64>,
+    /// Range end in unix seconds, defaults to now.
+    pub to: Option<i64>,
+    /// Bucket width in seconds. Derived from `max_points` when omitted.
+    pub step_seconds: Option<i64>,
+    /// Upper bound on returned buckets when `step_seconds` is omitted, defaults to 500.
+    pub max_points: Option<i64>,
+}
+
+/// Rates observed within one bucket, aligned to a multiple of the step.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RateBucket {
+    /// Bucket start in unix seconds.
+    pub timestamp: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub mean: f64,
+    pub samples: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RateSeries {
+    pub corridor_key: String,
+    pub from: i64,
+    pub to: i64,
+    pub step_seconds: i64,
+    /// Buckets without samples are omitted.
+    pub points: Vec<RateBucket>,
+}
+
+pub struct RateIndexStore {
+    db: Arc<Database>,
+    budget: QueryBudget,
+}
+
+impl RateIndexStore {
+    #[must_use]
+    pub fn new(db: Arc<Database>) -> Self {
+        let budget = QueryBudget::from_env(
+            "corridor-rates",
+            QueryBudget::new(MAX_POINTS_LIMIT, DEFAULT_MAX_RANGE_SECS),
+        );
+        Self { db, budget }
+    }
+
+    #[must_use]
+    pub const fn with_budget(mut self, budget: QueryBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Append samples for a corridor. Samples must be newer than anything already
+    /// stored for it; history is never rewritten.
+    pub async fn append(&self, corridor_key: &str, samples: &[RateSample]) -> Result<()> {
+        let Some(first) = samples.first() else {
+            return Ok(());
+        };
+
+        let mut tx = self.db.pool().begin().await?;
+
+        let last_ts: Option<i64> = sqlx::query_scalar(
+            "SELECT MAX(end_ts) FROM corridor_rate_blocks WHERE corridor_key = ?",
+        )
+        .bind(corridor_key)
+        .fetch_one(&mut *tx)
+        .await?;
+        if let Some(last_ts) = last_ts {
+            if first.timestamp <= last_ts {
+                bail!(
+                    "rate sample at {} is not newer than the last stored sample at {last_ts}",
+                    first.timestamp
+                );
+            }
+        }
+
+        let open = sqlx::query(
+            "SELECT id, data, sample_count FROM corridor_rate_blocks WHERE corridor_key = ? AND sealed = 0",
+        )
+        .bind(corridor_key)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let (open_id, mut block) = match open {
+            Some(row) => {
+                let data: Vec<u8> = row.get("data");
+                let count: i64 = row.get("sample_count");
+                let samples = codec::decode(&data, usize::try_from(count).unwrap_or(0))
+                    .with_context(|| format!("corrupt rate block for {corridor_key}"))?;
+                (Some(row.get::<i64, _>("id")), samples)
+            }
+            None => (None, Vec::new()),
+        };
+        // Validates ordering within the batch before anything is written
+        codec::encode(samples)?;
+
+        let mut open_id = open_id;
+        for sample in samples {
+            let span_exceeded = block
+                .first()
+                .is_some_and(|start| sample.timestamp - start.timestamp >= BLOCK_MAX_SPAN_SECS);
+            if span_exceeded {
+                write_block(&mut tx, corridor_key, open_id.take(), &block, true).await?;
+                block.clear();
+            }
+            block.push(*sample);
+            if block.len() >= BLOCK_MAX_SAMPLES {
+                write_block(&mut tx, corridor_key, open_id.take(), &block, true).await?;
+                block.clear();
+            }
+        }
+        if !block.is_empty() {
+            write_block(&mut tx, corridor_key, open_id, &block, false).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Raw samples for a corridor with `from <= timestamp <= to`, oldest first.
+    pub async fn query(&self, corridor_key: &str, from: i64, to: i64) -> Result<Vec<RateSample>> {
+        let rows = sqlx::query(
+            r"
+            SELECT data, sample_count FROM corridor_rate_blocks
+            WHERE corridor_key = ? AND end_ts >= ? AND start_ts <= ?
+            ORDER BY start_ts ASC
+            ",
+        )
+        .bind(corridor_key)
+        .bind(from)
+        .bind(to)
+        .fetch_all(self.db.pool())
+        .await?;
+
+        let mut samples = Vec::new();
+        for row in rows {
+            let data: Vec<u8> = row.get("data");
+            let count: i64 = row.get("sample_count");
+            let block = codec::decode(&data, usize::try_from(count).unwrap_or(0))
+                .with_context(|| format!("corrupt rate block for {corridor_key}"))?;
+            samples.extend(
+                block
+                    .into_iter()
+                    .filter(|s| (from..=to).contains(&s.timestamp)),
+            );
+        }
+        Ok(samples)
+    }
+
+    /// Downsampled series for charting. Long ranges stay cheap because only the
+    /// compressed blocks overlapping the range are read.
+    pub async fn series(&self, corridor_key: &str, query: &RateSeriesQuery) -> Result<RateSeries> {
+        let to = query.to.unwrap_or_else(|| Utc::now().timestamp());
+        let from = query.from.unwrap_or(to - DEFAULT_RANGE_SECS);
+        if from >= to {
+            bail!("from must be before to");
+        }
+        // Price an explicit step before resolve_step rejects it, so the caller gets a
+        // suggested step instead of a bare error
+        if let Some(step) = query.step_seconds.filter(|step| *step > 0) {
+            self.budget
+                .check(QueryCost::new(to - from, step, 1).with_granularity_param("step_seconds"))?;
+        }
+        let step_seconds = resolve_step(from, to, query.step_seconds, query.max_points)?;
+        self.budget
+            .check(QueryCost::new(to - from, step_seconds, 1))?;
+
+        let samples = self.query(corridor_key, from, to).await?;
+        Ok(RateSeries {
+            corridor_key: corridor_key.to_string(),
+            from,
+            to,
+            step_seconds,
+            points: downsample(&samples, step_seconds),
+        })
+    }
+}
+
+async fn write_block(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    corridor_key: &str,
+    id: Option<i64>,
+    samples: &[RateSample],
+    sealed: bool,
+) -> Result<()> {
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return Ok(());
+    };
+    let data = codec::encode(samples)?;
+    let count = i64::try_from(samples.len()).unwrap_or(i64::MAX);
+    let now = Utc::now().to_rfc3339();
+
+    if let Some(id) = id {
+        sqlx::query(
+            r"
+            UPDATE corridor_rate_blocks
+            SET end_ts = ?, sample_count = ?, data = ?, sealed = ?, updated_at = ?
+            WHERE id = ? AND sealed = 0
+            ",
+        )
+        .bind(last.timestamp)
+        .bind(count)
+        .bind(data)
+        .bind(sealed)
+        .bind(now)
+        .bind(id)
+        .execute(&mut **tx)
+        .await?;
+    } else {
+        sqlx::query(
+            r"
+            INSERT INTO corridor_rate_blocks
+                (corridor_key, start_ts, end_ts, sample_count, data, sealed, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ",
+        )
+        .bind(corridor_key)
+        .bind(first.timestamp)
+        .bind(last.timestamp)
+        .bind(count)
+        .bind(data)
+        .bind(sealed)
+        .bind(now)
+        .execute(&mut **tx)
+        .await?;
+    }
+    Ok(())
+}
+
+fn resolve_step(from: i64, to: i64, step: Option<i64>, max_points: Option<i64>) -> Result<i64> {
+    let span = to - from;
+    let step = match step {
+        Some(step) if step < 1 => bail!("step_seconds must be positive"),
+        Some(step) => step,
+        None => {
+            let max_points = max_points.unwrap_or(DEFAULT_MAX_POINTS);
+            if !(1..=MAX_POINTS_LIMIT).contains(&max_points) {
+                bail!("max_points must be between 1 and {MAX_POINTS_LIMIT}");
+            }
+            (span + max_points - 1) / max_points
+        }
+    };
+    if span / step > MAX_POINTS_LIMIT {
+        bail!("step_seconds too small for range; at most {MAX_POINTS_LIMIT} points are returned");
+    }
+    Ok(step.max(1))
+}
+
+/// Group time-ordered samples into buckets aligned to multiples of `step_seconds`.
+#[must_use]
+pub fn downsample(samples: &[RateSample], step_seconds: i64) -> Vec<RateBucket> {
+    let mut buckets: Vec<RateBucket> = Vec::new();
+    let mut sum = 0.0;
+
+    for sample in samples {
+        let start = sample.timestamp - sample.timestamp.rem_euclid(step_seconds);
+        match buckets.last_mut() {
+            Some(bucket) if bucket.timestamp == start => {
+                bucket.high = bucket.high.max(sample.rate);
+                bucket.low = bucket.low.min(sample.rate);
+                bucket.close = sample.rate;
+                bucket.samples += 1;
+                sum += sample.rate;
+                bucket.mean = sum / bucket.samples as f64;
+            }
+            _ => {
+                sum = sample.rate;
+                buckets.push(RateBucket {
+                    timestamp: start,
+                    open: sample.rate,
+                    high: sample.rate,
+                    low: sample.rate,
+                    close: sample.rate,
+                    mean: sample.rate,
+                    samples: 1,
+                });
+            }
+        }
+    }
+
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query_cost::QueryCostExceeded;
+    use crate::test_support::{self, migrations};
+
+    const CORRIDOR: &str = "USDC:GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN->EURC:GDHU6WRG4IEQXM5NZ4BMPKOXHW76MZM4Y2IEMFDVXBSDP6SJY4ITNPP2";
+
+    async fn setup_store() -> (RateIndexStore, sqlx::SqlitePool) {
+        let pool = test_support::sqlite_pool(&[migrations::CORRIDOR_RATE_INDEX]).await;
+        (
+            RateIndexStore::new(Arc::new(Database::new(pool.clone()))),
+            pool,
+        )
+    }
+
+    fn minutes(start: i64, count: i64) -> Vec<RateSample> {
+        (0..count)
+            .map(|i| RateSample {
+                timestamp: start + i * 60,
+                rate: ((i % 5) as f64).mul_add(0.001, 0.92),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_append_seals_full_blocks_and_queries_across_them() {
+        let (store, pool) = setup_store().await;
+        let samples = minutes(1_700_000_000, 1_000);
+
+        // Append in uneven batches so the open block is re-encoded several times
+        for chunk in samples.chunks(333) {
+            store.append(CORRIDOR, chunk).await.unwrap();
+        }
+
+        let blocks: Vec<(i64, bool)> = sqlx::query_as(
+            "SELECT sample_count, sealed FROM corridor_rate_blocks ORDER BY start_ts",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(blocks, vec![(720, true), (280, false)]);
+
+        let all = store.query(CORRIDOR, 0, i64::MAX).await.unwrap();
+        assert_eq!(all, samples);
+
+        let window = store
+            .query(CORRIDOR, samples[700].timestamp, samples[750].timestamp)
+            .await
+            .unwrap();
+        assert_eq!(window, samples[700..=750].to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_append_rejects_samples_older_than_stored_history() {
+        let (store, _pool) = setup_store().await;
+        store
+            .append(CORRIDOR, &minutes(1_700_000_000, 10))
+            .await
+            .unwrap();
+
+        let stale = minutes(1_700_000_000 + 9 * 60, 2);
+        assert!(store.append(CORRIDOR, &stale).await.is_err());
+        assert_eq!(store.query(CORRIDOR, 0, i64::MAX).await.unwrap().len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_sparse_samples_seal_on_span() {
+        let (store, pool) = setup_store().await;
+        let samples: Vec<_> = (0..4)
+            .map(|i| RateSample {
+                timestamp: 1_700_000_000 + i * 43_200,
+                rate: 1.0,
+            })
+            .collect();
+        store.append(CORRIDOR, &samples).await.unwrap();
+
+        let sealed: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM corridor_rate_blocks WHERE sealed = 1")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(sealed, 1);
+        assert_eq!(store.query(CORRIDOR, 0, i64::MAX).await.unwrap(), samples);
+    }
+
+    #[test]
+    fn test_downsample_builds_aligned_ohlc_buckets() {
+        let samples = [
+            RateSample {
+                timestamp: 3_590,
+                rate: 1.0,
+            },
+            RateSample {
+                timestamp: 3_600,
+                rate: 2.0,
+            },
+            RateSample {
+                timestamp: 3_700,
+                rate: 4.0,
+            },
+            RateSample {
+                timestamp: 7_199,
+                rate: 3.0,
+            },
+            RateSample {
+                timestamp: 10_800,
+                rate: 5.0,
+            },
+        ];
+
+        let buckets = downsample(&samples, 3_600);
+        assert_eq!(buckets.len(), 3);
+        assert_eq!(
+            buckets[1],
+            RateBucket {
+                timestamp: 3_600,
+                open: 2.0,
+                high: 4.0,
+                low: 2.0,
+                close: 3.0,
+                mean: 3.0,
+                samples: 3,
+            }
+        );
+        assert_eq!(buckets[0].timestamp, 0);
+        assert_eq!(buckets[2].timestamp, 10_800);
+    }
+
+    #[test]
+    fn test_resolve_step_from_max_points() {
+        assert_eq!(resolve_step(0, 86_400, None, None).unwrap(), 173);
+        assert_eq!(resolve_step(0, 86_400, None, Some(24)).unwrap(), 3_600);
+        assert_eq!(resolve_step(0, 86_400, Some(60), None).unwrap(), 60);
+        assert!(resolve_step(0, 86_400, Some(1), None).is_err());
+        assert!(resolve_step(0, 86_400, None, Some(0)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_series_rejects_queries_over_budget() {
+        let (store, _pool) = setup_store().await;
+        let store = store.with_budget(QueryBudget::new(1_000, 30 * 86_400));
+
+        let minutely_week = RateSeriesQuery {
+            from: Some(0),
+            to: Some(7 * 86_400),
+            step_seconds: Some(60),
+            max_points: None,
+        };
+        let err = store.series(CORRIDOR, &minutely_week).await.unwrap_err();
+        let exceeded = err.downcast_ref::<QueryCostExceeded>().unwrap();
+        assert_eq!(exceeded.suggested_granularity_seconds, Some(900));
+
+        let too_long = RateSeriesQuery {
+            from: Some(0),
+            to: Some(60 * 86_400),
+            step_seconds: None,
+            max_points: Some(100),
+        };
+        let err = store.series(CORRIDOR, &too_long).await.unwrap_err();
+        assert!(err.downcast_ref::<QueryCostExceeded>().is_some());
+    }
+}
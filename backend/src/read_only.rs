@@ -0,0 +1,154 @@
+//! Read-only deployment mode.
+//!
+//! With `READ_ONLY_MODE=true` the instance serves as a public analytics mirror: the
+//! router leaves out admin, authenticated and OAuth routes, and any request that is not
+//! `GET`, `HEAD` or `OPTIONS` is refused before it reaches a handler, except for the
+//! few `POST` endpoints in [`READ_ONLY_POST_PATHS`] that only read. Background work
+//! that needs credentials (contract submission, email and Telegram delivery, webhook
+//! dispatch) is not started, so a mirror can run without any secrets configured.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::ProblemDetails;
+
+const ALLOWED_METHODS: &str = "GET, HEAD, OPTIONS";
+
+/// `POST` routes that take their input in the body but never write, served on
+/// mirrors under every prefix the v1 router is mounted at
+pub const READ_ONLY_POST_PATHS: &[&str] = &["/verify/batch"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReadOnlyMode {
+    pub enabled: bool,
+}
+
+impl ReadOnlyMode {
+    /// Reads `READ_ONLY_MODE`; `true` or `1` enables it.
+    #[must_use]
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("READ_ONLY_MODE")
+            .is_ok_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "true" | "1"));
+        Self { enabled }
+    }
+}
+
+const fn is_safe(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+fn is_read_only_post(method: &Method, path: &str) -> bool {
+    if *method != Method::POST {
+        return false;
+    }
+    let path = path
+        .strip_prefix("/api/v1")
+        .or_else(|| path.strip_prefix("/api"))
+        .unwrap_or(path);
+    READ_ONLY_POST_PATHS.contains(&path)
+}
+
+/// Rejects mutating requests with 405 when read-only mode is on.
+pub async fn read_only_middleware(
+    State(mode): State<ReadOnlyMode>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !mode.enabled
+        || is_safe(request.method())
+        || is_read_only_post(request.method(), request.uri().path())
+    {
+        return next.run(request).await;
+    }
+
+    let mut response = ProblemDetails::from_status(
+        StatusCode::METHOD_NOT_ALLOWED,
+        "This deployment is a read-only mirror; only GET, HEAD and OPTIONS are served",
+    )
+    .into_response();
+    response
+        .headers_mut()
+        .insert(header::ALLOW, HeaderValue::from_static(ALLOWED_METHODS));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        middleware,
+        routing::{get, post},
+        Router,
+    };
+    use tower::ServiceExt;
+
+    fn app(enabled: bool) -> Router {
+        Router::new()
+            .route(
+                "/anchors",
+                get(|| async { "list" }).post(|| async { "created" }),
+            )
+            .route("/api/v1/verify/batch", post(|| async { "verified" }))
+            .route("/verify/batch", post(|| async { "verified" }))
+            .layer(middleware::from_fn_with_state(
+                ReadOnlyMode { enabled },
+                read_only_middleware,
+            ))
+    }
+
+    fn request(method: Method) -> Request {
+        request_to(method, "/anchors")
+    }
+
+    fn request_to(method: Method, uri: &str) -> Request {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reads_pass_and_writes_are_refused() {
+        let get = app(true).oneshot(request(Method::GET)).await.unwrap();
+        assert_eq!(get.status(), StatusCode::OK);
+
+        let post = app(true).oneshot(request(Method::POST)).await.unwrap();
+        assert_eq!(post.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(post.headers().get(header::ALLOW).unwrap(), ALLOWED_METHODS);
+
+        let delete = app(true).oneshot(request(Method::DELETE)).await.unwrap();
+        assert_eq!(delete.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_mode_passes_writes_through() {
+        let post = app(false).oneshot(request(Method::POST)).await.unwrap();
+        assert_eq!(post.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_posts_are_served() {
+        for uri in ["/api/v1/verify/batch", "/verify/batch"] {
+            let post = app(true)
+                .oneshot(request_to(Method::POST, uri))
+                .await
+                .unwrap();
+            assert_eq!(post.status(), StatusCode::OK);
+        }
+
+        // Only POST is exempt, and only on the listed paths
+        let put = app(true)
+            .oneshot(request_to(Method::PUT, "/verify/batch"))
+            .await
+            .unwrap();
+        assert_eq!(put.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert!(!is_read_only_post(&Method::POST, "/api/v1/anchors"));
+        assert!(is_read_only_post(&Method::POST, "/api/verify/batch"));
+    }
+}
@@ -0,0 +1,430 @@
+//! Liveness and readiness probes.
+//!
+//! Liveness only says the process is up and serving requests. Readiness runs a set
+//! of dependency checks, each under its own timeout, and reports a per-check
+//! breakdown so orchestrators only route traffic to instances that can serve it:
+//!
+//! - `database`: the pool can execute a query
+//! - `migrations`: every embedded migration has been applied successfully
+//! - `rpc`: the Stellar RPC endpoint answers `getHealth`
+//! - `ingestion`: the ledger cursor is within the configured lag of the network tip
+//! - `price_cache`: the price feed cache has been warmed
+//! - `redis`: the shared cache answers `PING` (reported, but not required)
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::migrate::Migrator;
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use crate::cache::CacheManager;
+use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::rpc::StellarRpcClient;
+use crate::services::price_feed::PriceFeedClient;
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Timeouts and thresholds for the readiness checks.
+#[derive(Debug, Clone)]
+pub struct ReadinessConfig {
+    /// Upper bound for each individual check.
+    pub check_timeout: Duration,
+    /// Maximum number of ledgers ingestion may trail the network tip. `0` disables
+    /// the ingestion check, e.g. for read-only replicas that do not ingest.
+    pub max_ingestion_lag_ledgers: u64,
+}
+
+impl Default for ReadinessConfig {
+    fn default() -> Self {
+        Self {
+            check_timeout: Duration::from_secs(2),
+            max_ingestion_lag_ledgers: 120,
+        }
+    }
+}
+
+impl ReadinessConfig {
+    /// Reads `READINESS_CHECK_TIMEOUT_MS` and `READINESS_MAX_INGESTION_LAG_LEDGERS`.
+    #[must_use]
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let check_timeout = std::env::var("READINESS_CHECK_TIMEOUT_MS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .filter(|ms| *ms > 0)
+            .map_or(default.check_timeout, Duration::from_millis);
+        let max_ingestion_lag_ledgers = std::env::var("READINESS_MAX_INGESTION_LAG_LEDGERS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(default.max_ingestion_lag_ledgers);
+
+        Self {
+            check_timeout,
+            max_ingestion_lag_ledgers,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    Timeout,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    /// Whether a failure of this check makes the instance not ready.
+    pub required: bool,
+    pub duration_ms: u64,
+    pub message: Option<String>,
+}
+
+impl CheckResult {
+    const fn is_blocking(&self) -> bool {
+        self.required && matches!(self.status, CheckStatus::Fail | CheckStatus::Timeout)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReadinessReport {
+    pub ready: bool,
+    pub checked_at: DateTime<Utc>,
+    pub checks: Vec<CheckResult>,
+}
+
+impl ReadinessReport {
+    #[must_use]
+    pub fn from_checks(checks: Vec<CheckResult>) -> Self {
+        Self {
+            ready: !checks.iter().any(CheckResult::is_blocking),
+            checked_at: Utc::now(),
+            checks,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LivenessReport {
+    pub status: &'static str,
+    pub uptime_seconds: u64,
+}
+
+/// Outcome of a check body before timing is attached.
+type Outcome = Result<Option<String>, String>;
+
+pub struct ReadinessProbe {
+    db: Arc<Database>,
+    cache: Arc<CacheManager>,
+    rpc: Arc<StellarRpcClient>,
+    price_feed: Arc<PriceFeedClient>,
+    config: ReadinessConfig,
+    started_at: Instant,
+    last_ready: AtomicBool,
+}
+
+impl ReadinessProbe {
+    #[must_use]
+    pub fn new(
+        db: Arc<Database>,
+        cache: Arc<CacheManager>,
+        rpc: Arc<StellarRpcClient>,
+        price_feed: Arc<PriceFeedClient>,
+    ) -> Self {
+        Self {
+            db,
+            cache,
+            rpc,
+            price_feed,
+            config: ReadinessConfig::default(),
+            started_at: Instant::now(),
+            last_ready: AtomicBool::new(false),
+        }
+    }
+
+    #[must_use]
+    pub const fn with_config(mut self, config: ReadinessConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    #[must_use]
+    pub fn liveness(&self) -> LivenessReport {
+        LivenessReport {
+            status: "alive",
+            uptime_seconds: self.started_at.elapsed().as_secs(),
+        }
+    }
+
+    /// Run every check concurrently and collect the breakdown.
+    pub async fn readiness(&self) -> ReadinessReport {
+        let timeout = self.config.check_timeout;
+        let (database, migrations, rpc, price_cache, redis) = tokio::join!(
+            run_check("database", true, timeout, self.check_database()),
+            run_check("migrations", true, timeout, self.check_migrations()),
+            self.check_rpc_and_ingestion(),
+            run_check("price_cache", true, timeout, self.check_price_cache()),
+            run_check("redis", false, timeout, self.check_redis()),
+        );
+        let (rpc, ingestion) = rpc;
+
+        let report = ReadinessReport::from_checks(vec![
+            database,
+            migrations,
+            rpc,
+            ingestion,
+            price_cache,
+            redis,
+        ]);
+
+        if self.last_ready.swap(report.ready, Ordering::Relaxed) != report.ready {
+            if report.ready {
+                log_event!(
+                    info,
+                    Subsystem::Api,
+                    "readiness.ready",
+                    "Instance is ready to receive traffic"
+                );
+            } else {
+                let failing: Vec<_> = report
+                    .checks
+                    .iter()
+                    .filter(|c| c.is_blocking())
+                    .map(|c| c.name)
+                    .collect();
+                log_event!(
+                    warn,
+                    Subsystem::Api,
+                    "readiness.not_ready",
+                    ?failing,
+                    "Instance is no longer ready"
+                );
+            }
+        }
+
+        report
+    }
+
+    async fn check_database(&self) -> Outcome {
+        sqlx::query("SELECT 1")
+            .execute(self.db.pool())
+            .await
+            .map(|_| None)
+            .map_err(|e| format!("Database query failed: {e}"))
+    }
+
+    async fn check_migrations(&self) -> Outcome {
+        let applied: Vec<i64> =
+            sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success = 1")
+                .fetch_all(self.db.pool())
+                .await
+                .map_err(|e| format!("Cannot read migration history: {e}"))?;
+
+        let pending = pending_migrations(MIGRATOR.iter().map(|m| m.version), &applied);
+        if pending.is_empty() {
+            Ok(Some(format!("{} migrations applied", applied.len())))
+        } else {
+            Err(format!("Pending migrations: {pending:?}"))
+        }
+    }
+
+    /// The ingestion check compares against the tip reported by the RPC check, so
+    /// both share one `getHealth` call.
+    async fn check_rpc_and_ingestion(&self) -> (CheckResult, CheckResult) {
+        let timeout = self.config.check_timeout;
+        let start = Instant::now();
+        let network_tip = match tokio::time::timeout(timeout, self.rpc.check_health()).await {
+            Ok(Ok(health)) => Ok(health.latest_ledger),
+            Ok(Err(e)) => Err((CheckStatus::Fail, format!("RPC health check failed: {e}"))),
+            Err(_) => Err((CheckStatus::Timeout, timeout_message(timeout))),
+        };
+        let rpc = match &network_tip {
+            Ok(tip) => finished("rpc", true, start, Ok(Some(format!("latest ledger {tip}")))),
+            Err((status, message)) => CheckResult {
+                name: "rpc",
+                status: *status,
+                required: true,
+                duration_ms: elapsed_ms(start),
+                message: Some(message.clone()),
+            },
+        };
+
+        let max_lag = self.config.max_ingestion_lag_ledgers;
+        let ingestion = if max_lag == 0 {
+            skipped("ingestion", "Ingestion lag check disabled")
+        } else if let Ok(tip) = network_tip {
+            run_check("ingestion", true, timeout, async {
+                let cursor: Option<i64> = sqlx::query_scalar(
+                    "SELECT last_ledger_sequence FROM ingestion_cursor WHERE id = 1",
+                )
+                .fetch_optional(self.db.pool())
+                .await
+                .map_err(|e| format!("Cannot read ingestion cursor: {e}"))?;
+                evaluate_ingestion_lag(cursor, tip, max_lag)
+            })
+            .await
+        } else {
+            skipped("ingestion", "Network tip unknown, RPC check failed")
+        };
+
+        (rpc, ingestion)
+    }
+
+    async fn check_price_cache(&self) -> Outcome {
+        // Expired entries are refreshed on read, so one completed warm-up is enough
+        let (total, fresh) = self.price_feed.cache_stats().await;
+        if total > 0 {
+            Ok(Some(format!("{fresh} of {total} cached prices fresh")))
+        } else {
+            Err("Price cache not warmed yet".to_string())
+        }
+    }
+
+    async fn check_redis(&self) -> Outcome {
+        self.cache
+            .ping()
+            .await
+            .map(|()| None)
+            .map_err(|e| format!("Redis unavailable, serving without shared cache: {e}"))
+    }
+}
+
+/// Versions that are embedded in the binary but not recorded as applied.
+fn pending_migrations(expected: impl IntoIterator<Item = i64>, applied: &[i64]) -> Vec<i64> {
+    let applied: BTreeSet<i64> = applied.iter().copied().collect();
+    expected
+        .into_iter()
+        .filter(|version| !applied.contains(version))
+        .collect()
+}
+
+fn evaluate_ingestion_lag(cursor: Option<i64>, network_tip: u64, max_lag: u64) -> Outcome {
+    let Some(cursor) = cursor else {
+        return Err("Ingestion has not recorded a ledger yet".to_string());
+    };
+    let cursor = u64::try_from(cursor).unwrap_or_default();
+    let lag = network_tip.saturating_sub(cursor);
+    let message = format!("cursor {cursor}, network tip {network_tip}, lag {lag} ledgers");
+    if lag <= max_lag {
+        Ok(Some(message))
+    } else {
+        Err(format!("{message} exceeds {max_lag}"))
+    }
+}
+
+async fn run_check(
+    name: &'static str,
+    required: bool,
+    timeout: Duration,
+    check: impl Future<Output = Outcome>,
+) -> CheckResult {
+    let start = Instant::now();
+    tokio::time::timeout(timeout, check).await.map_or_else(
+        |_| CheckResult {
+            name,
+            status: CheckStatus::Timeout,
+            required,
+            duration_ms: elapsed_ms(start),
+            message: Some(timeout_message(timeout)),
+        },
+        |outcome| finished(name, required, start, outcome),
+    )
+}
+
+fn finished(name: &'static str, required: bool, start: Instant, outcome: Outcome) -> CheckResult {
+    let (status, message) = match outcome {
+        Ok(message) => (CheckStatus::Pass, message),
+        Err(message) => (CheckStatus::Fail, Some(message)),
+    };
+    CheckResult {
+        name,
+        status,
+        required,
+        duration_ms: elapsed_ms(start),
+        message,
+    }
+}
+
+fn skipped(name: &'static str, reason: &str) -> CheckResult {
+    CheckResult {
+        name,
+        status: CheckStatus::Skipped,
+        required: false,
+        duration_ms: 0,
+        message: Some(reason.to_string()),
+    }
+}
+
+fn elapsed_ms(start: Instant) -> u64 {
+    u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+fn timeout_message(timeout: Duration) -> String {
+    format!("Check did not complete within {}ms", timeout.as_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_migrations() {
+        assert_eq!(pending_migrations([1, 2, 3], &[1, 2, 3]), Vec::<i64>::new());
+        assert_eq!(pending_migrations([1, 2, 3, 4], &[1, 3]), vec![2, 4]);
+    }
+
+    #[test]
+    fn test_ingestion_lag_threshold() {
+        assert!(evaluate_ingestion_lag(Some(1_000), 1_100, 120).is_ok());
+        assert!(evaluate_ingestion_lag(Some(1_000), 1_121, 120).is_err());
+        // A cursor ahead of a lagging RPC node is not a failure
+        assert!(evaluate_ingestion_lag(Some(1_200), 1_100, 120).is_ok());
+        assert!(evaluate_ingestion_lag(None, 1_100, 120).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_slow_check_times_out() {
+        let result = run_check("slow", true, Duration::from_millis(10), async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(None)
+        })
+        .await;
+
+        assert_eq!(result.status, CheckStatus::Timeout);
+        assert!(result.duration_ms < 5_000);
+    }
+
+    #[test]
+    fn test_only_required_failures_block_readiness() {
+        let check = |name, status, required| CheckResult {
+            name,
+            status,
+            required,
+            duration_ms: 1,
+            message: None,
+        };
+
+        let report = ReadinessReport::from_checks(vec![
+            check("database", CheckStatus::Pass, true),
+            check("redis", CheckStatus::Fail, false),
+            check("ingestion", CheckStatus::Skipped, false),
+        ]);
+        assert!(report.ready);
+
+        let report = ReadinessReport::from_checks(vec![
+            check("database", CheckStatus::Pass, true),
+            check("rpc", CheckStatus::Timeout, true),
+        ]);
+        assert!(!report.ready);
+    }
+}
@@ -0,0 +1,517 @@
+//! Replay Benchmark Mode
+//!
+//! Replays a fixed synthetic event corpus through the real replay engine against a
+//! private in-memory database and reports throughput, allocation counts and
+//! per-processor timing. Runs are stored in `replay_bench_runs` and compared with the
+//! latest run from a different release, so regressions show up as a percentage.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{sqlite::SqlitePoolOptions, Row, SqlitePool};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+use tokio::sync::RwLock;
+
+use super::{
+    checkpoint::CheckpointManager,
+    config::{ReplayConfig, ReplayMode, ReplayRange},
+    engine::ReplayEngine,
+    event_processor::{
+        CompositeEventProcessor, EventProcessor, ProcessingContext, ProcessingResult,
+        SnapshotEventProcessor,
+    },
+    registry::ProcessorSubscription,
+    state_builder::StateBuilder,
+    storage::{EventStorage, ReplayStorage},
+    ContractEvent, ReplayStatus,
+};
+use crate::network::StellarNetwork;
+
+/// First ledger of the synthetic corpus.
+const CORPUS_START_LEDGER: u64 = 1_000_000;
+/// Every Nth event re-submits an earlier epoch to exercise the idempotent skip path.
+const DUPLICATE_EVERY: u64 = 10;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Global allocator wrapper counting allocations. Binaries opt in with
+/// `#[global_allocator] static ALLOC: CountingAllocator = CountingAllocator;`.
+pub struct CountingAllocator;
+
+fn record_allocation(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED_BYTES.fetch_add(u64::try_from(size).unwrap_or(u64::MAX), Ordering::Relaxed);
+}
+
+// SAFETY: every call is forwarded unchanged to the system allocator; the wrapper only
+// updates atomic counters.
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record_allocation(layout.size());
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record_allocation(layout.size());
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record_allocation(new_size);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+/// Allocation counters between two points of a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllocationStats {
+    pub allocations: u64,
+    pub allocated_bytes: u64,
+}
+
+impl AllocationStats {
+    fn snapshot() -> Self {
+        Self {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+        }
+    }
+
+    const fn since(self, before: Self) -> Self {
+        Self {
+            allocations: self.allocations.saturating_sub(before.allocations),
+            allocated_bytes: self.allocated_bytes.saturating_sub(before.allocated_bytes),
+        }
+    }
+}
+
+/// Benchmark parameters. Runs are only compared with runs of the same shape.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchConfig {
+    pub events: u64,
+    pub batch_size: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            events: 10_000,
+            batch_size: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessorTiming {
+    pub name: String,
+    pub calls: u64,
+    pub total_ms: f64,
+    pub mean_us: f64,
+    pub max_us: f64,
+    /// Time spent in `is_processed`/`mark_processed`.
+    pub idempotency_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchComparison {
+    pub baseline_version: String,
+    pub baseline_events_per_sec: f64,
+    /// Positive when this run is faster than the baseline.
+    pub change_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BenchReport {
+    pub version: String,
+    pub git_sha: Option<String>,
+    pub corpus_events: u64,
+    pub batch_size: usize,
+    pub events_processed: u64,
+    pub events_failed: u64,
+    pub duration_ms: f64,
+    pub events_per_sec: f64,
+    /// `None` unless the binary installed [`CountingAllocator`].
+    pub allocations: Option<AllocationStats>,
+    pub processors: Vec<ProcessorTiming>,
+    pub baseline: Option<BenchComparison>,
+}
+
+/// The fixed corpus: one `snapshot_submitted` event per ledger, with periodic
+/// re-submissions of earlier epochs. Identical for a given size across releases.
+#[must_use]
+pub fn synthetic_corpus(events: u64) -> Vec<ContractEvent> {
+    let base = DateTime::<Utc>::UNIX_EPOCH + Duration::seconds(1_700_000_000);
+    (0..events)
+        .map(|i| {
+            let epoch = if i % DUPLICATE_EVERY == DUPLICATE_EVERY - 1 {
+                i / 2
+            } else {
+                i
+            };
+            let hash = hex::encode(Sha256::digest(format!("bench-snapshot-{epoch}")));
+            let ledger_sequence = CORPUS_START_LEDGER + i;
+            ContractEvent {
+                id: format!("bench-{i}"),
+                ledger_sequence,
+                transaction_hash: format!("{:064x}", i + 1),
+                tx_index: 0,
+                event_index: 0,
+                contract_id: "CBENCHSNAPSHOTCONTRACT".to_string(),
+                event_type: "snapshot_submitted".to_string(),
+                data: serde_json::json!({ "epoch": epoch, "hash": hash }),
+                timestamp: base + Duration::seconds(i64::try_from(i).unwrap_or(i64::MAX)),
+                network: StellarNetwork::Testnet.to_string(),
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Default)]
+struct TimingAccumulator {
+    calls: u64,
+    total_us: f64,
+    max_us: f64,
+    idempotency_us: f64,
+}
+
+/// Per-processor timings collected by [`TimedProcessor`].
+#[derive(Debug, Default)]
+pub struct ProcessorTimings {
+    by_name: Mutex<BTreeMap<String, TimingAccumulator>>,
+}
+
+impl ProcessorTimings {
+    fn record(&self, name: &str, started: Instant, idempotency: bool) {
+        let micros = started.elapsed().as_secs_f64() * 1_000_000.0;
+        let mut by_name = self.by_name.lock().unwrap_or_else(PoisonError::into_inner);
+        let entry = by_name.entry(name.to_string()).or_default();
+        if idempotency {
+            entry.idempotency_us += micros;
+        } else {
+            entry.calls += 1;
+            entry.total_us += micros;
+            entry.max_us = entry.max_us.max(micros);
+        }
+        drop(by_name);
+    }
+
+    #[must_use]
+    pub fn summary(&self) -> Vec<ProcessorTiming> {
+        let by_name = self.by_name.lock().unwrap_or_else(PoisonError::into_inner);
+        by_name
+            .iter()
+            .map(|(name, acc)| ProcessorTiming {
+                name: name.clone(),
+                calls: acc.calls,
+                total_ms: acc.total_us / 1_000.0,
+                mean_us: if acc.calls == 0 {
+                    0.0
+                } else {
+                    acc.total_us / acc.calls as f64
+                },
+                max_us: acc.max_us,
+                idempotency_ms: acc.idempotency_us / 1_000.0,
+            })
+            .collect()
+    }
+}
+
+/// Wraps a processor and records how long each of its calls takes.
+pub struct TimedProcessor {
+    inner: Arc<dyn EventProcessor>,
+    timings: Arc<ProcessorTimings>,
+}
+
+impl TimedProcessor {
+    #[must_use]
+    pub fn new(inner: Arc<dyn EventProcessor>, timings: Arc<ProcessorTimings>) -> Self {
+        Self { inner, timings }
+    }
+}
+
+#[async_trait]
+impl EventProcessor for TimedProcessor {
+    async fn process_event(
+        &self,
+        event: &ContractEvent,
+        context: &ProcessingContext,
+    ) -> Result<ProcessingResult> {
+        let started = Instant::now();
+        let result = self.inner.process_event(event, context).await;
+        self.timings.record(self.inner.name(), started, false);
+        result
+    }
+
+    async fn is_processed(&self, event: &ContractEvent) -> Result<bool> {
+        let started = Instant::now();
+        let result = self.inner.is_processed(event).await;
+        self.timings.record(self.inner.name(), started, true);
+        result
+    }
+
+    async fn mark_processed(&self, event: &ContractEvent) -> Result<()> {
+        let started = Instant::now();
+        let result = self.inner.mark_processed(event).await;
+        self.timings.record(self.inner.name(), started, true);
+        result
+    }
+
+    fn validate_event(&self, event: &ContractEvent) -> Result<()> {
+        self.inner.validate_event(event)
+    }
+
+    fn subscriptions(&self) -> Vec<ProcessorSubscription> {
+        self.inner.subscriptions()
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+}
+
+/// Migrations that create the replay tables, in the order they must run. The
+/// benchmark applies them to its in-memory database.
+pub const REPLAY_MIGRATIONS: &[&str] = &[
+    include_str!("../../migrations/022_create_replay_tables.sql"),
+    include_str!("../../migrations/046_add_replay_session_reports.sql"),
+    include_str!("../../migrations/047_create_replay_dead_letter.sql"),
+    include_str!("../../migrations/048_add_contract_event_positions.sql"),
+    include_str!("../../migrations/049_add_replay_session_integrity.sql"),
+    include_str!("../../migrations/050_add_replay_network.sql"),
+    include_str!("../../migrations/051_create_replay_state_entries.sql"),
+    include_str!("../../migrations/052_add_replay_state_scope.sql"),
+];
+
+/// Replay the synthetic corpus and measure the engine. Corpus loading is excluded
+/// from the timings.
+pub async fn run_bench(config: BenchConfig) -> Result<BenchReport> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .context("Failed to open benchmark database")?;
+    for migration in REPLAY_MIGRATIONS {
+        sqlx::raw_sql(migration)
+            .execute(&pool)
+            .await
+            .context("Failed to create replay tables for benchmark")?;
+    }
+
+    let event_storage = Arc::new(EventStorage::new(pool.clone()));
+    for event in synthetic_corpus(config.events) {
+        event_storage.store_event(&event).await?;
+    }
+
+    let timings = Arc::new(ProcessorTimings::default());
+    let processor = CompositeEventProcessor::new().add_processor(Arc::new(TimedProcessor::new(
+        Arc::new(SnapshotEventProcessor::new(pool.clone())),
+        Arc::clone(&timings),
+    )));
+
+    let replay_config = ReplayConfig::new()
+        .with_mode(ReplayMode::Full)
+        .with_range(ReplayRange::FromTo {
+            start: CORPUS_START_LEDGER,
+            end: CORPUS_START_LEDGER + config.events.saturating_sub(1),
+        })
+        .with_batch_size(config.batch_size)
+        .with_network(StellarNetwork::Testnet);
+    let engine = ReplayEngine::new(
+        replay_config,
+        event_storage,
+        Arc::new(ReplayStorage::new(pool.clone())),
+        Arc::new(CheckpointManager::new(pool.clone())),
+        Arc::new(processor),
+        Arc::new(RwLock::new(StateBuilder::new(
+            pool,
+            StellarNetwork::Testnet,
+        ))),
+    )?;
+
+    let allocations_before = AllocationStats::snapshot();
+    let started = Instant::now();
+    let metadata = engine.start().await?;
+    let elapsed = started.elapsed().as_secs_f64();
+    let allocations = AllocationStats::snapshot().since(allocations_before);
+
+    let (events_processed, events_failed) = match metadata.status {
+        ReplayStatus::Completed {
+            events_processed,
+            events_failed,
+            ..
+        } => (events_processed, events_failed),
+        ReplayStatus::Failed { error, .. } => anyhow::bail!("Benchmark replay failed: {error}"),
+        other => anyhow::bail!("Benchmark replay ended in unexpected state: {other}"),
+    };
+
+    Ok(BenchReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_sha: std::env::var("GIT_SHA").ok(),
+        corpus_events: config.events,
+        batch_size: config.batch_size,
+        events_processed,
+        events_failed,
+        duration_ms: elapsed * 1_000.0,
+        events_per_sec: if elapsed > 0.0 {
+            config.events as f64 / elapsed
+        } else {
+            0.0
+        },
+        // Nothing was counted: the running binary did not install the allocator.
+        allocations: (allocations.allocations > 0).then_some(allocations),
+        processors: timings.summary(),
+        baseline: None,
+    })
+}
+
+/// Persistence for benchmark runs.
+pub struct BenchStore {
+    pool: SqlitePool,
+}
+
+impl BenchStore {
+    #[must_use]
+    pub const fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Latest run with the same corpus from a different release.
+    pub async fn baseline_for(&self, report: &BenchReport) -> Result<Option<BenchComparison>> {
+        let row = sqlx::query(
+            r"
+            SELECT version, events_per_sec FROM replay_bench_runs
+            WHERE corpus_events = ? AND batch_size = ? AND version != ?
+            ORDER BY created_at DESC, id DESC
+            LIMIT 1
+            ",
+        )
+        .bind(i64::try_from(report.corpus_events).unwrap_or(i64::MAX))
+        .bind(i64::try_from(report.batch_size).unwrap_or(i64::MAX))
+        .bind(&report.version)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to load replay benchmark baseline")?;
+
+        Ok(row.map(|row| {
+            let baseline_events_per_sec: f64 = row.get("events_per_sec");
+            BenchComparison {
+                baseline_version: row.get("version"),
+                baseline_events_per_sec,
+                change_pct: if baseline_events_per_sec > 0.0 {
+                    (report.events_per_sec - baseline_events_per_sec) / baseline_events_per_sec
+                        * 100.0
+                } else {
+                    0.0
+                },
+            }
+        }))
+    }
+
+    pub async fn save(&self, report: &BenchReport) -> Result<()> {
+        sqlx::query(
+            r"
+            INSERT INTO replay_bench_runs (
+                version, git_sha, corpus_events, batch_size, duration_ms, events_per_sec,
+                allocations, allocated_bytes, processor_timings, created_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ",
+        )
+        .bind(&report.version)
+        .bind(&report.git_sha)
+        .bind(i64::try_from(report.corpus_events).unwrap_or(i64::MAX))
+        .bind(i64::try_from(report.batch_size).unwrap_or(i64::MAX))
+        .bind(report.duration_ms)
+        .bind(report.events_per_sec)
+        .bind(
+            report
+                .allocations
+                .map(|a| i64::try_from(a.allocations).unwrap_or(i64::MAX)),
+        )
+        .bind(
+            report
+                .allocations
+                .map(|a| i64::try_from(a.allocated_bytes).unwrap_or(i64::MAX)),
+        )
+        .bind(serde_json::to_string(&report.processors)?)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .context("Failed to store replay benchmark run")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations};
+
+    #[test]
+    fn test_corpus_is_deterministic() {
+        let a = synthetic_corpus(25);
+        let b = synthetic_corpus(25);
+        assert_eq!(a, b);
+        assert_eq!(a[0].ledger_sequence, CORPUS_START_LEDGER);
+        // The tenth event re-submits an earlier epoch.
+        assert_eq!(a[9].data["epoch"], 4);
+    }
+
+    #[tokio::test]
+    async fn test_bench_replays_whole_corpus() {
+        let report = run_bench(BenchConfig {
+            events: 50,
+            batch_size: 20,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(report.events_processed + report.events_failed, 50);
+        assert_eq!(report.events_failed, 0);
+        assert_eq!(report.processors.len(), 1);
+        assert_eq!(report.processors[0].calls, 50);
+        assert!(report.events_per_sec > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_baseline_comes_from_previous_release() {
+        let pool = test_support::sqlite_pool(&[migrations::REPLAY_BENCH_RUNS]).await;
+        let store = BenchStore::new(pool);
+
+        let mut report = BenchReport {
+            version: "0.9.0".to_string(),
+            git_sha: None,
+            corpus_events: 100,
+            batch_size: 10,
+            events_processed: 100,
+            events_failed: 0,
+            duration_ms: 100.0,
+            events_per_sec: 1_000.0,
+            allocations: None,
+            processors: Vec::new(),
+            baseline: None,
+        };
+        store.save(&report).await.unwrap();
+
+        report.version = "1.0.0".to_string();
+        report.events_per_sec = 800.0;
+        let comparison = store.baseline_for(&report).await.unwrap().unwrap();
+        assert_eq!(comparison.baseline_version, "0.9.0");
+        assert!((comparison.change_pct + 20.0).abs() < 1e-9);
+
+        // Runs of the same release are not a baseline.
+        report.version = "0.9.0".to_string();
+        assert!(store.baseline_for(&report).await.unwrap().is_none());
+    }
+}
@@ -2,13 +2,41 @@
 //!
 //! Provides checkpoint functionality for saving and resuming replay progress.
 //! Checkpoints enable recovery from failures and allow pausing/resuming replays.
+//! A session's latest checkpoint can be exported as a [`CheckpointBundle`] and
+//! imported into another environment to reproduce the replay from there.
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
 use std::collections::HashMap;
-use tracing::{debug, info};
+
+use super::state_builder::{ApplicationState, StateBuilder};
+use super::{ReplayMetadata, ReplayStorage};
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::network::StellarNetwork;
+
+/// Version of the [`CheckpointBundle`] format written by this build. Version 1
+/// bundles did not record the checkpoint's network and are not accepted.
+pub const BUNDLE_VERSION: u32 = 2;
+
+/// Checkpoint metadata key set when the state snapshot leaves out the entries
+/// the replay moved to `replay_state_entries` to stay within its memory budget
+pub const EXTERNAL_STATE_METADATA: &str = "state_entries";
+
+/// `replay_checkpoints` columns, in the order they are selected
+type CheckpointRow = (
+    String,
+    String,
+    String,
+    i64,
+    i64,
+    i64,
+    String,
+    String,
+    DateTime<Utc>,
+);
 
 /// Represents a checkpoint in the replay process
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -17,6 +45,9 @@ pub struct Checkpoint {
     pub id: String,
     /// Replay session ID
     pub session_id: String,
+    /// Network of the session
+    #[serde(default)]
+    pub network: StellarNetwork,
     /// Last processed ledger
     pub last_ledger: u64,
     /// Total events processed
@@ -38,6 +69,7 @@ impl Checkpoint {
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             session_id,
+            network: StellarNetwork::default(),
             last_ledger,
             events_processed: 0,
             events_failed: 0,
@@ -47,6 +79,13 @@ impl Checkpoint {
         }
     }
 
+    /// Set the network of the session
+    #[must_use]
+    pub const fn with_network(mut self, network: StellarNetwork) -> Self {
+        self.network = network;
+        self
+    }
+
     /// Add metadata to checkpoint
     #[must_use]
     pub fn with_metadata(mut self, key: String, value: String) -> Self {
@@ -70,6 +109,28 @@ impl Checkpoint {
     }
 }
 
+/// An event recorded in `processed_events`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProcessedEvent {
+    pub event_id: String,
+    pub ledger_sequence: u64,
+}
+
+/// Everything needed to resume a session in another environment: the session
+/// itself, its latest checkpoint with the state snapshot, and the events
+/// already processed up to the checkpoint's ledger.
+///
+/// Events are not included; the environment importing the bundle reads them
+/// from its own event source, e.g. Soroban RPC.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointBundle {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub session: ReplayMetadata,
+    pub checkpoint: Checkpoint,
+    pub processed_events: Vec<ProcessedEvent>,
+}
+
 /// Manages checkpoint storage and retrieval
 pub struct CheckpointManager {
     pool: SqlitePool,
@@ -84,9 +145,14 @@ impl CheckpointManager {
 
     /// Save a checkpoint
     pub async fn save(&self, checkpoint: &Checkpoint) -> Result<()> {
-        info!(
-            "Saving checkpoint {} for session {} at ledger {}",
-            checkpoint.id, checkpoint.session_id, checkpoint.last_ledger
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.checkpoint_saving",
+            checkpoint_id = %checkpoint.id,
+            session_id = %checkpoint.session_id,
+            ledger = checkpoint.last_ledger,
+            "Saving checkpoint"
         );
 
         let metadata_json = serde_json::to_string(&checkpoint.metadata)?;
@@ -95,10 +161,10 @@ impl CheckpointManager {
         sqlx::query(
             r"
             INSERT INTO replay_checkpoints (
-                id, session_id, last_ledger, events_processed, events_failed,
+                id, session_id, network, last_ledger, events_processed, events_failed,
                 state_snapshot, metadata, created_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             ON CONFLICT (id) DO UPDATE SET
                 last_ledger = EXCLUDED.last_ledger,
                 events_processed = EXCLUDED.events_processed,
@@ -109,6 +175,7 @@ impl CheckpointManager {
         )
         .bind(&checkpoint.id)
         .bind(&checkpoint.session_id)
+        .bind(checkpoint.network.to_string())
         .bind(checkpoint.last_ledger as i64)
         .bind(checkpoint.events_processed as i64)
         .bind(checkpoint.events_failed as i64)
@@ -119,32 +186,44 @@ impl CheckpointManager {
         .await
         .context("Failed to save checkpoint")?;
 
-        debug!("Checkpoint {} saved successfully", checkpoint.id);
+        log_event!(
+            debug,
+            Subsystem::Replay,
+            "replay.checkpoint_saved",
+            checkpoint_id = %checkpoint.id,
+            "Checkpoint saved"
+        );
         Ok(())
     }
 
     /// Load a checkpoint by ID
     pub async fn load(&self, checkpoint_id: &str) -> Result<Option<Checkpoint>> {
-        debug!("Loading checkpoint {}", checkpoint_id);
+        log_event!(
+            debug,
+            Subsystem::Replay,
+            "replay.checkpoint_loading",
+            checkpoint_id,
+            "Loading checkpoint"
+        );
 
-        let row: Option<(String, String, i64, i64, i64, String, String, DateTime<Utc>)> =
-            sqlx::query_as(
-                r"
-            SELECT id, session_id, last_ledger, events_processed, events_failed,
+        let row: Option<CheckpointRow> = sqlx::query_as(
+            r"
+            SELECT id, session_id, network, last_ledger, events_processed, events_failed,
                    state_snapshot, metadata, created_at
             FROM replay_checkpoints
             WHERE id = $1
             ",
-            )
-            .bind(checkpoint_id)
-            .fetch_optional(&self.pool)
-            .await
-            .context("Failed to load checkpoint")?;
+        )
+        .bind(checkpoint_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to load checkpoint")?;
 
         match row {
             Some((
                 id,
                 session_id,
+                network,
                 last_ledger,
                 events_processed,
                 events_failed,
@@ -154,10 +233,12 @@ impl CheckpointManager {
             )) => {
                 let state_snapshot: serde_json::Value = serde_json::from_str(&state_json)?;
                 let metadata: HashMap<String, String> = serde_json::from_str(&metadata_json)?;
+                let network = network.parse().map_err(anyhow::Error::msg)?;
 
                 Ok(Some(Checkpoint {
                     id,
                     session_id,
+                    network,
                     last_ledger: last_ledger as u64,
                     events_processed: events_processed as u64,
                     events_failed: events_failed as u64,
@@ -172,28 +253,34 @@ impl CheckpointManager {
 
     /// Get the latest checkpoint for a session
     pub async fn get_latest(&self, session_id: &str) -> Result<Option<Checkpoint>> {
-        debug!("Getting latest checkpoint for session {}", session_id);
+        log_event!(
+            debug,
+            Subsystem::Replay,
+            "replay.checkpoint_latest_loading",
+            session_id,
+            "Getting latest checkpoint"
+        );
 
-        let row: Option<(String, String, i64, i64, i64, String, String, DateTime<Utc>)> =
-            sqlx::query_as(
-                r"
-            SELECT id, session_id, last_ledger, events_processed, events_failed,
+        let row: Option<CheckpointRow> = sqlx::query_as(
+            r"
+            SELECT id, session_id, network, last_ledger, events_processed, events_failed,
                    state_snapshot, metadata, created_at
             FROM replay_checkpoints
             WHERE session_id = $1
             ORDER BY created_at DESC
             LIMIT 1
             ",
-            )
-            .bind(session_id)
-            .fetch_optional(&self.pool)
-            .await
-            .context("Failed to get latest checkpoint")?;
+        )
+        .bind(session_id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to get latest checkpoint")?;
 
         match row {
             Some((
                 id,
                 session_id,
+                network,
                 last_ledger,
                 events_processed,
                 events_failed,
@@ -203,10 +290,12 @@ impl CheckpointManager {
             )) => {
                 let state_snapshot: serde_json::Value = serde_json::from_str(&state_json)?;
                 let metadata: HashMap<String, String> = serde_json::from_str(&metadata_json)?;
+                let network = network.parse().map_err(anyhow::Error::msg)?;
 
                 Ok(Some(Checkpoint {
                     id,
                     session_id,
+                    network,
                     last_ledger: last_ledger as u64,
                     events_processed: events_processed as u64,
                     events_failed: events_failed as u64,
@@ -221,22 +310,27 @@ impl CheckpointManager {
 
     /// List all checkpoints for a session
     pub async fn list_for_session(&self, session_id: &str) -> Result<Vec<Checkpoint>> {
-        debug!("Listing checkpoints for session {}", session_id);
+        log_event!(
+            debug,
+            Subsystem::Replay,
+            "replay.checkpoints_listing",
+            session_id,
+            "Listing checkpoints"
+        );
 
-        let rows: Vec<(String, String, i64, i64, i64, String, String, DateTime<Utc>)> =
-            sqlx::query_as(
-                r"
-            SELECT id, session_id, last_ledger, events_processed, events_failed,
+        let rows: Vec<CheckpointRow> = sqlx::query_as(
+            r"
+            SELECT id, session_id, network, last_ledger, events_processed, events_failed,
                    state_snapshot, metadata, created_at
             FROM replay_checkpoints
             WHERE session_id = $1
             ORDER BY created_at DESC
             ",
-            )
-            .bind(session_id)
-            .fetch_all(&self.pool)
-            .await
-            .context("Failed to list checkpoints")?;
+        )
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to list checkpoints")?;
 
         let checkpoints = rows
             .into_iter()
@@ -244,6 +338,7 @@ impl CheckpointManager {
                 |(
                     id,
                     session_id,
+                    network,
                     last_ledger,
                     events_processed,
                     events_failed,
@@ -253,10 +348,12 @@ impl CheckpointManager {
                 )| {
                     let state_snapshot = serde_json::from_str(&state_json).ok()?;
                     let metadata = serde_json::from_str(&metadata_json).ok()?;
+                    let network = network.parse().ok()?;
 
                     Some(Checkpoint {
                         id,
                         session_id,
+                        network,
                         last_ledger: last_ledger as u64,
                         events_processed: events_processed as u64,
                         events_failed: events_failed as u64,
@@ -273,7 +370,13 @@ impl CheckpointManager {
 
     /// Delete a checkpoint
     pub async fn delete(&self, checkpoint_id: &str) -> Result<()> {
-        info!("Deleting checkpoint {}", checkpoint_id);
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.checkpoint_deleting",
+            checkpoint_id,
+            "Deleting checkpoint"
+        );
 
         sqlx::query("DELETE FROM replay_checkpoints WHERE id = $1")
             .bind(checkpoint_id)
@@ -286,7 +389,13 @@ impl CheckpointManager {
 
     /// Delete all checkpoints for a session
     pub async fn delete_for_session(&self, session_id: &str) -> Result<()> {
-        info!("Deleting all checkpoints for session {}", session_id);
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.checkpoints_deleting",
+            session_id,
+            "Deleting all checkpoints of session"
+        );
 
         sqlx::query("DELETE FROM replay_checkpoints WHERE session_id = $1")
             .bind(session_id)
@@ -297,9 +406,144 @@ impl CheckpointManager {
         Ok(())
     }
 
+    /// Bundle the latest checkpoint of a session for [`Self::import`]. `None`
+    /// if the session is unknown or has no checkpoint yet.
+    pub async fn export(&self, session_id: &str) -> Result<Option<CheckpointBundle>> {
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.checkpoint_exporting",
+            session_id,
+            "Exporting latest checkpoint"
+        );
+
+        let Some(session) = ReplayStorage::new(self.pool.clone())
+            .load_metadata(session_id)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let Some(checkpoint) = self.get_latest(session_id).await? else {
+            return Ok(None);
+        };
+        if checkpoint.metadata.contains_key(EXTERNAL_STATE_METADATA) {
+            anyhow::bail!(
+                "Checkpoint {} of session {} holds only part of its state, the rest being \
+                 in replay_state_entries, and cannot be exported",
+                checkpoint.id,
+                session_id
+            );
+        }
+
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            r"
+            SELECT event_id, ledger_sequence
+            FROM processed_events
+            WHERE ledger_sequence <= $1
+            ORDER BY ledger_sequence, event_id
+            ",
+        )
+        .bind(checkpoint.last_ledger as i64)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to export processed events")?;
+
+        let processed_events = rows
+            .into_iter()
+            .map(|(event_id, ledger_sequence)| ProcessedEvent {
+                event_id,
+                ledger_sequence: ledger_sequence as u64,
+            })
+            .collect();
+
+        Ok(Some(CheckpointBundle {
+            version: BUNDLE_VERSION,
+            exported_at: Utc::now(),
+            session,
+            checkpoint,
+            processed_events,
+        }))
+    }
+
+    /// Restore an exported bundle under its original session and checkpoint
+    /// ids, so a replay from that checkpoint picks up with the same state and
+    /// skips the same events. Existing rows with those ids are overwritten.
+    pub async fn import(&self, bundle: &CheckpointBundle) -> Result<()> {
+        if bundle.version != BUNDLE_VERSION {
+            anyhow::bail!(
+                "Unsupported checkpoint bundle version {} (expected {BUNDLE_VERSION})",
+                bundle.version
+            );
+        }
+        if bundle.checkpoint.session_id != bundle.session.session_id {
+            anyhow::bail!(
+                "Checkpoint {} belongs to session {}, not {}",
+                bundle.checkpoint.id,
+                bundle.checkpoint.session_id,
+                bundle.session.session_id
+            );
+        }
+        if bundle.checkpoint.network != bundle.session.config.network {
+            anyhow::bail!(
+                "Checkpoint {} is for {}, but session {} replays {}",
+                bundle.checkpoint.id,
+                bundle.checkpoint.network,
+                bundle.session.session_id,
+                bundle.session.config.network
+            );
+        }
+        let state = ApplicationState::from_json(&bundle.checkpoint.state_snapshot)
+            .context("Checkpoint bundle has no valid state snapshot")?;
+
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.checkpoint_importing",
+            checkpoint_id = %bundle.checkpoint.id,
+            session_id = %bundle.session.session_id,
+            ledger = bundle.checkpoint.last_ledger,
+            processed_events = bundle.processed_events.len(),
+            "Importing checkpoint"
+        );
+
+        ReplayStorage::new(self.pool.clone())
+            .save_metadata(&bundle.session)
+            .await?;
+        self.save(&bundle.checkpoint).await?;
+
+        let mut tx = self.pool.begin().await?;
+        for event in &bundle.processed_events {
+            sqlx::query(
+                r"
+                INSERT INTO processed_events (event_id, ledger_sequence, processed_at)
+                VALUES ($1, $2, CURRENT_TIMESTAMP)
+                ON CONFLICT (event_id) DO NOTHING
+                ",
+            )
+            .bind(&event.event_id)
+            .bind(event.ledger_sequence as i64)
+            .execute(&mut *tx)
+            .await
+            .context("Failed to import processed events")?;
+        }
+        tx.commit().await?;
+
+        // Resuming loads the nearest persisted state, so persist the snapshot.
+        StateBuilder::with_state(self.pool.clone(), bundle.checkpoint.network, state)
+            .for_filter(&bundle.session.config.filter)
+            .persist_state()
+            .await
+    }
+
     /// Clean up old checkpoints (older than specified days)
     pub async fn cleanup_old(&self, days: i64) -> Result<u64> {
-        info!("Cleaning up checkpoints older than {} days", days);
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.checkpoints_cleanup_started",
+            days,
+            "Cleaning up old checkpoints"
+        );
 
         let cutoff = Utc::now() - chrono::Duration::days(days);
 
@@ -310,7 +554,13 @@ impl CheckpointManager {
             .context("Failed to cleanup old checkpoints")?;
 
         let deleted = result.rows_affected();
-        info!("Deleted {} old checkpoints", deleted);
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.checkpoints_cleanup_completed",
+            deleted,
+            "Deleted old checkpoints"
+        );
 
         Ok(deleted)
     }
@@ -319,6 +569,8 @@ impl CheckpointManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::replay::{ReplayConfig, ReplayStatus};
+    use crate::test_support::{self, migrations};
 
     #[test]
     fn test_checkpoint_creation() {
@@ -332,4 +584,91 @@ mod tests {
         assert_eq!(checkpoint.events_failed, 5);
         assert_eq!(checkpoint.metadata.get("key"), Some(&"value".to_string()));
     }
+
+    async fn pool() -> SqlitePool {
+        let pool = test_support::sqlite_pool(migrations::REPLAY).await;
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_bundle_round_trips_between_environments() {
+        let production = pool().await;
+        let session = ReplayMetadata {
+            session_id: "session-prod".to_string(),
+            config: ReplayConfig::new().with_network(StellarNetwork::Testnet),
+            status: ReplayStatus::Pending,
+            started_at: Utc::now(),
+            ended_at: None,
+            checkpoint: None,
+            report: None,
+            integrity: None,
+            event_types: std::collections::BTreeMap::new(),
+        };
+        ReplayStorage::new(production.clone())
+            .save_metadata(&session)
+            .await
+            .unwrap();
+        for (event_id, ledger) in [("evt-1", 10), ("evt-2", 20), ("evt-3", 30)] {
+            sqlx::query("INSERT INTO processed_events (event_id, ledger_sequence) VALUES ($1, $2)")
+                .bind(event_id)
+                .bind(ledger)
+                .execute(&production)
+                .await
+                .unwrap();
+        }
+        let state = ApplicationState::at_ledger(20);
+        let checkpoint = Checkpoint::new(session.session_id.clone(), 20)
+            .with_network(StellarNetwork::Testnet)
+            .with_stats(2, 0)
+            .with_state(state.to_json().unwrap());
+        let exporter = CheckpointManager::new(production);
+        exporter.save(&checkpoint).await.unwrap();
+
+        let bundle = exporter.export(&session.session_id).await.unwrap().unwrap();
+        assert_eq!(bundle.checkpoint, checkpoint);
+        // Events after the checkpoint are left to the replay.
+        assert_eq!(
+            bundle
+                .processed_events
+                .iter()
+                .map(|e| e.event_id.as_str())
+                .collect::<Vec<_>>(),
+            ["evt-1", "evt-2"]
+        );
+        assert!(exporter.export("unknown").await.unwrap().is_none());
+
+        let json = serde_json::to_string(&bundle).unwrap();
+        let local = pool().await;
+        let importer = CheckpointManager::new(local.clone());
+        importer
+            .import(&serde_json::from_str(&json).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            importer.load(&checkpoint.id).await.unwrap(),
+            Some(checkpoint.clone())
+        );
+        let processed: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM processed_events")
+            .fetch_one(&local)
+            .await
+            .unwrap();
+        assert_eq!(processed, 2);
+        let mut builder = StateBuilder::new(local.clone(), StellarNetwork::Testnet);
+        assert_eq!(builder.load_nearest(25).await.unwrap(), Some(20));
+        assert_eq!(builder.state().compute_hash(), state.compute_hash());
+        let mut mainnet = StateBuilder::new(local, StellarNetwork::Mainnet);
+        assert_eq!(mainnet.load_nearest(25).await.unwrap(), None);
+
+        let crossed = CheckpointBundle {
+            checkpoint: checkpoint.with_network(StellarNetwork::Mainnet),
+            ..bundle.clone()
+        };
+        assert!(importer.import(&crossed).await.is_err());
+        let newer = CheckpointBundle {
+            version: BUNDLE_VERSION + 1,
+            ..bundle
+        };
+        assert!(importer.import(&newer).await.is_err());
+    }
 }
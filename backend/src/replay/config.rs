@@ -3,31 +3,52 @@
 //! Defines configuration options for replay operations including
 //! network selection, block ranges, and processing parameters.
 
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::error::DomainError;
+use crate::network::StellarNetwork;
 
 use super::EventFilter;
 
-/// Configuration for a replay operation
+/// Configuration for a replay operation. Fields missing when deserializing
+/// take their default value.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ReplayConfig {
     /// Replay mode
     pub mode: ReplayMode,
+    /// Network the session replays. Events, state snapshots and checkpoints of
+    /// any other network are never read or written.
+    pub network: StellarNetwork,
     /// Range of ledgers to replay
     pub range: ReplayRange,
     /// Event filter
     pub filter: EventFilter,
     /// Batch size for processing
     pub batch_size: usize,
-    /// Maximum concurrent workers
-    pub max_workers: usize,
+    /// Tasks processing each batch in parallel. Events are split into lanes by
+    /// contract, so each contract's events keep their order; 1 is sequential.
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
     /// Enable dry-run mode (no state changes)
     pub dry_run: bool,
     /// Enable verbose logging
     pub verbose: bool,
     /// Checkpoint interval (save state every N ledgers)
     pub checkpoint_interval: u64,
+    /// Persist a state snapshot every N ledgers so a resumed replay starts from
+    /// the nearest one (0 disables)
+    #[serde(default = "default_snapshot_interval")]
+    pub snapshot_interval: u64,
+    /// Number of most recent state snapshots kept when compacting
+    #[serde(default = "default_snapshot_retention")]
+    pub snapshot_retention: usize,
+    /// Memory a session may use, in MiB. Half bounds the events fetched ahead
+    /// of processing; state outgrowing the other half is moved to the
+    /// database, or stops a replay that must not write.
+    #[serde(default = "default_memory_budget_mb")]
+    pub memory_budget_mb: usize,
     /// Timeout for processing a single event (seconds)
     pub event_timeout_secs: u64,
     /// Maximum retries for failed events
@@ -38,19 +59,39 @@ impl Default for ReplayConfig {
     fn default() -> Self {
         Self {
             mode: ReplayMode::Full,
+            network: StellarNetwork::default(),
             range: ReplayRange::All,
             filter: EventFilter::default(),
             batch_size: 100,
-            max_workers: 4,
+            concurrency: default_concurrency(),
             dry_run: false,
             verbose: false,
             checkpoint_interval: 1000,
+            snapshot_interval: default_snapshot_interval(),
+            snapshot_retention: default_snapshot_retention(),
+            memory_budget_mb: default_memory_budget_mb(),
             event_timeout_secs: 30,
             max_retries: 3,
         }
     }
 }
 
+const fn default_concurrency() -> usize {
+    1
+}
+
+const fn default_snapshot_interval() -> u64 {
+    10_000
+}
+
+const fn default_snapshot_retention() -> usize {
+    3
+}
+
+const fn default_memory_budget_mb() -> usize {
+    512
+}
+
 impl ReplayConfig {
     /// Create a new replay config with defaults
     #[must_use]
@@ -65,6 +106,13 @@ impl ReplayConfig {
         self
     }
 
+    /// Set the network to replay
+    #[must_use]
+    pub const fn with_network(mut self, network: StellarNetwork) -> Self {
+        self.network = network;
+        self
+    }
+
     /// Set ledger range
     #[must_use]
     pub fn with_range(mut self, range: ReplayRange) -> Self {
@@ -86,6 +134,40 @@ impl ReplayConfig {
         self
     }
 
+    /// Set the number of parallel processing lanes
+    #[must_use]
+    pub const fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Set how often state snapshots are persisted and how many are kept
+    #[must_use]
+    pub const fn with_snapshots(mut self, interval: u64, retention: usize) -> Self {
+        self.snapshot_interval = interval;
+        self.snapshot_retention = retention;
+        self
+    }
+
+    /// Set the session's memory budget in MiB
+    #[must_use]
+    pub const fn with_memory_budget(mut self, mb: usize) -> Self {
+        self.memory_budget_mb = mb;
+        self
+    }
+
+    /// Bytes of fetched events that may wait for processing
+    #[must_use]
+    pub const fn event_budget_bytes(&self) -> usize {
+        self.memory_budget_mb.saturating_mul(1024 * 1024) / 2
+    }
+
+    /// Bytes the rebuilt state may grow to
+    #[must_use]
+    pub const fn state_budget_bytes(&self) -> usize {
+        self.memory_budget_mb.saturating_mul(1024 * 1024) - self.event_budget_bytes()
+    }
+
     /// Enable dry-run mode
     #[must_use]
     pub const fn dry_run(mut self) -> Self {
@@ -100,6 +182,22 @@ impl ReplayConfig {
         self
     }
 
+    /// The event filter, restricted to the session's network
+    #[must_use]
+    pub fn event_filter(&self) -> EventFilter {
+        EventFilter {
+            network: Some(self.network.to_string()),
+            ..self.filter.clone()
+        }
+    }
+
+    /// Whether application state is left untouched. Diff sessions never write,
+    /// whatever `dry_run` says.
+    #[must_use]
+    pub const fn is_dry_run(&self) -> bool {
+        self.dry_run || matches!(self.mode, ReplayMode::Diff)
+    }
+
     /// Validate configuration
     pub fn validate(&self) -> Result<(), DomainError> {
         if self.batch_size == 0 {
@@ -108,9 +206,9 @@ impl ReplayConfig {
             ));
         }
 
-        if self.max_workers == 0 {
+        if self.concurrency == 0 {
             return Err(DomainError::InvalidConfiguration(
-                "Max workers must be greater than 0".to_string(),
+                "Concurrency must be greater than 0".to_string(),
             ));
         }
 
@@ -120,6 +218,27 @@ impl ReplayConfig {
             ));
         }
 
+        if self.snapshot_interval > 0 && self.snapshot_retention == 0 {
+            return Err(DomainError::InvalidConfiguration(
+                "Snapshot retention must keep at least one snapshot".to_string(),
+            ));
+        }
+
+        if let Some(network) = &self.filter.network {
+            if network.parse::<StellarNetwork>() != Ok(self.network) {
+                return Err(DomainError::InvalidConfiguration(format!(
+                    "Filter network {network} does not match the session network {}",
+                    self.network
+                )));
+            }
+        }
+
+        if self.memory_budget_mb == 0 {
+            return Err(DomainError::InvalidConfiguration(
+                "Memory budget must be greater than 0".to_string(),
+            ));
+        }
+
         if self.event_timeout_secs == 0 {
             return Err(DomainError::InvalidConfiguration(
                 "Event timeout must be greater than 0".to_string(),
@@ -135,6 +254,14 @@ impl ReplayConfig {
                     });
                 }
             }
+            ReplayRange::TimeWindow { start, end } => {
+                if start >= end {
+                    return Err(DomainError::InvalidTimeRange {
+                        start: start.to_rfc3339(),
+                        end: end.to_rfc3339(),
+                    });
+                }
+            }
             ReplayRange::FromCheckpoint { checkpoint_id } => {
                 if checkpoint_id.is_empty() {
                     return Err(DomainError::InvalidConfiguration(
@@ -145,6 +272,19 @@ impl ReplayConfig {
             _ => {}
         }
 
+        if self.mode == ReplayMode::CatchUpThenFollow
+            && matches!(
+                self.range,
+                ReplayRange::To { .. }
+                    | ReplayRange::FromTo { .. }
+                    | ReplayRange::TimeWindow { .. }
+            )
+        {
+            return Err(DomainError::InvalidConfiguration(
+                "CatchUpThenFollow needs a range that ends at the chain head".to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -160,6 +300,11 @@ pub enum ReplayMode {
     Verification,
     /// Debug - replay with detailed logging and no state changes
     Debug,
+    /// Replay into memory only and report how the database differs
+    Diff,
+    /// Full replay up to the chain head, then keep processing live events
+    /// from the event storage's feed until stopped
+    CatchUpThenFollow,
 }
 
 impl std::fmt::Display for ReplayMode {
@@ -169,6 +314,8 @@ impl std::fmt::Display for ReplayMode {
             Self::Incremental => write!(f, "Incremental"),
             Self::Verification => write!(f, "Verification"),
             Self::Debug => write!(f, "Debug"),
+            Self::Diff => write!(f, "Diff"),
+            Self::CatchUpThenFollow => write!(f, "CatchUpThenFollow"),
         }
     }
 }
@@ -188,9 +335,34 @@ pub enum ReplayRange {
     FromCheckpoint { checkpoint_id: String },
     /// Replay last N ledgers
     Last { count: u64 },
+    /// Replay the ledgers closed from `start` up to, not including, `end`.
+    /// The event source resolves them to ledgers when the replay starts.
+    TimeWindow {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
 }
 
 impl ReplayRange {
+    /// The time window of the given length ending now, e.g. the last week
+    #[must_use]
+    pub fn past(duration: Duration) -> Self {
+        let end = Utc::now();
+        Self::TimeWindow {
+            start: end - duration,
+            end,
+        }
+    }
+
+    /// Start and end of a `TimeWindow` range
+    #[must_use]
+    pub const fn time_window(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        match self {
+            Self::TimeWindow { start, end } => Some((*start, *end)),
+            _ => None,
+        }
+    }
+
     /// Get the start ledger for this range
     #[must_use]
     pub const fn start_ledger(&self, latest: u64, checkpoint_ledger: Option<u64>) -> Option<u64> {
@@ -201,6 +373,7 @@ impl ReplayRange {
             Self::FromTo { start, .. } => Some(*start),
             Self::FromCheckpoint { .. } => checkpoint_ledger,
             Self::Last { count } => Some(latest.saturating_sub(*count)),
+            Self::TimeWindow { .. } => None,
         }
     }
 
@@ -214,6 +387,7 @@ impl ReplayRange {
             Self::FromTo { end, .. } => Some(*end),
             Self::FromCheckpoint { .. } => Some(latest),
             Self::Last { .. } => Some(latest),
+            Self::TimeWindow { .. } => None,
         }
     }
 
@@ -240,6 +414,79 @@ mod tests {
             ..Default::default()
         };
         assert!(invalid_config.validate().is_err());
+
+        let no_lanes = ReplayConfig::new().with_concurrency(0);
+        assert!(no_lanes.validate().is_err());
+
+        let keeps_nothing = ReplayConfig::new().with_snapshots(100, 0);
+        assert!(keeps_nothing.validate().is_err());
+        assert!(ReplayConfig::new().with_snapshots(0, 0).validate().is_ok());
+
+        assert!(ReplayConfig::new()
+            .with_memory_budget(0)
+            .validate()
+            .is_err());
+        let budget = ReplayConfig::new().with_memory_budget(1);
+        assert_eq!(
+            budget.event_budget_bytes() + budget.state_budget_bytes(),
+            1024 * 1024
+        );
+    }
+
+    #[test]
+    fn test_filter_network_must_match_session() {
+        let testnet = |filter_network: &str| {
+            ReplayConfig::new()
+                .with_network(StellarNetwork::Testnet)
+                .with_filter(EventFilter {
+                    network: Some(filter_network.to_string()),
+                    ..EventFilter::default()
+                })
+        };
+        assert!(testnet("testnet").validate().is_ok());
+        assert!(testnet("TESTNET").validate().is_ok());
+        assert!(testnet("mainnet").validate().is_err());
+        assert!(testnet("futurenet").validate().is_err());
+
+        let unfiltered = ReplayConfig::new().with_network(StellarNetwork::Testnet);
+        assert!(unfiltered.validate().is_ok());
+        assert_eq!(
+            unfiltered.event_filter().network.as_deref(),
+            Some("testnet")
+        );
+    }
+
+    #[test]
+    fn test_follow_mode_needs_open_range() {
+        let follow = ReplayConfig::new().with_mode(ReplayMode::CatchUpThenFollow);
+        assert!(follow.clone().validate().is_ok());
+        assert!(follow
+            .with_range(ReplayRange::FromTo { start: 1, end: 10 })
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_concurrency_defaults_for_stored_configs() {
+        let mut stored = serde_json::to_value(ReplayConfig::default()).unwrap();
+        let fields = stored.as_object_mut().unwrap();
+        fields.remove("concurrency");
+        // Sessions stored before concurrency replaced it still carry max_workers.
+        fields.insert("max_workers".to_string(), serde_json::json!(4));
+        let config: ReplayConfig = serde_json::from_value(stored).unwrap();
+        assert_eq!(config.concurrency, 1);
+    }
+
+    #[test]
+    fn test_partial_config_takes_defaults() {
+        let config: ReplayConfig = serde_json::from_value(serde_json::json!({
+            "range": { "FromTo": { "start": 10, "end": 20 } },
+            "dry_run": true,
+        }))
+        .unwrap();
+        assert_eq!(config.range, ReplayRange::FromTo { start: 10, end: 20 });
+        assert!(config.dry_run);
+        assert_eq!(config.batch_size, ReplayConfig::default().batch_size);
     }
 
     #[test]
@@ -259,4 +506,35 @@ mod tests {
         assert_eq!(range.start_ledger(1000, None), Some(900));
         assert_eq!(range.end_ledger(1000), Some(1000));
     }
+
+    #[test]
+    fn test_time_window_range() {
+        let range = ReplayRange::past(Duration::days(7));
+        let (start, end) = range.time_window().unwrap();
+        assert_eq!(end - start, Duration::days(7));
+        // Ledgers are only known once the event source resolves the window.
+        assert_eq!(range.start_ledger(1000, None), None);
+        assert!(ReplayConfig::new().with_range(range).validate().is_ok());
+
+        let backwards = ReplayRange::TimeWindow {
+            start: end,
+            end: start,
+        };
+        assert!(ReplayConfig::new()
+            .with_range(backwards)
+            .validate()
+            .is_err());
+
+        let config: ReplayConfig = serde_json::from_value(serde_json::json!({
+            "range": { "TimeWindow": {
+                "start": "2026-01-01T00:00:00Z",
+                "end": "2026-01-08T00:00:00Z",
+            } },
+        }))
+        .unwrap();
+        assert_eq!(
+            config.range.time_window().unwrap().1.to_rfc3339(),
+            "2026-01-08T00:00:00+00:00"
+        );
+    }
 }
@@ -0,0 +1,322 @@
+//! Consistency Replays
+//!
+//! Replays the most recent ledgers on a cron schedule and compares the state
+//! rebuilt from their events with the snapshots the application serves,
+//! raising an alert when the two disagree. Checks run as [`ReplayMode::Diff`]
+//! sessions, so they never write to application state, and show up among the
+//! replay sessions like any other.
+
+use anyhow::{bail, Result};
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+use super::{
+    config::{ReplayConfig, ReplayMode, ReplayRange},
+    diff::ReplayReport,
+    manager::ReplayManager,
+    ReplayStatus,
+};
+use crate::alerts::{AlertManager, AlertType};
+use crate::cluster::{LeaderElection, ROLE_INGESTION};
+use crate::jobs::CronSchedule;
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::network::StellarNetwork;
+
+/// Every six hours, on the hour
+const DEFAULT_SCHEDULE: &str = "0 */6 * * *";
+
+/// About a day of ledgers at five seconds each
+const DEFAULT_LEDGERS: u64 = 17_280;
+
+/// When consistency replays run and how far back they reach
+#[derive(Debug, Clone)]
+pub struct ConsistencyCheckConfig {
+    pub enabled: bool,
+    pub schedule: CronSchedule,
+    /// Ledgers each check replays, counting back from the latest stored event
+    pub ledgers: u64,
+    pub network: StellarNetwork,
+}
+
+impl ConsistencyCheckConfig {
+    /// Reads `REPLAY_CONSISTENCY_ENABLED`, `REPLAY_CONSISTENCY_SCHEDULE` (a
+    /// cron expression) and `REPLAY_CONSISTENCY_LEDGERS`. Defaults: enabled,
+    /// every six hours, the last 17 280 ledgers.
+    #[must_use]
+    pub fn from_env(network: StellarNetwork) -> Self {
+        let enabled = std::env::var("REPLAY_CONSISTENCY_ENABLED")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(true);
+        let schedule = std::env::var("REPLAY_CONSISTENCY_SCHEDULE")
+            .ok()
+            .and_then(|expression| {
+                expression
+                    .parse()
+                    .map_err(|e| {
+                        log_event!(
+                            warn,
+                            Subsystem::Replay,
+                            "replay.consistency_schedule_invalid",
+                            schedule = %expression,
+                            default_schedule = DEFAULT_SCHEDULE,
+                            error = format!("{e:#}"),
+                            "Invalid REPLAY_CONSISTENCY_SCHEDULE, using the default"
+                        );
+                    })
+                    .ok()
+            })
+            .unwrap_or_else(default_schedule);
+        let ledgers = std::env::var("REPLAY_CONSISTENCY_LEDGERS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|ledgers| *ledgers > 0)
+            .unwrap_or(DEFAULT_LEDGERS);
+
+        Self {
+            enabled,
+            schedule,
+            ledgers,
+            network,
+        }
+    }
+}
+
+fn default_schedule() -> CronSchedule {
+    DEFAULT_SCHEDULE
+        .parse()
+        .unwrap_or_else(|_| unreachable!("the default schedule is valid"))
+}
+
+/// Runs scheduled consistency replays and alerts on divergence
+pub struct ConsistencyMonitor {
+    manager: Arc<ReplayManager>,
+    alerts: Arc<AlertManager>,
+    config: ConsistencyCheckConfig,
+    leader: Option<Arc<LeaderElection>>,
+}
+
+impl ConsistencyMonitor {
+    #[must_use]
+    pub const fn new(
+        manager: Arc<ReplayManager>,
+        alerts: Arc<AlertManager>,
+        config: ConsistencyCheckConfig,
+    ) -> Self {
+        Self {
+            manager,
+            alerts,
+            config,
+            leader: None,
+        }
+    }
+
+    /// Only run scheduled checks while this instance leads ingestion, so a
+    /// multi-instance deployment replays and alerts once.
+    #[must_use]
+    pub fn with_leader_election(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    /// Replay the configured ledgers now and alert if the rebuilt state
+    /// diverges from the stored snapshots
+    pub async fn check(&self) -> Result<ReplayReport> {
+        let config = ReplayConfig::new()
+            .with_mode(ReplayMode::Diff)
+            .with_network(self.config.network)
+            .with_range(ReplayRange::Last {
+                count: self.config.ledgers,
+            });
+        let metadata = self.manager.run(config).await?;
+        let report = match (&metadata.status, metadata.report) {
+            (ReplayStatus::Completed { .. }, Some(report)) => report,
+            (status, _) => bail!(
+                "Consistency replay {} did not complete: {}",
+                metadata.session_id,
+                status
+            ),
+        };
+
+        if report.is_consistent() {
+            log_event!(
+                info,
+                Subsystem::Replay,
+                "replay.consistency_matched",
+                session_id = %metadata.session_id,
+                snapshots = report.replayed_snapshots,
+                ledger = report.ledger,
+                "Consistency replay matched the stored snapshots"
+            );
+            return Ok(report);
+        }
+
+        let discrepancies = report.missing.len() + report.divergent.len() + report.extra.len();
+        log_event!(
+            warn,
+            Subsystem::Replay,
+            "replay.consistency_diverged",
+            session_id = %metadata.session_id,
+            discrepancies,
+            ledger = report.ledger,
+            "Consistency replay found discrepancies"
+        );
+        self.alerts.send_network_alert(
+            AlertType::StateDivergence,
+            format!(
+                "Replaying the last {} {} ledgers up to {} diverges from stored snapshots: \
+                 {} missing, {} divergent, {} extra (session {})",
+                self.config.ledgers,
+                self.config.network,
+                report.ledger,
+                report.missing.len(),
+                report.divergent.len(),
+                report.extra.len(),
+                metadata.session_id
+            ),
+            f64::from(u32::try_from(report.replayed_snapshots).unwrap_or(u32::MAX)),
+            f64::from(u32::try_from(discrepancies).unwrap_or(u32::MAX)),
+        );
+        Ok(report)
+    }
+
+    /// Run [`Self::check`] at every time the schedule fires, until aborted
+    #[must_use]
+    pub fn spawn(self: Arc<Self>) -> JoinHandle<()> {
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.consistency_scheduled",
+            ledgers = self.config.ledgers,
+            schedule = %self.config.schedule,
+            "Scheduling consistency replays"
+        );
+        tokio::spawn(async move {
+            while let Some(next) = self.config.schedule.next_after(Utc::now()) {
+                let wait = (next - Utc::now()).to_std().unwrap_or_default();
+                tokio::time::sleep(wait).await;
+
+                if let Some(leader) = &self.leader {
+                    if !leader.is_leader(ROLE_INGESTION) {
+                        log_event!(
+                            debug,
+                            Subsystem::Replay,
+                            "replay.consistency_skipped_not_leader",
+                            "Skipping consistency replay: this instance is not the ingestion leader"
+                        );
+                        continue;
+                    }
+                }
+                if let Err(e) = self.check().await {
+                    log_event!(
+                        error,
+                        Subsystem::Replay,
+                        "replay.consistency_failed",
+                        error = format!("{e:#}"),
+                        "Scheduled consistency replay failed"
+                    );
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::{ContractEvent, EventStorage, ProgressHub};
+    use crate::test_support::{self, migrations};
+    use sqlx::SqlitePool;
+
+    async fn pool_with_snapshots() -> SqlitePool {
+        let pool = test_support::sqlite_pool(migrations::REPLAY).await;
+
+        // Epochs 1 to 20 submitted one per ledger, and stored as submitted.
+        let storage = EventStorage::new(pool.clone());
+        for epoch in 1..=20_u64 {
+            storage
+                .store_event(&ContractEvent {
+                    id: format!("evt-{epoch}"),
+                    ledger_sequence: 100 + epoch,
+                    transaction_hash: format!("{epoch:064x}"),
+                    tx_index: 0,
+                    event_index: 0,
+                    contract_id: "CSNAPSHOT".to_string(),
+                    event_type: "snapshot_submitted".to_string(),
+                    data: serde_json::json!({ "epoch": epoch, "hash": format!("hash-{epoch}") }),
+                    timestamp: Utc::now(),
+                    network: "mainnet".to_string(),
+                })
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO snapshots (epoch, hash) VALUES ($1, $2)")
+                .bind(i64::try_from(epoch).unwrap())
+                .bind(format!("hash-{epoch}"))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_alerts_when_recent_state_diverges() {
+        let pool = pool_with_snapshots().await;
+        let (alerts, mut subscription) = AlertManager::new();
+        let manager = Arc::new(ReplayManager::new(
+            pool.clone(),
+            Arc::new(ProgressHub::new()),
+        ));
+        let monitor = ConsistencyMonitor::new(
+            Arc::clone(&manager),
+            Arc::new(alerts),
+            ConsistencyCheckConfig {
+                enabled: true,
+                schedule: default_schedule(),
+                ledgers: 5,
+                network: StellarNetwork::Mainnet,
+            },
+        );
+
+        let report = monitor.check().await.unwrap();
+        // Ledgers 115 to 120, the latest.
+        assert!(report.is_consistent());
+        assert_eq!(report.replayed_snapshots, 6);
+
+        // Only the replayed window is compared: older epochs may differ freely.
+        for (epoch, hash) in [(3, "rewritten"), (17, "rewritten")] {
+            sqlx::query("UPDATE snapshots SET hash = $1 WHERE epoch = $2")
+                .bind(hash)
+                .bind(epoch)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        let report = monitor.check().await.unwrap();
+        assert_eq!(
+            report
+                .divergent
+                .iter()
+                .map(|divergence| divergence.epoch)
+                .collect::<Vec<_>>(),
+            [17]
+        );
+
+        // The first check raised nothing, so this is the second one's alert.
+        let alert = subscription.recv().await.unwrap();
+        assert!(matches!(alert.alert_type, AlertType::StateDivergence));
+        assert!(alert.message.contains("1 divergent"), "{}", alert.message);
+
+        let sessions = manager
+            .storage()
+            .list_sessions(Some(10), None)
+            .await
+            .unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert!(sessions
+            .iter()
+            .all(|session| session.config.mode == ReplayMode::Diff));
+    }
+}
@@ -0,0 +1,199 @@
+//! Replay Diff
+//!
+//! Compares the state rebuilt by a replay with the snapshots the application
+//! currently holds, without writing to either side.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::state_builder::{ApplicationState, SnapshotState};
+
+/// An epoch whose replayed hash is not among the database's hashes for it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotDivergence {
+    pub epoch: u64,
+    pub replayed_hash: String,
+    pub database_hashes: Vec<String>,
+}
+
+/// A database snapshot the replay did not produce
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtraSnapshot {
+    pub epoch: u64,
+    pub hash: Option<String>,
+}
+
+/// Differences between replayed state and the database, ordered by epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayReport {
+    /// Ledger the replayed state reached
+    pub ledger: u64,
+    /// Hash of the replayed state
+    pub state_hash: String,
+    /// Snapshots produced by the replay
+    pub replayed_snapshots: usize,
+    /// Database epochs compared against
+    pub database_snapshots: usize,
+    /// Replayed snapshots with no database row
+    pub missing: Vec<SnapshotState>,
+    /// Epochs present on both sides with different hashes
+    pub divergent: Vec<SnapshotDivergence>,
+    /// Database rows inside the replayed epoch window that the replay did not produce
+    pub extra: Vec<ExtraSnapshot>,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl ReplayReport {
+    /// True when the database matches the replay
+    #[must_use]
+    pub const fn is_consistent(&self) -> bool {
+        self.missing.is_empty() && self.divergent.is_empty() && self.extra.is_empty()
+    }
+}
+
+/// Diff `state` against the `snapshots` table.
+///
+/// Database rows are only compared up to the highest replayed epoch, and from
+/// the lowest one unless the replay started at genesis, so a partial replay
+/// does not report everything outside its range as extra.
+pub async fn diff_against_database(
+    state: &ApplicationState,
+    pool: &SqlitePool,
+    from_genesis: bool,
+) -> Result<ReplayReport> {
+    let lowest = if from_genesis {
+        0
+    } else {
+        state.snapshots.keys().min().copied().unwrap_or(0)
+    };
+    let highest = state.snapshots.keys().max().copied();
+
+    let mut database: BTreeMap<u64, BTreeSet<Option<String>>> = BTreeMap::new();
+    if let Some(highest) = highest {
+        let rows: Vec<(i64, Option<String>)> = sqlx::query_as(
+            r"
+            SELECT DISTINCT epoch, hash FROM snapshots
+            WHERE epoch IS NOT NULL AND epoch >= $1 AND epoch <= $2
+            ",
+        )
+        .bind(i64::try_from(lowest).unwrap_or(i64::MAX))
+        .bind(i64::try_from(highest).unwrap_or(i64::MAX))
+        .fetch_all(pool)
+        .await
+        .context("Failed to load snapshots for replay diff")?;
+
+        for (epoch, hash) in rows {
+            let epoch = u64::try_from(epoch).unwrap_or_default();
+            database.entry(epoch).or_default().insert(hash);
+        }
+    }
+
+    let mut replayed: Vec<&SnapshotState> = state.snapshots.values().collect();
+    replayed.sort_by_key(|snapshot| snapshot.epoch);
+
+    let mut missing = Vec::new();
+    let mut divergent = Vec::new();
+    for snapshot in replayed {
+        match database.get(&snapshot.epoch) {
+            None => missing.push(snapshot.clone()),
+            Some(hashes) if !hashes.contains(&Some(snapshot.hash.clone())) => {
+                divergent.push(SnapshotDivergence {
+                    epoch: snapshot.epoch,
+                    replayed_hash: snapshot.hash.clone(),
+                    database_hashes: hashes.iter().flatten().cloned().collect(),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    let extra = database
+        .iter()
+        .filter(|(epoch, _)| !state.snapshots.contains_key(epoch))
+        .flat_map(|(epoch, hashes)| {
+            hashes.iter().map(|hash| ExtraSnapshot {
+                epoch: *epoch,
+                hash: hash.clone(),
+            })
+        })
+        .collect();
+
+    Ok(ReplayReport {
+        ledger: state.ledger,
+        state_hash: state.compute_hash(),
+        replayed_snapshots: state.snapshots.len(),
+        database_snapshots: database.len(),
+        missing,
+        divergent,
+        extra,
+        generated_at: Utc::now(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations};
+
+    fn snapshot(epoch: u64, hash: &str) -> SnapshotState {
+        SnapshotState {
+            epoch,
+            hash: hash.to_string(),
+            ledger: 100 + epoch,
+            transaction_hash: format!("tx-{epoch}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_diff_reports_missing_divergent_and_extra() {
+        let pool = test_support::sqlite_pool(&[migrations::REPLAY_TABLES]).await;
+        for (epoch, hash) in [(1, "a"), (2, "stale"), (4, "d"), (9, "later")] {
+            sqlx::query("INSERT INTO snapshots (epoch, hash) VALUES ($1, $2)")
+                .bind(epoch)
+                .bind(hash)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let mut state = ApplicationState::at_ledger(110);
+        for (epoch, hash) in [(1, "a"), (2, "b"), (3, "c"), (5, "e")] {
+            state.snapshots.insert(epoch, snapshot(epoch, hash));
+        }
+
+        let report = diff_against_database(&state, &pool, true).await.unwrap();
+        assert!(!report.is_consistent());
+        assert_eq!(report.replayed_snapshots, 4);
+        assert_eq!(report.database_snapshots, 3);
+        assert_eq!(
+            report.missing.iter().map(|s| s.epoch).collect::<Vec<_>>(),
+            [3, 5]
+        );
+        assert_eq!(
+            report.divergent,
+            [SnapshotDivergence {
+                epoch: 2,
+                replayed_hash: "b".to_string(),
+                database_hashes: vec!["stale".to_string()],
+            }]
+        );
+        // Epoch 9 is past the replayed window and not reported.
+        assert_eq!(
+            report.extra,
+            [ExtraSnapshot {
+                epoch: 4,
+                hash: Some("d".to_string()),
+            }]
+        );
+
+        // Nothing was written.
+        let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM snapshots")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(rows, 4);
+    }
+}
@@ -5,29 +5,114 @@
 
 use anyhow::{Context, Result};
 use chrono::Utc;
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, OnceLock};
 use std::time::Instant;
-use tokio::sync::RwLock;
-use tracing::{error, info, warn};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::{watch, Notify, RwLock};
+use tokio::task::JoinSet;
 
 use super::{
-    checkpoint::{Checkpoint, CheckpointManager},
+    checkpoint::{Checkpoint, CheckpointManager, EXTERNAL_STATE_METADATA},
     config::{ReplayConfig, ReplayMode},
-    event_processor::{CompositeEventProcessor, ProcessingContext},
+    event_processor::{
+        CompositeEventProcessor, EventProcessor, ProcessingContext, ProcessingResult,
+    },
+    integrity::AnchoredSnapshots,
+    progress::{ProgressHub, ReplayProgress},
+    source::EventSource,
     state_builder::StateBuilder,
     storage::{EventStorage, ReplayStorage},
-    ContractEvent, ReplayError, ReplayMetadata, ReplayResult, ReplayStatus,
+    stream::EventStream,
+    ContractEvent, EventFilter, EventTypeMetrics, ReplayError, ReplayMetadata, ReplayResult,
+    ReplayStatus,
 };
+use crate::log_event;
+use crate::logging::Subsystem;
+
+/// Lane for a contract's events. FNV-1a rather than the std hasher so the
+/// assignment is stable across builds and runs.
+fn lane_for(contract_id: &str, lanes: usize) -> usize {
+    let hash = contract_id
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    // The remainder is below `lanes`, so it always fits.
+    usize::try_from(hash % lanes as u64).unwrap_or_default()
+}
+
+/// How far processing has got: the highest ledger seen and the events already
+/// handled at it. Storage and the live feed can both deliver an event near the
+/// switch-over, and this is what keeps it from being processed twice.
+#[derive(Debug)]
+struct Handover {
+    ledger: u64,
+    seen: HashSet<String>,
+}
+
+impl Handover {
+    fn admits(&self, event: &ContractEvent) -> bool {
+        event.ledger_sequence > self.ledger
+            || (event.ledger_sequence == self.ledger && !self.seen.contains(&event.id))
+    }
+
+    fn record(&mut self, event: &ContractEvent) {
+        if event.ledger_sequence > self.ledger {
+            self.ledger = event.ledger_sequence;
+            self.seen.clear();
+        }
+        if event.ledger_sequence == self.ledger {
+            self.seen.insert(event.id.clone());
+        }
+    }
+}
+
+/// What an operator has asked of a running session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Run,
+    Pause,
+    Cancel,
+}
+
+/// Last completed ledger and event counts recorded in a status
+const fn progress_of(status: &ReplayStatus) -> (u64, u64, u64) {
+    match *status {
+        ReplayStatus::InProgress {
+            current_ledger,
+            events_processed,
+            events_failed,
+        } => (
+            current_ledger.saturating_sub(1),
+            events_processed,
+            events_failed,
+        ),
+        ReplayStatus::Paused {
+            last_ledger,
+            events_processed,
+        } => (last_ledger, events_processed, 0),
+        _ => (0, 0, 0),
+    }
+}
 
 /// Main replay engine
 pub struct ReplayEngine {
     config: ReplayConfig,
+    /// The configured filter, restricted to the session's network
+    filter: EventFilter,
     event_storage: Arc<EventStorage>,
+    source: Arc<dyn EventSource>,
     replay_storage: Arc<ReplayStorage>,
     checkpoint_manager: Arc<CheckpointManager>,
     processor: Arc<CompositeEventProcessor>,
     state_builder: Arc<RwLock<StateBuilder>>,
     session_id: String,
+    stop_following: Notify,
+    progress: Option<Arc<ProgressHub>>,
+    anchors: Option<Arc<dyn AnchoredSnapshots>>,
+    range: OnceLock<(u64, u64)>,
+    control: watch::Sender<Control>,
 }
 
 impl ReplayEngine {
@@ -45,24 +130,85 @@ impl ReplayEngine {
             .validate()
             .map_err(|e| ReplayError::ConfigError(e.to_string()))?;
 
+        if config.mode == ReplayMode::CatchUpThenFollow && event_storage.subscribe().is_none() {
+            return Err(ReplayError::ConfigError(
+                "CatchUpThenFollow needs an event storage with a live feed".to_string(),
+            )
+            .into());
+        }
+
         let session_id = uuid::Uuid::new_v4().to_string();
 
         Ok(Self {
+            filter: config.event_filter(),
             config,
+            source: Arc::clone(&event_storage) as Arc<dyn EventSource>,
             event_storage,
             replay_storage,
             checkpoint_manager,
             processor,
             state_builder,
             session_id,
+            stop_following: Notify::new(),
+            progress: None,
+            anchors: None,
+            range: OnceLock::new(),
+            control: watch::channel(Control::Run).0,
         })
     }
 
+    /// ID of the session this engine records progress under
+    #[must_use]
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Add a processor on top of the ones passed to [`Self::new`]. It receives
+    /// the events matching its [`EventProcessor::subscriptions`].
+    #[must_use]
+    pub fn with_processor(mut self, processor: Box<dyn EventProcessor>) -> Self {
+        Arc::make_mut(&mut self.processor).register(Arc::from(processor));
+        self
+    }
+
+    /// Read historical events from `source` instead of the event storage.
+    /// Following live events in `CatchUpThenFollow` still uses the storage.
+    #[must_use]
+    pub fn with_event_source(mut self, source: Arc<dyn EventSource>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Publish the session's status to `progress` whenever it is saved. The
+    /// session counts as running there from now until it finishes.
+    #[must_use]
+    pub fn with_progress(mut self, progress: Arc<ProgressHub>) -> Self {
+        progress.track(&self.session_id);
+        self.progress = Some(progress);
+        self
+    }
+
+    /// After a replay that runs to completion, check the epochs it rebuilt
+    /// against the hashes anchored in `anchors` and record the result in the
+    /// session's `integrity` report.
+    #[must_use]
+    pub fn with_integrity_check(mut self, anchors: Arc<dyn AnchoredSnapshots>) -> Self {
+        self.anchors = Some(anchors);
+        self
+    }
+
     /// Start the replay process
     pub async fn start(&self) -> ReplayResult<ReplayMetadata> {
-        info!(
-            "Starting replay session {} with mode: {}",
-            self.session_id, self.config.mode
+        self.check_state_builder().await?;
+
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.session_started",
+            session_id = %self.session_id,
+            mode = %self.config.mode,
+            source = self.source.name(),
+            "Starting replay session"
         );
 
         // Create initial metadata
@@ -73,18 +219,59 @@ impl ReplayEngine {
             started_at: Utc::now(),
             ended_at: None,
             checkpoint: None,
+            report: None,
+            integrity: None,
+            event_types: BTreeMap::new(),
         };
 
         // Save initial metadata
-        self.replay_storage
-            .save_metadata(&metadata)
+        self.save_metadata(&metadata)
             .await
             .map_err(ReplayError::StorageError)?;
 
-        // Determine start and end ledgers
-        let (start_ledger, end_ledger) = self.determine_ledger_range().await?;
+        // Subscribe before reading the chain head: anything stored after that
+        // read is then guaranteed to arrive on the feed.
+        let feed = if self.config.mode == ReplayMode::CatchUpThenFollow {
+            self.event_storage.subscribe()
+        } else {
+            None
+        };
 
-        info!("Replay range: ledger {} to {}", start_ledger, end_ledger);
+        // Determine start and end ledgers. The session is over if that fails,
+        // so record it as such rather than leaving it pending.
+        let (start_ledger, end_ledger) = match self.determine_ledger_range().await {
+            Ok(range) => range,
+            Err(e) => {
+                log_event!(
+                    error,
+                    Subsystem::Replay,
+                    "replay.range_failed",
+                    session_id = %self.session_id,
+                    error = %e,
+                    "Failed to determine replay range"
+                );
+                metadata.status = ReplayStatus::Failed {
+                    error: e.to_string(),
+                    last_ledger: None,
+                };
+                metadata.ended_at = Some(Utc::now());
+                self.save_metadata(&metadata)
+                    .await
+                    .map_err(ReplayError::StorageError)?;
+                return Err(e.into());
+            }
+        };
+
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.range_resolved",
+            session_id = %self.session_id,
+            start_ledger,
+            end_ledger,
+            "Replay range resolved"
+        );
+        let _ = self.range.set((start_ledger, end_ledger));
 
         // Update status to in progress
         metadata.status = ReplayStatus::InProgress {
@@ -92,17 +279,44 @@ impl ReplayEngine {
             events_processed: 0,
             events_failed: 0,
         };
-        self.replay_storage
-            .save_metadata(&metadata)
+        self.save_metadata(&metadata)
             .await
             .map_err(ReplayError::StorageError)?;
 
         // Execute replay
         let start_time = Instant::now();
-        match self
-            .execute_replay(start_ledger, end_ledger, &mut metadata)
+        let outcome = self
+            .execute_replay(start_ledger, end_ledger, &mut metadata, feed)
+            .await;
+        self.conclude(outcome, start_time, &mut metadata).await;
+
+        // Save final metadata
+        self.save_metadata(&metadata)
             .await
-        {
+            .map_err(ReplayError::StorageError)?;
+
+        Ok(metadata)
+    }
+
+    /// Record how the replay ended in the session's metadata
+    async fn conclude(
+        &self,
+        outcome: Result<(u64, u64)>,
+        start_time: Instant,
+        metadata: &mut ReplayMetadata,
+    ) {
+        match outcome {
+            Ok(_) if matches!(metadata.status, ReplayStatus::Cancelled { .. }) => {
+                metadata.ended_at = Some(Utc::now());
+                log_event!(
+                    info,
+                    Subsystem::Replay,
+                    "replay.session_cancelled",
+                    session_id = %self.session_id,
+                    status = %metadata.status,
+                    "Replay cancelled"
+                );
+            }
             Ok((processed, failed)) => {
                 let duration = start_time.elapsed().as_secs();
                 metadata.status = ReplayStatus::Completed {
@@ -112,28 +326,52 @@ impl ReplayEngine {
                 };
                 metadata.ended_at = Some(Utc::now());
 
-                info!(
-                    "Replay completed: {} events processed, {} failed in {}s",
-                    processed, failed, duration
+                log_event!(
+                    info,
+                    Subsystem::Replay,
+                    "replay.session_completed",
+                    session_id = %self.session_id,
+                    events_processed = processed,
+                    events_failed = failed,
+                    duration_secs = duration,
+                    "Replay completed"
                 );
             }
             Err(e) => {
-                error!("Replay failed: {}", e);
+                log_event!(
+                    error,
+                    Subsystem::Replay,
+                    "replay.session_failed",
+                    session_id = %self.session_id,
+                    error = %e,
+                    "Replay failed"
+                );
                 metadata.status = ReplayStatus::Failed {
                     error: e.to_string(),
-                    last_ledger: self.get_current_ledger(&metadata).await,
+                    last_ledger: self.get_current_ledger(metadata).await,
                 };
                 metadata.ended_at = Some(Utc::now());
             }
         }
+    }
 
-        // Save final metadata
-        self.replay_storage
-            .save_metadata(&metadata)
-            .await
-            .map_err(ReplayError::StorageError)?;
-
-        Ok(metadata)
+    /// The state builder must build the state of the session's network and filter
+    async fn check_state_builder(&self) -> ReplayResult<()> {
+        let state_builder = self.state_builder.read().await;
+        if state_builder.network() != self.config.network {
+            return Err(ReplayError::ConfigError(format!(
+                "State builder is for {}, but the session replays {}",
+                state_builder.network(),
+                self.config.network
+            )));
+        }
+        if state_builder.scope() != self.filter.state_scope() {
+            return Err(ReplayError::ConfigError(
+                "State builder is for another contract and event type filter than the session's"
+                    .to_string(),
+            ));
+        }
+        Ok(())
     }
 
     /// Execute the replay process
@@ -142,64 +380,65 @@ impl ReplayEngine {
         start_ledger: u64,
         end_ledger: u64,
         metadata: &mut ReplayMetadata,
+        feed: Option<broadcast::Receiver<ContractEvent>>,
     ) -> Result<(u64, u64)> {
         let mut current_ledger = start_ledger;
         let mut total_processed = 0u64;
         let mut total_failed = 0u64;
+        let mut handover = Handover {
+            ledger: start_ledger,
+            seen: HashSet::new(),
+        };
 
         // Create processing context
-        let context = ProcessingContext::for_replay(self.session_id.clone(), self.config.dry_run);
+        let context =
+            ProcessingContext::for_replay(self.session_id.clone(), self.config.is_dry_run());
 
-        while current_ledger <= end_ledger {
-            // Fetch batch of events
-            let batch_end = (current_ledger + self.config.batch_size as u64 - 1).min(end_ledger);
+        let mut stream = self.stream(start_ledger, end_ledger);
 
-            info!(
-                "Processing ledgers {} to {} (batch size: {})",
-                current_ledger, batch_end, self.config.batch_size
-            );
+        while current_ledger <= end_ledger {
+            if self.honour_control(metadata).await? {
+                break;
+            }
 
-            let events = self
-                .event_storage
-                .get_events_in_range(
-                    current_ledger,
-                    batch_end,
-                    &self.config.filter,
-                    Some(self.config.batch_size),
-                )
+            let batch = stream
+                .next()
                 .await
-                .context("Failed to fetch events")?;
+                .with_context(|| format!("Event stream ended before ledger {current_ledger}"))??;
 
-            info!("Fetched {} events in batch", events.len());
+            log_event!(
+                info,
+                Subsystem::Replay,
+                "replay.batch_started",
+                session_id = %self.session_id,
+                start_ledger = batch.start,
+                end_ledger = batch.end,
+                events = batch.events.len(),
+                "Processing ledger batch"
+            );
 
-            // Process events
-            for event in &events {
-                match self.process_event(event, &context).await {
-                    Ok(result) => {
-                        if result.success {
-                            total_processed += 1;
-
-                            // Apply to state builder
-                            if self.config.mode == ReplayMode::Full
-                                || self.config.mode == ReplayMode::Verification
-                            {
-                                let mut state_builder = self.state_builder.write().await;
-                                state_builder.apply_event(event).await?;
-                            }
-                        } else {
-                            total_failed += 1;
-                            warn!("Event {} failed: {:?}", event.unique_id(), result.error);
-                        }
-                    }
-                    Err(e) => {
-                        total_failed += 1;
-                        error!("Error processing event {}: {}", event.unique_id(), e);
-                    }
-                }
+            let (processed, failed) = self
+                .process_batch(&batch.events, &context, &mut metadata.event_types)
+                .await?;
+            total_processed += processed;
+            total_failed += failed;
+            for event in &batch.events {
+                handover.record(event);
             }
+            // Hand the batch's share of the budget back to the fetcher
+            let reached = batch.end;
+            drop(batch);
+            self.maybe_snapshot(current_ledger.saturating_sub(1), reached)
+                .await?;
+            self.enforce_state_budget(reached).await?;
+
+            // Batches end on whole ledgers rather than a fixed stride, so
+            // checkpoint when one crosses an interval boundary.
+            let interval = self.config.checkpoint_interval;
+            let crossed = current_ledger / interval != (reached + 1) / interval;
 
             // Update current ledger
-            current_ledger = batch_end + 1;
+            current_ledger = reached + 1;
 
             // Update metadata
             metadata.status = ReplayStatus::InProgress {
@@ -208,35 +447,486 @@ impl ReplayEngine {
                 events_failed: total_failed,
             };
 
-            // Checkpoint if needed
-            if current_ledger.is_multiple_of(self.config.checkpoint_interval) {
+            // Every lane of the batch has joined by now, so nothing at or below
+            // this ledger is still in flight.
+            if crossed {
                 self.create_checkpoint(current_ledger, total_processed, total_failed, metadata)
                     .await?;
             }
 
             // Save metadata periodically
-            self.replay_storage.save_metadata(metadata).await?;
+            self.save_metadata(metadata).await?;
         }
 
-        // Final checkpoint
-        self.create_checkpoint(end_ledger, total_processed, total_failed, metadata)
+        // Final checkpoint, at the last ledger processed
+        let cancelled = matches!(metadata.status, ReplayStatus::Cancelled { .. });
+        let last_ledger = if cancelled {
+            current_ledger.saturating_sub(1)
+        } else {
+            end_ledger
+        };
+        self.create_checkpoint(last_ledger, total_processed, total_failed, metadata)
             .await?;
 
+        if let Some(feed) = feed.filter(|_| !cancelled) {
+            log_event!(
+                info,
+                Subsystem::Replay,
+                "replay.following_live",
+                session_id = %self.session_id,
+                ledger = end_ledger,
+                "Caught up, following live events"
+            );
+            let mut totals = (total_processed, total_failed);
+            self.follow(feed, &mut handover, &context, &mut totals, metadata)
+                .await?;
+            (total_processed, total_failed) = totals;
+
+            let last_ledger = handover.ledger.max(end_ledger);
+            self.create_checkpoint(last_ledger, total_processed, total_failed, metadata)
+                .await?;
+        }
+
         // Persist final state
-        if !self.config.dry_run {
+        if !self.config.is_dry_run() {
             let state_builder = self.state_builder.read().await;
             state_builder.persist_state().await?;
+            if self.config.snapshot_interval > 0 {
+                state_builder
+                    .compact(self.config.snapshot_retention)
+                    .await?;
+            }
+        }
+
+        // A partial replay would report every epoch it did not reach.
+        if !cancelled {
+            self.diff_against_database(start_ledger, metadata).await?;
+            self.check_integrity(start_ledger, metadata).await?;
         }
 
         Ok((total_processed, total_failed))
     }
 
+    /// Compare the rebuilt state with the stored snapshots, in diff mode
+    async fn diff_against_database(
+        &self,
+        start_ledger: u64,
+        metadata: &mut ReplayMetadata,
+    ) -> Result<()> {
+        if self.config.mode != ReplayMode::Diff {
+            return Ok(());
+        }
+        let report = self
+            .state_builder
+            .read()
+            .await
+            .diff_against_database(start_ledger == 0)
+            .await?;
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.diff_completed",
+            session_id = %self.session_id,
+            missing = report.missing.len(),
+            divergent = report.divergent.len(),
+            extra = report.extra.len(),
+            "Diff against database complete"
+        );
+        metadata.report = Some(report);
+        Ok(())
+    }
+
+    /// Compare the epochs replayed from `from_ledger` on with their anchored
+    /// hashes, if the engine was given an integrity check
+    async fn check_integrity(&self, from_ledger: u64, metadata: &mut ReplayMetadata) -> Result<()> {
+        let Some(anchors) = self.anchors.as_deref() else {
+            return Ok(());
+        };
+        let report = self
+            .state_builder
+            .read()
+            .await
+            .verify_against_chain(anchors, from_ledger)
+            .await?;
+        if report.is_consistent() {
+            log_event!(
+                info,
+                Subsystem::Replay,
+                "replay.integrity_verified",
+                session_id = %self.session_id,
+                epochs_checked = report.epochs_checked,
+                "All replayed epochs match their anchored hashes"
+            );
+        } else {
+            log_event!(
+                warn,
+                Subsystem::Replay,
+                "replay.integrity_failed",
+                session_id = %self.session_id,
+                failures = report.failures.len(),
+                epochs_checked = report.epochs_checked,
+                "Replayed epochs do not match their anchored hashes"
+            );
+        }
+        metadata.integrity = Some(report);
+        Ok(())
+    }
+
+    /// Process live events until [`Self::stop_following`] is called or the feed
+    /// closes. If the feed drops events because this session fell behind, the
+    /// missing range is read back from storage.
+    async fn follow(
+        &self,
+        mut feed: broadcast::Receiver<ContractEvent>,
+        handover: &mut Handover,
+        context: &ProcessingContext,
+        totals: &mut (u64, u64),
+        metadata: &mut ReplayMetadata,
+    ) -> Result<()> {
+        // Events stored while the historical replay ran are still buffered.
+        self.catch_up(handover, context, totals, metadata).await?;
+        let mut control = self.control.subscribe();
+
+        loop {
+            let received = tokio::select! {
+                () = self.stop_following.notified() => return Ok(()),
+                _ = control.changed() => {
+                    if self.honour_control(metadata).await? {
+                        return Ok(());
+                    }
+                    continue;
+                }
+                received = feed.recv() => received,
+            };
+
+            let event = match received {
+                Ok(event) => event,
+                Err(RecvError::Lagged(missed)) => {
+                    log_event!(
+                        warn,
+                        Subsystem::Replay,
+                        "replay.live_feed_lagged",
+                        session_id = %self.session_id,
+                        missed,
+                        ledger = handover.ledger,
+                        "Live feed dropped events, reading them from storage"
+                    );
+                    self.catch_up(handover, context, totals, metadata).await?;
+                    continue;
+                }
+                Err(RecvError::Closed) => return Ok(()),
+            };
+
+            if !event.matches_filter(&self.filter) || !handover.admits(&event) {
+                continue;
+            }
+
+            let previous_ledger = handover.ledger;
+            if event.ledger_sequence > previous_ledger {
+                // Everything up to the previous ledger is applied.
+                self.maybe_snapshot(previous_ledger, event.ledger_sequence - 1)
+                    .await?;
+            }
+            let (processed, failed) = self
+                .process_batch(
+                    std::slice::from_ref(&event),
+                    context,
+                    &mut metadata.event_types,
+                )
+                .await?;
+            totals.0 += processed;
+            totals.1 += failed;
+            handover.record(&event);
+
+            if handover.ledger > previous_ledger {
+                metadata.status = ReplayStatus::InProgress {
+                    current_ledger: handover.ledger,
+                    events_processed: totals.0,
+                    events_failed: totals.1,
+                };
+                // Ledgers arrive in order, so the previous one is complete.
+                if previous_ledger / self.config.checkpoint_interval
+                    != handover.ledger / self.config.checkpoint_interval
+                {
+                    self.create_checkpoint(previous_ledger, totals.0, totals.1, metadata)
+                        .await?;
+                }
+                self.save_metadata(metadata).await?;
+            }
+        }
+    }
+
+    /// Process stored events from the handover ledger up to the current head
+    /// that have not been processed yet.
+    async fn catch_up(
+        &self,
+        handover: &mut Handover,
+        context: &ProcessingContext,
+        totals: &mut (u64, u64),
+        metadata: &mut ReplayMetadata,
+    ) -> Result<()> {
+        let head = self.event_storage.get_latest_ledger().await?.unwrap_or(0);
+        let mut from = handover.ledger;
+
+        while from <= head {
+            let to = (from + self.config.batch_size as u64 - 1).min(head);
+            let events: Vec<ContractEvent> = self
+                .event_storage
+                .get_events_in_range(from, to, &self.filter, None)
+                .await
+                .context("Failed to fetch events")?
+                .into_iter()
+                .filter(|event| handover.admits(event))
+                .collect();
+
+            let (processed, failed) = self
+                .process_batch(&events, context, &mut metadata.event_types)
+                .await?;
+            totals.0 += processed;
+            totals.1 += failed;
+            for event in &events {
+                handover.record(event);
+            }
+
+            from = to + 1;
+        }
+
+        Ok(())
+    }
+
+    /// Save session metadata and publish it to progress subscribers
+    async fn save_metadata(&self, metadata: &ReplayMetadata) -> Result<()> {
+        self.replay_storage.save_metadata(metadata).await?;
+        if let Some(progress) = &self.progress {
+            progress.publish(ReplayProgress::from_metadata(
+                metadata,
+                self.range.get().copied(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Stop a `CatchUpThenFollow` session after the event in progress; the
+    /// session then completes normally.
+    pub fn stop_following(&self) {
+        self.stop_following.notify_one();
+    }
+
+    /// Process one fetched batch and apply the successful events to the state
+    /// builder in fetch order, whichever way the processing itself was scheduled.
+    /// Failed events are quarantined as dead letters rather than dropped.
+    async fn process_batch(
+        &self,
+        events: &[ContractEvent],
+        context: &ProcessingContext,
+        event_types: &mut BTreeMap<String, EventTypeMetrics>,
+    ) -> Result<(u64, u64)> {
+        let outcomes = if self.config.concurrency > 1 && events.len() > 1 {
+            self.process_lanes(events, context).await?
+        } else {
+            let mut outcomes = Vec::with_capacity(events.len());
+            for event in events {
+                outcomes.push(self.process_event(event, context).await);
+            }
+            outcomes
+        };
+
+        let mut processed = 0u64;
+        let mut failed = 0u64;
+        for (event, outcome) in events.iter().zip(outcomes) {
+            let metrics = event_types.entry(event.event_type.clone()).or_default();
+            let error = match outcome {
+                Ok(result) if result.success => {
+                    processed += 1;
+                    if result.skipped {
+                        metrics.skipped += 1;
+                    } else {
+                        metrics.processed += 1;
+                    }
+
+                    // Apply to state builder
+                    if self.applies_state() {
+                        let mut state_builder = self.state_builder.write().await;
+                        state_builder.apply_event(event).await?;
+                    }
+                    continue;
+                }
+                Ok(result) => {
+                    log_event!(
+                        warn,
+                        Subsystem::Replay,
+                        "replay.event_failed",
+                        session_id = %self.session_id,
+                        event_id = %event.unique_id(),
+                        error = ?result.error,
+                        "Event failed"
+                    );
+                    result.error.unwrap_or_else(|| "Unknown error".to_string())
+                }
+                Err(e) => {
+                    log_event!(
+                        error,
+                        Subsystem::Replay,
+                        "replay.event_errored",
+                        session_id = %self.session_id,
+                        event_id = %event.unique_id(),
+                        error = %e,
+                        "Error processing event"
+                    );
+                    e.to_string()
+                }
+            };
+
+            failed += 1;
+            metrics.failed += 1;
+            self.replay_storage
+                .record_dead_letter(&self.session_id, event, &error)
+                .await?;
+        }
+
+        Ok((processed, failed))
+    }
+
+    /// Split a batch into lanes by contract and process the lanes as parallel
+    /// tasks. Each lane is sequential, so a contract's events are never reordered.
+    /// Returns the outcomes in the order of `events`.
+    async fn process_lanes(
+        &self,
+        events: &[ContractEvent],
+        context: &ProcessingContext,
+    ) -> Result<Vec<Result<ProcessingResult>>> {
+        let lane_count = self.config.concurrency.min(events.len());
+        let mut lanes: Vec<Vec<(usize, ContractEvent)>> = vec![Vec::new(); lane_count];
+        for (index, event) in events.iter().enumerate() {
+            lanes[lane_for(&event.contract_id, lane_count)].push((index, event.clone()));
+        }
+
+        let mut tasks = JoinSet::new();
+        for lane in lanes.into_iter().filter(|lane| !lane.is_empty()) {
+            let processor = Arc::clone(&self.processor);
+            let context = context.clone();
+            let max_retries = self.config.max_retries;
+            tasks.spawn(async move {
+                let mut outcomes = Vec::with_capacity(lane.len());
+                for (index, event) in lane {
+                    let outcome = processor
+                        .process_with_retry(&event, &context, max_retries)
+                        .await;
+                    outcomes.push((index, outcome));
+                }
+                outcomes
+            });
+        }
+
+        let mut outcomes = Vec::with_capacity(events.len());
+        while let Some(lane) = tasks.join_next().await {
+            outcomes.extend(lane.context("Replay worker task failed")?);
+        }
+        outcomes.sort_by_key(|(index, _)| *index);
+
+        Ok(outcomes.into_iter().map(|(_, outcome)| outcome).collect())
+    }
+
+    /// Whether successful events are applied to the state builder
+    const fn applies_state(&self) -> bool {
+        matches!(
+            self.config.mode,
+            ReplayMode::Full
+                | ReplayMode::Verification
+                | ReplayMode::Diff
+                | ReplayMode::CatchUpThenFollow
+        )
+    }
+
+    /// Persist a state snapshot and compact older ones when processing has
+    /// moved from `completed` to `reached` across a snapshot boundary. State
+    /// must be complete up to `reached`.
+    async fn maybe_snapshot(&self, completed: u64, reached: u64) -> Result<()> {
+        let interval = self.config.snapshot_interval;
+        if interval == 0
+            || self.config.is_dry_run()
+            || !self.applies_state()
+            || completed / interval == reached / interval
+        {
+            return Ok(());
+        }
+
+        let state_builder = self.state_builder.read().await;
+        state_builder.persist_state().await?;
+        state_builder
+            .compact(self.config.snapshot_retention)
+            .await?;
+        drop(state_builder);
+
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.state_snapshot_saved",
+            session_id = %self.session_id,
+            ledger = reached,
+            "Saved state snapshot"
+        );
+        Ok(())
+    }
+
+    /// Stream `start..=end` from the event source, fetching ahead of
+    /// processing as far as the memory budget allows
+    fn stream(&self, start: u64, end: u64) -> EventStream {
+        EventStream::spawn(
+            Arc::clone(&self.source),
+            self.filter.clone(),
+            (start, end),
+            self.config.batch_size,
+            self.config.event_budget_bytes(),
+        )
+    }
+
+    /// Once the rebuilt state outgrows its share of the memory budget, move
+    /// its entries to the database, from where they are read back as events
+    /// refer to them. A replay that must not write cannot do that, and stops
+    /// at `reached` instead.
+    async fn enforce_state_budget(&self, reached: u64) -> Result<()> {
+        let size = self.state_builder.read().await.state().estimated_size();
+        let budget = self.config.state_budget_bytes();
+        if size <= budget {
+            return Ok(());
+        }
+
+        if self.config.is_dry_run() {
+            anyhow::bail!(
+                "Replay state at ledger {reached} needs about {} MiB, over its {} MiB share of \
+                 the memory budget, and a {} replay cannot move it out of memory; raise \
+                 memory_budget_mb or narrow the range or filter",
+                size.div_ceil(1024 * 1024),
+                budget / (1024 * 1024),
+                if self.config.mode == ReplayMode::Diff {
+                    "diff"
+                } else {
+                    "dry-run"
+                }
+            );
+        }
+
+        let evicted = self.state_builder.write().await.evict().await?;
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.state_spilled",
+            session_id = %self.session_id,
+            ledger = reached,
+            budget_mib = budget / (1024 * 1024),
+            evicted,
+            "Replay state outgrew its share of the memory budget, moved entries to the database"
+        );
+        Ok(())
+    }
+
     /// Process a single event
     async fn process_event(
         &self,
         event: &ContractEvent,
         context: &ProcessingContext,
-    ) -> Result<super::event_processor::ProcessingResult> {
+    ) -> Result<ProcessingResult> {
         self.processor
             .process_with_retry(event, context, self.config.max_retries)
             .await
@@ -250,17 +940,32 @@ impl ReplayEngine {
         failed: u64,
         metadata: &mut ReplayMetadata,
     ) -> Result<()> {
-        info!("Creating checkpoint at ledger {}", ledger);
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.checkpoint_creating",
+            session_id = %self.session_id,
+            ledger,
+            "Creating checkpoint"
+        );
 
         // Get current state
         let state_builder = self.state_builder.read().await;
         let state_json = state_builder.state().to_json()?;
 
         // Create checkpoint
-        let checkpoint = Checkpoint::new(self.session_id.clone(), ledger)
+        let mut checkpoint = Checkpoint::new(self.session_id.clone(), ledger)
+            .with_network(self.config.network)
             .with_stats(processed, failed)
             .with_state(state_json)
             .with_metadata("mode".to_string(), self.config.mode.to_string());
+        if state_builder.has_evicted_entries() {
+            checkpoint = checkpoint.with_metadata(
+                EXTERNAL_STATE_METADATA.to_string(),
+                "replay_state_entries".to_string(),
+            );
+        }
+        drop(state_builder);
 
         // Save checkpoint
         self.checkpoint_manager.save(&checkpoint).await?;
@@ -273,22 +978,61 @@ impl ReplayEngine {
 
     /// Determine the ledger range for replay
     async fn determine_ledger_range(&self) -> Result<(u64, u64)> {
-        let latest_ledger = self.event_storage.get_latest_ledger().await?.unwrap_or(0);
+        if let Some((start, end)) = self.config.range.time_window() {
+            return self
+                .source
+                .ledgers_between(start, end)
+                .await?
+                .with_context(|| {
+                    format!(
+                        "No ledgers in {} between {} and {}",
+                        self.source.name(),
+                        start.to_rfc3339(),
+                        end.to_rfc3339()
+                    )
+                });
+        }
+
+        let latest_ledger = self.source.get_latest_ledger().await?.unwrap_or(0);
 
         let checkpoint_ledger = if let Some(checkpoint_id) = self.get_checkpoint_id() {
-            self.checkpoint_manager
-                .load(&checkpoint_id)
-                .await?
-                .map(|c| c.last_ledger)
+            let checkpoint = self.checkpoint_manager.load(&checkpoint_id).await?;
+            if let Some(checkpoint) = &checkpoint {
+                if checkpoint.network != self.config.network {
+                    return Err(ReplayError::InvalidCheckpoint(format!(
+                        "Checkpoint {checkpoint_id} is for {}, but the session replays {}",
+                        checkpoint.network, self.config.network
+                    ))
+                    .into());
+                }
+            }
+            checkpoint.map(|c| c.last_ledger)
         } else {
             None
         };
 
-        let start = self
+        let mut start = self
             .config
             .range
             .start_ledger(latest_ledger, checkpoint_ledger)
             .unwrap_or(0);
+
+        // Resume from the nearest state snapshot rather than an empty state.
+        if let Some(checkpoint_ledger) = checkpoint_ledger.filter(|_| self.applies_state()) {
+            let mut state_builder = self.state_builder.write().await;
+            if let Some(snapshot_ledger) = state_builder.load_nearest(checkpoint_ledger).await? {
+                log_event!(
+                    info,
+                    Subsystem::Replay,
+                    "replay.state_snapshot_resumed",
+                    session_id = %self.session_id,
+                    ledger = snapshot_ledger,
+                    checkpoint_ledger,
+                    "Resuming from state snapshot"
+                );
+                start = snapshot_ledger + 1;
+            }
+        }
         let end = self
             .config
             .range
@@ -317,18 +1061,98 @@ impl ReplayEngine {
         }
     }
 
-    /// Pause the replay
-    pub async fn pause(&self) -> Result<()> {
-        info!("Pausing replay session {}", self.session_id);
-        // Implementation would set a flag that the execute loop checks
-        Ok(())
+    /// Pause the replay once the batch in flight is done
+    pub fn pause(&self) {
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.session_pausing",
+            session_id = %self.session_id,
+            "Pausing replay session"
+        );
+        self.control.send_if_modified(|control| {
+            let running = *control == Control::Run;
+            if running {
+                *control = Control::Pause;
+            }
+            running
+        });
     }
 
     /// Resume a paused replay
-    pub async fn resume(&self) -> Result<()> {
-        info!("Resuming replay session {}", self.session_id);
-        // Implementation would clear the pause flag
-        Ok(())
+    pub fn resume(&self) {
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.session_resuming",
+            session_id = %self.session_id,
+            "Resuming replay session"
+        );
+        self.control.send_if_modified(|control| {
+            let paused = *control == Control::Pause;
+            if paused {
+                *control = Control::Run;
+            }
+            paused
+        });
+    }
+
+    /// Stop the replay once the batch in flight is done, paused or not. The
+    /// session ends as `Cancelled` with a checkpoint at its last ledger.
+    pub fn cancel(&self) {
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.session_cancelling",
+            session_id = %self.session_id,
+            "Cancelling replay session"
+        );
+        self.control.send_replace(Control::Cancel);
+    }
+
+    /// Hold the session while it is paused. Returns true once it has been
+    /// cancelled, with `metadata` then carrying the `Cancelled` status.
+    async fn honour_control(&self, metadata: &mut ReplayMetadata) -> Result<bool> {
+        let mut control = self.control.subscribe();
+
+        if *control.borrow_and_update() == Control::Pause {
+            let running = metadata.status.clone();
+            let (last_ledger, events_processed, _) = progress_of(&running);
+            metadata.status = ReplayStatus::Paused {
+                last_ledger,
+                events_processed,
+            };
+            self.save_metadata(metadata).await?;
+            log_event!(
+                info,
+                Subsystem::Replay,
+                "replay.session_paused",
+                session_id = %self.session_id,
+                ledger = last_ledger,
+                "Replay session paused"
+            );
+
+            if control
+                .wait_for(|control| *control != Control::Pause)
+                .await
+                .is_err()
+            {
+                anyhow::bail!("Replay control closed while paused");
+            }
+            metadata.status = running;
+        }
+
+        if *control.borrow() == Control::Cancel {
+            let (last_ledger, events_processed, events_failed) = progress_of(&metadata.status);
+            metadata.status = ReplayStatus::Cancelled {
+                last_ledger,
+                events_processed,
+                events_failed,
+            };
+            return Ok(true);
+        }
+
+        Ok(false)
     }
 
     /// Get replay status
@@ -344,9 +1168,749 @@ impl ReplayEngine {
 mod tests {
     use super::*;
 
+    use crate::network::StellarNetwork;
+    use crate::replay::{EventFilter, ReplayRange};
+    use crate::test_support::{self, migrations};
+    use async_trait::async_trait;
+    use sqlx::SqlitePool;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
     #[test]
     fn test_replay_engine_creation() {
         // Placeholder for actual tests
         // Would require mock implementations of dependencies
     }
+
+    /// Records the order events reach it in; slows down one contract so lanes
+    /// interleave.
+    #[derive(Default)]
+    struct RecordingProcessor {
+        seen: Mutex<Vec<(String, u64)>>,
+    }
+
+    #[async_trait]
+    impl EventProcessor for RecordingProcessor {
+        async fn process_event(
+            &self,
+            event: &ContractEvent,
+            _context: &ProcessingContext,
+        ) -> Result<ProcessingResult> {
+            if event.contract_id == "CSLOW" {
+                tokio::time::sleep(Duration::from_millis(2)).await;
+            }
+            self.seen
+                .lock()
+                .unwrap()
+                .push((event.contract_id.clone(), event.ledger_sequence));
+            Ok(ProcessingResult::success())
+        }
+
+        async fn is_processed(&self, _event: &ContractEvent) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn mark_processed(&self, _event: &ContractEvent) -> Result<()> {
+            Ok(())
+        }
+
+        fn name(&self) -> &'static str {
+            "RecordingProcessor"
+        }
+    }
+
+    /// Lets a test keep a handle on a processor handed to `with_processor`.
+    struct SharedProcessor(Arc<RecordingProcessor>);
+
+    #[async_trait]
+    impl EventProcessor for SharedProcessor {
+        async fn process_event(
+            &self,
+            event: &ContractEvent,
+            context: &ProcessingContext,
+        ) -> Result<ProcessingResult> {
+            self.0.process_event(event, context).await
+        }
+
+        async fn is_processed(&self, event: &ContractEvent) -> Result<bool> {
+            self.0.is_processed(event).await
+        }
+
+        async fn mark_processed(&self, event: &ContractEvent) -> Result<()> {
+            self.0.mark_processed(event).await
+        }
+
+        fn name(&self) -> &str {
+            self.0.name()
+        }
+    }
+
+    async fn pool_with_events(count: u64) -> SqlitePool {
+        let pool = test_support::sqlite_pool(migrations::REPLAY).await;
+
+        let storage = EventStorage::new(pool.clone());
+        for i in 0..count {
+            storage.store_event(&test_event(i)).await.unwrap();
+        }
+        pool
+    }
+
+    fn test_event(i: u64) -> ContractEvent {
+        ContractEvent {
+            id: format!("evt-{i}"),
+            ledger_sequence: 100 + i,
+            transaction_hash: format!("{i:064x}"),
+            tx_index: 0,
+            event_index: 0,
+            contract_id: ["CSLOW", "CFAST", "COTHER"][(i % 3) as usize].to_string(),
+            event_type: "snapshot_submitted".to_string(),
+            data: serde_json::json!({ "epoch": i, "hash": format!("hash-{i}") }),
+            timestamp: Utc::now(),
+            network: "mainnet".to_string(),
+        }
+    }
+
+    async fn replay(
+        pool: &SqlitePool,
+        concurrency: usize,
+        processor: Arc<RecordingProcessor>,
+    ) -> (ReplayMetadata, String) {
+        let config = ReplayConfig::new()
+            .with_range(ReplayRange::FromTo {
+                start: 100,
+                end: 159,
+            })
+            .with_filter(EventFilter::default())
+            .with_batch_size(20)
+            .with_concurrency(concurrency)
+            .dry_run();
+        let state_builder = Arc::new(RwLock::new(StateBuilder::new(
+            pool.clone(),
+            StellarNetwork::Mainnet,
+        )));
+        let engine = ReplayEngine::new(
+            config,
+            Arc::new(EventStorage::new(pool.clone())),
+            Arc::new(ReplayStorage::new(pool.clone())),
+            Arc::new(CheckpointManager::new(pool.clone())),
+            Arc::new(CompositeEventProcessor::new()),
+            Arc::clone(&state_builder),
+        )
+        .unwrap()
+        .with_processor(Box::new(SharedProcessor(processor)));
+
+        let metadata = engine.start().await.unwrap();
+        let state_hash = state_builder.read().await.state().compute_hash();
+        (metadata, state_hash)
+    }
+
+    #[test]
+    fn test_lane_assignment_is_stable() {
+        assert_eq!(lane_for("CSLOW", 4), lane_for("CSLOW", 4));
+        assert!(lane_for("CFAST", 3) < 3);
+        assert_eq!(lane_for("anything", 1), 0);
+    }
+
+    #[tokio::test]
+    async fn test_parallel_replay_keeps_per_contract_order() {
+        let pool = pool_with_events(60).await;
+
+        let sequential = Arc::new(RecordingProcessor::default());
+        let (seq_meta, seq_hash) = replay(&pool, 1, Arc::clone(&sequential)).await;
+        let parallel = Arc::new(RecordingProcessor::default());
+        let (par_meta, par_hash) = replay(&pool, 3, Arc::clone(&parallel)).await;
+
+        for metadata in [&seq_meta, &par_meta] {
+            assert!(matches!(
+                metadata.status,
+                ReplayStatus::Completed {
+                    events_processed: 60,
+                    events_failed: 0,
+                    ..
+                }
+            ));
+        }
+        // State is applied in fetch order regardless of scheduling.
+        assert_eq!(seq_hash, par_hash);
+
+        let seen = parallel.seen.lock().unwrap().clone();
+        assert_eq!(seen.len(), 60);
+        for contract in ["CSLOW", "CFAST", "COTHER"] {
+            let ledgers: Vec<u64> = seen
+                .iter()
+                .filter(|(id, _)| id == contract)
+                .map(|(_, ledger)| *ledger)
+                .collect();
+            assert_eq!(ledgers.len(), 20);
+            assert!(
+                ledgers.windows(2).all(|w| w[0] < w[1]),
+                "{contract} reordered"
+            );
+        }
+    }
+
+    #[test]
+    fn test_handover_skips_events_already_handled() {
+        let mut handover = Handover {
+            ledger: 100,
+            seen: HashSet::new(),
+        };
+        let mut event = test_event(0);
+        assert!(handover.admits(&event));
+        handover.record(&event);
+        assert!(!handover.admits(&event));
+
+        event.id = "evt-0b".to_string();
+        assert!(handover.admits(&event), "same ledger, different event");
+        assert!(!handover.admits(&ContractEvent {
+            ledger_sequence: 99,
+            ..test_event(1)
+        }));
+
+        handover.record(&test_event(5));
+        assert_eq!(handover.ledger, 105);
+        assert!(!handover.admits(&event));
+    }
+
+    #[tokio::test]
+    async fn test_follow_mode_switches_to_live_feed_without_gaps() {
+        let pool = pool_with_events(30).await;
+        let storage = Arc::new(EventStorage::new(pool.clone()).with_live_feed(4));
+        let processor = Arc::new(RecordingProcessor::default());
+        let engine = Arc::new(
+            ReplayEngine::new(
+                ReplayConfig::new()
+                    .with_mode(ReplayMode::CatchUpThenFollow)
+                    .with_batch_size(7)
+                    .dry_run(),
+                Arc::clone(&storage),
+                Arc::new(ReplayStorage::new(pool.clone())),
+                Arc::new(CheckpointManager::new(pool.clone())),
+                Arc::new(CompositeEventProcessor::new().add_processor(processor.clone())),
+                Arc::new(RwLock::new(StateBuilder::new(
+                    pool,
+                    StellarNetwork::Mainnet,
+                ))),
+            )
+            .unwrap(),
+        );
+        let session = tokio::spawn({
+            let engine = Arc::clone(&engine);
+            async move { engine.start().await.unwrap() }
+        });
+
+        // Races the historical replay; a feed of 4 also forces lag recovery.
+        for i in 30..60 {
+            storage.store_event(&test_event(i)).await.unwrap();
+        }
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while processor.seen.lock().unwrap().len() < 60 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        engine.stop_following();
+
+        let metadata = session.await.unwrap();
+        assert!(matches!(
+            metadata.status,
+            ReplayStatus::Completed {
+                events_processed: 60,
+                events_failed: 0,
+                ..
+            }
+        ));
+        let ledgers: Vec<u64> = processor
+            .seen
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, ledger)| *ledger)
+            .collect();
+        assert_eq!(ledgers, (100..160).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_follow_mode_requires_live_feed() {
+        let pool = SqlitePool::connect_lazy("sqlite::memory:").unwrap();
+        let result = ReplayEngine::new(
+            ReplayConfig::new().with_mode(ReplayMode::CatchUpThenFollow),
+            Arc::new(EventStorage::new(pool.clone())),
+            Arc::new(ReplayStorage::new(pool.clone())),
+            Arc::new(CheckpointManager::new(pool.clone())),
+            Arc::new(CompositeEventProcessor::new()),
+            Arc::new(RwLock::new(StateBuilder::new(
+                pool,
+                StellarNetwork::Mainnet,
+            ))),
+        );
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_diff_mode_reports_without_writing() {
+        let pool = pool_with_events(6).await;
+        let replay_storage = Arc::new(ReplayStorage::new(pool.clone()));
+        let engine = ReplayEngine::new(
+            ReplayConfig::new().with_mode(ReplayMode::Diff),
+            Arc::new(EventStorage::new(pool.clone())),
+            Arc::clone(&replay_storage),
+            Arc::new(CheckpointManager::new(pool.clone())),
+            Arc::new(CompositeEventProcessor::new()),
+            Arc::new(RwLock::new(StateBuilder::new(
+                pool.clone(),
+                StellarNetwork::Mainnet,
+            ))),
+        )
+        .unwrap()
+        .with_processor(Box::new(
+            crate::replay::event_processor::SnapshotEventProcessor::new(pool.clone()),
+        ));
+
+        let metadata = engine.start().await.unwrap();
+        let report = metadata.report.unwrap();
+        assert_eq!(report.replayed_snapshots, 6);
+        assert_eq!(report.missing.len(), 6);
+        assert!(!report.is_consistent());
+
+        let written: i64 = sqlx::query_scalar(
+            "SELECT (SELECT COUNT(*) FROM snapshots) + (SELECT COUNT(*) FROM processed_events) \
+             + (SELECT COUNT(*) FROM replay_state)",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(written, 0);
+
+        let stored = replay_storage
+            .load_metadata(engine.session_id())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.report.unwrap().missing.len(), 6);
+    }
+
+    /// A builder already holding roughly a megabyte of state, over the
+    /// half-MiB share of a 1 MiB budget
+    fn oversized_state(pool: &SqlitePool) -> Arc<RwLock<StateBuilder>> {
+        let mut state = crate::replay::state_builder::ApplicationState::new();
+        for epoch in 1_000..6_000 {
+            state.snapshots.insert(
+                epoch,
+                crate::replay::state_builder::SnapshotState {
+                    epoch,
+                    hash: format!("{epoch:064x}"),
+                    ledger: 1,
+                    transaction_hash: format!("{epoch:064x}"),
+                },
+            );
+        }
+        Arc::new(RwLock::new(StateBuilder::with_state(
+            pool.clone(),
+            StellarNetwork::Mainnet,
+            state,
+        )))
+    }
+
+    fn over_budget_engine(
+        pool: &SqlitePool,
+        config: ReplayConfig,
+        state_builder: &Arc<RwLock<StateBuilder>>,
+    ) -> ReplayEngine {
+        ReplayEngine::new(
+            config
+                .with_range(ReplayRange::FromTo {
+                    start: 100,
+                    end: 105,
+                })
+                .with_batch_size(2)
+                .with_snapshots(0, 1)
+                .with_memory_budget(1),
+            Arc::new(EventStorage::new(pool.clone())),
+            Arc::new(ReplayStorage::new(pool.clone())),
+            Arc::new(CheckpointManager::new(pool.clone())),
+            Arc::new(CompositeEventProcessor::new()),
+            Arc::clone(state_builder),
+        )
+        .unwrap()
+        .with_processor(Box::new(SharedProcessor(Arc::default())))
+    }
+
+    #[tokio::test]
+    async fn test_state_over_budget_moves_to_database() {
+        let pool = pool_with_events(6).await;
+        let state_builder = oversized_state(&pool);
+        let engine = over_budget_engine(&pool, ReplayConfig::new(), &state_builder);
+
+        let metadata = engine.start().await.unwrap();
+        assert!(
+            matches!(
+                metadata.status,
+                ReplayStatus::Completed {
+                    events_processed: 6,
+                    ..
+                }
+            ),
+            "{}",
+            metadata.status
+        );
+
+        // Evicted after the first batch; only the later epochs stayed in memory.
+        let builder = state_builder.read().await;
+        assert!(builder.has_evicted_entries());
+        assert_eq!(builder.state().snapshots.len(), 4);
+        drop(builder);
+        let entries: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM replay_state_entries")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(entries, 5_006);
+
+        // The final state persists by reference to its entries and loads back
+        // without reading them into memory.
+        let mut resumed = StateBuilder::new(pool.clone(), StellarNetwork::Mainnet);
+        assert_eq!(resumed.load_nearest(105).await.unwrap(), Some(105));
+        assert!(resumed.state().snapshots.is_empty());
+        let again = resumed.apply_event(&test_event(0)).await.unwrap();
+        assert!(again.skipped);
+        let report = resumed.diff_against_database(true).await.unwrap();
+        assert_eq!(report.replayed_snapshots, 5_006);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_state_over_budget_stops_replay() {
+        let pool = pool_with_events(6).await;
+        let state_builder = oversized_state(&pool);
+        let engine = over_budget_engine(&pool, ReplayConfig::new().dry_run(), &state_builder);
+
+        let metadata = engine.start().await.unwrap();
+        assert!(
+            matches!(
+                &metadata.status,
+                ReplayStatus::Failed { error, .. } if error.contains("memory budget")
+            ),
+            "{}",
+            metadata.status
+        );
+
+        // Nothing was written to make room.
+        let written: i64 = sqlx::query_scalar(
+            "SELECT (SELECT COUNT(*) FROM replay_state) + (SELECT COUNT(*) FROM replay_state_entries)",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(written, 0);
+    }
+
+    #[tokio::test]
+    async fn test_replay_only_sees_its_network() {
+        let pool = pool_with_events(6).await;
+        let storage = EventStorage::new(pool.clone());
+        for i in 0..3 {
+            let mut event = test_event(i);
+            event.id = format!("testnet-{i}");
+            event.transaction_hash = format!("{:064x}", i + 1_000);
+            event.network = "testnet".to_string();
+            storage.store_event(&event).await.unwrap();
+        }
+
+        let engine = |state_network| {
+            ReplayEngine::new(
+                ReplayConfig::new()
+                    .with_range(ReplayRange::FromTo {
+                        start: 100,
+                        end: 105,
+                    })
+                    .with_network(StellarNetwork::Testnet)
+                    .dry_run(),
+                Arc::new(EventStorage::new(pool.clone())),
+                Arc::new(ReplayStorage::new(pool.clone())),
+                Arc::new(CheckpointManager::new(pool.clone())),
+                Arc::new(CompositeEventProcessor::new()),
+                Arc::new(RwLock::new(StateBuilder::new(pool.clone(), state_network))),
+            )
+            .unwrap()
+        };
+
+        let processor = Arc::new(RecordingProcessor::default());
+        let metadata = engine(StellarNetwork::Testnet)
+            .with_processor(Box::new(SharedProcessor(Arc::clone(&processor))))
+            .start()
+            .await
+            .unwrap();
+        assert!(matches!(
+            metadata.status,
+            ReplayStatus::Completed {
+                events_processed: 3,
+                ..
+            }
+        ));
+        assert_eq!(processor.seen.lock().unwrap().len(), 3);
+
+        // State from one network is never built on by another's replay.
+        assert!(matches!(
+            engine(StellarNetwork::Mainnet).start().await,
+            Err(ReplayError::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_resume_starts_from_nearest_state_snapshot() {
+        let pool = pool_with_events(60).await;
+        let run = |range: ReplayRange, processor: Arc<RecordingProcessor>| {
+            let pool = pool.clone();
+            async move {
+                let state_builder = Arc::new(RwLock::new(StateBuilder::new(
+                    pool.clone(),
+                    StellarNetwork::Mainnet,
+                )));
+                let engine = ReplayEngine::new(
+                    ReplayConfig::new()
+                        .with_range(range)
+                        .with_batch_size(20)
+                        .with_snapshots(10, 2),
+                    Arc::new(EventStorage::new(pool.clone())),
+                    Arc::new(ReplayStorage::new(pool.clone())),
+                    Arc::new(CheckpointManager::new(pool.clone())),
+                    Arc::new(CompositeEventProcessor::new()),
+                    Arc::clone(&state_builder),
+                )
+                .unwrap()
+                .with_processor(Box::new(SharedProcessor(processor)));
+                let metadata = engine.start().await.unwrap();
+                let snapshots = state_builder.read().await.state().snapshots.len();
+                (metadata, snapshots)
+            }
+        };
+
+        let (first, _) = run(
+            ReplayRange::FromTo {
+                start: 100,
+                end: 159,
+            },
+            Arc::default(),
+        )
+        .await;
+        let ledgers: Vec<i64> =
+            sqlx::query_scalar("SELECT ledger FROM replay_state ORDER BY ledger")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        // Batches end at 119, 139 and 159; older snapshots were compacted.
+        assert_eq!(ledgers, [139, 159]);
+
+        // Lose the final snapshot, as if the replay had died after ledger 150.
+        sqlx::query("DELETE FROM replay_state WHERE ledger = 159")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let checkpoint = first.checkpoint.unwrap();
+        let resumed = Arc::new(RecordingProcessor::default());
+        let (_, snapshots) = run(
+            ReplayRange::FromCheckpoint {
+                checkpoint_id: checkpoint.id,
+            },
+            Arc::clone(&resumed),
+        )
+        .await;
+
+        let seen = resumed.seen.lock().unwrap().clone();
+        assert_eq!(seen.first().map(|(_, ledger)| *ledger), Some(140));
+        assert_eq!(seen.len(), 20);
+        assert_eq!(snapshots, 60);
+    }
+
+    #[tokio::test]
+    async fn test_progress_is_published_until_completion() {
+        let pool = pool_with_events(30).await;
+        let hub = Arc::new(ProgressHub::new());
+        let config = ReplayConfig::new()
+            .with_range(ReplayRange::FromTo {
+                start: 100,
+                end: 129,
+            })
+            .with_batch_size(10)
+            .dry_run();
+        let engine = ReplayEngine::new(
+            config,
+            Arc::new(EventStorage::new(pool.clone())),
+            Arc::new(ReplayStorage::new(pool.clone())),
+            Arc::new(CheckpointManager::new(pool.clone())),
+            Arc::new(CompositeEventProcessor::new()),
+            Arc::new(RwLock::new(StateBuilder::new(
+                pool.clone(),
+                StellarNetwork::Mainnet,
+            ))),
+        )
+        .unwrap()
+        .with_processor(Box::new(SharedProcessor(Arc::default())))
+        .with_progress(Arc::clone(&hub));
+
+        let mut updates = hub.subscribe(engine.session_id());
+        assert!(hub.is_running(engine.session_id()));
+        engine.start().await.unwrap();
+
+        let mut received = Vec::new();
+        while let Ok(update) = updates.recv().await {
+            received.push(update);
+        }
+        let ledgers: Vec<_> = received
+            .iter()
+            .filter_map(|update| match update.status {
+                ReplayStatus::InProgress { current_ledger, .. } => Some(current_ledger),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ledgers, [100, 110, 120, 130]);
+        assert_eq!(received[0].status, ReplayStatus::Pending);
+        assert_eq!(received[0].end_ledger, None);
+
+        let last = received.last().unwrap();
+        assert!(matches!(
+            last.status,
+            ReplayStatus::Completed {
+                events_processed: 30,
+                ..
+            }
+        ));
+        assert_eq!((last.start_ledger, last.end_ledger), (Some(100), Some(129)));
+        assert_eq!(hub.active_sessions(), 0);
+        assert!(!hub.is_running(engine.session_id()));
+    }
+
+    /// Holds the event at ledger 125 until released.
+    #[derive(Default)]
+    struct Gate {
+        reached: Notify,
+        release: Notify,
+    }
+
+    #[async_trait]
+    impl EventProcessor for Gate {
+        async fn process_event(
+            &self,
+            event: &ContractEvent,
+            _context: &ProcessingContext,
+        ) -> Result<ProcessingResult> {
+            if event.ledger_sequence == 125 {
+                self.reached.notify_one();
+                self.release.notified().await;
+            }
+            Ok(ProcessingResult::success())
+        }
+
+        async fn is_processed(&self, _event: &ContractEvent) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn mark_processed(&self, _event: &ContractEvent) -> Result<()> {
+            Ok(())
+        }
+
+        fn name(&self) -> &'static str {
+            "Gate"
+        }
+    }
+
+    struct SharedGate(Arc<Gate>);
+
+    #[async_trait]
+    impl EventProcessor for SharedGate {
+        async fn process_event(
+            &self,
+            event: &ContractEvent,
+            context: &ProcessingContext,
+        ) -> Result<ProcessingResult> {
+            self.0.process_event(event, context).await
+        }
+
+        async fn is_processed(&self, event: &ContractEvent) -> Result<bool> {
+            self.0.is_processed(event).await
+        }
+
+        async fn mark_processed(&self, event: &ContractEvent) -> Result<()> {
+            self.0.mark_processed(event).await
+        }
+
+        fn name(&self) -> &str {
+            self.0.name()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pause_then_cancel_stops_between_batches() {
+        let pool = pool_with_events(60).await;
+        let hub = Arc::new(ProgressHub::new());
+        let gate = Arc::new(Gate::default());
+        let checkpoints = Arc::new(CheckpointManager::new(pool.clone()));
+        let config = ReplayConfig::new()
+            .with_range(ReplayRange::FromTo {
+                start: 100,
+                end: 159,
+            })
+            .with_batch_size(10)
+            .dry_run();
+        let engine = Arc::new(
+            ReplayEngine::new(
+                config,
+                Arc::new(EventStorage::new(pool.clone())),
+                Arc::new(ReplayStorage::new(pool.clone())),
+                Arc::clone(&checkpoints),
+                Arc::new(CompositeEventProcessor::new()),
+                Arc::new(RwLock::new(StateBuilder::new(
+                    pool.clone(),
+                    StellarNetwork::Mainnet,
+                ))),
+            )
+            .unwrap()
+            .with_processor(Box::new(SharedGate(Arc::clone(&gate))))
+            .with_progress(Arc::clone(&hub)),
+        );
+        let mut updates = hub.subscribe(engine.session_id());
+
+        let running = tokio::spawn({
+            let engine = Arc::clone(&engine);
+            async move { engine.start().await.unwrap() }
+        });
+        gate.reached.notified().await;
+        engine.pause();
+        gate.release.notify_one();
+
+        // The batch in flight finishes before the session pauses.
+        loop {
+            let update = updates.recv().await.unwrap();
+            if let ReplayStatus::Paused {
+                last_ledger,
+                events_processed,
+            } = update.status
+            {
+                assert_eq!((last_ledger, events_processed), (129, 30));
+                break;
+            }
+        }
+
+        engine.cancel();
+        let metadata = tokio::time::timeout(Duration::from_secs(5), running)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            metadata.status,
+            ReplayStatus::Cancelled {
+                last_ledger: 129,
+                events_processed: 30,
+                events_failed: 0,
+            }
+        );
+        assert!(metadata.ended_at.is_some());
+        let checkpoint = checkpoints
+            .get_latest(engine.session_id())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(checkpoint.last_ledger, 129);
+        assert!(!hub.is_running(engine.session_id()));
+    }
 }
@@ -9,9 +9,11 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::timeout;
-use tracing::{debug, info, warn};
 
+use super::registry::{ProcessorRegistry, ProcessorSubscription};
 use super::ContractEvent;
+use crate::log_event;
+use crate::logging::Subsystem;
 
 /// Context provided to event processors
 #[derive(Debug, Clone)]
@@ -175,13 +177,20 @@ pub trait EventProcessor: Send + Sync {
         Ok(())
     }
 
+    /// Events this processor handles; the registry only routes matching
+    /// events to it. Defaults to every event.
+    fn subscriptions(&self) -> Vec<ProcessorSubscription> {
+        vec![ProcessorSubscription::all()]
+    }
+
     /// Get processor name for logging
     fn name(&self) -> &str;
 }
 
 /// Composite processor that delegates to specific processors based on event type
+#[derive(Clone)]
 pub struct CompositeEventProcessor {
-    processors: Vec<Arc<dyn EventProcessor>>,
+    registry: ProcessorRegistry,
 }
 
 impl CompositeEventProcessor {
@@ -189,16 +198,33 @@ impl CompositeEventProcessor {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            processors: Vec::new(),
+            registry: ProcessorRegistry::new(),
         }
     }
 
+    /// Create a composite processor over an existing registry
+    #[must_use]
+    pub const fn from_registry(registry: ProcessorRegistry) -> Self {
+        Self { registry }
+    }
+
     /// Add a processor
     pub fn add_processor(mut self, processor: Arc<dyn EventProcessor>) -> Self {
-        self.processors.push(processor);
+        self.register(processor);
         self
     }
 
+    /// Register a processor under its declared subscriptions
+    pub fn register(&mut self, processor: Arc<dyn EventProcessor>) {
+        self.registry.register(processor);
+    }
+
+    /// The processors events are routed to
+    #[must_use]
+    pub const fn registry(&self) -> &ProcessorRegistry {
+        &self.registry
+    }
+
     /// Process an event with timeout and retry logic
     pub async fn process_with_retry(
         &self,
@@ -211,11 +237,14 @@ impl CompositeEventProcessor {
 
         while attempts <= max_retries {
             if attempts > 0 {
-                debug!(
-                    "Retrying event {} (attempt {}/{})",
-                    event.unique_id(),
-                    attempts,
-                    max_retries
+                log_event!(
+                    debug,
+                    Subsystem::Replay,
+                    "replay.event_retry",
+                    event_id = %event.unique_id(),
+                    attempt = attempts,
+                    max_attempts = max_retries,
+                    "Retrying event"
                 );
                 // Exponential backoff
                 tokio::time::sleep(Duration::from_millis(100 * 2_u64.pow(attempts))).await;
@@ -253,10 +282,16 @@ impl CompositeEventProcessor {
         let start = std::time::Instant::now();
 
         // Find appropriate processor
-        for processor in &self.processors {
+        for processor in self.registry.processors_for(event) {
             // Check idempotency
             if processor.is_processed(event).await? {
-                debug!("Event {} already processed, skipping", event.unique_id());
+                log_event!(
+                    debug,
+                    Subsystem::Replay,
+                    "replay.event_skipped",
+                    event_id = %event.unique_id(),
+                    "Event already processed, skipping"
+                );
                 return Ok(ProcessingResult::skipped());
             }
 
@@ -273,30 +308,42 @@ impl CompositeEventProcessor {
                         processor.mark_processed(event).await?;
                     }
 
-                    info!(
-                        "Event {} processed by {} in {}ms (success: {}, skipped: {})",
-                        event.unique_id(),
-                        processor.name(),
-                        result.duration_ms,
-                        result.success,
-                        result.skipped
+                    log_event!(
+                        info,
+                        Subsystem::Replay,
+                        "replay.event_processed",
+                        event_id = %event.unique_id(),
+                        processor = processor.name(),
+                        duration_ms = result.duration_ms,
+                        success = result.success,
+                        skipped = result.skipped,
+                        "Event processed"
                     );
 
                     return Ok(result);
                 }
                 Err(e) => {
-                    warn!(
-                        "Processor {} failed to process event {}: {}",
-                        processor.name(),
-                        event.unique_id(),
-                        e
+                    log_event!(
+                        warn,
+                        Subsystem::Replay,
+                        "replay.event_failed",
+                        event_id = %event.unique_id(),
+                        processor = processor.name(),
+                        error = %e,
+                        "Processor failed to process event"
                     );
                 }
             }
         }
 
         // No processor handled the event
-        warn!("No processor found for event {}", event.unique_id());
+        log_event!(
+            warn,
+            Subsystem::Replay,
+            "replay.event_unhandled",
+            event_id = %event.unique_id(),
+            "No processor found for event"
+        );
         Ok(ProcessingResult::failure(
             "No processor found for event".to_string(),
         ))
@@ -338,9 +385,13 @@ impl SnapshotEventProcessor {
             .and_then(|v| v.as_str())
             .context("Missing hash in event data")?;
 
-        debug!(
-            "Processing snapshot submission: epoch={}, hash={}",
-            epoch, hash
+        log_event!(
+            debug,
+            Subsystem::Replay,
+            "replay.snapshot_submission_processing",
+            epoch,
+            hash,
+            "Processing snapshot submission"
         );
 
         // Check if already exists (idempotency)
@@ -430,6 +481,10 @@ impl EventProcessor for SnapshotEventProcessor {
         Ok(())
     }
 
+    fn subscriptions(&self) -> Vec<ProcessorSubscription> {
+        vec![ProcessorSubscription::event_type("snapshot_submitted")]
+    }
+
     fn name(&self) -> &'static str {
         "SnapshotEventProcessor"
     }
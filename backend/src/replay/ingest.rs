@@ -0,0 +1,201 @@
+//! Live Event Ingestion
+//!
+//! Tails an [`EventSource`], normally Soroban RPC, into the `contract_events`
+//! table. Events are stored through [`EventStorage::store_event`], so sessions
+//! in [`ReplayMode::CatchUpThenFollow`](super::ReplayMode::CatchUpThenFollow)
+//! that share the storage receive them on its live feed as they arrive.
+//!
+//! With leader election, only the replica holding the ingestion lease polls,
+//! so follow sessions only receive events live on that replica.
+
+use anyhow::Result;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{source::EventSource, storage::EventStorage, EventFilter};
+use crate::cluster::{LeaderElection, ROLE_INGESTION};
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::network::StellarNetwork;
+
+/// Poll interval when `REPLAY_INGEST_INTERVAL_SECS` is unset
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 10;
+
+/// Most ledgers fetched in one poll, so catching up after downtime is paced
+const MAX_LEDGERS_PER_POLL: u64 = 10_000;
+
+/// Which events are ingested and how often
+#[derive(Debug, Clone)]
+pub struct IngestConfig {
+    /// Contracts whose events are ingested. Ingestion is off when empty.
+    pub contract_ids: Vec<String>,
+    pub poll_interval: Duration,
+}
+
+impl IngestConfig {
+    /// Reads `REPLAY_INGEST_CONTRACTS`, a comma-separated list of contract
+    /// IDs defaulting to `SNAPSHOT_CONTRACT_ID`, and
+    /// `REPLAY_INGEST_INTERVAL_SECS` (default 10).
+    #[must_use]
+    pub fn from_env() -> Self {
+        let contract_ids = std::env::var("REPLAY_INGEST_CONTRACTS")
+            .or_else(|_| std::env::var("SNAPSHOT_CONTRACT_ID"))
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(str::to_string)
+            .collect();
+        let poll_interval = std::env::var("REPLAY_INGEST_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_POLL_INTERVAL_SECS);
+
+        Self {
+            contract_ids,
+            poll_interval: Duration::from_secs(poll_interval),
+        }
+    }
+
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        !self.contract_ids.is_empty()
+    }
+}
+
+/// Copies new events from a source into event storage
+pub struct EventIngestor {
+    source: Arc<dyn EventSource>,
+    storage: Arc<EventStorage>,
+    filter: EventFilter,
+    poll_interval: Duration,
+    leader: Option<Arc<LeaderElection>>,
+}
+
+impl EventIngestor {
+    #[must_use]
+    pub fn new(
+        source: Arc<dyn EventSource>,
+        storage: Arc<EventStorage>,
+        config: &IngestConfig,
+        network: StellarNetwork,
+    ) -> Self {
+        Self {
+            source,
+            storage,
+            filter: EventFilter {
+                contract_ids: Some(config.contract_ids.clone()),
+                network: Some(network.to_string()),
+                ..EventFilter::default()
+            },
+            poll_interval: config.poll_interval,
+            leader: None,
+        }
+    }
+
+    /// Only poll on the replica holding the ingestion lease.
+    #[must_use]
+    pub fn with_leader_election(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    /// Store the events of the ledgers after `after`, up to the source's
+    /// latest ledger or [`MAX_LEDGERS_PER_POLL`] of them. Returns the last
+    /// ledger covered and how many events were stored.
+    pub async fn ingest_after(&self, after: u64) -> Result<(u64, usize)> {
+        let Some(latest) = self.source.get_latest_ledger().await? else {
+            return Ok((after, 0));
+        };
+        if latest <= after {
+            return Ok((after, 0));
+        }
+        let end = latest.min(after.saturating_add(MAX_LEDGERS_PER_POLL));
+
+        let events = self
+            .source
+            .get_events_in_range(after + 1, end, &self.filter, None)
+            .await?;
+        for event in &events {
+            self.storage.store_event(event).await?;
+        }
+        Ok((end, events.len()))
+    }
+
+    /// Resume after the latest stored event or, with nothing stored yet, at
+    /// the source's head: history is replayed from the source directly.
+    async fn starting_ledger(&self) -> Result<u64> {
+        if let Some(ledger) = self.storage.get_latest_ledger().await? {
+            return Ok(ledger);
+        }
+        Ok(self.source.get_latest_ledger().await?.unwrap_or(0))
+    }
+
+    /// Poll the source on the configured interval
+    pub async fn start(self: Arc<Self>) {
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.ingest_started",
+            contract_ids = ?self.filter.contract_ids,
+            source = self.source.name(),
+            poll_interval_seconds = self.poll_interval.as_secs(),
+            "Ingesting contract events"
+        );
+        let mut cursor = None;
+        let mut interval = tokio::time::interval(self.poll_interval);
+        loop {
+            interval.tick().await;
+            if let Some(leader) = &self.leader {
+                if !leader.is_leader(ROLE_INGESTION) {
+                    // Another replica stores events meanwhile; resume after them
+                    cursor = None;
+                    continue;
+                }
+            }
+            let after = match cursor {
+                Some(after) => after,
+                None => match self.starting_ledger().await {
+                    Ok(after) => after,
+                    Err(e) => {
+                        log_event!(
+                            warn,
+                            Subsystem::Replay,
+                            "replay.ingest_resume_failed",
+                            error = format!("{e:#}"),
+                            "Failed to find where event ingestion resumes"
+                        );
+                        continue;
+                    }
+                },
+            };
+            match self.ingest_after(after).await {
+                Ok((ledger, stored)) => {
+                    if stored > 0 {
+                        log_event!(
+                            debug,
+                            Subsystem::Replay,
+                            "replay.ingest_batch_stored",
+                            events = stored,
+                            ledger,
+                            "Ingested events"
+                        );
+                    }
+                    cursor = Some(ledger);
+                }
+                Err(e) => {
+                    log_event!(
+                        warn,
+                        Subsystem::Replay,
+                        "replay.ingest_failed",
+                        after_ledger = after,
+                        error = format!("{e:#}"),
+                        "Event ingestion failed"
+                    );
+                    cursor = Some(after);
+                }
+            }
+        }
+    }
+}
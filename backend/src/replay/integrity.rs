@@ -0,0 +1,276 @@
+//! Replay Integrity
+//!
+//! Checks the epochs a replay rebuilt against the snapshot hashes anchored in
+//! the snapshot contract. Each epoch's stored analytics snapshot is hashed
+//! again with [`SnapshotGenerator`], so both the submission the replay
+//! rebuilt from events and the backend's own data are compared with the chain.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use super::state_builder::{ApplicationState, SnapshotState};
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::services::contract::ContractService;
+use crate::snapshot::{AnalyticsSnapshot, SnapshotGenerator};
+
+/// Snapshot hashes anchored on chain
+#[async_trait]
+pub trait AnchoredSnapshots: Send + Sync {
+    /// Hex-encoded hash anchored for `epoch`, or `None` if there is none
+    async fn anchored_hash(&self, epoch: u64) -> Result<Option<String>>;
+}
+
+#[async_trait]
+impl AnchoredSnapshots for ContractService {
+    async fn anchored_hash(&self, epoch: u64) -> Result<Option<String>> {
+        self.get_snapshot_by_epoch(epoch).await
+    }
+}
+
+/// How an epoch compared with its anchored hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EpochIntegrity {
+    /// Replayed and recomputed hashes both match the anchored one
+    Verified,
+    /// The contract holds no hash for the epoch
+    NotAnchored,
+    /// The hash rebuilt from the submission event differs from the anchored one
+    ReplayMismatch,
+    /// No analytics snapshot is stored for the epoch to recompute
+    SnapshotMissing,
+    /// The stored analytics snapshot hashes to something other than the anchored hash
+    SnapshotMismatch,
+}
+
+/// One epoch's hashes and how they compared
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpochCheck {
+    pub epoch: u64,
+    /// Ledger of the submission the replay rebuilt
+    pub ledger: u64,
+    pub replayed_hash: String,
+    pub recomputed_hash: Option<String>,
+    pub anchored_hash: Option<String>,
+    pub outcome: EpochIntegrity,
+}
+
+/// Epochs whose rebuilt state does not match the chain, ordered by epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    /// Replayed epochs compared against the contract
+    pub epochs_checked: usize,
+    /// Every checked epoch that did not verify
+    pub failures: Vec<EpochCheck>,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl IntegrityReport {
+    /// True when every checked epoch matches its anchored hash
+    #[must_use]
+    pub const fn is_consistent(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Verify the epochs `state` rebuilt from `from_ledger` on against `anchors`.
+///
+/// Snapshots applied before `from_ledger` came from a resumed state and are
+/// left to the replay that produced them.
+pub async fn verify_against_chain(
+    state: &ApplicationState,
+    pool: &SqlitePool,
+    anchors: &dyn AnchoredSnapshots,
+    from_ledger: u64,
+) -> Result<IntegrityReport> {
+    let mut replayed: Vec<&SnapshotState> = state
+        .snapshots
+        .values()
+        .filter(|snapshot| snapshot.ledger >= from_ledger)
+        .collect();
+    replayed.sort_by_key(|snapshot| snapshot.epoch);
+
+    let mut failures = Vec::new();
+    for snapshot in &replayed {
+        let anchored_hash = anchors
+            .anchored_hash(snapshot.epoch)
+            .await
+            .with_context(|| {
+                format!("Failed to read anchored hash for epoch {}", snapshot.epoch)
+            })?;
+        let recomputed_hash = recompute_hash(pool, snapshot.epoch).await?;
+
+        let matches = |hash: &str| {
+            anchored_hash
+                .as_deref()
+                .is_some_and(|anchored| anchored.eq_ignore_ascii_case(hash))
+        };
+        let outcome = if anchored_hash.is_none() {
+            EpochIntegrity::NotAnchored
+        } else if !matches(&snapshot.hash) {
+            EpochIntegrity::ReplayMismatch
+        } else {
+            match &recomputed_hash {
+                None => EpochIntegrity::SnapshotMissing,
+                Some(hash) if !matches(hash) => EpochIntegrity::SnapshotMismatch,
+                Some(_) => EpochIntegrity::Verified,
+            }
+        };
+        log_event!(
+            debug,
+            Subsystem::Replay,
+            "replay.epoch_integrity_checked",
+            epoch = snapshot.epoch,
+            outcome = ?outcome,
+            "Epoch integrity checked"
+        );
+
+        if outcome != EpochIntegrity::Verified {
+            failures.push(EpochCheck {
+                epoch: snapshot.epoch,
+                ledger: snapshot.ledger,
+                replayed_hash: snapshot.hash.clone(),
+                recomputed_hash,
+                anchored_hash,
+                outcome,
+            });
+        }
+    }
+
+    Ok(IntegrityReport {
+        epochs_checked: replayed.len(),
+        failures,
+        generated_at: Utc::now(),
+    })
+}
+
+/// Hash the latest analytics snapshot stored for `epoch`, if there is one
+async fn recompute_hash(pool: &SqlitePool, epoch: u64) -> Result<Option<String>> {
+    let data: Option<String> = sqlx::query_scalar(
+        r"
+        SELECT data FROM snapshots
+        WHERE epoch = $1 AND entity_type = 'analytics_snapshot'
+        ORDER BY created_at DESC
+        LIMIT 1
+        ",
+    )
+    .bind(i64::try_from(epoch).unwrap_or(i64::MAX))
+    .fetch_optional(pool)
+    .await
+    .context("Failed to load analytics snapshot for integrity check")?;
+
+    let Some(data) = data else {
+        return Ok(None);
+    };
+    match serde_json::from_str::<AnalyticsSnapshot>(&data) {
+        Ok(snapshot) => Ok(Some(SnapshotGenerator::generate_hash_hex(snapshot)?)),
+        Err(e) => {
+            log_event!(
+                warn,
+                Subsystem::Replay,
+                "replay.snapshot_unreadable",
+                epoch,
+                error = %e,
+                "Stored analytics snapshot is unreadable"
+            );
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations};
+    use std::collections::HashMap;
+
+    /// Anchored hashes keyed by epoch
+    struct Contract(HashMap<u64, String>);
+
+    #[async_trait]
+    impl AnchoredSnapshots for Contract {
+        async fn anchored_hash(&self, epoch: u64) -> Result<Option<String>> {
+            Ok(self.0.get(&epoch).cloned())
+        }
+    }
+
+    fn analytics(epoch: u64) -> AnalyticsSnapshot {
+        let timestamp = DateTime::from_timestamp(1_767_225_600, 0).unwrap();
+        AnalyticsSnapshot::new(epoch, timestamp)
+    }
+
+    #[tokio::test]
+    async fn test_reports_epochs_that_do_not_match_the_chain() {
+        let pool = test_support::sqlite_pool(&[migrations::METRICS_CORRIDORS_SNAPSHOTS]).await;
+        for epoch in [1, 2, 4, 5] {
+            let mut snapshot = analytics(epoch);
+            if epoch == 5 {
+                snapshot.schema_version = 1;
+            }
+            sqlx::query(
+                "INSERT INTO snapshots (id, entity_id, entity_type, data, epoch, timestamp) \
+                 VALUES ($1, 'system', 'analytics_snapshot', $2, $3, $4)",
+            )
+            .bind(format!("snap-{epoch}"))
+            .bind(serde_json::to_string(&snapshot).unwrap())
+            .bind(i64::try_from(epoch).unwrap())
+            .bind(snapshot.timestamp)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let hash = |epoch| SnapshotGenerator::generate_hash_hex(analytics(epoch)).unwrap();
+        let mut state = ApplicationState::at_ledger(200);
+        for (epoch, replayed) in [
+            (0, hash(0)),
+            (1, hash(1)),
+            (2, "tampered".to_string()),
+            (3, hash(3)),
+            (4, hash(4)),
+            (5, hash(5)),
+        ] {
+            state.snapshots.insert(
+                epoch,
+                SnapshotState {
+                    epoch,
+                    hash: replayed,
+                    ledger: 100 + epoch,
+                    transaction_hash: format!("tx-{epoch}"),
+                },
+            );
+        }
+        // Epoch 0 was applied before the replayed range and is not checked.
+        let contract = Contract(
+            [1, 2, 3, 5]
+                .into_iter()
+                .map(|epoch| (epoch, hash(epoch).to_uppercase()))
+                .collect(),
+        );
+
+        let report = verify_against_chain(&state, &pool, &contract, 101)
+            .await
+            .unwrap();
+        assert_eq!(report.epochs_checked, 5);
+        assert!(!report.is_consistent());
+        assert_eq!(
+            report
+                .failures
+                .iter()
+                .map(|check| (check.epoch, check.outcome))
+                .collect::<Vec<_>>(),
+            [
+                (2, EpochIntegrity::ReplayMismatch),
+                (3, EpochIntegrity::SnapshotMissing),
+                (4, EpochIntegrity::NotAnchored),
+                (5, EpochIntegrity::SnapshotMismatch),
+            ]
+        );
+        assert_eq!(report.failures[3].ledger, 105);
+        assert_ne!(report.failures[3].recomputed_hash, Some(hash(5)));
+    }
+}
@@ -0,0 +1,509 @@
+//! Replay Manager
+//!
+//! Starts replay sessions in the background and keeps a handle on the ones
+//! running in this process, so they can be paused, resumed or cancelled later.
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use tokio::sync::RwLock;
+
+use super::{
+    checkpoint::CheckpointManager,
+    config::ReplayConfig,
+    engine::ReplayEngine,
+    event_processor::{CompositeEventProcessor, ProcessingContext, SnapshotEventProcessor},
+    progress::ProgressHub,
+    state_builder::StateBuilder,
+    storage::{EventStorage, ReplayStorage},
+    ReplayError, ReplayMetadata, ReplayResult,
+};
+use crate::log_event;
+use crate::logging::Subsystem;
+
+/// Sessions started through this manager that have not finished yet
+type Running = Arc<Mutex<HashMap<String, Arc<ReplayEngine>>>>;
+
+/// Outcome of retrying a session's dead letters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadLetterRetry {
+    /// Dead letters processed again
+    pub retried: usize,
+    /// Processed successfully and released from quarantine
+    pub resolved: usize,
+    /// Failed again and still quarantined
+    pub remaining: usize,
+}
+
+/// Runs replay sessions against the application database
+pub struct ReplayManager {
+    pool: SqlitePool,
+    events: Arc<EventStorage>,
+    storage: Arc<ReplayStorage>,
+    checkpoints: Arc<CheckpointManager>,
+    processor: Arc<CompositeEventProcessor>,
+    progress: Arc<ProgressHub>,
+    running: Running,
+}
+
+impl ReplayManager {
+    /// Create a manager publishing session progress to `progress`. Sessions
+    /// process snapshot events unless given other processors, and can only
+    /// follow live events once given the ingestion's event storage.
+    #[must_use]
+    pub fn new(pool: SqlitePool, progress: Arc<ProgressHub>) -> Self {
+        let processor = CompositeEventProcessor::new()
+            .add_processor(Arc::new(SnapshotEventProcessor::new(pool.clone())));
+        Self {
+            events: Arc::new(EventStorage::new(pool.clone())),
+            storage: Arc::new(ReplayStorage::new(pool.clone())),
+            checkpoints: Arc::new(CheckpointManager::new(pool.clone())),
+            processor: Arc::new(processor),
+            pool,
+            progress,
+            running: Arc::default(),
+        }
+    }
+
+    /// Use these processors for sessions and dead letter retries
+    #[must_use]
+    pub fn with_processor(mut self, processor: CompositeEventProcessor) -> Self {
+        self.processor = Arc::new(processor);
+        self
+    }
+
+    /// Read events from `events`. `CatchUpThenFollow` sessions follow its live
+    /// feed, so it should be the storage live events are ingested through.
+    #[must_use]
+    pub fn with_event_storage(mut self, events: Arc<EventStorage>) -> Self {
+        self.events = events;
+        self
+    }
+
+    fn running(&self) -> MutexGuard<'_, HashMap<String, Arc<ReplayEngine>>> {
+        self.running.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn new_engine(&self, config: ReplayConfig) -> ReplayResult<Arc<ReplayEngine>> {
+        let state_builder =
+            StateBuilder::new(self.pool.clone(), config.network).for_filter(&config.filter);
+        let engine = ReplayEngine::new(
+            config,
+            Arc::clone(&self.events),
+            Arc::clone(&self.storage),
+            Arc::clone(&self.checkpoints),
+            Arc::clone(&self.processor),
+            Arc::new(RwLock::new(state_builder)),
+        )
+        .map_err(|e| {
+            e.downcast::<ReplayError>()
+                .unwrap_or_else(ReplayError::from)
+        })?
+        .with_progress(Arc::clone(&self.progress));
+        Ok(Arc::new(engine))
+    }
+
+    /// Start a replay in the background and return its session id
+    pub fn start(&self, config: ReplayConfig) -> ReplayResult<String> {
+        let engine = self.new_engine(config)?;
+        let session_id = engine.session_id().to_string();
+        self.running()
+            .insert(session_id.clone(), Arc::clone(&engine));
+
+        let running = Arc::clone(&self.running);
+        tokio::spawn(async move {
+            match engine.start().await {
+                Ok(metadata) => log_event!(
+                    info,
+                    Subsystem::Replay,
+                    "replay.session_ended",
+                    session_id = %metadata.session_id,
+                    status = %metadata.status,
+                    "Replay ended"
+                ),
+                Err(e) => log_event!(
+                    error,
+                    Subsystem::Replay,
+                    "replay.session_failed",
+                    session_id = %engine.session_id(),
+                    error = %e,
+                    "Replay failed"
+                ),
+            }
+            running
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(engine.session_id());
+        });
+
+        Ok(session_id)
+    }
+
+    /// Run a replay to completion. It can be paused or cancelled like one
+    /// started in the background while it runs.
+    pub async fn run(&self, config: ReplayConfig) -> ReplayResult<ReplayMetadata> {
+        let engine = self.new_engine(config)?;
+        self.running()
+            .insert(engine.session_id().to_string(), Arc::clone(&engine));
+        let result = engine.start().await;
+        self.running().remove(engine.session_id());
+        result
+    }
+
+    fn engine(&self, session_id: &str) -> Option<Arc<ReplayEngine>> {
+        self.running().get(session_id).cloned()
+    }
+
+    /// Pause a running session. Returns false if it is not running here.
+    #[must_use]
+    pub fn pause(&self, session_id: &str) -> bool {
+        self.engine(session_id)
+            .map(|engine| engine.pause())
+            .is_some()
+    }
+
+    /// Resume a paused session. Returns false if it is not running here.
+    #[must_use]
+    pub fn resume(&self, session_id: &str) -> bool {
+        self.engine(session_id)
+            .map(|engine| engine.resume())
+            .is_some()
+    }
+
+    /// Cancel a running session. Returns false if it is not running here.
+    #[must_use]
+    pub fn cancel(&self, session_id: &str) -> bool {
+        self.engine(session_id)
+            .map(|engine| engine.cancel())
+            .is_some()
+    }
+
+    /// Let a `CatchUpThenFollow` session complete after the event in
+    /// progress. Returns false if it is not running here.
+    #[must_use]
+    pub fn stop_following(&self, session_id: &str) -> bool {
+        self.engine(session_id)
+            .map(|engine| engine.stop_following())
+            .is_some()
+    }
+
+    /// Process a finished session's dead letters again, in ledger order, with
+    /// the session's dry-run setting and retry limit. Events that succeed leave
+    /// quarantine; the rest stay with their new error. Returns `None` for an
+    /// unknown session.
+    pub async fn retry_dead_letters(
+        &self,
+        session_id: &str,
+    ) -> ReplayResult<Option<DeadLetterRetry>> {
+        if self.is_running(session_id) {
+            return Err(ReplayError::AlreadyInProgress(session_id.to_string()));
+        }
+        let Some(metadata) = self.storage.load_metadata(session_id).await? else {
+            return Ok(None);
+        };
+
+        let context =
+            ProcessingContext::for_replay(session_id.to_string(), metadata.config.is_dry_run());
+        let mut outcome = DeadLetterRetry::default();
+        for dead_letter in self.storage.list_dead_letters(session_id).await? {
+            outcome.retried += 1;
+            let result = self
+                .processor
+                .process_with_retry(&dead_letter.event, &context, metadata.config.max_retries)
+                .await?;
+            if result.success {
+                self.storage
+                    .resolve_dead_letter(session_id, &dead_letter.event)
+                    .await?;
+                outcome.resolved += 1;
+            } else {
+                let error = result.error.unwrap_or_else(|| "Unknown error".to_string());
+                self.storage
+                    .record_dead_letter(session_id, &dead_letter.event, &error)
+                    .await?;
+                outcome.remaining += 1;
+            }
+        }
+
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.dead_letters_retried",
+            session_id,
+            retried = outcome.retried,
+            resolved = outcome.resolved,
+            remaining = outcome.remaining,
+            "Retried dead letters"
+        );
+        Ok(Some(outcome))
+    }
+
+    /// Check if a session is running in this process
+    #[must_use]
+    pub fn is_running(&self, session_id: &str) -> bool {
+        self.running().contains_key(session_id)
+    }
+
+    /// Stored contract events
+    #[must_use]
+    pub const fn event_storage(&self) -> &Arc<EventStorage> {
+        &self.events
+    }
+
+    /// Stored session metadata
+    #[must_use]
+    pub fn storage(&self) -> &ReplayStorage {
+        &self.storage
+    }
+
+    /// Checkpoints of all sessions
+    #[must_use]
+    pub fn checkpoints(&self) -> &CheckpointManager {
+        &self.checkpoints
+    }
+
+    /// Progress updates of the sessions running here
+    #[must_use]
+    pub fn progress(&self) -> &ProgressHub {
+        &self.progress
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::StellarNetwork;
+    use crate::replay::{
+        ContractEvent, EventIngestor, EventProcessor, EventTypeMetrics, IngestConfig,
+        ProcessingResult, ReplayMode, ReplayRange, ReplayStatus, DEFAULT_LIVE_FEED_CAPACITY,
+    };
+    use crate::test_support::{self, migrations};
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use chrono::Utc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    async fn test_pool() -> SqlitePool {
+        let pool = test_support::sqlite_pool(migrations::REPLAY).await;
+        pool
+    }
+
+    fn snapshot_event(ledger: u64) -> ContractEvent {
+        ContractEvent {
+            id: format!("evt-{ledger}"),
+            ledger_sequence: ledger,
+            transaction_hash: format!("{ledger:064x}"),
+            tx_index: 0,
+            event_index: 0,
+            contract_id: "CSNAP".to_string(),
+            event_type: "snapshot_submitted".to_string(),
+            data: serde_json::json!({ "epoch": ledger, "hash": format!("hash-{ledger}") }),
+            timestamp: Utc::now(),
+            network: "mainnet".to_string(),
+        }
+    }
+
+    async fn wait_until_finished(manager: &ReplayManager, session_id: &str) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while manager.is_running(session_id) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    /// Fails the event at ledger 3 while broken.
+    struct Flaky(Arc<AtomicBool>);
+
+    #[async_trait]
+    impl EventProcessor for Flaky {
+        async fn process_event(
+            &self,
+            event: &ContractEvent,
+            _context: &ProcessingContext,
+        ) -> Result<ProcessingResult> {
+            if event.ledger_sequence == 3 && self.0.load(Ordering::SeqCst) {
+                return Ok(ProcessingResult::failure("malformed".to_string()));
+            }
+            Ok(ProcessingResult::success())
+        }
+
+        async fn is_processed(&self, _event: &ContractEvent) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn mark_processed(&self, _event: &ContractEvent) -> Result<()> {
+            Ok(())
+        }
+
+        fn name(&self) -> &'static str {
+            "Flaky"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sessions_are_released_when_finished() {
+        let manager = ReplayManager::new(test_pool().await, Arc::new(ProgressHub::new()));
+
+        let invalid = ReplayConfig::new().with_batch_size(0);
+        assert!(matches!(
+            manager.start(invalid),
+            Err(ReplayError::ConfigError(_))
+        ));
+
+        let config = ReplayConfig::new()
+            .with_range(ReplayRange::FromTo { start: 1, end: 10 })
+            .dry_run();
+        let session_id = manager.start(config).unwrap();
+        assert!(manager.is_running(&session_id));
+        wait_until_finished(&manager, &session_id).await;
+
+        assert!(!manager.cancel(&session_id));
+        let metadata = manager
+            .storage()
+            .load_metadata(&session_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(metadata.status, ReplayStatus::Completed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_failed_events_are_quarantined_and_retried() {
+        let pool = test_pool().await;
+        let storage = EventStorage::new(pool.clone());
+        for ledger in 1..=5 {
+            storage.store_event(&snapshot_event(ledger)).await.unwrap();
+        }
+        let broken = Arc::new(AtomicBool::new(true));
+        let manager = ReplayManager::new(pool, Arc::new(ProgressHub::new())).with_processor(
+            CompositeEventProcessor::new().add_processor(Arc::new(Flaky(Arc::clone(&broken)))),
+        );
+
+        let config = ReplayConfig {
+            max_retries: 0,
+            ..ReplayConfig::new().with_range(ReplayRange::FromTo { start: 1, end: 5 })
+        };
+        let session_id = manager.start(config).unwrap();
+        wait_until_finished(&manager, &session_id).await;
+
+        let metadata = manager
+            .storage()
+            .load_metadata(&session_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            metadata.event_types["snapshot_submitted"],
+            EventTypeMetrics {
+                processed: 4,
+                failed: 1,
+                skipped: 0,
+            }
+        );
+        let dead_letters = manager
+            .storage()
+            .list_dead_letters(&session_id)
+            .await
+            .unwrap();
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!(dead_letters[0].event.ledger_sequence, 3);
+        assert_eq!(dead_letters[0].error, "malformed");
+
+        let still_broken = manager.retry_dead_letters(&session_id).await.unwrap();
+        assert_eq!(
+            still_broken,
+            Some(DeadLetterRetry {
+                retried: 1,
+                resolved: 0,
+                remaining: 1,
+            })
+        );
+        let dead_letters = manager
+            .storage()
+            .list_dead_letters(&session_id)
+            .await
+            .unwrap();
+        assert_eq!(dead_letters[0].attempts, 2);
+
+        broken.store(false, Ordering::SeqCst);
+        let fixed = manager.retry_dead_letters(&session_id).await.unwrap();
+        assert_eq!(fixed.map(|retry| retry.resolved), Some(1));
+        assert!(manager
+            .storage()
+            .list_dead_letters(&session_id)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(manager.retry_dead_letters("unknown").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_follow_sessions_receive_ingested_events() {
+        let pool = test_pool().await;
+        let events =
+            Arc::new(EventStorage::new(pool.clone()).with_live_feed(DEFAULT_LIVE_FEED_CAPACITY));
+        for ledger in 1..=3 {
+            events.store_event(&snapshot_event(ledger)).await.unwrap();
+        }
+        let manager = ReplayManager::new(pool, Arc::new(ProgressHub::new()))
+            .with_event_storage(Arc::clone(&events))
+            .with_processor(
+                CompositeEventProcessor::new()
+                    .add_processor(Arc::new(Flaky(Arc::new(AtomicBool::new(false))))),
+            );
+        let session_id = manager
+            .start(ReplayConfig::new().with_mode(ReplayMode::CatchUpThenFollow))
+            .unwrap();
+
+        // Upstream stands in for Soroban RPC, a few ledgers ahead.
+        let upstream = Arc::new(EventStorage::new(test_pool().await));
+        for ledger in 1..=6 {
+            upstream.store_event(&snapshot_event(ledger)).await.unwrap();
+        }
+        let ingestor = EventIngestor::new(
+            upstream,
+            Arc::clone(manager.event_storage()),
+            &IngestConfig {
+                contract_ids: vec!["CSNAP".to_string()],
+                poll_interval: Duration::from_secs(1),
+            },
+            StellarNetwork::Mainnet,
+        );
+        assert_eq!(ingestor.ingest_after(3).await.unwrap(), (6, 3));
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let metadata = manager.storage().load_metadata(&session_id).await.unwrap();
+                if let Some(ReplayStatus::InProgress {
+                    current_ledger: 6, ..
+                }) = metadata.map(|metadata| metadata.status)
+                {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(manager.stop_following(&session_id));
+        wait_until_finished(&manager, &session_id).await;
+
+        let metadata = manager
+            .storage()
+            .load_metadata(&session_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            metadata.status,
+            ReplayStatus::Completed {
+                events_processed: 6,
+                ..
+            }
+        ));
+    }
+}
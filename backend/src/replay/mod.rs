@@ -5,29 +5,69 @@
 //! idempotency guarantees and comprehensive error handling.
 //!
 //! ## Features
-//! - Deterministic event replay from historical data
+//! - Deterministic event replay from historical data, stored locally or paged
+//!   from Soroban RPC (`RpcEventSource`)
 //! - Idempotent processing (safe to replay multiple times)
-//! - Checkpoint and resume capability
+//! - Checkpoint and resume capability, from periodic compacted state snapshots
+//! - Checkpoint export and import (`CheckpointBundle`), to reproduce a session
+//!   in another environment
 //! - Structured logging and tracing
 //! - Network and contract filtering
+//! - Ledger ranges or time windows (`ReplayRange::TimeWindow`), resolved to
+//!   ledgers through the event source
 //! - Shared processing logic with live event handling
-//! - Performance optimized for large datasets
+//! - Processors routed by the event types and contracts they subscribe to
+//! - Performance optimized for large datasets: events stream in ahead of
+//!   processing within a memory budget (`memory_budget_mb`), and the fetch
+//!   waits while processing catches up
+//! - Parallel batch processing that keeps per-contract ordering (`concurrency`)
+//! - Live tail after historical catch-up (`ReplayMode::CatchUpThenFollow`),
+//!   fed by the events `EventIngestor` stores
+//! - Diff of replayed state against the database (`ReplayMode::Diff`)
+//! - Integrity check of replayed epochs against the hashes anchored on chain
+//!   (`AnchoredSnapshots`)
+//! - Scheduled diffs of the most recent ledgers that alert on divergence
+//!   (`ConsistencyMonitor`)
+//! - Live progress updates per session (`ProgressHub`)
+//! - Per-event-type outcome counts, with failed events quarantined as dead
+//!   letters that can be retried
+//! - Pause, resume and cancel of running sessions (`ReplayManager`)
+//! - Benchmark mode over a fixed synthetic corpus (`bench`)
 
+pub mod bench;
 pub mod checkpoint;
 pub mod config;
+pub mod consistency;
+pub mod diff;
 pub mod engine;
 pub mod event_processor;
+pub mod ingest;
+pub mod integrity;
+pub mod manager;
+pub mod progress;
+pub mod registry;
+pub mod source;
 pub mod state_builder;
 pub mod storage;
+pub mod stream;
 
-pub use checkpoint::{Checkpoint, CheckpointManager};
+pub use checkpoint::{Checkpoint, CheckpointBundle, CheckpointManager};
 pub use config::{ReplayConfig, ReplayMode, ReplayRange};
+pub use consistency::{ConsistencyCheckConfig, ConsistencyMonitor};
+pub use diff::ReplayReport;
 pub use engine::ReplayEngine;
 pub use event_processor::{EventProcessor, ProcessingContext, ProcessingResult};
+pub use ingest::{EventIngestor, IngestConfig};
+pub use integrity::{AnchoredSnapshots, IntegrityReport};
+pub use manager::{DeadLetterRetry, ReplayManager};
+pub use progress::{ProgressHub, ReplayProgress};
+pub use registry::{ProcessorRegistry, ProcessorSubscription};
+pub use source::{EventSource, RpcEventSource};
 pub use state_builder::StateBuilder;
-pub use storage::{EventStorage, ReplayStorage};
+pub use storage::{DeadLetter, EventStorage, ReplayStorage, DEFAULT_LIVE_FEED_CAPACITY};
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 
 /// Represents a contract event from the blockchain
@@ -39,6 +79,12 @@ pub struct ContractEvent {
     pub ledger_sequence: u64,
     /// Transaction hash
     pub transaction_hash: String,
+    /// Position of the transaction in the ledger's apply order
+    #[serde(default)]
+    pub tx_index: u32,
+    /// Position of the event among its transaction's events
+    #[serde(default)]
+    pub event_index: u32,
     /// Contract ID that emitted the event
     pub contract_id: String,
     /// Event type/topic
@@ -97,6 +143,27 @@ pub struct EventFilter {
     pub network: Option<String>,
 }
 
+impl EventFilter {
+    /// Identifies the state a replay with this filter rebuilds: a digest of the
+    /// contracts and event types it selects, whatever their order. The network
+    /// is kept apart from it.
+    #[must_use]
+    pub fn state_scope(&self) -> String {
+        use sha2::{Digest, Sha256};
+        fn canonical(values: &[String]) -> Vec<&str> {
+            let mut values: Vec<&str> = values.iter().map(String::as_str).collect();
+            values.sort_unstable();
+            values.dedup();
+            values
+        }
+        let selection = serde_json::json!({
+            "contract_ids": self.contract_ids.as_deref().map(canonical),
+            "event_types": self.event_types.as_deref().map(canonical),
+        });
+        hex::encode(Sha256::digest(selection.to_string()))
+    }
+}
+
 /// Status of a replay operation
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ReplayStatus {
@@ -134,6 +201,26 @@ pub enum ReplayStatus {
         /// Events processed so far
         events_processed: u64,
     },
+    /// Replay cancelled by an operator
+    Cancelled {
+        /// Last processed ledger
+        last_ledger: u64,
+        /// Events processed before cancelling
+        events_processed: u64,
+        /// Events failed before cancelling
+        events_failed: u64,
+    },
+}
+
+impl ReplayStatus {
+    /// Check if the session has ended and will not change again
+    #[must_use]
+    pub const fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            Self::Completed { .. } | Self::Failed { .. } | Self::Cancelled { .. }
+        )
+    }
 }
 
 impl fmt::Display for ReplayStatus {
@@ -166,6 +253,14 @@ impl fmt::Display for ReplayStatus {
                 f,
                 "Paused (last ledger: {last_ledger}, processed: {events_processed})"
             ),
+            Self::Cancelled {
+                last_ledger,
+                events_processed,
+                events_failed,
+            } => write!(
+                f,
+                "Cancelled (last ledger: {last_ledger}, processed: {events_processed}, failed: {events_failed})"
+            ),
         }
     }
 }
@@ -185,6 +280,27 @@ pub struct ReplayMetadata {
     pub ended_at: Option<chrono::DateTime<chrono::Utc>>,
     /// Checkpoint information
     pub checkpoint: Option<Checkpoint>,
+    /// Differences from the database, for `ReplayMode::Diff` sessions
+    #[serde(default)]
+    pub report: Option<ReplayReport>,
+    /// Replayed epochs compared with their anchored hashes, for sessions
+    /// checked against the chain
+    #[serde(default)]
+    pub integrity: Option<IntegrityReport>,
+    /// Processing outcomes by event type
+    #[serde(default)]
+    pub event_types: BTreeMap<String, EventTypeMetrics>,
+}
+
+/// Processing outcomes for one event type
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventTypeMetrics {
+    /// Events processed successfully
+    pub processed: u64,
+    /// Events that failed and went to the dead letter table
+    pub failed: u64,
+    /// Events skipped as already processed
+    pub skipped: u64,
 }
 
 /// Error types specific to replay operations
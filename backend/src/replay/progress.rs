@@ -0,0 +1,172 @@
+//! Replay Progress
+//!
+//! Live progress updates per replay session, so clients can follow a session
+//! instead of polling its stored metadata.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+use super::{ReplayMetadata, ReplayStatus};
+
+/// Updates buffered per subscriber. A slow subscriber skips to newer updates.
+const PROGRESS_CAPACITY: usize = 64;
+
+/// A progress update for one replay session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayProgress {
+    pub session_id: String,
+    pub status: ReplayStatus,
+    /// First ledger of the replayed range, once known
+    pub start_ledger: Option<u64>,
+    /// Last ledger of the replayed range, once known
+    pub end_ledger: Option<u64>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ReplayProgress {
+    /// Progress for a session's current metadata
+    #[must_use]
+    pub fn from_metadata(metadata: &ReplayMetadata, range: Option<(u64, u64)>) -> Self {
+        Self {
+            session_id: metadata.session_id.clone(),
+            status: metadata.status.clone(),
+            start_ledger: range.map(|(start, _)| start),
+            end_ledger: range.map(|(_, end)| end),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Sessions {
+    channels: HashMap<String, broadcast::Sender<ReplayProgress>>,
+    running: HashSet<String>,
+}
+
+/// Progress channels keyed by session id, for the sessions running in this
+/// process.
+///
+/// A channel exists only while someone is subscribed, so publishing for a
+/// session nobody follows costs a map lookup.
+#[derive(Debug, Default)]
+pub struct ProgressHub {
+    sessions: Mutex<Sessions>,
+}
+
+impl ProgressHub {
+    /// Create a hub with no subscribers
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn sessions(&self) -> std::sync::MutexGuard<'_, Sessions> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Mark a session as running here until it publishes a finished status
+    pub fn track(&self, session_id: &str) {
+        self.sessions().running.insert(session_id.to_string());
+    }
+
+    /// Check if a session will publish further updates through this hub
+    #[must_use]
+    pub fn is_running(&self, session_id: &str) -> bool {
+        self.sessions().running.contains(session_id)
+    }
+
+    /// Receive updates for a session from now on. The channel closes after
+    /// the session completes or fails.
+    pub fn subscribe(&self, session_id: &str) -> broadcast::Receiver<ReplayProgress> {
+        let mut sessions = self.sessions();
+        sessions
+            .channels
+            .retain(|_, sender| sender.receiver_count() > 0);
+        sessions
+            .channels
+            .entry(session_id.to_string())
+            .or_insert_with(|| broadcast::channel(PROGRESS_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// Send an update to the session's subscribers
+    pub fn publish(&self, progress: ReplayProgress) {
+        let mut sessions = self.sessions();
+        let session_id = progress.session_id.clone();
+        let finished = progress.status.is_finished();
+        if let Some(sender) = sessions.channels.get(&session_id) {
+            // Subscribers may all have gone; they are pruned below.
+            let _ = sender.send(progress);
+        }
+        if finished {
+            sessions.channels.remove(&session_id);
+            sessions.running.remove(&session_id);
+        }
+        sessions
+            .channels
+            .retain(|_, sender| sender.receiver_count() > 0);
+    }
+
+    /// Number of sessions with at least one subscriber
+    #[must_use]
+    pub fn active_sessions(&self) -> usize {
+        self.sessions()
+            .channels
+            .values()
+            .filter(|sender| sender.receiver_count() > 0)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::broadcast::error::RecvError;
+
+    fn progress(session_id: &str, status: ReplayStatus) -> ReplayProgress {
+        ReplayProgress {
+            session_id: session_id.to_string(),
+            status,
+            start_ledger: Some(1),
+            end_ledger: Some(10),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hub_streams_until_session_finishes() {
+        let hub = ProgressHub::new();
+        // Nobody is subscribed yet; this update is dropped.
+        hub.publish(progress("a", ReplayStatus::Pending));
+
+        hub.track("a");
+        let mut updates = hub.subscribe("a");
+        let mut other = hub.subscribe("b");
+        let running = ReplayStatus::InProgress {
+            current_ledger: 5,
+            events_processed: 3,
+            events_failed: 0,
+        };
+        let done = ReplayStatus::Completed {
+            events_processed: 8,
+            events_failed: 0,
+            duration_secs: 1,
+        };
+        hub.publish(progress("a", running.clone()));
+        hub.publish(progress("a", done.clone()));
+
+        assert_eq!(updates.recv().await.unwrap().status, running);
+        assert_eq!(updates.recv().await.unwrap().status, done);
+        assert!(!hub.is_running("a"));
+        assert!(matches!(updates.recv().await, Err(RecvError::Closed)));
+        assert!(other.try_recv().is_err());
+
+        drop(other);
+        assert_eq!(hub.active_sessions(), 0);
+    }
+}
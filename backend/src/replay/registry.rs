@@ -0,0 +1,231 @@
+//! Processor Registry
+//!
+//! Routes events to the processors that asked for them. Each processor declares
+//! its subscriptions through [`EventProcessor::subscriptions`], so adding one
+//! never needs changes to the engine.
+
+use std::sync::Arc;
+
+use super::{event_processor::EventProcessor, ContractEvent};
+
+/// Events a processor wants to see. A `None` field matches anything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessorSubscription {
+    /// Only events of this type
+    pub event_type: Option<String>,
+    /// Only events emitted by this contract
+    pub contract_id: Option<String>,
+}
+
+impl ProcessorSubscription {
+    /// Subscribe to every event
+    #[must_use]
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Subscribe to one event type from any contract
+    #[must_use]
+    pub fn event_type(event_type: impl Into<String>) -> Self {
+        Self {
+            event_type: Some(event_type.into()),
+            contract_id: None,
+        }
+    }
+
+    /// Subscribe to every event from one contract
+    #[must_use]
+    pub fn contract(contract_id: impl Into<String>) -> Self {
+        Self {
+            event_type: None,
+            contract_id: Some(contract_id.into()),
+        }
+    }
+
+    /// Narrow the subscription to one contract
+    #[must_use]
+    pub fn from_contract(mut self, contract_id: impl Into<String>) -> Self {
+        self.contract_id = Some(contract_id.into());
+        self
+    }
+
+    /// Check if an event falls under this subscription
+    #[must_use]
+    pub fn matches(&self, event: &ContractEvent) -> bool {
+        self.event_type
+            .as_ref()
+            .is_none_or(|event_type| *event_type == event.event_type)
+            && self
+                .contract_id
+                .as_ref()
+                .is_none_or(|contract_id| *contract_id == event.contract_id)
+    }
+}
+
+#[derive(Clone)]
+struct Registration {
+    processor: Arc<dyn EventProcessor>,
+    subscriptions: Vec<ProcessorSubscription>,
+}
+
+/// Processors in registration order with the subscriptions they declared.
+#[derive(Clone, Default)]
+pub struct ProcessorRegistry {
+    registrations: Vec<Registration>,
+}
+
+impl ProcessorRegistry {
+    /// Create an empty registry
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a processor under the subscriptions it declares
+    pub fn register(&mut self, processor: Arc<dyn EventProcessor>) {
+        let subscriptions = processor.subscriptions();
+        self.registrations.push(Registration {
+            processor,
+            subscriptions,
+        });
+    }
+
+    /// Processors subscribed to an event, in registration order
+    pub fn processors_for<'a>(
+        &'a self,
+        event: &'a ContractEvent,
+    ) -> impl Iterator<Item = &'a Arc<dyn EventProcessor>> + 'a {
+        self.registrations
+            .iter()
+            .filter(|registration| {
+                registration
+                    .subscriptions
+                    .iter()
+                    .any(|subscription| subscription.matches(event))
+            })
+            .map(|registration| &registration.processor)
+    }
+
+    /// Names of the registered processors, in registration order
+    #[must_use]
+    pub fn names(&self) -> Vec<String> {
+        self.registrations
+            .iter()
+            .map(|registration| registration.processor.name().to_string())
+            .collect()
+    }
+
+    /// Number of registered processors
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.registrations.len()
+    }
+
+    /// Check if no processor is registered
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.registrations.is_empty()
+    }
+}
+
+impl std::fmt::Debug for ProcessorRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::event_processor::{ProcessingContext, ProcessingResult};
+    use anyhow::Result;
+    use async_trait::async_trait;
+
+    struct Named {
+        name: &'static str,
+        subscriptions: Vec<ProcessorSubscription>,
+    }
+
+    #[async_trait]
+    impl EventProcessor for Named {
+        async fn process_event(
+            &self,
+            _event: &ContractEvent,
+            _context: &ProcessingContext,
+        ) -> Result<ProcessingResult> {
+            Ok(ProcessingResult::success())
+        }
+
+        async fn is_processed(&self, _event: &ContractEvent) -> Result<bool> {
+            Ok(false)
+        }
+
+        async fn mark_processed(&self, _event: &ContractEvent) -> Result<()> {
+            Ok(())
+        }
+
+        fn subscriptions(&self) -> Vec<ProcessorSubscription> {
+            self.subscriptions.clone()
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+    }
+
+    fn event(contract_id: &str, event_type: &str) -> ContractEvent {
+        ContractEvent {
+            id: "evt".to_string(),
+            ledger_sequence: 1,
+            transaction_hash: "tx".to_string(),
+            tx_index: 0,
+            event_index: 0,
+            contract_id: contract_id.to_string(),
+            event_type: event_type.to_string(),
+            data: serde_json::json!({}),
+            timestamp: chrono::Utc::now(),
+            network: "testnet".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_routes_by_event_type_and_contract() {
+        let mut registry = ProcessorRegistry::new();
+        registry.register(Arc::new(Named {
+            name: "snapshots",
+            subscriptions: vec![ProcessorSubscription::event_type("snapshot_submitted")],
+        }));
+        registry.register(Arc::new(Named {
+            name: "anchor",
+            subscriptions: vec![
+                ProcessorSubscription::contract("CANCHOR"),
+                ProcessorSubscription::event_type("snapshot_verified").from_contract("CSNAP"),
+            ],
+        }));
+        registry.register(Arc::new(Named {
+            name: "audit",
+            subscriptions: vec![ProcessorSubscription::all()],
+        }));
+
+        let names = |event: &ContractEvent| -> Vec<String> {
+            registry
+                .processors_for(event)
+                .map(|p| p.name().to_string())
+                .collect()
+        };
+        assert_eq!(
+            names(&event("CSNAP", "snapshot_submitted")),
+            ["snapshots", "audit"]
+        );
+        assert_eq!(
+            names(&event("CANCHOR", "snapshot_submitted")),
+            ["snapshots", "anchor", "audit"]
+        );
+        assert_eq!(
+            names(&event("CSNAP", "snapshot_verified")),
+            ["anchor", "audit"]
+        );
+        assert_eq!(names(&event("COTHER", "snapshot_verified")), ["audit"]);
+        assert_eq!(registry.names(), ["snapshots", "anchor", "audit"]);
+    }
+}
@@ -0,0 +1,714 @@
+//! Event Sources
+//!
+//! Where a replay reads its events from. [`EventStorage`] serves what was
+//! ingested locally; [`RpcEventSource`] pages `getEvents` from Soroban RPC, so
+//! ranges that were never ingested can be replayed too, within the RPC's
+//! retention window.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use super::{storage::EventStorage, ContractEvent, EventFilter};
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::rpc::circuit_breaker::{rpc_circuit_breaker, SharedCircuitBreaker};
+use crate::rpc::config::{initial_backoff_from_env, max_backoff_from_env, max_retries_from_env};
+use crate::rpc::error::{with_retry, RetryConfig, RpcError};
+
+/// A source of historical contract events, in ledger order
+#[async_trait]
+pub trait EventSource: Send + Sync {
+    /// Get events in a ledger range, both ends inclusive, ordered by ledger
+    async fn get_events_in_range(
+        &self,
+        start_ledger: u64,
+        end_ledger: u64,
+        filter: &EventFilter,
+        limit: Option<usize>,
+    ) -> Result<Vec<ContractEvent>>;
+
+    /// Get the latest ledger the source can serve
+    async fn get_latest_ledger(&self) -> Result<Option<u64>>;
+
+    /// Resolve a time window, start inclusive and end exclusive, to the first
+    /// and last ledger closed within it. `None` if the source has nothing
+    /// there.
+    async fn ledgers_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Option<(u64, u64)>>;
+
+    /// Source name for logging
+    fn name(&self) -> &str;
+}
+
+#[async_trait]
+impl EventSource for EventStorage {
+    async fn get_events_in_range(
+        &self,
+        start_ledger: u64,
+        end_ledger: u64,
+        filter: &EventFilter,
+        limit: Option<usize>,
+    ) -> Result<Vec<ContractEvent>> {
+        Self::get_events_in_range(self, start_ledger, end_ledger, filter, limit).await
+    }
+
+    async fn get_latest_ledger(&self) -> Result<Option<u64>> {
+        Self::get_latest_ledger(self).await
+    }
+
+    async fn ledgers_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Option<(u64, u64)>> {
+        self.get_ledgers_between(start, end).await
+    }
+
+    fn name(&self) -> &'static str {
+        "contract_events"
+    }
+}
+
+/// Default number of events requested per `getEvents` page
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Topic published by the snapshot contract on `submit_snapshot`
+const TOPIC_SNAPSHOT_SUBMITTED: &str = "SNAP_SUB";
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse<T> {
+    result: Option<T>,
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcError {
+    code: i32,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GetEventsPage {
+    #[serde(default)]
+    events: Vec<RpcEvent>,
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RpcEvent {
+    id: String,
+    ledger: u64,
+    ledger_closed_at: String,
+    contract_id: String,
+    #[serde(default)]
+    topic: Vec<String>,
+    #[serde(default)]
+    value: serde_json::Value,
+    #[serde(default)]
+    tx_hash: String,
+    in_successful_contract_call: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct LatestLedger {
+    sequence: u64,
+}
+
+/// Ledgers the RPC retains, from `getHealth`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Retention {
+    latest_ledger: u64,
+    oldest_ledger: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetLedgersPage {
+    #[serde(default)]
+    ledgers: Vec<LedgerInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LedgerInfo {
+    sequence: u64,
+    /// Unix seconds, as a string
+    ledger_close_time: String,
+}
+
+/// Events paged from a Soroban RPC endpoint with `getEvents`.
+///
+/// Requests go through the shared RPC circuit breaker and retry transient
+/// failures with the same backoff settings as [`crate::rpc::StellarRpcClient`].
+/// Events from failed contract calls are skipped, as they changed no state.
+pub struct RpcEventSource {
+    client: Client,
+    rpc_url: String,
+    network: String,
+    page_size: usize,
+    topics: HashMap<String, String>,
+    retry_config: RetryConfig,
+    circuit_breaker: SharedCircuitBreaker,
+}
+
+impl RpcEventSource {
+    /// Create a source for `rpc_url`, labelling events with `network`
+    pub fn new(rpc_url: impl Into<String>, network: impl Into<String>) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            client,
+            rpc_url: rpc_url.into(),
+            network: network.into(),
+            page_size: DEFAULT_PAGE_SIZE,
+            topics: HashMap::from([(
+                TOPIC_SNAPSHOT_SUBMITTED.to_string(),
+                "snapshot_submitted".to_string(),
+            )]),
+            retry_config: RetryConfig {
+                max_attempts: max_retries_from_env() + 1,
+                base_delay_ms: u64::try_from(initial_backoff_from_env().as_millis())
+                    .unwrap_or(u64::MAX),
+                max_delay_ms: u64::try_from(max_backoff_from_env().as_millis()).unwrap_or(u64::MAX),
+            },
+            circuit_breaker: rpc_circuit_breaker(),
+        })
+    }
+
+    /// Set how many events each `getEvents` request asks for
+    #[must_use]
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Replay events carrying `topic` as `event_type`. Events with no mapped
+    /// topic use their first topic as the type.
+    #[must_use]
+    pub fn with_topic(mut self, topic: &str, event_type: &str) -> Self {
+        self.topics
+            .insert(topic.to_string(), event_type.to_string());
+        self
+    }
+
+    /// Override the retry settings taken from the environment
+    #[must_use]
+    pub const fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Use a dedicated circuit breaker instead of the shared RPC one
+    #[must_use]
+    pub fn with_circuit_breaker(mut self, circuit_breaker: SharedCircuitBreaker) -> Self {
+        self.circuit_breaker = circuit_breaker;
+        self
+    }
+
+    async fn call<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: &serde_json::Value,
+    ) -> Result<T, RpcError> {
+        let payload = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        with_retry(
+            || async {
+                let response = self
+                    .client
+                    .post(&self.rpc_url)
+                    .json(&payload)
+                    .send()
+                    .await
+                    .map_err(|e| RpcError::NetworkError(e.to_string()))?;
+
+                let status = response.status();
+                if status.as_u16() == 429 {
+                    return Err(RpcError::RateLimitError { retry_after: None });
+                }
+                if !status.is_success() {
+                    return Err(RpcError::ServerError {
+                        status: status.as_u16(),
+                        message: response.text().await.unwrap_or_default(),
+                    });
+                }
+
+                let body: JsonRpcResponse<T> = response
+                    .json()
+                    .await
+                    .map_err(|e| RpcError::ParseError(e.to_string()))?;
+                if let Some(error) = body.error {
+                    return Err(RpcError::ServerError {
+                        status: 500,
+                        message: format!("RPC error: {} (code: {})", error.message, error.code),
+                    });
+                }
+                body.result
+                    .ok_or_else(|| RpcError::ParseError(format!("No result in {method} response")))
+            },
+            self.retry_config.clone(),
+            self.circuit_breaker.clone(),
+        )
+        .await
+    }
+
+    /// When `ledger` closed, if the RPC still retains it
+    async fn ledger_close_time(&self, ledger: u64) -> Result<Option<DateTime<Utc>>> {
+        let page: GetLedgersPage = self
+            .call(
+                "getLedgers",
+                &json!({ "startLedger": ledger, "pagination": { "limit": 1 } }),
+            )
+            .await
+            .with_context(|| format!("getLedgers failed for ledger {ledger}"))?;
+
+        match page.ledgers.first() {
+            Some(info) if info.sequence == ledger => {
+                let secs: i64 = info.ledger_close_time.parse().with_context(|| {
+                    format!(
+                        "Bad close time for ledger {ledger}: {}",
+                        info.ledger_close_time
+                    )
+                })?;
+                Ok(DateTime::from_timestamp(secs, 0))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// First retained ledger in `[low, high)` that closed at or after `at`,
+    /// or `high` if there is none
+    async fn first_ledger_closed_from(
+        &self,
+        at: DateTime<Utc>,
+        mut low: u64,
+        mut high: u64,
+    ) -> Result<u64> {
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.ledger_close_time(mid).await? {
+                Some(closed_at) if closed_at >= at => high = mid,
+                Some(_) => low = mid + 1,
+                None => anyhow::bail!("Ledger {mid} is outside the RPC retention window"),
+            }
+        }
+        Ok(low)
+    }
+
+    fn to_contract_event(&self, event: RpcEvent) -> Result<Option<ContractEvent>> {
+        let event_type = event
+            .topic
+            .iter()
+            .find_map(|topic| self.topics.get(topic))
+            .or_else(|| event.topic.first());
+        let Some(event_type) = event_type.cloned() else {
+            log_event!(
+                debug,
+                Subsystem::Replay,
+                "replay.rpc_event_skipped",
+                event_id = %event.id,
+                "Skipping RPC event without topics"
+            );
+            return Ok(None);
+        };
+
+        let timestamp = DateTime::parse_from_rfc3339(&event.ledger_closed_at)
+            .with_context(|| format!("Invalid ledgerClosedAt on RPC event {}", event.id))?
+            .with_timezone(&Utc);
+
+        let (tx_index, event_index) = event_position(&event.id).unwrap_or_else(|| {
+            log_event!(
+                debug,
+                Subsystem::Replay,
+                "replay.rpc_event_unpositioned",
+                event_id = %event.id,
+                "No position in RPC event id"
+            );
+            (0, 0)
+        });
+
+        Ok(Some(ContractEvent {
+            id: event.id,
+            ledger_sequence: event.ledger,
+            transaction_hash: event.tx_hash,
+            tx_index,
+            event_index,
+            contract_id: event.contract_id,
+            event_type,
+            data: event.value,
+            timestamp,
+            network: self.network.clone(),
+        }))
+    }
+}
+
+/// Transaction and event position encoded in an RPC event id,
+/// `<TOID>-<event index>`. The TOID packs the ledger (32 bits), the
+/// transaction's apply order (20 bits) and the operation index (12 bits).
+fn event_position(id: &str) -> Option<(u32, u32)> {
+    let (toid, event_index) = id.split_once('-')?;
+    let toid: u64 = toid.parse().ok()?;
+    let tx_index = u32::try_from((toid >> 12) & 0xF_FFFF).ok()?;
+    Some((tx_index, event_index.parse().ok()?))
+}
+
+#[async_trait]
+impl EventSource for RpcEventSource {
+    async fn get_events_in_range(
+        &self,
+        start_ledger: u64,
+        end_ledger: u64,
+        filter: &EventFilter,
+        limit: Option<usize>,
+    ) -> Result<Vec<ContractEvent>> {
+        let wanted = limit.unwrap_or(usize::MAX);
+        if start_ledger > end_ledger
+            || wanted == 0
+            || filter
+                .network
+                .as_ref()
+                .is_some_and(|network| *network != self.network)
+        {
+            return Ok(Vec::new());
+        }
+
+        let filters = filter.contract_ids.as_ref().map_or_else(Vec::new, |ids| {
+            vec![json!({ "type": "contract", "contractIds": ids })]
+        });
+
+        let mut events = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            // The first page is addressed by ledger, later ones by cursor.
+            // `endLedger` is exclusive.
+            let params = cursor.as_ref().map_or_else(
+                || {
+                    json!({
+                        "startLedger": start_ledger,
+                        "endLedger": end_ledger + 1,
+                        "filters": filters,
+                        "pagination": { "limit": self.page_size },
+                    })
+                },
+                |cursor| {
+                    json!({
+                        "filters": filters,
+                        "pagination": { "cursor": cursor, "limit": self.page_size },
+                    })
+                },
+            );
+
+            let page: GetEventsPage = self.call("getEvents", &params).await.with_context(|| {
+                format!("getEvents failed for ledgers {start_ledger} to {end_ledger}")
+            })?;
+            let full_page = page.events.len() >= self.page_size;
+            let next_cursor = page
+                .cursor
+                .or_else(|| page.events.last().map(|event| event.id.clone()));
+
+            for event in page.events {
+                if event.ledger > end_ledger {
+                    return Ok(events);
+                }
+                if event.ledger < start_ledger || !event.in_successful_contract_call {
+                    continue;
+                }
+                if let Some(event) = self.to_contract_event(event)? {
+                    if event.matches_filter(filter) {
+                        events.push(event);
+                        if events.len() >= wanted {
+                            return Ok(events);
+                        }
+                    }
+                }
+            }
+
+            match next_cursor {
+                Some(next) if full_page && cursor.as_ref() != Some(&next) => cursor = Some(next),
+                _ => return Ok(events),
+            }
+        }
+    }
+
+    async fn get_latest_ledger(&self) -> Result<Option<u64>> {
+        let latest: LatestLedger = self
+            .call("getLatestLedger", &json!({}))
+            .await
+            .context("getLatestLedger failed")?;
+        Ok(Some(latest.sequence))
+    }
+
+    /// Binary searches `getLedgers` over the retention window, so resolving
+    /// a window costs a few dozen requests whatever its length.
+    async fn ledgers_between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Option<(u64, u64)>> {
+        let retention: Retention = self
+            .call("getHealth", &json!({}))
+            .await
+            .context("getHealth failed")?;
+        let past_latest = retention.latest_ledger + 1;
+        let first = self
+            .first_ledger_closed_from(start, retention.oldest_ledger, past_latest)
+            .await?;
+        let after = self
+            .first_ledger_closed_from(end, first, past_latest)
+            .await?;
+        log_event!(
+            debug,
+            Subsystem::Replay,
+            "replay.time_range_resolved",
+            start = %start,
+            end = %end,
+            first_ledger = first,
+            after_ledger = after,
+            rpc_url = %self.rpc_url,
+            "Resolved time range to ledgers"
+        );
+
+        Ok((first < after).then(|| (first, after - 1)))
+    }
+
+    fn name(&self) -> &str {
+        &self.rpc_url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(Clone)]
+    struct MockRpc {
+        events: Arc<Vec<serde_json::Value>>,
+        requests: Arc<AtomicUsize>,
+    }
+
+    fn rpc_event(
+        id: &str,
+        ledger: u64,
+        contract: &str,
+        topic: &str,
+        ok: bool,
+    ) -> serde_json::Value {
+        json!({
+            "type": "contract",
+            "id": id,
+            "ledger": ledger,
+            "ledgerClosedAt": "2026-01-01T00:00:00Z",
+            "contractId": contract,
+            "topic": [topic],
+            "value": { "epoch": ledger, "hash": format!("hash-{id}") },
+            "txHash": format!("tx-{id}"),
+            "inSuccessfulContractCall": ok,
+        })
+    }
+
+    /// Serves `getEvents` pages by cursor and rate-limits every third request.
+    async fn handle(
+        State(rpc): State<MockRpc>,
+        Json(request): Json<serde_json::Value>,
+    ) -> Result<Json<serde_json::Value>, StatusCode> {
+        if rpc.requests.fetch_add(1, Ordering::SeqCst) % 3 == 1 {
+            return Err(StatusCode::TOO_MANY_REQUESTS);
+        }
+
+        let params = &request["params"];
+        let result = match request["method"].as_str() {
+            Some("getLatestLedger") => json!({ "id": "x", "sequence": 42 }),
+            Some("getHealth") => {
+                json!({ "status": "healthy", "latestLedger": 42, "oldestLedger": 3 })
+            }
+            // Ledger n closed 5n seconds after the epoch.
+            Some("getLedgers") => {
+                let sequence = params["startLedger"].as_u64().unwrap();
+                let ledgers: Vec<_> = (sequence..=42)
+                    .take(1)
+                    .map(|n| json!({ "sequence": n, "ledgerCloseTime": (5 * n).to_string() }))
+                    .collect();
+                json!({ "ledgers": ledgers, "latestLedger": 42, "oldestLedger": 3 })
+            }
+            Some("getEvents") => {
+                let limit =
+                    usize::try_from(params["pagination"]["limit"].as_u64().unwrap()).unwrap();
+                let from = params["pagination"]["cursor"].as_str().map_or_else(
+                    || {
+                        let start = params["startLedger"].as_u64().unwrap();
+                        rpc.events
+                            .iter()
+                            .position(|event| event["ledger"].as_u64().unwrap() >= start)
+                            .unwrap_or(rpc.events.len())
+                    },
+                    |cursor| {
+                        rpc.events
+                            .iter()
+                            .position(|event| event["id"] == cursor)
+                            .unwrap()
+                            + 1
+                    },
+                );
+                let page: Vec<_> = rpc.events.iter().skip(from).take(limit).cloned().collect();
+                let cursor = page.last().map(|event| event["id"].clone());
+                json!({ "events": page, "latestLedger": 42, "cursor": cursor })
+            }
+            _ => return Err(StatusCode::BAD_REQUEST),
+        };
+        Ok(Json(json!({ "jsonrpc": "2.0", "id": 1, "result": result })))
+    }
+
+    async fn serve(events: Vec<serde_json::Value>) -> (String, Arc<AtomicUsize>) {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let app = Router::new().route("/", post(handle)).with_state(MockRpc {
+            events: Arc::new(events),
+            requests: Arc::clone(&requests),
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (url, requests)
+    }
+
+    fn source(url: &str) -> RpcEventSource {
+        RpcEventSource::new(url, "testnet")
+            .unwrap()
+            .with_page_size(2)
+            .with_topic("REM_SETL", "remittance_settled")
+            .with_retry_config(RetryConfig {
+                max_attempts: 3,
+                base_delay_ms: 1,
+                max_delay_ms: 5,
+            })
+    }
+
+    #[test]
+    fn test_event_position_from_rpc_id() {
+        // Ledger 3, third transaction, first operation, second event.
+        let toid = (3_u64 << 32) | (2 << 12);
+        assert_eq!(
+            event_position(&format!("{toid:019}-0000000001")),
+            Some((2, 1))
+        );
+        assert_eq!(event_position("e1"), None);
+    }
+
+    #[tokio::test]
+    async fn test_rpc_source_pages_get_events() {
+        let (url, requests) = serve(vec![
+            rpc_event("e1", 5, "CSNAP", "SNAP_SUB", true),
+            rpc_event("e2", 10, "CSNAP", "SNAP_SUB", true),
+            rpc_event("e3", 10, "CSNAP", "SNAP_SUB", false),
+            rpc_event("e4", 12, "CREM", "REM_SETL", true),
+            rpc_event("e5", 15, "CSNAP", "OTHER", true),
+            rpc_event("e6", 20, "CSNAP", "SNAP_SUB", true),
+            rpc_event("e7", 21, "CSNAP", "SNAP_SUB", true),
+        ])
+        .await;
+        let source = source(&url);
+
+        let events = source
+            .get_events_in_range(10, 20, &EventFilter::default(), None)
+            .await
+            .unwrap();
+        let summary: Vec<_> = events
+            .iter()
+            .map(|event| {
+                (
+                    event.id.as_str(),
+                    event.ledger_sequence,
+                    event.event_type.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("e2", 10, "snapshot_submitted"),
+                ("e4", 12, "remittance_settled"),
+                ("e5", 15, "OTHER"),
+                ("e6", 20, "snapshot_submitted"),
+            ]
+        );
+        assert_eq!(events[0].transaction_hash, "tx-e2");
+        assert_eq!(events[0].network, "testnet");
+        assert_eq!(events[0].data["hash"], "hash-e2");
+        // Three pages, the second of them retried after being rate limited.
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+
+        let filter = EventFilter {
+            contract_ids: Some(vec!["CSNAP".to_string()]),
+            ..EventFilter::default()
+        };
+        let limited = source
+            .get_events_in_range(0, 100, &filter, Some(3))
+            .await
+            .unwrap();
+        assert_eq!(
+            limited.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(),
+            ["e1", "e2", "e5"]
+        );
+
+        let other_network = EventFilter {
+            network: Some("mainnet".to_string()),
+            ..EventFilter::default()
+        };
+        assert!(source
+            .get_events_in_range(0, 100, &other_network, None)
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(source.get_latest_ledger().await.unwrap(), Some(42));
+    }
+
+    #[tokio::test]
+    async fn test_rpc_source_resolves_time_window() {
+        let (url, _) = serve(Vec::new()).await;
+        let source = source(&url);
+        let at = |secs| DateTime::from_timestamp(secs, 0).unwrap();
+
+        // Ledger 20 closed at 100s; the window end is exclusive.
+        assert_eq!(
+            source.ledgers_between(at(98), at(150)).await.unwrap(),
+            Some((20, 29))
+        );
+        // Clamped to what the RPC retains.
+        assert_eq!(
+            source.ledgers_between(at(0), at(1_000)).await.unwrap(),
+            Some((3, 42))
+        );
+        assert_eq!(
+            source.ledgers_between(at(101), at(104)).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            source.ledgers_between(at(500), at(600)).await.unwrap(),
+            None
+        );
+    }
+}
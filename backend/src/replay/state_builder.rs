@@ -1,14 +1,29 @@
 //! State Builder
 //!
 //! Rebuilds application state from contract events in a deterministic manner.
+//! A state too large to hold in memory is moved to `replay_state_entries` and
+//! read back from there as events refer to it.
 
 use anyhow::{Context, Result};
+use futures::TryStreamExt;
 use serde::{Deserialize, Serialize};
-use sqlx::SqlitePool;
+use sha2::{Digest, Sha256};
+use sqlx::{SqliteConnection, SqlitePool};
+use std::borrow::Cow;
 use std::collections::HashMap;
-use tracing::{debug, info};
 
-use super::{ContractEvent, ProcessingResult};
+use super::integrity::{self, AnchoredSnapshots, IntegrityReport};
+use super::{diff, ContractEvent, EventFilter, ProcessingResult, ReplayReport};
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::network::StellarNetwork;
+
+/// `replay_state_entries` kind of a [`SnapshotState`], keyed by epoch
+const SNAPSHOT_ENTRY: &str = "snapshot";
+
+/// `replay_state_entries` kind of a [`VerificationState`], keyed by
+/// `epoch:verifier`
+const VERIFICATION_ENTRY: &str = "verification";
 
 /// Represents the application state at a specific point in time
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,10 +71,39 @@ impl ApplicationState {
         Ok(serde_json::from_value(value.clone())?)
     }
 
+    /// Approximate memory held by the state, in bytes. Cheaper than
+    /// serializing it, and close enough to enforce a budget.
+    #[must_use]
+    pub fn estimated_size(&self) -> usize {
+        const ENTRY: usize = 64;
+        let snapshots: usize = self
+            .snapshots
+            .values()
+            .map(|snapshot| {
+                ENTRY
+                    + size_of::<SnapshotState>()
+                    + snapshot.hash.len()
+                    + snapshot.transaction_hash.len()
+            })
+            .sum();
+        let verifications: usize = self
+            .verifications
+            .iter()
+            .map(|(key, verification)| {
+                ENTRY + key.len() + size_of::<VerificationState>() + verification.verifier.len()
+            })
+            .sum();
+        let metadata: usize = self
+            .metadata
+            .iter()
+            .map(|(key, value)| ENTRY + key.len() + value.to_string().len())
+            .sum();
+        size_of::<Self>() + snapshots + verifications + metadata
+    }
+
     /// Compute state hash for verification
     #[must_use]
     pub fn compute_hash(&self) -> String {
-        use sha2::{Digest, Sha256};
         let json = self.to_json().unwrap_or_default();
         let json_str = serde_json::to_string(&json).unwrap_or_default();
         let hash = Sha256::digest(json_str.as_bytes());
@@ -88,42 +132,85 @@ pub struct VerificationState {
     pub verifier: String,
     pub epoch: u64,
     pub verified_at: chrono::DateTime<chrono::Utc>,
+    /// Ledger the verification was applied at
+    #[serde(default)]
+    pub ledger: u64,
 }
 
-/// Builds application state from events
+/// Builds application state from events. Persisted states are kept per
+/// network and per [state scope](EventFilter::state_scope), and a builder only
+/// sees those of its own.
 pub struct StateBuilder {
     pool: SqlitePool,
+    network: StellarNetwork,
+    /// Scope of the filter whose events the state is built from
+    scope: String,
     state: ApplicationState,
+    /// The state's entries are in `replay_state_entries`, and memory only holds
+    /// those applied since they were last written there
+    external: bool,
 }
 
 impl StateBuilder {
-    /// Create a new state builder
+    /// Create a new state builder, for replays of all events of the network
+    #[must_use]
+    pub fn new(pool: SqlitePool, network: StellarNetwork) -> Self {
+        Self::with_state(pool, network, ApplicationState::new())
+    }
+
+    /// Create state builder with initial state
     #[must_use]
-    pub fn new(pool: SqlitePool) -> Self {
+    pub fn with_state(pool: SqlitePool, network: StellarNetwork, state: ApplicationState) -> Self {
         Self {
             pool,
-            state: ApplicationState::new(),
+            network,
+            scope: EventFilter::default().state_scope(),
+            state,
+            external: false,
         }
     }
 
-    /// Create state builder with initial state
+    /// Build the state of replays with `filter` rather than of all events
+    #[must_use]
+    pub fn for_filter(mut self, filter: &EventFilter) -> Self {
+        self.scope = filter.state_scope();
+        self
+    }
+
+    /// Network whose state this builds
     #[must_use]
-    pub const fn with_state(pool: SqlitePool, state: ApplicationState) -> Self {
-        Self { pool, state }
+    pub const fn network(&self) -> StellarNetwork {
+        self.network
     }
 
-    /// Get current state
+    /// [State scope](EventFilter::state_scope) of the filter this builds for
+    #[must_use]
+    pub fn scope(&self) -> &str {
+        &self.scope
+    }
+
+    /// Get current state. Entries moved out of memory by [`Self::evict`] are
+    /// not included.
     #[must_use]
     pub const fn state(&self) -> &ApplicationState {
         &self.state
     }
 
+    /// Whether part of the state has been moved out of memory
+    #[must_use]
+    pub const fn has_evicted_entries(&self) -> bool {
+        self.external
+    }
+
     /// Apply an event to the state
     pub async fn apply_event(&mut self, event: &ContractEvent) -> Result<ProcessingResult> {
-        debug!(
-            "Applying event {} to state at ledger {}",
-            event.unique_id(),
-            self.state.ledger
+        log_event!(
+            debug,
+            Subsystem::Replay,
+            "replay.event_applying",
+            event_id = %event.unique_id(),
+            ledger = self.state.ledger,
+            "Applying event to state"
         );
 
         // Update ledger
@@ -136,7 +223,13 @@ impl StateBuilder {
             "snapshot_submitted" => self.apply_snapshot_submission(event).await,
             "snapshot_verified" => self.apply_snapshot_verification(event).await,
             _ => {
-                debug!("Unknown event type: {}", event.event_type);
+                log_event!(
+                    debug,
+                    Subsystem::Replay,
+                    "replay.event_type_unknown",
+                    event_type = %event.event_type,
+                    "Unknown event type"
+                );
                 Ok(ProcessingResult::success())
             }
         }
@@ -161,7 +254,11 @@ impl StateBuilder {
             .to_string();
 
         // Check if already exists (idempotency)
-        if self.state.snapshots.contains_key(&epoch) {
+        if self.state.snapshots.contains_key(&epoch)
+            || self
+                .has_evicted_entry(SNAPSHOT_ENTRY, &epoch.to_string())
+                .await?
+        {
             return Ok(ProcessingResult::skipped());
         }
 
@@ -176,7 +273,13 @@ impl StateBuilder {
             },
         );
 
-        info!("Applied snapshot submission for epoch {}", epoch);
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.snapshot_submission_applied",
+            epoch,
+            "Applied snapshot submission"
+        );
         Ok(ProcessingResult::success())
     }
 
@@ -201,7 +304,9 @@ impl StateBuilder {
         let key = format!("{epoch}:{verifier}");
 
         // Check if already exists (idempotency)
-        if self.state.verifications.contains_key(&key) {
+        if self.state.verifications.contains_key(&key)
+            || self.has_evicted_entry(VERIFICATION_ENTRY, &key).await?
+        {
             return Ok(ProcessingResult::skipped());
         }
 
@@ -212,112 +317,484 @@ impl StateBuilder {
                 verifier: verifier.clone(),
                 epoch,
                 verified_at: event.timestamp,
+                ledger: event.ledger_sequence,
             },
         );
 
-        info!(
-            "Applied snapshot verification for epoch {} by {}",
-            epoch, verifier
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.snapshot_verification_applied",
+            epoch,
+            verifier = %verifier,
+            "Applied snapshot verification"
         );
         Ok(ProcessingResult::success())
     }
 
     /// Persist current state to database
     pub async fn persist_state(&self) -> Result<()> {
-        info!("Persisting state at ledger {}", self.state.ledger);
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.state_persisting",
+            network = %self.network,
+            ledger = self.state.ledger,
+            "Persisting replay state"
+        );
 
-        let state_json = self.state.to_json()?;
-        let state_hash = self.state.compute_hash();
+        let mut tx = self.pool.begin().await?;
+        let (state_json, state_hash) = if self.external {
+            self.write_entries(&mut tx).await?;
+            let header = self.header();
+            let hash = self.external_hash(&mut tx, &header).await?;
+            (header.to_json()?, hash)
+        } else {
+            (self.state.to_json()?, self.state.compute_hash())
+        };
 
         sqlx::query(
             r"
-            INSERT INTO replay_state (ledger, state_json, state_hash, updated_at)
-            VALUES ($1, $2, $3, CURRENT_TIMESTAMP)
-            ON CONFLICT (ledger) DO UPDATE SET
+            INSERT INTO replay_state
+                (network, ledger, state_json, state_hash, external_entries, scope, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, CURRENT_TIMESTAMP)
+            ON CONFLICT (network, scope, ledger) DO UPDATE SET
                 state_json = EXCLUDED.state_json,
                 state_hash = EXCLUDED.state_hash,
+                external_entries = EXCLUDED.external_entries,
                 updated_at = CURRENT_TIMESTAMP
             ",
         )
+        .bind(self.network.to_string())
         .bind(self.state.ledger as i64)
         .bind(serde_json::to_string(&state_json)?)
         .bind(&state_hash)
-        .execute(&self.pool)
+        .bind(self.external)
+        .bind(&self.scope)
+        .execute(&mut *tx)
         .await
         .context("Failed to persist state")?;
+        tx.commit().await.context("Failed to persist state")?;
 
         Ok(())
     }
 
+    /// Move the snapshot and verification entries to `replay_state_entries`,
+    /// leaving only the ledger and metadata in memory; they are read back as
+    /// events refer to them. Returns how many entries were moved.
+    ///
+    /// The first eviction of a state rebuilt in memory replaces the entries an
+    /// earlier replay of the network and scope left there, along with the
+    /// persisted states made of them.
+    pub async fn evict(&mut self) -> Result<usize> {
+        let network = self.network.to_string();
+        let mut tx = self.pool.begin().await?;
+        if !self.external {
+            sqlx::query(
+                "DELETE FROM replay_state WHERE network = $1 AND scope = $2 AND external_entries",
+            )
+            .bind(&network)
+            .bind(&self.scope)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query("DELETE FROM replay_state_entries WHERE network = $1 AND scope = $2")
+                .bind(&network)
+                .bind(&self.scope)
+                .execute(&mut *tx)
+                .await?;
+        }
+        let evicted = self.write_entries(&mut tx).await?;
+        tx.commit().await.context("Failed to evict state entries")?;
+
+        self.state.snapshots = HashMap::new();
+        self.state.verifications = HashMap::new();
+        self.external = true;
+        log_event!(
+            info,
+            Subsystem::Replay,
+            "replay.state_entries_evicted",
+            network = %self.network,
+            evicted,
+            ledger = self.state.ledger,
+            "Moved state entries out of memory"
+        );
+        Ok(evicted)
+    }
+
+    /// Write the entries held in memory to `replay_state_entries`
+    async fn write_entries(&self, conn: &mut SqliteConnection) -> Result<usize> {
+        let network = self.network.to_string();
+        for snapshot in self.state.snapshots.values() {
+            write_entry(
+                conn,
+                (&network, &self.scope),
+                SNAPSHOT_ENTRY,
+                &snapshot.epoch.to_string(),
+                snapshot.ledger,
+                &serde_json::to_string(snapshot)?,
+            )
+            .await?;
+        }
+        for (key, verification) in &self.state.verifications {
+            write_entry(
+                conn,
+                (&network, &self.scope),
+                VERIFICATION_ENTRY,
+                key,
+                verification.ledger,
+                &serde_json::to_string(verification)?,
+            )
+            .await?;
+        }
+        Ok(self.state.snapshots.len() + self.state.verifications.len())
+    }
+
+    /// Whether an entry moved out of memory exists at the current ledger
+    async fn has_evicted_entry(&self, kind: &str, key: &str) -> Result<bool> {
+        if !self.external {
+            return Ok(false);
+        }
+        sqlx::query_scalar(
+            r"
+            SELECT EXISTS (
+                SELECT 1 FROM replay_state_entries
+                WHERE network = $1 AND kind = $2 AND entry_key = $3 AND ledger <= $4
+                    AND scope = $5
+            )
+            ",
+        )
+        .bind(self.network.to_string())
+        .bind(kind)
+        .bind(key)
+        .bind(i64::try_from(self.state.ledger).unwrap_or(i64::MAX))
+        .bind(&self.scope)
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to look up evicted state entry")
+    }
+
+    /// Hash of a state whose entries are in `replay_state_entries`: its header
+    /// followed by the entries applied up to its ledger, read a row at a time
+    async fn external_hash(
+        &self,
+        conn: &mut SqliteConnection,
+        header: &ApplicationState,
+    ) -> Result<String> {
+        let mut hasher = Sha256::new();
+        hasher.update(header.compute_hash());
+
+        let mut rows = sqlx::query_as::<_, (String, String, String)>(
+            r"
+            SELECT kind, entry_key, entry_json FROM replay_state_entries
+            WHERE network = $1 AND scope = $2 AND ledger <= $3
+            ORDER BY kind, entry_key
+            ",
+        )
+        .bind(self.network.to_string())
+        .bind(&self.scope)
+        .bind(i64::try_from(header.ledger).unwrap_or(i64::MAX))
+        .fetch(conn);
+        while let Some((kind, key, entry_json)) = rows.try_next().await? {
+            for part in [&kind, &key, &entry_json] {
+                hasher.update(part.as_bytes());
+                hasher.update([0]);
+            }
+        }
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    /// The state without its snapshot and verification entries
+    fn header(&self) -> ApplicationState {
+        ApplicationState {
+            metadata: self.state.metadata.clone(),
+            ..ApplicationState::at_ledger(self.state.ledger)
+        }
+    }
+
+    /// The state with the snapshots moved out of memory read back, for
+    /// comparisons that need all of them at once
+    async fn with_all_snapshots(&self) -> Result<Cow<'_, ApplicationState>> {
+        if !self.external {
+            return Ok(Cow::Borrowed(&self.state));
+        }
+        let rows: Vec<(String,)> = sqlx::query_as(
+            r"
+            SELECT entry_json FROM replay_state_entries
+            WHERE network = $1 AND kind = $2 AND ledger <= $3 AND scope = $4
+            ",
+        )
+        .bind(self.network.to_string())
+        .bind(SNAPSHOT_ENTRY)
+        .bind(i64::try_from(self.state.ledger).unwrap_or(i64::MAX))
+        .bind(&self.scope)
+        .fetch_all(&self.pool)
+        .await
+        .context("Failed to read back evicted snapshots")?;
+
+        let mut state = self.header();
+        for (entry_json,) in rows {
+            let snapshot: SnapshotState = serde_json::from_str(&entry_json)?;
+            state.snapshots.insert(snapshot.epoch, snapshot);
+        }
+        state.snapshots.extend(
+            self.state
+                .snapshots
+                .iter()
+                .map(|(epoch, snapshot)| (*epoch, snapshot.clone())),
+        );
+        Ok(Cow::Owned(state))
+    }
+
     /// Load state from database
     pub async fn load_state(&mut self, ledger: u64) -> Result<bool> {
-        debug!("Loading state at ledger {}", ledger);
+        log_event!(
+            debug,
+            Subsystem::Replay,
+            "replay.state_loading",
+            network = %self.network,
+            ledger,
+            "Loading state"
+        );
 
-        let row: Option<(String, String)> =
-            sqlx::query_as("SELECT state_json, state_hash FROM replay_state WHERE ledger = $1")
-                .bind(ledger as i64)
-                .fetch_optional(&self.pool)
-                .await?;
+        let row: Option<(String, String, bool)> = sqlx::query_as(
+            r"
+            SELECT state_json, state_hash, external_entries FROM replay_state
+            WHERE network = $1 AND ledger = $2 AND scope = $3
+            ",
+        )
+        .bind(self.network.to_string())
+        .bind(ledger as i64)
+        .bind(&self.scope)
+        .fetch_optional(&self.pool)
+        .await?;
 
-        if let Some((state_json, state_hash)) = row {
+        if let Some((state_json, state_hash, external)) = row {
             let state_value: serde_json::Value = serde_json::from_str(&state_json)?;
-            self.state = ApplicationState::from_json(&state_value)?;
+            let state = ApplicationState::from_json(&state_value)?;
 
-            // Verify hash
-            let computed_hash = self.state.compute_hash();
+            // Verify hash. The entries of an external state stay where they are.
+            let computed_hash = if external {
+                let mut conn = self.pool.acquire().await?;
+                self.external_hash(&mut conn, &state).await?
+            } else {
+                state.compute_hash()
+            };
             if computed_hash != state_hash {
                 return Err(anyhow::anyhow!(
                     "State hash mismatch: expected {state_hash}, got {computed_hash}"
                 ));
             }
-
-            info!("Loaded state at ledger {} (hash: {})", ledger, state_hash);
+            self.state = state;
+            self.external = external;
+
+            log_event!(
+                info,
+                Subsystem::Replay,
+                "replay.state_loaded",
+                network = %self.network,
+                ledger,
+                state_hash = %state_hash,
+                "Loaded state"
+            );
             Ok(true)
         } else {
-            debug!("No state found at ledger {}", ledger);
+            log_event!(
+                debug,
+                Subsystem::Replay,
+                "replay.state_missing",
+                network = %self.network,
+                ledger,
+                "No state found"
+            );
             Ok(false)
         }
     }
 
+    /// Load the latest persisted state at or below `ledger`. Returns the
+    /// ledger of the loaded state, or `None` if there is none.
+    pub async fn load_nearest(&mut self, ledger: u64) -> Result<Option<u64>> {
+        let nearest: Option<i64> = sqlx::query_scalar(
+            r"
+            SELECT ledger FROM replay_state
+            WHERE network = $1 AND scope = $2 AND ledger <= $3
+            ORDER BY ledger DESC
+            LIMIT 1
+            ",
+        )
+        .bind(self.network.to_string())
+        .bind(&self.scope)
+        .bind(i64::try_from(ledger).unwrap_or(i64::MAX))
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to find nearest state snapshot")?;
+
+        let Some(nearest) = nearest.and_then(|n| u64::try_from(n).ok()) else {
+            return Ok(None);
+        };
+        Ok(self.load_state(nearest).await?.then_some(nearest))
+    }
+
+    /// Delete all but the `keep` most recent persisted states of the network
+    /// and scope
+    pub async fn compact(&self, keep: usize) -> Result<u64> {
+        let result = sqlx::query(
+            r"
+            DELETE FROM replay_state
+            WHERE network = $1 AND scope = $2 AND ledger NOT IN (
+                SELECT ledger FROM replay_state
+                WHERE network = $1 AND scope = $2
+                ORDER BY ledger DESC
+                LIMIT $3
+            )
+            ",
+        )
+        .bind(self.network.to_string())
+        .bind(&self.scope)
+        .bind(i64::try_from(keep).unwrap_or(i64::MAX))
+        .execute(&self.pool)
+        .await
+        .context("Failed to compact state snapshots")?;
+
+        let deleted = result.rows_affected();
+        if deleted > 0 {
+            log_event!(
+                debug,
+                Subsystem::Replay,
+                "replay.state_compacted",
+                network = %self.network,
+                deleted,
+                kept = keep,
+                "Compacted state snapshots"
+            );
+        }
+        Ok(deleted)
+    }
+
     /// Compare current state with database state
     pub async fn verify_state(&self, ledger: u64) -> Result<bool> {
-        debug!("Verifying state at ledger {}", ledger);
+        log_event!(
+            debug,
+            Subsystem::Replay,
+            "replay.state_verifying",
+            network = %self.network,
+            ledger,
+            "Verifying state"
+        );
 
-        let row: Option<(String,)> =
-            sqlx::query_as("SELECT state_hash FROM replay_state WHERE ledger = $1")
-                .bind(ledger as i64)
-                .fetch_optional(&self.pool)
-                .await?;
+        let row: Option<(String,)> = sqlx::query_as(
+            "SELECT state_hash FROM replay_state WHERE network = $1 AND ledger = $2 AND scope = $3",
+        )
+        .bind(self.network.to_string())
+        .bind(ledger as i64)
+        .bind(&self.scope)
+        .fetch_optional(&self.pool)
+        .await?;
 
         if let Some((expected_hash,)) = row {
-            let actual_hash = self.state.compute_hash();
+            let actual_hash = if self.external {
+                // Hash the entries in memory along with the evicted ones, without
+                // keeping them there.
+                let mut tx = self.pool.begin().await?;
+                self.write_entries(&mut tx).await?;
+                self.external_hash(&mut tx, &self.header()).await?
+            } else {
+                self.state.compute_hash()
+            };
             let matches = actual_hash == expected_hash;
 
             if matches {
-                info!("State verification passed at ledger {}", ledger);
+                log_event!(
+                    info,
+                    Subsystem::Replay,
+                    "replay.state_verified",
+                    network = %self.network,
+                    ledger,
+                    "State verification passed"
+                );
             } else {
-                info!(
-                    "State verification failed at ledger {}: expected {}, got {}",
-                    ledger, expected_hash, actual_hash
+                log_event!(
+                    warn,
+                    Subsystem::Replay,
+                    "replay.state_verification_failed",
+                    network = %self.network,
+                    ledger,
+                    expected_hash = %expected_hash,
+                    actual_hash = %actual_hash,
+                    "State verification failed"
                 );
             }
 
             Ok(matches)
         } else {
-            debug!("No state to verify at ledger {}", ledger);
+            log_event!(
+                debug,
+                Subsystem::Replay,
+                "replay.state_unverifiable",
+                network = %self.network,
+                ledger,
+                "No state to verify"
+            );
             Ok(false)
         }
     }
 
+    /// Compare the current state with the application's snapshots
+    pub async fn diff_against_database(&self, from_genesis: bool) -> Result<ReplayReport> {
+        let state = self.with_all_snapshots().await?;
+        diff::diff_against_database(&state, &self.pool, from_genesis).await
+    }
+
+    /// Compare the snapshots applied from `from_ledger` on with the hashes
+    /// anchored on chain
+    pub async fn verify_against_chain(
+        &self,
+        anchors: &dyn AnchoredSnapshots,
+        from_ledger: u64,
+    ) -> Result<IntegrityReport> {
+        let state = self.with_all_snapshots().await?;
+        integrity::verify_against_chain(&state, &self.pool, anchors, from_ledger).await
+    }
+
     /// Reset state to empty
     pub fn reset(&mut self) {
         self.state = ApplicationState::new();
+        self.external = false;
     }
 }
 
+async fn write_entry(
+    conn: &mut SqliteConnection,
+    (network, scope): (&str, &str),
+    kind: &str,
+    key: &str,
+    ledger: u64,
+    entry_json: &str,
+) -> Result<()> {
+    sqlx::query(
+        r"
+        INSERT INTO replay_state_entries (network, scope, kind, entry_key, ledger, entry_json)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (network, scope, kind, entry_key) DO UPDATE SET
+            ledger = EXCLUDED.ledger,
+            entry_json = EXCLUDED.entry_json
+        ",
+    )
+    .bind(network)
+    .bind(scope)
+    .bind(kind)
+    .bind(key)
+    .bind(i64::try_from(ledger).unwrap_or(i64::MAX))
+    .bind(entry_json)
+    .execute(conn)
+    .await
+    .context("Failed to write state entry")?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{self, migrations};
 
     #[test]
     fn test_application_state() {
@@ -327,6 +804,94 @@ mod tests {
 
         let hash = state.compute_hash();
         assert!(!hash.is_empty());
+
+        let mut grown = state.clone();
+        grown.snapshots.insert(
+            1,
+            SnapshotState {
+                epoch: 1,
+                hash: "a".repeat(64),
+                ledger: 10,
+                transaction_hash: "b".repeat(64),
+            },
+        );
+        assert!(grown.estimated_size() > state.estimated_size() + 128);
+    }
+
+    #[tokio::test]
+    async fn test_compaction_and_nearest_snapshot() {
+        let pool = test_support::sqlite_pool(migrations::REPLAY).await;
+
+        for (network, ledger) in [
+            (StellarNetwork::Mainnet, 100),
+            (StellarNetwork::Mainnet, 200),
+            (StellarNetwork::Mainnet, 300),
+            (StellarNetwork::Mainnet, 400),
+            (StellarNetwork::Testnet, 150),
+            (StellarNetwork::Testnet, 350),
+        ] {
+            StateBuilder::with_state(pool.clone(), network, ApplicationState::at_ledger(ledger))
+                .persist_state()
+                .await
+                .unwrap();
+        }
+
+        let mut builder = StateBuilder::new(pool.clone(), StellarNetwork::Mainnet);
+        assert_eq!(builder.compact(2).await.unwrap(), 2);
+        assert_eq!(builder.load_nearest(399).await.unwrap(), Some(300));
+        assert_eq!(builder.state().ledger, 300);
+        assert_eq!(builder.load_nearest(1_000).await.unwrap(), Some(400));
+        // 100 and 200 were compacted away.
+        assert_eq!(builder.load_nearest(250).await.unwrap(), None);
+
+        // Testnet states were neither compacted nor visible to mainnet.
+        let mut testnet = StateBuilder::new(pool.clone(), StellarNetwork::Testnet);
+        assert_eq!(testnet.load_nearest(399).await.unwrap(), Some(350));
+        assert_eq!(testnet.load_nearest(250).await.unwrap(), Some(150));
+    }
+
+    #[tokio::test]
+    async fn test_states_are_scoped_by_filter() {
+        let pool = test_support::sqlite_pool(migrations::REPLAY).await;
+        let one_contract = EventFilter {
+            contract_ids: Some(vec!["CSNAPSHOT".to_string()]),
+            ..EventFilter::default()
+        };
+        let builder = |ledger| {
+            StateBuilder::with_state(
+                pool.clone(),
+                StellarNetwork::Mainnet,
+                ApplicationState::at_ledger(ledger),
+            )
+        };
+
+        builder(100).persist_state().await.unwrap();
+        builder(200).persist_state().await.unwrap();
+        for ledger in [150, 250] {
+            builder(ledger)
+                .for_filter(&one_contract)
+                .persist_state()
+                .await
+                .unwrap();
+        }
+
+        let mut all_events = StateBuilder::new(pool.clone(), StellarNetwork::Mainnet);
+        assert_eq!(all_events.compact(1).await.unwrap(), 1);
+        assert_eq!(all_events.load_nearest(300).await.unwrap(), Some(200));
+        assert_eq!(all_events.load_nearest(199).await.unwrap(), None);
+
+        // The filtered states were neither compacted nor visible without the filter,
+        // and the filter's order and network do not change its scope.
+        let reordered = EventFilter {
+            contract_ids: Some(vec!["CSNAPSHOT".to_string(), "CSNAPSHOT".to_string()]),
+            network: Some("mainnet".to_string()),
+            ..EventFilter::default()
+        };
+        let mut filtered =
+            StateBuilder::new(pool.clone(), StellarNetwork::Mainnet).for_filter(&reordered);
+        assert_eq!(filtered.load_nearest(300).await.unwrap(), Some(250));
+        assert_eq!(filtered.load_nearest(199).await.unwrap(), Some(150));
+        assert_ne!(filtered.scope(), all_events.scope());
     }
 
     #[test]
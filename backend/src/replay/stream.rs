@@ -0,0 +1,282 @@
+//! Event Streaming
+//!
+//! Fetches a replay's ledger range ahead of processing in a background task.
+//! Every fetched batch is charged against a byte budget until it has been
+//! processed, so the fetcher stalls once the buffered batches reach the budget
+//! and picks up again as processing frees it.
+
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
+
+use super::{source::EventSource, ContractEvent, EventFilter};
+use crate::log_event;
+use crate::logging::Subsystem;
+
+/// Batches fetched but not yet handed out, regardless of their size
+const PREFETCH_BATCHES: usize = 16;
+
+/// Budget accounting unit, so large budgets fit in a semaphore
+const PERMIT_BYTES: usize = 1024;
+
+/// Every event in a run of whole ledgers
+#[derive(Debug)]
+pub struct Batch {
+    /// First ledger covered
+    pub start: u64,
+    /// Last ledger covered; a ledger is never split across batches
+    pub end: u64,
+    pub events: Vec<ContractEvent>,
+    /// Budget the batch holds until it is dropped
+    _permit: OwnedSemaphorePermit,
+}
+
+/// Batches of a ledger range, in ledger order, fetched ahead within a byte budget
+pub struct EventStream {
+    batches: mpsc::Receiver<Result<Batch>>,
+    fetcher: JoinHandle<()>,
+}
+
+impl EventStream {
+    /// Start fetching `start..=end` from `source` in batches of up to
+    /// `batch_size` events, keeping at most `budget_bytes` of them in memory.
+    /// A batch larger than the whole budget is still fetched, on its own.
+    pub fn spawn(
+        source: Arc<dyn EventSource>,
+        filter: EventFilter,
+        (start, end): (u64, u64),
+        batch_size: usize,
+        budget_bytes: usize,
+    ) -> Self {
+        let (sender, batches) = mpsc::channel(PREFETCH_BATCHES);
+        let permits = budget_bytes
+            .div_ceil(PERMIT_BYTES)
+            .clamp(1, Semaphore::MAX_PERMITS);
+        let budget = Arc::new(Semaphore::new(permits));
+
+        let fetcher = tokio::spawn(async move {
+            let mut from = start;
+            while from <= end {
+                let to = from.saturating_add(batch_size as u64 - 1).min(end);
+                let fetched = fetch_batch(source.as_ref(), &filter, from, to, batch_size).await;
+                let (batch_end, events) = match fetched {
+                    Ok(fetched) => fetched,
+                    Err(e) => {
+                        let _ = sender.send(Err(e)).await;
+                        return;
+                    }
+                };
+
+                let bytes: usize = events.iter().map(estimated_size).sum();
+                let wanted = bytes.div_ceil(PERMIT_BYTES).clamp(1, permits);
+                // Only fails once the stream is dropped and the semaphore with it.
+                let Ok(permit) = Arc::clone(&budget)
+                    .acquire_many_owned(u32::try_from(wanted).unwrap_or(u32::MAX))
+                    .await
+                else {
+                    return;
+                };
+                log_event!(
+                    debug,
+                    Subsystem::Replay,
+                    "replay.batch_fetched",
+                    start_ledger = from,
+                    end_ledger = batch_end,
+                    events = events.len(),
+                    bytes,
+                    "Fetched ledger batch"
+                );
+
+                let batch = Batch {
+                    start: from,
+                    end: batch_end,
+                    events,
+                    _permit: permit,
+                };
+                if sender.send(Ok(batch)).await.is_err() {
+                    return;
+                }
+                from = batch_end + 1;
+            }
+        });
+
+        Self { batches, fetcher }
+    }
+
+    /// The next batch, or `None` once the range is exhausted
+    pub async fn next(&mut self) -> Option<Result<Batch>> {
+        self.batches.recv().await
+    }
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        self.fetcher.abort();
+    }
+}
+
+/// Fetch up to `limit` events from `from..=to`, ending on a whole ledger.
+/// Returns the last ledger covered along with the events.
+async fn fetch_batch(
+    source: &dyn EventSource,
+    filter: &EventFilter,
+    from: u64,
+    to: u64,
+    limit: usize,
+) -> Result<(u64, Vec<ContractEvent>)> {
+    // One event over the limit tells whether the range holds more.
+    let mut events = source
+        .get_events_in_range(from, to, filter, Some(limit.saturating_add(1)))
+        .await
+        .context("Failed to fetch events")?;
+    if events.len() <= limit {
+        return Ok((to, events));
+    }
+
+    // The last ledger may have been cut short. Leave it to the next batch,
+    // unless it is the only one, which is then fetched whole.
+    let last = events.last().map_or(from, |event| event.ledger_sequence);
+    if last > from {
+        events.retain(|event| event.ledger_sequence < last);
+        return Ok((last - 1, events));
+    }
+    let events = source
+        .get_events_in_range(from, from, filter, None)
+        .await
+        .context("Failed to fetch events")?;
+    Ok((from, events))
+}
+
+/// Approximate heap and inline size of an event
+fn estimated_size(event: &ContractEvent) -> usize {
+    size_of::<ContractEvent>()
+        + event.id.len()
+        + event.transaction_hash.len()
+        + event.contract_id.len()
+        + event.event_type.len()
+        + event.network.len()
+        + event.data.to_string().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Three events per ledger, counting fetches
+    struct Ledgers {
+        fetches: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EventSource for Ledgers {
+        async fn get_events_in_range(
+            &self,
+            start_ledger: u64,
+            end_ledger: u64,
+            _filter: &EventFilter,
+            limit: Option<usize>,
+        ) -> Result<Vec<ContractEvent>> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            Ok((start_ledger..=end_ledger)
+                .flat_map(|ledger| {
+                    (0..3).map(move |index| ContractEvent {
+                        id: format!("{ledger}-{index}"),
+                        ledger_sequence: ledger,
+                        transaction_hash: format!("tx-{ledger}"),
+                        tx_index: 0,
+                        event_index: index,
+                        contract_id: "CONTRACT".to_string(),
+                        event_type: "snapshot_submitted".to_string(),
+                        data: json!({ "epoch": ledger }),
+                        timestamp: DateTime::<Utc>::UNIX_EPOCH,
+                        network: "testnet".to_string(),
+                    })
+                })
+                .take(limit.unwrap_or(usize::MAX))
+                .collect())
+        }
+
+        async fn get_latest_ledger(&self) -> Result<Option<u64>> {
+            Ok(None)
+        }
+
+        async fn ledgers_between(
+            &self,
+            _start: DateTime<Utc>,
+            _end: DateTime<Utc>,
+        ) -> Result<Option<(u64, u64)>> {
+            Ok(None)
+        }
+
+        fn name(&self) -> &'static str {
+            "ledgers"
+        }
+    }
+
+    fn ledgers() -> Arc<Ledgers> {
+        Arc::new(Ledgers {
+            fetches: AtomicUsize::new(0),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_batches_keep_ledgers_whole() {
+        let mut stream =
+            EventStream::spawn(ledgers(), EventFilter::default(), (1, 4), 4, usize::MAX);
+
+        let mut ranges = Vec::new();
+        let mut ids = Vec::new();
+        while let Some(batch) = stream.next().await {
+            let batch = batch.unwrap();
+            ranges.push((batch.start, batch.end));
+            ids.extend(batch.events.into_iter().map(|event| event.id));
+        }
+
+        // Four events reach into ledger 2, so it moves to the next batch; a
+        // limit of two would have fetched ledger 1 on its own, whole.
+        assert_eq!(ranges, [(1, 1), (2, 2), (3, 3), (4, 4)]);
+        assert_eq!(ids.len(), 12);
+        assert_eq!(ids.first().map(String::as_str), Some("1-0"));
+        assert_eq!(ids.last().map(String::as_str), Some("4-2"));
+
+        let first_batch = |batch_size| async move {
+            let mut stream =
+                EventStream::spawn(ledgers(), EventFilter::default(), (1, 2), batch_size, 1);
+            let batch = stream.next().await.unwrap().unwrap();
+            (batch.start, batch.end, batch.events.len())
+        };
+        assert_eq!(first_batch(2).await, (1, 1, 3));
+        // A range holding exactly the limit is not cut.
+        assert_eq!(first_batch(6).await, (1, 2, 6));
+    }
+
+    #[tokio::test]
+    async fn test_fetching_waits_for_budget() {
+        let source = ledgers();
+        let mut stream = EventStream::spawn(
+            Arc::clone(&source) as Arc<dyn EventSource>,
+            EventFilter::default(),
+            (1, 100),
+            4,
+            1,
+        );
+
+        // A one-byte budget holds a single batch: the fetcher has the next one
+        // in hand but cannot queue it until the first is dropped.
+        let first = stream.next().await.unwrap().unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(source.fetches.load(Ordering::SeqCst), 2);
+
+        drop(first);
+        let second = stream.next().await.unwrap().unwrap();
+        assert_eq!((second.start, second.end), (2, 2));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(source.fetches.load(Ordering::SeqCst), 3);
+        drop(second);
+    }
+}
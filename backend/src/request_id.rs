@@ -6,8 +6,11 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use std::fmt;
+use tracing::Instrument;
 use uuid::Uuid;
 
+use crate::logging::{correlation_span, Subsystem};
+
 /// Request ID wrapper for storing in request extensions
 #[derive(Clone, Debug)]
 pub struct RequestId(pub String);
@@ -44,7 +47,7 @@ impl fmt::Display for RequestId {
 /// - Generates a unique request ID for each request
 /// - Adds it to request extensions for use in handlers
 /// - Includes it in response headers as X-Request-ID
-/// - Logs the request ID for tracing
+/// - Runs the rest of the stack inside a span carrying it as `correlation_id`
 pub async fn request_id_middleware(mut req: Request<Body>, next: Next) -> Response {
     // Check if request already has an X-Request-ID header (from upstream)
     let request_id = if let Some(existing_id) = req.headers().get("X-Request-ID") {
@@ -62,15 +65,20 @@ pub async fn request_id_middleware(mut req: Request<Body>, next: Next) -> Respon
     // Log the request with ID
     let method = req.method().clone();
     let uri = req.uri().clone();
-    tracing::info!(
-        request_id = %request_id,
-        method = %method,
-        uri = %uri,
-        "Incoming request"
-    );
+    let span = correlation_span(Subsystem::Api, &request_id);
+    span.in_scope(|| {
+        crate::log_event!(
+            info,
+            Subsystem::Api,
+            "http.request_received",
+            method = %method,
+            uri = %uri,
+            "Incoming request"
+        );
+    });
 
     // Process the request
-    let response = next.run(req).await;
+    let response = next.run(req).instrument(span).await;
 
     // Add request ID to response headers
     let (mut parts, body) = response.into_parts();
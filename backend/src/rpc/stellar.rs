@@ -119,6 +119,32 @@ pub struct HealthResponse {
     pub ledger_retention_window: u64,
 }
 
+/// Result of the JSON-RPC `getVersionInfo` method.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcVersionInfo {
+    pub version: String,
+    #[serde(rename = "commitHash", default)]
+    pub commit_hash: String,
+    #[serde(rename = "buildTimestamp", default)]
+    pub build_timestamp: String,
+    #[serde(rename = "captiveCoreVersion", default)]
+    pub captive_core_version: String,
+    #[serde(rename = "protocolVersion")]
+    pub protocol_version: u32,
+}
+
+/// Subset of the Horizon root resource describing the protocol the network runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HorizonRootInfo {
+    pub horizon_version: String,
+    pub core_version: String,
+    pub current_protocol_version: u32,
+    /// Highest protocol the connected stellar-core build can vote for. When this is
+    /// above `current_protocol_version` an upgrade may be imminent.
+    pub core_supported_protocol_version: u32,
+    pub network_passphrase: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JsonRpcResponse<T> {
     pub jsonrpc: String,
@@ -726,6 +752,88 @@ impl StellarRpcClient {
             .ok_or_else(|| RpcError::ParseError("No result in health response".to_string()))
     }
 
+    /// Fetch build and protocol version of the RPC endpoint
+    pub async fn fetch_rpc_version_info(&self) -> Result<RpcVersionInfo, RpcError> {
+        if self.mock_mode {
+            return Ok(Self::mock_rpc_version_info());
+        }
+
+        let result = self
+            .execute_with_retry(|| self.fetch_rpc_version_info_internal())
+            .await;
+
+        result.inspect_err(|e| {
+            metrics::record_rpc_error(e.error_type_label(), "stellar");
+        })
+    }
+
+    async fn fetch_rpc_version_info_internal(&self) -> Result<RpcVersionInfo, RpcError> {
+        let payload = json!({
+            "jsonrpc": "2.0",
+            "method": "getVersionInfo",
+            "id": 1
+        });
+
+        let response = self
+            .client
+            .post(&self.rpc_url)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| RpcError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(map_response_error(response).await);
+        }
+
+        let json_response: JsonRpcResponse<RpcVersionInfo> = response
+            .json()
+            .await
+            .map_err(|e| RpcError::ParseError(e.to_string()))?;
+
+        if let Some(error) = json_response.error {
+            return Err(RpcError::ServerError {
+                status: 500,
+                message: format!("RPC error: {} (code: {})", error.message, error.code),
+            });
+        }
+
+        json_response
+            .result
+            .ok_or_else(|| RpcError::ParseError("No result in version info response".to_string()))
+    }
+
+    /// Fetch protocol and software versions from the Horizon root resource
+    pub async fn fetch_horizon_root(&self) -> Result<HorizonRootInfo, RpcError> {
+        if self.mock_mode {
+            return Ok(Self::mock_horizon_root());
+        }
+
+        let result = self
+            .execute_with_retry(|| self.fetch_horizon_root_internal())
+            .await;
+
+        result.inspect_err(|e| {
+            metrics::record_rpc_error(e.error_type_label(), "stellar");
+        })
+    }
+
+    async fn fetch_horizon_root_internal(&self) -> Result<HorizonRootInfo, RpcError> {
+        let response = self
+            .client
+            .get(&self.horizon_url)
+            .send()
+            .await
+            .map_err(|e| RpcError::NetworkError(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(map_response_error(response).await);
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| RpcError::ParseError(e.to_string()))
+    }
+
     /// Fetch latest ledger information
     pub async fn fetch_latest_ledger(&self) -> Result<LedgerInfo, RpcError> {
         if self.mock_mode {
@@ -1585,6 +1693,26 @@ impl StellarRpcClient {
         }
     }
 
+    fn mock_rpc_version_info() -> RpcVersionInfo {
+        RpcVersionInfo {
+            version: "21.1.0".to_string(),
+            commit_hash: "mock".to_string(),
+            build_timestamp: "2026-01-22T10:30:00Z".to_string(),
+            captive_core_version: "stellar-core 21.0.0".to_string(),
+            protocol_version: 21,
+        }
+    }
+
+    fn mock_horizon_root() -> HorizonRootInfo {
+        HorizonRootInfo {
+            horizon_version: "2.30.0".to_string(),
+            core_version: "stellar-core 21.0.0".to_string(),
+            current_protocol_version: 21,
+            core_supported_protocol_version: 21,
+            network_passphrase: "Public Global Stellar Network ; September 2015".to_string(),
+        }
+    }
+
     fn mock_ledger_info() -> LedgerInfo {
         LedgerInfo {
             sequence: 51_583_040,
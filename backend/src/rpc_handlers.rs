@@ -1,12 +1,12 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
     response::IntoResponse,
     Json,
 };
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::error::{ApiError, ApiResult};
 use crate::rpc::{Asset, StellarRpcClient};
 
 #[derive(Debug, Deserialize)]
@@ -32,41 +32,23 @@ pub struct OrderBookQuery {
     pub limit: u32,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ErrorResponse {
-    pub error: String,
-}
-
 /// Health check for Stellar RPC
 #[tracing::instrument(skip(client))]
 pub async fn rpc_health_check(
     State(client): State<Arc<StellarRpcClient>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    match client.check_health().await {
-        Ok(health) => Ok(Json(health)),
-        Err(e) => Err((
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(ErrorResponse {
-                error: format!("RPC health check failed: {e}"),
-            }),
-        )),
-    }
+) -> ApiResult<impl IntoResponse> {
+    let health = client.check_health().await.map_err(|e| {
+        ApiError::service_unavailable("RPC_UNHEALTHY", format!("RPC health check failed: {e}"))
+    })?;
+    Ok(Json(health))
 }
 
 /// Get latest ledger information
 #[tracing::instrument(skip(client))]
 pub async fn get_latest_ledger(
     State(client): State<Arc<StellarRpcClient>>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    match client.fetch_latest_ledger().await {
-        Ok(ledger) => Ok(Json(ledger)),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to fetch ledger: {e}"),
-            }),
-        )),
-    }
+) -> ApiResult<impl IntoResponse> {
+    Ok(Json(client.fetch_latest_ledger().await?))
 }
 
 /// Get recent payments
@@ -74,17 +56,9 @@ pub async fn get_latest_ledger(
 pub async fn get_payments(
     State(client): State<Arc<StellarRpcClient>>,
     Query(params): Query<PaginationQuery>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+) -> ApiResult<impl IntoResponse> {
     let cursor = params.cursor.as_deref();
-    match client.fetch_payments(params.limit, cursor).await {
-        Ok(payments) => Ok(Json(payments)),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to fetch payments: {e}"),
-            }),
-        )),
-    }
+    Ok(Json(client.fetch_payments(params.limit, cursor).await?))
 }
 
 /// Get payments for a specific account
@@ -93,19 +67,12 @@ pub async fn get_account_payments(
     State(client): State<Arc<StellarRpcClient>>,
     Path(account_id): Path<String>,
     Query(params): Query<PaginationQuery>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
-    match client
-        .fetch_account_payments(&account_id, params.limit)
-        .await
-    {
-        Ok(payments) => Ok(Json(payments)),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to fetch account payments: {e}"),
-            }),
-        )),
-    }
+) -> ApiResult<impl IntoResponse> {
+    Ok(Json(
+        client
+            .fetch_account_payments(&account_id, params.limit)
+            .await?,
+    ))
 }
 
 /// Get recent trades
@@ -113,17 +80,9 @@ pub async fn get_account_payments(
 pub async fn get_trades(
     State(client): State<Arc<StellarRpcClient>>,
     Query(params): Query<PaginationQuery>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+) -> ApiResult<impl IntoResponse> {
     let cursor = params.cursor.as_deref();
-    match client.fetch_trades(params.limit, cursor).await {
-        Ok(trades) => Ok(Json(trades)),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to fetch trades: {e}"),
-            }),
-        )),
-    }
+    Ok(Json(client.fetch_trades(params.limit, cursor).await?))
 }
 
 /// Get order book for a trading pair
@@ -131,7 +90,7 @@ pub async fn get_trades(
 pub async fn get_order_book(
     State(client): State<Arc<StellarRpcClient>>,
     Query(params): Query<OrderBookQuery>,
-) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+) -> ApiResult<impl IntoResponse> {
     let selling_asset = Asset {
         asset_type: params.selling_asset_type,
         asset_code: params.selling_asset_code,
@@ -144,16 +103,9 @@ pub async fn get_order_book(
         asset_issuer: params.buying_asset_issuer,
     };
 
-    match client
-        .fetch_order_book(&selling_asset, &buying_asset, params.limit)
-        .await
-    {
-        Ok(order_book) => Ok(Json(order_book)),
-        Err(e) => Err((
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: format!("Failed to fetch order book: {e}"),
-            }),
-        )),
-    }
+    Ok(Json(
+        client
+            .fetch_order_book(&selling_asset, &buying_asset, params.limit)
+            .await?,
+    ))
 }
@@ -6,7 +6,10 @@ use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
 use crate::models::corridor::{CorridorMetrics, HourlyCorridorMetrics, VolumeTrend};
+use crate::projections::{ProjectionEvent, ProjectionPublisher};
 use crate::services::analytics::compute_metrics_from_payments;
 
 const MAX_RETRIES: i32 = 3;
@@ -32,12 +35,24 @@ impl Default for AggregationConfig {
 pub struct AggregationService {
     db: Arc<Database>,
     config: AggregationConfig,
+    projections: Option<ProjectionPublisher>,
 }
 
 impl AggregationService {
     #[must_use]
     pub const fn new(db: Arc<Database>, config: AggregationConfig) -> Self {
-        Self { db, config }
+        Self {
+            db,
+            config,
+            projections: None,
+        }
+    }
+
+    /// Refresh corridor read-model projections after each stored rollup
+    #[must_use]
+    pub fn with_projections(mut self, publisher: ProjectionPublisher) -> Self {
+        self.projections = Some(publisher);
+        self
     }
 
     /// Start the hourly aggregation job scheduler
@@ -103,6 +118,48 @@ impl AggregationService {
         }
     }
 
+    /// Rebuild the hourly metrics for the hour starting at `hour` from stored payments,
+    /// replacing whatever was there. Used after a backfill lands payments in an hour
+    /// that was already aggregated.
+    pub async fn recompute_hour(&self, hour: DateTime<Utc>) -> Result<usize> {
+        let start_time = self.truncate_to_hour(hour);
+        let end_time = start_time + Duration::hours(1);
+
+        self.db
+            .delete_hourly_metrics_by_timerange(start_time, end_time)
+            .await
+            .context("Failed to clear hourly metrics for recomputation")?;
+
+        let mut payments = self
+            .db
+            .fetch_payments_by_timerange(start_time, end_time, self.config.batch_size)
+            .await
+            .context("Failed to fetch payments for recomputation")?;
+        if payments.len() as i64 >= self.config.batch_size {
+            log_event!(
+                warn,
+                Subsystem::Jobs,
+                "aggregation.recompute_truncated",
+                hour = %start_time.to_rfc3339(),
+                batch_size = self.config.batch_size,
+                "Hour has more payments than one batch; recomputed metrics are truncated"
+            );
+        }
+        // The fetch is inclusive of `end_time`, which belongs to the next bucket
+        payments.retain(|payment| payment.timestamp < end_time);
+        if payments.is_empty() {
+            return Ok(0);
+        }
+
+        let mut corridor_metrics = compute_metrics_from_payments(&payments);
+        // Computed metrics are dated now; pin them to the hour being rebuilt
+        for metric in &mut corridor_metrics {
+            metric.date = start_time;
+        }
+        let hourly_metrics = self.group_by_hour_bucket(corridor_metrics, start_time);
+        self.store_hourly_metrics(hourly_metrics).await
+    }
+
     /// Execute the actual aggregation logic
     async fn execute_aggregation(&self, job_id: &str, now: DateTime<Utc>) -> Result<usize> {
         // Calculate time window for aggregation
@@ -262,12 +319,20 @@ impl AggregationService {
     /// Store hourly metrics in the database
     async fn store_hourly_metrics(&self, metrics: Vec<HourlyCorridorMetrics>) -> Result<usize> {
         let count = metrics.len();
+        let mut touched_corridors = std::collections::BTreeSet::new();
 
         for metric in metrics {
             self.db
                 .upsert_hourly_corridor_metric(&metric)
                 .await
                 .context("Failed to store hourly corridor metric")?;
+            touched_corridors.insert(metric.corridor_key);
+        }
+
+        if let Some(projections) = &self.projections {
+            for corridor_key in touched_corridors {
+                projections.publish(ProjectionEvent::CorridorChanged(corridor_key));
+            }
         }
 
         info!("Stored {} hourly corridor metrics", count);
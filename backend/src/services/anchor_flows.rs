@@ -0,0 +1,599 @@
+use crate::database::Database;
+use crate::query_cost::{QueryBudget, QueryCost};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::BuildHasher;
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+/// Default look-back window when the request does not specify a period.
+const DEFAULT_PERIOD_DAYS: i64 = 30;
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 500;
+/// Flows are summed from hourly rollups, one row per hour per issuer at most.
+const ROLLUP_GRANULARITY_SECS: i64 = 3_600;
+const DEFAULT_MAX_ROLLUP_ROWS: i64 = 200_000;
+const DEFAULT_MAX_RANGE_DAYS: i64 = 366;
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AnchorFlowQuery {
+    /// Start of the window (defaults to 30 days before `to`).
+    pub from: Option<DateTime<Utc>>,
+    /// End of the window, exclusive (defaults to now).
+    pub to: Option<DateTime<Utc>>,
+    /// Links per page, ordered by volume (default 50, max 500).
+    #[param(example = 50, minimum = 1, maximum = 500)]
+    pub limit: Option<i64>,
+    #[param(example = 0, minimum = 0)]
+    pub offset: Option<i64>,
+}
+
+/// Validated window and page for a flows request.
+#[derive(Debug, Clone, Copy)]
+pub struct FlowWindow {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl FlowWindow {
+    pub fn resolve(query: &AnchorFlowQuery, now: DateTime<Utc>) -> Result<Self> {
+        let to = query.to.unwrap_or(now);
+        let from = query
+            .from
+            .unwrap_or_else(|| to - Duration::days(DEFAULT_PERIOD_DAYS));
+        if from >= to {
+            return Err(anyhow!("from must be earlier than to"));
+        }
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err(anyhow!("limit must be between 1 and {MAX_LIMIT}"));
+        }
+        let offset = query.offset.unwrap_or(0);
+        if offset < 0 {
+            return Err(anyhow!("offset must not be negative"));
+        }
+        Ok(Self {
+            from,
+            to,
+            limit,
+            offset,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FlowDirection {
+    Inbound,
+    Outbound,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FlowNode {
+    pub id: String,
+    pub label: String,
+    /// Set when the node is a registered anchor.
+    pub anchor_id: Option<String>,
+}
+
+/// One sankey link. `asset_code`/`asset_issuer` identify the asset sent along the
+/// link: the anchor's own asset for outbound flows, the counterparty's for inbound.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FlowLink {
+    pub source: String,
+    pub target: String,
+    pub direction: FlowDirection,
+    pub counterparty_anchor_id: Option<String>,
+    pub asset_code: String,
+    pub asset_issuer: String,
+    pub volume_usd: f64,
+    pub transactions: i64,
+    pub successful_transactions: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AnchorFlows {
+    pub anchor_id: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Nodes referenced by `links`, anchor first.
+    pub nodes: Vec<FlowNode>,
+    pub links: Vec<FlowLink>,
+    pub total_links: usize,
+    pub limit: i64,
+    pub offset: i64,
+    pub inbound_volume_usd: f64,
+    pub outbound_volume_usd: f64,
+    /// Volume of links not on this page, so the long tail can be drawn as one node.
+    pub other_volume_usd: f64,
+}
+
+/// Hourly corridor rollups summed over the window for one asset pair.
+#[derive(Debug, Clone)]
+pub struct PairVolume {
+    pub asset_a_code: String,
+    pub asset_a_issuer: String,
+    pub asset_b_code: String,
+    pub asset_b_issuer: String,
+    pub transactions: i64,
+    pub successful_transactions: i64,
+    pub volume_usd: f64,
+}
+
+#[derive(Debug, Clone)]
+struct AnchorRef {
+    id: String,
+    name: String,
+}
+
+pub struct AnchorFlowService {
+    db: Arc<Database>,
+    budget: QueryBudget,
+}
+
+impl AnchorFlowService {
+    #[must_use]
+    pub fn new(db: Arc<Database>) -> Self {
+        let budget = QueryBudget::from_env(
+            "anchor-flows",
+            QueryBudget::new(DEFAULT_MAX_ROLLUP_ROWS, DEFAULT_MAX_RANGE_DAYS * 86_400),
+        );
+        Self { db, budget }
+    }
+
+    #[must_use]
+    pub const fn with_budget(mut self, budget: QueryBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Aggregate an anchor's inbound and outbound payment flows. Returns `None` when
+    /// the anchor does not exist.
+    pub async fn flows(
+        &self,
+        anchor_id: &str,
+        query: &AnchorFlowQuery,
+    ) -> Result<Option<AnchorFlows>> {
+        let window = FlowWindow::resolve(query, Utc::now())?;
+        let Some(anchor) = self.anchor(anchor_id).await? else {
+            return Ok(None);
+        };
+        let issuers = self.issuer_directory().await?;
+        let own: Vec<&str> = issuers
+            .iter()
+            .filter(|(_, owner)| owner.id == anchor.id)
+            .map(|(issuer, _)| issuer.as_str())
+            .collect();
+        self.budget.check(QueryCost::new(
+            (window.to - window.from).num_seconds(),
+            ROLLUP_GRANULARITY_SECS,
+            i64::try_from(own.len()).unwrap_or(i64::MAX),
+        ))?;
+
+        let pairs = self.pair_volumes(&own, window.from, window.to).await?;
+        Ok(Some(build_flows(
+            &anchor.id,
+            &anchor.name,
+            &pairs,
+            &issuers_by_ref(&issuers),
+            window,
+        )))
+    }
+
+    async fn anchor(&self, anchor_id: &str) -> Result<Option<AnchorRef>> {
+        let row = sqlx::query("SELECT id, name FROM anchors WHERE id = ?")
+            .bind(anchor_id)
+            .fetch_optional(self.db.pool())
+            .await
+            .context("Failed to load anchor")?;
+        Ok(row.map(|row| AnchorRef {
+            id: row.get("id"),
+            name: row.get("name"),
+        }))
+    }
+
+    /// Map every known issuing account (anchor accounts and registered asset issuers)
+    /// to the anchor that owns it.
+    async fn issuer_directory(&self) -> Result<HashMap<String, AnchorRef>> {
+        let rows = sqlx::query(
+            r"
+            SELECT a.id, a.name, a.stellar_account AS issuer FROM anchors a
+            UNION
+            SELECT a.id, a.name, s.asset_issuer AS issuer
+            FROM assets s JOIN anchors a ON a.id = s.anchor_id
+            ",
+        )
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to load anchor issuers")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.get("issuer"),
+                    AnchorRef {
+                        id: row.get("id"),
+                        name: row.get("name"),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    async fn pair_volumes(
+        &self,
+        issuers: &[&str],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<PairVolume>> {
+        if issuers.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; issuers.len()].join(", ");
+        let sql = format!(
+            r"
+            SELECT asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer,
+                   COALESCE(SUM(total_transactions), 0) AS transactions,
+                   COALESCE(SUM(successful_transactions), 0) AS successful_transactions,
+                   COALESCE(SUM(volume_usd), 0.0) AS volume_usd
+            FROM corridor_metrics_hourly
+            WHERE hour_bucket >= ? AND hour_bucket < ?
+              AND (asset_a_issuer IN ({placeholders}) OR asset_b_issuer IN ({placeholders}))
+            GROUP BY asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer
+            "
+        );
+
+        let mut query = sqlx::query(&sql)
+            .bind(from.to_rfc3339())
+            .bind(to.to_rfc3339());
+        for _ in 0..2 {
+            for issuer in issuers {
+                query = query.bind(*issuer);
+            }
+        }
+
+        let rows = query
+            .fetch_all(self.db.pool())
+            .await
+            .context("Failed to load corridor rollups for anchor flows")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| PairVolume {
+                asset_a_code: row.get("asset_a_code"),
+                asset_a_issuer: row.get("asset_a_issuer"),
+                asset_b_code: row.get("asset_b_code"),
+                asset_b_issuer: row.get("asset_b_issuer"),
+                transactions: row.get("transactions"),
+                successful_transactions: row.get("successful_transactions"),
+                volume_usd: row.get("volume_usd"),
+            })
+            .collect())
+    }
+}
+
+fn issuers_by_ref(issuers: &HashMap<String, AnchorRef>) -> HashMap<&str, (&str, &str)> {
+    issuers
+        .iter()
+        .map(|(issuer, owner)| (issuer.as_str(), (owner.id.as_str(), owner.name.as_str())))
+        .collect()
+}
+
+/// Turn pair rollups into sankey nodes and links for `anchor_id`.
+///
+/// Payments run from `asset_a` to `asset_b`, so a pair is outbound when the anchor
+/// issues `asset_a` and inbound when it issues `asset_b`. Counterparties are placed on
+/// separate inbound and outbound nodes to keep the graph acyclic, and pairs where the
+/// anchor issues both sides are left out for the same reason.
+#[must_use]
+pub fn build_flows<S: BuildHasher>(
+    anchor_id: &str,
+    anchor_name: &str,
+    pairs: &[PairVolume],
+    issuers: &HashMap<&str, (&str, &str), S>,
+    window: FlowWindow,
+) -> AnchorFlows {
+    let owner = |issuer: &str| issuers.get(issuer).copied();
+    let anchor_node = format!("anchor:{anchor_id}");
+
+    // (direction, counterparty node, asset code, asset issuer) -> link
+    let mut grouped: BTreeMap<(FlowDirection, String, String, String), FlowLink> = BTreeMap::new();
+    let mut labels: HashMap<String, FlowNode> = HashMap::new();
+
+    for pair in pairs {
+        let a_owner = owner(&pair.asset_a_issuer);
+        let b_owner = owner(&pair.asset_b_issuer);
+        let a_is_self = a_owner.is_some_and(|(id, _)| id == anchor_id);
+        let b_is_self = b_owner.is_some_and(|(id, _)| id == anchor_id);
+
+        let (direction, counterparty_issuer, counterparty) = match (a_is_self, b_is_self) {
+            (true, false) => (FlowDirection::Outbound, &pair.asset_b_issuer, b_owner),
+            (false, true) => (FlowDirection::Inbound, &pair.asset_a_issuer, a_owner),
+            _ => continue,
+        };
+
+        let node = counterparty_node(direction, counterparty_issuer, counterparty);
+        let (source, target) = match direction {
+            FlowDirection::Inbound => (node.id.clone(), anchor_node.clone()),
+            FlowDirection::Outbound => (anchor_node.clone(), node.id.clone()),
+        };
+        let link = grouped
+            .entry((
+                direction,
+                node.id.clone(),
+                pair.asset_a_code.clone(),
+                pair.asset_a_issuer.clone(),
+            ))
+            .or_insert_with(|| FlowLink {
+                source,
+                target,
+                direction,
+                counterparty_anchor_id: counterparty.map(|(id, _)| id.to_string()),
+                asset_code: pair.asset_a_code.clone(),
+                asset_issuer: pair.asset_a_issuer.clone(),
+                volume_usd: 0.0,
+                transactions: 0,
+                successful_transactions: 0,
+            });
+        link.volume_usd += pair.volume_usd;
+        link.transactions += pair.transactions;
+        link.successful_transactions += pair.successful_transactions;
+        labels.entry(node.id.clone()).or_insert(node);
+    }
+
+    let mut links: Vec<FlowLink> = grouped.into_values().collect();
+    // Stable ordering: BTreeMap order breaks volume ties.
+    links.sort_by(|a, b| b.volume_usd.total_cmp(&a.volume_usd));
+
+    let volume = |direction| {
+        links
+            .iter()
+            .filter(|l| l.direction == direction)
+            .map(|l| l.volume_usd)
+            .sum::<f64>()
+    };
+    let inbound_volume_usd = volume(FlowDirection::Inbound);
+    let outbound_volume_usd = volume(FlowDirection::Outbound);
+    let total_links = links.len();
+
+    let page: Vec<FlowLink> = links
+        .into_iter()
+        .skip(usize::try_from(window.offset).unwrap_or(usize::MAX))
+        .take(usize::try_from(window.limit).unwrap_or(0))
+        .collect();
+    let page_volume: f64 = page.iter().map(|l| l.volume_usd).sum();
+
+    let mut nodes = vec![FlowNode {
+        id: anchor_node,
+        label: anchor_name.to_string(),
+        anchor_id: Some(anchor_id.to_string()),
+    }];
+    for link in &page {
+        let counterparty = match link.direction {
+            FlowDirection::Inbound => &link.source,
+            FlowDirection::Outbound => &link.target,
+        };
+        if let Some(node) = labels.remove(counterparty) {
+            nodes.push(node);
+        }
+    }
+
+    AnchorFlows {
+        anchor_id: anchor_id.to_string(),
+        from: window.from,
+        to: window.to,
+        nodes,
+        links: page,
+        total_links,
+        limit: window.limit,
+        offset: window.offset,
+        inbound_volume_usd,
+        outbound_volume_usd,
+        other_volume_usd: (inbound_volume_usd + outbound_volume_usd - page_volume).max(0.0),
+    }
+}
+
+/// Node for a counterparty: the owning anchor when the issuer is registered, otherwise
+/// the bare issuer account (or XLM for the native asset).
+fn counterparty_node(
+    direction: FlowDirection,
+    issuer: &str,
+    owner: Option<(&str, &str)>,
+) -> FlowNode {
+    let (key, label) = match owner {
+        Some((id, name)) => (format!("anchor:{id}"), name.to_string()),
+        None if issuer.is_empty() || issuer.eq_ignore_ascii_case("native") => {
+            ("native".to_string(), "XLM".to_string())
+        }
+        None => (format!("issuer:{issuer}"), issuer.to_string()),
+    };
+    let side = match direction {
+        FlowDirection::Inbound => "in",
+        FlowDirection::Outbound => "out",
+    };
+    FlowNode {
+        id: format!("{side}:{key}"),
+        label,
+        anchor_id: owner.map(|(id, _)| id.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations, CorridorHour};
+
+    fn pair(a: (&str, &str), b: (&str, &str), volume_usd: f64) -> PairVolume {
+        PairVolume {
+            asset_a_code: a.0.to_string(),
+            asset_a_issuer: a.1.to_string(),
+            asset_b_code: b.0.to_string(),
+            asset_b_issuer: b.1.to_string(),
+            transactions: 10,
+            successful_transactions: 9,
+            volume_usd,
+        }
+    }
+
+    fn page(limit: i64, offset: i64) -> FlowWindow {
+        let to = Utc::now();
+        FlowWindow {
+            from: to - Duration::days(1),
+            to,
+            limit,
+            offset,
+        }
+    }
+
+    fn directory() -> HashMap<&'static str, (&'static str, &'static str)> {
+        HashMap::from([
+            ("GSELF", ("a1", "Self Anchor")),
+            ("GSELF2", ("a1", "Self Anchor")),
+            ("GOTHER", ("a2", "Other Anchor")),
+        ])
+    }
+
+    #[test]
+    fn test_pairs_are_split_by_direction() {
+        let pairs = vec![
+            pair(("USDC", "GSELF"), ("EURC", "GOTHER"), 100.0),
+            pair(("EURC", "GOTHER"), ("USDC", "GSELF"), 40.0),
+            pair(("XLM", "native"), ("USDC", "GSELF"), 5.0),
+        ];
+        let flows = build_flows("a1", "Self Anchor", &pairs, &directory(), page(50, 0));
+
+        assert_eq!(flows.total_links, 3);
+        assert!((flows.outbound_volume_usd - 100.0).abs() < f64::EPSILON);
+        assert!((flows.inbound_volume_usd - 45.0).abs() < f64::EPSILON);
+
+        let outbound = &flows.links[0];
+        assert_eq!(outbound.direction, FlowDirection::Outbound);
+        assert_eq!(outbound.source, "anchor:a1");
+        assert_eq!(outbound.target, "out:anchor:a2");
+        assert_eq!(outbound.asset_code, "USDC");
+
+        let inbound = &flows.links[1];
+        assert_eq!(inbound.source, "in:anchor:a2");
+        assert_eq!(inbound.target, "anchor:a1");
+        assert_eq!(flows.links[2].source, "in:native");
+
+        // Anchor plus one node per counterparty side.
+        assert_eq!(flows.nodes.len(), 4);
+        assert_eq!(flows.nodes[0].id, "anchor:a1");
+    }
+
+    #[test]
+    fn test_same_counterparty_and_asset_are_merged() {
+        let pairs = vec![
+            pair(("USDC", "GSELF"), ("EURC", "GOTHER"), 100.0),
+            pair(("USDC", "GSELF"), ("BRL", "GOTHER"), 50.0),
+            // Internal conversion between the anchor's own assets is not a flow.
+            pair(("USDC", "GSELF"), ("EURT", "GSELF2"), 999.0),
+        ];
+        let flows = build_flows("a1", "Self Anchor", &pairs, &directory(), page(50, 0));
+
+        assert_eq!(flows.total_links, 1);
+        assert!((flows.links[0].volume_usd - 150.0).abs() < f64::EPSILON);
+        assert_eq!(flows.links[0].transactions, 20);
+    }
+
+    #[tokio::test]
+    async fn test_flows_from_hourly_rollups() {
+        let pool =
+            test_support::sqlite_pool(&[migrations::ANCHORS, migrations::CORRIDOR_AGGREGATES])
+                .await;
+        sqlx::raw_sql(
+            r"
+            INSERT INTO anchors (id, name, stellar_account) VALUES
+                ('a1', 'Self Anchor', 'GSELF'), ('a2', 'Other Anchor', 'GOTHER');
+            INSERT INTO assets (id, anchor_id, asset_code, asset_issuer) VALUES
+                ('s1', 'a1', 'USDC', 'GSELFISSUER');
+            ",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let outbound = ["USDC", "GSELFISSUER", "EURC", "GOTHER"];
+        let inbound = ["EURC", "GOTHER", "USDC", "GSELFISSUER"];
+        for (corridor_key, assets, hour, total, successful, volume_usd) in [
+            ("k1", outbound, "2026-01-01T10:00:00Z", 5, 5, 100.0),
+            ("k1", outbound, "2026-01-01T11:00:00Z", 5, 4, 50.0),
+            ("k2", inbound, "2025-06-01T10:00:00Z", 1, 1, 7.0),
+        ] {
+            test_support::insert_hour(
+                &pool,
+                &CorridorHour {
+                    corridor_key,
+                    assets,
+                    hour: hour.parse().unwrap(),
+                    total,
+                    successful,
+                    volume_usd,
+                    ..CorridorHour::default()
+                },
+            )
+            .await;
+        }
+
+        let service = AnchorFlowService::new(Arc::new(Database::new(pool)));
+        let query = AnchorFlowQuery {
+            from: Some("2026-01-01T00:00:00Z".parse().unwrap()),
+            to: Some("2026-01-02T00:00:00Z".parse().unwrap()),
+            ..AnchorFlowQuery::default()
+        };
+
+        let flows = service.flows("a1", &query).await.unwrap().unwrap();
+        assert_eq!(flows.total_links, 1);
+        assert_eq!(flows.links[0].target, "out:anchor:a2");
+        assert_eq!(flows.links[0].transactions, 10);
+        assert!((flows.outbound_volume_usd - 150.0).abs() < f64::EPSILON);
+        assert!(flows.inbound_volume_usd.abs() < f64::EPSILON);
+
+        assert!(service.flows("missing", &query).await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_window_validation() {
+        let now = Utc::now();
+        let window = FlowWindow::resolve(&AnchorFlowQuery::default(), now).unwrap();
+        assert_eq!(window.to, now);
+        assert_eq!(window.from, now - Duration::days(DEFAULT_PERIOD_DAYS));
+        assert_eq!(window.limit, DEFAULT_LIMIT);
+
+        let inverted = AnchorFlowQuery {
+            from: Some(now),
+            to: Some(now - Duration::hours(1)),
+            ..AnchorFlowQuery::default()
+        };
+        assert!(FlowWindow::resolve(&inverted, now).is_err());
+        let too_big = AnchorFlowQuery {
+            limit: Some(MAX_LIMIT + 1),
+            ..AnchorFlowQuery::default()
+        };
+        assert!(FlowWindow::resolve(&too_big, now).is_err());
+    }
+
+    #[test]
+    fn test_pagination_reports_tail_volume() {
+        let pairs = vec![
+            pair(("USDC", "GSELF"), ("EURC", "GOTHER"), 100.0),
+            pair(("USDC", "GSELF"), ("NGN", "GUNKNOWN"), 30.0),
+            pair(("EURC", "GOTHER"), ("USDC", "GSELF"), 10.0),
+        ];
+        let flows = build_flows("a1", "Self Anchor", &pairs, &directory(), page(1, 1));
+
+        assert_eq!(flows.total_links, 3);
+        assert_eq!(flows.links.len(), 1);
+        assert_eq!(flows.links[0].target, "out:issuer:GUNKNOWN");
+        assert!(flows.links[0].counterparty_anchor_id.is_none());
+        assert!((flows.other_volume_usd - 110.0).abs() < 1e-9);
+        assert_eq!(flows.nodes.len(), 2);
+    }
+}
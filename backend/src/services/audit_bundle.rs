@@ -0,0 +1,621 @@
+//! Auditor bundles for anchored snapshots.
+//!
+//! A bundle packages everything needed to check one epoch without trusting this
+//! service: the canonical snapshot exactly as hashed, a Merkle tree over its records
+//! with a proof per record, the on-chain transactions that anchored the hash, the
+//! manifest of contract events recorded for the epoch, checksums and a standalone
+//! verification script. Bundles are plain tar archives and are deterministic for a
+//! given database state, so two downloads of the same epoch are byte-identical.
+
+use anyhow::{bail, Context, Result};
+use chrono::DateTime;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use crate::database::Database;
+
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+const VERIFY_SCRIPT: &str = include_str!("../../scripts/verify_audit_bundle.py");
+/// Snapshot sections whose records become Merkle leaves, in leaf order.
+const MERKLE_SECTIONS: [&str; 3] = ["anchor_metrics", "corridor_metrics", "region_pair_metrics"];
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+const TAR_BLOCK: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofPosition {
+    Left,
+    Right,
+}
+
+/// One step of a Merkle proof: the sibling hash and which side it sits on.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProofStep {
+    pub hash: String,
+    pub position: ProofPosition,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MerkleLeaf {
+    /// `<section>/<index>` within `snapshot.json`.
+    pub path: String,
+    /// The record's compact JSON encoding; this exact string is what gets hashed.
+    pub record: String,
+    pub leaf_hash: String,
+    pub proof: Vec<ProofStep>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MerkleTree {
+    pub algorithm: &'static str,
+    pub root: String,
+    pub leaf_count: usize,
+    pub leaves: Vec<MerkleLeaf>,
+}
+
+/// An on-chain transaction that anchored the snapshot hash.
+#[derive(Debug, Clone, Serialize)]
+pub struct OnChainAnchoring {
+    pub contract_id: String,
+    pub transaction_hash: String,
+    pub ledger: i64,
+    pub hash: Option<String>,
+    /// `contract_event` for the original submission, `reanchor` for copies made on a
+    /// replacement contract.
+    pub source: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AnchoringRecord {
+    epoch: u64,
+    snapshot_hash: String,
+    anchorings: Vec<OnChainAnchoring>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestEvent {
+    pub id: String,
+    pub contract_id: String,
+    pub event_type: String,
+    pub ledger: i64,
+    pub transaction_hash: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct LedgerRange {
+    pub first: i64,
+    pub last: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct EventRangeManifest {
+    format_version: u32,
+    epoch: u64,
+    snapshot_id: String,
+    snapshot_timestamp: String,
+    previous_epoch: Option<i64>,
+    previous_snapshot_timestamp: Option<String>,
+    anchor_records: usize,
+    corridor_records: usize,
+    region_pair_records: usize,
+    ledger_range: Option<LedgerRange>,
+    events: Vec<ManifestEvent>,
+}
+
+/// A generated bundle, ready to be served as a download.
+#[derive(Debug, Clone)]
+pub struct AuditBundle {
+    pub epoch: u64,
+    pub snapshot_hash: String,
+    pub archive: Vec<u8>,
+}
+
+impl AuditBundle {
+    #[must_use]
+    pub fn file_name(&self) -> String {
+        format!("audit-bundle-epoch-{}.tar", self.epoch)
+    }
+}
+
+struct StoredSnapshot {
+    id: String,
+    canonical_json: String,
+    hash: String,
+}
+
+pub struct AuditBundleService {
+    db: Arc<Database>,
+}
+
+impl AuditBundleService {
+    #[must_use]
+    pub const fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Build the bundle for `epoch`, or `None` when no snapshot was stored for it.
+    pub async fn build(&self, epoch: u64) -> Result<Option<AuditBundle>> {
+        let epoch_param = i64::try_from(epoch).context("Epoch out of range")?;
+        let Some(snapshot) = self.load_snapshot(epoch_param).await? else {
+            return Ok(None);
+        };
+
+        let parsed: serde_json::Value = serde_json::from_str(&snapshot.canonical_json)
+            .context("Stored snapshot is not valid JSON")?;
+        let records = snapshot_records(&parsed);
+        let section_records = |section: &str| {
+            records
+                .iter()
+                .filter(|(path, _)| path.split('/').next() == Some(section))
+                .count()
+        };
+        let anchor_records = section_records("anchor_metrics");
+        let corridor_records = section_records("corridor_metrics");
+        let region_pair_records = section_records("region_pair_metrics");
+        let merkle = build_merkle_tree(records);
+
+        let anchoring = AnchoringRecord {
+            epoch,
+            snapshot_hash: snapshot.hash.clone(),
+            anchorings: self.load_anchorings(epoch_param).await?,
+        };
+
+        let snapshot_timestamp = parsed["timestamp"].as_str().unwrap_or_default().to_string();
+        let (previous_epoch, previous_snapshot_timestamp) =
+            self.load_previous_snapshot(epoch_param).await?;
+        let events = self.load_events(epoch_param).await?;
+        let ledger_range =
+            events
+                .iter()
+                .map(|e| e.ledger)
+                .fold(None, |range: Option<LedgerRange>, ledger| {
+                    Some(range.map_or(
+                        LedgerRange {
+                            first: ledger,
+                            last: ledger,
+                        },
+                        |r| LedgerRange {
+                            first: r.first.min(ledger),
+                            last: r.last.max(ledger),
+                        },
+                    ))
+                });
+        let manifest = EventRangeManifest {
+            format_version: BUNDLE_FORMAT_VERSION,
+            epoch,
+            snapshot_id: snapshot.id,
+            snapshot_timestamp: snapshot_timestamp.clone(),
+            previous_epoch,
+            previous_snapshot_timestamp,
+            anchor_records,
+            corridor_records,
+            region_pair_records,
+            ledger_range,
+            events,
+        };
+
+        // The snapshot time doubles as the archive mtime so the bundle is reproducible.
+        let mtime = DateTime::parse_from_rfc3339(&snapshot_timestamp)
+            .ok()
+            .and_then(|t| u64::try_from(t.timestamp()).ok())
+            .unwrap_or_default();
+
+        let files: Vec<(&str, Vec<u8>, u32)> = vec![
+            ("snapshot.json", snapshot.canonical_json.into_bytes(), 0o644),
+            ("merkle.json", serde_json::to_vec_pretty(&merkle)?, 0o644),
+            (
+                "anchoring.json",
+                serde_json::to_vec_pretty(&anchoring)?,
+                0o644,
+            ),
+            (
+                "manifest.json",
+                serde_json::to_vec_pretty(&manifest)?,
+                0o644,
+            ),
+            ("verify.py", VERIFY_SCRIPT.as_bytes().to_vec(), 0o755),
+        ];
+        let mut checksums = String::new();
+        for (name, data, _) in &files {
+            let _ = writeln!(checksums, "{}  {name}", hex::encode(Sha256::digest(data)));
+        }
+
+        let dir = format!("epoch-{epoch}");
+        let mut archive = Vec::new();
+        for (name, data, mode) in &files {
+            tar_append(&mut archive, &format!("{dir}/{name}"), data, *mode, mtime)?;
+        }
+        tar_append(
+            &mut archive,
+            &format!("{dir}/SHA256SUMS"),
+            checksums.as_bytes(),
+            0o644,
+            mtime,
+        )?;
+        archive.resize(archive.len() + 2 * TAR_BLOCK, 0);
+
+        Ok(Some(AuditBundle {
+            epoch,
+            snapshot_hash: snapshot.hash,
+            archive,
+        }))
+    }
+
+    async fn load_snapshot(&self, epoch: i64) -> Result<Option<StoredSnapshot>> {
+        let row = sqlx::query(
+            r"
+            SELECT id, data, hash FROM snapshots
+            WHERE epoch = ? AND entity_type = 'analytics_snapshot' AND hash IS NOT NULL
+            ORDER BY created_at DESC
+            LIMIT 1
+            ",
+        )
+        .bind(epoch)
+        .fetch_optional(self.db.pool())
+        .await
+        .context("Failed to load snapshot")?;
+
+        Ok(row.map(|row| StoredSnapshot {
+            id: row.get("id"),
+            canonical_json: row.get("data"),
+            hash: row.get("hash"),
+        }))
+    }
+
+    async fn load_anchorings(&self, epoch: i64) -> Result<Vec<OnChainAnchoring>> {
+        let submitted = sqlx::query(
+            r"
+            SELECT contract_id, transaction_hash, ledger, hash FROM contract_events
+            WHERE epoch = ? AND event_type = 'SNAP_SUB'
+            ORDER BY ledger, transaction_hash
+            ",
+        )
+        .bind(epoch)
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to load snapshot submission events")?;
+
+        let reanchored = sqlx::query(
+            r"
+            SELECT target_contract_id, target_transaction_hash, target_ledger, hash
+            FROM snapshot_reanchors
+            WHERE epoch = ? AND target_transaction_hash IS NOT NULL
+            ORDER BY target_ledger, target_transaction_hash
+            ",
+        )
+        .bind(epoch)
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to load snapshot re-anchorings")?;
+
+        let mut anchorings: Vec<OnChainAnchoring> = submitted
+            .into_iter()
+            .map(|row| OnChainAnchoring {
+                contract_id: row.get("contract_id"),
+                transaction_hash: row.get("transaction_hash"),
+                ledger: row.get("ledger"),
+                hash: row.get("hash"),
+                source: "contract_event",
+            })
+            .collect();
+        anchorings.extend(reanchored.into_iter().map(|row| {
+            OnChainAnchoring {
+                contract_id: row.get("target_contract_id"),
+                transaction_hash: row.get("target_transaction_hash"),
+                ledger: row
+                    .get::<Option<i64>, _>("target_ledger")
+                    .unwrap_or_default(),
+                hash: row.get("hash"),
+                source: "reanchor",
+            }
+        }));
+        Ok(anchorings)
+    }
+
+    async fn load_previous_snapshot(&self, epoch: i64) -> Result<(Option<i64>, Option<String>)> {
+        let row = sqlx::query(
+            r"
+            SELECT epoch, timestamp FROM snapshots
+            WHERE epoch < ? AND entity_type = 'analytics_snapshot'
+            ORDER BY epoch DESC, created_at DESC
+            LIMIT 1
+            ",
+        )
+        .bind(epoch)
+        .fetch_optional(self.db.pool())
+        .await
+        .context("Failed to load previous snapshot")?;
+
+        Ok(row.map_or((None, None), |row| {
+            (Some(row.get("epoch")), Some(row.get("timestamp")))
+        }))
+    }
+
+    async fn load_events(&self, epoch: i64) -> Result<Vec<ManifestEvent>> {
+        let rows = sqlx::query(
+            r"
+            SELECT id, contract_id, event_type, ledger, transaction_hash FROM contract_events
+            WHERE epoch = ?
+            ORDER BY ledger, id
+            ",
+        )
+        .bind(epoch)
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to load contract events for epoch")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ManifestEvent {
+                id: row.get("id"),
+                contract_id: row.get("contract_id"),
+                event_type: row.get("event_type"),
+                ledger: row.get("ledger"),
+                transaction_hash: row.get("transaction_hash"),
+            })
+            .collect())
+    }
+}
+
+/// `(path, compact JSON)` for every record of the Merkle sections, in snapshot order.
+fn snapshot_records(snapshot: &serde_json::Value) -> Vec<(String, String)> {
+    MERKLE_SECTIONS
+        .iter()
+        .flat_map(|section| {
+            snapshot[*section]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .enumerate()
+                .map(move |(i, record)| (format!("{section}/{i}"), record.to_string()))
+        })
+        .collect()
+}
+
+fn leaf_hash(record: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(record.as_bytes());
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Binary SHA-256 tree with domain-separated leaves and nodes. An unpaired node is
+/// carried up to the next level unchanged, so it gets no proof step at that level.
+fn build_merkle_tree(records: Vec<(String, String)>) -> MerkleTree {
+    let mut levels: Vec<Vec<[u8; 32]>> = vec![records
+        .iter()
+        .map(|(_, record)| leaf_hash(record))
+        .collect()];
+    while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+        let next = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => node_hash(left, right),
+                _ => pair[0],
+            })
+            .collect();
+        levels.push(next);
+    }
+
+    let root = levels
+        .last()
+        .and_then(|level| level.first().copied())
+        .unwrap_or_else(|| Sha256::digest([]).into());
+
+    let leaves = records
+        .into_iter()
+        .enumerate()
+        .map(|(index, (path, record))| {
+            let mut proof = Vec::new();
+            let mut position = index;
+            for level in &levels[..levels.len() - 1] {
+                let sibling = position ^ 1;
+                if let Some(hash) = level.get(sibling) {
+                    proof.push(ProofStep {
+                        hash: hex::encode(hash),
+                        position: if sibling < position {
+                            ProofPosition::Left
+                        } else {
+                            ProofPosition::Right
+                        },
+                    });
+                }
+                position /= 2;
+            }
+            MerkleLeaf {
+                path,
+                leaf_hash: hex::encode(levels[0][index]),
+                record,
+                proof,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    MerkleTree {
+        algorithm: "sha256; leaf = H(0x00 || record), node = H(0x01 || left || right); \
+                    unpaired nodes are promoted",
+        root: hex::encode(root),
+        leaf_count: leaves.len(),
+        leaves,
+    }
+}
+
+/// Append one regular file to a ustar archive.
+fn tar_append(out: &mut Vec<u8>, path: &str, data: &[u8], mode: u32, mtime: u64) -> Result<()> {
+    fn field(header: &mut [u8], offset: usize, len: usize, value: &str) {
+        let bytes = value.as_bytes();
+        header[offset..offset + bytes.len().min(len)]
+            .copy_from_slice(&bytes[..bytes.len().min(len)]);
+    }
+
+    if path.len() > 100 {
+        bail!("Archive path too long: {path}");
+    }
+
+    let mut header = [0u8; TAR_BLOCK];
+    field(&mut header, 0, 100, path);
+    field(&mut header, 100, 8, &format!("{mode:07o}"));
+    field(&mut header, 108, 8, "0000000");
+    field(&mut header, 116, 8, "0000000");
+    field(&mut header, 124, 12, &format!("{:011o}", data.len()));
+    field(&mut header, 136, 12, &format!("{mtime:011o}"));
+    header[148..156].fill(b' ');
+    header[156] = b'0';
+    field(&mut header, 257, 6, "ustar");
+    field(&mut header, 263, 2, "00");
+    let checksum: u32 = header.iter().map(|b| u32::from(*b)).sum();
+    field(&mut header, 148, 8, &format!("{checksum:06o}\0 "));
+
+    out.extend_from_slice(&header);
+    out.extend_from_slice(data);
+    let padding = (TAR_BLOCK - data.len() % TAR_BLOCK) % TAR_BLOCK;
+    out.resize(out.len() + padding, 0);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations};
+
+    fn verify_proof(leaf: &MerkleLeaf, root: &str) -> bool {
+        let mut current = leaf_hash(&leaf.record);
+        for step in &leaf.proof {
+            let sibling: [u8; 32] = hex::decode(&step.hash).unwrap().try_into().unwrap();
+            current = match step.position {
+                ProofPosition::Left => node_hash(&sibling, &current),
+                ProofPosition::Right => node_hash(&current, &sibling),
+            };
+        }
+        hex::encode(current) == root
+    }
+
+    /// Minimal ustar reader: `(path, contents)` per entry.
+    fn untar(archive: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let mut entries = Vec::new();
+        let mut offset = 0;
+        while offset + TAR_BLOCK <= archive.len() && archive[offset] != 0 {
+            let header = &archive[offset..offset + TAR_BLOCK];
+            let name = String::from_utf8(
+                header[..100]
+                    .iter()
+                    .copied()
+                    .take_while(|b| *b != 0)
+                    .collect(),
+            )
+            .unwrap();
+            let size_field = std::str::from_utf8(&header[124..135]).unwrap();
+            let size = usize::from_str_radix(size_field, 8).unwrap();
+            let start = offset + TAR_BLOCK;
+            entries.push((name, archive[start..start + size].to_vec()));
+            offset = start + size.div_ceil(TAR_BLOCK) * TAR_BLOCK;
+        }
+        entries
+    }
+
+    #[test]
+    fn test_every_merkle_proof_reaches_root() {
+        for count in [1, 2, 3, 5, 8] {
+            let records = (0..count)
+                .map(|i| (format!("anchor_metrics/{i}"), format!("{{\"id\":{i}}}")))
+                .collect();
+            let tree = build_merkle_tree(records);
+            assert_eq!(tree.leaf_count, count);
+            for leaf in &tree.leaves {
+                assert!(
+                    verify_proof(leaf, &tree.root),
+                    "{count} leaves: {}",
+                    leaf.path
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_tampered_record_fails_proof() {
+        let records = (0..4)
+            .map(|i| (format!("corridor_metrics/{i}"), format!("{{\"v\":{i}}}")))
+            .collect();
+        let tree = build_merkle_tree(records);
+        let mut leaf = tree.leaves[2].clone();
+        leaf.record = "{\"v\":99}".to_string();
+        assert!(!verify_proof(&leaf, &tree.root));
+    }
+
+    #[tokio::test]
+    async fn test_bundle_contains_snapshot_proofs_and_anchoring() {
+        let pool = test_support::sqlite_pool(&[
+            migrations::METRICS_CORRIDORS_SNAPSHOTS,
+            migrations::CONTRACT_EVENTS,
+            migrations::SNAPSHOT_REANCHORS,
+        ])
+        .await;
+
+        let canonical = r#"{"anchor_metrics":[{"id":"a","volume_usd":1.5},{"id":"b","volume_usd":2.0}],"corridor_metrics":[{"id":"c","success_rate":0.99}],"epoch":7,"schema_version":1,"timestamp":"2024-05-01T00:00:00+00:00"}"#;
+        let hash = hex::encode(Sha256::digest(canonical.as_bytes()));
+        sqlx::query(
+            "INSERT INTO snapshots (id, entity_id, entity_type, data, hash, epoch, timestamp)
+             VALUES ('s7', 'system', 'analytics_snapshot', ?, ?, 7, '2024-05-01T00:00:00+00:00')",
+        )
+        .bind(canonical)
+        .bind(&hash)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO contract_events (id, contract_id, event_type, epoch, hash, ledger, transaction_hash)
+             VALUES ('e1', 'CSNAP', 'SNAP_SUB', 7, ?, 5000, 'tx7')",
+        )
+        .bind(&hash)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let service = AuditBundleService::new(Arc::new(Database::new(pool)));
+        assert!(service.build(8).await.unwrap().is_none());
+
+        let bundle = service.build(7).await.unwrap().unwrap();
+        assert_eq!(bundle.snapshot_hash, hash);
+        assert_eq!(bundle.archive.len() % TAR_BLOCK, 0);
+
+        let entries = untar(&bundle.archive);
+        let names: Vec<&str> = entries.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "epoch-7/snapshot.json",
+                "epoch-7/merkle.json",
+                "epoch-7/anchoring.json",
+                "epoch-7/manifest.json",
+                "epoch-7/verify.py",
+                "epoch-7/SHA256SUMS",
+            ]
+        );
+        assert_eq!(entries[0].1, canonical.as_bytes());
+
+        let merkle: serde_json::Value = serde_json::from_slice(&entries[1].1).unwrap();
+        assert_eq!(merkle["leaf_count"], 3);
+        let anchoring: serde_json::Value = serde_json::from_slice(&entries[2].1).unwrap();
+        assert_eq!(anchoring["anchorings"][0]["transaction_hash"], "tx7");
+        let manifest: serde_json::Value = serde_json::from_slice(&entries[3].1).unwrap();
+        assert_eq!(manifest["ledger_range"]["first"], 5000);
+        assert_eq!(manifest["anchor_records"], 2);
+
+        // Same database state, same bytes.
+        let again = service.build(7).await.unwrap().unwrap();
+        assert_eq!(again.archive, bundle.archive);
+    }
+}
@@ -0,0 +1,661 @@
+//! Backfill Recomputation
+//!
+//! Backfills insert payments into hours that were already rolled up, leaving
+//! `corridor_metrics_hourly` and any snapshot drafted since then out of date. Backfill
+//! jobs report the period they touched; reports that overlap a range still waiting
+//! to run are merged into it. A background worker rebuilds each dirty hour oldest
+//! first, then redrafts unanchored snapshots taken after the range began. Progress
+//! is saved after every hour, so a restart resumes where the last run stopped.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::aggregation::AggregationService;
+use super::snapshot::SnapshotService;
+use crate::cluster::{LeaderElection, ROLE_INGESTION};
+use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
+
+/// Longest range a single report may mark dirty.
+pub const MAX_RANGE_HOURS: i64 = 24 * 366;
+const DEFAULT_LIST_LIMIT: i64 = 50;
+const MAX_LIST_LIMIT: i64 = 500;
+const WORKER_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Rebuilds one hour of rollups from stored payments.
+#[async_trait::async_trait]
+pub trait HourlyRollups: Send + Sync {
+    async fn recompute_hour(&self, hour: DateTime<Utc>) -> Result<usize>;
+}
+
+#[async_trait::async_trait]
+impl HourlyRollups for AggregationService {
+    async fn recompute_hour(&self, hour: DateTime<Utc>) -> Result<usize> {
+        Self::recompute_hour(self, hour).await
+    }
+}
+
+/// Regenerates snapshot drafts that have not been anchored yet.
+#[async_trait::async_trait]
+pub trait SnapshotDrafts: Send + Sync {
+    /// Returns how many snapshots were redrafted and how many were already anchored.
+    async fn redraft_unanchored_since(&self, since: DateTime<Utc>) -> Result<(u32, u32)>;
+}
+
+#[async_trait::async_trait]
+impl SnapshotDrafts for SnapshotService {
+    async fn redraft_unanchored_since(&self, since: DateTime<Utc>) -> Result<(u32, u32)> {
+        Self::redraft_unanchored_since(self, since).await
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecomputeStatus {
+    /// Waiting for the worker; later reports may still be merged in.
+    Pending,
+    /// Hours are being rebuilt.
+    Running,
+    Completed,
+    /// Stopped at `hours_done`; reporting the period again retries it.
+    Failed,
+}
+
+impl RecomputeStatus {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "pending" => Ok(Self::Pending),
+            "running" => Ok(Self::Running),
+            "completed" => Ok(Self::Completed),
+            "failed" => Ok(Self::Failed),
+            other => Err(anyhow!("Unknown recompute status '{other}'")),
+        }
+    }
+}
+
+/// Period a backfill inserted data into.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MarkDirtyRequest {
+    /// Name of the backfill, shown in progress listings.
+    #[schema(example = "horizon-backfill-2024-03")]
+    pub source: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RecomputeRangeQuery {
+    /// Only ranges in this state.
+    pub status: Option<RecomputeStatus>,
+    /// Default 50, at most 500.
+    pub limit: Option<i64>,
+}
+
+/// A dirty period and how far its recomputation has got.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecomputeRange {
+    pub id: String,
+    /// Backfills that dirtied this range.
+    pub sources: Vec<String>,
+    /// First dirty hour.
+    pub range_start: DateTime<Utc>,
+    /// End of the last dirty hour, exclusive.
+    pub range_end: DateTime<Utc>,
+    pub status: RecomputeStatus,
+    pub hours_total: i64,
+    pub hours_done: i64,
+    pub progress_percent: f64,
+    pub snapshots_redrafted: i64,
+    /// Snapshots covering the range that were already anchored and so kept as they are.
+    pub anchored_snapshots: i64,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+fn parse_time(row: &sqlx::sqlite::SqliteRow, column: &str) -> Result<DateTime<Utc>> {
+    let value: String = row.get(column);
+    Ok(DateTime::parse_from_rfc3339(&value)
+        .with_context(|| format!("Invalid {column} '{value}'"))?
+        .with_timezone(&Utc))
+}
+
+fn split_sources(sources: &str) -> BTreeSet<String> {
+    sources
+        .split(',')
+        .filter(|source| !source.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl RecomputeRange {
+    fn from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Self> {
+        let hours_total: i64 = row.get("hours_total");
+        let hours_done: i64 = row.get("hours_done");
+        Ok(Self {
+            id: row.get("id"),
+            sources: split_sources(&row.get::<String, _>("source"))
+                .into_iter()
+                .collect(),
+            range_start: parse_time(row, "range_start")?,
+            range_end: parse_time(row, "range_end")?,
+            status: RecomputeStatus::parse(&row.get::<String, _>("status"))?,
+            hours_total,
+            hours_done,
+            progress_percent: if hours_total > 0 {
+                (hours_done as f64 / hours_total as f64 * 1000.0).round() / 10.0
+            } else {
+                100.0
+            },
+            snapshots_redrafted: row.get("snapshots_redrafted"),
+            anchored_snapshots: row.get("anchored_snapshots"),
+            error: row.get("error"),
+            created_at: parse_time(row, "created_at")?,
+            updated_at: parse_time(row, "updated_at")?,
+        })
+    }
+}
+
+/// Widen `[start, end)` to whole hours.
+pub fn hour_bounds(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+    let hour = Duration::hours(1);
+    let floor = start
+        .duration_trunc(hour)
+        .context("start is out of range")?;
+    let truncated_end = end.duration_trunc(hour).context("end is out of range")?;
+    let ceil = if truncated_end == end {
+        end
+    } else {
+        truncated_end + hour
+    };
+    Ok((floor, ceil))
+}
+
+pub struct BackfillCoordinator {
+    db: Arc<Database>,
+    rollups: Option<Arc<dyn HourlyRollups>>,
+    snapshots: Option<Arc<dyn SnapshotDrafts>>,
+    leader: Option<Arc<LeaderElection>>,
+}
+
+impl BackfillCoordinator {
+    #[must_use]
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            rollups: None,
+            snapshots: None,
+            leader: None,
+        }
+    }
+
+    /// Rebuild dirty hours with `rollups`; required for [`Self::start`].
+    #[must_use]
+    pub fn with_rollups(mut self, rollups: Arc<dyn HourlyRollups>) -> Self {
+        self.rollups = Some(rollups);
+        self
+    }
+
+    /// Also redraft unanchored snapshots once a range's rollups are rebuilt.
+    #[must_use]
+    pub fn with_snapshots(mut self, snapshots: Arc<dyn SnapshotDrafts>) -> Self {
+        self.snapshots = Some(snapshots);
+        self
+    }
+
+    /// Only recompute on the replica holding the ingestion lease.
+    #[must_use]
+    pub fn with_leader_election(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    /// Record that `source` inserted data between `start` and `end`. The period is
+    /// widened to whole hours and merged with any pending or failed range it
+    /// overlaps or touches; a range already running is left alone.
+    pub async fn mark_dirty(
+        &self,
+        request: &MarkDirtyRequest,
+        now: DateTime<Utc>,
+    ) -> Result<RecomputeRange> {
+        let source = request.source.trim();
+        if source.is_empty() || source.contains(',') {
+            bail!("source must be a non-empty name without commas");
+        }
+        if request.start >= request.end {
+            bail!("start must be before end");
+        }
+        let (mut range_start, mut range_end) = hour_bounds(request.start, request.end)?;
+        if (range_end - range_start).num_hours() > MAX_RANGE_HOURS {
+            bail!("A range may span at most {MAX_RANGE_HOURS} hours");
+        }
+
+        let mut tx = self.db.pool().begin().await?;
+        let overlapping = sqlx::query(
+            r"
+            SELECT id, source, range_start, range_end
+            FROM recompute_ranges
+            WHERE status IN ('pending', 'failed')
+              AND range_start <= ? AND range_end >= ?
+            ",
+        )
+        .bind(range_end.to_rfc3339())
+        .bind(range_start.to_rfc3339())
+        .fetch_all(&mut *tx)
+        .await
+        .context("Failed to load overlapping recompute ranges")?;
+
+        let mut sources = BTreeSet::from([source.to_string()]);
+        for row in &overlapping {
+            range_start = range_start.min(parse_time(row, "range_start")?);
+            range_end = range_end.max(parse_time(row, "range_end")?);
+            sources.extend(split_sources(&row.get::<String, _>("source")));
+            sqlx::query("DELETE FROM recompute_ranges WHERE id = ?")
+                .bind(row.get::<String, _>("id"))
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let id = Uuid::new_v4().to_string();
+        sqlx::query(
+            r"
+            INSERT INTO recompute_ranges (
+                id, source, range_start, range_end, status, hours_total,
+                created_at, updated_at
+            ) VALUES (?, ?, ?, ?, 'pending', ?, ?, ?)
+            ",
+        )
+        .bind(&id)
+        .bind(sources.into_iter().collect::<Vec<_>>().join(","))
+        .bind(range_start.to_rfc3339())
+        .bind(range_end.to_rfc3339())
+        .bind((range_end - range_start).num_hours())
+        .bind(now.to_rfc3339())
+        .bind(now.to_rfc3339())
+        .execute(&mut *tx)
+        .await
+        .context("Failed to record recompute range")?;
+        tx.commit().await?;
+
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "recompute.range_marked",
+            range_start = %range_start,
+            range_end = %range_end,
+            source,
+            "Marked range dirty after backfill"
+        );
+        self.get(&id)
+            .await?
+            .ok_or_else(|| anyhow!("Recompute range {id} vanished after insert"))
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<RecomputeRange>> {
+        let row = sqlx::query("SELECT * FROM recompute_ranges WHERE id = ?")
+            .bind(id)
+            .fetch_optional(self.db.pool())
+            .await
+            .context("Failed to load recompute range")?;
+        row.as_ref().map(RecomputeRange::from_row).transpose()
+    }
+
+    /// Ranges, newest first.
+    pub async fn list(&self, query: &RecomputeRangeQuery) -> Result<Vec<RecomputeRange>> {
+        let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+        if !(1..=MAX_LIST_LIMIT).contains(&limit) {
+            bail!("limit must be between 1 and {MAX_LIST_LIMIT}");
+        }
+        let rows = sqlx::query(
+            r"
+            SELECT * FROM recompute_ranges
+            WHERE ? IS NULL OR status = ?
+            ORDER BY created_at DESC
+            LIMIT ?
+            ",
+        )
+        .bind(query.status.map(RecomputeStatus::as_str))
+        .bind(query.status.map(RecomputeStatus::as_str))
+        .bind(limit)
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to list recompute ranges")?;
+        rows.iter().map(RecomputeRange::from_row).collect()
+    }
+
+    async fn update_progress(
+        &self,
+        id: &str,
+        status: RecomputeStatus,
+        hours_done: i64,
+        error: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r"
+            UPDATE recompute_ranges
+            SET status = ?, hours_done = ?, error = ?, updated_at = ?
+            WHERE id = ?
+            ",
+        )
+        .bind(status.as_str())
+        .bind(hours_done)
+        .bind(error)
+        .bind(Utc::now().to_rfc3339())
+        .bind(id)
+        .execute(self.db.pool())
+        .await
+        .context("Failed to update recompute progress")?;
+        Ok(())
+    }
+
+    /// Recompute the oldest outstanding range, resuming an interrupted one first.
+    /// Returns the range as it ended up, or `None` when nothing is outstanding. A
+    /// failing hour marks the range failed rather than returning an error.
+    pub async fn run_next(&self) -> Result<Option<RecomputeRange>> {
+        let rollups = self
+            .rollups
+            .as_ref()
+            .ok_or_else(|| anyhow!("Backfill coordinator has no rollup service"))?;
+        let row = sqlx::query(
+            r"
+            SELECT * FROM recompute_ranges
+            WHERE status IN ('running', 'pending')
+            ORDER BY status = 'running' DESC, range_start ASC
+            LIMIT 1
+            ",
+        )
+        .fetch_optional(self.db.pool())
+        .await
+        .context("Failed to load next recompute range")?;
+        let Some(range) = row.as_ref().map(RecomputeRange::from_row).transpose()? else {
+            return Ok(None);
+        };
+
+        self.update_progress(&range.id, RecomputeStatus::Running, range.hours_done, None)
+            .await?;
+        for done in range.hours_done..range.hours_total {
+            let hour = range.range_start + Duration::hours(done);
+            if let Err(e) = rollups.recompute_hour(hour).await {
+                log_event!(
+                    warn,
+                    Subsystem::Jobs,
+                    "recompute.hour_failed",
+                    range_id = %range.id,
+                    hour = %hour,
+                    error = format!("{e:#}"),
+                    "Recomputing hour failed"
+                );
+                self.update_progress(
+                    &range.id,
+                    RecomputeStatus::Failed,
+                    done,
+                    Some(&format!("Hour {}: {e:#}", hour.to_rfc3339())),
+                )
+                .await?;
+                return self.get(&range.id).await;
+            }
+            self.update_progress(&range.id, RecomputeStatus::Running, done + 1, None)
+                .await?;
+        }
+
+        if let Some(snapshots) = &self.snapshots {
+            match snapshots.redraft_unanchored_since(range.range_start).await {
+                Ok((redrafted, anchored)) => {
+                    sqlx::query(
+                        r"
+                        UPDATE recompute_ranges
+                        SET snapshots_redrafted = ?, anchored_snapshots = ?
+                        WHERE id = ?
+                        ",
+                    )
+                    .bind(i64::from(redrafted))
+                    .bind(i64::from(anchored))
+                    .bind(&range.id)
+                    .execute(self.db.pool())
+                    .await
+                    .context("Failed to record redrafted snapshots")?;
+                    if anchored > 0 {
+                        log_event!(
+                            warn,
+                            Subsystem::Snapshot,
+                            "recompute.anchored_snapshots_stale",
+                            anchored,
+                            range_start = %range.range_start,
+                            "Anchored snapshots cover backfilled data"
+                        );
+                    }
+                }
+                Err(e) => {
+                    self.update_progress(
+                        &range.id,
+                        RecomputeStatus::Failed,
+                        range.hours_total,
+                        Some(&format!("Redrafting snapshots: {e:#}")),
+                    )
+                    .await?;
+                    return self.get(&range.id).await;
+                }
+            }
+        }
+
+        self.update_progress(
+            &range.id,
+            RecomputeStatus::Completed,
+            range.hours_total,
+            None,
+        )
+        .await?;
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "recompute.completed",
+            range_id = %range.id,
+            hours = range.hours_total,
+            range_start = %range.range_start,
+            sources = %range.sources.join(", "),
+            "Recomputed backfilled range"
+        );
+        self.get(&range.id).await
+    }
+
+    pub async fn start(self: Arc<Self>) {
+        let mut ticker = tokio::time::interval(WORKER_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Some(leader) = &self.leader {
+                if !leader.is_leader(ROLE_INGESTION) {
+                    continue;
+                }
+            }
+            loop {
+                match self.run_next().await {
+                    Ok(Some(_)) => {}
+                    Ok(None) => break,
+                    Err(e) => {
+                        log_event!(
+                            warn,
+                            Subsystem::Jobs,
+                            "recompute.failed",
+                            error = format!("{e:#}"),
+                            "Backfill recomputation failed"
+                        );
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct FakeRollups {
+        hours: Mutex<Vec<DateTime<Utc>>>,
+        fail_at: Option<DateTime<Utc>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HourlyRollups for FakeRollups {
+        async fn recompute_hour(&self, hour: DateTime<Utc>) -> Result<usize> {
+            if self.fail_at == Some(hour) {
+                bail!("payments table locked");
+            }
+            self.hours.lock().unwrap().push(hour);
+            Ok(1)
+        }
+    }
+
+    struct FakeDrafts;
+
+    #[async_trait::async_trait]
+    impl SnapshotDrafts for FakeDrafts {
+        async fn redraft_unanchored_since(&self, _since: DateTime<Utc>) -> Result<(u32, u32)> {
+            Ok((2, 1))
+        }
+    }
+
+    async fn setup() -> Arc<Database> {
+        test_support::sqlite_db(&[migrations::RECOMPUTE_RANGES]).await
+    }
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn request(source: &str, start: &str, end: &str) -> MarkDirtyRequest {
+        MarkDirtyRequest {
+            source: source.to_string(),
+            start: at(start),
+            end: at(end),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mark_dirty_merges_overlapping_ranges() {
+        let coordinator = BackfillCoordinator::new(setup().await);
+        let now = at("2024-03-10T00:00:00Z");
+
+        let first = coordinator
+            .mark_dirty(
+                &request("march", "2024-03-01T10:15:00Z", "2024-03-01T12:30:00Z"),
+                now,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            (first.range_start, first.range_end, first.hours_total),
+            (at("2024-03-01T10:00:00Z"), at("2024-03-01T13:00:00Z"), 3)
+        );
+
+        // Touching the end of the pending range extends it
+        let merged = coordinator
+            .mark_dirty(
+                &request("fix", "2024-03-01T13:00:00Z", "2024-03-01T14:00:00Z"),
+                now,
+            )
+            .await
+            .unwrap();
+        assert_eq!(merged.range_start, at("2024-03-01T10:00:00Z"));
+        assert_eq!(merged.hours_total, 4);
+        assert_eq!(merged.sources, ["fix", "march"]);
+
+        coordinator
+            .mark_dirty(
+                &request("april", "2024-04-01T00:00:00Z", "2024-04-01T01:00:00Z"),
+                now,
+            )
+            .await
+            .unwrap();
+        let all = coordinator
+            .list(&RecomputeRangeQuery {
+                status: Some(RecomputeStatus::Pending),
+                limit: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 2);
+
+        assert!(coordinator
+            .mark_dirty(
+                &request("empty", "2024-03-01T10:00:00Z", "2024-03-01T10:00:00Z"),
+                now
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_next_resumes_after_failure() {
+        let db = setup().await;
+        let now = at("2024-03-10T00:00:00Z");
+        let failing = Arc::new(FakeRollups {
+            fail_at: Some(at("2024-03-01T11:00:00Z")),
+            ..FakeRollups::default()
+        });
+        let coordinator = BackfillCoordinator::new(Arc::clone(&db)).with_rollups(failing);
+        coordinator
+            .mark_dirty(
+                &request("march", "2024-03-01T10:00:00Z", "2024-03-01T13:00:00Z"),
+                now,
+            )
+            .await
+            .unwrap();
+
+        let failed = coordinator.run_next().await.unwrap().unwrap();
+        assert_eq!(failed.status, RecomputeStatus::Failed);
+        assert_eq!(failed.hours_done, 1);
+        assert!(failed.error.unwrap().contains("payments table locked"));
+        assert!(coordinator.run_next().await.unwrap().is_none());
+
+        // Reporting the period again picks the failed range back up from scratch
+        let rollups = Arc::new(FakeRollups::default());
+        let coordinator = BackfillCoordinator::new(db)
+            .with_rollups(rollups.clone())
+            .with_snapshots(Arc::new(FakeDrafts));
+        coordinator
+            .mark_dirty(
+                &request("retry", "2024-03-01T12:00:00Z", "2024-03-01T13:00:00Z"),
+                now,
+            )
+            .await
+            .unwrap();
+        let done = coordinator.run_next().await.unwrap().unwrap();
+        assert_eq!(done.status, RecomputeStatus::Completed);
+        assert_eq!((done.hours_done, done.progress_percent), (3, 100.0));
+        assert_eq!((done.snapshots_redrafted, done.anchored_snapshots), (2, 1));
+        assert_eq!(
+            *rollups.hours.lock().unwrap(),
+            [
+                at("2024-03-01T10:00:00Z"),
+                at("2024-03-01T11:00:00Z"),
+                at("2024-03-01T12:00:00Z")
+            ]
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! Bulk Snapshot Verification
+//!
+//! Auditors check months of history by submitting many `(epoch, hash)` pairs at once.
+//! Pairs with a cached verdict are answered from the cache; the rest are grouped into
+//! runs of consecutive epochs and each run is checked with one simulated
+//! `verify_snapshot_range` call on the snapshot contract, a few runs at a time.
+
+use crate::cache::{keys, CacheManager};
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::services::contract::{ContractService, MAX_VERIFY_RANGE};
+use anyhow::{anyhow, Result};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+/// Most pairs accepted in one request.
+pub const MAX_BATCH_ITEMS: usize = 1_000;
+const MAX_CONCURRENT_CALLS: usize = 4;
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct BatchVerifyItem {
+    pub epoch: u64,
+    /// Hex-encoded 32-byte snapshot hash.
+    #[schema(example = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08")]
+    pub hash: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchVerifyRequest {
+    pub items: Vec<BatchVerifyItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// The contract holds this hash for the epoch.
+    Verified,
+    /// The epoch has no live snapshot, or a different hash.
+    NotVerified,
+    /// The contract could not be reached; retry later.
+    Unavailable,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BatchVerifyResult {
+    pub epoch: u64,
+    pub hash: String,
+    pub verdict: Verdict,
+    /// Whether the verdict came from the cache rather than the contract.
+    pub cached: bool,
+}
+
+/// Verdicts in request order, with totals.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BatchVerifyResponse {
+    pub results: Vec<BatchVerifyResult>,
+    pub verified: usize,
+    pub not_verified: usize,
+    pub unavailable: usize,
+    /// Simulated contract calls made for the uncached pairs.
+    pub contract_calls: usize,
+}
+
+/// Consecutive epochs checked with one contract call; `hashes[i]` is for
+/// `start_epoch + i`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpochRange {
+    pub start_epoch: u64,
+    pub hashes: Vec<String>,
+}
+
+/// Check every item and normalize hashes to lowercase hex, rejecting the batch with
+/// the position of the first bad item.
+pub fn validate_items(items: &[BatchVerifyItem]) -> Result<Vec<(u64, String)>> {
+    if items.is_empty() {
+        return Err(anyhow!("items must not be empty"));
+    }
+    if items.len() > MAX_BATCH_ITEMS {
+        return Err(anyhow!(
+            "At most {MAX_BATCH_ITEMS} items can be verified per request, got {}",
+            items.len()
+        ));
+    }
+
+    items
+        .iter()
+        .enumerate()
+        .map(|(position, item)| {
+            if item.epoch == 0 {
+                return Err(anyhow!("items[{position}]: epoch must be positive"));
+            }
+            let hash = item.hash.trim();
+            let hash = hash.strip_prefix("0x").unwrap_or(hash).to_ascii_lowercase();
+            if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(anyhow!("items[{position}]: hash must be 32 bytes of hex"));
+            }
+            Ok((item.epoch, hash))
+        })
+        .collect()
+}
+
+/// Group pairs into runs of consecutive epochs of at most [`MAX_VERIFY_RANGE`].
+/// A second hash for an epoch already in a run starts a new run.
+#[must_use]
+pub fn plan_ranges(pairs: &BTreeSet<(u64, String)>) -> Vec<EpochRange> {
+    let mut ranges: Vec<EpochRange> = Vec::new();
+    for (epoch, hash) in pairs {
+        match ranges.last_mut() {
+            Some(range)
+                if range.hashes.len() < MAX_VERIFY_RANGE
+                    && range.start_epoch + range.hashes.len() as u64 == *epoch =>
+            {
+                range.hashes.push(hash.clone());
+            }
+            _ => ranges.push(EpochRange {
+                start_epoch: *epoch,
+                hashes: vec![hash.clone()],
+            }),
+        }
+    }
+    ranges
+}
+
+pub struct BatchVerificationService {
+    contract: Option<Arc<ContractService>>,
+    cache: Arc<CacheManager>,
+}
+
+impl BatchVerificationService {
+    #[must_use]
+    pub const fn new(contract: Option<Arc<ContractService>>, cache: Arc<CacheManager>) -> Self {
+        Self { contract, cache }
+    }
+
+    #[must_use]
+    pub const fn has_contract(&self) -> bool {
+        self.contract.is_some()
+    }
+
+    /// Verdict for each validated pair, in order. Contract failures mark the affected
+    /// pairs [`Verdict::Unavailable`] instead of failing the batch, and are not cached.
+    pub async fn verify_batch(&self, pairs: &[(u64, String)]) -> BatchVerifyResponse {
+        let mut verdicts: HashMap<(u64, String), (Verdict, bool)> = HashMap::new();
+        let mut uncached: BTreeSet<(u64, String)> = BTreeSet::new();
+        for (epoch, hash) in pairs {
+            let key = (*epoch, hash.clone());
+            if verdicts.contains_key(&key) || uncached.contains(&key) {
+                continue;
+            }
+            match self
+                .cache
+                .get::<Verdict>(&keys::snapshot_verdict(*epoch, hash))
+                .await
+            {
+                Ok(Some(verdict)) => {
+                    verdicts.insert(key, (verdict, true));
+                }
+                _ => {
+                    uncached.insert(key);
+                }
+            }
+        }
+
+        let ranges = plan_ranges(&uncached);
+        let contract_calls = if self.has_contract() { ranges.len() } else { 0 };
+        let checked: Vec<(EpochRange, Option<Vec<bool>>)> = stream::iter(ranges)
+            .map(|range| async move {
+                let flags = match &self.contract {
+                    Some(contract) => contract
+                        .verify_snapshot_range(range.start_epoch, &range.hashes)
+                        .await
+                        .map_err(|e| {
+                            log_event!(
+                                warn,
+                                Subsystem::Contract,
+                                "batch_verification.range_failed",
+                                start_epoch = range.start_epoch,
+                                error = format!("{e:#}"),
+                                "Range verification failed"
+                            );
+                        })
+                        .ok(),
+                    None => None,
+                };
+                (range, flags)
+            })
+            .buffer_unordered(MAX_CONCURRENT_CALLS)
+            .collect()
+            .await;
+
+        let ttl = self.cache.config.get_ttl("snapshot");
+        for (range, flags) in checked {
+            for (offset, (epoch, hash)) in (range.start_epoch..).zip(range.hashes).enumerate() {
+                let verdict = match flags.as_ref().and_then(|flags| flags.get(offset)) {
+                    Some(true) => Verdict::Verified,
+                    Some(false) => Verdict::NotVerified,
+                    None => Verdict::Unavailable,
+                };
+                if verdict != Verdict::Unavailable {
+                    if let Err(e) = self
+                        .cache
+                        .set(&keys::snapshot_verdict(epoch, &hash), &verdict, ttl)
+                        .await
+                    {
+                        log_event!(
+                            warn,
+                            Subsystem::Cache,
+                            "batch_verification.cache_write_failed",
+                            epoch,
+                            error = %e,
+                            "Failed to cache verdict"
+                        );
+                    }
+                }
+                verdicts.insert((epoch, hash), (verdict, false));
+            }
+        }
+
+        let results: Vec<BatchVerifyResult> = pairs
+            .iter()
+            .map(|(epoch, hash)| {
+                let (verdict, cached) = verdicts
+                    .get(&(*epoch, hash.clone()))
+                    .copied()
+                    .unwrap_or((Verdict::Unavailable, false));
+                BatchVerifyResult {
+                    epoch: *epoch,
+                    hash: hash.clone(),
+                    verdict,
+                    cached,
+                }
+            })
+            .collect();
+        let count = |verdict: Verdict| results.iter().filter(|r| r.verdict == verdict).count();
+
+        BatchVerifyResponse {
+            verified: count(Verdict::Verified),
+            not_verified: count(Verdict::NotVerified),
+            unavailable: count(Verdict::Unavailable),
+            contract_calls,
+            results,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheConfig;
+
+    fn hash(byte: char) -> String {
+        byte.to_string().repeat(64)
+    }
+
+    #[test]
+    fn test_plan_ranges_splits_gaps_duplicates_and_long_runs() {
+        let mut pairs: BTreeSet<(u64, String)> = (1..=60).map(|epoch| (epoch, hash('a'))).collect();
+        pairs.insert((62, hash('a')));
+        pairs.insert((62, hash('b')));
+
+        let ranges = plan_ranges(&pairs);
+        let shape: Vec<(u64, usize)> = ranges
+            .iter()
+            .map(|r| (r.start_epoch, r.hashes.len()))
+            .collect();
+        assert_eq!(shape, [(1, 50), (51, 10), (62, 1), (62, 1)]);
+    }
+
+    #[test]
+    fn test_validate_items() {
+        let item = |epoch, hash: &str| BatchVerifyItem {
+            epoch,
+            hash: hash.to_string(),
+        };
+        let pairs = validate_items(&[item(3, &format!("0x{}", "AB".repeat(32)))]).unwrap();
+        assert_eq!(pairs, [(3, "ab".repeat(32))]);
+
+        let err = validate_items(&[item(1, &hash('a')), item(2, "abc")]).unwrap_err();
+        assert_eq!(err.to_string(), "items[1]: hash must be 32 bytes of hex");
+        assert!(validate_items(&[item(0, &hash('a'))]).is_err());
+        assert!(validate_items(&[]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cached_verdicts_skip_the_contract() {
+        let cache = Arc::new(CacheManager::new_in_memory_for_tests(CacheConfig::default()));
+        cache
+            .set(
+                &keys::snapshot_verdict(7, &hash('a')),
+                &Verdict::Verified,
+                60,
+            )
+            .await
+            .unwrap();
+        let service = BatchVerificationService::new(None, Arc::clone(&cache));
+
+        let response = service
+            .verify_batch(&[(7, hash('a')), (8, hash('b')), (7, hash('a'))])
+            .await;
+        let verdicts: Vec<(Verdict, bool)> = response
+            .results
+            .iter()
+            .map(|r| (r.verdict, r.cached))
+            .collect();
+        assert_eq!(
+            verdicts,
+            [
+                (Verdict::Verified, true),
+                (Verdict::Unavailable, false),
+                (Verdict::Verified, true)
+            ]
+        );
+        assert_eq!((response.verified, response.unavailable), (2, 1));
+        assert_eq!(response.contract_calls, 0);
+    }
+}
@@ -11,7 +11,10 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::time::Duration;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, info, warn};
+
+use crate::log_event;
+use crate::logging::Subsystem;
 
 // Stellar SDK transaction signing is handled via the Soroban RPC simulation flow.
 // Full keypair-based signing requires a Soroban-compatible SDK; the current
@@ -21,6 +24,8 @@ const MAX_RETRIES: u32 = 3;
 const INITIAL_BACKOFF_MS: u64 = 1000;
 const BACKOFF_MULTIPLIER: u64 = 2;
 const REQUEST_TIMEOUT_SECS: u64 = 30;
+/// Most hashes the contract's `verify_snapshot_range` accepts per call
+pub const MAX_VERIFY_RANGE: usize = 50;
 
 /// Configuration for the contract service
 #[derive(Clone, Debug)]
@@ -33,6 +38,9 @@ pub struct ContractConfig {
     pub network_passphrase: String,
     /// Source account secret key for signing transactions
     pub source_secret_key: String,
+    /// Source account address, passed as the authorizing `caller` of `submit_snapshot`.
+    /// Must match the snapshot contract's admin.
+    pub source_public_key: String,
 }
 
 /// Service for interacting with the Soroban snapshot contract
@@ -125,11 +133,70 @@ impl ContractService {
                 .unwrap_or_else(|_| "Test SDF Network ; September 2015".to_string()),
             source_secret_key: std::env::var("STELLAR_SOURCE_SECRET_KEY")
                 .context("STELLAR_SOURCE_SECRET_KEY environment variable not set")?,
+            source_public_key: std::env::var("STELLAR_SOURCE_PUBLIC_KEY")
+                .context("STELLAR_SOURCE_PUBLIC_KEY environment variable not set")?,
         };
 
         Self::new(config)
     }
 
+    /// Create a verification-only client from environment variables
+    ///
+    /// Read-only mirrors use this: it never loads `STELLAR_SOURCE_SECRET_KEY`, so
+    /// the service can simulate reads such as `verify_snapshot_range` but refuses
+    /// to submit.
+    pub fn read_only_from_env() -> Result<Self> {
+        let config = ContractConfig {
+            rpc_url: std::env::var("SOROBAN_RPC_URL")
+                .unwrap_or_else(|_| "https://soroban-testnet.stellar.org".to_string()),
+            contract_id: std::env::var("SNAPSHOT_CONTRACT_ID")
+                .context("SNAPSHOT_CONTRACT_ID environment variable not set")?,
+            network_passphrase: std::env::var("STELLAR_NETWORK_PASSPHRASE")
+                .unwrap_or_else(|_| "Test SDF Network ; September 2015".to_string()),
+            source_secret_key: String::new(),
+            source_public_key: std::env::var("STELLAR_SOURCE_PUBLIC_KEY").unwrap_or_default(),
+        };
+
+        Self::new(config)
+    }
+
+    /// Snapshot contract this service submits to
+    #[must_use]
+    pub fn contract_id(&self) -> &str {
+        &self.config.contract_id
+    }
+
+    /// Simulate a snapshot submission without sending it, returning the raw
+    /// `simulateTransaction` result with resource fee and footprint.
+    pub async fn simulate_snapshot_submission(
+        &self,
+        hash: [u8; 32],
+        epoch: u64,
+    ) -> Result<serde_json::Value> {
+        let invoke_args = self.build_invoke_args(hash, epoch)?;
+        self.simulate_transaction(&invoke_args).await
+    }
+
+    /// Simulate an arbitrary contract invocation without sending it
+    ///
+    /// # Arguments
+    /// * `contract_id` - Contract to invoke
+    /// * `function` - Contract function name
+    /// * `args` - Typed arguments in the same `{"type", "value"}` form used for submissions
+    pub async fn simulate_invocation(
+        &self,
+        contract_id: &str,
+        function: &str,
+        args: &[serde_json::Value],
+    ) -> Result<serde_json::Value> {
+        let invoke_args = json!({
+            "contractId": contract_id,
+            "function": function,
+            "args": args
+        });
+        self.simulate_transaction(&invoke_args).await
+    }
+
     /// Submit a snapshot hash to the on-chain contract
     ///
     /// This function will:
@@ -169,10 +236,19 @@ impl ContractService {
         hash: [u8; 32],
         epoch: u64,
     ) -> Result<SubmissionResult> {
-        info!(
-            "Submitting snapshot hash for epoch {}: {}",
+        if self.config.source_secret_key.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Contract service is verification-only; no source secret key is loaded"
+            ));
+        }
+        log_event!(
+            info,
+            Subsystem::Contract,
+            "snapshot.submit_started",
             epoch,
-            hex::encode(hash)
+            hash = %hex::encode(hash),
+            contract_id = %self.config.contract_id,
+            "Submitting snapshot hash"
         );
 
         let mut attempt = 0;
@@ -183,26 +259,44 @@ impl ContractService {
 
             match self.try_submit_snapshot(hash, epoch).await {
                 Ok(result) => {
-                    info!(
-                        "✓ Successfully submitted snapshot for epoch {} (tx: {}, ledger: {})",
-                        epoch, result.transaction_hash, result.ledger
+                    log_event!(
+                        info,
+                        Subsystem::Contract,
+                        "snapshot.submitted",
+                        epoch,
+                        tx_hash = %result.transaction_hash,
+                        ledger = result.ledger,
+                        attempt,
+                        "Snapshot submitted on-chain"
                     );
                     return Ok(result);
                 }
                 Err(e) => {
                     if attempt >= MAX_RETRIES {
-                        error!(
-                            "✗ Failed to submit snapshot for epoch {} after {} attempts: {}",
-                            epoch, MAX_RETRIES, e
+                        log_event!(
+                            error,
+                            Subsystem::Contract,
+                            "snapshot.submit_failed",
+                            epoch,
+                            attempts = MAX_RETRIES,
+                            error = %e,
+                            "Snapshot submission failed"
                         );
                         return Err(e).context(format!(
                             "Failed to submit snapshot after {MAX_RETRIES} retries"
                         ));
                     }
 
-                    warn!(
-                        "Attempt {}/{} failed for epoch {}: {}. Retrying in {}ms...",
-                        attempt, MAX_RETRIES, epoch, e, backoff_ms
+                    log_event!(
+                        warn,
+                        Subsystem::Contract,
+                        "snapshot.submit_retry",
+                        epoch,
+                        attempt,
+                        max_attempts = MAX_RETRIES,
+                        backoff_ms,
+                        error = %e,
+                        "Snapshot submission attempt failed, retrying"
                     );
 
                     tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
@@ -243,7 +337,7 @@ impl ContractService {
         let hash_hex = hex::encode(hash);
 
         // Build Soroban contract invocation parameters
-        // Format: invoke contract_id submit_snapshot [hash_bytes, epoch_u64]
+        // Format: invoke contract_id submit_snapshot [hash_bytes, epoch_u64, caller_address]
         Ok(json!({
             "contractId": self.config.contract_id,
             "function": "submit_snapshot",
@@ -255,6 +349,10 @@ impl ContractService {
                 {
                     "type": "u64",
                     "value": epoch.to_string()
+                },
+                {
+                    "type": "address",
+                    "value": self.config.source_public_key
                 }
             ]
         }))
@@ -555,6 +653,65 @@ impl ContractService {
         }
     }
 
+    /// Check consecutive epochs in one simulated call: `hashes[i]` is compared with
+    /// the snapshot at `start_epoch + i`, returning one flag per hash.
+    pub async fn verify_snapshot_range(
+        &self,
+        start_epoch: u64,
+        hashes: &[String],
+    ) -> Result<Vec<bool>> {
+        if hashes.len() > MAX_VERIFY_RANGE {
+            return Err(anyhow::anyhow!(
+                "At most {MAX_VERIFY_RANGE} hashes can be verified per call"
+            ));
+        }
+        let result = self
+            .simulate_transaction(&self.build_verify_range_args(start_epoch, hashes))
+            .await?;
+
+        let verdicts: Vec<bool> = result
+            .get("returnValue")
+            .and_then(serde_json::Value::as_array)
+            .context("verify_snapshot_range returned no list")?
+            .iter()
+            .map(|value| value.as_bool().unwrap_or(false))
+            .collect();
+        if verdicts.len() != hashes.len() {
+            return Err(anyhow::anyhow!(
+                "verify_snapshot_range returned {} results for {} hashes",
+                verdicts.len(),
+                hashes.len()
+            ));
+        }
+        debug!(
+            "Verified {} epochs from {} in one call",
+            hashes.len(),
+            start_epoch
+        );
+        Ok(verdicts)
+    }
+
+    fn build_verify_range_args(&self, start_epoch: u64, hashes: &[String]) -> serde_json::Value {
+        let hashes: Vec<serde_json::Value> = hashes
+            .iter()
+            .map(|hash| json!({ "type": "bytes", "value": hash }))
+            .collect();
+        json!({
+            "contractId": self.config.contract_id,
+            "function": "verify_snapshot_range",
+            "args": [
+                {
+                    "type": "vec",
+                    "value": hashes
+                },
+                {
+                    "type": "u64",
+                    "value": start_epoch.to_string()
+                }
+            ]
+        })
+    }
+
     /// Get snapshot data for a specific epoch from the contract
     pub async fn get_snapshot_by_epoch(&self, epoch: u64) -> Result<Option<String>> {
         debug!("Getting snapshot for epoch {}", epoch);
@@ -623,6 +780,7 @@ mod tests {
             contract_id: "CBGTG4JJFEQE3SPBGQFP3X5HM46N47LXZPXQACVKB7QA6X2XB2IG5CTA".to_string(),
             network_passphrase: "Test SDF Network ; September 2015".to_string(),
             source_secret_key: "S...".to_string(),
+            source_public_key: "GADMIN".to_string(),
         };
 
         let service = ContractService::new(config).unwrap();
@@ -636,7 +794,9 @@ mod tests {
             "CBGTG4JJFEQE3SPBGQFP3X5HM46N47LXZPXQACVKB7QA6X2XB2IG5CTA"
         );
         assert_eq!(args["function"], "submit_snapshot");
-        assert!(args["args"].is_array());
+        assert_eq!(args["args"].as_array().unwrap().len(), 3);
+        assert_eq!(args["args"][2]["type"], "address");
+        assert_eq!(args["args"][2]["value"], "GADMIN");
     }
 
     #[tokio::test]
@@ -1,5 +1,8 @@
 use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
 use crate::services::alert_service::AlertService;
+use crate::services::contract_webhook_bridge::ContractWebhookBridge;
 use anyhow::{Context, Result};
 use chrono::Utc;
 use reqwest::Client;
@@ -77,6 +80,7 @@ pub struct ContractEventListener {
     config: ListenerConfig,
     db: Arc<Database>,
     alert_service: Arc<AlertService>,
+    webhook_bridge: Option<Arc<ContractWebhookBridge>>,
     last_ledger: u64,
 }
 
@@ -102,10 +106,18 @@ impl ContractEventListener {
             config,
             db,
             alert_service,
+            webhook_bridge: None,
             last_ledger: 0,
         })
     }
 
+    /// Forward bridged contract events to webhook subscribers as they are processed
+    #[must_use]
+    pub fn with_webhook_bridge(mut self, bridge: Arc<ContractWebhookBridge>) -> Self {
+        self.webhook_bridge = Some(bridge);
+        self
+    }
+
     /// Start listening to contract events
     pub async fn start_listening(&mut self) -> Result<()> {
         info!("Starting contract event listener");
@@ -226,6 +238,19 @@ impl ContractEventListener {
     async fn process_event(&self, event: ContractEvent) -> Result<()> {
         debug!("Processing contract event: {:?}", event);
 
+        if let Some(bridge) = &self.webhook_bridge {
+            if let Err(e) = bridge.forward(&event).await {
+                log_event!(
+                    warn,
+                    Subsystem::Webhooks,
+                    "contract_webhook.forward_failed",
+                    event_id = %event.id,
+                    error = %e,
+                    "Failed to forward contract event to webhooks"
+                );
+            }
+        }
+
         // Check if this is a snapshot submission event
         if event.topic.contains(&"SNAP_SUB".to_string()) {
             self.process_snapshot_event(event).await?;
@@ -576,7 +601,8 @@ impl ContractEventListener {
                 .and_then(|s| s.parse().ok()),
         };
 
-        Self::new(config, db, alert_service)
+        let bridge = Arc::new(ContractWebhookBridge::new(Arc::clone(&db)));
+        Ok(Self::new(config, db, alert_service)?.with_webhook_bridge(bridge))
     }
 }
 
@@ -0,0 +1,240 @@
+//! Contract Event Webhook Bridge
+//!
+//! Turns decoded on-chain contract events into webhook deliveries as the
+//! contract listener sees them, so subscribers can react to snapshot submissions
+//! without running their own indexer. Only topics of contracts in this
+//! repository are bridged by default; `with_topic` maps others.
+
+use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::services::contract_listener::ContractEvent;
+use crate::services::webhook_event_service::WebhookEventService;
+use crate::webhooks::events::ContractActivityEvent;
+use crate::webhooks::WebhookEventType;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Topic published by the snapshot contract on `submit_snapshot`.
+pub const TOPIC_SNAPSHOT_SUBMITTED: &str = "SNAP_SUB";
+
+pub struct ContractWebhookBridge {
+    db: Arc<Database>,
+    webhooks: WebhookEventService,
+    topics: HashMap<String, WebhookEventType>,
+}
+
+impl ContractWebhookBridge {
+    #[must_use]
+    pub fn new(db: Arc<Database>) -> Self {
+        let webhooks = WebhookEventService::new(db.pool().clone());
+        let topics = HashMap::from([(
+            TOPIC_SNAPSHOT_SUBMITTED.to_string(),
+            WebhookEventType::ContractSnapshotSubmitted,
+        )]);
+
+        Self {
+            db,
+            webhooks,
+            topics,
+        }
+    }
+
+    /// Map an additional (or renamed) contract topic to a webhook event type.
+    #[must_use]
+    pub fn with_topic(mut self, topic: &str, event_type: WebhookEventType) -> Self {
+        self.topics.insert(topic.to_string(), event_type);
+        self
+    }
+
+    /// Webhook event type for a contract event, if any of its topics is bridged.
+    #[must_use]
+    pub fn classify(&self, event: &ContractEvent) -> Option<WebhookEventType> {
+        event
+            .topic
+            .iter()
+            .find_map(|topic| self.topics.get(topic).cloned())
+    }
+
+    /// Queue webhook deliveries for a contract event.
+    ///
+    /// Returns `false` when the event is not bridged, came from a failed call, or was
+    /// already forwarded (the listener may replay ledgers after a restart).
+    pub async fn forward(&self, event: &ContractEvent) -> Result<bool> {
+        if !event.in_successful_contract_call {
+            return Ok(false);
+        }
+        let Some(event_type) = self.classify(event) else {
+            return Ok(false);
+        };
+        let ledger = event
+            .ledger
+            .parse::<u64>()
+            .context("Invalid ledger number")?;
+
+        let claimed = sqlx::query(
+            r"
+            INSERT OR IGNORE INTO contract_webhook_forwards
+                (event_id, contract_id, event_type, ledger, forwarded_at)
+            VALUES (?, ?, ?, ?, ?)
+            ",
+        )
+        .bind(&event.id)
+        .bind(&event.contract_id)
+        .bind(event_type.as_str())
+        .bind(i64::try_from(ledger).unwrap_or(i64::MAX))
+        .bind(Utc::now().to_rfc3339())
+        .execute(self.db.pool())
+        .await
+        .context("Failed to record contract webhook forward")?
+        .rows_affected()
+            > 0;
+        if !claimed {
+            log_event!(
+                debug,
+                Subsystem::Webhooks,
+                "contract_event.already_forwarded",
+                event_id = %event.id,
+                "Contract event already forwarded to webhooks"
+            );
+            return Ok(false);
+        }
+
+        let activity = ContractActivityEvent {
+            contract_id: event.contract_id.clone(),
+            event_id: event.id.clone(),
+            ledger,
+            ledger_closed_at: event.ledger_closed_at.clone(),
+            topic: event.topic.clone(),
+            data: event.value.clone(),
+        };
+
+        if let Err(e) = self
+            .webhooks
+            .trigger_contract_event(event_type.clone(), &activity)
+            .await
+        {
+            // Release the claim so the event is retried when it is seen again
+            sqlx::query("DELETE FROM contract_webhook_forwards WHERE event_id = ?")
+                .bind(&event.id)
+                .execute(self.db.pool())
+                .await?;
+            return Err(e);
+        }
+
+        log_event!(
+            info,
+            Subsystem::Webhooks,
+            "contract_event.forwarded",
+            event_id = %event.id,
+            event_type = event_type.as_str(),
+            "Forwarded contract event to webhooks"
+        );
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations};
+    use serde_json::json;
+
+    async fn setup_bridge() -> (ContractWebhookBridge, sqlx::SqlitePool) {
+        let pool = test_support::sqlite_pool(&[
+            migrations::USERS,
+            migrations::OAUTH_WEBHOOKS,
+            migrations::CONTRACT_WEBHOOK_FORWARDS,
+        ])
+        .await;
+        sqlx::query("INSERT INTO users (id, username) VALUES ('user-1', 'ops')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            r"
+            INSERT INTO webhooks (id, user_id, url, event_types, filters, secret, is_active, created_at)
+            VALUES ('wh-1', 'user-1', 'https://example.com/hook', 'contract.snapshot_submitted', NULL, 's', 1, '2026-01-01T00:00:00Z')
+            ",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        (
+            ContractWebhookBridge::new(Arc::new(Database::new(pool.clone()))),
+            pool,
+        )
+    }
+
+    fn contract_event(id: &str, topic: &str) -> ContractEvent {
+        ContractEvent {
+            id: id.to_string(),
+            paging_token: id.to_string(),
+            ledger: "51234".to_string(),
+            ledger_closed_at: "2026-01-15T12:30:00Z".to_string(),
+            contract_id: "CCONTRACT".to_string(),
+            topic: vec![topic.to_string()],
+            value: json!({ "epoch": 42, "hash": "ab".repeat(32), "timestamp": 1_768_480_200 }),
+            in_successful_contract_call: true,
+        }
+    }
+
+    async fn queued_events(pool: &sqlx::SqlitePool) -> Vec<(String, String)> {
+        sqlx::query_as("SELECT event_type, payload FROM webhook_events ORDER BY created_at")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_forwards_subscribed_event_once() {
+        let (bridge, pool) = setup_bridge().await;
+        let event = contract_event("0000219-0001", TOPIC_SNAPSHOT_SUBMITTED);
+
+        assert!(bridge.forward(&event).await.unwrap());
+        assert!(!bridge.forward(&event).await.unwrap());
+
+        let queued = queued_events(&pool).await;
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].0, "contract.snapshot_submitted");
+        let payload: serde_json::Value = serde_json::from_str(&queued[0].1).unwrap();
+        assert_eq!(payload["event_id"], "0000219-0001");
+        assert_eq!(payload["ledger"], 51234);
+        assert_eq!(payload["data"]["epoch"], 42);
+    }
+
+    #[tokio::test]
+    async fn test_skips_unbridged_and_failed_events() {
+        let (bridge, pool) = setup_bridge().await;
+
+        assert!(!bridge
+            .forward(&contract_event("0000219-0002", "STOPPED"))
+            .await
+            .unwrap());
+
+        let mut failed = contract_event("0000219-0003", TOPIC_SNAPSHOT_SUBMITTED);
+        failed.in_successful_contract_call = false;
+        assert!(!bridge.forward(&failed).await.unwrap());
+
+        assert!(queued_events(&pool).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_custom_topic_mapping() {
+        let (bridge, pool) = setup_bridge().await;
+        // A redeployed snapshot contract publishing under a new topic
+        let bridge = bridge.with_topic("snapshot", WebhookEventType::ContractSnapshotSubmitted);
+
+        assert!(bridge
+            .forward(&contract_event("0000219-0004", "snapshot"))
+            .await
+            .unwrap());
+        assert_eq!(
+            queued_events(&pool).await[0].0,
+            "contract.snapshot_submitted"
+        );
+    }
+}
@@ -0,0 +1,808 @@
+use crate::alerts::{AlertManager, AlertType};
+use crate::cluster::{LeaderElection, ROLE_INGESTION};
+use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+const DEFAULT_EVALUATION_WINDOW_HOURS: i64 = 24;
+const DEFAULT_EVAL_INTERVAL_SECS: u64 = 300;
+const DEFAULT_COMPLIANCE_MONTHS: i64 = 6;
+const RECENT_BREACH_LIMIT: i64 = 20;
+
+pub const METRIC_SUCCESS_RATE: &str = "success_rate";
+pub const METRIC_P95_SETTLEMENT: &str = "p95_settlement_ms";
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CorridorSla {
+    pub corridor_key: String,
+    /// Minimum acceptable success rate, in percent.
+    pub min_success_rate: f64,
+    /// Maximum acceptable p95 settlement time, in milliseconds.
+    pub max_p95_settlement_ms: i64,
+    /// Trailing window each evaluation looks at.
+    pub evaluation_window_hours: i64,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpsertCorridorSlaRequest {
+    #[schema(example = 98.5)]
+    pub min_success_rate: f64,
+    #[schema(example = 5000)]
+    pub max_p95_settlement_ms: i64,
+    /// Defaults to 24 hours.
+    pub evaluation_window_hours: Option<i64>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+const fn default_enabled() -> bool {
+    true
+}
+
+/// Corridor performance observed over an SLA evaluation window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlaObservation {
+    pub total_transactions: i64,
+    pub success_rate: f64,
+    pub p95_settlement_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SlaEvaluation {
+    pub corridor_key: String,
+    pub evaluated_at: DateTime<Utc>,
+    pub window_start: DateTime<Utc>,
+    pub total_transactions: i64,
+    pub success_rate: f64,
+    pub p95_settlement_ms: Option<f64>,
+    pub success_rate_met: bool,
+    pub settlement_time_met: bool,
+    pub compliant: bool,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SlaBreach {
+    pub id: i64,
+    pub corridor_key: String,
+    /// `success_rate` or `p95_settlement_ms`
+    pub metric: String,
+    pub threshold: f64,
+    /// Value observed when the breach was opened.
+    pub observed: f64,
+    pub started_at: DateTime<Utc>,
+    /// `None` while the breach is ongoing.
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MonthlyCompliance {
+    /// Calendar month, `YYYY-MM`.
+    pub month: String,
+    pub evaluations: i64,
+    pub compliant_evaluations: i64,
+    pub compliance_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CorridorSlaStatus {
+    pub sla: CorridorSla,
+    pub latest_evaluation: Option<SlaEvaluation>,
+    pub open_breaches: Vec<SlaBreach>,
+    pub recent_breaches: Vec<SlaBreach>,
+    /// Most recent month first.
+    pub monthly_compliance: Vec<MonthlyCompliance>,
+}
+
+/// Stores operator-defined corridor SLAs, evaluates them against the hourly corridor
+/// aggregates and keeps a history of evaluations and breaches.
+pub struct CorridorSlaService {
+    db: Arc<Database>,
+    alert_manager: Option<Arc<AlertManager>>,
+    leader: Option<Arc<LeaderElection>>,
+}
+
+impl CorridorSlaService {
+    #[must_use]
+    pub const fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            alert_manager: None,
+            leader: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_alerts(mut self, alert_manager: Arc<AlertManager>) -> Self {
+        self.alert_manager = Some(alert_manager);
+        self
+    }
+
+    /// Only run scheduled evaluations on the replica holding the ingestion
+    /// lease, so each breach is opened and alerted once.
+    #[must_use]
+    pub fn with_leader_election(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    pub async fn upsert_sla(
+        &self,
+        corridor_key: &str,
+        request: UpsertCorridorSlaRequest,
+    ) -> Result<CorridorSla> {
+        validate_request(&request)?;
+        let now = Utc::now().to_rfc3339();
+        let window_hours = request
+            .evaluation_window_hours
+            .unwrap_or(DEFAULT_EVALUATION_WINDOW_HOURS);
+
+        sqlx::query(
+            r"
+            INSERT INTO corridor_slas (
+                corridor_key, min_success_rate, max_p95_settlement_ms,
+                evaluation_window_hours, enabled, created_at, updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (corridor_key) DO UPDATE SET
+                min_success_rate = excluded.min_success_rate,
+                max_p95_settlement_ms = excluded.max_p95_settlement_ms,
+                evaluation_window_hours = excluded.evaluation_window_hours,
+                enabled = excluded.enabled,
+                updated_at = excluded.updated_at
+            ",
+        )
+        .bind(corridor_key)
+        .bind(request.min_success_rate)
+        .bind(request.max_p95_settlement_ms)
+        .bind(window_hours)
+        .bind(request.enabled)
+        .bind(&now)
+        .bind(&now)
+        .execute(self.db.pool())
+        .await
+        .with_context(|| format!("Failed to store SLA for corridor {corridor_key}"))?;
+
+        self.get_sla(corridor_key)
+            .await?
+            .ok_or_else(|| anyhow!("SLA for corridor {corridor_key} disappeared after upsert"))
+    }
+
+    /// Remove the SLA and resolve any breaches still open against it.
+    pub async fn delete_sla(&self, corridor_key: &str) -> Result<bool> {
+        let deleted = sqlx::query("DELETE FROM corridor_slas WHERE corridor_key = ?")
+            .bind(corridor_key)
+            .execute(self.db.pool())
+            .await
+            .with_context(|| format!("Failed to delete SLA for corridor {corridor_key}"))?
+            .rows_affected();
+
+        sqlx::query(
+            "UPDATE corridor_sla_breaches SET resolved_at = ? WHERE corridor_key = ? AND resolved_at IS NULL",
+        )
+        .bind(Utc::now().to_rfc3339())
+        .bind(corridor_key)
+        .execute(self.db.pool())
+        .await
+        .context("Failed to resolve open SLA breaches")?;
+
+        Ok(deleted > 0)
+    }
+
+    pub async fn get_sla(&self, corridor_key: &str) -> Result<Option<CorridorSla>> {
+        let row = sqlx::query("SELECT * FROM corridor_slas WHERE corridor_key = ?")
+            .bind(corridor_key)
+            .fetch_optional(self.db.pool())
+            .await
+            .with_context(|| format!("Failed to load SLA for corridor {corridor_key}"))?;
+
+        row.map(|row| sla_from_row(&row)).transpose()
+    }
+
+    pub async fn list_enabled_slas(&self) -> Result<Vec<CorridorSla>> {
+        let rows = sqlx::query("SELECT * FROM corridor_slas WHERE enabled = 1")
+            .fetch_all(self.db.pool())
+            .await
+            .context("Failed to list corridor SLAs")?;
+
+        rows.iter().map(sla_from_row).collect()
+    }
+
+    /// Aggregate hourly corridor metrics in `[start, end)`.
+    ///
+    /// Settlement latency is only available as an hourly average, so the p95 is taken
+    /// over the hourly averages in the window.
+    pub async fn observe(
+        &self,
+        corridor_key: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<SlaObservation> {
+        let rows = sqlx::query(
+            r"
+            SELECT total_transactions, successful_transactions, avg_settlement_latency_ms
+            FROM corridor_metrics_hourly
+            WHERE corridor_key = ? AND hour_bucket >= ? AND hour_bucket < ?
+            ",
+        )
+        .bind(corridor_key)
+        .bind(start.to_rfc3339())
+        .bind(end.to_rfc3339())
+        .fetch_all(self.db.pool())
+        .await
+        .with_context(|| format!("Failed to load hourly metrics for corridor {corridor_key}"))?;
+
+        let mut total = 0_i64;
+        let mut successful = 0_i64;
+        let mut latencies = Vec::with_capacity(rows.len());
+        for row in &rows {
+            total += row.get::<Option<i64>, _>("total_transactions").unwrap_or(0);
+            successful += row
+                .get::<Option<i64>, _>("successful_transactions")
+                .unwrap_or(0);
+            if let Some(latency) = row.get::<Option<i64>, _>("avg_settlement_latency_ms") {
+                latencies.push(latency as f64);
+            }
+        }
+
+        let success_rate = if total > 0 {
+            successful as f64 / total as f64 * 100.0
+        } else {
+            0.0
+        };
+
+        Ok(SlaObservation {
+            total_transactions: total,
+            success_rate,
+            p95_settlement_ms: percentile(&mut latencies, 95),
+        })
+    }
+
+    /// Evaluate a single SLA, record the result and open/resolve breaches.
+    ///
+    /// Returns `None` when the corridor had no traffic in the window; an idle corridor
+    /// neither meets nor breaches its SLA.
+    pub async fn evaluate(
+        &self,
+        sla: &CorridorSla,
+        now: DateTime<Utc>,
+    ) -> Result<Option<SlaEvaluation>> {
+        let window_start = now - Duration::hours(sla.evaluation_window_hours);
+        let observation = self.observe(&sla.corridor_key, window_start, now).await?;
+        if observation.total_transactions == 0 {
+            return Ok(None);
+        }
+
+        let evaluation = evaluate_observation(sla, &observation, window_start, now);
+        self.record_evaluation(&evaluation).await?;
+
+        self.track_breach(
+            sla,
+            METRIC_SUCCESS_RATE,
+            evaluation.success_rate_met,
+            sla.min_success_rate,
+            evaluation.success_rate,
+            now,
+        )
+        .await?;
+        self.track_breach(
+            sla,
+            METRIC_P95_SETTLEMENT,
+            evaluation.settlement_time_met,
+            sla.max_p95_settlement_ms as f64,
+            evaluation.p95_settlement_ms.unwrap_or_default(),
+            now,
+        )
+        .await?;
+
+        Ok(Some(evaluation))
+    }
+
+    /// Evaluate every enabled SLA. Failures for one corridor do not stop the others.
+    pub async fn evaluate_all(&self) -> Result<Vec<SlaEvaluation>> {
+        let now = Utc::now();
+        let mut evaluations = Vec::new();
+        for sla in self.list_enabled_slas().await? {
+            match self.evaluate(&sla, now).await {
+                Ok(Some(evaluation)) => evaluations.push(evaluation),
+                Ok(None) => {}
+                Err(e) => log_event!(
+                    warn,
+                    Subsystem::Alerts,
+                    "sla.evaluation_failed",
+                    corridor_key = %sla.corridor_key,
+                    error = %e,
+                    "Failed to evaluate corridor SLA"
+                ),
+            }
+        }
+        Ok(evaluations)
+    }
+
+    /// Re-evaluate all SLAs on an interval (`CORRIDOR_SLA_EVAL_INTERVAL_SECS`, default 5 min).
+    pub async fn start(self: Arc<Self>) {
+        let secs = std::env::var("CORRIDOR_SLA_EVAL_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_EVAL_INTERVAL_SECS);
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(secs));
+        log_event!(
+            info,
+            Subsystem::Alerts,
+            "sla.evaluator_started",
+            interval_secs = secs,
+            "Corridor SLA evaluator started"
+        );
+
+        loop {
+            interval.tick().await;
+            if let Some(leader) = &self.leader {
+                if !leader.is_leader(ROLE_INGESTION) {
+                    continue;
+                }
+            }
+            match self.evaluate_all().await {
+                Ok(evaluations) => {
+                    let breaching = evaluations.iter().filter(|e| !e.compliant).count();
+                    log_event!(
+                        debug,
+                        Subsystem::Alerts,
+                        "sla.evaluation_completed",
+                        evaluated = evaluations.len(),
+                        breaching,
+                        "Corridor SLA evaluation complete"
+                    );
+                }
+                Err(e) => log_event!(
+                    error,
+                    Subsystem::Alerts,
+                    "sla.evaluation_cycle_failed",
+                    error = %e,
+                    "Corridor SLA evaluation failed"
+                ),
+            }
+        }
+    }
+
+    pub async fn latest_evaluation(&self, corridor_key: &str) -> Result<Option<SlaEvaluation>> {
+        let row = sqlx::query(
+            r"
+            SELECT * FROM corridor_sla_evaluations
+            WHERE corridor_key = ?
+            ORDER BY evaluated_at DESC, id DESC
+            LIMIT 1
+            ",
+        )
+        .bind(corridor_key)
+        .fetch_optional(self.db.pool())
+        .await
+        .context("Failed to load latest SLA evaluation")?;
+
+        row.map(|row| evaluation_from_row(&row)).transpose()
+    }
+
+    pub async fn breaches(
+        &self,
+        corridor_key: &str,
+        open_only: bool,
+        limit: i64,
+    ) -> Result<Vec<SlaBreach>> {
+        let rows = sqlx::query(
+            r"
+            SELECT * FROM corridor_sla_breaches
+            WHERE corridor_key = ? AND (? = 0 OR resolved_at IS NULL)
+            ORDER BY started_at DESC, id DESC
+            LIMIT ?
+            ",
+        )
+        .bind(corridor_key)
+        .bind(open_only)
+        .bind(limit)
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to load SLA breaches")?;
+
+        rows.iter().map(breach_from_row).collect()
+    }
+
+    /// Share of compliant evaluations per calendar month (UTC), newest first.
+    pub async fn monthly_compliance(
+        &self,
+        corridor_key: &str,
+        months: i64,
+    ) -> Result<Vec<MonthlyCompliance>> {
+        let rows = sqlx::query(
+            r"
+            SELECT substr(evaluated_at, 1, 7) AS month,
+                   COUNT(*) AS evaluations,
+                   COALESCE(SUM(compliant), 0) AS compliant_evaluations
+            FROM corridor_sla_evaluations
+            WHERE corridor_key = ?
+            GROUP BY month
+            ORDER BY month DESC
+            LIMIT ?
+            ",
+        )
+        .bind(corridor_key)
+        .bind(months)
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to compute monthly SLA compliance")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let evaluations: i64 = row.get("evaluations");
+                let compliant_evaluations: i64 = row.get("compliant_evaluations");
+                MonthlyCompliance {
+                    month: row.get("month"),
+                    evaluations,
+                    compliant_evaluations,
+                    compliance_pct: compliance_pct(compliant_evaluations, evaluations),
+                }
+            })
+            .collect())
+    }
+
+    /// Everything the `/api/corridors/:id/sla` endpoint reports, or `None` if no SLA
+    /// has been defined for the corridor.
+    pub async fn status(&self, corridor_key: &str) -> Result<Option<CorridorSlaStatus>> {
+        let Some(sla) = self.get_sla(corridor_key).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(CorridorSlaStatus {
+            latest_evaluation: self.latest_evaluation(corridor_key).await?,
+            open_breaches: self
+                .breaches(corridor_key, true, RECENT_BREACH_LIMIT)
+                .await?,
+            recent_breaches: self
+                .breaches(corridor_key, false, RECENT_BREACH_LIMIT)
+                .await?,
+            monthly_compliance: self
+                .monthly_compliance(corridor_key, DEFAULT_COMPLIANCE_MONTHS)
+                .await?,
+            sla,
+        }))
+    }
+
+    async fn record_evaluation(&self, evaluation: &SlaEvaluation) -> Result<()> {
+        sqlx::query(
+            r"
+            INSERT INTO corridor_sla_evaluations (
+                corridor_key, evaluated_at, window_start, total_transactions, success_rate,
+                p95_settlement_ms, success_rate_met, settlement_time_met, compliant
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ",
+        )
+        .bind(&evaluation.corridor_key)
+        .bind(evaluation.evaluated_at.to_rfc3339())
+        .bind(evaluation.window_start.to_rfc3339())
+        .bind(evaluation.total_transactions)
+        .bind(evaluation.success_rate)
+        .bind(evaluation.p95_settlement_ms)
+        .bind(evaluation.success_rate_met)
+        .bind(evaluation.settlement_time_met)
+        .bind(evaluation.compliant)
+        .execute(self.db.pool())
+        .await
+        .context("Failed to record SLA evaluation")?;
+        Ok(())
+    }
+
+    /// Open a breach when a metric starts failing and resolve it once it recovers.
+    /// Alerts fire only when a breach is opened, not on every failing evaluation.
+    async fn track_breach(
+        &self,
+        sla: &CorridorSla,
+        metric: &str,
+        met: bool,
+        threshold: f64,
+        observed: f64,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let open_id: Option<i64> = sqlx::query_scalar(
+            r"
+            SELECT id FROM corridor_sla_breaches
+            WHERE corridor_key = ? AND metric = ? AND resolved_at IS NULL
+            LIMIT 1
+            ",
+        )
+        .bind(&sla.corridor_key)
+        .bind(metric)
+        .fetch_optional(self.db.pool())
+        .await
+        .context("Failed to look up open SLA breach")?;
+
+        match (met, open_id) {
+            (true, Some(id)) => {
+                sqlx::query("UPDATE corridor_sla_breaches SET resolved_at = ? WHERE id = ?")
+                    .bind(now.to_rfc3339())
+                    .bind(id)
+                    .execute(self.db.pool())
+                    .await
+                    .context("Failed to resolve SLA breach")?;
+                log_event!(
+                    info,
+                    Subsystem::Alerts,
+                    "sla.breach_resolved",
+                    corridor_key = %sla.corridor_key,
+                    metric,
+                    "Corridor SLA breach resolved"
+                );
+            }
+            (false, None) => {
+                sqlx::query(
+                    r"
+                    INSERT INTO corridor_sla_breaches (corridor_key, metric, threshold, observed, started_at)
+                    VALUES (?, ?, ?, ?, ?)
+                    ",
+                )
+                .bind(&sla.corridor_key)
+                .bind(metric)
+                .bind(threshold)
+                .bind(observed)
+                .bind(now.to_rfc3339())
+                .execute(self.db.pool())
+                .await
+                .context("Failed to record SLA breach")?;
+
+                log_event!(
+                    warn,
+                    Subsystem::Alerts,
+                    "sla.breach_opened",
+                    corridor_key = %sla.corridor_key,
+                    metric,
+                    threshold,
+                    observed,
+                    "Corridor SLA breached"
+                );
+                if let Some(alert_manager) = &self.alert_manager {
+                    alert_manager.send_corridor_alert(
+                        AlertType::SlaBreach,
+                        &sla.corridor_key,
+                        breach_message(metric, threshold, observed),
+                        threshold,
+                        observed,
+                    );
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Compare an observation against the SLA thresholds.
+#[must_use]
+pub fn evaluate_observation(
+    sla: &CorridorSla,
+    observation: &SlaObservation,
+    window_start: DateTime<Utc>,
+    evaluated_at: DateTime<Utc>,
+) -> SlaEvaluation {
+    let success_rate_met = observation.success_rate >= sla.min_success_rate;
+    // With no latency samples there is nothing to hold against the settlement target.
+    let settlement_time_met = observation
+        .p95_settlement_ms
+        .is_none_or(|p95| p95 <= sla.max_p95_settlement_ms as f64);
+
+    SlaEvaluation {
+        corridor_key: sla.corridor_key.clone(),
+        evaluated_at,
+        window_start,
+        total_transactions: observation.total_transactions,
+        success_rate: observation.success_rate,
+        p95_settlement_ms: observation.p95_settlement_ms,
+        success_rate_met,
+        settlement_time_met,
+        compliant: success_rate_met && settlement_time_met,
+    }
+}
+
+/// Nearest-rank percentile; `pct` in `1..=100`.
+fn percentile(values: &mut [f64], pct: usize) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let rank = (pct * values.len()).div_ceil(100);
+    values.get(rank.clamp(1, values.len()) - 1).copied()
+}
+
+fn compliance_pct(compliant: i64, total: i64) -> f64 {
+    if total == 0 {
+        return 100.0;
+    }
+    compliant as f64 / total as f64 * 100.0
+}
+
+fn breach_message(metric: &str, threshold: f64, observed: f64) -> String {
+    if metric == METRIC_SUCCESS_RATE {
+        format!("SLA breach: success rate {observed:.2}% is below the {threshold:.2}% target")
+    } else {
+        format!(
+            "SLA breach: p95 settlement time {observed:.0}ms exceeds the {threshold:.0}ms target"
+        )
+    }
+}
+
+fn validate_request(request: &UpsertCorridorSlaRequest) -> Result<()> {
+    if !(0.0..=100.0).contains(&request.min_success_rate) {
+        return Err(anyhow!("min_success_rate must be between 0 and 100"));
+    }
+    if request.max_p95_settlement_ms <= 0 {
+        return Err(anyhow!("max_p95_settlement_ms must be positive"));
+    }
+    if let Some(hours) = request.evaluation_window_hours {
+        if !(1..=24 * 31).contains(&hours) {
+            return Err(anyhow!("evaluation_window_hours must be between 1 and 744"));
+        }
+    }
+    Ok(())
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .with_context(|| format!("Invalid timestamp in SLA tables: {value}"))
+}
+
+fn sla_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<CorridorSla> {
+    Ok(CorridorSla {
+        corridor_key: row.get("corridor_key"),
+        min_success_rate: row.get("min_success_rate"),
+        max_p95_settlement_ms: row.get("max_p95_settlement_ms"),
+        evaluation_window_hours: row.get("evaluation_window_hours"),
+        enabled: row.get("enabled"),
+        created_at: parse_timestamp(row.get("created_at"))?,
+        updated_at: parse_timestamp(row.get("updated_at"))?,
+    })
+}
+
+fn evaluation_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<SlaEvaluation> {
+    Ok(SlaEvaluation {
+        corridor_key: row.get("corridor_key"),
+        evaluated_at: parse_timestamp(row.get("evaluated_at"))?,
+        window_start: parse_timestamp(row.get("window_start"))?,
+        total_transactions: row.get("total_transactions"),
+        success_rate: row.get("success_rate"),
+        p95_settlement_ms: row.get("p95_settlement_ms"),
+        success_rate_met: row.get("success_rate_met"),
+        settlement_time_met: row.get("settlement_time_met"),
+        compliant: row.get("compliant"),
+    })
+}
+
+fn breach_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<SlaBreach> {
+    let resolved_at: Option<String> = row.get("resolved_at");
+    Ok(SlaBreach {
+        id: row.get("id"),
+        corridor_key: row.get("corridor_key"),
+        metric: row.get("metric"),
+        threshold: row.get("threshold"),
+        observed: row.get("observed"),
+        started_at: parse_timestamp(row.get("started_at"))?,
+        resolved_at: resolved_at.as_deref().map(parse_timestamp).transpose()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations, CorridorHour};
+
+    const CORRIDOR: &str = "USDC:GA5Z->EURC:GB3Q";
+
+    async fn setup() -> CorridorSlaService {
+        CorridorSlaService::new(
+            test_support::sqlite_db(&[migrations::CORRIDOR_AGGREGATES, migrations::CORRIDOR_SLAS])
+                .await,
+        )
+    }
+
+    async fn insert_hour(
+        service: &CorridorSlaService,
+        hour: DateTime<Utc>,
+        total: i64,
+        successful: i64,
+        latency_ms: i64,
+    ) {
+        test_support::insert_hour(
+            service.db.pool(),
+            &CorridorHour {
+                corridor_key: CORRIDOR,
+                assets: ["USDC", "GA5Z", "EURC", "GB3Q"],
+                hour,
+                total,
+                successful,
+                avg_settlement_latency_ms: Some(latency_ms),
+                ..CorridorHour::default()
+            },
+        )
+        .await;
+    }
+
+    fn request(min_success_rate: f64, max_p95_settlement_ms: i64) -> UpsertCorridorSlaRequest {
+        UpsertCorridorSlaRequest {
+            min_success_rate,
+            max_p95_settlement_ms,
+            evaluation_window_hours: Some(24),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        let mut values: Vec<f64> = (1..=20).map(f64::from).collect();
+        assert_eq!(percentile(&mut values, 95), Some(19.0));
+        assert_eq!(percentile(&mut [42.0], 95), Some(42.0));
+        assert_eq!(percentile(&mut [], 95), None);
+    }
+
+    #[test]
+    fn test_validate_request_bounds() {
+        assert!(validate_request(&request(101.0, 1000)).is_err());
+        assert!(validate_request(&request(99.0, 0)).is_err());
+        assert!(validate_request(&request(99.0, 1000)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_breach_opens_once_and_resolves() {
+        let service = setup().await;
+        let sla = service
+            .upsert_sla(CORRIDOR, request(95.0, 3_000))
+            .await
+            .unwrap();
+        let now = Utc::now();
+
+        // 90% success rate over the window breaches the 95% target.
+        insert_hour(&service, now - Duration::hours(2), 100, 90, 1_000).await;
+        let evaluation = service.evaluate(&sla, now).await.unwrap().unwrap();
+        assert!(!evaluation.success_rate_met);
+        assert!(evaluation.settlement_time_met);
+
+        // A second failing evaluation keeps the same breach open.
+        service.evaluate(&sla, now).await.unwrap();
+        let open = service.breaches(CORRIDOR, true, 10).await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].metric, METRIC_SUCCESS_RATE);
+
+        insert_hour(&service, now - Duration::hours(1), 1_000, 1_000, 1_000).await;
+        let evaluation = service.evaluate(&sla, now).await.unwrap().unwrap();
+        assert!(evaluation.compliant);
+        assert!(service
+            .breaches(CORRIDOR, true, 10)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let compliance = service.monthly_compliance(CORRIDOR, 6).await.unwrap();
+        assert_eq!(compliance.len(), 1);
+        assert_eq!(compliance[0].evaluations, 3);
+        assert_eq!(compliance[0].compliant_evaluations, 1);
+    }
+
+    #[tokio::test]
+    async fn test_idle_corridor_is_not_evaluated() {
+        let service = setup().await;
+        let sla = service
+            .upsert_sla(CORRIDOR, request(95.0, 3_000))
+            .await
+            .unwrap();
+
+        assert!(service.evaluate(&sla, Utc::now()).await.unwrap().is_none());
+        let status = service.status(CORRIDOR).await.unwrap().unwrap();
+        assert!(status.latest_evaluation.is_none());
+        assert!(status.monthly_compliance.is_empty());
+    }
+}
@@ -0,0 +1,359 @@
+use crate::database::Database;
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+/// Upper bound for a platform fee expressed in basis points (100%).
+pub const MAX_FEE_BPS: u32 = 10_000;
+
+/// Default look-back window when the request does not specify a period.
+const DEFAULT_PERIOD_DAYS: i64 = 30;
+
+/// Revenue is not read from fees the contract collected: it is historical corridor
+/// volume multiplied by the fee rate, so every figure is a proxy estimate.
+pub const ESTIMATE_BASIS: &str =
+    "proxy estimate: hourly corridor volume (USD) x fee rate, not fees collected on-chain";
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FeeSimulationRequest {
+    /// Fee currently configured on-chain, in basis points.
+    #[schema(example = 250)]
+    pub current_fee_bps: u32,
+    /// Fee the admin intends to set via `update_fee`, in basis points.
+    #[schema(example = 300)]
+    pub proposed_fee_bps: u32,
+    /// Start of the historical window (defaults to 30 days before `end`).
+    pub start: Option<DateTime<Utc>>,
+    /// End of the historical window (defaults to now).
+    pub end: Option<DateTime<Utc>>,
+    /// Restrict the simulation to a subset of corridors.
+    pub corridor_keys: Option<Vec<String>>,
+    /// Price elasticity of volume: fraction of volume lost per 1% relative fee increase.
+    /// `0.0` (the default) assumes volume does not react to the fee change.
+    #[serde(default)]
+    pub volume_elasticity: f64,
+}
+
+/// Historical volume observed for a single corridor in the simulation window.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CorridorVolume {
+    pub corridor_key: String,
+    pub transaction_count: i64,
+    pub volume_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CorridorFeeImpact {
+    pub corridor_key: String,
+    pub transaction_count: i64,
+    pub volume_usd: f64,
+    pub projected_volume_usd: f64,
+    pub current_revenue_usd: f64,
+    pub projected_revenue_usd: f64,
+    pub revenue_delta_usd: f64,
+    pub avg_fee_per_tx_current_usd: f64,
+    pub avg_fee_per_tx_projected_usd: f64,
+}
+
+/// Projected impact of a fee change. Revenue figures are a proxy estimate derived
+/// from corridor volume, not fees the contract actually collected.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FeeSimulationResult {
+    /// How the revenue figures were derived, see [`ESTIMATE_BASIS`]
+    #[schema(
+        example = "proxy estimate: hourly corridor volume (USD) x fee rate, not fees collected on-chain"
+    )]
+    pub estimate_basis: &'static str,
+    pub current_fee_bps: u32,
+    pub proposed_fee_bps: u32,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub volume_elasticity: f64,
+    pub total_transactions: i64,
+    pub total_volume_usd: f64,
+    pub projected_volume_usd: f64,
+    pub current_revenue_usd: f64,
+    pub projected_revenue_usd: f64,
+    pub revenue_delta_usd: f64,
+    pub revenue_delta_pct: Option<f64>,
+    /// Additional amount users would have paid (negative when the fee drops).
+    pub user_cost_delta_usd: f64,
+    pub avg_user_cost_delta_per_tx_usd: f64,
+    pub corridors: Vec<CorridorFeeImpact>,
+}
+
+pub struct FeeSimulationService {
+    db: Arc<Database>,
+}
+
+impl FeeSimulationService {
+    #[must_use]
+    pub const fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Replays historical corridor volumes under the proposed fee and reports the
+    /// estimated revenue and user-cost impact against the current fee.
+    pub async fn simulate(&self, request: FeeSimulationRequest) -> Result<FeeSimulationResult> {
+        validate_request(&request)?;
+
+        let end = request.end.unwrap_or_else(Utc::now);
+        let start = request
+            .start
+            .unwrap_or_else(|| end - Duration::days(DEFAULT_PERIOD_DAYS));
+        if start >= end {
+            return Err(anyhow!("start must be before end"));
+        }
+
+        let volumes = self
+            .historical_volumes(start, end, request.corridor_keys.as_deref())
+            .await?;
+
+        Ok(simulate_fee_change(
+            &volumes,
+            request.current_fee_bps,
+            request.proposed_fee_bps,
+            request.volume_elasticity,
+            start,
+            end,
+        ))
+    }
+
+    async fn historical_volumes(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        corridor_keys: Option<&[String]>,
+    ) -> Result<Vec<CorridorVolume>> {
+        let corridor_filter = match corridor_keys {
+            Some([]) => return Ok(Vec::new()),
+            Some(keys) => format!("AND corridor_key IN ({})", vec!["?"; keys.len()].join(", ")),
+            None => String::new(),
+        };
+        let query = format!(
+            r"
+            SELECT corridor_key,
+                   COALESCE(SUM(successful_transactions), 0) AS transaction_count,
+                   COALESCE(SUM(volume_usd), 0.0) AS volume_usd
+            FROM corridor_metrics_hourly
+            WHERE hour_bucket >= ? AND hour_bucket < ? {corridor_filter}
+            GROUP BY corridor_key
+            ORDER BY volume_usd DESC
+            "
+        );
+        let mut rows = sqlx::query(&query)
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339());
+        for key in corridor_keys.unwrap_or_default() {
+            rows = rows.bind(key);
+        }
+        let rows = rows
+            .fetch_all(self.db.pool())
+            .await
+            .context("Failed to load historical corridor volumes")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| CorridorVolume {
+                corridor_key: row.get("corridor_key"),
+                transaction_count: row.get("transaction_count"),
+                volume_usd: row.get("volume_usd"),
+            })
+            .collect())
+    }
+}
+
+fn validate_request(request: &FeeSimulationRequest) -> Result<()> {
+    if request.current_fee_bps > MAX_FEE_BPS || request.proposed_fee_bps > MAX_FEE_BPS {
+        return Err(anyhow!("fee bps must be between 0 and {MAX_FEE_BPS}"));
+    }
+    if !request.volume_elasticity.is_finite() || request.volume_elasticity < 0.0 {
+        return Err(anyhow!("volume_elasticity must be a non-negative number"));
+    }
+    Ok(())
+}
+
+/// Pure projection of a fee change over a set of historical corridor volumes.
+#[must_use]
+pub fn simulate_fee_change(
+    volumes: &[CorridorVolume],
+    current_fee_bps: u32,
+    proposed_fee_bps: u32,
+    volume_elasticity: f64,
+    period_start: DateTime<Utc>,
+    period_end: DateTime<Utc>,
+) -> FeeSimulationResult {
+    let current_rate = f64::from(current_fee_bps) / 10_000.0;
+    let proposed_rate = f64::from(proposed_fee_bps) / 10_000.0;
+    let volume_factor =
+        projected_volume_factor(current_fee_bps, proposed_fee_bps, volume_elasticity);
+
+    let corridors: Vec<CorridorFeeImpact> = volumes
+        .iter()
+        .map(|volume| {
+            let projected_volume_usd = volume.volume_usd * volume_factor;
+            let current_revenue_usd = volume.volume_usd * current_rate;
+            let projected_revenue_usd = projected_volume_usd * proposed_rate;
+            let tx_count = volume.transaction_count.max(1) as f64;
+            CorridorFeeImpact {
+                corridor_key: volume.corridor_key.clone(),
+                transaction_count: volume.transaction_count,
+                volume_usd: volume.volume_usd,
+                projected_volume_usd,
+                current_revenue_usd,
+                projected_revenue_usd,
+                revenue_delta_usd: projected_revenue_usd - current_revenue_usd,
+                avg_fee_per_tx_current_usd: volume.volume_usd * current_rate / tx_count,
+                avg_fee_per_tx_projected_usd: volume.volume_usd * proposed_rate / tx_count,
+            }
+        })
+        .collect();
+
+    let total_transactions: i64 = corridors.iter().map(|c| c.transaction_count).sum();
+    let total_volume_usd: f64 = corridors.iter().map(|c| c.volume_usd).sum();
+    let projected_volume_usd: f64 = corridors.iter().map(|c| c.projected_volume_usd).sum();
+    let current_revenue_usd: f64 = corridors.iter().map(|c| c.current_revenue_usd).sum();
+    let projected_revenue_usd: f64 = corridors.iter().map(|c| c.projected_revenue_usd).sum();
+    let revenue_delta_usd = projected_revenue_usd - current_revenue_usd;
+
+    // User cost is measured on the same historical volume so the delta reflects
+    // what the existing user base would have paid under the proposed fee.
+    let user_cost_delta_usd = total_volume_usd * (proposed_rate - current_rate);
+    let avg_user_cost_delta_per_tx_usd = if total_transactions > 0 {
+        user_cost_delta_usd / total_transactions as f64
+    } else {
+        0.0
+    };
+
+    FeeSimulationResult {
+        estimate_basis: ESTIMATE_BASIS,
+        current_fee_bps,
+        proposed_fee_bps,
+        period_start,
+        period_end,
+        volume_elasticity,
+        total_transactions,
+        total_volume_usd,
+        projected_volume_usd,
+        current_revenue_usd,
+        projected_revenue_usd,
+        revenue_delta_usd,
+        revenue_delta_pct: (current_revenue_usd > 0.0)
+            .then(|| revenue_delta_usd / current_revenue_usd * 100.0),
+        user_cost_delta_usd,
+        avg_user_cost_delta_per_tx_usd,
+        corridors,
+    }
+}
+
+/// Multiplier applied to historical volume given the relative fee change and elasticity.
+fn projected_volume_factor(current_fee_bps: u32, proposed_fee_bps: u32, elasticity: f64) -> f64 {
+    if current_fee_bps == 0 || elasticity == 0.0 {
+        return 1.0;
+    }
+    let relative_change_pct = (f64::from(proposed_fee_bps) - f64::from(current_fee_bps))
+        / f64::from(current_fee_bps)
+        * 100.0;
+    (1.0 - elasticity * relative_change_pct / 100.0).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations, CorridorHour};
+
+    fn volumes() -> Vec<CorridorVolume> {
+        vec![
+            CorridorVolume {
+                corridor_key: "USDC:GA-NGN:GB".to_string(),
+                transaction_count: 100,
+                volume_usd: 100_000.0,
+            },
+            CorridorVolume {
+                corridor_key: "USDC:GA-PHP:GC".to_string(),
+                transaction_count: 50,
+                volume_usd: 20_000.0,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_fee_increase_without_elasticity() {
+        let now = Utc::now();
+        let result = simulate_fee_change(&volumes(), 250, 300, 0.0, now - Duration::days(30), now);
+
+        assert!((result.current_revenue_usd - 3_000.0).abs() < 1e-6);
+        assert!((result.projected_revenue_usd - 3_600.0).abs() < 1e-6);
+        assert!((result.user_cost_delta_usd - 600.0).abs() < 1e-6);
+        assert!((result.avg_user_cost_delta_per_tx_usd - 4.0).abs() < 1e-6);
+        assert_eq!(result.corridors.len(), 2);
+        assert!((result.revenue_delta_pct.unwrap_or_default() - 20.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_elasticity_reduces_projected_volume() {
+        let now = Utc::now();
+        // 20% fee increase with elasticity 0.5 -> 10% volume drop
+        let result = simulate_fee_change(&volumes(), 250, 300, 0.5, now - Duration::days(30), now);
+
+        assert!((result.projected_volume_usd - 108_000.0).abs() < 1e-6);
+        assert!((result.projected_revenue_usd - 3_240.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_empty_history_has_no_revenue_pct() {
+        let now = Utc::now();
+        let result = simulate_fee_change(&[], 250, 300, 0.0, now - Duration::days(30), now);
+
+        assert_eq!(result.total_transactions, 0);
+        assert!(result.revenue_delta_pct.is_none());
+        assert!(result.avg_user_cost_delta_per_tx_usd.abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn test_corridor_filter_is_applied_in_the_query() {
+        let pool = test_support::sqlite_pool(&[migrations::CORRIDOR_AGGREGATES]).await;
+        let end = Utc::now();
+        for (corridor_key, volume_usd) in [("USDC:GA-NGN:GB", 1_000.0), ("USDC:GA-PHP:GC", 500.0)] {
+            test_support::insert_hour(
+                &pool,
+                &CorridorHour {
+                    corridor_key,
+                    assets: ["USDC", "GA", "NGN", "GB"],
+                    hour: end - Duration::hours(1),
+                    total: 10,
+                    successful: 10,
+                    volume_usd,
+                    ..CorridorHour::default()
+                },
+            )
+            .await;
+        }
+        let service = FeeSimulationService::new(Arc::new(Database::new(pool)));
+        let request = |corridor_keys: Option<Vec<String>>| FeeSimulationRequest {
+            current_fee_bps: 250,
+            proposed_fee_bps: 300,
+            start: None,
+            end: Some(end),
+            corridor_keys,
+            volume_elasticity: 0.0,
+        };
+
+        let all = service.simulate(request(None)).await.unwrap();
+        assert_eq!(all.corridors.len(), 2);
+        assert_eq!(all.estimate_basis, ESTIMATE_BASIS);
+
+        let filtered = service
+            .simulate(request(Some(vec!["USDC:GA-PHP:GC".to_string()])))
+            .await
+            .unwrap();
+        assert_eq!(filtered.corridors.len(), 1);
+        assert!((filtered.total_volume_usd - 500.0).abs() < 1e-6);
+
+        let none = service.simulate(request(Some(Vec::new()))).await.unwrap();
+        assert!(none.corridors.is_empty());
+    }
+}
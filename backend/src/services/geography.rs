@@ -0,0 +1,665 @@
+use crate::database::Database;
+use crate::snapshot::schema::{SnapshotCorridorMetrics, SnapshotRegionPairMetrics};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+pub const ENTITY_ANCHOR: &str = "anchor";
+pub const ENTITY_ASSET: &str = "asset";
+
+/// Region reported for corridor sides with no asset or anchor mapping.
+pub const UNMAPPED_REGION: &str = "Unmapped";
+
+const DEFAULT_WINDOW_HOURS: i64 = 24;
+const MAX_REGION_LEN: usize = 64;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct GeographyMapping {
+    /// `anchor` or `asset`
+    pub entity_type: String,
+    /// Anchor id, or `CODE:ISSUER` (`native` for XLM) for assets.
+    pub entity_key: String,
+    /// ISO 3166-1 alpha-2 country code.
+    pub country_code: String,
+    pub region: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpsertGeographyMappingRequest {
+    #[schema(example = "asset")]
+    pub entity_type: String,
+    #[schema(example = "NGNC:GCLRUX6CZXDC4B2CSC3QRVDPQRPGUBPVRVXWBXAYQ4VRP6J3SQA6HUKO")]
+    pub entity_key: String,
+    #[schema(example = "NG")]
+    pub country_code: String,
+    #[schema(example = "West Africa")]
+    pub region: String,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RegionPairQuery {
+    /// Trailing window of hourly rollups to aggregate, defaults to 24 hours.
+    pub window_hours: Option<i64>,
+}
+
+/// Corridor totals rolled up by the regions of their source and destination assets.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RegionPairMetrics {
+    pub source_region: String,
+    pub destination_region: String,
+    pub corridor_count: i64,
+    pub total_transactions: i64,
+    pub successful_transactions: i64,
+    pub failed_transactions: i64,
+    /// Percentage of successful transactions across all corridors of the pair.
+    pub success_rate: f64,
+    pub volume_usd: f64,
+}
+
+impl From<RegionPairMetrics> for SnapshotRegionPairMetrics {
+    fn from(m: RegionPairMetrics) -> Self {
+        Self {
+            source_region: m.source_region,
+            destination_region: m.destination_region,
+            corridor_count: m.corridor_count,
+            total_transactions: m.total_transactions,
+            successful_transactions: m.successful_transactions,
+            failed_transactions: m.failed_transactions,
+            success_rate: m.success_rate,
+            volume_usd: m.volume_usd,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RegionPairSummary {
+    pub window_hours: i64,
+    pub generated_at: DateTime<Utc>,
+    /// Sorted by volume, largest first.
+    pub region_pairs: Vec<RegionPairMetrics>,
+}
+
+/// One corridor's totals, as fed into [`aggregate_region_pairs`].
+#[derive(Debug, Clone, Copy)]
+pub struct CorridorFlow<'a> {
+    pub corridor_key: &'a str,
+    pub source_asset_code: &'a str,
+    pub source_asset_issuer: &'a str,
+    pub destination_asset_code: &'a str,
+    pub destination_asset_issuer: &'a str,
+    pub total_transactions: i64,
+    pub successful_transactions: i64,
+    pub failed_transactions: i64,
+    pub volume_usd: f64,
+}
+
+impl<'a> From<&'a SnapshotCorridorMetrics> for CorridorFlow<'a> {
+    fn from(m: &'a SnapshotCorridorMetrics) -> Self {
+        Self {
+            corridor_key: &m.corridor_key,
+            source_asset_code: &m.source_asset_code,
+            source_asset_issuer: &m.source_asset_issuer,
+            destination_asset_code: &m.destination_asset_code,
+            destination_asset_issuer: &m.destination_asset_issuer,
+            total_transactions: m.total_transactions,
+            successful_transactions: m.successful_transactions,
+            failed_transactions: m.failed_transactions,
+            volume_usd: m.volume_usd,
+        }
+    }
+}
+
+/// In-memory view of the mappings used to place a corridor side in a region.
+///
+/// An explicit asset mapping wins; otherwise the asset inherits the region of the
+/// anchor whose account issues it.
+#[derive(Debug, Clone, Default)]
+pub struct GeographyDirectory {
+    assets: HashMap<String, String>,
+    issuers: HashMap<String, String>,
+}
+
+impl GeographyDirectory {
+    pub fn map_asset(&mut self, asset_key: impl Into<String>, region: impl Into<String>) {
+        self.assets.insert(asset_key.into(), region.into());
+    }
+
+    pub fn map_issuer(&mut self, stellar_account: impl Into<String>, region: impl Into<String>) {
+        self.issuers.insert(stellar_account.into(), region.into());
+    }
+
+    /// Region of an asset, or `None` when neither the asset nor its issuer is mapped.
+    #[must_use]
+    pub fn region_of(&self, asset_code: &str, asset_issuer: &str) -> Option<&str> {
+        self.assets
+            .get(&asset_key(asset_code, asset_issuer))
+            .or_else(|| self.issuers.get(asset_issuer))
+            .map(String::as_str)
+    }
+}
+
+/// Canonical mapping key for an asset: `CODE:ISSUER`, or `native` for XLM.
+#[must_use]
+pub fn asset_key(asset_code: &str, asset_issuer: &str) -> String {
+    if asset_issuer.is_empty() || asset_issuer.eq_ignore_ascii_case("native") {
+        "native".to_string()
+    } else {
+        format!("{asset_code}:{asset_issuer}")
+    }
+}
+
+#[derive(Default)]
+struct RegionPairTotals {
+    corridors: BTreeSet<String>,
+    total_transactions: i64,
+    successful_transactions: i64,
+    failed_transactions: i64,
+    volume_usd: f64,
+}
+
+/// Sum corridor totals per `(source_region, destination_region)`, ordered by region pair.
+/// Corridor sides without a mapping land in [`UNMAPPED_REGION`].
+pub fn aggregate_region_pairs<'a>(
+    directory: &GeographyDirectory,
+    flows: impl IntoIterator<Item = CorridorFlow<'a>>,
+) -> Vec<RegionPairMetrics> {
+    let mut pairs: BTreeMap<(String, String), RegionPairTotals> = BTreeMap::new();
+    for flow in flows {
+        let source = directory
+            .region_of(flow.source_asset_code, flow.source_asset_issuer)
+            .unwrap_or(UNMAPPED_REGION);
+        let destination = directory
+            .region_of(flow.destination_asset_code, flow.destination_asset_issuer)
+            .unwrap_or(UNMAPPED_REGION);
+
+        let totals = pairs
+            .entry((source.to_string(), destination.to_string()))
+            .or_default();
+        totals.corridors.insert(flow.corridor_key.to_string());
+        totals.total_transactions += flow.total_transactions;
+        totals.successful_transactions += flow.successful_transactions;
+        totals.failed_transactions += flow.failed_transactions;
+        totals.volume_usd += flow.volume_usd;
+    }
+
+    pairs
+        .into_iter()
+        .map(|((source_region, destination_region), totals)| {
+            let success_rate = if totals.total_transactions > 0 {
+                totals.successful_transactions as f64 / totals.total_transactions as f64 * 100.0
+            } else {
+                0.0
+            };
+            RegionPairMetrics {
+                source_region,
+                destination_region,
+                corridor_count: i64::try_from(totals.corridors.len()).unwrap_or(i64::MAX),
+                total_transactions: totals.total_transactions,
+                successful_transactions: totals.successful_transactions,
+                failed_transactions: totals.failed_transactions,
+                success_rate,
+                volume_usd: totals.volume_usd,
+            }
+        })
+        .collect()
+}
+
+/// Maintains the anchor/asset → country/region mappings and aggregates corridor
+/// rollups by region pair.
+pub struct GeographyService {
+    db: Arc<Database>,
+}
+
+impl GeographyService {
+    #[must_use]
+    pub const fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    pub async fn list_mappings(&self) -> Result<Vec<GeographyMapping>> {
+        let rows = sqlx::query(
+            "SELECT * FROM geography_mappings ORDER BY entity_type, region, entity_key",
+        )
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to list geography mappings")?;
+
+        rows.iter().map(mapping_from_row).collect()
+    }
+
+    pub async fn upsert_mapping(
+        &self,
+        request: UpsertGeographyMappingRequest,
+    ) -> Result<GeographyMapping> {
+        let request = normalize_request(&request)?;
+        let now = Utc::now();
+
+        sqlx::query(
+            r"
+            INSERT INTO geography_mappings (entity_type, entity_key, country_code, region, updated_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT (entity_type, entity_key) DO UPDATE SET
+                country_code = excluded.country_code,
+                region = excluded.region,
+                updated_at = excluded.updated_at
+            ",
+        )
+        .bind(&request.entity_type)
+        .bind(&request.entity_key)
+        .bind(&request.country_code)
+        .bind(&request.region)
+        .bind(now.to_rfc3339())
+        .execute(self.db.pool())
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to store geography mapping for {} {}",
+                request.entity_type, request.entity_key
+            )
+        })?;
+
+        Ok(GeographyMapping {
+            entity_type: request.entity_type,
+            entity_key: request.entity_key,
+            country_code: request.country_code,
+            region: request.region,
+            updated_at: now,
+        })
+    }
+
+    pub async fn delete_mapping(&self, entity_type: &str, entity_key: &str) -> Result<bool> {
+        let deleted =
+            sqlx::query("DELETE FROM geography_mappings WHERE entity_type = ? AND entity_key = ?")
+                .bind(entity_type)
+                .bind(entity_key)
+                .execute(self.db.pool())
+                .await
+                .with_context(|| {
+                    format!("Failed to delete geography mapping for {entity_type} {entity_key}")
+                })?
+                .rows_affected();
+
+        Ok(deleted > 0)
+    }
+
+    /// Load every mapping into a [`GeographyDirectory`]. Anchor mappings are resolved
+    /// to the anchor's Stellar account so assets can inherit them by issuer.
+    pub async fn directory(&self) -> Result<GeographyDirectory> {
+        let rows = sqlx::query(
+            r"
+            SELECT g.entity_type, g.entity_key, g.region, a.stellar_account
+            FROM geography_mappings g
+            LEFT JOIN anchors a ON g.entity_type = 'anchor' AND a.id = g.entity_key
+            ",
+        )
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to load geography mappings")?;
+
+        let mut directory = GeographyDirectory::default();
+        for row in rows {
+            let entity_type: String = row.get("entity_type");
+            let region: String = row.get("region");
+            if entity_type == ENTITY_ASSET {
+                directory.map_asset(row.get::<String, _>("entity_key"), region);
+            } else if let Some(account) = row.get::<Option<String>, _>("stellar_account") {
+                directory.map_issuer(account, region);
+            }
+        }
+        Ok(directory)
+    }
+
+    /// Aggregate the trailing window of hourly corridor rollups by region pair.
+    pub async fn region_pair_summary(&self, query: &RegionPairQuery) -> Result<RegionPairSummary> {
+        let window_hours = query.window_hours.unwrap_or(DEFAULT_WINDOW_HOURS);
+        if !(1..=24 * 31).contains(&window_hours) {
+            return Err(anyhow!("window_hours must be between 1 and 744"));
+        }
+        let now = Utc::now();
+
+        let rows = sqlx::query(
+            r"
+            SELECT corridor_key, asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer,
+                   SUM(total_transactions) AS total_transactions,
+                   SUM(successful_transactions) AS successful_transactions,
+                   SUM(failed_transactions) AS failed_transactions,
+                   SUM(volume_usd) AS volume_usd
+            FROM corridor_metrics_hourly
+            WHERE hour_bucket >= ?
+            GROUP BY corridor_key
+            ",
+        )
+        .bind((now - Duration::hours(window_hours)).to_rfc3339())
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to load hourly corridor rollups")?;
+
+        let corridors: Vec<(String, [String; 4], [i64; 3], f64)> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.get("corridor_key"),
+                    [
+                        row.get("asset_a_code"),
+                        row.get("asset_a_issuer"),
+                        row.get("asset_b_code"),
+                        row.get("asset_b_issuer"),
+                    ],
+                    [
+                        row.get("total_transactions"),
+                        row.get("successful_transactions"),
+                        row.get("failed_transactions"),
+                    ],
+                    row.get("volume_usd"),
+                )
+            })
+            .collect();
+
+        let directory = self.directory().await?;
+        let mut region_pairs = aggregate_region_pairs(
+            &directory,
+            corridors
+                .iter()
+                .map(|(key, assets, counts, volume)| CorridorFlow {
+                    corridor_key: key,
+                    source_asset_code: &assets[0],
+                    source_asset_issuer: &assets[1],
+                    destination_asset_code: &assets[2],
+                    destination_asset_issuer: &assets[3],
+                    total_transactions: counts[0],
+                    successful_transactions: counts[1],
+                    failed_transactions: counts[2],
+                    volume_usd: *volume,
+                }),
+        );
+        region_pairs.sort_by(|a, b| b.volume_usd.total_cmp(&a.volume_usd));
+
+        Ok(RegionPairSummary {
+            window_hours,
+            generated_at: now,
+            region_pairs,
+        })
+    }
+}
+
+/// Validate a mapping request and normalize it to the stored form.
+fn normalize_request(
+    request: &UpsertGeographyMappingRequest,
+) -> Result<UpsertGeographyMappingRequest> {
+    let entity_type = request.entity_type.trim().to_ascii_lowercase();
+    let entity_key = request.entity_key.trim().to_string();
+    match entity_type.as_str() {
+        ENTITY_ANCHOR => {
+            Uuid::parse_str(&entity_key).context("Anchor entity_key must be an anchor id")?;
+        }
+        ENTITY_ASSET => {
+            let valid = entity_key == "native"
+                || entity_key
+                    .split_once(':')
+                    .is_some_and(|(code, issuer)| !code.is_empty() && !issuer.is_empty());
+            if !valid {
+                return Err(anyhow!(
+                    "Asset entity_key must be CODE:ISSUER or native, got {entity_key}"
+                ));
+            }
+        }
+        other => {
+            return Err(anyhow!(
+                "entity_type must be '{ENTITY_ANCHOR}' or '{ENTITY_ASSET}', got '{other}'"
+            ))
+        }
+    }
+
+    let country_code = request.country_code.trim().to_ascii_uppercase();
+    if country_code.len() != 2 || !country_code.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(anyhow!(
+            "country_code must be an ISO 3166-1 alpha-2 code, got {}",
+            request.country_code
+        ));
+    }
+
+    let region = request.region.trim().to_string();
+    if region.is_empty() || region.len() > MAX_REGION_LEN {
+        return Err(anyhow!(
+            "region must be between 1 and {MAX_REGION_LEN} characters"
+        ));
+    }
+    if region == UNMAPPED_REGION {
+        return Err(anyhow!(
+            "'{UNMAPPED_REGION}' is reserved for unmapped corridors"
+        ));
+    }
+
+    Ok(UpsertGeographyMappingRequest {
+        entity_type,
+        entity_key,
+        country_code,
+        region,
+    })
+}
+
+fn mapping_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<GeographyMapping> {
+    let updated_at: String = row.get("updated_at");
+    Ok(GeographyMapping {
+        entity_type: row.get("entity_type"),
+        entity_key: row.get("entity_key"),
+        country_code: row.get("country_code"),
+        region: row.get("region"),
+        updated_at: DateTime::parse_from_rfc3339(&updated_at)
+            .map(|dt| dt.with_timezone(&Utc))
+            .with_context(|| format!("Invalid timestamp in geography mappings: {updated_at}"))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations, CorridorHour};
+
+    const NGNC_ISSUER: &str = "GCLRUX6CZXDC4B2CSC3QRVDPQRPGUBPVRVXWBXAYQ4VRP6J3SQA6HUKO";
+    const EURC_ISSUER: &str = "GDHU6WRG4IEQXM5NZ4BMPKOXHW76MZM4Y2IEMFDVXBSDP6SJY4ITNPP2";
+    const ANCHOR_ID: &str = "00000000-0000-0000-0000-00000000000a";
+
+    async fn setup() -> GeographyService {
+        let pool = test_support::sqlite_pool(&[
+            migrations::ANCHORS,
+            migrations::CORRIDOR_AGGREGATES,
+            migrations::GEOGRAPHY_MAPPINGS,
+        ])
+        .await;
+        sqlx::query("INSERT INTO anchors (id, name, stellar_account) VALUES (?, 'Euro Anchor', ?)")
+            .bind(ANCHOR_ID)
+            .bind(EURC_ISSUER)
+            .execute(&pool)
+            .await
+            .unwrap();
+        GeographyService::new(Arc::new(Database::new(pool)))
+    }
+
+    fn request(
+        entity_type: &str,
+        entity_key: &str,
+        country: &str,
+        region: &str,
+    ) -> UpsertGeographyMappingRequest {
+        UpsertGeographyMappingRequest {
+            entity_type: entity_type.to_string(),
+            entity_key: entity_key.to_string(),
+            country_code: country.to_string(),
+            region: region.to_string(),
+        }
+    }
+
+    async fn insert_hour(
+        service: &GeographyService,
+        corridor_key: &str,
+        assets: [&str; 4],
+        total: i64,
+        successful: i64,
+        volume: f64,
+    ) {
+        test_support::insert_hour(
+            service.db.pool(),
+            &CorridorHour {
+                corridor_key,
+                assets,
+                hour: Utc::now() - Duration::hours(1),
+                total,
+                successful,
+                volume_usd: volume,
+                ..CorridorHour::default()
+            },
+        )
+        .await;
+    }
+
+    #[test]
+    fn test_asset_mapping_takes_precedence_over_issuer() {
+        let mut directory = GeographyDirectory::default();
+        directory.map_issuer(EURC_ISSUER, "EU");
+        directory.map_asset(format!("NGNC:{EURC_ISSUER}"), "West Africa");
+
+        assert_eq!(
+            directory.region_of("NGNC", EURC_ISSUER),
+            Some("West Africa")
+        );
+        assert_eq!(directory.region_of("EURC", EURC_ISSUER), Some("EU"));
+        assert_eq!(directory.region_of("USDC", "GOTHER"), None);
+        assert_eq!(asset_key("XLM", "native"), "native");
+    }
+
+    #[test]
+    fn test_aggregate_region_pairs_sums_corridors() {
+        let mut directory = GeographyDirectory::default();
+        directory.map_issuer(EURC_ISSUER, "EU");
+        directory.map_issuer(NGNC_ISSUER, "West Africa");
+
+        let flow = |key, source: &'static str, destination: &'static str, total, ok, volume| {
+            CorridorFlow {
+                corridor_key: key,
+                source_asset_code: "EURC",
+                source_asset_issuer: source,
+                destination_asset_code: "NGNC",
+                destination_asset_issuer: destination,
+                total_transactions: total,
+                successful_transactions: ok,
+                failed_transactions: total - ok,
+                volume_usd: volume,
+            }
+        };
+        let pairs = aggregate_region_pairs(
+            &directory,
+            [
+                flow("a", EURC_ISSUER, NGNC_ISSUER, 100, 90, 1_000.0),
+                flow("b", EURC_ISSUER, NGNC_ISSUER, 100, 100, 500.0),
+                flow("c", EURC_ISSUER, "GUNKNOWN", 10, 5, 10.0),
+            ],
+        );
+
+        assert_eq!(pairs.len(), 2);
+        assert_eq!(pairs[0].destination_region, UNMAPPED_REGION);
+        let eu_west_africa = &pairs[1];
+        assert_eq!(eu_west_africa.source_region, "EU");
+        assert_eq!(eu_west_africa.destination_region, "West Africa");
+        assert_eq!(eu_west_africa.corridor_count, 2);
+        assert_eq!(eu_west_africa.total_transactions, 200);
+        assert!((eu_west_africa.success_rate - 95.0).abs() < 1e-9);
+        assert!((eu_west_africa.volume_usd - 1_500.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_validates_and_normalizes() {
+        let service = setup().await;
+
+        let stored = service
+            .upsert_mapping(request(
+                " Asset ",
+                &format!("NGNC:{NGNC_ISSUER}"),
+                "ng",
+                "West Africa",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(stored.entity_type, ENTITY_ASSET);
+        assert_eq!(stored.country_code, "NG");
+
+        for bad in [
+            request("corridor", "x", "NG", "West Africa"),
+            request(ENTITY_ASSET, "NGNC", "NG", "West Africa"),
+            request(ENTITY_ANCHOR, "not-a-uuid", "NG", "West Africa"),
+            request(ENTITY_ASSET, "native", "NGA", "West Africa"),
+            request(ENTITY_ASSET, "native", "NG", " "),
+            request(ENTITY_ASSET, "native", "NG", UNMAPPED_REGION),
+        ] {
+            assert!(service.upsert_mapping(bad).await.is_err());
+        }
+
+        assert_eq!(service.list_mappings().await.unwrap().len(), 1);
+        assert!(service
+            .delete_mapping(ENTITY_ASSET, &format!("NGNC:{NGNC_ISSUER}"))
+            .await
+            .unwrap());
+        assert!(service.list_mappings().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_region_pair_summary_uses_anchor_and_asset_mappings() {
+        let service = setup().await;
+        service
+            .upsert_mapping(request(ENTITY_ANCHOR, ANCHOR_ID, "DE", "EU"))
+            .await
+            .unwrap();
+        service
+            .upsert_mapping(request(
+                ENTITY_ASSET,
+                &format!("NGNC:{NGNC_ISSUER}"),
+                "NG",
+                "West Africa",
+            ))
+            .await
+            .unwrap();
+
+        insert_hour(
+            &service,
+            "EURC->NGNC",
+            ["EURC", EURC_ISSUER, "NGNC", NGNC_ISSUER],
+            100,
+            98,
+            5_000.0,
+        )
+        .await;
+        insert_hour(
+            &service,
+            "XLM->NGNC",
+            ["XLM", "native", "NGNC", NGNC_ISSUER],
+            10,
+            10,
+            50.0,
+        )
+        .await;
+
+        let summary = service
+            .region_pair_summary(&RegionPairQuery { window_hours: None })
+            .await
+            .unwrap();
+        assert_eq!(summary.window_hours, 24);
+        assert_eq!(summary.region_pairs.len(), 2);
+        assert_eq!(summary.region_pairs[0].source_region, "EU");
+        assert_eq!(summary.region_pairs[0].destination_region, "West Africa");
+        assert_eq!(summary.region_pairs[0].total_transactions, 100);
+        assert_eq!(summary.region_pairs[1].source_region, UNMAPPED_REGION);
+
+        assert!(service
+            .region_pair_summary(&RegionPairQuery {
+                window_hours: Some(0)
+            })
+            .await
+            .is_err());
+    }
+}
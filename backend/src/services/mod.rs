@@ -3,19 +3,33 @@ pub mod aggregation;
 pub mod alert_manager;
 pub mod alert_service;
 pub mod analytics;
+pub mod anchor_flows;
 pub mod anchor_monitor;
 pub mod asset_verifier;
+pub mod audit_bundle;
+pub mod backfill_coordinator;
+pub mod batch_verification;
 pub mod contract;
 pub mod contract_listener;
+pub mod contract_webhook_bridge;
+pub mod corridor_sla;
 pub mod event_indexer;
 pub mod fee_bump_tracker;
+pub mod fee_simulation;
+pub mod geography;
 pub mod governance;
 pub mod indexing;
 pub mod liquidity_pool_analyzer;
 pub mod price_feed;
+pub mod protocol_compat;
 pub mod realtime_broadcaster;
+pub mod recommendations;
+pub mod runbook;
 pub mod slack_bot;
 pub mod snapshot;
+pub mod snapshot_aggregates;
+pub mod snapshot_reanchor;
+pub mod soroban_estimates;
 pub mod stellar_toml;
 pub mod trustline_analyzer;
 pub mod verification_rewards;
@@ -0,0 +1,349 @@
+use crate::alerts::{AlertManager, AlertType};
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::rpc::StellarRpcClient;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio::time::{interval, Duration};
+use utoipa::ToSchema;
+
+/// Oldest ledger protocol the bundled `stellar-xdr` definitions can decode.
+pub const MIN_SUPPORTED_PROTOCOL: u32 = 20;
+/// Newest ledger protocol the bundled `stellar-xdr` (v21, `curr`) definitions can decode.
+pub const MAX_SUPPORTED_PROTOCOL: u32 = 21;
+
+const DEFAULT_CHECK_INTERVAL_SECS: u64 = 3600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CompatibilityStatus {
+    /// Running protocol is within the supported range and no upgrade is staged.
+    Compatible,
+    /// Validators can vote for a protocol this build cannot parse.
+    UpgradePending,
+    /// The network already runs a protocol outside the supported range.
+    Incompatible,
+    /// The source could not be queried.
+    Unknown,
+}
+
+impl CompatibilityStatus {
+    const fn severity(self) -> u8 {
+        match self {
+            Self::Compatible => 0,
+            Self::Unknown => 1,
+            Self::UpgradePending => 2,
+            Self::Incompatible => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SourceCompatibility {
+    /// `rpc` or `horizon`
+    pub source: String,
+    pub software_version: Option<String>,
+    pub core_version: Option<String>,
+    pub current_protocol_version: Option<u32>,
+    /// Highest protocol the connected stellar-core build supports (Horizon only).
+    pub core_supported_protocol_version: Option<u32>,
+    pub status: CompatibilityStatus,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CompatibilityReport {
+    pub status: CompatibilityStatus,
+    pub min_supported_protocol: u32,
+    pub max_supported_protocol: u32,
+    pub sources: Vec<SourceCompatibility>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Compares the protocol versions reported by Horizon and Soroban RPC against the
+/// range our XDR parsing supports, and warns operators before an upgrade lands.
+pub struct ProtocolCompatibilityService {
+    rpc_client: Arc<StellarRpcClient>,
+    alert_manager: Option<Arc<AlertManager>>,
+    min_supported: u32,
+    max_supported: u32,
+    latest_report: RwLock<Option<CompatibilityReport>>,
+}
+
+impl ProtocolCompatibilityService {
+    #[must_use]
+    pub fn new(rpc_client: Arc<StellarRpcClient>) -> Self {
+        let min_supported = std::env::var("STELLAR_MIN_SUPPORTED_PROTOCOL")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(MIN_SUPPORTED_PROTOCOL);
+        let max_supported = std::env::var("STELLAR_MAX_SUPPORTED_PROTOCOL")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(MAX_SUPPORTED_PROTOCOL);
+
+        Self {
+            rpc_client,
+            alert_manager: None,
+            min_supported,
+            max_supported,
+            latest_report: RwLock::new(None),
+        }
+    }
+
+    #[must_use]
+    pub fn with_alerts(mut self, alert_manager: Arc<AlertManager>) -> Self {
+        self.alert_manager = Some(alert_manager);
+        self
+    }
+
+    /// Last report produced by [`Self::check`], if any.
+    pub async fn latest_report(&self) -> Option<CompatibilityReport> {
+        self.latest_report.read().await.clone()
+    }
+
+    /// Query both endpoints, store the resulting report and alert on regressions.
+    pub async fn check(&self) -> CompatibilityReport {
+        let rpc = match self.rpc_client.fetch_rpc_version_info().await {
+            Ok(info) => {
+                let (status, message) = evaluate(
+                    info.protocol_version,
+                    None,
+                    self.min_supported,
+                    self.max_supported,
+                );
+                SourceCompatibility {
+                    source: "rpc".to_string(),
+                    software_version: Some(info.version),
+                    core_version: Some(info.captive_core_version),
+                    current_protocol_version: Some(info.protocol_version),
+                    core_supported_protocol_version: None,
+                    status,
+                    message,
+                }
+            }
+            Err(e) => unreachable_source("rpc", &e.to_string()),
+        };
+
+        let horizon = match self.rpc_client.fetch_horizon_root().await {
+            Ok(info) => {
+                let (status, message) = evaluate(
+                    info.current_protocol_version,
+                    Some(info.core_supported_protocol_version),
+                    self.min_supported,
+                    self.max_supported,
+                );
+                SourceCompatibility {
+                    source: "horizon".to_string(),
+                    software_version: Some(info.horizon_version),
+                    core_version: Some(info.core_version),
+                    current_protocol_version: Some(info.current_protocol_version),
+                    core_supported_protocol_version: Some(info.core_supported_protocol_version),
+                    status,
+                    message,
+                }
+            }
+            Err(e) => unreachable_source("horizon", &e.to_string()),
+        };
+
+        let sources = vec![rpc, horizon];
+        let status = sources
+            .iter()
+            .map(|s| s.status)
+            .max_by_key(|s| s.severity())
+            .unwrap_or(CompatibilityStatus::Unknown);
+
+        let report = CompatibilityReport {
+            status,
+            min_supported_protocol: self.min_supported,
+            max_supported_protocol: self.max_supported,
+            sources,
+            checked_at: Utc::now(),
+        };
+
+        let previous = self.latest_report.write().await.replace(report.clone());
+        self.notify(previous.map(|r| r.status), &report);
+
+        report
+    }
+
+    /// Re-run the check on the configured interval (`PROTOCOL_CHECK_INTERVAL_SECS`,
+    /// default one hour). The startup check is expected to have been run by the caller.
+    pub async fn start(self: Arc<Self>) {
+        let secs = std::env::var("PROTOCOL_CHECK_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_CHECK_INTERVAL_SECS);
+        let mut check_interval = interval(Duration::from_secs(secs));
+        // The first tick completes immediately; skip it so startup isn't checked twice.
+        check_interval.tick().await;
+        log_event!(
+            info,
+            Subsystem::Rpc,
+            "protocol_compat.monitor_started",
+            min_supported = self.min_supported,
+            max_supported = self.max_supported,
+            "Protocol compatibility monitor started"
+        );
+
+        loop {
+            check_interval.tick().await;
+            self.check().await;
+        }
+    }
+
+    fn notify(&self, previous: Option<CompatibilityStatus>, report: &CompatibilityReport) {
+        for source in &report.sources {
+            match source.status {
+                CompatibilityStatus::Incompatible => {
+                    log_event!(
+                        error,
+                        Subsystem::Rpc,
+                        "protocol_compat.incompatible",
+                        source = %source.source,
+                        "{}",
+                        source.message
+                    );
+                }
+                CompatibilityStatus::UpgradePending | CompatibilityStatus::Unknown => {
+                    log_event!(
+                        warn,
+                        Subsystem::Rpc,
+                        "protocol_compat.degraded",
+                        source = %source.source,
+                        status = ?source.status,
+                        "{}",
+                        source.message
+                    );
+                }
+                CompatibilityStatus::Compatible => {}
+            }
+        }
+
+        // Only page operators when the overall status gets worse, not on every tick.
+        let escalated = previous.map_or(true, |p| report.status.severity() > p.severity());
+        if !escalated
+            || !matches!(
+                report.status,
+                CompatibilityStatus::UpgradePending | CompatibilityStatus::Incompatible
+            )
+        {
+            return;
+        }
+
+        if let Some(alert_manager) = &self.alert_manager {
+            let current = report
+                .sources
+                .iter()
+                .filter_map(|s| s.current_protocol_version)
+                .max()
+                .unwrap_or_default();
+            let upcoming = report
+                .sources
+                .iter()
+                .filter_map(|s| s.core_supported_protocol_version)
+                .max()
+                .unwrap_or(current);
+            let message = report
+                .sources
+                .iter()
+                .filter(|s| s.status == report.status)
+                .map(|s| format!("{}: {}", s.source, s.message))
+                .collect::<Vec<_>>()
+                .join("; ");
+            alert_manager.send_network_alert(
+                AlertType::NetworkProtocolUpgrade,
+                message,
+                f64::from(current),
+                f64::from(upcoming),
+            );
+        }
+    }
+}
+
+fn unreachable_source(source: &str, error: &str) -> SourceCompatibility {
+    SourceCompatibility {
+        source: source.to_string(),
+        software_version: None,
+        core_version: None,
+        current_protocol_version: None,
+        core_supported_protocol_version: None,
+        status: CompatibilityStatus::Unknown,
+        message: format!("Failed to query {source} version: {error}"),
+    }
+}
+
+/// Classify a reported protocol version against the supported range.
+#[must_use]
+pub fn evaluate(
+    current: u32,
+    core_supported: Option<u32>,
+    min_supported: u32,
+    max_supported: u32,
+) -> (CompatibilityStatus, String) {
+    if current < min_supported || current > max_supported {
+        return (
+            CompatibilityStatus::Incompatible,
+            format!(
+                "Network runs protocol {current}, outside supported range \
+                 {min_supported}..={max_supported}; XDR parsing may fail"
+            ),
+        );
+    }
+
+    match core_supported {
+        Some(next) if next > max_supported => (
+            CompatibilityStatus::UpgradePending,
+            format!(
+                "stellar-core supports protocol {next}; an upgrade vote would move the \
+                 network beyond supported protocol {max_supported}"
+            ),
+        ),
+        _ => (
+            CompatibilityStatus::Compatible,
+            format!("Protocol {current} is supported"),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_supported_protocol() {
+        let (status, _) = evaluate(21, Some(21), 20, 21);
+        assert_eq!(status, CompatibilityStatus::Compatible);
+    }
+
+    #[test]
+    fn test_evaluate_pending_upgrade() {
+        let (status, message) = evaluate(21, Some(22), 20, 21);
+        assert_eq!(status, CompatibilityStatus::UpgradePending);
+        assert!(message.contains("22"));
+    }
+
+    #[test]
+    fn test_evaluate_out_of_range() {
+        assert_eq!(
+            evaluate(22, None, 20, 21).0,
+            CompatibilityStatus::Incompatible
+        );
+        assert_eq!(
+            evaluate(19, None, 20, 21).0,
+            CompatibilityStatus::Incompatible
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_with_mock_client() {
+        let client = Arc::new(StellarRpcClient::new_with_defaults(true));
+        let service = ProtocolCompatibilityService::new(client);
+
+        let report = service.check().await;
+        assert_eq!(report.sources.len(), 2);
+        assert!(service.latest_report().await.is_some());
+    }
+}
@@ -0,0 +1,695 @@
+//! Corridors to Watch
+//!
+//! Compares each corridor's hourly rollups over the trailing window with the window
+//! before it and recommends corridors whose liquidity is deepening while their cost
+//! (average slippage) holds or falls. A least-squares line through the daily
+//! liquidity averages projects where depth is heading one window ahead. Results can
+//! be narrowed to a region, through the geography mappings, or to a watchlist of
+//! corridor keys, and Telegram chats can opt in to a weekly digest of the same list.
+
+use crate::cluster::{LeaderElection, ROLE_DIGESTS};
+use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::services::geography::{GeographyService, UNMAPPED_REGION};
+use crate::telegram::client::TelegramClient;
+use crate::telegram::formatter;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+const DEFAULT_WINDOW_DAYS: i64 = 7;
+const MAX_WINDOW_DAYS: i64 = 30;
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 50;
+const MAX_REGION_LEN: usize = 64;
+/// Corridors with fewer transactions in the current window are too thin to trend.
+const MIN_TRANSACTIONS: i64 = 20;
+const DIGEST_INTERVAL_DAYS: i64 = 7;
+const DELIVERY_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct RecommendationQuery {
+    /// Only corridors with a source or destination asset mapped to this region.
+    pub region: Option<String>,
+    /// Comma-separated corridor keys; only these corridors are considered.
+    pub watchlist: Option<String>,
+    /// Length in days of each of the two compared windows, defaults to 7.
+    pub window_days: Option<i64>,
+    /// Maximum number of recommendations, defaults to 10.
+    pub limit: Option<usize>,
+}
+
+/// One corridor day, averaged over its hourly rollups.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorridorDay {
+    pub corridor_key: String,
+    pub source_region: String,
+    pub destination_region: String,
+    pub day: NaiveDate,
+    pub total_transactions: i64,
+    pub liquidity_depth_usd: f64,
+    pub avg_slippage_bps: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct CorridorRecommendation {
+    pub corridor_key: String,
+    pub source_region: String,
+    pub destination_region: String,
+    /// Transactions in the current window.
+    pub total_transactions: i64,
+    /// Average liquidity depth over the current window.
+    pub liquidity_depth_usd: f64,
+    /// Change in average liquidity depth against the previous window.
+    pub liquidity_change_pct: f64,
+    /// Average slippage over the current window, in basis points.
+    pub avg_slippage_bps: f64,
+    /// Change in average slippage against the previous window; negative is cheaper.
+    pub slippage_change_bps: f64,
+    /// Liquidity depth the daily trend reaches by the end of the next window.
+    pub projected_liquidity_depth_usd: f64,
+    /// Liquidity gain plus relative slippage reduction, both in percent.
+    pub score: f64,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecommendationReport {
+    pub window_days: i64,
+    pub generated_at: DateTime<Utc>,
+    pub region: Option<String>,
+    pub watchlist: Vec<String>,
+    pub recommendations: Vec<CorridorRecommendation>,
+}
+
+/// Split a watchlist on commas or whitespace, dropping blanks and duplicates.
+#[must_use]
+pub fn parse_watchlist(watchlist: &str) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for key in watchlist.split(|c: char| c == ',' || c.is_whitespace()) {
+        let key = key.trim();
+        if !key.is_empty() && !keys.iter().any(|k| k == key) {
+            keys.push(key.to_string());
+        }
+    }
+    keys
+}
+
+/// Rank corridors that got deeper or cheaper without getting worse on the other, best first.
+///
+/// Compares the `window_days` before `current_start` with the window from it. `days`
+/// may hold any mix of corridors and days; anything older than both is ignored.
+#[must_use]
+pub fn rank_corridors(
+    days: &[CorridorDay],
+    current_start: NaiveDate,
+    window_days: i64,
+) -> Vec<CorridorRecommendation> {
+    let previous_start = current_start - Duration::days(window_days);
+    let mut corridors: BTreeMap<&str, Vec<&CorridorDay>> = BTreeMap::new();
+    for day in days.iter().filter(|d| d.day >= previous_start) {
+        corridors.entry(&day.corridor_key).or_default().push(day);
+    }
+
+    let mut recommendations: Vec<CorridorRecommendation> = corridors
+        .into_iter()
+        .filter_map(|(corridor_key, days)| {
+            let (current, previous): (Vec<&CorridorDay>, Vec<&CorridorDay>) =
+                days.iter().copied().partition(|d| d.day >= current_start);
+            if current.is_empty() || previous.is_empty() {
+                return None;
+            }
+            let total_transactions: i64 = current.iter().map(|d| d.total_transactions).sum();
+            if total_transactions < MIN_TRANSACTIONS {
+                return None;
+            }
+
+            let previous_liquidity = mean(previous.iter().map(|d| d.liquidity_depth_usd));
+            let liquidity = mean(current.iter().map(|d| d.liquidity_depth_usd));
+            if previous_liquidity <= 0.0 {
+                return None;
+            }
+            let liquidity_change_pct = (liquidity - previous_liquidity) / previous_liquidity * 100.0;
+
+            let previous_slippage = mean(previous.iter().map(|d| d.avg_slippage_bps));
+            let slippage = mean(current.iter().map(|d| d.avg_slippage_bps));
+            let slippage_change_bps = slippage - previous_slippage;
+            let slippage_reduction_pct = if previous_slippage > 0.0 {
+                -slippage_change_bps / previous_slippage * 100.0
+            } else {
+                0.0
+            };
+
+            let improving = liquidity_change_pct >= 0.0
+                && slippage_change_bps <= 0.0
+                && (liquidity_change_pct > 0.0 || slippage_change_bps < 0.0);
+            if !improving {
+                return None;
+            }
+
+            let mut reasons = Vec::new();
+            if liquidity_change_pct > 0.0 {
+                reasons.push(format!(
+                    "Liquidity depth up {liquidity_change_pct:.1}% on the previous {window_days} days"
+                ));
+            }
+            if slippage_change_bps < 0.0 {
+                reasons.push(format!(
+                    "Average slippage down {:.1} bps to {slippage:.1} bps",
+                    -slippage_change_bps
+                ));
+            }
+
+            let first = days[0];
+            Some(CorridorRecommendation {
+                corridor_key: corridor_key.to_string(),
+                source_region: first.source_region.clone(),
+                destination_region: first.destination_region.clone(),
+                total_transactions,
+                liquidity_depth_usd: liquidity,
+                liquidity_change_pct,
+                avg_slippage_bps: slippage,
+                slippage_change_bps,
+                projected_liquidity_depth_usd: project_liquidity(
+                    &days,
+                    previous_start,
+                    current_start + Duration::days(2 * window_days - 1),
+                ),
+                score: liquidity_change_pct + slippage_reduction_pct,
+                reasons,
+            })
+        })
+        .collect();
+
+    recommendations.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.corridor_key.cmp(&b.corridor_key))
+    });
+    recommendations
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0_u32), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        sum / f64::from(count)
+    }
+}
+
+/// Least-squares line through the daily liquidity averages, read at `target`. Never
+/// negative; a single day projects flat.
+#[allow(clippy::cast_precision_loss)]
+fn project_liquidity(days: &[&CorridorDay], origin: NaiveDate, target: NaiveDate) -> f64 {
+    let points: Vec<(f64, f64)> = days
+        .iter()
+        .map(|d| ((d.day - origin).num_days() as f64, d.liquidity_depth_usd))
+        .collect();
+    let x_mean = mean(points.iter().map(|(x, _)| *x));
+    let y_mean = mean(points.iter().map(|(_, y)| *y));
+    let variance: f64 = points.iter().map(|(x, _)| (x - x_mean).powi(2)).sum();
+    let slope = if variance > 0.0 {
+        points
+            .iter()
+            .map(|(x, y)| (x - x_mean) * (y - y_mean))
+            .sum::<f64>()
+            / variance
+    } else {
+        0.0
+    };
+    let x = (target - origin).num_days() as f64;
+    (slope.mul_add(x - x_mean, y_mean)).max(0.0)
+}
+
+/// `MarkdownV2` digest of a report for Telegram.
+#[must_use]
+pub fn digest_text(report: &RecommendationReport) -> String {
+    let scope = match (&report.region, report.watchlist.is_empty()) {
+        (Some(region), _) => format!(" in {region}"),
+        (None, false) => " on your watchlist".to_string(),
+        (None, true) => String::new(),
+    };
+    let mut lines = vec![format!(
+        "*{}*\n",
+        formatter::escape_markdown(&format!("Corridors to watch{scope}"))
+    )];
+    for (rank, recommendation) in report.recommendations.iter().enumerate() {
+        lines.push(format!(
+            "{}\\. `{}`\n{}",
+            rank + 1,
+            formatter::escape_markdown(&recommendation.corridor_key),
+            formatter::escape_markdown(&recommendation.reasons.join("; ")),
+        ));
+    }
+    lines.join("\n")
+}
+
+pub struct RecommendationService {
+    db: Arc<Database>,
+    telegram: Option<Arc<TelegramClient>>,
+    leader: Option<Arc<LeaderElection>>,
+}
+
+impl RecommendationService {
+    #[must_use]
+    pub const fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            telegram: None,
+            leader: None,
+        }
+    }
+
+    /// Enable the weekly Telegram digest.
+    #[must_use]
+    pub fn with_telegram(mut self, telegram: Arc<TelegramClient>) -> Self {
+        self.telegram = Some(telegram);
+        self
+    }
+
+    /// Only send digests from the replica holding the digests lease.
+    #[must_use]
+    pub fn with_leader_election(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    /// Corridors to watch, comparing the last `window_days` complete days with the
+    /// ones before.
+    pub async fn recommend(
+        &self,
+        query: &RecommendationQuery,
+        now: DateTime<Utc>,
+    ) -> Result<RecommendationReport> {
+        let window_days = query.window_days.unwrap_or(DEFAULT_WINDOW_DAYS);
+        if !(1..=MAX_WINDOW_DAYS).contains(&window_days) {
+            bail!("window_days must be between 1 and {MAX_WINDOW_DAYS}");
+        }
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            bail!("limit must be between 1 and {MAX_LIMIT}");
+        }
+        let region = query
+            .region
+            .as_deref()
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(str::to_string);
+        let watchlist = query
+            .watchlist
+            .as_deref()
+            .map(parse_watchlist)
+            .unwrap_or_default();
+
+        let today = now.date_naive();
+        let current_start = today - Duration::days(window_days);
+        let rows = sqlx::query(
+            r"
+            SELECT corridor_key, asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer,
+                   substr(hour_bucket, 1, 10) AS day,
+                   COALESCE(SUM(total_transactions), 0) AS total_transactions,
+                   COALESCE(AVG(liquidity_depth_usd), 0.0) AS liquidity_depth_usd,
+                   COALESCE(AVG(avg_slippage_bps), 0.0) AS avg_slippage_bps
+            FROM corridor_metrics_hourly
+            WHERE hour_bucket >= ? AND hour_bucket < ?
+            GROUP BY corridor_key, day
+            ",
+        )
+        .bind(
+            (current_start - Duration::days(window_days))
+                .and_time(chrono::NaiveTime::MIN)
+                .and_utc()
+                .to_rfc3339(),
+        )
+        .bind(
+            today
+                .and_time(chrono::NaiveTime::MIN)
+                .and_utc()
+                .to_rfc3339(),
+        )
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to load hourly corridor rollups")?;
+
+        let directory = GeographyService::new(Arc::clone(&self.db))
+            .directory()
+            .await?;
+        let mut days = Vec::with_capacity(rows.len());
+        for row in rows {
+            let corridor_key: String = row.get("corridor_key");
+            if !watchlist.is_empty() && !watchlist.contains(&corridor_key) {
+                continue;
+            }
+            let source_region = directory
+                .region_of(row.get("asset_a_code"), row.get("asset_a_issuer"))
+                .unwrap_or(UNMAPPED_REGION)
+                .to_string();
+            let destination_region = directory
+                .region_of(row.get("asset_b_code"), row.get("asset_b_issuer"))
+                .unwrap_or(UNMAPPED_REGION)
+                .to_string();
+            if let Some(region) = &region {
+                if !source_region.eq_ignore_ascii_case(region)
+                    && !destination_region.eq_ignore_ascii_case(region)
+                {
+                    continue;
+                }
+            }
+            let day: String = row.get("day");
+            days.push(CorridorDay {
+                corridor_key,
+                source_region,
+                destination_region,
+                day: NaiveDate::parse_from_str(&day, "%Y-%m-%d")
+                    .with_context(|| format!("Unexpected hour bucket day '{day}'"))?,
+                total_transactions: row.get("total_transactions"),
+                liquidity_depth_usd: row.get("liquidity_depth_usd"),
+                avg_slippage_bps: row.get("avg_slippage_bps"),
+            });
+        }
+
+        let mut recommendations = rank_corridors(&days, current_start, window_days);
+        recommendations.truncate(limit);
+        Ok(RecommendationReport {
+            window_days,
+            generated_at: now,
+            region,
+            watchlist,
+            recommendations,
+        })
+    }
+
+    /// Opt a chat in to the weekly digest, or replace its region and watchlist.
+    pub async fn subscribe(
+        &self,
+        chat_id: i64,
+        region: Option<&str>,
+        watchlist: &[String],
+    ) -> Result<()> {
+        let region = region.map(str::trim).filter(|r| !r.is_empty());
+        if region.is_some_and(|r| r.len() > MAX_REGION_LEN) {
+            return Err(anyhow!(
+                "Region must be at most {MAX_REGION_LEN} characters"
+            ));
+        }
+        let watchlist = (!watchlist.is_empty()).then(|| watchlist.join(","));
+
+        sqlx::query(
+            r"
+            INSERT INTO recommendation_subscriptions (chat_id, region, watchlist, subscribed_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(chat_id) DO UPDATE SET
+                region = excluded.region,
+                watchlist = excluded.watchlist
+            ",
+        )
+        .bind(chat_id)
+        .bind(region)
+        .bind(watchlist)
+        .bind(Utc::now().to_rfc3339())
+        .execute(self.db.pool())
+        .await
+        .context("Failed to save recommendation subscription")?;
+        Ok(())
+    }
+
+    /// Returns whether the chat was subscribed.
+    pub async fn unsubscribe(&self, chat_id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM recommendation_subscriptions WHERE chat_id = ?")
+            .bind(chat_id)
+            .execute(self.db.pool())
+            .await
+            .context("Failed to delete recommendation subscription")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Send the digest to every subscribed chat that has not had one in the last
+    /// week. Chats with nothing to recommend are skipped until the following week.
+    /// Returns how many digests were sent.
+    pub async fn deliver_due(&self, now: DateTime<Utc>) -> Result<usize> {
+        let Some(telegram) = &self.telegram else {
+            bail!("Telegram is not configured");
+        };
+        let due = sqlx::query(
+            r"
+            SELECT chat_id, region, watchlist
+            FROM recommendation_subscriptions
+            WHERE last_sent_at IS NULL OR last_sent_at <= ?
+            ORDER BY chat_id
+            ",
+        )
+        .bind((now - Duration::days(DIGEST_INTERVAL_DAYS)).to_rfc3339())
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to find chats due a recommendation digest")?;
+
+        let mut sent = 0;
+        for row in due {
+            let chat_id: i64 = row.get("chat_id");
+            let query = RecommendationQuery {
+                region: row.get("region"),
+                watchlist: row.get("watchlist"),
+                ..RecommendationQuery::default()
+            };
+            let report = match self.recommend(&query, now).await {
+                Ok(report) => report,
+                Err(e) => {
+                    log_event!(
+                        warn,
+                        Subsystem::Telegram,
+                        "recommendations.build_failed",
+                        chat_id,
+                        error = format!("{e:#}"),
+                        "Failed to build recommendations for chat"
+                    );
+                    continue;
+                }
+            };
+            if !report.recommendations.is_empty() {
+                if let Err(e) = telegram.send_message(chat_id, &digest_text(&report)).await {
+                    log_event!(
+                        warn,
+                        Subsystem::Telegram,
+                        "recommendations.digest_send_failed",
+                        chat_id,
+                        error = %e,
+                        "Failed to send recommendation digest to chat"
+                    );
+                    continue;
+                }
+                sent += 1;
+            }
+
+            sqlx::query(
+                "UPDATE recommendation_subscriptions SET last_sent_at = ? WHERE chat_id = ?",
+            )
+            .bind(now.to_rfc3339())
+            .bind(chat_id)
+            .execute(self.db.pool())
+            .await
+            .context("Failed to record recommendation digest")?;
+        }
+
+        if sent > 0 {
+            log_event!(
+                info,
+                Subsystem::Telegram,
+                "recommendations.digests_sent",
+                sent,
+                "Sent corridor recommendation digests"
+            );
+        }
+        Ok(sent)
+    }
+
+    /// Check hourly for chats due their weekly digest.
+    pub async fn start(self: Arc<Self>) {
+        let mut ticker = tokio::time::interval(DELIVERY_CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Some(leader) = &self.leader {
+                if !leader.is_leader(ROLE_DIGESTS) {
+                    continue;
+                }
+            }
+            if let Err(e) = self.deliver_due(Utc::now()).await {
+                log_event!(
+                    warn,
+                    Subsystem::Telegram,
+                    "recommendations.delivery_failed",
+                    error = format!("{e:#}"),
+                    "Recommendation digest delivery failed"
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations, CorridorHour};
+
+    fn day(corridor_key: &str, date: NaiveDate, liquidity: f64, slippage: f64) -> CorridorDay {
+        CorridorDay {
+            corridor_key: corridor_key.to_string(),
+            source_region: "West Africa".to_string(),
+            destination_region: UNMAPPED_REGION.to_string(),
+            day: date,
+            total_transactions: 10,
+            liquidity_depth_usd: liquidity,
+            avg_slippage_bps: slippage,
+        }
+    }
+
+    #[test]
+    fn test_rank_corridors_prefers_improving_liquidity_and_cost() {
+        let start = NaiveDate::from_ymd_opt(2026, 3, 8).unwrap();
+        let mut days = Vec::new();
+        for offset in -3..3 {
+            let date = start + Duration::days(offset);
+            let step = f64::from(i32::try_from(offset + 3).unwrap());
+            // Deepening and cheaper
+            days.push(day(
+                "A->B",
+                date,
+                100.0f64.mul_add(step, 1_000.0),
+                20.0 - step,
+            ));
+            // Deepening but slippage rising: not recommended
+            days.push(day(
+                "C->D",
+                date,
+                200.0f64.mul_add(step, 1_000.0),
+                10.0 + step,
+            ));
+            // Only cheaper
+            days.push(day("E->F", date, 1_000.0, 10.0 - step));
+        }
+        // Too old to count
+        days.push(day("A->B", start - Duration::days(10), 1.0, 99.0));
+
+        let ranked = rank_corridors(&days, start, 3);
+        let keys: Vec<&str> = ranked.iter().map(|r| r.corridor_key.as_str()).collect();
+        assert_eq!(keys, ["A->B", "E->F"]);
+
+        let top = &ranked[0];
+        assert_eq!(top.total_transactions, 30);
+        assert!((top.liquidity_change_pct - 27.272_727_272_727_27).abs() < 1e-9);
+        assert!((top.slippage_change_bps + 3.0).abs() < 1e-9);
+        // Linear series, read at the last day of the next window
+        assert!((top.projected_liquidity_depth_usd - 1_800.0).abs() < 1e-6);
+        assert_eq!(top.reasons.len(), 2);
+        assert_eq!(ranked[1].reasons.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_watchlist() {
+        assert_eq!(
+            parse_watchlist("USDC:GA->XLM:native, NGNC:GB->USDC:GA\nUSDC:GA->XLM:native,,"),
+            ["USDC:GA->XLM:native", "NGNC:GB->USDC:GA"]
+        );
+        assert!(parse_watchlist(" , ").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_recommend_filters_by_region_and_tracks_subscriptions() {
+        let pool = test_support::sqlite_pool(&[
+            migrations::ANCHORS,
+            migrations::CORRIDOR_AGGREGATES,
+            migrations::GEOGRAPHY_MAPPINGS,
+            migrations::RECOMMENDATION_SUBSCRIPTIONS,
+        ])
+        .await;
+        sqlx::query(
+            "INSERT INTO geography_mappings (entity_type, entity_key, country_code, region, updated_at)
+             VALUES ('asset', 'NGNC:GNG', 'NG', 'West Africa', ?)",
+        )
+        .bind(Utc::now().to_rfc3339())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let now = Utc::now();
+        for (corridor, code, issuer) in
+            [("NGNC->USDC", "NGNC", "GNG"), ("EURC->USDC", "EURC", "GEU")]
+        {
+            for days_ago in 1..=14 {
+                let hour = now - Duration::days(days_ago);
+                let step = f64::from(15 - i32::try_from(days_ago).unwrap());
+                test_support::insert_hour(
+                    &pool,
+                    &CorridorHour {
+                        corridor_key: corridor,
+                        assets: [code, issuer, "USDC", "GUS"],
+                        hour,
+                        total: 10,
+                        avg_slippage_bps: 30.0 - step,
+                        liquidity_depth_usd: 250.0f64.mul_add(step, 5_000.0),
+                        ..CorridorHour::default()
+                    },
+                )
+                .await;
+            }
+        }
+
+        let service = RecommendationService::new(Arc::new(Database::new(pool)));
+        let report = service
+            .recommend(&RecommendationQuery::default(), now)
+            .await
+            .unwrap();
+        assert_eq!(report.recommendations.len(), 2);
+
+        let query = RecommendationQuery {
+            region: Some("west africa".to_string()),
+            ..RecommendationQuery::default()
+        };
+        let report = service.recommend(&query, now).await.unwrap();
+        let keys: Vec<&str> = report
+            .recommendations
+            .iter()
+            .map(|r| r.corridor_key.as_str())
+            .collect();
+        assert_eq!(keys, ["NGNC->USDC"]);
+        assert_eq!(report.recommendations[0].source_region, "West Africa");
+
+        let query = RecommendationQuery {
+            watchlist: Some("EURC->USDC".to_string()),
+            ..RecommendationQuery::default()
+        };
+        let report = service.recommend(&query, now).await.unwrap();
+        assert_eq!(report.recommendations[0].corridor_key, "EURC->USDC");
+        assert!(digest_text(&report).contains("on your watchlist"));
+
+        let bad = RecommendationQuery {
+            window_days: Some(0),
+            ..RecommendationQuery::default()
+        };
+        assert!(service.recommend(&bad, now).await.is_err());
+
+        service
+            .subscribe(42, Some("West Africa"), &[])
+            .await
+            .unwrap();
+        service
+            .subscribe(42, None, &["EURC->USDC".to_string()])
+            .await
+            .unwrap();
+        let (region, watchlist): (Option<String>, Option<String>) = sqlx::query_as(
+            "SELECT region, watchlist FROM recommendation_subscriptions WHERE chat_id = 42",
+        )
+        .fetch_one(service.db.pool())
+        .await
+        .unwrap();
+        assert_eq!(region, None);
+        assert_eq!(watchlist.as_deref(), Some("EURC->USDC"));
+        assert!(service.unsubscribe(42).await.unwrap());
+        assert!(!service.unsubscribe(42).await.unwrap());
+    }
+}
@@ -0,0 +1,1050 @@
+//! Operator Runbook Actions
+//!
+//! Common incident remediations exposed as single admin calls: rebuild a corridor's
+//! rollups, resync an anchor from RPC, re-anchor an epoch's snapshot hash on-chain,
+//! and drain then restart ingestion. A request is recorded as a job whose steps are
+//! fixed when it is created; a background worker runs them in order and saves
+//! progress after each one, so a restart resumes at the step it stopped on. Every
+//! request and outcome is written to the admin audit log.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::backfill_coordinator::HourlyRollups;
+use super::snapshot_aggregates::SnapshotAggregateCache;
+use super::snapshot_reanchor::SnapshotAnchorTarget;
+use crate::cluster::{LeaderElection, ROLE_INGESTION};
+use crate::database::Database;
+use crate::ingestion::DataIngestionService;
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::projections::ProjectionStore;
+
+/// Hours a corridor rebuild covers when the request does not say.
+pub const DEFAULT_REBUILD_HOURS: i64 = 24;
+/// Longest window a single corridor rebuild may cover.
+pub const MAX_REBUILD_HOURS: i64 = 24 * 31;
+const DEFAULT_LIST_LIMIT: i64 = 50;
+const MAX_LIST_LIMIT: i64 = 500;
+const WORKER_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+const AUDIT_ACTION: &str = "runbook_action";
+
+/// Ingestion controls the runbook drives.
+#[async_trait::async_trait]
+pub trait IngestionControl: Send + Sync {
+    /// Stop scheduled syncs and wait for the one in progress.
+    async fn drain(&self);
+
+    /// Allow syncs again and run one straight away.
+    async fn resume(&self) -> Result<()>;
+
+    async fn sync_anchor(&self, stellar_account: &str) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl IngestionControl for DataIngestionService {
+    async fn drain(&self) {
+        Self::drain(self).await;
+    }
+
+    async fn resume(&self) -> Result<()> {
+        Self::resume(self);
+        self.sync_all_metrics().await
+    }
+
+    async fn sync_anchor(&self, stellar_account: &str) -> Result<()> {
+        Self::sync_anchor(self, stellar_account).await
+    }
+}
+
+/// Read-side projections rebuilt after their source rows change.
+#[async_trait::async_trait]
+pub trait ReadModels: Send + Sync {
+    async fn refresh_corridor(&self, corridor_key: &str) -> Result<()>;
+
+    async fn refresh_anchor(&self, anchor_id: Uuid) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl ReadModels for ProjectionStore {
+    async fn refresh_corridor(&self, corridor_key: &str) -> Result<()> {
+        Self::refresh_corridor(self, corridor_key).await?;
+        Ok(())
+    }
+
+    async fn refresh_anchor(&self, anchor_id: Uuid) -> Result<()> {
+        Self::refresh_anchor(self, anchor_id).await?;
+        Ok(())
+    }
+}
+
+/// A remediation to run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RunbookAction {
+    /// Recompute hourly rollups for the last `hours` hours, then refresh the
+    /// corridor's projection and the snapshot aggregates.
+    RebuildCorridor {
+        #[schema(example = "USDC:GA5Z->NGNC:GBNG")]
+        corridor_key: String,
+        /// Default 24, at most 744.
+        hours: Option<i64>,
+    },
+    /// Re-fetch the anchor's metrics from RPC and refresh its projection.
+    ResyncAnchor { anchor_id: Uuid },
+    /// Submit the stored snapshot hash for `epoch` to the contract and verify it.
+    ReanchorEpoch { epoch: u64 },
+    /// Wait for the sync in progress to finish, then resume ingestion with a full sync.
+    RestartIngestion,
+}
+
+impl RunbookAction {
+    const fn name(&self) -> &'static str {
+        match self {
+            Self::RebuildCorridor { .. } => "rebuild_corridor",
+            Self::ResyncAnchor { .. } => "resync_anchor",
+            Self::ReanchorEpoch { .. } => "reanchor_epoch",
+            Self::RestartIngestion => "restart_ingestion",
+        }
+    }
+
+    fn target(&self) -> String {
+        match self {
+            Self::RebuildCorridor { corridor_key, .. } => corridor_key.clone(),
+            Self::ResyncAnchor { anchor_id } => anchor_id.to_string(),
+            Self::ReanchorEpoch { epoch } => epoch.to_string(),
+            Self::RestartIngestion => "ingestion".to_string(),
+        }
+    }
+
+    fn steps(&self, created_at: DateTime<Utc>) -> Result<Vec<Step>> {
+        Ok(match self {
+            Self::RebuildCorridor {
+                corridor_key,
+                hours,
+            } => {
+                let hours = hours.unwrap_or(DEFAULT_REBUILD_HOURS);
+                let last = created_at
+                    .duration_trunc(Duration::hours(1))
+                    .context("created_at is out of range")?;
+                let mut steps: Vec<Step> = (0..hours)
+                    .rev()
+                    .map(|back| Step::RecomputeHour(last - Duration::hours(back)))
+                    .collect();
+                steps.push(Step::RefreshCorridor(corridor_key.clone()));
+                steps.push(Step::InvalidateAggregates);
+                steps
+            }
+            Self::ResyncAnchor { anchor_id } => {
+                vec![
+                    Step::SyncAnchor(*anchor_id),
+                    Step::RefreshAnchor(*anchor_id),
+                ]
+            }
+            Self::ReanchorEpoch { epoch } => vec![Step::Reanchor(*epoch)],
+            Self::RestartIngestion => vec![Step::Drain, Step::Resume],
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    RecomputeHour(DateTime<Utc>),
+    RefreshCorridor(String),
+    InvalidateAggregates,
+    SyncAnchor(Uuid),
+    RefreshAnchor(Uuid),
+    Reanchor(u64),
+    Drain,
+    Resume,
+}
+
+impl Step {
+    fn describe(&self) -> String {
+        match self {
+            Self::RecomputeHour(hour) => format!("Recomputing hour {}", hour.to_rfc3339()),
+            Self::RefreshCorridor(key) => format!("Refreshing corridor projection {key}"),
+            Self::InvalidateAggregates => "Invalidating snapshot aggregates".to_string(),
+            Self::SyncAnchor(id) => format!("Syncing anchor {id} from RPC"),
+            Self::RefreshAnchor(id) => format!("Refreshing anchor projection {id}"),
+            Self::Reanchor(epoch) => format!("Re-anchoring epoch {epoch}"),
+            Self::Drain => "Draining ingestion".to_string(),
+            Self::Resume => "Resuming ingestion".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RunbookStatus {
+    /// Waiting for the worker.
+    Pending,
+    Running,
+    Completed,
+    /// Stopped at `steps_done`; requesting the action again starts a new job.
+    Failed,
+}
+
+impl RunbookStatus {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "pending" => Ok(Self::Pending),
+            "running" => Ok(Self::Running),
+            "completed" => Ok(Self::Completed),
+            "failed" => Ok(Self::Failed),
+            other => Err(anyhow!("Unknown runbook status '{other}'")),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct RunbookJobQuery {
+    /// Only jobs in this state.
+    pub status: Option<RunbookStatus>,
+    /// Default 50, at most 500.
+    pub limit: Option<i64>,
+}
+
+/// A requested action and how far it has got.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RunbookJob {
+    pub id: String,
+    pub request: RunbookAction,
+    /// Corridor key, anchor id, epoch, or `ingestion`.
+    pub target: String,
+    pub status: RunbookStatus,
+    pub steps_total: i64,
+    pub steps_done: i64,
+    pub progress_percent: f64,
+    /// Step in progress, or the one that failed.
+    pub current_step: Option<String>,
+    /// Outcome details, for actions that report any.
+    #[schema(value_type = Option<Object>)]
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    pub requested_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+fn parse_time(row: &sqlx::sqlite::SqliteRow, column: &str) -> Result<DateTime<Utc>> {
+    let value: String = row.get(column);
+    Ok(DateTime::parse_from_rfc3339(&value)
+        .with_context(|| format!("Invalid {column} '{value}'"))?
+        .with_timezone(&Utc))
+}
+
+impl RunbookJob {
+    fn from_row(row: &sqlx::sqlite::SqliteRow) -> Result<Self> {
+        let steps_total: i64 = row.get("steps_total");
+        let steps_done: i64 = row.get("steps_done");
+        Ok(Self {
+            id: row.get("id"),
+            request: serde_json::from_str(&row.get::<String, _>("params"))
+                .context("Invalid runbook action params")?,
+            target: row.get("target"),
+            status: RunbookStatus::parse(&row.get::<String, _>("status"))?,
+            steps_total,
+            steps_done,
+            progress_percent: if steps_total > 0 {
+                (steps_done as f64 / steps_total as f64 * 1000.0).round() / 10.0
+            } else {
+                100.0
+            },
+            current_step: row.get("current_step"),
+            result: row
+                .get::<Option<String>, _>("result")
+                .map(|result| serde_json::from_str(&result))
+                .transpose()
+                .context("Invalid runbook action result")?,
+            error: row.get("error"),
+            requested_by: row.get("requested_by"),
+            created_at: parse_time(row, "created_at")?,
+            updated_at: parse_time(row, "updated_at")?,
+        })
+    }
+}
+
+pub struct RunbookService {
+    db: Arc<Database>,
+    rollups: Option<Arc<dyn HourlyRollups>>,
+    read_models: Option<Arc<dyn ReadModels>>,
+    ingestion: Option<Arc<dyn IngestionControl>>,
+    contract: Option<Arc<dyn SnapshotAnchorTarget>>,
+    leader: Option<Arc<LeaderElection>>,
+}
+
+impl RunbookService {
+    #[must_use]
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            rollups: None,
+            read_models: None,
+            ingestion: None,
+            contract: None,
+            leader: None,
+        }
+    }
+
+    /// Needed by corridor rebuilds.
+    #[must_use]
+    pub fn with_rollups(mut self, rollups: Arc<dyn HourlyRollups>) -> Self {
+        self.rollups = Some(rollups);
+        self
+    }
+
+    /// Needed by corridor rebuilds and anchor resyncs.
+    #[must_use]
+    pub fn with_read_models(mut self, read_models: Arc<dyn ReadModels>) -> Self {
+        self.read_models = Some(read_models);
+        self
+    }
+
+    /// Needed by anchor resyncs and ingestion restarts.
+    #[must_use]
+    pub fn with_ingestion(mut self, ingestion: Arc<dyn IngestionControl>) -> Self {
+        self.ingestion = Some(ingestion);
+        self
+    }
+
+    /// Needed by epoch re-anchoring.
+    #[must_use]
+    pub fn with_contract(mut self, contract: Arc<dyn SnapshotAnchorTarget>) -> Self {
+        self.contract = Some(contract);
+        self
+    }
+
+    /// Only run jobs on the replica holding the ingestion lease.
+    #[must_use]
+    pub fn with_leader_election(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
+    /// Queue `action` after checking its target exists. If the same action on the
+    /// same target is already pending or running, that job is returned instead.
+    pub async fn request(
+        &self,
+        action: &RunbookAction,
+        requested_by: &str,
+        now: DateTime<Utc>,
+    ) -> Result<RunbookJob> {
+        self.validate(action).await?;
+        let target = action.target();
+
+        let existing = sqlx::query(
+            r"
+            SELECT * FROM runbook_actions
+            WHERE action = ? AND target = ? AND status IN ('pending', 'running')
+            ORDER BY created_at ASC
+            LIMIT 1
+            ",
+        )
+        .bind(action.name())
+        .bind(&target)
+        .fetch_optional(self.db.pool())
+        .await
+        .context("Failed to look up outstanding runbook actions")?;
+        if let Some(row) = existing {
+            let job = RunbookJob::from_row(&row)?;
+            if &job.request == action {
+                return Ok(job);
+            }
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let steps_total = i64::try_from(action.steps(now)?.len())?;
+        sqlx::query(
+            r"
+            INSERT INTO runbook_actions (
+                id, action, target, params, status, steps_total, requested_by,
+                created_at, updated_at
+            ) VALUES (?, ?, ?, ?, 'pending', ?, ?, ?, ?)
+            ",
+        )
+        .bind(&id)
+        .bind(action.name())
+        .bind(&target)
+        .bind(serde_json::to_string(action)?)
+        .bind(steps_total)
+        .bind(requested_by)
+        .bind(now.to_rfc3339())
+        .bind(now.to_rfc3339())
+        .execute(self.db.pool())
+        .await
+        .context("Failed to record runbook action")?;
+
+        let job = self
+            .get(&id)
+            .await?
+            .ok_or_else(|| anyhow!("Runbook action {id} vanished after insert"))?;
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "runbook.requested",
+            job_id = %id,
+            action = action.name(),
+            job_target = %target,
+            requested_by = %requested_by,
+            "Runbook action requested"
+        );
+        self.audit(&job, "requested").await;
+        Ok(job)
+    }
+
+    async fn validate(&self, action: &RunbookAction) -> Result<()> {
+        match action {
+            RunbookAction::RebuildCorridor {
+                corridor_key,
+                hours,
+            } => {
+                if corridor_key.trim().is_empty() {
+                    bail!("corridor_key must not be empty");
+                }
+                if let Some(hours) = hours {
+                    if !(1..=MAX_REBUILD_HOURS).contains(hours) {
+                        bail!("hours must be between 1 and {MAX_REBUILD_HOURS}");
+                    }
+                }
+                let known: Option<i64> = sqlx::query_scalar(
+                    "SELECT 1 FROM corridor_metrics_hourly WHERE corridor_key = ? LIMIT 1",
+                )
+                .bind(corridor_key)
+                .fetch_optional(self.db.pool())
+                .await?;
+                if known.is_none() {
+                    bail!("No hourly metrics recorded for corridor {corridor_key}");
+                }
+            }
+            RunbookAction::ResyncAnchor { anchor_id } => {
+                self.anchor_account(*anchor_id).await?;
+            }
+            RunbookAction::ReanchorEpoch { epoch } => {
+                self.stored_hash(*epoch).await?;
+            }
+            RunbookAction::RestartIngestion => {}
+        }
+        Ok(())
+    }
+
+    async fn anchor_account(&self, anchor_id: Uuid) -> Result<String> {
+        sqlx::query_scalar("SELECT stellar_account FROM anchors WHERE id = ?")
+            .bind(anchor_id.to_string())
+            .fetch_optional(self.db.pool())
+            .await?
+            .ok_or_else(|| anyhow!("No anchor {anchor_id}"))
+    }
+
+    /// Latest snapshot hash stored for `epoch`.
+    async fn stored_hash(&self, epoch: u64) -> Result<String> {
+        sqlx::query_scalar(
+            r"
+            SELECT hash FROM snapshots
+            WHERE entity_type = 'analytics_snapshot' AND epoch = ? AND hash IS NOT NULL
+            ORDER BY created_at DESC
+            LIMIT 1
+            ",
+        )
+        .bind(i64::try_from(epoch)?)
+        .fetch_optional(self.db.pool())
+        .await?
+        .ok_or_else(|| anyhow!("No snapshot hash stored for epoch {epoch}"))
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<RunbookJob>> {
+        let row = sqlx::query("SELECT * FROM runbook_actions WHERE id = ?")
+            .bind(id)
+            .fetch_optional(self.db.pool())
+            .await
+            .context("Failed to load runbook action")?;
+        row.as_ref().map(RunbookJob::from_row).transpose()
+    }
+
+    /// Jobs, newest first.
+    pub async fn list(&self, query: &RunbookJobQuery) -> Result<Vec<RunbookJob>> {
+        let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+        if !(1..=MAX_LIST_LIMIT).contains(&limit) {
+            bail!("limit must be between 1 and {MAX_LIST_LIMIT}");
+        }
+        let rows = sqlx::query(
+            r"
+            SELECT * FROM runbook_actions
+            WHERE ? IS NULL OR status = ?
+            ORDER BY created_at DESC
+            LIMIT ?
+            ",
+        )
+        .bind(query.status.map(RunbookStatus::as_str))
+        .bind(query.status.map(RunbookStatus::as_str))
+        .bind(limit)
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to list runbook actions")?;
+        rows.iter().map(RunbookJob::from_row).collect()
+    }
+
+    async fn update_progress(
+        &self,
+        id: &str,
+        status: RunbookStatus,
+        steps_done: i64,
+        current_step: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r"
+            UPDATE runbook_actions
+            SET status = ?, steps_done = ?, current_step = ?, error = ?, updated_at = ?
+            WHERE id = ?
+            ",
+        )
+        .bind(status.as_str())
+        .bind(steps_done)
+        .bind(current_step)
+        .bind(error)
+        .bind(Utc::now().to_rfc3339())
+        .bind(id)
+        .execute(self.db.pool())
+        .await
+        .context("Failed to update runbook progress")?;
+        Ok(())
+    }
+
+    /// Run the oldest outstanding job, resuming an interrupted one first. Returns
+    /// the job as it ended up, or `None` when nothing is outstanding. A failing step
+    /// marks the job failed rather than returning an error.
+    pub async fn run_next(&self) -> Result<Option<RunbookJob>> {
+        let row = sqlx::query(
+            r"
+            SELECT * FROM runbook_actions
+            WHERE status IN ('running', 'pending')
+            ORDER BY status = 'running' DESC, created_at ASC
+            LIMIT 1
+            ",
+        )
+        .fetch_optional(self.db.pool())
+        .await
+        .context("Failed to load next runbook action")?;
+        let Some(job) = row.as_ref().map(RunbookJob::from_row).transpose()? else {
+            return Ok(None);
+        };
+
+        let steps = job.request.steps(job.created_at)?;
+        let start = usize::try_from(job.steps_done)?;
+        for (done, step) in steps.iter().enumerate().skip(start) {
+            let done = i64::try_from(done)?;
+            let description = step.describe();
+            self.update_progress(
+                &job.id,
+                RunbookStatus::Running,
+                done,
+                Some(&description),
+                None,
+            )
+            .await?;
+            match self.run_step(step).await {
+                Ok(Some(result)) => {
+                    sqlx::query("UPDATE runbook_actions SET result = ? WHERE id = ?")
+                        .bind(result.to_string())
+                        .bind(&job.id)
+                        .execute(self.db.pool())
+                        .await
+                        .context("Failed to record runbook result")?;
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log_event!(
+                        warn,
+                        Subsystem::Jobs,
+                        "runbook.job_failed",
+                        job_id = %job.id,
+                        step = %description,
+                        error = format!("{e:#}"),
+                        "Runbook job failed"
+                    );
+                    if matches!(job.request, RunbookAction::RestartIngestion) {
+                        // Never leave ingestion drained behind a failed restart
+                        if let Some(ingestion) = &self.ingestion {
+                            if let Err(e) = ingestion.resume().await {
+                                log_event!(
+                                    warn,
+                                    Subsystem::Jobs,
+                                    "runbook.ingestion_resume_failed",
+                                    job_id = %job.id,
+                                    error = format!("{e:#}"),
+                                    "Failed to resume ingestion after failed restart"
+                                );
+                            }
+                        }
+                    }
+                    self.update_progress(
+                        &job.id,
+                        RunbookStatus::Failed,
+                        done,
+                        Some(&description),
+                        Some(&format!("{e:#}")),
+                    )
+                    .await?;
+                    return self.finish(&job.id).await;
+                }
+            }
+        }
+
+        self.update_progress(
+            &job.id,
+            RunbookStatus::Completed,
+            job.steps_total,
+            None,
+            None,
+        )
+        .await?;
+        log_event!(
+            info,
+            Subsystem::Jobs,
+            "runbook.job_completed",
+            job_id = %job.id,
+            action = job.request.name(),
+            job_target = %job.target,
+            "Runbook job completed"
+        );
+        self.finish(&job.id).await
+    }
+
+    async fn finish(&self, id: &str) -> Result<Option<RunbookJob>> {
+        let job = self.get(id).await?;
+        if let Some(job) = &job {
+            self.audit(job, job.status.as_str()).await;
+        }
+        Ok(job)
+    }
+
+    async fn run_step(&self, step: &Step) -> Result<Option<serde_json::Value>> {
+        match step {
+            Step::RecomputeHour(hour) => {
+                self.rollups()?.recompute_hour(*hour).await?;
+            }
+            Step::RefreshCorridor(key) => self.read_models()?.refresh_corridor(key).await?,
+            Step::InvalidateAggregates => {
+                SnapshotAggregateCache::new(Arc::clone(&self.db))
+                    .invalidate()
+                    .await?;
+            }
+            Step::SyncAnchor(anchor_id) => {
+                let account = self.anchor_account(*anchor_id).await?;
+                self.ingestion()?.sync_anchor(&account).await?;
+            }
+            Step::RefreshAnchor(anchor_id) => {
+                self.read_models()?.refresh_anchor(*anchor_id).await?;
+            }
+            Step::Reanchor(epoch) => return self.reanchor(*epoch).await.map(Some),
+            Step::Drain => self.ingestion()?.drain().await,
+            Step::Resume => self.ingestion()?.resume().await?,
+        }
+        Ok(None)
+    }
+
+    async fn reanchor(&self, epoch: u64) -> Result<serde_json::Value> {
+        let contract = self
+            .contract
+            .as_ref()
+            .ok_or_else(|| anyhow!("No snapshot contract configured"))?;
+        let hash = self.stored_hash(epoch).await?;
+        match contract.anchored_hash(epoch).await? {
+            Some(existing) if existing.eq_ignore_ascii_case(&hash) => {
+                return Ok(serde_json::json!({ "status": "already_anchored", "hash": hash }));
+            }
+            Some(existing) => {
+                bail!("Contract already holds a different hash for epoch {epoch}: {existing}");
+            }
+            None => {}
+        }
+
+        let bytes: [u8; 32] = hex::decode(&hash)
+            .context("Stored snapshot hash is not valid hex")?
+            .try_into()
+            .map_err(|_| anyhow!("Stored snapshot hash is not 32 bytes"))?;
+        let submission = contract.submit(bytes, epoch).await?;
+        if !contract.verify(&hash, epoch).await? {
+            bail!("Hash not verifiable on the contract after submission");
+        }
+        Ok(serde_json::json!({
+            "status": "verified",
+            "hash": hash,
+            "transaction_hash": submission.transaction_hash,
+            "ledger": submission.ledger,
+        }))
+    }
+
+    fn rollups(&self) -> Result<&Arc<dyn HourlyRollups>> {
+        self.rollups
+            .as_ref()
+            .ok_or_else(|| anyhow!("Runbook has no rollup service"))
+    }
+
+    fn read_models(&self) -> Result<&Arc<dyn ReadModels>> {
+        self.read_models
+            .as_ref()
+            .ok_or_else(|| anyhow!("Runbook has no projection store"))
+    }
+
+    fn ingestion(&self) -> Result<&Arc<dyn IngestionControl>> {
+        self.ingestion
+            .as_ref()
+            .ok_or_else(|| anyhow!("Runbook has no ingestion service"))
+    }
+
+    /// Append to the admin audit log, chained onto the latest entry. Failures are
+    /// logged rather than returned so they never block the remediation itself.
+    async fn audit(&self, job: &RunbookJob, status: &str) {
+        let result = async {
+            let prev_hash: Option<String> = sqlx::query_scalar(
+                "SELECT hash FROM admin_audit_log ORDER BY timestamp DESC, created_at DESC LIMIT 1",
+            )
+            .fetch_optional(self.db.pool())
+            .await?;
+            self.db
+                .admin_audit_logger
+                .log_action(
+                    AUDIT_ACTION,
+                    &format!("{}:{}", job.request.name(), job.target),
+                    &job.requested_by,
+                    status,
+                    serde_json::json!({
+                        "job_id": job.id,
+                        "request": job.request,
+                        "steps_done": job.steps_done,
+                        "steps_total": job.steps_total,
+                        "result": job.result,
+                        "error": job.error,
+                    }),
+                    prev_hash.as_deref(),
+                )
+                .await
+        }
+        .await;
+        if let Err(e) = result {
+            log_event!(
+                warn,
+                Subsystem::Jobs,
+                "runbook.audit_failed",
+                job_id = %job.id,
+                error = format!("{e:#}"),
+                "Failed to audit runbook job"
+            );
+        }
+    }
+
+    pub async fn start(self: Arc<Self>) {
+        let mut ticker = tokio::time::interval(WORKER_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Some(leader) = &self.leader {
+                if !leader.is_leader(ROLE_INGESTION) {
+                    continue;
+                }
+            }
+            loop {
+                match self.run_next().await {
+                    Ok(Some(_)) => {}
+                    Ok(None) => break,
+                    Err(e) => {
+                        log_event!(
+                            warn,
+                            Subsystem::Jobs,
+                            "runbook.worker_failed",
+                            error = format!("{e:#}"),
+                            "Runbook worker failed"
+                        );
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::contract::SubmissionResult;
+    use crate::test_support::{self, migrations, CorridorHour};
+    use std::sync::Mutex;
+
+    const NGN: &str = "USDC:GA5Z->NGNC:GBNG";
+    const HASH: &str = "ab12000000000000000000000000000000000000000000000000000000000000";
+
+    #[derive(Default)]
+    struct Calls(Mutex<Vec<String>>);
+
+    impl Calls {
+        fn push(&self, call: impl Into<String>) {
+            self.0.lock().unwrap().push(call.into());
+        }
+
+        fn take(&self) -> Vec<String> {
+            std::mem::take(&mut *self.0.lock().unwrap())
+        }
+    }
+
+    #[derive(Default)]
+    struct Fakes {
+        calls: Calls,
+        fail_sync: bool,
+        anchored: Mutex<Option<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl HourlyRollups for Fakes {
+        async fn recompute_hour(&self, hour: DateTime<Utc>) -> Result<usize> {
+            self.calls.push(format!("hour {}", hour.format("%H")));
+            Ok(1)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ReadModels for Fakes {
+        async fn refresh_corridor(&self, corridor_key: &str) -> Result<()> {
+            self.calls.push(format!("corridor {corridor_key}"));
+            Ok(())
+        }
+
+        async fn refresh_anchor(&self, anchor_id: Uuid) -> Result<()> {
+            self.calls.push(format!("anchor {anchor_id}"));
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl IngestionControl for Fakes {
+        async fn drain(&self) {
+            self.calls.push("drain");
+        }
+
+        async fn resume(&self) -> Result<()> {
+            self.calls.push("resume");
+            if self.fail_sync {
+                bail!("RPC unavailable");
+            }
+            Ok(())
+        }
+
+        async fn sync_anchor(&self, stellar_account: &str) -> Result<()> {
+            self.calls.push(format!("sync {stellar_account}"));
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl SnapshotAnchorTarget for Fakes {
+        async fn anchored_hash(&self, _epoch: u64) -> Result<Option<String>> {
+            Ok(self.anchored.lock().unwrap().clone())
+        }
+
+        async fn submit(&self, hash: [u8; 32], epoch: u64) -> Result<SubmissionResult> {
+            *self.anchored.lock().unwrap() = Some(hex::encode(hash));
+            Ok(SubmissionResult {
+                transaction_hash: "tx1".to_string(),
+                epoch,
+                ledger: 42,
+                timestamp: 0,
+            })
+        }
+
+        async fn verify(&self, hash: &str, _epoch: u64) -> Result<bool> {
+            Ok(self.anchored.lock().unwrap().as_deref() == Some(hash))
+        }
+    }
+
+    async fn setup() -> Arc<Database> {
+        test_support::sqlite_db(&[
+            migrations::ANCHORS,
+            migrations::METRICS_CORRIDORS_SNAPSHOTS,
+            migrations::CORRIDOR_AGGREGATES,
+            migrations::ADMIN_AUDIT_LOG,
+            migrations::SNAPSHOT_AGGREGATE_CACHE,
+            migrations::RUNBOOK_ACTIONS,
+        ])
+        .await
+    }
+
+    fn service(db: &Arc<Database>, fakes: &Arc<Fakes>) -> RunbookService {
+        RunbookService::new(Arc::clone(db))
+            .with_rollups(fakes.clone())
+            .with_read_models(fakes.clone())
+            .with_ingestion(fakes.clone())
+            .with_contract(fakes.clone())
+    }
+
+    fn at(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    async fn audit_statuses(db: &Database) -> Vec<String> {
+        sqlx::query_scalar("SELECT status FROM admin_audit_log ORDER BY rowid")
+            .fetch_all(db.pool())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_corridor_runs_each_step_and_audits() {
+        let db = setup().await;
+        test_support::insert_hour(
+            db.pool(),
+            &CorridorHour {
+                corridor_key: NGN,
+                assets: ["USDC", "GA5Z", "NGNC", "GBNG"],
+                hour: at("2024-03-01T09:00:00Z"),
+                total: 10,
+                successful: 9,
+                volume_usd: 100.0,
+                ..CorridorHour::default()
+            },
+        )
+        .await;
+        let fakes = Arc::new(Fakes::default());
+        let runbook = service(&db, &fakes);
+        let now = at("2024-03-01T10:20:00Z");
+        let rebuild = RunbookAction::RebuildCorridor {
+            corridor_key: NGN.to_string(),
+            hours: Some(2),
+        };
+
+        let job = runbook.request(&rebuild, "admin-1", now).await.unwrap();
+        assert_eq!((job.status, job.steps_total), (RunbookStatus::Pending, 4));
+        // Asking again while it is queued returns the same job
+        let again = runbook.request(&rebuild, "admin-2", now).await.unwrap();
+        assert_eq!(again.id, job.id);
+
+        let unknown = RunbookAction::RebuildCorridor {
+            corridor_key: "XLM->BRL".to_string(),
+            hours: None,
+        };
+        assert!(runbook.request(&unknown, "admin-1", now).await.is_err());
+
+        let done = runbook.run_next().await.unwrap().unwrap();
+        assert_eq!(done.status, RunbookStatus::Completed);
+        assert_eq!((done.steps_done, done.progress_percent), (4, 100.0));
+        assert_eq!(
+            fakes.calls.take(),
+            ["hour 09", "hour 10", &format!("corridor {NGN}")]
+        );
+        assert!(runbook.run_next().await.unwrap().is_none());
+        assert_eq!(audit_statuses(&db).await, ["requested", "completed"]);
+    }
+
+    #[tokio::test]
+    async fn test_resync_anchor_and_reanchor_epoch() {
+        let db = setup().await;
+        let anchor_id = Uuid::new_v4();
+        sqlx::query("INSERT INTO anchors (id, name, stellar_account) VALUES (?, 'Acme', 'GACME')")
+            .bind(anchor_id.to_string())
+            .execute(db.pool())
+            .await
+            .unwrap();
+        sqlx::query(
+            r"
+            INSERT INTO snapshots (id, entity_id, entity_type, data, hash, epoch, timestamp)
+            VALUES ('s7', 'all', 'analytics_snapshot', '{}', ?, 7, '2024-03-01T00:00:00Z')
+            ",
+        )
+        .bind(HASH)
+        .execute(db.pool())
+        .await
+        .unwrap();
+        let fakes = Arc::new(Fakes::default());
+        let runbook = service(&db, &fakes);
+        let now = Utc::now();
+
+        assert!(runbook
+            .request(
+                &RunbookAction::ResyncAnchor {
+                    anchor_id: Uuid::new_v4()
+                },
+                "admin-1",
+                now
+            )
+            .await
+            .is_err());
+        runbook
+            .request(&RunbookAction::ResyncAnchor { anchor_id }, "admin-1", now)
+            .await
+            .unwrap();
+        let resynced = runbook.run_next().await.unwrap().unwrap();
+        assert_eq!(resynced.status, RunbookStatus::Completed);
+        assert_eq!(
+            fakes.calls.take(),
+            ["sync GACME".to_string(), format!("anchor {anchor_id}")]
+        );
+
+        assert!(runbook
+            .request(&RunbookAction::ReanchorEpoch { epoch: 8 }, "admin-1", now)
+            .await
+            .is_err());
+        runbook
+            .request(&RunbookAction::ReanchorEpoch { epoch: 7 }, "admin-1", now)
+            .await
+            .unwrap();
+        let anchored = runbook.run_next().await.unwrap().unwrap();
+        assert_eq!(anchored.status, RunbookStatus::Completed);
+        let result = anchored.result.unwrap();
+        assert_eq!(result["status"], "verified");
+        assert_eq!(result["ledger"], 42);
+
+        // A second run finds the hash already on-chain
+        runbook
+            .request(&RunbookAction::ReanchorEpoch { epoch: 7 }, "admin-1", now)
+            .await
+            .unwrap();
+        let repeat = runbook.run_next().await.unwrap().unwrap();
+        assert_eq!(repeat.result.unwrap()["status"], "already_anchored");
+    }
+
+    #[tokio::test]
+    async fn test_failed_restart_resumes_ingestion() {
+        let db = setup().await;
+        let fakes = Arc::new(Fakes {
+            fail_sync: true,
+            ..Fakes::default()
+        });
+        let runbook = service(&db, &fakes);
+        runbook
+            .request(&RunbookAction::RestartIngestion, "admin-1", Utc::now())
+            .await
+            .unwrap();
+
+        let failed = runbook.run_next().await.unwrap().unwrap();
+        assert_eq!(failed.status, RunbookStatus::Failed);
+        assert_eq!(failed.steps_done, 1);
+        assert_eq!(failed.current_step.as_deref(), Some("Resuming ingestion"));
+        assert!(failed.error.unwrap().contains("RPC unavailable"));
+        assert_eq!(fakes.calls.take(), ["drain", "resume", "resume"]);
+        assert_eq!(audit_statuses(&db).await, ["requested", "failed"]);
+
+        let failed_only = runbook
+            .list(&RunbookJobQuery {
+                status: Some(RunbookStatus::Failed),
+                limit: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(failed_only.len(), 1);
+    }
+}
@@ -1,19 +1,19 @@
+use crate::alert_bus::AlertSubscription;
 use crate::alerts::{Alert, AlertType};
 use anyhow::{Context, Result};
 use reqwest::{Client, StatusCode};
-use tokio::sync::broadcast;
 
 /// Slack Bot Service for sending alerts to Slack channels
 pub struct SlackBotService {
     webhook_url: String,
     http_client: Client,
-    alert_rx: broadcast::Receiver<Alert>,
+    alert_rx: AlertSubscription,
 }
 
 impl SlackBotService {
     /// Create a new `SlackBotService`
     #[must_use]
-    pub fn new(webhook_url: String, alert_rx: broadcast::Receiver<Alert>) -> Self {
+    pub fn new(webhook_url: String, alert_rx: AlertSubscription) -> Self {
         let http_client = Client::builder()
             .timeout(std::time::Duration::from_secs(5))
             .build()
@@ -30,7 +30,7 @@ impl SlackBotService {
     pub async fn start(mut self) {
         tracing::info!("Slack Bot Service started, listening for alerts");
 
-        while let Ok(alert) = self.alert_rx.recv().await {
+        while let Some(alert) = self.alert_rx.recv().await {
             if let Err(e) = self.send_alert_to_slack(&alert).await {
                 tracing::error!("Failed to send alert to Slack: {}", e);
             }
@@ -45,6 +45,9 @@ impl SlackBotService {
             AlertType::LiquidityDecrease => ("Liquidity Decrease", "#E8912D", "🟠"),
             AlertType::AnchorStatusChange => ("Anchor Status Change", "#36A64F", "🔵"),
             AlertType::AnchorMetricChange => ("Anchor Metric Change", "#2EB67D", "📊"),
+            AlertType::NetworkProtocolUpgrade => ("Network Protocol Upgrade", "#611F69", "⚠️"),
+            AlertType::SlaBreach => ("Corridor SLA Breach", "#E01E5A", "🚨"),
+            AlertType::StateDivergence => ("State Divergence", "#E01E5A", "🧭"),
         };
 
         let mut fields = vec![
@@ -1,6 +1,10 @@
+use crate::cluster::{LeaderElection, ROLE_SNAPSHOT_SUBMISSION};
 use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
 use crate::snapshot::schema::{
-    AnalyticsSnapshot, SnapshotAnchorMetrics, SnapshotCorridorMetrics, SCHEMA_VERSION,
+    AnalyticsSnapshot, SnapshotAnchorMetrics, SnapshotCorridorMetrics, SnapshotRegionPairMetrics,
+    SCHEMA_VERSION,
 };
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
@@ -10,11 +14,13 @@ use sha2::{Digest, Sha256};
 use sqlx::Row;
 use std::collections::BTreeMap;
 use std::sync::Arc;
-use tracing::{debug, error, info, warn};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use super::contract::{ContractService, SubmissionResult};
 use super::event_indexer::{EventIndexer, VerificationSummary};
+use super::geography::{aggregate_region_pairs, CorridorFlow, GeographyService};
+use super::snapshot_aggregates::SnapshotAggregateCache;
 
 /// Result of snapshot generation and submission process
 #[derive(Debug, Clone, Serialize)]
@@ -43,6 +49,7 @@ pub struct SnapshotService {
     db: Arc<Database>,
     contract_service: Option<Arc<ContractService>>,
     event_indexer: Option<Arc<EventIndexer>>,
+    leader: Option<Arc<LeaderElection>>,
 }
 
 impl SnapshotService {
@@ -57,9 +64,18 @@ impl SnapshotService {
             db,
             contract_service,
             event_indexer,
+            leader: None,
         }
     }
 
+    /// Refuse on-chain submission unless this instance leads the snapshot submission
+    /// role, so two instances never race to anchor the same epoch.
+    #[must_use]
+    pub fn with_leader_election(mut self, leader: Arc<LeaderElection>) -> Self {
+        self.leader = Some(leader);
+        self
+    }
+
     /// Generate a complete analytics snapshot with hash generation and submission
     ///
     /// This is the main entry point that fulfills all acceptance criteria:
@@ -75,6 +91,15 @@ impl SnapshotService {
     ) -> Result<SnapshotGenerationResult> {
         info!("Starting snapshot generation for epoch {}", epoch);
 
+        if let (Some(_), Some(leader)) = (&self.contract_service, &self.leader) {
+            if !leader.is_leader(ROLE_SNAPSHOT_SUBMISSION) {
+                anyhow::bail!(
+                    "Instance {} is not the snapshot submission leader; see /api/admin/cluster",
+                    leader.instance_id()
+                );
+            }
+        }
+
         // Step 1: Aggregate all metrics
         let snapshot = self
             .aggregate_all_metrics(epoch)
@@ -149,149 +174,38 @@ impl SnapshotService {
         let timestamp = Utc::now();
         let mut snapshot = AnalyticsSnapshot::new(epoch, timestamp);
 
-        // Aggregate anchor metrics
-        let anchor_metrics = self
-            .aggregate_anchor_metrics()
+        // Only anchors and corridors that changed since the last epoch are recomputed
+        let aggregates = SnapshotAggregateCache::new(self.db.clone())
+            .refresh(timestamp)
             .await
-            .context("Failed to aggregate anchor metrics")?;
+            .context("Failed to refresh anchor and corridor aggregates")?;
+        info!(
+            "Recomputed {} anchors and {} corridors for epoch {}",
+            aggregates.stats.anchors_recomputed, aggregates.stats.corridors_recomputed, epoch
+        );
 
-        for metrics in anchor_metrics {
+        for metrics in aggregates.anchors {
             snapshot.add_anchor_metrics(metrics);
         }
-
-        // Aggregate corridor metrics
-        let corridor_metrics = self
-            .aggregate_corridor_metrics()
-            .await
-            .context("Failed to aggregate corridor metrics")?;
-
-        for metrics in corridor_metrics {
+        for metrics in aggregates.corridors {
             snapshot.add_corridor_metrics(metrics);
         }
 
-        Ok(snapshot)
-    }
-
-    /// Aggregate anchor metrics from database
-    async fn aggregate_anchor_metrics(&self) -> Result<Vec<SnapshotAnchorMetrics>> {
-        let query = r"
-            SELECT
-                id,
-                name,
-                stellar_account,
-                total_transactions,
-                successful_transactions,
-                failed_transactions,
-                total_volume_usd,
-                avg_settlement_time_ms,
-                reliability_score,
-                status
-            FROM anchors
-            WHERE status != 'inactive'
-            ORDER BY id
-        ";
-
-        let rows = sqlx::query(query)
-            .fetch_all(self.db.pool())
-            .await
-            .context("Failed to fetch anchor data")?;
-
-        let mut metrics = Vec::new();
-
-        for row in rows {
-            let total_transactions: i64 = row.get("total_transactions");
-            let successful_transactions: i64 = row.get("successful_transactions");
-            let failed_transactions: i64 = row.get("failed_transactions");
-
-            let success_rate = if total_transactions > 0 {
-                successful_transactions as f64 / total_transactions as f64
-            } else {
-                0.0
-            };
-
-            let failure_rate = if total_transactions > 0 {
-                failed_transactions as f64 / total_transactions as f64
-            } else {
-                0.0
-            };
-
-            let anchor_metrics = SnapshotAnchorMetrics {
-                id: Uuid::parse_str(&row.get::<String, _>("id"))
-                    .context("Invalid anchor ID format")?,
-                name: row.get("name"),
-                stellar_account: row.get("stellar_account"),
-                success_rate,
-                failure_rate,
-                reliability_score: row.get("reliability_score"),
-                total_transactions,
-                successful_transactions,
-                failed_transactions,
-                avg_settlement_time_ms: row.get("avg_settlement_time_ms"),
-                volume_usd: row.get("total_volume_usd"),
-                status: row.get("status"),
-            };
-
-            metrics.push(anchor_metrics);
-        }
-
-        debug!("Aggregated {} anchor metrics", metrics.len());
-        Ok(metrics)
-    }
-
-    /// Aggregate corridor metrics from database
-    async fn aggregate_corridor_metrics(&self) -> Result<Vec<SnapshotCorridorMetrics>> {
-        let query = r"
-            SELECT
-                cm.id,
-                cm.corridor_key,
-                cm.asset_a_code,
-                cm.asset_a_issuer,
-                cm.asset_b_code,
-                cm.asset_b_issuer,
-                cm.total_transactions,
-                cm.successful_transactions,
-                cm.failed_transactions,
-                cm.success_rate,
-                cm.volume_usd,
-                cm.avg_settlement_latency_ms,
-                cm.liquidity_depth_usd
-            FROM corridor_metrics cm
-            WHERE cm.date >= datetime('now', '-1 day')
-            GROUP BY cm.corridor_key
-            HAVING cm.date = MAX(cm.date)
-            ORDER BY cm.corridor_key
-        ";
-
-        let rows = sqlx::query(query)
-            .fetch_all(self.db.pool())
+        // Roll the corridors up by region pair using the operator geography mappings
+        let directory = GeographyService::new(self.db.clone())
+            .directory()
             .await
-            .context("Failed to fetch corridor metrics")?;
-
-        let mut metrics = Vec::new();
+            .context("Failed to load geography mappings")?;
+        let region_pairs = aggregate_region_pairs(
+            &directory,
+            snapshot.corridor_metrics.iter().map(CorridorFlow::from),
+        );
 
-        for row in rows {
-            let corridor_metrics = SnapshotCorridorMetrics {
-                id: Uuid::parse_str(&row.get::<String, _>("id"))
-                    .context("Invalid corridor metrics ID format")?,
-                corridor_key: row.get("corridor_key"),
-                source_asset_code: row.get("asset_a_code"),
-                source_asset_issuer: row.get("asset_a_issuer"),
-                destination_asset_code: row.get("asset_b_code"),
-                destination_asset_issuer: row.get("asset_b_issuer"),
-                total_transactions: row.get("total_transactions"),
-                successful_transactions: row.get("successful_transactions"),
-                failed_transactions: row.get("failed_transactions"),
-                success_rate: row.get("success_rate"),
-                volume_usd: row.get("volume_usd"),
-                avg_settlement_latency_ms: row.get("avg_settlement_latency_ms"),
-                liquidity_depth_usd: row.get("liquidity_depth_usd"),
-            };
-
-            metrics.push(corridor_metrics);
+        for metrics in region_pairs {
+            snapshot.add_region_pair_metrics(metrics.into());
         }
 
-        debug!("Aggregated {} corridor metrics", metrics.len());
-        Ok(metrics)
+        Ok(snapshot)
     }
 
     /// Store snapshot and hash in database
@@ -325,6 +239,75 @@ impl SnapshotService {
         Ok(snapshot_id)
     }
 
+    /// Regenerate the stored snapshots taken at or after `since` that were never
+    /// anchored on-chain, so drafts reflect data backfilled into that period.
+    /// Anchored snapshots are left untouched; returns `(redrafted, anchored)` counts.
+    pub async fn redraft_unanchored_since(&self, since: DateTime<Utc>) -> Result<(u32, u32)> {
+        let rows = sqlx::query(
+            r"
+            SELECT s.id, s.epoch,
+                   COALESCE(s.verification_status, 'pending') AS verification_status,
+                   EXISTS (
+                       SELECT 1 FROM contract_events e
+                       WHERE e.event_type = 'SNAP_SUB' AND e.epoch = s.epoch
+                   ) AS submitted
+            FROM snapshots s
+            WHERE s.entity_type = 'analytics_snapshot'
+              AND s.epoch IS NOT NULL
+              AND s.timestamp >= ?
+            ORDER BY s.epoch ASC
+            ",
+        )
+        .bind(since)
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to load snapshots affected by backfill")?;
+
+        // Backfill recomputes can delete hourly rollups, which the cache cannot see
+        SnapshotAggregateCache::new(self.db.clone())
+            .invalidate()
+            .await?;
+
+        let (mut redrafted, mut anchored) = (0, 0);
+        for row in rows {
+            let status: String = row.get("verification_status");
+            if status != "pending" || row.get::<bool, _>("submitted") {
+                anchored += 1;
+                continue;
+            }
+
+            let id: String = row.get("id");
+            let epoch = row.get::<i64, _>("epoch") as u64;
+            let snapshot = self
+                .aggregate_all_metrics(epoch)
+                .await
+                .with_context(|| format!("Failed to re-aggregate snapshot for epoch {epoch}"))?;
+            let canonical_json = Self::serialize_deterministically(snapshot.clone())
+                .context("Failed to serialize snapshot deterministically")?;
+            let hash_hex = hex::encode(Self::compute_sha256_hash_bytes(&canonical_json));
+
+            sqlx::query("UPDATE snapshots SET data = ?, hash = ?, timestamp = ? WHERE id = ?")
+                .bind(&canonical_json)
+                .bind(&hash_hex)
+                .bind(snapshot.timestamp)
+                .bind(&id)
+                .execute(self.db.pool())
+                .await
+                .context("Failed to update redrafted snapshot")?;
+            log_event!(
+                info,
+                Subsystem::Snapshot,
+                "snapshot.redrafted",
+                epoch,
+                hash = %hash_hex,
+                "Redrafted pending snapshot"
+            );
+            redrafted += 1;
+        }
+
+        Ok((redrafted, anchored))
+    }
+
     /// Verify that the submission was successful by querying the contract
     /// Verify that a snapshot submission was successful by checking on-chain
     ///
@@ -421,6 +404,20 @@ impl SnapshotService {
             Value::Array(corridor_metrics),
         );
 
+        // Region pairs only exist from schema version 2 and are left out when empty,
+        // which keeps the canonical form of version 1 snapshots unchanged
+        if !snapshot.region_pair_metrics.is_empty() {
+            let region_pair_metrics: Vec<Value> = snapshot
+                .region_pair_metrics
+                .into_iter()
+                .map(|m| Self::serialize_region_pair_metrics(&m))
+                .collect();
+            map.insert(
+                "region_pair_metrics".to_string(),
+                Value::Array(region_pair_metrics),
+            );
+        }
+
         // Convert to JSON string with no extra whitespace
         // Note: serde_json::Map uses IndexMap internally which preserves insertion order.
         // Since we iterate over BTreeMap (sorted), insertion order is sorted, ensuring determinism.
@@ -561,6 +558,50 @@ impl SnapshotService {
         Value::Object(json_map)
     }
 
+    /// Serialize region-pair metrics to a deterministic JSON value
+    fn serialize_region_pair_metrics(metrics: &SnapshotRegionPairMetrics) -> Value {
+        let mut map = BTreeMap::new();
+
+        map.insert(
+            "source_region".to_string(),
+            Value::String(metrics.source_region.clone()),
+        );
+        map.insert(
+            "destination_region".to_string(),
+            Value::String(metrics.destination_region.clone()),
+        );
+        map.insert(
+            "corridor_count".to_string(),
+            Value::Number(metrics.corridor_count.into()),
+        );
+        map.insert(
+            "total_transactions".to_string(),
+            Value::Number(metrics.total_transactions.into()),
+        );
+        map.insert(
+            "successful_transactions".to_string(),
+            Value::Number(metrics.successful_transactions.into()),
+        );
+        map.insert(
+            "failed_transactions".to_string(),
+            Value::Number(metrics.failed_transactions.into()),
+        );
+        map.insert(
+            "success_rate".to_string(),
+            Self::serialize_f64(metrics.success_rate),
+        );
+        map.insert(
+            "volume_usd".to_string(),
+            Self::serialize_f64(metrics.volume_usd),
+        );
+
+        let mut json_map = Map::new();
+        for (k, v) in map {
+            json_map.insert(k, v);
+        }
+        Value::Object(json_map)
+    }
+
     /// Serialize f64 to a deterministic JSON number representation
     ///
     /// This ensures that floating point numbers are always serialized
@@ -1161,4 +1202,41 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_region_pairs_only_serialized_when_present() {
+        let now = Utc::now();
+        let empty =
+            SnapshotService::serialize_deterministically(AnalyticsSnapshot::new(1, now)).unwrap();
+        assert!(!empty.contains("region_pair_metrics"));
+
+        let pair = |source: &str, destination: &str| SnapshotRegionPairMetrics {
+            source_region: source.to_string(),
+            destination_region: destination.to_string(),
+            corridor_count: 2,
+            total_transactions: 200,
+            successful_transactions: 190,
+            failed_transactions: 10,
+            success_rate: 95.0,
+            volume_usd: 1_500.0,
+        };
+        let mut snapshot1 = AnalyticsSnapshot::new(1, now);
+        snapshot1.add_region_pair_metrics(pair("EU", "West Africa"));
+        snapshot1.add_region_pair_metrics(pair("Asia", "EU"));
+        let mut snapshot2 = AnalyticsSnapshot::new(1, now);
+        snapshot2.add_region_pair_metrics(pair("Asia", "EU"));
+        snapshot2.add_region_pair_metrics(pair("EU", "West Africa"));
+
+        let json = SnapshotService::serialize_deterministically(snapshot1).unwrap();
+        assert_eq!(
+            json,
+            SnapshotService::serialize_deterministically(snapshot2).unwrap()
+        );
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed["region_pair_metrics"][0]["source_region"], "Asia");
+        assert_eq!(
+            parsed["region_pair_metrics"][1]["destination_region"],
+            "West Africa"
+        );
+    }
 }
@@ -0,0 +1,578 @@
+//! Incremental Snapshot Aggregates
+//!
+//! Snapshot generation used to recompute every anchor and corridor from scratch each
+//! epoch, which takes minutes once the rollup tables are large. The per-entity results
+//! are now kept in `snapshot_entity_aggregates`, and each refresh recomputes only the
+//! entities that are dirty since the previous one:
+//!
+//! - anchors whose row was updated, or that are not cached yet
+//! - corridors with an hourly rollup updated inside the window, or with an hour that
+//!   slid out of the window since the last refresh
+//!
+//! Everything else is read back from the cache, so the result matches a full
+//! recomputation. Deleting hourly rollups without writing replacements is not
+//! detected; callers doing that (backfill recomputes) call [`invalidate`] afterwards.
+//!
+//! [`invalidate`]: SnapshotAggregateCache::invalidate
+
+use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
+use crate::snapshot::schema::{SnapshotAnchorMetrics, SnapshotCorridorMetrics};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::{sqlite::SqliteRow, Row, Sqlite, Transaction};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Hours of hourly rollups summed into a snapshot's corridor metrics.
+pub const CORRIDOR_WINDOW_HOURS: i64 = 24;
+
+/// Corridor keys bound per `IN (...)` query, well under SQLite's parameter limit.
+const KEY_CHUNK: usize = 500;
+
+const ANCHOR: &str = "anchor";
+const CORRIDOR: &str = "corridor";
+
+/// How many entities a refresh had to recompute; the rest came from the cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RefreshStats {
+    pub anchors_recomputed: usize,
+    pub corridors_recomputed: usize,
+}
+
+/// Every active anchor's and corridor's metrics for one epoch, ordered by key.
+#[derive(Debug, Clone, Default)]
+pub struct EpochAggregates {
+    pub anchors: Vec<SnapshotAnchorMetrics>,
+    pub corridors: Vec<SnapshotCorridorMetrics>,
+    pub stats: RefreshStats,
+}
+
+pub struct SnapshotAggregateCache {
+    db: Arc<Database>,
+}
+
+impl SnapshotAggregateCache {
+    #[must_use]
+    pub const fn new(db: Arc<Database>) -> Self {
+        Self { db }
+    }
+
+    /// Bring the cache up to date as of `now` and return the aggregates for the epoch.
+    pub async fn refresh(&self, now: DateTime<Utc>) -> Result<EpochAggregates> {
+        let window_start = (now - Duration::hours(CORRIDOR_WINDOW_HOURS)).to_rfc3339();
+        let state = sqlx::query(
+            "SELECT window_start, refreshed_at FROM snapshot_aggregate_state WHERE id = 1",
+        )
+        .fetch_optional(self.db.pool())
+        .await
+        .context("Failed to load snapshot aggregate state")?;
+
+        let mut tx = self
+            .db
+            .pool()
+            .begin()
+            .await
+            .context("Failed to begin snapshot aggregate refresh")?;
+
+        // Without a previous refresh nothing in the cache can be trusted
+        let (previous_window_start, refreshed_at) = match state {
+            Some(row) => (
+                row.get::<String, _>("window_start"),
+                row.get("refreshed_at"),
+            ),
+            None => {
+                sqlx::query("DELETE FROM snapshot_entity_aggregates")
+                    .execute(&mut *tx)
+                    .await
+                    .context("Failed to clear snapshot aggregates")?;
+                (window_start.clone(), String::new())
+            }
+        };
+
+        let stats = RefreshStats {
+            anchors_recomputed: refresh_anchors(&mut tx, &refreshed_at, now).await?,
+            corridors_recomputed: refresh_corridors(
+                &mut tx,
+                &previous_window_start,
+                &window_start,
+                &refreshed_at,
+                now,
+            )
+            .await?,
+        };
+
+        sqlx::query(
+            r"
+            INSERT INTO snapshot_aggregate_state (id, window_start, refreshed_at)
+            VALUES (1, ?, ?)
+            ON CONFLICT(id) DO UPDATE SET
+                window_start = excluded.window_start,
+                refreshed_at = excluded.refreshed_at
+            ",
+        )
+        .bind(&window_start)
+        .bind(now.to_rfc3339())
+        .execute(&mut *tx)
+        .await
+        .context("Failed to record snapshot aggregate state")?;
+
+        let anchors = load_cached(&mut tx, ANCHOR).await?;
+        let corridors = load_cached(&mut tx, CORRIDOR).await?;
+        tx.commit()
+            .await
+            .context("Failed to commit snapshot aggregate refresh")?;
+
+        log_event!(
+            debug,
+            Subsystem::Snapshot,
+            "snapshot_aggregates.refreshed",
+            anchors_recomputed = stats.anchors_recomputed,
+            anchors = anchors.len(),
+            corridors_recomputed = stats.corridors_recomputed,
+            corridors = corridors.len(),
+            "Refreshed snapshot aggregates"
+        );
+        Ok(EpochAggregates {
+            anchors,
+            corridors,
+            stats,
+        })
+    }
+
+    /// Drop the cache so the next refresh recomputes every entity.
+    pub async fn invalidate(&self) -> Result<()> {
+        sqlx::query("DELETE FROM snapshot_aggregate_state")
+            .execute(self.db.pool())
+            .await
+            .context("Failed to invalidate snapshot aggregates")?;
+        Ok(())
+    }
+}
+
+/// Recompute anchors updated since `refreshed_at` or missing from the cache, and drop
+/// anchors that went inactive. Returns how many were recomputed.
+async fn refresh_anchors(
+    tx: &mut Transaction<'_, Sqlite>,
+    refreshed_at: &str,
+    now: DateTime<Utc>,
+) -> Result<usize> {
+    sqlx::query(
+        r"
+        DELETE FROM snapshot_entity_aggregates
+        WHERE entity_type = 'anchor'
+          AND entity_key NOT IN (SELECT id FROM anchors WHERE status != 'inactive')
+        ",
+    )
+    .execute(&mut **tx)
+    .await
+    .context("Failed to drop inactive anchors from snapshot aggregates")?;
+
+    let rows = sqlx::query(
+        r"
+        SELECT a.id, a.name, a.stellar_account, a.total_transactions,
+               a.successful_transactions, a.failed_transactions, a.total_volume_usd,
+               a.avg_settlement_time_ms, a.reliability_score, a.status
+        FROM anchors a
+        LEFT JOIN snapshot_entity_aggregates c
+            ON c.entity_type = 'anchor' AND c.entity_key = a.id
+        WHERE a.status != 'inactive'
+          AND (c.entity_key IS NULL OR datetime(a.updated_at) >= datetime(?))
+        ",
+    )
+    .bind(refreshed_at)
+    .fetch_all(&mut **tx)
+    .await
+    .context("Failed to fetch dirty anchors")?;
+
+    for row in &rows {
+        let id: String = row.get("id");
+        store(tx, ANCHOR, &id, &anchor_metrics(row)?, now).await?;
+    }
+    Ok(rows.len())
+}
+
+fn anchor_metrics(row: &SqliteRow) -> Result<SnapshotAnchorMetrics> {
+    let total_transactions: i64 = row.get("total_transactions");
+    let successful_transactions: i64 = row.get("successful_transactions");
+    let failed_transactions: i64 = row.get("failed_transactions");
+
+    let (success_rate, failure_rate) = if total_transactions > 0 {
+        (
+            successful_transactions as f64 / total_transactions as f64,
+            failed_transactions as f64 / total_transactions as f64,
+        )
+    } else {
+        (0.0, 0.0)
+    };
+
+    Ok(SnapshotAnchorMetrics {
+        id: Uuid::parse_str(&row.get::<String, _>("id")).context("Invalid anchor ID format")?,
+        name: row.get("name"),
+        stellar_account: row.get("stellar_account"),
+        success_rate,
+        failure_rate,
+        reliability_score: row.get("reliability_score"),
+        total_transactions,
+        successful_transactions,
+        failed_transactions,
+        avg_settlement_time_ms: row.get("avg_settlement_time_ms"),
+        volume_usd: row.get("total_volume_usd"),
+        status: row.get("status"),
+    })
+}
+
+/// Recompute corridors whose window contents changed since the last refresh: an
+/// hourly rollup inside the window was written, an hour slid out of the window, or
+/// the corridor is not cached yet. Returns how many were recomputed.
+async fn refresh_corridors(
+    tx: &mut Transaction<'_, Sqlite>,
+    previous_window_start: &str,
+    window_start: &str,
+    refreshed_at: &str,
+    now: DateTime<Utc>,
+) -> Result<usize> {
+    let dirty: Vec<String> = sqlx::query_scalar(
+        r"
+        SELECT corridor_key FROM corridor_metrics_hourly
+        WHERE hour_bucket >= ? AND hour_bucket < ?
+        UNION
+        SELECT corridor_key FROM corridor_metrics_hourly
+        WHERE hour_bucket >= ? AND datetime(updated_at) >= datetime(?)
+        UNION
+        SELECT h.corridor_key FROM corridor_metrics_hourly h
+        LEFT JOIN snapshot_entity_aggregates c
+            ON c.entity_type = 'corridor' AND c.entity_key = h.corridor_key
+        WHERE h.hour_bucket >= ? AND c.entity_key IS NULL
+        ",
+    )
+    .bind(previous_window_start)
+    .bind(window_start)
+    .bind(window_start)
+    .bind(refreshed_at)
+    .bind(window_start)
+    .fetch_all(&mut **tx)
+    .await
+    .context("Failed to find dirty corridors")?;
+
+    for keys in dirty.chunks(KEY_CHUNK) {
+        let placeholders = vec!["?"; keys.len()].join(", ");
+        let query = format!(
+            r"
+            SELECT id, corridor_key, asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer,
+                   total_transactions, successful_transactions, failed_transactions,
+                   volume_usd, avg_settlement_latency_ms, liquidity_depth_usd
+            FROM corridor_metrics_hourly
+            WHERE hour_bucket >= ? AND corridor_key IN ({placeholders})
+            ORDER BY corridor_key, hour_bucket
+            "
+        );
+        let mut rows = sqlx::query(&query).bind(window_start);
+        for key in keys {
+            rows = rows.bind(key);
+        }
+        let rows = rows
+            .fetch_all(&mut **tx)
+            .await
+            .context("Failed to fetch hourly rollups for dirty corridors")?;
+
+        let mut windows: BTreeMap<String, CorridorWindow> = BTreeMap::new();
+        for row in &rows {
+            let key: String = row.get("corridor_key");
+            match windows.get_mut(&key) {
+                Some(window) => window.add(row)?,
+                None => {
+                    windows.insert(key, CorridorWindow::new(row)?);
+                }
+            }
+        }
+
+        for key in keys {
+            match windows.remove(key) {
+                Some(window) => store(tx, CORRIDOR, key, &window.into_metrics(), now).await?,
+                // Every hour left the window
+                None => {
+                    sqlx::query(
+                        "DELETE FROM snapshot_entity_aggregates WHERE entity_type = 'corridor' AND entity_key = ?",
+                    )
+                    .bind(key)
+                    .execute(&mut **tx)
+                    .await
+                    .context("Failed to drop corridor from snapshot aggregates")?;
+                }
+            }
+        }
+    }
+    Ok(dirty.len())
+}
+
+/// Running totals of one corridor's hourly rollups, fed in hour order.
+struct CorridorWindow {
+    metrics: SnapshotCorridorMetrics,
+    weighted_latency: f64,
+    latency_weight: i64,
+}
+
+impl CorridorWindow {
+    fn new(row: &SqliteRow) -> Result<Self> {
+        let mut window = Self {
+            metrics: SnapshotCorridorMetrics {
+                id: Uuid::nil(),
+                corridor_key: row.get("corridor_key"),
+                source_asset_code: row.get("asset_a_code"),
+                source_asset_issuer: row.get("asset_a_issuer"),
+                destination_asset_code: row.get("asset_b_code"),
+                destination_asset_issuer: row.get("asset_b_issuer"),
+                total_transactions: 0,
+                successful_transactions: 0,
+                failed_transactions: 0,
+                success_rate: 0.0,
+                volume_usd: 0.0,
+                avg_settlement_latency_ms: None,
+                liquidity_depth_usd: 0.0,
+            },
+            weighted_latency: 0.0,
+            latency_weight: 0,
+        };
+        window.add(row)?;
+        Ok(window)
+    }
+
+    fn add(&mut self, row: &SqliteRow) -> Result<()> {
+        let total = row.get::<Option<i64>, _>("total_transactions").unwrap_or(0);
+        let metrics = &mut self.metrics;
+        // The latest hour identifies the corridor and carries its current liquidity
+        metrics.id = Uuid::parse_str(&row.get::<String, _>("id"))
+            .context("Invalid hourly corridor metrics ID format")?;
+        metrics.total_transactions += total;
+        metrics.successful_transactions += row
+            .get::<Option<i64>, _>("successful_transactions")
+            .unwrap_or(0);
+        metrics.failed_transactions += row
+            .get::<Option<i64>, _>("failed_transactions")
+            .unwrap_or(0);
+        metrics.volume_usd += row.get::<Option<f64>, _>("volume_usd").unwrap_or(0.0);
+        metrics.liquidity_depth_usd = row
+            .get::<Option<f64>, _>("liquidity_depth_usd")
+            .unwrap_or(0.0);
+        if let Some(latency) = row.get::<Option<i64>, _>("avg_settlement_latency_ms") {
+            self.weighted_latency += latency as f64 * total as f64;
+            self.latency_weight += total;
+        }
+        Ok(())
+    }
+
+    fn into_metrics(self) -> SnapshotCorridorMetrics {
+        let mut metrics = self.metrics;
+        if metrics.total_transactions > 0 {
+            metrics.success_rate =
+                metrics.successful_transactions as f64 / metrics.total_transactions as f64 * 100.0;
+        }
+        if self.latency_weight > 0 {
+            metrics.avg_settlement_latency_ms =
+                Some((self.weighted_latency / self.latency_weight as f64).round() as i32);
+        }
+        metrics
+    }
+}
+
+async fn store<T: Serialize>(
+    tx: &mut Transaction<'_, Sqlite>,
+    entity_type: &str,
+    entity_key: &str,
+    metrics: &T,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    sqlx::query(
+        r"
+        INSERT INTO snapshot_entity_aggregates (entity_type, entity_key, metrics, computed_at)
+        VALUES (?, ?, ?, ?)
+        ON CONFLICT(entity_type, entity_key) DO UPDATE SET
+            metrics = excluded.metrics,
+            computed_at = excluded.computed_at
+        ",
+    )
+    .bind(entity_type)
+    .bind(entity_key)
+    .bind(serde_json::to_string(metrics)?)
+    .bind(now.to_rfc3339())
+    .execute(&mut **tx)
+    .await
+    .context("Failed to store snapshot aggregate")?;
+    Ok(())
+}
+
+async fn load_cached<T: DeserializeOwned>(
+    tx: &mut Transaction<'_, Sqlite>,
+    entity_type: &str,
+) -> Result<Vec<T>> {
+    let rows: Vec<String> = sqlx::query_scalar(
+        "SELECT metrics FROM snapshot_entity_aggregates WHERE entity_type = ? ORDER BY entity_key",
+    )
+    .bind(entity_type)
+    .fetch_all(&mut **tx)
+    .await
+    .context("Failed to load snapshot aggregates")?;
+
+    rows.iter()
+        .map(|metrics| serde_json::from_str(metrics).context("Corrupt snapshot aggregate"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations};
+
+    const NGN: &str = "USDC:GA5Z->NGNC:GBNG";
+    const KES: &str = "USDC:GA5Z->KESC:GBKE";
+
+    async fn setup() -> (Arc<Database>, SnapshotAggregateCache) {
+        let db = test_support::sqlite_db(&[
+            migrations::ANCHORS,
+            migrations::CORRIDOR_AGGREGATES,
+            migrations::SNAPSHOT_AGGREGATE_CACHE,
+        ])
+        .await;
+        (db.clone(), SnapshotAggregateCache::new(db))
+    }
+
+    async fn insert_anchor(db: &Database, name: &str, updated_at: DateTime<Utc>) -> String {
+        let id = Uuid::new_v4().to_string();
+        sqlx::query(
+            r"
+            INSERT INTO anchors (id, name, stellar_account, total_transactions,
+                                 successful_transactions, failed_transactions, updated_at)
+            VALUES (?, ?, ?, 10, 9, 1, ?)
+            ",
+        )
+        .bind(&id)
+        .bind(name)
+        .bind(format!("G{name}"))
+        .bind(updated_at.to_rfc3339())
+        .execute(db.pool())
+        .await
+        .unwrap();
+        id
+    }
+
+    async fn upsert_hour(
+        db: &Database,
+        corridor_key: &str,
+        hour: DateTime<Utc>,
+        total: i64,
+        latency_ms: i64,
+        updated_at: DateTime<Utc>,
+    ) {
+        sqlx::query(
+            r"
+            INSERT INTO corridor_metrics_hourly (
+                id, corridor_key, asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer,
+                hour_bucket, total_transactions, successful_transactions, failed_transactions,
+                volume_usd, avg_settlement_latency_ms, liquidity_depth_usd, updated_at
+            )
+            VALUES (?, ?, 'USDC', 'GA5Z', 'NGNC', 'GBNG', ?, ?, ?, 1, ?, ?, ?, ?)
+            ON CONFLICT(corridor_key, hour_bucket) DO UPDATE SET
+                total_transactions = excluded.total_transactions,
+                successful_transactions = excluded.successful_transactions,
+                volume_usd = excluded.volume_usd,
+                updated_at = excluded.updated_at
+            ",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(corridor_key)
+        .bind(hour.to_rfc3339())
+        .bind(total)
+        .bind(total - 1)
+        .bind(total as f64 * 100.0)
+        .bind(latency_ms)
+        .bind(total as f64 * 1_000.0)
+        .bind(updated_at.to_rfc3339())
+        .execute(db.pool())
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_refresh_recomputes_only_dirty_entities() {
+        let (db, cache) = setup().await;
+        let t0 = DateTime::parse_from_rfc3339("2026-10-01T12:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let hour = |h: i64| t0 - Duration::minutes(30) - Duration::hours(h);
+
+        let written = t0 - Duration::minutes(1);
+        insert_anchor(&db, "alpha", written).await;
+        insert_anchor(&db, "beta", written).await;
+        upsert_hour(&db, NGN, hour(23), 4, 1_000, written).await;
+        upsert_hour(&db, NGN, hour(1), 8, 4_000, written).await;
+        upsert_hour(&db, KES, hour(2), 5, 2_000, written).await;
+
+        let first = cache.refresh(t0).await.unwrap();
+        assert_eq!(
+            first.stats,
+            RefreshStats {
+                anchors_recomputed: 2,
+                corridors_recomputed: 2
+            }
+        );
+        let ngn = first
+            .corridors
+            .iter()
+            .find(|c| c.corridor_key == NGN)
+            .unwrap();
+        assert_eq!(ngn.total_transactions, 12);
+        assert_eq!(ngn.successful_transactions, 10);
+        assert_eq!(ngn.avg_settlement_latency_ms, Some(3_000));
+        assert_eq!(ngn.liquidity_depth_usd, 8_000.0);
+
+        // Nothing changed: everything comes from the cache
+        let t1 = t0 + Duration::minutes(5);
+        let second = cache.refresh(t1).await.unwrap();
+        assert_eq!(second.stats, RefreshStats::default());
+        assert_eq!(second.corridors, first.corridors);
+        assert_eq!(second.anchors, first.anchors);
+
+        // One hour of KES is rewritten, and NGN's oldest hour leaves the window
+        let t2 = t0 + Duration::hours(1);
+        upsert_hour(&db, KES, hour(2), 7, 2_000, t2 - Duration::minutes(1)).await;
+        let third = cache.refresh(t2).await.unwrap();
+        assert_eq!(third.stats.anchors_recomputed, 0);
+        assert_eq!(third.stats.corridors_recomputed, 2);
+        let ngn = third
+            .corridors
+            .iter()
+            .find(|c| c.corridor_key == NGN)
+            .unwrap();
+        assert_eq!(ngn.total_transactions, 8);
+
+        // Same result as computing everything from scratch
+        cache.invalidate().await.unwrap();
+        let full = cache.refresh(t2).await.unwrap();
+        assert_eq!(full.stats.corridors_recomputed, 2);
+        assert_eq!(full.corridors, third.corridors);
+        assert_eq!(full.anchors, third.anchors);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_drops_inactive_anchors_and_empty_corridors() {
+        let (db, cache) = setup().await;
+        let t0 = Utc::now();
+        let id = insert_anchor(&db, "alpha", t0).await;
+        upsert_hour(&db, NGN, t0 - Duration::hours(23), 3, 1_000, t0).await;
+        let first = cache.refresh(t0).await.unwrap();
+        assert_eq!((first.anchors.len(), first.corridors.len()), (1, 1));
+
+        sqlx::query("UPDATE anchors SET status = 'inactive' WHERE id = ?")
+            .bind(&id)
+            .execute(db.pool())
+            .await
+            .unwrap();
+        let later = cache.refresh(t0 + Duration::hours(2)).await.unwrap();
+        assert!(later.anchors.is_empty());
+        assert!(later.corridors.is_empty());
+    }
+}
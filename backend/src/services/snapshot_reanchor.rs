@@ -0,0 +1,500 @@
+//! Re-anchoring of historical snapshot hashes onto a new snapshot contract.
+//!
+//! The snapshot contract only accepts strictly increasing epochs, so history has to
+//! be replayed oldest-first and a run cannot skip over an epoch and come back to it.
+//! Every epoch is verified on the new contract after submission and the outcome is
+//! recorded in `snapshot_reanchors`, which makes runs resumable.
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use serde::Serialize;
+use sqlx::Row;
+use std::sync::Arc;
+
+use super::contract::{ContractService, SubmissionResult};
+use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
+
+/// Contract operations needed to re-anchor history.
+#[async_trait::async_trait]
+pub trait SnapshotAnchorTarget: Send + Sync {
+    /// Hex hash stored on-chain for `epoch`, if any.
+    async fn anchored_hash(&self, epoch: u64) -> Result<Option<String>>;
+
+    async fn submit(&self, hash: [u8; 32], epoch: u64) -> Result<SubmissionResult>;
+
+    async fn verify(&self, hash: &str, epoch: u64) -> Result<bool>;
+}
+
+#[async_trait::async_trait]
+impl SnapshotAnchorTarget for ContractService {
+    async fn anchored_hash(&self, epoch: u64) -> Result<Option<String>> {
+        self.get_snapshot_by_epoch(epoch).await
+    }
+
+    async fn submit(&self, hash: [u8; 32], epoch: u64) -> Result<SubmissionResult> {
+        self.submit_snapshot_hash(hash, epoch).await
+    }
+
+    async fn verify(&self, hash: &str, epoch: u64) -> Result<bool> {
+        self.verify_snapshot_exists(hash, epoch).await
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReanchorOptions {
+    /// First epoch to re-anchor (inclusive).
+    pub from_epoch: Option<u64>,
+    /// Last epoch to re-anchor (inclusive).
+    pub to_epoch: Option<u64>,
+    /// Report what would be submitted without touching the new contract.
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReanchorStatus {
+    /// Submitted to the new contract and verified there.
+    Verified,
+    /// The new contract already held the same hash for this epoch.
+    AlreadyAnchored,
+    /// Submission or verification failed; the run stops at this epoch.
+    Failed,
+}
+
+impl ReanchorStatus {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Verified => "verified",
+            Self::AlreadyAnchored => "already_anchored",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReanchorOutcome {
+    pub epoch: u64,
+    pub hash: String,
+    pub status: ReanchorStatus,
+    pub target_transaction_hash: Option<String>,
+    pub target_ledger: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReanchorReport {
+    pub source_contract_id: String,
+    pub target_contract_id: String,
+    pub dry_run: bool,
+    /// Epochs skipped because an earlier run already recorded them.
+    pub skipped: Vec<u64>,
+    /// Epochs that would be submitted (dry run only).
+    pub pending: Vec<u64>,
+    pub outcomes: Vec<ReanchorOutcome>,
+}
+
+impl ReanchorReport {
+    #[must_use]
+    pub fn failed(&self) -> bool {
+        self.outcomes
+            .iter()
+            .any(|o| o.status == ReanchorStatus::Failed)
+    }
+}
+
+/// Replays stored snapshot hashes from `source_contract_id` onto a new contract.
+pub struct SnapshotReanchorService {
+    db: Arc<Database>,
+    target: Arc<dyn SnapshotAnchorTarget>,
+    source_contract_id: String,
+    target_contract_id: String,
+}
+
+impl SnapshotReanchorService {
+    #[must_use]
+    pub fn new(
+        db: Arc<Database>,
+        target: Arc<dyn SnapshotAnchorTarget>,
+        source_contract_id: impl Into<String>,
+        target_contract_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            db,
+            target,
+            source_contract_id: source_contract_id.into(),
+            target_contract_id: target_contract_id.into(),
+        }
+    }
+
+    /// Re-anchor every stored epoch in range, oldest first, stopping at the first
+    /// failure so the new contract never ends up with a gap below its latest epoch.
+    pub async fn run(&self, options: &ReanchorOptions) -> Result<ReanchorReport> {
+        if self.source_contract_id == self.target_contract_id {
+            bail!(
+                "Source and target contract are the same ({})",
+                self.source_contract_id
+            );
+        }
+
+        let history = self.load_history(options).await?;
+        let done = self.completed_epochs().await?;
+
+        let mut report = ReanchorReport {
+            source_contract_id: self.source_contract_id.clone(),
+            target_contract_id: self.target_contract_id.clone(),
+            dry_run: options.dry_run,
+            ..ReanchorReport::default()
+        };
+
+        log_event!(
+            info,
+            Subsystem::Snapshot,
+            "snapshot.reanchor_started",
+            contract_id = %self.target_contract_id,
+            source_contract_id = %self.source_contract_id,
+            epochs = history.len(),
+            dry_run = options.dry_run,
+            "Starting snapshot re-anchoring"
+        );
+
+        for (epoch, hash) in history {
+            if done.contains(&epoch) {
+                report.skipped.push(epoch);
+                continue;
+            }
+            if options.dry_run {
+                report.pending.push(epoch);
+                continue;
+            }
+
+            let outcome = self.reanchor_epoch(epoch, &hash).await;
+            self.record(&outcome).await?;
+            let failed = outcome.status == ReanchorStatus::Failed;
+            report.outcomes.push(outcome);
+            if failed {
+                log_event!(
+                    warn,
+                    Subsystem::Snapshot,
+                    "snapshot.reanchor_halted",
+                    epoch,
+                    "Re-anchoring stopped; later epochs were not submitted"
+                );
+                break;
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn reanchor_epoch(&self, epoch: u64, hash: &str) -> ReanchorOutcome {
+        let mut outcome = ReanchorOutcome {
+            epoch,
+            hash: hash.to_string(),
+            status: ReanchorStatus::Failed,
+            target_transaction_hash: None,
+            target_ledger: None,
+            error: None,
+        };
+
+        match self.submit_and_verify(epoch, hash).await {
+            Ok((status, submission)) => {
+                outcome.status = status;
+                outcome.target_transaction_hash =
+                    submission.as_ref().map(|s| s.transaction_hash.clone());
+                outcome.target_ledger = submission.map(|s| s.ledger);
+                log_event!(
+                    info,
+                    Subsystem::Snapshot,
+                    "snapshot.reanchored",
+                    epoch,
+                    status = status.as_str(),
+                    "Snapshot re-anchored"
+                );
+            }
+            Err(e) => {
+                log_event!(
+                    warn,
+                    Subsystem::Snapshot,
+                    "snapshot.reanchor_failed",
+                    epoch,
+                    error = format!("{e:#}"),
+                    "Failed to re-anchor snapshot"
+                );
+                outcome.error = Some(format!("{e:#}"));
+            }
+        }
+        outcome
+    }
+
+    async fn submit_and_verify(
+        &self,
+        epoch: u64,
+        hash: &str,
+    ) -> Result<(ReanchorStatus, Option<SubmissionResult>)> {
+        match self.target.anchored_hash(epoch).await? {
+            Some(existing) if existing.eq_ignore_ascii_case(hash) => {
+                return Ok((ReanchorStatus::AlreadyAnchored, None));
+            }
+            Some(existing) => {
+                bail!("Target already holds a different hash for epoch {epoch}: {existing}");
+            }
+            None => {}
+        }
+
+        let submission = self.target.submit(decode_hash(hash)?, epoch).await?;
+
+        if !self.target.verify(hash, epoch).await? {
+            bail!("Hash not verifiable on target after submission");
+        }
+        match self.target.anchored_hash(epoch).await? {
+            Some(stored) if stored.eq_ignore_ascii_case(hash) => {}
+            other => bail!("Target returned {other:?} for epoch {epoch} after submission"),
+        }
+
+        Ok((ReanchorStatus::Verified, Some(submission)))
+    }
+
+    /// Latest stored hash per epoch, ascending.
+    async fn load_history(&self, options: &ReanchorOptions) -> Result<Vec<(u64, String)>> {
+        let from = i64::try_from(options.from_epoch.unwrap_or(0))?;
+        let to = options
+            .to_epoch
+            .map(i64::try_from)
+            .transpose()?
+            .unwrap_or(i64::MAX);
+
+        let rows = sqlx::query(
+            r"
+            SELECT s.epoch, s.hash
+            FROM snapshots s
+            WHERE s.entity_type = 'analytics_snapshot'
+              AND s.hash IS NOT NULL
+              AND s.epoch BETWEEN ? AND ?
+              AND s.created_at = (
+                  SELECT MAX(created_at) FROM snapshots
+                  WHERE epoch = s.epoch AND entity_type = 'analytics_snapshot' AND hash IS NOT NULL
+              )
+            GROUP BY s.epoch
+            ORDER BY s.epoch ASC
+            ",
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to load historical snapshot hashes")?;
+
+        rows.into_iter()
+            .map(|row| {
+                let epoch: i64 = row.get("epoch");
+                let epoch = u64::try_from(epoch)
+                    .map_err(|_| anyhow!("Stored snapshot has negative epoch {epoch}"))?;
+                Ok((epoch, row.get("hash")))
+            })
+            .collect()
+    }
+
+    async fn completed_epochs(&self) -> Result<std::collections::HashSet<u64>> {
+        let epochs: Vec<i64> = sqlx::query_scalar(
+            r"
+            SELECT epoch FROM snapshot_reanchors
+            WHERE source_contract_id = ? AND target_contract_id = ? AND status != 'failed'
+            ",
+        )
+        .bind(&self.source_contract_id)
+        .bind(&self.target_contract_id)
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to load previous re-anchoring results")?;
+
+        Ok(epochs
+            .into_iter()
+            .filter_map(|e| u64::try_from(e).ok())
+            .collect())
+    }
+
+    async fn record(&self, outcome: &ReanchorOutcome) -> Result<()> {
+        sqlx::query(
+            r"
+            INSERT INTO snapshot_reanchors (
+                source_contract_id, target_contract_id, epoch, hash, status,
+                target_transaction_hash, target_ledger, error, reanchored_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (source_contract_id, target_contract_id, epoch) DO UPDATE SET
+                hash = excluded.hash,
+                status = excluded.status,
+                target_transaction_hash = excluded.target_transaction_hash,
+                target_ledger = excluded.target_ledger,
+                error = excluded.error,
+                reanchored_at = excluded.reanchored_at
+            ",
+        )
+        .bind(&self.source_contract_id)
+        .bind(&self.target_contract_id)
+        .bind(i64::try_from(outcome.epoch)?)
+        .bind(&outcome.hash)
+        .bind(outcome.status.as_str())
+        .bind(&outcome.target_transaction_hash)
+        .bind(outcome.target_ledger.map(i64::try_from).transpose()?)
+        .bind(&outcome.error)
+        .bind(Utc::now().to_rfc3339())
+        .execute(self.db.pool())
+        .await
+        .context("Failed to record re-anchoring outcome")?;
+        Ok(())
+    }
+}
+
+fn decode_hash(hash: &str) -> Result<[u8; 32]> {
+    let bytes = hex::decode(hash).context("Stored snapshot hash is not valid hex")?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow!("Stored snapshot hash is not 32 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations};
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    /// In-memory stand-in for the snapshot contract, enforcing increasing epochs.
+    #[derive(Default)]
+    struct FakeContract {
+        anchored: Mutex<BTreeMap<u64, String>>,
+        reject_epoch: Option<u64>,
+    }
+
+    #[async_trait::async_trait]
+    impl SnapshotAnchorTarget for FakeContract {
+        async fn anchored_hash(&self, epoch: u64) -> Result<Option<String>> {
+            Ok(self.anchored.lock().unwrap().get(&epoch).cloned())
+        }
+
+        async fn submit(&self, hash: [u8; 32], epoch: u64) -> Result<SubmissionResult> {
+            if self.reject_epoch == Some(epoch) {
+                bail!("simulated submission failure");
+            }
+            let mut anchored = self.anchored.lock().unwrap();
+            if anchored
+                .keys()
+                .next_back()
+                .is_some_and(|latest| epoch <= *latest)
+            {
+                bail!("EpochMonotonicityViolated");
+            }
+            anchored.insert(epoch, hex::encode(hash));
+            drop(anchored);
+            Ok(SubmissionResult {
+                transaction_hash: format!("tx-{epoch}"),
+                epoch,
+                ledger: 1_000 + epoch,
+                timestamp: 0,
+            })
+        }
+
+        async fn verify(&self, hash: &str, epoch: u64) -> Result<bool> {
+            Ok(self
+                .anchored
+                .lock()
+                .unwrap()
+                .get(&epoch)
+                .map(String::as_str)
+                == Some(hash))
+        }
+    }
+
+    async fn setup(epochs: &[u64]) -> Arc<Database> {
+        let pool = test_support::sqlite_pool(&[
+            migrations::METRICS_CORRIDORS_SNAPSHOTS,
+            migrations::SNAPSHOT_REANCHORS,
+        ])
+        .await;
+
+        for epoch in epochs {
+            sqlx::query(
+                "INSERT INTO snapshots VALUES (?, 'system', 'analytics_snapshot', '{}', ?, ?, '', ?)",
+            )
+            .bind(format!("snap-{epoch}"))
+            .bind(hash_for(*epoch))
+            .bind(i64::try_from(*epoch).unwrap())
+            .bind(Utc::now().to_rfc3339())
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        Arc::new(Database::new(pool))
+    }
+
+    fn hash_for(epoch: u64) -> String {
+        hex::encode([u8::try_from(epoch).unwrap(); 32])
+    }
+
+    #[tokio::test]
+    async fn test_reanchors_in_epoch_order_and_resumes() {
+        let db = setup(&[3, 1, 2]).await;
+        let target = Arc::new(FakeContract::default());
+        let service = SnapshotReanchorService::new(db, target.clone(), "OLD", "NEW");
+
+        let report = service.run(&ReanchorOptions::default()).await.unwrap();
+        let epochs: Vec<u64> = report.outcomes.iter().map(|o| o.epoch).collect();
+        assert_eq!(epochs, vec![1, 2, 3]);
+        assert!(report
+            .outcomes
+            .iter()
+            .all(|o| o.status == ReanchorStatus::Verified));
+        assert_eq!(target.anchored.lock().unwrap().len(), 3);
+
+        let rerun = service.run(&ReanchorOptions::default()).await.unwrap();
+        assert!(rerun.outcomes.is_empty());
+        assert_eq!(rerun.skipped, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_stops_at_first_failure() {
+        let db = setup(&[1, 2, 3]).await;
+        let target = Arc::new(FakeContract {
+            reject_epoch: Some(2),
+            ..FakeContract::default()
+        });
+        let service = SnapshotReanchorService::new(db, target.clone(), "OLD", "NEW");
+
+        let report = service.run(&ReanchorOptions::default()).await.unwrap();
+        assert!(report.failed());
+        assert_eq!(report.outcomes.len(), 2);
+        assert_eq!(report.outcomes[1].status, ReanchorStatus::Failed);
+        assert!(!target.anchored.lock().unwrap().contains_key(&3));
+    }
+
+    #[tokio::test]
+    async fn test_existing_matching_hash_is_not_resubmitted() {
+        let db = setup(&[1]).await;
+        let target = Arc::new(FakeContract::default());
+        target.anchored.lock().unwrap().insert(1, hash_for(1));
+        let service = SnapshotReanchorService::new(db, target, "OLD", "NEW");
+
+        let report = service.run(&ReanchorOptions::default()).await.unwrap();
+        assert_eq!(report.outcomes[0].status, ReanchorStatus::AlreadyAnchored);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_submits_nothing() {
+        let db = setup(&[1, 2]).await;
+        let target = Arc::new(FakeContract::default());
+        let service = SnapshotReanchorService::new(db, target.clone(), "OLD", "NEW");
+
+        let report = service
+            .run(&ReanchorOptions {
+                dry_run: true,
+                ..ReanchorOptions::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(report.pending, vec![1, 2]);
+        assert!(target.anchored.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,634 @@
+use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
+use anyhow::{anyhow, bail, Context, Result};
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::Row;
+use std::sync::Arc;
+use stellar_xdr::curr::{
+    ContractDataDurability, LedgerKey, Limits, ReadXdr, SorobanTransactionData, WriteXdr,
+};
+use utoipa::{IntoParams, ToSchema};
+
+use super::contract::ContractService;
+
+/// Estimates older than this are not used as a baseline for fee trends.
+const TREND_WINDOW_DAYS: i64 = 30;
+/// Growth in bytes read, relative to the baseline, that points at contract storage growth.
+const STORAGE_GROWTH_READ_BYTES_PCT: f64 = 20.0;
+/// Fee growth that must accompany the read growth before it is flagged.
+const STORAGE_GROWTH_FEE_PCT: f64 = 10.0;
+const DEFAULT_HISTORY_LIMIT: i64 = 100;
+const MAX_HISTORY_LIMIT: i64 = 1_000;
+/// Soroban function names are symbols of at most 32 characters.
+const MAX_FUNCTION_NAME_LEN: usize = 32;
+
+/// What to simulate. Nothing is signed or submitted.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(tag = "target", rename_all = "snake_case")]
+pub enum EstimateRequest {
+    /// `submit_snapshot` on the configured snapshot contract. `epoch` defaults to the
+    /// epoch after the latest stored snapshot.
+    SnapshotSubmission { epoch: Option<u64> },
+    /// Any function of a deployed contract, e.g. remittance contract entry points.
+    ContractCall {
+        contract_id: String,
+        function: String,
+        /// Typed arguments: `[{"type": "u64", "value": "42"}, ...]`
+        #[serde(default)]
+        #[schema(value_type = Vec<Object>)]
+        args: Vec<Value>,
+    },
+}
+
+impl EstimateRequest {
+    pub fn validate(&self) -> Result<()> {
+        let Self::ContractCall {
+            contract_id,
+            function,
+            ..
+        } = self
+        else {
+            return Ok(());
+        };
+
+        if contract_id.len() != 56
+            || !contract_id.starts_with('C')
+            || !contract_id
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        {
+            bail!("contract_id must be a 56-character contract address starting with 'C'");
+        }
+        if function.is_empty()
+            || function.len() > MAX_FUNCTION_NAME_LEN
+            || !function
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            bail!("function must be 1-32 characters of [A-Za-z0-9_]");
+        }
+        Ok(())
+    }
+
+    const fn target(&self) -> &'static str {
+        match self {
+            Self::SnapshotSubmission { .. } => "snapshot_submission",
+            Self::ContractCall { .. } => "contract_call",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FootprintEntry {
+    /// Ledger entry type, e.g. `contract_data`, `contract_code`, `account`
+    pub kind: String,
+    /// `persistent` or `temporary` for contract data
+    pub durability: Option<String>,
+    /// Base64 XDR `LedgerKey`
+    pub key_xdr: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct Footprint {
+    pub read_only: Vec<FootprintEntry>,
+    pub read_write: Vec<FootprintEntry>,
+}
+
+/// Resource usage reported by `simulateTransaction`.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SimulatedResources {
+    /// Minimum resource fee in stroops
+    pub min_resource_fee: i64,
+    pub instructions: Option<u32>,
+    pub read_bytes: Option<u32>,
+    pub write_bytes: Option<u32>,
+    pub cpu_instructions: Option<u64>,
+    pub memory_bytes: Option<u64>,
+    /// `None` when the RPC returned no decodable `transactionData`
+    pub footprint: Option<Footprint>,
+    /// Archived entries must be restored before the call can succeed
+    pub restore_required: bool,
+    pub restore_min_resource_fee: Option<i64>,
+    pub latest_ledger: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FeeTrend {
+    pub baseline_estimated_at: DateTime<Utc>,
+    pub baseline_min_resource_fee: i64,
+    pub baseline_read_bytes: Option<i64>,
+    pub fee_change_pct: f64,
+    pub read_bytes_change_pct: Option<f64>,
+    /// Bytes read and fees both grew past their thresholds since the baseline
+    pub storage_growth_detected: bool,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FeeEstimate {
+    pub target: String,
+    pub contract_id: String,
+    pub function: String,
+    pub resources: SimulatedResources,
+    /// Comparison with the oldest estimate of the same call in the last 30 days
+    pub trend: Option<FeeTrend>,
+    pub estimated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct StoredEstimate {
+    pub id: i64,
+    pub target: String,
+    pub contract_id: String,
+    pub function: String,
+    pub min_resource_fee: i64,
+    pub instructions: Option<i64>,
+    pub read_bytes: Option<i64>,
+    pub write_bytes: Option<i64>,
+    pub footprint_entries: Option<i64>,
+    pub latest_ledger: Option<i64>,
+    pub estimated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct EstimateHistoryQuery {
+    pub contract_id: Option<String>,
+    pub function: Option<String>,
+    /// Defaults to 100, at most 1000
+    pub limit: Option<i64>,
+}
+
+/// The simulation ran but the contract call itself would fail.
+#[derive(Debug)]
+pub struct SimulationRejected(pub String);
+
+impl std::fmt::Display for SimulationRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Simulation rejected the call: {}", self.0)
+    }
+}
+
+impl std::error::Error for SimulationRejected {}
+
+pub struct SorobanEstimateService {
+    db: Arc<Database>,
+    contract: Option<Arc<ContractService>>,
+}
+
+impl SorobanEstimateService {
+    #[must_use]
+    pub const fn new(db: Arc<Database>, contract: Option<Arc<ContractService>>) -> Self {
+        Self { db, contract }
+    }
+
+    #[must_use]
+    pub const fn is_configured(&self) -> bool {
+        self.contract.is_some()
+    }
+
+    /// Simulate the request, record it and compare it with earlier estimates.
+    pub async fn estimate(&self, request: EstimateRequest) -> Result<FeeEstimate> {
+        let contract = self
+            .contract
+            .as_ref()
+            .ok_or_else(|| anyhow!("Contract service not configured"))?;
+
+        let target = request.target();
+        let (contract_id, function, simulation) = match request {
+            EstimateRequest::SnapshotSubmission { epoch } => {
+                let epoch = match epoch {
+                    Some(epoch) => epoch,
+                    None => self.next_snapshot_epoch().await?,
+                };
+                // Any 32-byte value costs the same to store; derive one from the epoch.
+                let hash: [u8; 32] = Sha256::digest(epoch.to_be_bytes()).into();
+                let simulation = contract.simulate_snapshot_submission(hash, epoch).await?;
+                (
+                    contract.contract_id().to_string(),
+                    "submit_snapshot".to_string(),
+                    simulation,
+                )
+            }
+            EstimateRequest::ContractCall {
+                contract_id,
+                function,
+                args,
+            } => {
+                let simulation = contract
+                    .simulate_invocation(&contract_id, &function, &args)
+                    .await?;
+                (contract_id, function, simulation)
+            }
+        };
+
+        let resources = parse_simulation(&simulation)?;
+        let estimated_at = Utc::now();
+        let baseline = self.baseline(&contract_id, &function, estimated_at).await?;
+        let trend = baseline.map(|baseline| fee_trend(&baseline, &resources));
+        self.record(target, &contract_id, &function, &resources, estimated_at)
+            .await?;
+
+        Ok(FeeEstimate {
+            target: target.to_string(),
+            contract_id,
+            function,
+            resources,
+            trend,
+            estimated_at,
+        })
+    }
+
+    /// Recorded estimates, newest first.
+    pub async fn history(&self, query: &EstimateHistoryQuery) -> Result<Vec<StoredEstimate>> {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_HISTORY_LIMIT)
+            .clamp(1, MAX_HISTORY_LIMIT);
+
+        let rows = sqlx::query(
+            r"
+            SELECT * FROM soroban_fee_estimates
+            WHERE (? IS NULL OR contract_id = ?) AND (? IS NULL OR function_name = ?)
+            ORDER BY estimated_at DESC, id DESC
+            LIMIT ?
+            ",
+        )
+        .bind(&query.contract_id)
+        .bind(&query.contract_id)
+        .bind(&query.function)
+        .bind(&query.function)
+        .bind(limit)
+        .fetch_all(self.db.pool())
+        .await
+        .context("Failed to load fee estimate history")?;
+
+        rows.iter().map(stored_estimate_from_row).collect()
+    }
+
+    async fn next_snapshot_epoch(&self) -> Result<u64> {
+        let latest: Option<i64> = sqlx::query_scalar("SELECT MAX(epoch) FROM snapshots")
+            .fetch_one(self.db.pool())
+            .await
+            .context("Failed to load latest snapshot epoch")?;
+        Ok(latest
+            .and_then(|epoch| u64::try_from(epoch).ok())
+            .map_or(1, |epoch| epoch + 1))
+    }
+
+    async fn baseline(
+        &self,
+        contract_id: &str,
+        function: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<StoredEstimate>> {
+        let row = sqlx::query(
+            r"
+            SELECT * FROM soroban_fee_estimates
+            WHERE contract_id = ? AND function_name = ? AND estimated_at >= ?
+            ORDER BY estimated_at ASC, id ASC
+            LIMIT 1
+            ",
+        )
+        .bind(contract_id)
+        .bind(function)
+        .bind((now - Duration::days(TREND_WINDOW_DAYS)).to_rfc3339())
+        .fetch_optional(self.db.pool())
+        .await
+        .context("Failed to load baseline fee estimate")?;
+
+        row.as_ref().map(stored_estimate_from_row).transpose()
+    }
+
+    async fn record(
+        &self,
+        target: &str,
+        contract_id: &str,
+        function: &str,
+        resources: &SimulatedResources,
+        estimated_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let footprint_entries = resources
+            .footprint
+            .as_ref()
+            .map(|f| i64::try_from(f.read_only.len() + f.read_write.len()).unwrap_or(i64::MAX));
+
+        sqlx::query(
+            r"
+            INSERT INTO soroban_fee_estimates (
+                target, contract_id, function_name, min_resource_fee, instructions,
+                read_bytes, write_bytes, footprint_entries, latest_ledger, estimated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ",
+        )
+        .bind(target)
+        .bind(contract_id)
+        .bind(function)
+        .bind(resources.min_resource_fee)
+        .bind(resources.instructions.map(i64::from))
+        .bind(resources.read_bytes.map(i64::from))
+        .bind(resources.write_bytes.map(i64::from))
+        .bind(footprint_entries)
+        .bind(
+            resources
+                .latest_ledger
+                .and_then(|ledger| i64::try_from(ledger).ok()),
+        )
+        .bind(estimated_at.to_rfc3339())
+        .execute(self.db.pool())
+        .await
+        .context("Failed to record fee estimate")?;
+        Ok(())
+    }
+}
+
+fn stored_estimate_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<StoredEstimate> {
+    let estimated_at: String = row.get("estimated_at");
+    Ok(StoredEstimate {
+        id: row.get("id"),
+        target: row.get("target"),
+        contract_id: row.get("contract_id"),
+        function: row.get("function_name"),
+        min_resource_fee: row.get("min_resource_fee"),
+        instructions: row.get("instructions"),
+        read_bytes: row.get("read_bytes"),
+        write_bytes: row.get("write_bytes"),
+        footprint_entries: row.get("footprint_entries"),
+        latest_ledger: row.get("latest_ledger"),
+        estimated_at: DateTime::parse_from_rfc3339(&estimated_at)
+            .context("Invalid estimated_at timestamp")?
+            .with_timezone(&Utc),
+    })
+}
+
+fn pct_change(baseline: f64, current: f64) -> f64 {
+    if baseline > 0.0 {
+        (current - baseline) / baseline * 100.0
+    } else {
+        0.0
+    }
+}
+
+fn fee_trend(baseline: &StoredEstimate, current: &SimulatedResources) -> FeeTrend {
+    let fee_change_pct = pct_change(
+        baseline.min_resource_fee as f64,
+        current.min_resource_fee as f64,
+    );
+    let read_bytes_change_pct = baseline
+        .read_bytes
+        .zip(current.read_bytes)
+        .map(|(before, now)| pct_change(before as f64, f64::from(now)));
+
+    FeeTrend {
+        baseline_estimated_at: baseline.estimated_at,
+        baseline_min_resource_fee: baseline.min_resource_fee,
+        baseline_read_bytes: baseline.read_bytes,
+        fee_change_pct,
+        read_bytes_change_pct,
+        storage_growth_detected: fee_change_pct >= STORAGE_GROWTH_FEE_PCT
+            && read_bytes_change_pct.is_some_and(|pct| pct >= STORAGE_GROWTH_READ_BYTES_PCT),
+    }
+}
+
+/// Numbers come back as decimal strings from the RPC; accept plain numbers too.
+fn lenient_i64(value: &Value) -> Option<i64> {
+    value
+        .as_i64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
+
+fn lenient_u64(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
+
+fn footprint_entry(key: &LedgerKey) -> FootprintEntry {
+    let (kind, durability) = match key {
+        LedgerKey::Account(_) => ("account", None),
+        LedgerKey::Trustline(_) => ("trustline", None),
+        LedgerKey::Offer(_) => ("offer", None),
+        LedgerKey::Data(_) => ("data", None),
+        LedgerKey::ClaimableBalance(_) => ("claimable_balance", None),
+        LedgerKey::LiquidityPool(_) => ("liquidity_pool", None),
+        LedgerKey::ContractData(data) => (
+            "contract_data",
+            Some(match data.durability {
+                ContractDataDurability::Persistent => "persistent",
+                ContractDataDurability::Temporary => "temporary",
+            }),
+        ),
+        LedgerKey::ContractCode(_) => ("contract_code", None),
+        LedgerKey::ConfigSetting(_) => ("config_setting", None),
+        LedgerKey::Ttl(_) => ("ttl", None),
+    };
+
+    FootprintEntry {
+        kind: kind.to_string(),
+        durability: durability.map(str::to_string),
+        key_xdr: key
+            .to_xdr(Limits::none())
+            .map(|bytes| base64::engine::general_purpose::STANDARD.encode(bytes))
+            .unwrap_or_default(),
+    }
+}
+
+fn decode_transaction_data(encoded: &str) -> Option<SorobanTransactionData> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()?;
+    SorobanTransactionData::from_xdr(bytes, Limits::none()).ok()
+}
+
+/// Extract fees, resources and footprint from a `simulateTransaction` result.
+pub fn parse_simulation(result: &Value) -> Result<SimulatedResources> {
+    if let Some(error) = result.get("error").and_then(Value::as_str) {
+        return Err(SimulationRejected(error.to_string()).into());
+    }
+
+    let min_resource_fee = result
+        .get("minResourceFee")
+        .and_then(lenient_i64)
+        .ok_or_else(|| anyhow!("Simulation result has no minResourceFee"))?;
+
+    let transaction_data = result
+        .get("transactionData")
+        .and_then(Value::as_str)
+        .and_then(decode_transaction_data);
+    if transaction_data.is_none() {
+        log_event!(
+            warn,
+            Subsystem::Contract,
+            "soroban_estimate.footprint_unavailable",
+            "Simulation returned no decodable transactionData; footprint unavailable"
+        );
+    }
+
+    let restore = result.get("restorePreamble");
+    let cost = result.get("cost");
+
+    Ok(SimulatedResources {
+        min_resource_fee,
+        instructions: transaction_data.as_ref().map(|d| d.resources.instructions),
+        read_bytes: transaction_data.as_ref().map(|d| d.resources.read_bytes),
+        write_bytes: transaction_data.as_ref().map(|d| d.resources.write_bytes),
+        cpu_instructions: cost.and_then(|c| c.get("cpuInsns")).and_then(lenient_u64),
+        memory_bytes: cost.and_then(|c| c.get("memBytes")).and_then(lenient_u64),
+        footprint: transaction_data.map(|d| Footprint {
+            read_only: d
+                .resources
+                .footprint
+                .read_only
+                .iter()
+                .map(footprint_entry)
+                .collect(),
+            read_write: d
+                .resources
+                .footprint
+                .read_write
+                .iter()
+                .map(footprint_entry)
+                .collect(),
+        }),
+        restore_required: restore.is_some_and(|r| !r.is_null()),
+        restore_min_resource_fee: restore
+            .and_then(|r| r.get("minResourceFee"))
+            .and_then(lenient_i64),
+        latest_ledger: result.get("latestLedger").and_then(lenient_u64),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, migrations};
+    use stellar_xdr::curr::{
+        ExtensionPoint, Hash, LedgerFootprint, LedgerKeyContractCode, LedgerKeyContractData,
+        ScAddress, ScVal, SorobanResources,
+    };
+
+    fn transaction_data_b64() -> String {
+        let contract = ScAddress::Contract(Hash([7; 32]));
+        let data = SorobanTransactionData {
+            ext: ExtensionPoint::V0,
+            resources: SorobanResources {
+                footprint: LedgerFootprint {
+                    read_only: vec![LedgerKey::ContractCode(LedgerKeyContractCode {
+                        hash: Hash([9; 32]),
+                    })]
+                    .try_into()
+                    .unwrap(),
+                    read_write: vec![LedgerKey::ContractData(LedgerKeyContractData {
+                        contract,
+                        key: ScVal::U64(42),
+                        durability: ContractDataDurability::Persistent,
+                    })]
+                    .try_into()
+                    .unwrap(),
+                },
+                instructions: 1_500_000,
+                read_bytes: 2_048,
+                write_bytes: 128,
+            },
+            resource_fee: 90_000,
+        };
+        base64::engine::general_purpose::STANDARD.encode(data.to_xdr(Limits::none()).unwrap())
+    }
+
+    #[test]
+    fn test_parse_simulation_decodes_footprint() {
+        let result = serde_json::json!({
+            "transactionData": transaction_data_b64(),
+            "minResourceFee": "88123",
+            "cost": { "cpuInsns": "1400000", "memBytes": "300000" },
+            "latestLedger": 51_000_000
+        });
+
+        let parsed = parse_simulation(&result).unwrap();
+        assert_eq!(parsed.min_resource_fee, 88_123);
+        assert_eq!(parsed.read_bytes, Some(2_048));
+        assert_eq!(parsed.cpu_instructions, Some(1_400_000));
+        assert!(!parsed.restore_required);
+
+        let footprint = parsed.footprint.unwrap();
+        assert_eq!(footprint.read_only[0].kind, "contract_code");
+        assert_eq!(footprint.read_write[0].kind, "contract_data");
+        assert_eq!(
+            footprint.read_write[0].durability.as_deref(),
+            Some("persistent")
+        );
+    }
+
+    #[test]
+    fn test_parse_simulation_surfaces_contract_errors() {
+        let result = serde_json::json!({ "error": "HostError: Error(Contract, #3)" });
+        let err = parse_simulation(&result).unwrap_err();
+        assert!(err.downcast_ref::<SimulationRejected>().is_some());
+    }
+
+    #[test]
+    fn test_contract_call_validation() {
+        let valid = EstimateRequest::ContractCall {
+            contract_id: format!("C{}", "A".repeat(55)),
+            function: "send_remittance".to_string(),
+            args: Vec::new(),
+        };
+        assert!(valid.validate().is_ok());
+
+        let bad_contract = EstimateRequest::ContractCall {
+            contract_id: "GABC".to_string(),
+            function: "transfer".to_string(),
+            args: Vec::new(),
+        };
+        assert!(bad_contract.validate().is_err());
+
+        let bad_function = EstimateRequest::ContractCall {
+            contract_id: format!("C{}", "A".repeat(55)),
+            function: "x".repeat(33),
+            args: Vec::new(),
+        };
+        assert!(bad_function.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_storage_growth_is_flagged_against_baseline() {
+        let db = test_support::sqlite_db(&[migrations::SOROBAN_FEE_ESTIMATES]).await;
+        let service = SorobanEstimateService::new(db, None);
+
+        let mut resources = parse_simulation(&serde_json::json!({
+            "transactionData": transaction_data_b64(),
+            "minResourceFee": "100000"
+        }))
+        .unwrap();
+        let now = Utc::now();
+        service
+            .record(
+                "contract_call",
+                "CX",
+                "send",
+                &resources,
+                now - Duration::days(5),
+            )
+            .await
+            .unwrap();
+
+        resources.min_resource_fee = 125_000;
+        resources.read_bytes = Some(3_072);
+        let baseline = service.baseline("CX", "send", now).await.unwrap().unwrap();
+        let trend = fee_trend(&baseline, &resources);
+        assert!((trend.fee_change_pct - 25.0).abs() < 1e-9);
+        assert!(trend.storage_growth_detected);
+
+        // Outside the window there is no baseline.
+        let later = now + Duration::days(TREND_WINDOW_DAYS);
+        assert!(service
+            .baseline("CX", "send", later)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -6,8 +6,8 @@ use sqlx::SqlitePool;
 use std::sync::Arc;
 
 use crate::webhooks::events::{
-    AnchorStatusChangedEvent, CorridorHealthDegradedEvent, CorridorLiquidityDroppedEvent,
-    CorridorMetrics, PaymentCreatedEvent,
+    AnchorStatusChangedEvent, ContractActivityEvent, CorridorHealthDegradedEvent,
+    CorridorLiquidityDroppedEvent, CorridorMetrics, PaymentCreatedEvent,
 };
 use crate::webhooks::{WebhookEventType, WebhookService};
 
@@ -118,6 +118,16 @@ impl WebhookEventService {
             .await
     }
 
+    /// Trigger an on-chain contract event (`contract.*` event types)
+    pub async fn trigger_contract_event(
+        &self,
+        event_type: WebhookEventType,
+        event: &ContractActivityEvent,
+    ) -> Result<()> {
+        let payload = json!(event);
+        self.trigger_event(event_type, payload).await
+    }
+
     /// Generic method to trigger an event for all matching webhooks
     async fn trigger_event(
         &self,
@@ -3,5 +3,6 @@ pub mod schema;
 
 pub use generator::SnapshotGenerator;
 pub use schema::{
-    AnalyticsSnapshot, SnapshotAnchorMetrics, SnapshotCorridorMetrics, SCHEMA_VERSION,
+    AnalyticsSnapshot, SnapshotAnchorMetrics, SnapshotCorridorMetrics, SnapshotRegionPairMetrics,
+    SCHEMA_VERSION,
 };
@@ -3,7 +3,10 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Snapshot schema version for backward compatibility
-pub const SCHEMA_VERSION: u32 = 1;
+///
+/// - 1: anchor and corridor metrics
+/// - 2: adds region-pair aggregates
+pub const SCHEMA_VERSION: u32 = 2;
 
 /// Individual anchor metrics within a snapshot
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -40,6 +43,19 @@ pub struct SnapshotCorridorMetrics {
     pub liquidity_depth_usd: f64,
 }
 
+/// Corridor metrics rolled up by the regions of their source and destination assets
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SnapshotRegionPairMetrics {
+    pub source_region: String,
+    pub destination_region: String,
+    pub corridor_count: i64,
+    pub total_transactions: i64,
+    pub successful_transactions: i64,
+    pub failed_transactions: i64,
+    pub success_rate: f64,
+    pub volume_usd: f64,
+}
+
 /// Complete snapshot containing all metrics at a specific epoch
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalyticsSnapshot {
@@ -53,6 +69,10 @@ pub struct AnalyticsSnapshot {
     pub anchor_metrics: Vec<SnapshotAnchorMetrics>,
     /// All corridor metrics at this epoch
     pub corridor_metrics: Vec<SnapshotCorridorMetrics>,
+    /// Corridor metrics aggregated by region pair (schema version 2+). Omitted from the
+    /// canonical form when empty so version 1 snapshots keep their original hashes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub region_pair_metrics: Vec<SnapshotRegionPairMetrics>,
 }
 
 impl AnalyticsSnapshot {
@@ -65,6 +85,7 @@ impl AnalyticsSnapshot {
             timestamp,
             anchor_metrics: Vec::new(),
             corridor_metrics: Vec::new(),
+            region_pair_metrics: Vec::new(),
         }
     }
 
@@ -78,6 +99,11 @@ impl AnalyticsSnapshot {
         self.corridor_metrics.push(metrics);
     }
 
+    /// Add region-pair metrics to the snapshot
+    pub fn add_region_pair_metrics(&mut self, metrics: SnapshotRegionPairMetrics) {
+        self.region_pair_metrics.push(metrics);
+    }
+
     /// Sort all arrays deterministically for consistent serialization
     pub fn normalize(&mut self) {
         // Sort anchor metrics by id for deterministic ordering
@@ -87,6 +113,12 @@ impl AnalyticsSnapshot {
         // Sort corridor metrics by id for deterministic ordering
         self.corridor_metrics
             .sort_by(|a, b| a.id.as_bytes().cmp(b.id.as_bytes()));
+
+        // Region pairs have no id; the (source, destination) pair is unique
+        self.region_pair_metrics.sort_by(|a, b| {
+            (&a.source_region, &a.destination_region)
+                .cmp(&(&b.source_region, &b.destination_region))
+        });
     }
 }
 
@@ -194,4 +226,44 @@ mod tests {
         assert_eq!(snapshot.anchor_metrics[1].id, id1);
         assert_eq!(snapshot.anchor_metrics[2].id, id3);
     }
+
+    #[test]
+    fn test_normalize_sorts_region_pairs() {
+        let mut snapshot = AnalyticsSnapshot::new(1, Utc::now());
+        for (source, destination) in [("EU", "West Africa"), ("EU", "East Africa"), ("Asia", "EU")]
+        {
+            snapshot.add_region_pair_metrics(SnapshotRegionPairMetrics {
+                source_region: source.to_string(),
+                destination_region: destination.to_string(),
+                corridor_count: 1,
+                total_transactions: 10,
+                successful_transactions: 9,
+                failed_transactions: 1,
+                success_rate: 90.0,
+                volume_usd: 100.0,
+            });
+        }
+
+        snapshot.normalize();
+        let pairs: Vec<_> = snapshot
+            .region_pair_metrics
+            .iter()
+            .map(|m| (m.source_region.as_str(), m.destination_region.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![("Asia", "EU"), ("EU", "East Africa"), ("EU", "West Africa")]
+        );
+    }
+
+    #[test]
+    fn test_v1_snapshot_without_region_pairs_deserializes() {
+        let json = r#"{"schema_version":1,"epoch":3,"timestamp":"2026-01-15T12:30:00Z","anchor_metrics":[],"corridor_metrics":[]}"#;
+        let snapshot: AnalyticsSnapshot = serde_json::from_str(json).unwrap();
+        assert_eq!(snapshot.schema_version, 1);
+        assert!(snapshot.region_pair_metrics.is_empty());
+        assert!(!serde_json::to_string(&snapshot)
+            .unwrap()
+            .contains("region_pair_metrics"));
+    }
 }
@@ -1,6 +1,7 @@
 use crate::cache::CacheManager;
 use crate::database::Database;
 use crate::ingestion::DataIngestionService;
+use crate::replay::{EventStorage, ProgressHub, ReplayManager, DEFAULT_LIVE_FEED_CAPACITY};
 use crate::rpc::StellarRpcClient;
 use crate::websocket::WsState;
 use std::sync::{Arc, atomic::{AtomicU64, Ordering}};
@@ -15,6 +16,13 @@ pub struct AppState {
     pub ingestion: Arc<DataIngestionService>,
     pub rpc_client: Arc<StellarRpcClient>,
     pub server_start_time: Arc<AtomicU64>,
+    /// Live progress of replay sessions started by this process
+    pub replay_progress: Arc<ProgressHub>,
+    /// Contract events for replay; ingestion stores through it, so follow
+    /// sessions receive them live
+    pub replay_events: Arc<EventStorage>,
+    /// Runs replay sessions, for the admin API and the consistency checks alike
+    pub replay_manager: Arc<ReplayManager>,
 }
 
 impl AppState {
@@ -26,6 +34,14 @@ impl AppState {
         ingestion: Arc<DataIngestionService>,
         rpc_client: Arc<StellarRpcClient>,
     ) -> Self {
+        let replay_events = Arc::new(
+            EventStorage::new(db.pool().clone()).with_live_feed(DEFAULT_LIVE_FEED_CAPACITY),
+        );
+        let replay_progress = Arc::new(ProgressHub::new());
+        let replay_manager = Arc::new(
+            ReplayManager::new(db.pool().clone(), Arc::clone(&replay_progress))
+                .with_event_storage(Arc::clone(&replay_events)),
+        );
         Self {
             db,
             cache,
@@ -35,6 +51,9 @@ impl AppState {
             server_start_time: Arc::new(AtomicU64::new(
                 SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs()
             )),
+            replay_progress,
+            replay_events,
+            replay_manager,
         }
     }
 }
@@ -3,7 +3,8 @@ use std::time::Duration;
 
 use tokio::sync::broadcast;
 
-use crate::alerts::{Alert, AlertManager};
+use crate::alert_bus::AlertSubscription;
+use crate::alerts::AlertManager;
 use crate::cache::CacheManager;
 use crate::database::Database;
 use crate::rpc::StellarRpcClient;
@@ -16,7 +17,7 @@ pub struct TelegramBot {
     client: Arc<TelegramClient>,
     command_handler: Arc<CommandHandler>,
     subscriptions: Arc<SubscriptionService>,
-    alert_rx: broadcast::Receiver<Alert>,
+    alert_rx: AlertSubscription,
 }
 
 impl TelegramBot {
@@ -35,8 +36,9 @@ impl TelegramBot {
             cache,
             rpc_client,
             Arc::clone(&subscriptions),
+            Arc::clone(&client),
         ));
-        let alert_rx = alert_manager.subscribe();
+        let alert_rx = alert_manager.subscribe_durable("telegram");
 
         Self {
             client,
@@ -133,22 +135,8 @@ async fn polling_loop(
                                 if let Some(text) = &message.text {
                                     if let Some((command, args)) = parse_command(text) {
                                         let chat_id = message.chat.id;
-                                        let chat_type = &message.chat.chat_type;
-                                        let chat_title = message.chat.title.as_deref();
-                                        let username = message
-                                            .from
-                                            .as_ref()
-                                            .and_then(|u| u.username.as_deref());
-
                                         let response = handler
-                                            .handle_command(
-                                                command,
-                                                args,
-                                                chat_id,
-                                                chat_type,
-                                                chat_title,
-                                                username,
-                                            )
+                                            .handle_command(command, args, message)
                                             .await;
 
                                         if let Err(e) = client.send_message(chat_id, &response).await {
@@ -180,7 +168,7 @@ async fn polling_loop(
 async fn alert_loop(
     client: Arc<TelegramClient>,
     subscriptions: Arc<SubscriptionService>,
-    mut alert_rx: broadcast::Receiver<Alert>,
+    mut alert_rx: AlertSubscription,
     mut shutdown_rx: broadcast::Receiver<()>,
 ) {
     tracing::info!("Telegram alert forwarding started");
@@ -189,7 +177,7 @@ async fn alert_loop(
         tokio::select! {
             result = alert_rx.recv() => {
                 match result {
-                    Ok(alert) => {
+                    Some(alert) => {
                         let message = formatter::format_alert(&alert);
 
                         match subscriptions.get_active_chat_ids().await {
@@ -213,10 +201,7 @@ async fn alert_loop(
                             }
                         }
                     }
-                    Err(broadcast::error::RecvError::Lagged(n)) => {
-                        tracing::warn!("Telegram alert receiver lagged by {} messages", n);
-                    }
-                    Err(broadcast::error::RecvError::Closed) => {
+                    None => {
                         tracing::info!("Alert channel closed, stopping alert loop");
                         break;
                     }
@@ -262,11 +247,15 @@ async fn register_commands(client: &TelegramClient) -> anyhow::Result<()> {
         },
         BotCommand {
             command: "subscribe".to_string(),
-            description: "Subscribe to alerts".to_string(),
+            description: "Subscribe to alerts (admins only in groups)".to_string(),
         },
         BotCommand {
             command: "unsubscribe".to_string(),
-            description: "Unsubscribe from alerts".to_string(),
+            description: "Unsubscribe from alerts (admins only in groups)".to_string(),
+        },
+        BotCommand {
+            command: "recommendations".to_string(),
+            description: "Weekly corridors to watch (admins only in groups)".to_string(),
         },
     ];
 
@@ -26,6 +26,8 @@ pub struct Message {
     pub message_id: i64,
     pub chat: Chat,
     pub from: Option<User>,
+    /// Set when the message was sent on behalf of a chat, e.g. by an anonymous group admin.
+    pub sender_chat: Option<Chat>,
     pub text: Option<String>,
     pub date: i64,
 }
@@ -47,6 +49,20 @@ pub struct User {
     pub username: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatMember {
+    /// `creator`, `administrator`, `member`, `restricted`, `left` or `kicked`
+    pub status: String,
+    pub user: User,
+}
+
+impl ChatMember {
+    #[must_use]
+    pub fn is_admin(&self) -> bool {
+        matches!(self.status.as_str(), "creator" | "administrator")
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct BotCommand {
     pub command: String,
@@ -67,6 +83,12 @@ struct GetUpdatesRequest {
     allowed_updates: Vec<String>,
 }
 
+#[derive(Debug, Serialize)]
+struct GetChatMemberRequest {
+    chat_id: i64,
+    user_id: i64,
+}
+
 #[derive(Debug, Serialize)]
 struct SetMyCommandsRequest<'a> {
     commands: &'a [BotCommand],
@@ -154,6 +176,27 @@ impl TelegramClient {
         Ok(())
     }
 
+    pub async fn get_chat_member(&self, chat_id: i64, user_id: i64) -> anyhow::Result<ChatMember> {
+        let body = GetChatMemberRequest { chat_id, user_id };
+
+        let resp: TelegramResponse<ChatMember> = self
+            .client
+            .post(format!("{}/getChatMember", self.base_url))
+            .json(&body)
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        match resp.result {
+            Some(member) if resp.ok => Ok(member),
+            _ => anyhow::bail!(
+                "Telegram getChatMember failed: {}",
+                resp.description.unwrap_or_default()
+            ),
+        }
+    }
+
     pub async fn set_my_commands(&self, commands: &[BotCommand]) -> anyhow::Result<()> {
         let body = SetMyCommandsRequest { commands };
 
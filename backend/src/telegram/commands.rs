@@ -2,15 +2,27 @@ use std::sync::Arc;
 
 use crate::cache::CacheManager;
 use crate::database::Database;
+use crate::log_event;
+use crate::logging::Subsystem;
 use crate::rpc::{StellarRpcClient, circuit_breaker::rpc_circuit_breaker};
+use crate::services::recommendations::{parse_watchlist, RecommendationService};
+use crate::telegram::client::{Message, TelegramClient};
 use crate::telegram::formatter;
 use crate::telegram::subscription::SubscriptionService;
 
+/// Commands that change what a chat receives. In group chats only administrators may
+/// run them, so one member can't silence a team's alert channel; new subscription or
+/// threshold commands belong here too.
+const ADMIN_COMMANDS: &[&str] = &["subscribe", "unsubscribe", "recommendations"];
+
+const RECOMMENDATIONS_USAGE: &str = "Usage: /recommendations [region <name> | watch <corridor keys> | off]\nExample: /recommendations watch USDC:GA5Z->XLM:native";
+
 pub struct CommandHandler {
     db: Arc<Database>,
     cache: Arc<CacheManager>,
     rpc_client: Arc<StellarRpcClient>,
     subscriptions: Arc<SubscriptionService>,
+    client: Arc<TelegramClient>,
 }
 
 impl CommandHandler {
@@ -20,24 +32,49 @@ impl CommandHandler {
         cache: Arc<CacheManager>,
         rpc_client: Arc<StellarRpcClient>,
         subscriptions: Arc<SubscriptionService>,
+        client: Arc<TelegramClient>,
     ) -> Self {
         Self {
             db,
             cache,
             rpc_client,
             subscriptions,
+            client,
         }
     }
 
-    pub async fn handle_command(
-        &self,
-        command: &str,
-        args: &str,
-        chat_id: i64,
-        chat_type: &str,
-        chat_title: Option<&str>,
-        username: Option<&str>,
-    ) -> String {
+    pub async fn handle_command(&self, command: &str, args: &str, message: &Message) -> String {
+        let chat_id = message.chat.id;
+        let chat_type = message.chat.chat_type.as_str();
+
+        if requires_admin(command, chat_type) {
+            match self.sender_is_admin(message).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    return formatter::escape_markdown(&format!(
+                        "Only chat administrators can use /{command} in this group."
+                    ));
+                }
+                Err(e) => {
+                    log_event!(
+                        warn,
+                        Subsystem::Telegram,
+                        "telegram.admin_check_failed",
+                        chat_id,
+                        command,
+                        error = %e,
+                        "Failed to check Telegram admin status"
+                    );
+                    return formatter::escape_markdown(&format!(
+                        "Couldn't verify that you are a chat administrator, so /{command} was not applied. Please try again."
+                    ));
+                }
+            }
+        }
+
+        let chat_title = message.chat.title.as_deref();
+        let username = message.from.as_ref().and_then(|u| u.username.as_deref());
+
         match command {
             "start" | "help" => formatter::format_help(),
             "status" => self.handle_status().await,
@@ -50,6 +87,7 @@ impl CommandHandler {
                     .await
             }
             "unsubscribe" => self.handle_unsubscribe(chat_id).await,
+            "recommendations" => self.handle_recommendations(chat_id, args).await,
             _ => formatter::escape_markdown("Unknown command. Use /help for available commands."),
         }
     }
@@ -242,6 +280,23 @@ impl CommandHandler {
         }
     }
 
+    async fn sender_is_admin(&self, message: &Message) -> anyhow::Result<bool> {
+        // Anonymous admins post as the group itself; only admins can do that
+        if message
+            .sender_chat
+            .as_ref()
+            .is_some_and(|sender| sender.id == message.chat.id)
+        {
+            return Ok(true);
+        }
+        let Some(user) = &message.from else {
+            return Ok(false);
+        };
+
+        let member = self.client.get_chat_member(message.chat.id, user.id).await?;
+        Ok(member.is_admin())
+    }
+
     async fn handle_unsubscribe(&self, chat_id: i64) -> String {
         match self.subscriptions.unsubscribe(chat_id).await {
             Ok(true) => formatter::escape_markdown(
@@ -251,4 +306,73 @@ impl CommandHandler {
             Err(e) => formatter::escape_markdown(&format!("Failed to unsubscribe: {e}")),
         }
     }
+
+    /// `/recommendations` opts in to the weekly digest for every corridor,
+    /// `/recommendations region <name>` or `/recommendations watch <keys>` narrows it,
+    /// and `/recommendations off` opts out.
+    async fn handle_recommendations(&self, chat_id: i64, args: &str) -> String {
+        let service = RecommendationService::new(Arc::clone(&self.db));
+        let args = args.trim();
+        let (mode, rest) = args
+            .split_once(char::is_whitespace)
+            .map_or((args, ""), |(mode, rest)| (mode, rest.trim()));
+
+        let result = match mode.to_ascii_lowercase().as_str() {
+            "" => service.subscribe(chat_id, None, &[]).await.map(|()| {
+                "You will get a weekly digest of corridors with improving liquidity and costs."
+                    .to_string()
+            }),
+            "region" if !rest.is_empty() => {
+                service.subscribe(chat_id, Some(rest), &[]).await.map(|()| {
+                    format!("You will get a weekly digest of improving corridors in {rest}.")
+                })
+            }
+            "watch" => {
+                let watchlist = parse_watchlist(rest);
+                if watchlist.is_empty() {
+                    return formatter::escape_markdown(RECOMMENDATIONS_USAGE);
+                }
+                service.subscribe(chat_id, None, &watchlist).await.map(|()| {
+                    format!(
+                        "You will get a weekly digest covering {} watched corridors.",
+                        watchlist.len()
+                    )
+                })
+            }
+            "off" => service.unsubscribe(chat_id).await.map(|removed| {
+                if removed {
+                    "Weekly recommendations turned off.".to_string()
+                } else {
+                    "You are not receiving weekly recommendations.".to_string()
+                }
+            }),
+            _ => return formatter::escape_markdown(RECOMMENDATIONS_USAGE),
+        };
+
+        match result {
+            Ok(text) => formatter::escape_markdown(&text),
+            Err(e) => formatter::escape_markdown(&format!("Failed to update recommendations: {e}")),
+        }
+    }
+}
+
+/// Private chats manage their own subscription; groups need an administrator for
+/// anything in [`ADMIN_COMMANDS`].
+fn requires_admin(command: &str, chat_type: &str) -> bool {
+    matches!(chat_type, "group" | "supergroup") && ADMIN_COMMANDS.contains(&command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_group_management_commands_require_admin() {
+        assert!(requires_admin("subscribe", "group"));
+        assert!(requires_admin("unsubscribe", "supergroup"));
+        assert!(requires_admin("recommendations", "group"));
+        assert!(!requires_admin("subscribe", "private"));
+        assert!(!requires_admin("status", "group"));
+        assert!(!requires_admin("corridors", "supergroup"));
+    }
 }
@@ -24,6 +24,9 @@ pub fn format_alert(alert: &Alert) -> String {
         AlertType::LiquidityDecrease => ("\u{1F7E0}", "Liquidity Decrease"),
         AlertType::AnchorStatusChange => ("\u{1F504}", "Anchor Status Change"),
         AlertType::AnchorMetricChange => ("\u{1F4CA}", "Anchor Metric Change"),
+        AlertType::NetworkProtocolUpgrade => ("\u{26A0}", "Network Protocol Upgrade"),
+        AlertType::SlaBreach => ("\u{1F6A8}", "Corridor SLA Breach"),
+        AlertType::StateDivergence => ("\u{1F9ED}", "State Divergence"),
     };
 
     let corridor = escape_markdown(alert.corridor_id.as_deref().unwrap_or("N/A"));
@@ -198,8 +201,12 @@ pub fn format_help() -> String {
         ("/corridor <key>", "Detailed corridor info"),
         ("/anchors", "List anchors with reliability"),
         ("/anchor <id>", "Detailed anchor info"),
-        ("/subscribe", "Subscribe to alerts"),
-        ("/unsubscribe", "Unsubscribe from alerts"),
+        ("/subscribe", "Subscribe to alerts (admins only in groups)"),
+        ("/unsubscribe", "Unsubscribe from alerts (admins only in groups)"),
+        (
+            "/recommendations",
+            "Weekly corridors to watch; add region <name>, watch <keys> or off",
+        ),
         ("/help", "Show this message"),
     ];
 
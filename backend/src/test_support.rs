@@ -0,0 +1,129 @@
+//! Fixtures shared by the service unit tests.
+//!
+//! Tests get a single-connection in-memory SQLite database with only the tables they
+//! need, so each test starts empty and unrelated migrations (seed data in particular)
+//! stay out of the way.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use crate::database::Database;
+
+/// Migrations the fixtures can apply, in the order they must run.
+pub mod migrations {
+    pub const ANCHORS: &str = include_str!("../migrations/001_create_anchors.sql");
+    pub const METRICS_CORRIDORS_SNAPSHOTS: &str =
+        include_str!("../migrations/002_create_metrics_corridors_snapshots.sql");
+    pub const CORRIDOR_AGGREGATES: &str =
+        include_str!("../migrations/005_create_corridor_aggregates.sql");
+    pub const USERS: &str = include_str!("../migrations/006_create_users.sql");
+    pub const ADMIN_AUDIT_LOG: &str = include_str!("../migrations/018_create_admin_audit_log.sql");
+    pub const OAUTH_WEBHOOKS: &str = include_str!("../migrations/019_oauth_webhooks.sql");
+    pub const CONTRACT_EVENTS: &str = include_str!("../migrations/025_create_contract_events.sql");
+    pub const ALERT_SPILL: &str = include_str!("../migrations/030_create_alert_spill.sql");
+    pub const CORRIDOR_SLAS: &str = include_str!("../migrations/031_create_corridor_slas.sql");
+    pub const SNAPSHOT_REANCHORS: &str =
+        include_str!("../migrations/032_create_snapshot_reanchors.sql");
+    pub const IDEMPOTENCY_KEYS: &str =
+        include_str!("../migrations/033_create_idempotency_keys.sql");
+    pub const REPLAY_BENCH_RUNS: &str =
+        include_str!("../migrations/034_create_replay_bench_runs.sql");
+    pub const SOROBAN_FEE_ESTIMATES: &str =
+        include_str!("../migrations/035_create_soroban_fee_estimates.sql");
+    pub const GEOGRAPHY_MAPPINGS: &str =
+        include_str!("../migrations/036_create_geography_mappings.sql");
+    pub const CORRIDOR_RATE_INDEX: &str =
+        include_str!("../migrations/037_create_corridor_rate_index.sql");
+    pub const CONTRACT_WEBHOOK_FORWARDS: &str =
+        include_str!("../migrations/038_create_contract_webhook_forwards.sql");
+    pub const CLUSTER_LEASES: &str = include_str!("../migrations/039_create_cluster_leases.sql");
+    pub const RECOMMENDATION_SUBSCRIPTIONS: &str =
+        include_str!("../migrations/041_create_recommendation_subscriptions.sql");
+    pub const RECOMPUTE_RANGES: &str =
+        include_str!("../migrations/042_create_recompute_ranges.sql");
+    pub const SNAPSHOT_AGGREGATE_CACHE: &str =
+        include_str!("../migrations/044_create_snapshot_aggregate_cache.sql");
+    pub const RUNBOOK_ACTIONS: &str = include_str!("../migrations/045_create_runbook_actions.sql");
+
+    /// Every replay table; see [`crate::replay::bench::REPLAY_MIGRATIONS`]
+    pub use crate::replay::bench::REPLAY_MIGRATIONS as REPLAY;
+    pub const REPLAY_TABLES: &str = REPLAY[0];
+}
+
+/// In-memory database with `migrations` applied in order.
+///
+/// One connection only: every connection to `sqlite::memory:` opens its own database.
+pub async fn sqlite_pool(migrations: &[&str]) -> SqlitePool {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    for migration in migrations {
+        sqlx::raw_sql(migration).execute(&pool).await.unwrap();
+    }
+    pool
+}
+
+/// [`sqlite_pool`] wrapped in a [`Database`].
+pub async fn sqlite_db(migrations: &[&str]) -> Arc<Database> {
+    Arc::new(Database::new(sqlite_pool(migrations).await))
+}
+
+/// One `corridor_metrics_hourly` row; unset metrics keep their column defaults.
+#[derive(Debug, Clone, Default)]
+pub struct CorridorHour<'a> {
+    pub corridor_key: &'a str,
+    /// Asset A code and issuer, then asset B code and issuer
+    pub assets: [&'a str; 4],
+    pub hour: DateTime<Utc>,
+    pub total: i64,
+    pub successful: i64,
+    pub volume_usd: f64,
+    pub avg_slippage_bps: f64,
+    pub avg_settlement_latency_ms: Option<i64>,
+    pub liquidity_depth_usd: f64,
+}
+
+/// Insert `row` into `corridor_metrics_hourly`, deriving the failure count and
+/// success rate from the totals.
+pub async fn insert_hour(pool: &SqlitePool, row: &CorridorHour<'_>) {
+    let success_rate = if row.total > 0 {
+        row.successful as f64 / row.total as f64 * 100.0
+    } else {
+        0.0
+    };
+    sqlx::query(
+        r"
+        INSERT INTO corridor_metrics_hourly (
+            id, corridor_key, asset_a_code, asset_a_issuer, asset_b_code, asset_b_issuer,
+            hour_bucket, total_transactions, successful_transactions, failed_transactions,
+            success_rate, volume_usd, avg_slippage_bps, avg_settlement_latency_ms,
+            liquidity_depth_usd
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(row.corridor_key)
+    .bind(row.assets[0])
+    .bind(row.assets[1])
+    .bind(row.assets[2])
+    .bind(row.assets[3])
+    .bind(row.hour.to_rfc3339())
+    .bind(row.total)
+    .bind(row.successful)
+    .bind(row.total - row.successful)
+    .bind(success_rate)
+    .bind(row.volume_usd)
+    .bind(row.avg_slippage_bps)
+    .bind(row.avg_settlement_latency_ms)
+    .bind(row.liquidity_depth_usd)
+    .execute(pool)
+    .await
+    .unwrap();
+}
@@ -44,6 +44,19 @@ pub struct CorridorLiquidityDroppedEvent {
     pub severity: String,        // "warning" | "critical"
 }
 
+/// On-chain contract event forwarded by the contract event webhook bridge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractActivityEvent {
+    pub contract_id: String,
+    /// Soroban RPC event id; stable across redeliveries of the same event
+    pub event_id: String,
+    pub ledger: u64,
+    pub ledger_closed_at: String,
+    pub topic: Vec<String>,
+    /// Decoded event body as emitted by the contract
+    pub data: serde_json::Value,
+}
+
 /// Corridor Metrics snapshot
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CorridorMetrics {
@@ -83,6 +83,7 @@ pub enum WebhookEventType {
     AnchorStatusChanged,
     PaymentCreated,
     CorridorLiquidityDropped,
+    ContractSnapshotSubmitted,
 }
 
 impl WebhookEventType {
@@ -93,6 +94,7 @@ impl WebhookEventType {
             Self::AnchorStatusChanged => "anchor.status_changed",
             Self::PaymentCreated => "payment.created",
             Self::CorridorLiquidityDropped => "corridor.liquidity_dropped",
+            Self::ContractSnapshotSubmitted => "contract.snapshot_submitted",
         }
     }
 
@@ -103,6 +105,7 @@ impl WebhookEventType {
             "anchor.status_changed" => Some(Self::AnchorStatusChanged),
             "payment.created" => Some(Self::PaymentCreated),
             "corridor.liquidity_dropped" => Some(Self::CorridorLiquidityDropped),
+            "contract.snapshot_submitted" => Some(Self::ContractSnapshotSubmitted),
             _ => None,
         }
     }
@@ -186,3 +186,124 @@ fn test_stack_trace_in_debug_mode() {
         assert!(response.error.stack_trace.is_none());
     }
 }
+
+#[tokio::test]
+async fn test_problem_details_middleware_adds_correlation_id() {
+    use axum::{body::to_bytes, http::Request, middleware, routing::get, Router};
+    use stellar_insights_backend::error::{problem_details_middleware, PROBLEM_JSON};
+    use stellar_insights_backend::request_id::request_id_middleware;
+    use tower::ServiceExt;
+
+    async fn missing() -> Result<(), ApiError> {
+        Err(ApiError::not_found(
+            "CORRIDOR_NOT_FOUND",
+            "Corridor not found",
+        ))
+    }
+
+    let app = Router::new()
+        .route("/corridors/missing", get(missing))
+        .layer(middleware::from_fn(problem_details_middleware))
+        .layer(middleware::from_fn(request_id_middleware));
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/corridors/missing")
+                .header("X-Request-ID", "corr-42")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+    assert_eq!(response.headers()["content-type"], PROBLEM_JSON);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let problem: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(problem["code"], "CORRIDOR_NOT_FOUND");
+    assert_eq!(problem["request_id"], "corr-42");
+    assert_eq!(problem["instance"], "/corridors/missing");
+
+    // Framework responses without a body become problems too
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/no-such-route")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+    assert_eq!(response.headers()["content-type"], PROBLEM_JSON);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let problem: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(problem["code"], "NOT_FOUND");
+    assert_eq!(problem["retryable"], false);
+    assert!(problem["request_id"].is_string());
+}
+
+#[tokio::test]
+async fn test_problem_details_middleware_folds_json_error_bodies() {
+    use axum::{
+        body::to_bytes, http::Request, http::StatusCode, middleware, routing::get, Json, Router,
+    };
+    use std::sync::Arc;
+    use stellar_insights_backend::api::price_feed;
+    use stellar_insights_backend::error::{problem_details_middleware, PROBLEM_JSON};
+    use stellar_insights_backend::services::price_feed::{PriceFeedClient, PriceFeedConfig};
+    use tower::ServiceExt;
+
+    async fn legacy() -> (StatusCode, Json<serde_json::Value>) {
+        (
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({ "error": "Webhook already registered" })),
+        )
+    }
+
+    let price_feed = Arc::new(PriceFeedClient::new(
+        PriceFeedConfig::default(),
+        HashMap::new(),
+    ));
+    let app = Router::new()
+        .nest("/api/prices", price_feed::routes(price_feed))
+        .route("/legacy", get(legacy))
+        .layer(middleware::from_fn(problem_details_middleware));
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/prices/batch?assets=,")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    assert_eq!(response.headers()["content-type"], PROBLEM_JSON);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let problem: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(problem["code"], "NO_ASSETS");
+    assert_eq!(problem["detail"], "No assets provided");
+    assert_eq!(problem["instance"], "/api/prices/batch");
+
+    // Handlers that still build their own JSON error get the same envelope
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/legacy")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 409);
+    assert_eq!(response.headers()["content-type"], PROBLEM_JSON);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let problem: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(problem["code"], "CONFLICT");
+    assert_eq!(problem["detail"], "Webhook already registered");
+    assert_eq!(problem["instance"], "/legacy");
+}
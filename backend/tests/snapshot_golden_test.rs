@@ -0,0 +1,156 @@
+//! Golden-file tests for snapshot canonicalization.
+//!
+//! Each case under `tests/fixtures/snapshot_golden/v<schema>/<case>/` holds a recorded
+//! `input.json` together with the canonical JSON and SHA-256 hash that were produced
+//! when the case was recorded. Hashes of the canonical form are what gets anchored
+//! on-chain, so any drift here means previously submitted snapshots would no longer
+//! verify.
+//!
+//! Expected files:
+//! - `canonical.json` / `canonical.sha256`: `SnapshotService::serialize_deterministically`
+//!   (the submission path)
+//! - `generator.json` / `generator.sha256`: `SnapshotGenerator::to_canonical_json`
+//!
+//! If a change to serialization is intentional, bump `SCHEMA_VERSION`, add a new
+//! `v<schema>` directory and re-record it with `UPDATE_GOLDEN=1 cargo test --test
+//! snapshot_golden_test`. Existing version directories must keep passing.
+
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use stellar_insights_backend::services::snapshot::SnapshotService;
+use stellar_insights_backend::snapshot::{AnalyticsSnapshot, SnapshotGenerator, SCHEMA_VERSION};
+
+fn golden_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/snapshot_golden")
+}
+
+fn update_mode() -> bool {
+    std::env::var("UPDATE_GOLDEN").is_ok_and(|v| v == "1")
+}
+
+/// All `(schema_version, case_dir)` pairs, sorted for stable output.
+fn golden_cases() -> Vec<(u32, PathBuf)> {
+    let mut cases = Vec::new();
+    for entry in fs::read_dir(golden_root()).expect("golden fixture directory missing") {
+        let version_dir = entry.unwrap().path();
+        let Some(version) = version_dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_prefix('v'))
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        for case in fs::read_dir(&version_dir).unwrap() {
+            let case = case.unwrap().path();
+            if case.join("input.json").is_file() {
+                cases.push((version, case));
+            }
+        }
+    }
+    cases.sort();
+    cases
+}
+
+fn load_input(case: &Path) -> AnalyticsSnapshot {
+    let raw = fs::read_to_string(case.join("input.json")).unwrap();
+    serde_json::from_str(&raw)
+        .unwrap_or_else(|e| panic!("{}: invalid input.json: {e}", case.display()))
+}
+
+fn sha256_hex(data: &str) -> String {
+    hex::encode(Sha256::digest(data.as_bytes()))
+}
+
+fn assert_golden(case: &Path, file: &str, actual: &str) {
+    let path = case.join(file);
+    if update_mode() {
+        fs::write(&path, format!("{actual}\n")).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+    assert_eq!(
+        expected.trim_end_matches('\n'),
+        actual,
+        "golden mismatch in {}",
+        path.display()
+    );
+}
+
+#[test]
+fn test_golden_cases_exist_for_current_schema() {
+    let cases = golden_cases();
+    assert!(!cases.is_empty(), "no golden snapshot fixtures found");
+    assert!(
+        cases.iter().any(|(v, _)| *v == SCHEMA_VERSION),
+        "no golden fixtures recorded for current schema version {SCHEMA_VERSION}"
+    );
+}
+
+#[test]
+fn test_fixture_schema_versions_match_directory() {
+    for (version, case) in golden_cases() {
+        let input = load_input(&case);
+        assert_eq!(
+            input.schema_version,
+            version,
+            "{}: input schema_version does not match its v{version} directory",
+            case.display()
+        );
+        assert!(
+            version <= SCHEMA_VERSION,
+            "{}: fixture is newer than SCHEMA_VERSION {SCHEMA_VERSION}",
+            case.display()
+        );
+    }
+}
+
+#[test]
+fn test_service_canonical_json_matches_golden() {
+    for (_, case) in golden_cases() {
+        let canonical = SnapshotService::serialize_deterministically(load_input(&case)).unwrap();
+        assert_golden(&case, "canonical.json", &canonical);
+    }
+}
+
+#[test]
+fn test_service_hash_matches_golden() {
+    for (_, case) in golden_cases() {
+        let hash = SnapshotService::hash_snapshot_hex(load_input(&case)).unwrap();
+        assert_golden(&case, "canonical.sha256", &hash);
+
+        let canonical = SnapshotService::serialize_deterministically(load_input(&case)).unwrap();
+        assert_eq!(hash, sha256_hex(&canonical), "{}", case.display());
+    }
+}
+
+#[test]
+fn test_generator_matches_golden() {
+    for (_, case) in golden_cases() {
+        let canonical = SnapshotGenerator::to_canonical_json(load_input(&case)).unwrap();
+        assert_golden(&case, "generator.json", &canonical);
+
+        let hash = SnapshotGenerator::generate_hash_hex(load_input(&case)).unwrap();
+        assert_golden(&case, "generator.sha256", &hash);
+    }
+}
+
+#[test]
+fn test_golden_hashes_independent_of_input_order() {
+    for (_, case) in golden_cases() {
+        let expected = SnapshotService::hash_snapshot_hex(load_input(&case)).unwrap();
+
+        let mut reversed = load_input(&case);
+        reversed.anchor_metrics.reverse();
+        reversed.corridor_metrics.reverse();
+        reversed.region_pair_metrics.reverse();
+
+        assert_eq!(
+            SnapshotService::hash_snapshot_hex(reversed).unwrap(),
+            expected,
+            "{}: hash depends on metric ordering",
+            case.display()
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use futures::{Stream, StreamExt};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{client::IntoClientRequest, http::HeaderValue, Message},
+};
+use url::Url;
+
+use crate::client::Client;
+use crate::error::{Error, Result};
+use crate::models::Alert;
+
+impl Client {
+    /// Subscribes to `/ws/alerts`.
+    ///
+    /// The connection attempt is retried under the client's retry policy. The stream
+    /// ends when the server closes the socket; call again to resubscribe. Alerts sent
+    /// while disconnected are not replayed.
+    pub async fn subscribe_alerts(&self) -> Result<impl Stream<Item = Result<Alert>>> {
+        let url = self.websocket_url(&["ws", "alerts"]);
+        let mut request = url
+            .as_str()
+            .into_client_request()
+            .map_err(|e| Error::WebSocket(Box::new(e)))?;
+        if let Some(token) = self.bearer_token() {
+            if let Ok(value) = HeaderValue::from_str(&format!("Bearer {token}")) {
+                request.headers_mut().insert("authorization", value);
+            }
+        }
+
+        let retry = self.retry_policy();
+        let mut attempt = 1;
+        let socket = loop {
+            match connect_async(request.clone()).await {
+                Ok((socket, _)) => break socket,
+                Err(_) if attempt < retry.max_attempts => {
+                    tokio::time::sleep(retry.delay(attempt, None)).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(Error::WebSocket(Box::new(e))),
+            }
+        };
+
+        Ok(socket.filter_map(|message| async move {
+            match message {
+                Ok(Message::Text(text)) => Some(serde_json::from_str(&text).map_err(Error::from)),
+                Ok(_) => None,
+                Err(e) => Some(Err(Error::WebSocket(Box::new(e)))),
+            }
+        }))
+    }
+
+    fn websocket_url(&self, segments: &[&str]) -> Url {
+        let mut url = self.base_url().clone();
+        let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+        // http(s) and ws(s) are all special schemes, so switching cannot fail.
+        let _ = url.set_scheme(scheme);
+        if let Ok(mut path) = url.path_segments_mut() {
+            path.pop_if_empty().extend(segments);
+        }
+        url
+    }
+}
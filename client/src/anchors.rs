@@ -0,0 +1,47 @@
+use futures::Stream;
+
+use crate::client::Client;
+use crate::error::Result;
+use crate::models::{Anchor, AnchorDetail, AnchorMetrics, AnchorsPage};
+use crate::pagination::paginate;
+
+/// The server clamps anchor pages to this size.
+const MAX_ANCHOR_PAGE: u32 = 100;
+
+#[derive(serde::Serialize)]
+struct PageQuery {
+    limit: u32,
+    offset: u32,
+}
+
+impl Client {
+    /// One page of `GET /api/v1/anchors`.
+    pub async fn list_anchors(&self, limit: u32, offset: u32) -> Result<AnchorsPage> {
+        let query = PageQuery {
+            limit: limit.min(MAX_ANCHOR_PAGE),
+            offset,
+        };
+        self.get_json(&["anchors"], &query).await
+    }
+
+    /// Every anchor, fetched `page_size` (at most 100) at a time.
+    pub fn anchors(&self, page_size: u32) -> impl Stream<Item = Result<AnchorMetrics>> {
+        let client = self.clone();
+        paginate(page_size.min(MAX_ANCHOR_PAGE), move |limit, offset| {
+            let client = client.clone();
+            async move { Ok(client.list_anchors(limit, offset).await?.anchors) }
+        })
+    }
+
+    /// `GET /api/v1/anchors/{id}` with assets and metrics history.
+    pub async fn anchor(&self, anchor_id: &str) -> Result<AnchorDetail> {
+        self.get_json(&["anchors", anchor_id], &()).await
+    }
+
+    /// `GET /api/v1/anchors/account/{stellar_account}`; muxed M-addresses resolve to
+    /// their base account.
+    pub async fn anchor_by_account(&self, stellar_account: &str) -> Result<Anchor> {
+        self.get_json(&["anchors", "account", stellar_account], &())
+            .await
+    }
+}
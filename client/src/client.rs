@@ -0,0 +1,226 @@
+use std::time::Duration;
+
+use reqwest::{header::HeaderMap, RequestBuilder, Response};
+use serde::{de::DeserializeOwned, Serialize};
+use url::Url;
+
+use crate::error::{Error, Problem, Result};
+use crate::retry::RetryPolicy;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Async client for one Stellar Insights deployment.
+///
+/// Cloning is cheap and clones share the connection pool.
+#[derive(Debug, Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: Url,
+    bearer_token: Option<String>,
+    retry: RetryPolicy,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+    base_url: String,
+    bearer_token: Option<String>,
+    retry: RetryPolicy,
+    timeout: Duration,
+    user_agent: String,
+}
+
+impl ClientBuilder {
+    /// Sent as `Authorization: Bearer ...` on every request.
+    #[must_use]
+    pub fn bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    #[must_use]
+    pub const fn retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Per-attempt timeout; retries each get the full timeout.
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn build(self) -> Result<Client> {
+        let mut base_url = Url::parse(&self.base_url)?;
+        if base_url.cannot_be_a_base() {
+            return Err(Error::Url(
+                url::ParseError::RelativeUrlWithCannotBeABaseBase,
+            ));
+        }
+        if let Ok(mut segments) = base_url.path_segments_mut() {
+            segments.pop_if_empty();
+        }
+
+        let http = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .user_agent(self.user_agent)
+            .build()?;
+
+        Ok(Client {
+            http,
+            base_url,
+            bearer_token: self.bearer_token,
+            retry: self.retry,
+        })
+    }
+}
+
+impl Client {
+    /// Client with default settings for `base_url`, e.g. `https://api.example.com`.
+    pub fn new(base_url: &str) -> Result<Self> {
+        Self::builder(base_url).build()
+    }
+
+    #[must_use]
+    pub fn builder(base_url: impl Into<String>) -> ClientBuilder {
+        ClientBuilder {
+            base_url: base_url.into(),
+            bearer_token: None,
+            retry: RetryPolicy::default(),
+            timeout: DEFAULT_TIMEOUT,
+            user_agent: concat!("stellar-insights-client/", env!("CARGO_PKG_VERSION")).to_string(),
+        }
+    }
+
+    #[must_use]
+    pub const fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    pub(crate) const fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    pub(crate) fn bearer_token(&self) -> Option<&str> {
+        self.bearer_token.as_deref()
+    }
+
+    /// URL under `/api/v1`; segments are percent-encoded, so corridor keys such as
+    /// `USDC:GA...->XLM:native` can be passed as-is.
+    pub(crate) fn endpoint(&self, segments: &[&str]) -> Url {
+        let mut url = self.base_url.clone();
+        if let Ok(mut path) = url.path_segments_mut() {
+            path.pop_if_empty().extend(["api", "v1"]).extend(segments);
+        }
+        url
+    }
+
+    pub(crate) async fn get_json<T, Q>(&self, segments: &[&str], query: &Q) -> Result<T>
+    where
+        T: DeserializeOwned,
+        Q: Serialize + Sync + ?Sized,
+    {
+        let url = self.endpoint(segments);
+        let response = self
+            .execute(|| self.http.get(url.clone()).query(query))
+            .await?;
+        decode(response).await
+    }
+
+    pub(crate) async fn post_json<T, B>(&self, segments: &[&str], body: &B) -> Result<T>
+    where
+        T: DeserializeOwned,
+        B: Serialize + Sync + ?Sized,
+    {
+        let url = self.endpoint(segments);
+        let response = self
+            .execute(|| self.http.post(url.clone()).json(body))
+            .await?;
+        decode(response).await
+    }
+
+    pub(crate) async fn get_bytes(&self, segments: &[&str]) -> Result<(HeaderMap, Vec<u8>)> {
+        let url = self.endpoint(segments);
+        let response = self.execute(|| self.http.get(url.clone())).await?;
+        let headers = response.headers().clone();
+        Ok((headers, response.bytes().await?.to_vec()))
+    }
+
+    /// Sends the request built by `build`, rebuilding and resending it while the
+    /// retry policy allows.
+    async fn execute(&self, build: impl Fn() -> RequestBuilder) -> Result<Response> {
+        let mut attempt = 1;
+        loop {
+            let mut request = build();
+            if let Some(token) = &self.bearer_token {
+                request = request.bearer_auth(token);
+            }
+
+            let (error, retry, retry_after) = match request.send().await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) => {
+                    let status = response.status();
+                    let header_retry_after = retry_after(response.headers());
+                    let body = response.text().await.unwrap_or_default();
+                    let problem = Problem::from_body(&body);
+                    let retry = RetryPolicy::should_retry_status(status, &problem);
+                    let retry_after = header_retry_after
+                        .or_else(|| problem.retry_after_seconds.map(Duration::from_secs));
+                    let error = Error::Api {
+                        status: status.as_u16(),
+                        problem: Box::new(problem),
+                    };
+                    (error, retry, retry_after)
+                }
+                Err(e) => {
+                    let retry = RetryPolicy::should_retry_error(&e);
+                    (Error::Http(e), retry, None)
+                }
+            };
+
+            if !retry || attempt >= self.retry.max_attempts {
+                return Err(error);
+            }
+            tokio::time::sleep(self.retry.delay(attempt, retry_after)).await;
+            attempt += 1;
+        }
+    }
+}
+
+async fn decode<T: DeserializeOwned>(response: Response) -> Result<T> {
+    let bytes = response.bytes().await?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+/// `Retry-After` in delta-seconds; HTTP-date values are ignored.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_encodes_segments_under_base_path() {
+        let client = Client::new("https://insights.example.com/stellar/").unwrap();
+        let url = client.endpoint(&["corridors", "USDC:GA5Z->XLM:native"]);
+        assert_eq!(
+            url.as_str(),
+            "https://insights.example.com/stellar/api/v1/corridors/USDC:GA5Z-%3EXLM:native"
+        );
+    }
+}
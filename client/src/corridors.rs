@@ -0,0 +1,57 @@
+use futures::Stream;
+
+use crate::client::Client;
+use crate::error::Result;
+use crate::models::{Corridor, CorridorDetail, CorridorFilter, CorridorSummary};
+use crate::pagination::paginate;
+
+#[derive(serde::Serialize)]
+struct ListQuery<'a> {
+    limit: u32,
+    offset: u32,
+    #[serde(flatten)]
+    filter: &'a CorridorFilter,
+}
+
+impl Client {
+    /// One page of `GET /api/v1/corridors`.
+    pub async fn list_corridors(
+        &self,
+        filter: &CorridorFilter,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<Corridor>> {
+        let query = ListQuery {
+            limit,
+            offset,
+            filter,
+        };
+        self.get_json(&["corridors"], &query).await
+    }
+
+    /// Every corridor matching `filter`, fetched `page_size` at a time.
+    pub fn corridors(
+        &self,
+        filter: CorridorFilter,
+        page_size: u32,
+    ) -> impl Stream<Item = Result<Corridor>> {
+        let client = self.clone();
+        paginate(page_size, move |limit, offset| {
+            let client = client.clone();
+            let filter = filter.clone();
+            async move { client.list_corridors(&filter, limit, offset).await }
+        })
+    }
+
+    /// `GET /api/v1/corridors/{key}`; check `sections` for parts served from cache
+    /// or left out under load.
+    pub async fn corridor(&self, corridor_key: &str) -> Result<CorridorDetail> {
+        self.get_json(&["corridors", corridor_key], &()).await
+    }
+
+    /// `GET /api/v1/corridors/{key}/summary`, the hourly rollup read model.
+    pub async fn corridor_summary(&self, corridor_key: &str) -> Result<CorridorSummary> {
+        self.get_json(&["corridors", corridor_key, "summary"], &())
+            .await
+    }
+}
@@ -0,0 +1,77 @@
+use serde::Deserialize;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("invalid URL: {0}")]
+    Url(#[from] url::ParseError),
+
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The API answered with a non-2xx status.
+    #[error("API error {status}: {}", .problem.summary())]
+    Api { status: u16, problem: Box<Problem> },
+
+    #[error("failed to decode response: {0}")]
+    Decode(#[from] serde_json::Error),
+
+    #[error("WebSocket error: {0}")]
+    WebSocket(#[from] Box<tokio_tungstenite::tungstenite::Error>),
+}
+
+impl Error {
+    /// The stable machine-readable error code, when the API sent one.
+    #[must_use]
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            Self::Api { problem, .. } => problem.code.as_deref(),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn status(&self) -> Option<u16> {
+        match self {
+            Self::Api { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+}
+
+/// RFC 7807 problem document returned by the API on errors.
+///
+/// Every field is optional so bodies from proxies or older deployments still parse.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub problem_type: Option<String>,
+    pub title: Option<String>,
+    pub status: Option<u16>,
+    pub detail: Option<String>,
+    pub instance: Option<String>,
+    pub code: Option<String>,
+    pub retryable: Option<bool>,
+    pub retry_after_seconds: Option<u64>,
+    pub request_id: Option<String>,
+    pub details: Option<serde_json::Value>,
+}
+
+impl Problem {
+    /// Parses a problem body, falling back to the raw text as `detail`.
+    pub(crate) fn from_body(body: &str) -> Self {
+        serde_json::from_str(body).unwrap_or_else(|_| Self {
+            detail: (!body.is_empty()).then(|| body.to_string()),
+            ..Self::default()
+        })
+    }
+
+    fn summary(&self) -> &str {
+        self.detail
+            .as_deref()
+            .or(self.title.as_deref())
+            .unwrap_or("no details")
+    }
+}
@@ -0,0 +1,37 @@
+//! Typed async client for the Stellar Insights REST and WebSocket APIs.
+//!
+//! Covers corridors, anchors, snapshot verification and live alerts. Requests that
+//! fail with a connection error, a timeout or a retryable status are retried under a
+//! [`RetryPolicy`], and listings are exposed both page by page and as streams that
+//! walk every page.
+//!
+//! ```no_run
+//! use futures::TryStreamExt;
+//! use stellar_insights_client::{models::CorridorFilter, Client};
+//!
+//! # async fn run() -> stellar_insights_client::Result<()> {
+//! let client = Client::new("https://insights.example.com")?;
+//! let corridors: Vec<_> = client
+//!     .corridors(CorridorFilter::default(), 100)
+//!     .try_collect()
+//!     .await?;
+//! let detail = client.corridor(&corridors[0].id).await?;
+//! println!("{} {:.1}%", detail.corridor.id, detail.corridor.success_rate);
+//! # Ok(())
+//! # }
+//! ```
+
+mod alerts;
+mod anchors;
+mod client;
+mod corridors;
+mod error;
+pub mod models;
+mod pagination;
+mod retry;
+mod snapshots;
+
+pub use client::{Client, ClientBuilder};
+pub use error::{Error, Problem, Result};
+pub use retry::RetryPolicy;
+pub use snapshots::MAX_VERIFY_BATCH;
@@ -0,0 +1,308 @@
+//! Response and request types mirroring the API's JSON.
+//!
+//! Enums carry an `Unknown` fallback so a newer server adding a variant does not break
+//! deserialization in older clients.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// ---------------------------------------------------------------------------
+// Corridors
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CorridorSort {
+    #[default]
+    SuccessRate,
+    Volume,
+}
+
+/// Filters for `GET /api/v1/corridors`; pagination is handled by the client.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CorridorFilter {
+    pub sort_by: CorridorSort,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success_rate_min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success_rate_max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume_min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume_max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset_code: Option<String>,
+    /// `24h`, `7d` or `30d`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_period: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Corridor {
+    pub id: String,
+    pub source_asset: String,
+    pub destination_asset: String,
+    pub success_rate: f64,
+    pub total_attempts: i64,
+    pub successful_payments: i64,
+    pub failed_payments: i64,
+    pub average_latency_ms: f64,
+    pub median_latency_ms: f64,
+    pub p95_latency_ms: f64,
+    pub p99_latency_ms: f64,
+    pub liquidity_depth_usd: f64,
+    pub liquidity_volume_24h_usd: f64,
+    pub liquidity_trend: String,
+    pub health_score: f64,
+    pub last_updated: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuccessRatePoint {
+    pub timestamp: String,
+    pub success_rate: f64,
+    pub attempts: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyBucket {
+    pub latency_bucket_ms: i32,
+    pub count: i64,
+    pub percentage: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiquidityPoint {
+    pub timestamp: String,
+    pub liquidity_usd: f64,
+    pub volume_24h_usd: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionFreshness {
+    Fresh,
+    Cached,
+    Unavailable,
+    Skipped,
+    #[serde(other)]
+    Unknown,
+}
+
+/// How one part of a corridor detail response was sourced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DetailSection {
+    pub name: String,
+    pub freshness: SectionFreshness,
+    pub duration_ms: u64,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderBookDepth {
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+    pub spread_bps: Option<f64>,
+    pub bid_depth: f64,
+    pub ask_depth: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorridorHourlyPoint {
+    pub hour_bucket: String,
+    pub total_transactions: i64,
+    pub success_rate: f64,
+    pub volume_usd: f64,
+    pub avg_settlement_latency_ms: Option<i64>,
+    pub liquidity_depth_usd: f64,
+}
+
+/// Corridor read model built from hourly rollups.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorridorSummary {
+    pub corridor_key: String,
+    pub asset_a_code: String,
+    pub asset_a_issuer: String,
+    pub asset_b_code: String,
+    pub asset_b_issuer: String,
+    pub source_region: Option<String>,
+    pub destination_region: Option<String>,
+    pub total_transactions_24h: i64,
+    pub successful_transactions_24h: i64,
+    pub success_rate_24h: f64,
+    pub volume_usd_24h: f64,
+    pub avg_settlement_latency_ms_24h: Option<f64>,
+    pub liquidity_depth_usd: f64,
+    pub hourly: Vec<CorridorHourlyPoint>,
+    pub projected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorridorDetail {
+    pub corridor: Corridor,
+    pub historical_success_rate: Vec<SuccessRatePoint>,
+    pub latency_distribution: Vec<LatencyBucket>,
+    pub liquidity_trends: Vec<LiquidityPoint>,
+    pub related_corridors: Option<Vec<Corridor>>,
+    #[serde(default)]
+    pub order_book: Option<OrderBookDepth>,
+    #[serde(default)]
+    pub summary: Option<CorridorSummary>,
+    #[serde(default)]
+    pub sections: Vec<DetailSection>,
+}
+
+// ---------------------------------------------------------------------------
+// Anchors
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnchorMetrics {
+    pub id: String,
+    pub name: String,
+    pub stellar_account: String,
+    pub reliability_score: f64,
+    pub asset_coverage: usize,
+    pub failure_rate: f64,
+    pub total_transactions: i64,
+    pub successful_transactions: i64,
+    pub failed_transactions: i64,
+    /// `green`, `yellow` or `red`.
+    pub status: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnchorsPage {
+    pub anchors: Vec<AnchorMetrics>,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Anchor {
+    pub id: String,
+    pub name: String,
+    pub stellar_account: String,
+    pub home_domain: Option<String>,
+    pub total_transactions: i64,
+    pub successful_transactions: i64,
+    pub failed_transactions: i64,
+    pub total_volume_usd: f64,
+    pub avg_settlement_time_ms: i32,
+    pub reliability_score: f64,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnchorAsset {
+    pub id: String,
+    pub anchor_id: String,
+    pub asset_code: String,
+    pub asset_issuer: String,
+    pub total_supply: Option<f64>,
+    pub num_holders: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnchorMetricsHistory {
+    pub id: String,
+    pub anchor_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub success_rate: f64,
+    pub failure_rate: f64,
+    pub reliability_score: f64,
+    pub total_transactions: i64,
+    pub successful_transactions: i64,
+    pub failed_transactions: i64,
+    pub avg_settlement_time_ms: Option<i32>,
+    pub volume_usd: Option<f64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnchorDetail {
+    pub anchor: Anchor,
+    pub assets: Vec<AnchorAsset>,
+    pub metrics_history: Vec<AnchorMetricsHistory>,
+}
+
+// ---------------------------------------------------------------------------
+// Snapshots
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyItem {
+    pub epoch: u64,
+    /// Hex-encoded 32-byte snapshot hash.
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Verified,
+    NotVerified,
+    /// The contract could not be reached; retry later.
+    Unavailable,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyResult {
+    pub epoch: u64,
+    pub hash: String,
+    pub verdict: Verdict,
+    pub cached: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerifyBatchResponse {
+    pub results: Vec<VerifyResult>,
+    pub verified: usize,
+    pub not_verified: usize,
+    pub unavailable: usize,
+    pub contract_calls: usize,
+}
+
+/// Tar archive an auditor can check offline with the bundled `verify.py`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditBundle {
+    pub epoch: u64,
+    /// From the `X-Snapshot-Hash` header, when the server sent it.
+    pub snapshot_hash: Option<String>,
+    pub archive: Vec<u8>,
+}
+
+// ---------------------------------------------------------------------------
+// Alerts
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertType {
+    SuccessRateDrop,
+    LatencyIncrease,
+    LiquidityDecrease,
+    AnchorStatusChange,
+    AnchorMetricChange,
+    NetworkProtocolUpgrade,
+    SlaBreach,
+    StateDivergence,
+    #[serde(other)]
+    Unknown,
+}
+
+/// One alert pushed over `/ws/alerts`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    pub alert_type: AlertType,
+    pub corridor_id: Option<String>,
+    pub anchor_id: Option<String>,
+    pub message: String,
+    pub old_value: f64,
+    pub new_value: f64,
+    pub timestamp: String,
+}
@@ -0,0 +1,53 @@
+use std::future::Future;
+
+use futures::{stream, Stream, TryStreamExt};
+
+use crate::error::{Error, Result};
+
+/// Walks a limit/offset listing page by page, yielding items until a short page.
+///
+/// A page that fails ends the stream after yielding the error.
+pub fn paginate<T, F, Fut>(page_size: u32, fetch: F) -> impl Stream<Item = Result<T>>
+where
+    F: FnMut(u32, u32) -> Fut,
+    Fut: Future<Output = Result<Vec<T>>>,
+{
+    let page_size = page_size.max(1);
+    stream::try_unfold((fetch, Some(0u32)), move |(mut fetch, offset)| async move {
+        let Some(offset) = offset else {
+            return Ok::<_, Error>(None);
+        };
+        let page = fetch(page_size, offset).await?;
+        let next = (page.len() >= page_size as usize).then(|| offset.saturating_add(page_size));
+        Ok(Some((page, (fetch, next))))
+    })
+    .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+    .try_flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stops_after_short_page() {
+        let items: Vec<u32> = (0..7).collect();
+        let mut requests = Vec::new();
+        let collected: Vec<u32> = paginate(3, |limit, offset| {
+            requests.push(offset);
+            let page = items
+                .iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .copied()
+                .collect();
+            async move { Ok(page) }
+        })
+        .try_collect()
+        .await
+        .unwrap();
+
+        assert_eq!(collected, items);
+        assert_eq!(requests, vec![0, 3, 6]);
+    }
+}
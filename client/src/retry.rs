@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+use crate::error::Problem;
+
+/// How failed requests are retried.
+///
+/// Connection failures, timeouts, 429, 502, 503 and 504 responses, and problems the
+/// API marks `retryable`, are retried with exponential backoff. A `Retry-After` from
+/// the server replaces the computed backoff, capped at `max_backoff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts including the first; `1` disables retries.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    #[must_use]
+    pub const fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        }
+    }
+
+    /// Delay before the attempt after `attempt` (1-based).
+    pub(crate) fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let backoff = retry_after.unwrap_or_else(|| {
+            self.initial_backoff
+                .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        });
+        backoff.min(self.max_backoff)
+    }
+
+    pub(crate) fn should_retry_status(status: StatusCode, problem: &Problem) -> bool {
+        problem.retryable.unwrap_or(false)
+            || matches!(
+                status,
+                StatusCode::TOO_MANY_REQUESTS
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            )
+    }
+
+    pub(crate) fn should_retry_error(error: &reqwest::Error) -> bool {
+        error.is_connect() || error.is_timeout()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_and_is_capped() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+        };
+        assert_eq!(policy.delay(1, None), Duration::from_millis(100));
+        assert_eq!(policy.delay(2, None), Duration::from_millis(200));
+        assert_eq!(policy.delay(3, None), Duration::from_millis(350));
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(30))),
+            Duration::from_millis(350)
+        );
+    }
+
+    #[test]
+    fn test_retryable_statuses() {
+        let problem = Problem::default();
+        assert!(RetryPolicy::should_retry_status(
+            StatusCode::SERVICE_UNAVAILABLE,
+            &problem
+        ));
+        assert!(!RetryPolicy::should_retry_status(
+            StatusCode::NOT_FOUND,
+            &problem
+        ));
+        let retryable = Problem {
+            retryable: Some(true),
+            ..Problem::default()
+        };
+        assert!(RetryPolicy::should_retry_status(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            &retryable
+        ));
+    }
+}
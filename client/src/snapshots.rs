@@ -0,0 +1,40 @@
+use crate::client::Client;
+use crate::error::Result;
+use crate::models::{AuditBundle, VerifyBatchResponse, VerifyItem};
+
+/// Most pairs the server accepts in one verification request.
+pub const MAX_VERIFY_BATCH: usize = 1_000;
+
+#[derive(serde::Serialize)]
+struct VerifyBatchRequest<'a> {
+    items: &'a [VerifyItem],
+}
+
+impl Client {
+    /// Checks `(epoch, hash)` pairs against the on-chain snapshot contract in one call.
+    ///
+    /// Results come back in request order. Pairs with an `Unavailable` verdict could
+    /// not be checked and are worth resubmitting later.
+    pub async fn verify_snapshots(&self, items: &[VerifyItem]) -> Result<VerifyBatchResponse> {
+        self.post_json(&["verify", "batch"], &VerifyBatchRequest { items })
+            .await
+    }
+
+    /// Downloads the auditor bundle for `epoch`.
+    pub async fn audit_bundle(&self, epoch: u64) -> Result<AuditBundle> {
+        let epoch_segment = epoch.to_string();
+        let (headers, archive) = self
+            .get_bytes(&["snapshots", &epoch_segment, "audit-bundle"])
+            .await?;
+        let snapshot_hash = headers
+            .get("x-snapshot-hash")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+
+        Ok(AuditBundle {
+            epoch,
+            snapshot_hash,
+            archive,
+        })
+    }
+}
@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use futures::TryStreamExt;
+use serde_json::json;
+use stellar_insights_client::{models::Verdict, models::VerifyItem, Client, Error, RetryPolicy};
+use wiremock::matchers::{body_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[allow(clippy::unwrap_used)]
+fn client(server: &MockServer) -> Client {
+    Client::builder(server.uri())
+        .retry_policy(RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+        })
+        .bearer_token("token")
+        .build()
+        .unwrap()
+}
+
+fn anchor(id: u32) -> serde_json::Value {
+    json!({
+        "id": id.to_string(),
+        "name": format!("Anchor {id}"),
+        "stellar_account": "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN",
+        "reliability_score": 99.5,
+        "asset_coverage": 2,
+        "failure_rate": 0.5,
+        "total_transactions": 100,
+        "successful_transactions": 99,
+        "failed_transactions": 1,
+        "status": "green"
+    })
+}
+
+#[tokio::test]
+async fn test_anchors_stream_walks_every_page() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/anchors"))
+        .and(query_param("offset", "0"))
+        .and(header("authorization", "Bearer token"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "anchors": [anchor(1), anchor(2)], "total": 3 })),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/anchors"))
+        .and(query_param("offset", "2"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "anchors": [anchor(3)], "total": 3 })),
+        )
+        .mount(&server)
+        .await;
+
+    let anchors: Vec<_> = client(&server).anchors(2).try_collect().await.unwrap();
+    let ids: Vec<_> = anchors.iter().map(|a| a.id.as_str()).collect();
+    assert_eq!(ids, ["1", "2", "3"]);
+}
+
+#[tokio::test]
+async fn test_retries_unavailable_then_succeeds() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/verify/batch"))
+        .respond_with(ResponseTemplate::new(503).insert_header("retry-after", "1"))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/verify/batch"))
+        .and(body_json(
+            json!({ "items": [{ "epoch": 7, "hash": "ab" }] }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "results": [{ "epoch": 7, "hash": "ab", "verdict": "verified", "cached": false }],
+            "verified": 1,
+            "not_verified": 0,
+            "unavailable": 0,
+            "contract_calls": 1
+        })))
+        .mount(&server)
+        .await;
+
+    let response = client(&server)
+        .verify_snapshots(&[VerifyItem {
+            epoch: 7,
+            hash: "ab".to_string(),
+        }])
+        .await
+        .unwrap();
+    assert_eq!(response.results[0].verdict, Verdict::Verified);
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_problem_details_surface_without_retry() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/anchors/missing"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+            "type": "about:blank",
+            "title": "Not Found",
+            "status": 404,
+            "detail": "Anchor not found",
+            "code": "NOT_FOUND",
+            "retryable": false
+        })))
+        .mount(&server)
+        .await;
+
+    let err = client(&server).anchor("missing").await.unwrap_err();
+    assert_eq!(err.status(), Some(404));
+    assert_eq!(err.code(), Some("NOT_FOUND"));
+    assert!(matches!(err, Error::Api { .. }));
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
@@ -13,6 +13,20 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 const LEDGERS_TO_EXTEND: u32 = 518_400;
 const INSTANCE_TTL_THRESHOLD: u32 = 100_000;
 const INSTANCE_TTL_EXTEND: u32 = 518_400;
+/// Most epochs `verify_snapshot_range` checks per call, keeping its reads well
+/// inside the per-transaction ledger entry limit
+const MAX_VERIFY_RANGE: u32 = 50;
+/// Longest content URI accepted in snapshot metadata, in bytes; enough for an IPFS
+/// CID with its scheme
+const MAX_CONTENT_URI_LEN: u32 = 128;
+/// Most snapshots one submission or `prune_before` call removes. Each one clears
+/// several entries, so a large backlog is worked off over later calls instead of
+/// blowing one transaction's budget.
+const MAX_PRUNE_PER_CALL: u32 = 3;
+/// Ledgers attestations for an epoch that has not reached quorum are kept after the
+/// latest one (~1 day). Epochs passed over by a later commit can never reach quorum,
+/// so their attestations simply expire.
+const PENDING_ATTESTATION_TTL: u32 = 17_280;
 
 fn bump_instance(env: &Env) {
     env.storage()
@@ -41,6 +55,18 @@ pub enum Error {
     SignerNotAdmin = 15,
     ActionNotFound = 16,
     ActionExpired = 17,
+    UnauthorizedCaller = 18,
+    QuorumNotConfigured = 19,
+    AlreadyAttested = 20,
+    SnapshotRevoked = 21,
+    RangeTooLarge = 22,
+    InvalidProof = 23,
+    NotMerkleRoot = 24,
+    InvalidLeafCount = 25,
+    InvalidTtl = 26,
+    InvalidMetadata = 27,
+    QuorumRequired = 28,
+    HashAlreadyIndexed = 29,
 }
 
 #[contracttype]
@@ -51,14 +77,102 @@ pub struct Snapshot {
     pub timestamp: u64,
 }
 
+/// Which off-chain blob a snapshot hash covers. Stored beside the snapshot rather than
+/// inside it, so entries written before metadata existed still decode.
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct SnapshotSubmittedEvent {
+pub struct SnapshotMetadata {
+    pub schema_version: Option<u32>,
+    /// Short locator for the blob, e.g. an IPFS CID
+    pub content_uri: Option<String>,
+}
+
+/// Payload of `SNAP_SUB`, named and shaped like the stellar_insights contract's
+/// `SnapshotSubmitted` so indexers decode submissions from both the same way
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SnapshotSubmitted {
     pub epoch: u64,
     pub hash: Bytes,
     pub timestamp: u64,
     pub previous_epoch: u64, // 0 means no previous epoch
     pub ledger_sequence: u32,
+    /// The admin, or for attested snapshots the submitter whose attestation met quorum
+    pub submitter: Address,
+    /// Schema version from the snapshot's metadata, if any was recorded
+    pub schema_version: Option<u32>,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RevisionKind {
+    /// Withdrawn with no replacement
+    Revoked,
+    /// Replaced by a corrected hash
+    Superseded,
+}
+
+/// A hash that was once recorded for an epoch and later revoked or superseded
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SnapshotRevision {
+    pub hash: Bytes,
+    /// When the hash was originally recorded
+    pub recorded_at: u64,
+    /// When it was revoked or superseded
+    pub replaced_at: u64,
+    pub kind: RevisionKind,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SnapshotRevokedEvent {
+    pub epoch: u64,
+    pub hash: Bytes,
+    pub revoked_by: Address,
+    pub ledger_sequence: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SnapshotSupersededEvent {
+    pub epoch: u64,
+    /// Hash being replaced, `None` if the epoch had been revoked
+    pub previous_hash: Option<Bytes>,
+    pub new_hash: Bytes,
+    pub superseded_by: Address,
+    pub ledger_sequence: u32,
+}
+
+/// One registered submitter's claim that `hash` is the snapshot for an epoch
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attestation {
+    pub submitter: Address,
+    pub hash: Bytes,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SnapshotAttestedEvent {
+    pub epoch: u64,
+    pub hash: Bytes,
+    pub submitter: Address,
+    /// Attestations for this (epoch, hash), including this one
+    pub attestations: u32,
+    pub quorum: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SnapshotsPrunedEvent {
+    /// Snapshots with an epoch below this one are being removed, oldest first
+    pub before_epoch: u64,
+    /// Snapshots removed by this call; any left below `before_epoch` go on later calls
+    pub removed: u32,
+    pub remaining: u32,
+    pub ledger_sequence: u32,
 }
 
 #[contracttype]
@@ -77,6 +191,77 @@ pub struct UnpauseEvent {
     pub ledger_sequence: u32,
 }
 
+/// Payload of `STOPPED` and `RESUMED`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractStateEvent {
+    pub admin: Address,
+    pub ledger_sequence: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractUpgradedEvent {
+    pub new_wasm_hash: Bytes,
+    pub version: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractMigratedEvent {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+/// Payload of `SUB_ADD` and `SUB_REM`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubmitterChangedEvent {
+    pub submitter: Address,
+    pub changed_by: Address,
+}
+
+/// Payload of `INIT`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractInitializedEvent {
+    pub admin: Address,
+    pub version: u32,
+}
+
+/// Payload of `ADM_XFER`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminChangedEvent {
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+/// Payload of `UPG_PREP`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpgradePreparedEvent {
+    pub new_wasm_hash: Bytes,
+}
+
+/// Payload of `RET_SET`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetentionSetEvent {
+    /// `0` when every snapshot is kept
+    pub max_epochs: u32,
+    pub set_by: Address,
+}
+
+/// Payload of `QUORUM`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QuorumSetEvent {
+    /// `0` when attestation is off
+    pub quorum: u32,
+    pub set_by: Address,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ContractMetadata {
@@ -107,6 +292,33 @@ pub struct ContractInfo {
     pub total_snapshots: u64,
 }
 
+/// Returned by `get_stats`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SnapshotStats {
+    /// Epochs in the index, including revoked ones whose history is still stored
+    pub total_snapshots: u32,
+    pub earliest_epoch: Option<u64>,
+    pub latest_epoch: Option<u64>,
+    /// Ledger timestamp of the most recent submission
+    pub last_submission_at: Option<u64>,
+}
+
+/// Positions of the stored epochs: `DataKey::EpochAt(slot)` holds an epoch for
+/// every slot in `[first, next)`, in ascending epoch order
+#[contracttype]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct EpochSlots {
+    pub first: u32,
+    pub next: u32,
+}
+
+impl EpochSlots {
+    const fn len(self) -> u32 {
+        self.next - self.first
+    }
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct MultiSigConfig {
@@ -126,7 +338,18 @@ pub struct PendingAction {
 
 #[contracttype]
 pub enum DataKey {
+    /// Legacy single map of every snapshot, moved to per-epoch keys by `migrate`
     Snapshots,
+    /// One persistent entry per epoch
+    Snapshot(u64),
+    /// Legacy single vector of stored epochs, moved to `EpochAt` slots by `migrate`
+    EpochIndex,
+    /// Bounds of the occupied `EpochAt` slots
+    EpochSlots,
+    /// One stored epoch per slot, ascending with the slot number
+    EpochAt(u32),
+    /// Reverse index from snapshot hash to the live epochs that recorded it, ascending
+    HashEpoch(Bytes),
     LatestEpoch,
     Metadata,
     Admin,
@@ -136,6 +359,347 @@ pub enum DataKey {
     MultiSigConfig,
     PendingAction(u64),
     NextActionId,
+    RetentionEpochs,
+    /// Addresses allowed to attest snapshots
+    Submitters,
+    /// Matching attestations needed before a snapshot becomes canonical
+    AttestationQuorum,
+    /// Attestations collected for an epoch; temporary until quorum, then persistent
+    Attestations(u64),
+    /// Hashes previously recorded for an epoch, oldest first
+    SnapshotHistory(u64),
+    /// Leaves under an epoch whose snapshot hash is a Merkle root
+    MerkleLeafCount(u64),
+    /// Schema version and content URI recorded with an epoch's snapshot
+    SnapshotMetadata(u64),
+    /// Ledger timestamp of the last `commit_snapshot`
+    LastSubmissionAt,
+}
+
+fn epoch_slots(env: &Env) -> EpochSlots {
+    let slots: Option<EpochSlots> = env.storage().persistent().get(&DataKey::EpochSlots);
+    if slots.is_some() {
+        env.storage().persistent().extend_ttl(
+            &DataKey::EpochSlots,
+            LEDGERS_TO_EXTEND,
+            LEDGERS_TO_EXTEND,
+        );
+    }
+    slots.unwrap_or_default()
+}
+
+fn save_epoch_slots(env: &Env, slots: &EpochSlots) {
+    env.storage().persistent().set(&DataKey::EpochSlots, slots);
+    env.storage().persistent().extend_ttl(
+        &DataKey::EpochSlots,
+        LEDGERS_TO_EXTEND,
+        LEDGERS_TO_EXTEND,
+    );
+}
+
+/// Epoch stored in `slot`, bumping the slot's TTL
+fn epoch_at(env: &Env, slot: u32) -> Option<u64> {
+    let key = DataKey::EpochAt(slot);
+    let epoch: Option<u64> = env.storage().persistent().get(&key);
+    if epoch.is_some() {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+    }
+    epoch
+}
+
+/// Append `epoch` in the slot after the last one
+fn push_epoch(env: &Env, slots: &mut EpochSlots, epoch: u64) {
+    let key = DataKey::EpochAt(slots.next);
+    env.storage().persistent().set(&key, &epoch);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+    slots.next += 1;
+}
+
+/// Every stored epoch in ascending order. Reads one entry per epoch, so only
+/// `migrate` and tests call it.
+fn indexed_epochs(env: &Env) -> Vec<u64> {
+    let slots = epoch_slots(env);
+    let mut epochs = Vec::new(env);
+    for slot in slots.first..slots.next {
+        if let Some(epoch) = epoch_at(env, slot) {
+            epochs.push_back(epoch);
+        }
+    }
+    epochs
+}
+
+/// Read the latest epoch, bumping its TTL so the entry outlives quiet periods.
+fn latest_epoch(env: &Env) -> Option<u64> {
+    let latest: Option<u64> = env.storage().persistent().get(&DataKey::LatestEpoch);
+    if latest.is_some() {
+        env.storage().persistent().extend_ttl(
+            &DataKey::LatestEpoch,
+            LEDGERS_TO_EXTEND,
+            LEDGERS_TO_EXTEND,
+        );
+    }
+    latest
+}
+
+/// Read one epoch's snapshot, bumping its TTL so verified snapshots stay live.
+fn load_snapshot(env: &Env, epoch: u64) -> Option<Snapshot> {
+    let key = DataKey::Snapshot(epoch);
+    let snapshot: Option<Snapshot> = env.storage().persistent().get(&key);
+    if snapshot.is_some() {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+    }
+    snapshot
+}
+
+/// Write a snapshot under its own key and append its epoch to the index.
+/// Epochs are only ever appended in increasing order, so the index stays sorted.
+fn store_snapshot(env: &Env, slots: &mut EpochSlots, snapshot: &Snapshot) {
+    let key = DataKey::Snapshot(snapshot.epoch);
+    env.storage().persistent().set(&key, snapshot);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+    index_hash(env, &snapshot.hash, snapshot.epoch);
+    push_epoch(env, slots, snapshot.epoch);
+}
+
+/// Add `epoch` to the epochs recorded for `hash` in the reverse index. Every live
+/// copy of a repeated hash is kept, so retiring or pruning one leaves the hash
+/// verifiable through the others.
+fn index_hash(env: &Env, hash: &Bytes, epoch: u64) {
+    let key = DataKey::HashEpoch(hash.clone());
+    let mut epochs: Vec<u64> = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| Vec::new(env));
+    if let Err(position) = epochs.binary_search(epoch) {
+        epochs.insert(position, epoch);
+    }
+    env.storage().persistent().set(&key, &epochs);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+}
+
+/// Drop `epoch` from the epochs recorded for `hash`, removing the entry with the last one
+fn unindex_hash(env: &Env, hash: &Bytes, epoch: u64) {
+    let key = DataKey::HashEpoch(hash.clone());
+    let Some(mut epochs) = env.storage().persistent().get::<DataKey, Vec<u64>>(&key) else {
+        return;
+    };
+    let Ok(position) = epochs.binary_search(epoch) else {
+        return;
+    };
+    epochs.remove(position);
+    if epochs.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, &epochs);
+    }
+}
+
+/// Look up the latest live epoch that recorded `hash`, bumping the entry's TTL.
+fn epoch_for_hash(env: &Env, hash: &Bytes) -> Option<u64> {
+    let key = DataKey::HashEpoch(hash.clone());
+    let epochs: Vec<u64> = env.storage().persistent().get(&key)?;
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+    epochs.last()
+}
+
+/// Remove up to `MAX_PRUNE_PER_CALL` of the oldest snapshots with an epoch below
+/// `cutoff`, returning how many were removed.
+fn remove_before(env: &Env, slots: &mut EpochSlots, cutoff: u64) -> u32 {
+    let mut removed = 0;
+    while slots.first < slots.next && removed < MAX_PRUNE_PER_CALL {
+        let slot_key = DataKey::EpochAt(slots.first);
+        let Some(epoch) = env.storage().persistent().get::<DataKey, u64>(&slot_key) else {
+            slots.first += 1;
+            continue;
+        };
+        if epoch >= cutoff {
+            break;
+        }
+        let key = DataKey::Snapshot(epoch);
+        if let Some(snapshot) = env.storage().persistent().get::<DataKey, Snapshot>(&key) {
+            unindex_hash(env, &snapshot.hash, epoch);
+        }
+        env.storage().persistent().remove(&key);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Attestations(epoch));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::SnapshotHistory(epoch));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::MerkleLeafCount(epoch));
+        env.storage()
+            .persistent()
+            .remove(&DataKey::SnapshotMetadata(epoch));
+        env.storage().persistent().remove(&slot_key);
+        slots.first += 1;
+        removed += 1;
+    }
+    removed
+}
+
+fn publish_pruned(env: &Env, before_epoch: u64, removed: u32, remaining: u32) {
+    env.events().publish(
+        (Symbol::new(env, "SNAP_PRUNED"),),
+        SnapshotsPrunedEvent {
+            before_epoch,
+            removed,
+            remaining,
+            ledger_sequence: env.ledger().sequence(),
+        },
+    );
+}
+
+/// Make `hash` the canonical snapshot for `epoch`: store it, apply retention,
+/// advance the latest epoch and emit `SNAP_SUB`. Callers validate first.
+fn commit_snapshot(
+    env: &Env,
+    hash: Bytes,
+    epoch: u64,
+    previous: Option<u64>,
+    submitter: &Address,
+    schema_version: Option<u32>,
+) -> u64 {
+    let timestamp = env.ledger().timestamp();
+    let snapshot = Snapshot {
+        hash: hash.clone(),
+        epoch,
+        timestamp,
+    };
+
+    // Only this epoch's entries are written, never older snapshots
+    let mut slots = epoch_slots(env);
+    store_snapshot(env, &mut slots, &snapshot);
+
+    // Drop the oldest snapshots beyond the retention window, if one is set. A window
+    // shrunk far below the stored history is caught up a few snapshots per submission.
+    let retention: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::RetentionEpochs)
+        .unwrap_or(0);
+    if retention > 0 && slots.len() > retention {
+        if let Some(cutoff) = epoch_at(env, slots.next - retention) {
+            let removed = remove_before(env, &mut slots, cutoff);
+            publish_pruned(env, cutoff, removed, slots.len());
+        }
+    }
+
+    save_epoch_slots(env, &slots);
+    env.storage()
+        .persistent()
+        .set(&DataKey::LatestEpoch, &epoch);
+    env.storage().persistent().extend_ttl(
+        &DataKey::LatestEpoch,
+        LEDGERS_TO_EXTEND,
+        LEDGERS_TO_EXTEND,
+    );
+    env.storage()
+        .instance()
+        .set(&DataKey::LastSubmissionAt, &timestamp);
+
+    env.events().publish(
+        (symbol_short!("SNAP_SUB"),),
+        SnapshotSubmitted {
+            epoch,
+            hash,
+            timestamp,
+            previous_epoch: previous.unwrap_or(0),
+            ledger_sequence: env.ledger().sequence(),
+            submitter: submitter.clone(),
+            schema_version,
+        },
+    );
+    timestamp
+}
+
+/// Leaf node of a snapshot Merkle tree: `sha256(0x00 || leaf)`
+fn merkle_leaf(env: &Env, leaf: &Bytes) -> Bytes {
+    let mut data = Bytes::from_array(env, &[0]);
+    data.append(leaf);
+    env.crypto().sha256(&data).into()
+}
+
+/// Parent of two nodes: `sha256(0x01 || min(a, b) || max(a, b))`. Sorting the pair
+/// means proofs need no left/right flags; the prefixes keep leaves and inner nodes apart.
+fn merkle_parent(env: &Env, a: &Bytes, b: &Bytes) -> Bytes {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut data = Bytes::from_array(env, &[1]);
+    data.append(first);
+    data.append(second);
+    env.crypto().sha256(&data).into()
+}
+
+/// Height of a tree over `leaf_count` leaves, the longest proof it can have
+fn merkle_depth(leaf_count: u32) -> u32 {
+    let mut depth = 0;
+    while (1u64 << depth) < u64::from(leaf_count) {
+        depth += 1;
+    }
+    depth
+}
+
+fn snapshot_history(env: &Env, epoch: u64) -> Vec<SnapshotRevision> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::SnapshotHistory(epoch))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Move the live snapshot for an epoch into its history and drop it from the hash index
+fn retire_snapshot(env: &Env, snapshot: &Snapshot, kind: RevisionKind) {
+    let mut history = snapshot_history(env, snapshot.epoch);
+    history.push_back(SnapshotRevision {
+        hash: snapshot.hash.clone(),
+        recorded_at: snapshot.timestamp,
+        replaced_at: env.ledger().timestamp(),
+        kind,
+    });
+    let key = DataKey::SnapshotHistory(snapshot.epoch);
+    env.storage().persistent().set(&key, &history);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+
+    unindex_hash(env, &snapshot.hash, snapshot.epoch);
+}
+
+/// Whether the epoch's snapshot was revoked and not superseded since
+fn is_revoked(env: &Env, epoch: u64) -> bool {
+    !env.storage().persistent().has(&DataKey::Snapshot(epoch))
+        && snapshot_history(env, epoch)
+            .last()
+            .is_some_and(|revision| revision.kind == RevisionKind::Revoked)
+}
+
+/// Error for an epoch with no live snapshot, telling revoked data apart from missing data
+fn missing_snapshot(env: &Env, epoch: u64) -> Error {
+    if is_revoked(env, epoch) {
+        Error::SnapshotRevoked
+    } else {
+        Error::SnapshotNotFound
+    }
+}
+
+fn submitters(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Submitters)
+        .unwrap_or_else(|| Vec::new(env))
 }
 
 #[contract]
@@ -156,6 +720,14 @@ impl SnapshotContract {
         Ok(())
     }
 
+    /// Internal: reject snapshot writes while submissions are paused
+    fn require_not_paused(env: &Env) -> Result<(), Error> {
+        if Self::is_paused(env.clone()) {
+            return Err(Error::ContractPaused);
+        }
+        Ok(())
+    }
+
     /// Internal: check and set reentrancy guard
     fn check_and_set_reentrancy_guard(env: &Env) -> Result<(), &'static str> {
         let is_locked: bool = env
@@ -206,7 +778,13 @@ impl SnapshotContract {
         admin.require_auth();
         env.storage().instance().set(&DataKey::Stopped, &true);
         bump_instance(&env);
-        env.events().publish((symbol_short!("STOPPED"),), (admin,));
+        env.events().publish(
+            (symbol_short!("STOPPED"),),
+            ContractStateEvent {
+                admin,
+                ledger_sequence: env.ledger().sequence(),
+            },
+        );
         Ok(())
     }
 
@@ -216,7 +794,13 @@ impl SnapshotContract {
         admin.require_auth();
         env.storage().instance().set(&DataKey::Stopped, &false);
         bump_instance(&env);
-        env.events().publish((symbol_short!("RESUMED"),), (admin,));
+        env.events().publish(
+            (symbol_short!("RESUMED"),),
+            ContractStateEvent {
+                admin,
+                ledger_sequence: env.ledger().sequence(),
+            },
+        );
         Ok(())
     }
 
@@ -240,8 +824,13 @@ impl SnapshotContract {
             .instance()
             .extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL_EXTEND);
 
-        env.events()
-            .publish((symbol_short!("INIT"),), (admin, CONTRACT_VERSION));
+        env.events().publish(
+            (symbol_short!("INIT"),),
+            ContractInitializedEvent {
+                admin,
+                version: CONTRACT_VERSION,
+            },
+        );
         Ok(())
     }
 
@@ -272,20 +861,35 @@ impl SnapshotContract {
         Self::is_admin(env, addr)
     }
 
-    /// Transfer admin rights to a new address (only callable by existing admin)
-    pub fn transfer_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+    /// Rotate the admin key. `caller` must be the current admin.
+    pub fn set_admin(env: Env, caller: Address, new_admin: Address) -> Result<(), Error> {
         Self::require_not_stopped(&env)?;
+        caller.require_auth();
         let current_admin = Self::get_admin(&env)?;
-        current_admin.require_auth();
+        if caller != current_admin {
+            return Err(Error::UnauthorizedCaller);
+        }
 
         env.storage().instance().set(&DataKey::Admin, &new_admin);
         bump_instance(&env);
 
-        env.events()
-            .publish((symbol_short!("ADM_XFER"),), (current_admin, new_admin));
+        env.events().publish(
+            (symbol_short!("ADM_XFER"),),
+            AdminChangedEvent {
+                previous_admin: current_admin,
+                new_admin,
+            },
+        );
         Ok(())
     }
 
+    /// Transfer admin rights to a new address (only callable by existing admin).
+    /// Same as `set_admin` with the current admin as `caller`.
+    pub fn transfer_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        let current_admin = Self::get_admin(&env)?;
+        Self::set_admin(env, current_admin, new_admin)
+    }
+
     /// Prepare for contract upgrade by validating the new WASM hash
     pub fn prepare_upgrade(env: Env, new_wasm_hash: Bytes) -> Result<(), Error> {
         Self::require_not_stopped(&env)?;
@@ -296,14 +900,22 @@ impl SnapshotContract {
             return Err(Error::InvalidHashSize);
         }
 
-        env.events()
-            .publish((symbol_short!("UPG_PREP"),), (new_wasm_hash,));
+        env.events().publish(
+            (symbol_short!("UPG_PREP"),),
+            UpgradePreparedEvent { new_wasm_hash },
+        );
         Ok(())
     }
 
-    /// Execute contract upgrade
+    /// Replace the contract code with previously uploaded WASM, keeping all instance
+    /// and persistent storage, and bump the stored version reported by `version`.
+    /// Works while the contract is stopped, so a fix can be deployed mid-incident;
+    /// call `migrate` afterwards if the new code changes the storage layout.
+    ///
+    /// # Errors
+    /// * `Error::NotInitialized` - If no admin is set
+    /// * `Error::InvalidHashSize` - If `new_wasm_hash` is not 32 bytes
     pub fn upgrade(env: Env, new_wasm_hash: Bytes) -> Result<(), Error> {
-        Self::require_not_stopped(&env)?;
         let admin = Self::get_admin(&env)?;
         admin.require_auth();
 
@@ -332,7 +944,10 @@ impl SnapshotContract {
 
         env.events().publish(
             (symbol_short!("UPGRADED"),),
-            (new_wasm_hash, metadata.version),
+            ContractUpgradedEvent {
+                new_wasm_hash,
+                version: metadata.version,
+            },
         );
         Ok(())
     }
@@ -348,11 +963,64 @@ impl SnapshotContract {
             return Err(Error::InvalidMigration);
         }
 
+        // Move snapshots stored under the legacy single map into per-epoch keys, and
+        // epochs listed in the legacy single vector into slots
+        let legacy: Option<Map<u64, Snapshot>> =
+            env.storage().persistent().get(&DataKey::Snapshots);
+        let legacy_index: Option<Vec<u64>> = env.storage().persistent().get(&DataKey::EpochIndex);
+        if legacy.is_some() || legacy_index.is_some() {
+            // Maps iterate in key order, so this sorts the epochs of all three sources
+            let mut epochs: Map<u64, bool> = Map::new(&env);
+            if let Some(legacy) = legacy {
+                for (epoch, snapshot) in legacy.iter() {
+                    if !env.storage().persistent().has(&DataKey::Snapshot(epoch)) {
+                        env.storage()
+                            .persistent()
+                            .set(&DataKey::Snapshot(epoch), &snapshot);
+                    }
+                    epochs.set(epoch, true);
+                }
+                env.storage().persistent().remove(&DataKey::Snapshots);
+            }
+            for epoch in legacy_index.into_iter().flatten() {
+                epochs.set(epoch, true);
+            }
+            env.storage().persistent().remove(&DataKey::EpochIndex);
+
+            let previous = epoch_slots(&env);
+            for slot in previous.first..previous.next {
+                if let Some(epoch) = epoch_at(&env, slot) {
+                    epochs.set(epoch, true);
+                }
+                env.storage().persistent().remove(&DataKey::EpochAt(slot));
+            }
+            let mut slots = EpochSlots::default();
+            for epoch in epochs.keys() {
+                push_epoch(&env, &mut slots, epoch);
+            }
+            save_epoch_slots(&env, &slots);
+        }
+
+        // Backfill the hash reverse index for snapshots stored before it existed.
+        // Indexing is idempotent, so epochs already indexed are left as they are.
+        for epoch in indexed_epochs(&env).iter() {
+            if let Some(snapshot) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, Snapshot>(&DataKey::Snapshot(epoch))
+            {
+                index_hash(&env, &snapshot.hash, epoch);
+            }
+        }
+
         bump_instance(&env);
 
         env.events().publish(
             (symbol_short!("MIGRATED"),),
-            (from_version, current_version),
+            ContractMigratedEvent {
+                from_version,
+                to_version: current_version,
+            },
         );
         Ok(())
     }
@@ -362,49 +1030,73 @@ impl SnapshotContract {
     /// # Arguments
     /// * `hash` - 32-byte SHA-256 hash of analytics snapshot
     /// * `epoch` - Epoch identifier (must be positive)
+    /// * `caller` - Submitting address (must be the admin)
+    ///
+    /// # Errors
+    /// * `Error::NotInitialized` - If no admin has been set
+    /// * `Error::UnauthorizedCaller` - If caller is not the admin
+    /// * `Error::QuorumRequired` - If an attestation quorum is set; submitters attest
+    ///   epochs through `attest_snapshot` instead
+    /// * `Error::ContractPaused` - If contract is paused for emergency maintenance
+    /// * `Error::InvalidHashSize` - If hash is not exactly 32 bytes
+    /// * `Error::InvalidEpoch` - If epoch is 0
+    /// * `Error::EpochAlreadyExists` - If a snapshot already exists for this epoch
+    /// * `Error::EpochMonotonicityViolated` - If epoch is older than the latest epoch
     ///
     /// # Panics
-    /// * If contract is paused for emergency maintenance
-    /// * If hash is not exactly 32 bytes
-    /// * If epoch is 0
-    /// * If a snapshot already exists for this epoch
-    /// * If epoch <= latest (monotonicity violated: out-of-order or duplicate)
     /// * If reentrancy is detected
     ///
     /// # Returns
     /// * Ledger timestamp when snapshot was recorded
-    pub fn submit_snapshot(env: Env, hash: Bytes, epoch: u64) -> Result<u64, Error> {
+    pub fn submit_snapshot(
+        env: Env,
+        hash: Bytes,
+        epoch: u64,
+        caller: Address,
+    ) -> Result<u64, Error> {
+        Self::record_submission(&env, hash, epoch, &caller, None)
+    }
+
+    /// Validate and commit an admin submission, reporting `schema_version` in `SNAP_SUB`
+    fn record_submission(
+        env: &Env,
+        hash: Bytes,
+        epoch: u64,
+        caller: &Address,
+        schema_version: Option<u32>,
+    ) -> Result<u64, Error> {
+        // Only the admin may write snapshots, otherwise anyone could overwrite an epoch
+        caller.require_auth();
+        let admin = Self::get_admin(env)?;
+        if *caller != admin {
+            return Err(Error::UnauthorizedCaller);
+        }
+        // With a quorum set, epochs are only recorded once submitters agree
+        if Self::get_attestation_quorum(env.clone()) > 0 {
+            return Err(Error::QuorumRequired);
+        }
+        Self::require_not_paused(env)?;
+
         // Check reentrancy guard
-        if let Err(e) = Self::check_and_set_reentrancy_guard(&env) {
+        if let Err(e) = Self::check_and_set_reentrancy_guard(env) {
             panic!("{}", e);
         }
 
-        // Check if contract is paused
-        let is_paused: bool = env
-            .storage()
-            .instance()
-            .get(&DataKey::Paused)
-            .unwrap_or(false);
-        if is_paused {
-            Self::clear_reentrancy_guard(&env);
-            return Err(Error::ContractPaused);
-        }
-
         // Validate inputs
         if hash.len() != HASH_SIZE {
-            Self::clear_reentrancy_guard(&env);
+            Self::clear_reentrancy_guard(env);
             return Err(Error::InvalidHashSize);
         }
 
         if epoch == 0 {
-            Self::clear_reentrancy_guard(&env);
+            Self::clear_reentrancy_guard(env);
             return Err(Error::InvalidEpoch);
         }
 
-        let current_latest: Option<u64> = env.storage().persistent().get(&DataKey::LatestEpoch);
+        let current_latest = latest_epoch(env);
         if let Some(latest) = current_latest {
             if epoch <= latest {
-                Self::clear_reentrancy_guard(&env);
+                Self::clear_reentrancy_guard(env);
                 if epoch == latest {
                     return Err(Error::EpochAlreadyExists);
                 } else {
@@ -413,132 +1105,164 @@ impl SnapshotContract {
             }
         }
 
-        let timestamp = env.ledger().timestamp();
-        let ledger_sequence = env.ledger().sequence();
+        if env.storage().persistent().has(&DataKey::Snapshot(epoch)) {
+            Self::clear_reentrancy_guard(env);
+            return Err(Error::EpochAlreadyExists);
+        }
 
-        let snapshot = Snapshot {
-            hash: hash.clone(),
-            epoch,
-            timestamp,
-        };
+        let timestamp = commit_snapshot(env, hash, epoch, current_latest, caller, schema_version);
 
-        let mut snapshots: Map<u64, Snapshot> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Snapshots)
-            .unwrap_or_else(|| Map::new(&env));
+        // Clear guard before returning
+        Self::clear_reentrancy_guard(env);
 
-        if snapshots.contains_key(epoch) {
-            Self::clear_reentrancy_guard(&env);
-            return Err(Error::EpochAlreadyExists);
+        Ok(timestamp)
+    }
+
+    /// Anchor an epoch with the Merkle root of its per-corridor hashes rather than one
+    /// snapshot hash. The root is recorded exactly like `submit_snapshot` would record
+    /// a hash, so every existing read and verify call works on it, and
+    /// `verify_merkle_proof` can additionally check a single leaf.
+    ///
+    /// # Errors
+    /// * `Error::InvalidLeafCount` - If `leaf_count` is zero
+    /// * Any error `submit_snapshot` returns
+    pub fn submit_merkle_root(
+        env: Env,
+        root: Bytes,
+        epoch: u64,
+        leaf_count: u32,
+        caller: Address,
+    ) -> Result<u64, Error> {
+        if leaf_count == 0 {
+            return Err(Error::InvalidLeafCount);
         }
+        let timestamp = Self::submit_snapshot(env.clone(), root, epoch, caller)?;
 
-        snapshots.set(epoch, snapshot);
+        let key = DataKey::MerkleLeafCount(epoch);
+        env.storage().persistent().set(&key, &leaf_count);
         env.storage()
             .persistent()
-            .set(&DataKey::Snapshots, &snapshots);
-        env.storage()
-            .persistent()
-            .set(&DataKey::LatestEpoch, &epoch);
-
-        // Extend storage TTL (~30 days at 5s per ledger)
-        const LEDGERS_TO_EXTEND: u32 = 518_400;
-        env.storage().persistent().extend_ttl(
-            &DataKey::Snapshots,
-            LEDGERS_TO_EXTEND,
-            LEDGERS_TO_EXTEND,
-        );
-        env.storage().persistent().extend_ttl(
-            &DataKey::LatestEpoch,
-            LEDGERS_TO_EXTEND,
-            LEDGERS_TO_EXTEND,
-        );
-
-        env.events().publish(
-            (symbol_short!("SNAP_SUB"),),
-            SnapshotSubmittedEvent {
-                epoch,
-                hash,
-                timestamp,
-                previous_epoch: current_latest.unwrap_or(0),
-                ledger_sequence,
-            },
-        );
+            .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+        Ok(timestamp)
+    }
 
-        // Clear guard before returning
-        Self::clear_reentrancy_guard(&env);
+    /// Record a snapshot hash along with the schema version and content URI of the
+    /// blob it covers, so verifiers know what to fetch and how to read it.
+    ///
+    /// # Errors
+    /// * `Error::InvalidMetadata` - If `content_uri` is empty or longer than 128 bytes
+    /// * Any error `submit_snapshot` returns
+    pub fn submit_snapshot_with_metadata(
+        env: Env,
+        hash: Bytes,
+        epoch: u64,
+        caller: Address,
+        metadata: SnapshotMetadata,
+    ) -> Result<u64, Error> {
+        if let Some(uri) = &metadata.content_uri {
+            if uri.is_empty() || uri.len() > MAX_CONTENT_URI_LEN {
+                return Err(Error::InvalidMetadata);
+            }
+        }
+        let timestamp =
+            Self::record_submission(&env, hash, epoch, &caller, metadata.schema_version)?;
 
+        let key = DataKey::SnapshotMetadata(epoch);
+        env.storage().persistent().set(&key, &metadata);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
         Ok(timestamp)
     }
 
-    /// Get snapshot hash for a specific epoch
-    pub fn get_snapshot(env: Env, epoch: u64) -> Result<Bytes, Error> {
+    /// Schema version and content URI recorded for an epoch's snapshot. Snapshots
+    /// submitted without metadata, including those stored before it existed, report
+    /// both as `None`.
+    ///
+    /// # Errors
+    /// * `Error::SnapshotNotFound` / `Error::SnapshotRevoked` - If the epoch has no live snapshot
+    pub fn get_snapshot_metadata(env: Env, epoch: u64) -> Result<SnapshotMetadata, Error> {
         Self::require_not_stopped(&env)?;
-        const LEDGERS_TO_EXTEND: u32 = 518_400;
-        if env.storage().persistent().has(&DataKey::Snapshots) {
-            env.storage().persistent().extend_ttl(
-                &DataKey::Snapshots,
-                LEDGERS_TO_EXTEND,
-                LEDGERS_TO_EXTEND,
-            );
+        if load_snapshot(&env, epoch).is_none() {
+            return Err(missing_snapshot(&env, epoch));
+        }
+        let key = DataKey::SnapshotMetadata(epoch);
+        let Some(metadata) = env.storage().persistent().get(&key) else {
+            return Ok(SnapshotMetadata {
+                schema_version: None,
+                content_uri: None,
+            });
+        };
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+        Ok(metadata)
+    }
+
+    /// Check that `leaf` is one of the hashes under the Merkle root anchored for
+    /// `epoch`. `proof` lists sibling nodes from the leaf up; see the README for how
+    /// the tree is built.
+    ///
+    /// # Errors
+    /// * `Error::InvalidHashSize` - If `leaf` is not 32 bytes
+    /// * `Error::SnapshotNotFound` / `Error::SnapshotRevoked` - If the epoch has no live snapshot
+    /// * `Error::NotMerkleRoot` - If the epoch was anchored with a plain hash
+    /// * `Error::InvalidProof` - If a proof node is not 32 bytes or the proof is
+    ///   longer than the tree is deep
+    pub fn verify_merkle_proof(
+        env: Env,
+        epoch: u64,
+        leaf: Bytes,
+        proof: Vec<Bytes>,
+    ) -> Result<bool, Error> {
+        Self::require_not_stopped(&env)?;
+        if leaf.len() != HASH_SIZE {
+            return Err(Error::InvalidHashSize);
         }
-        let snapshots: Map<u64, Snapshot> = env
+        let snapshot = load_snapshot(&env, epoch).ok_or_else(|| missing_snapshot(&env, epoch))?;
+        let leaf_count: u32 = env
             .storage()
             .persistent()
-            .get(&DataKey::Snapshots)
-            .unwrap_or_else(|| Map::new(&env));
+            .get(&DataKey::MerkleLeafCount(epoch))
+            .ok_or(Error::NotMerkleRoot)?;
+        if proof.len() > merkle_depth(leaf_count) {
+            return Err(Error::InvalidProof);
+        }
+
+        let mut node = merkle_leaf(&env, &leaf);
+        for sibling in proof.iter() {
+            if sibling.len() != HASH_SIZE {
+                return Err(Error::InvalidProof);
+            }
+            node = merkle_parent(&env, &node, &sibling);
+        }
+        Ok(node == snapshot.hash)
+    }
 
-        snapshots
-            .get(epoch)
+    /// Get snapshot hash for a specific epoch
+    pub fn get_snapshot(env: Env, epoch: u64) -> Result<Bytes, Error> {
+        Self::require_not_stopped(&env)?;
+        load_snapshot(&env, epoch)
             .map(|s| s.hash)
-            .ok_or(Error::SnapshotNotFound)
+            .ok_or_else(|| missing_snapshot(&env, epoch))
     }
 
     /// Get the latest snapshot
     pub fn latest_snapshot(env: Env) -> Result<Snapshot, Error> {
         Self::require_not_stopped(&env)?;
-        if env.storage().persistent().has(&DataKey::LatestEpoch) {
-            env.storage().persistent().extend_ttl(
-                &DataKey::LatestEpoch,
-                LEDGERS_TO_EXTEND,
-                LEDGERS_TO_EXTEND,
-            );
-        }
-        let latest_epoch: Option<u64> = env.storage().persistent().get(&DataKey::LatestEpoch);
-
-        let epoch = latest_epoch.ok_or(Error::SnapshotNotFound)?;
-        if env.storage().persistent().has(&DataKey::Snapshots) {
-            env.storage().persistent().extend_ttl(
-                &DataKey::Snapshots,
-                LEDGERS_TO_EXTEND,
-                LEDGERS_TO_EXTEND,
-            );
-        }
-        let snapshots: Map<u64, Snapshot> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Snapshots)
-            .unwrap_or_else(|| Map::new(&env));
-
-        snapshots.get(epoch).ok_or(Error::SnapshotNotFound)
+        let epoch = latest_epoch(&env).ok_or(Error::SnapshotNotFound)?;
+        load_snapshot(&env, epoch).ok_or_else(|| missing_snapshot(&env, epoch))
     }
 
     /// Verify if a hash matches any stored snapshot
     pub fn verify_snapshot(env: Env, hash: Bytes) -> bool {
-        Self::require_not_stopped(&env).is_ok_and(|_| {
-            let snapshots: Map<u64, Snapshot> = env
-                .storage()
-                .persistent()
-                .get(&DataKey::Snapshots)
-                .unwrap_or(Map::new(&env));
+        Self::require_not_stopped(&env).is_ok_and(|_| epoch_for_hash(&env, &hash).is_some())
+    }
 
-            for (_, snapshot) in snapshots.iter() {
-                if snapshot.hash == hash {
-                    return true;
-                }
-            }
-            false
-        })
+    /// Epoch whose snapshot has this hash, the latest one if it was submitted more than once
+    pub fn find_epoch_by_hash(env: Env, hash: Bytes) -> Result<u64, Error> {
+        Self::require_not_stopped(&env)?;
+        epoch_for_hash(&env, &hash).ok_or(Error::SnapshotNotFound)
     }
 
     /// Verify if a hash matches the snapshot at a specific epoch
@@ -546,18 +1270,41 @@ impl SnapshotContract {
         if Self::require_not_stopped(&env).is_err() {
             return false;
         }
-        let snapshots: Map<u64, Snapshot> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Snapshots)
-            .unwrap_or(Map::new(&env));
-
-        match snapshots.get(epoch) {
+        match load_snapshot(&env, epoch) {
             Some(snapshot) => snapshot.hash == hash,
             None => false,
         }
     }
 
+    /// Check a contiguous run of epochs in one call: `hashes[i]` is compared with
+    /// the snapshot at `start_epoch + i`. Returns one flag per hash, `false` where the
+    /// epoch has no live snapshot or a different hash.
+    ///
+    /// # Errors
+    /// * `Error::RangeTooLarge` - If more than `MAX_VERIFY_RANGE` hashes are given
+    /// * `Error::InvalidEpoch` - If the range runs past `u64::MAX`
+    pub fn verify_snapshot_range(
+        env: Env,
+        hashes: Vec<Bytes>,
+        start_epoch: u64,
+    ) -> Result<Vec<bool>, Error> {
+        Self::require_not_stopped(&env)?;
+        if hashes.len() > MAX_VERIFY_RANGE {
+            return Err(Error::RangeTooLarge);
+        }
+        start_epoch
+            .checked_add(u64::from(hashes.len()))
+            .ok_or(Error::InvalidEpoch)?;
+
+        let mut results = Vec::new(&env);
+        for (epoch, hash) in (start_epoch..).zip(hashes.iter()) {
+            results.push_back(
+                load_snapshot(&env, epoch).is_some_and(|snapshot| snapshot.hash == hash),
+            );
+        }
+        Ok(results)
+    }
+
     /// Verify if a hash matches the latest snapshot
     pub fn verify_latest_snapshot(env: Env, hash: Bytes) -> bool {
         match Self::latest_snapshot(env) {
@@ -566,119 +1313,581 @@ impl SnapshotContract {
         }
     }
 
-    /// Emergency pause the contract
-    pub fn pause(env: Env, caller: Address) -> Result<(), Error> {
+    /// Withdraw a bad snapshot. The epoch stays claimed, so it can only be
+    /// corrected with `supersede_snapshot`, and the revoked hash is kept in its history.
+    ///
+    /// # Errors
+    /// * `Error::UnauthorizedCaller` - If caller is not the admin
+    /// * `Error::SnapshotNotFound` - If the epoch has no live snapshot
+    pub fn revoke_snapshot(env: Env, caller: Address, epoch: u64) -> Result<(), Error> {
+        Self::require_not_stopped(&env)?;
         caller.require_auth();
-        let admin = Self::get_admin(&env)?;
-        if caller != admin {
-            return Err(Error::Unauthorized);
+        if caller != Self::get_admin(&env)? {
+            return Err(Error::UnauthorizedCaller);
         }
-        env.storage().instance().set(&DataKey::Paused, &true);
-        bump_instance(&env);
+
+        let snapshot = load_snapshot(&env, epoch).ok_or_else(|| missing_snapshot(&env, epoch))?;
+        retire_snapshot(&env, &snapshot, RevisionKind::Revoked);
+        env.storage().persistent().remove(&DataKey::Snapshot(epoch));
+
         env.events().publish(
-            (symbol_short!("pause"), caller.clone()),
-            PauseEvent {
-                paused_by: caller,
-                timestamp: env.ledger().timestamp(),
+            (symbol_short!("SNAP_REV"),),
+            SnapshotRevokedEvent {
+                epoch,
+                hash: snapshot.hash,
+                revoked_by: caller,
                 ledger_sequence: env.ledger().sequence(),
             },
         );
         Ok(())
     }
 
-    /// Unpause the contract
-    pub fn unpause(env: Env, caller: Address) -> Result<(), Error> {
+    /// Replace the hash recorded for an epoch, live or revoked, keeping the old
+    /// one in the epoch's history
+    ///
+    /// # Errors
+    /// * `Error::UnauthorizedCaller` - If caller is not the admin
+    /// * `Error::QuorumRequired` - If an attestation quorum is set; the admin alone
+    ///   cannot replace what submitters attested
+    /// * `Error::InvalidHashSize` - If `new_hash` is not 32 bytes
+    /// * `Error::SnapshotNotFound` - If nothing was ever recorded for the epoch
+    /// * `Error::HashAlreadyIndexed` - If `new_hash` is already the snapshot of an epoch
+    pub fn supersede_snapshot(
+        env: Env,
+        caller: Address,
+        epoch: u64,
+        new_hash: Bytes,
+    ) -> Result<u64, Error> {
+        Self::require_not_stopped(&env)?;
+        caller.require_auth();
+        if caller != Self::get_admin(&env)? {
+            return Err(Error::UnauthorizedCaller);
+        }
+        if Self::get_attestation_quorum(env.clone()) > 0 {
+            return Err(Error::QuorumRequired);
+        }
+        if new_hash.len() != HASH_SIZE {
+            return Err(Error::InvalidHashSize);
+        }
+        // A correction must be a hash no other live epoch already holds
+        if epoch_for_hash(&env, &new_hash).is_some() {
+            return Err(Error::HashAlreadyIndexed);
+        }
+
+        let previous_hash = match load_snapshot(&env, epoch) {
+            Some(snapshot) => {
+                retire_snapshot(&env, &snapshot, RevisionKind::Superseded);
+                Some(snapshot.hash)
+            }
+            None if is_revoked(&env, epoch) => None,
+            None => return Err(Error::SnapshotNotFound),
+        };
+
+        let timestamp = env.ledger().timestamp();
+        let key = DataKey::Snapshot(epoch);
+        env.storage().persistent().set(
+            &key,
+            &Snapshot {
+                hash: new_hash.clone(),
+                epoch,
+                timestamp,
+            },
+        );
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+        index_hash(&env, &new_hash, epoch);
+        // The replacement is a plain hash; a corrected root must be re-anchored at a new epoch
+        env.storage()
+            .persistent()
+            .remove(&DataKey::MerkleLeafCount(epoch));
+        // Metadata described the old blob
+        env.storage()
+            .persistent()
+            .remove(&DataKey::SnapshotMetadata(epoch));
+
+        env.events().publish(
+            (symbol_short!("SNAP_SUPR"),),
+            SnapshotSupersededEvent {
+                epoch,
+                previous_hash,
+                new_hash,
+                superseded_by: caller,
+                ledger_sequence: env.ledger().sequence(),
+            },
+        );
+        Ok(timestamp)
+    }
+
+    /// Keep an epoch's snapshot live for `ledgers` more ledgers, along with its hash
+    /// index, history, attestations and Merkle leaf count. Anyone can pay to keep data
+    /// they rely on verifiable; `ledgers` is capped at the network's maximum TTL.
+    pub fn extend_ttl(env: Env, epoch: u64, ledgers: u32) -> Result<(), Error> {
+        Self::require_not_stopped(&env)?;
+        if ledgers == 0 || ledgers > env.storage().max_ttl() {
+            return Err(Error::InvalidTtl);
+        }
+        let key = DataKey::Snapshot(epoch);
+        let snapshot: Snapshot = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .ok_or_else(|| missing_snapshot(&env, epoch))?;
+
+        let storage = env.storage().persistent();
+        storage.extend_ttl(&key, ledgers, ledgers);
+        let hash_key = DataKey::HashEpoch(snapshot.hash);
+        if storage
+            .get::<DataKey, Vec<u64>>(&hash_key)
+            .is_some_and(|epochs| epochs.contains(epoch))
+        {
+            storage.extend_ttl(&hash_key, ledgers, ledgers);
+        }
+        for key in [
+            DataKey::SnapshotHistory(epoch),
+            DataKey::Attestations(epoch),
+            DataKey::MerkleLeafCount(epoch),
+            DataKey::SnapshotMetadata(epoch),
+        ] {
+            if storage.has(&key) {
+                storage.extend_ttl(&key, ledgers, ledgers);
+            }
+        }
+        Ok(())
+    }
+
+    /// Hashes previously recorded for an epoch, oldest first
+    pub fn get_snapshot_history(env: Env, epoch: u64) -> Vec<SnapshotRevision> {
+        snapshot_history(&env, epoch)
+    }
+
+    /// Whether the epoch's snapshot is currently revoked
+    pub fn is_snapshot_revoked(env: Env, epoch: u64) -> bool {
+        is_revoked(&env, epoch)
+    }
+
+    /// Keep at most `max_epochs` snapshots; older ones are pruned as new ones are
+    /// submitted, at most `MAX_PRUNE_PER_CALL` per submission, so shrinking the
+    /// window on a long history takes effect gradually. `0` disables retention and
+    /// keeps every snapshot.
+    pub fn set_retention(env: Env, caller: Address, max_epochs: u32) -> Result<(), Error> {
+        Self::require_not_stopped(&env)?;
         caller.require_auth();
         let admin = Self::get_admin(&env)?;
         if caller != admin {
-            return Err(Error::Unauthorized);
+            return Err(Error::UnauthorizedCaller);
         }
-        env.storage().instance().set(&DataKey::Paused, &false);
+        env.storage()
+            .instance()
+            .set(&DataKey::RetentionEpochs, &max_epochs);
         bump_instance(&env);
         env.events().publish(
-            (symbol_short!("unpause"), caller.clone()),
-            UnpauseEvent {
-                unpaused_by: caller,
-                timestamp: env.ledger().timestamp(),
-                ledger_sequence: env.ledger().sequence(),
+            (symbol_short!("RET_SET"),),
+            RetentionSetEvent {
+                max_epochs,
+                set_by: caller,
             },
         );
         Ok(())
     }
 
-    /// Check if contract is paused
-    pub fn is_paused(env: Env) -> bool {
+    /// Configured retention window, `0` when every snapshot is kept
+    pub fn get_retention(env: Env) -> u32 {
         env.storage()
             .instance()
-            .get(&DataKey::Paused)
-            .unwrap_or(false)
+            .get(&DataKey::RetentionEpochs)
+            .unwrap_or(0)
     }
 
-    /// Get public contract metadata
+    /// Remove snapshots older than `epoch`, oldest first and at most
+    /// `MAX_PRUNE_PER_CALL` per call. Call again until it returns `0` to remove them
+    /// all. The latest snapshot is always kept.
     ///
-    /// Returns metadata information including name, version, author,
-    /// description, repository, and license.
-    pub fn get_metadata(env: Env) -> PublicMetadata {
-        PublicMetadata {
-            name: String::from_str(&env, "Stellar Insights Snapshot"),
-            version: String::from_str(&env, VERSION),
-            author: String::from_str(&env, "Stellar Insights Team"),
-            description: String::from_str(
-                &env,
-                "Snapshot management and verification contract for Stellar analytics",
-            ),
-            repository: String::from_str(&env, "https://github.com/stellar-insights/contracts"),
-            license: String::from_str(&env, "MIT"),
+    /// # Returns
+    /// * Number of snapshots removed by this call
+    pub fn prune_before(env: Env, caller: Address, epoch: u64) -> Result<u32, Error> {
+        Self::require_not_stopped(&env)?;
+        caller.require_auth();
+        let admin = Self::get_admin(&env)?;
+        if caller != admin {
+            return Err(Error::UnauthorizedCaller);
         }
+
+        let Some(latest) = latest_epoch(&env) else {
+            return Ok(0);
+        };
+        let mut slots = epoch_slots(&env);
+        let cutoff = epoch.min(latest);
+        let removed = remove_before(&env, &mut slots, cutoff);
+        if removed == 0 {
+            return Ok(0);
+        }
+
+        save_epoch_slots(&env, &slots);
+        publish_pruned(&env, cutoff, removed, slots.len());
+        Ok(removed)
     }
 
-    /// Get comprehensive contract information
-    ///
-    /// Returns both metadata and current runtime state including
-    /// initialization status, pause state, admin address, and snapshot count.
-    pub fn get_contract_info(env: Env) -> ContractInfo {
-        ContractInfo {
-            metadata: Self::get_metadata(env.clone()),
-            initialized: env.storage().instance().has(&DataKey::Admin),
-            paused: env
-                .storage()
+    /// Allow `submitter` to attest snapshots
+    pub fn add_submitter(env: Env, caller: Address, submitter: Address) -> Result<(), Error> {
+        Self::require_not_stopped(&env)?;
+        caller.require_auth();
+        if caller != Self::get_admin(&env)? {
+            return Err(Error::UnauthorizedCaller);
+        }
+
+        let mut registry = submitters(&env);
+        if !registry.contains(&submitter) {
+            registry.push_back(submitter.clone());
+            env.storage()
                 .instance()
-                .get(&DataKey::Paused)
-                .unwrap_or(false),
-            admin: env.storage().instance().get(&DataKey::Admin),
-            total_snapshots: env
-                .storage()
-                .persistent()
-                .get(&DataKey::LatestEpoch)
-                .unwrap_or(0),
+                .set(&DataKey::Submitters, &registry);
+            bump_instance(&env);
+            env.events().publish(
+                (symbol_short!("SUB_ADD"),),
+                SubmitterChangedEvent {
+                    submitter,
+                    changed_by: caller,
+                },
+            );
         }
+        Ok(())
     }
 
-    pub fn initialize_multisig(
-        env: Env,
-        admins: Vec<Address>,
-        threshold: u32,
-    ) -> Result<(), Error> {
-        if threshold == 0 || threshold > admins.len() as u32 {
-            return Err(Error::InvalidThreshold);
+    /// Revoke `submitter`. Attestations it already made stop counting toward quorum.
+    ///
+    /// # Errors
+    /// * `Error::InvalidThreshold` - If fewer submitters than the quorum would remain
+    pub fn remove_submitter(env: Env, caller: Address, submitter: Address) -> Result<(), Error> {
+        Self::require_not_stopped(&env)?;
+        caller.require_auth();
+        if caller != Self::get_admin(&env)? {
+            return Err(Error::UnauthorizedCaller);
         }
 
-        let config = MultiSigConfig { admins, threshold };
+        let mut registry = submitters(&env);
+        let Some(position) = registry.first_index_of(&submitter) else {
+            return Ok(());
+        };
+        if registry.len() - 1 < Self::get_attestation_quorum(env.clone()) {
+            return Err(Error::InvalidThreshold);
+        }
+        registry.remove(position);
         env.storage()
             .instance()
-            .set(&DataKey::MultiSigConfig, &config);
+            .set(&DataKey::Submitters, &registry);
         bump_instance(&env);
-
+        env.events().publish(
+            (symbol_short!("SUB_REM"),),
+            SubmitterChangedEvent {
+                submitter,
+                changed_by: caller,
+            },
+        );
         Ok(())
     }
 
-    pub fn propose_action(
-        env: Env,
-        proposer: Address,
-        action_type: String,
-        _action_data: BytesN<32>,
-    ) -> Result<u64, Error> {
-        proposer.require_auth();
+    pub fn get_submitters(env: Env) -> Vec<Address> {
+        submitters(&env)
+    }
+
+    /// Require `quorum` registered submitters to attest the same hash for an epoch
+    /// before it is recorded. `0` turns attestation off.
+    ///
+    /// # Errors
+    /// * `Error::InvalidThreshold` - If `quorum` exceeds the number of registered submitters
+    pub fn set_attestation_quorum(env: Env, caller: Address, quorum: u32) -> Result<(), Error> {
+        Self::require_not_stopped(&env)?;
+        caller.require_auth();
+        if caller != Self::get_admin(&env)? {
+            return Err(Error::UnauthorizedCaller);
+        }
+        if quorum > submitters(&env).len() {
+            return Err(Error::InvalidThreshold);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::AttestationQuorum, &quorum);
+        bump_instance(&env);
+        env.events().publish(
+            (symbol_short!("QUORUM"),),
+            QuorumSetEvent {
+                quorum,
+                set_by: caller,
+            },
+        );
+        Ok(())
+    }
+
+    /// Configured attestation quorum, `0` when attestation is off
+    pub fn get_attestation_quorum(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::AttestationQuorum)
+            .unwrap_or(0)
+    }
+
+    /// Attest that `hash` is the snapshot for `epoch`. Once `quorum` submitters have
+    /// attested the same hash, it is recorded exactly as `submit_snapshot` would.
+    ///
+    /// # Errors
+    /// * `Error::QuorumNotConfigured` - If attestation is off
+    /// * `Error::UnauthorizedCaller` - If `submitter` is not registered
+    /// * `Error::AlreadyAttested` - If `submitter` already attested this epoch
+    /// * `Error::EpochAlreadyExists` / `Error::EpochMonotonicityViolated` - If the
+    ///   epoch is already canonical or older than the latest
+    ///
+    /// # Returns
+    /// * `true` if this attestation reached quorum and made the snapshot canonical
+    pub fn attest_snapshot(
+        env: Env,
+        submitter: Address,
+        hash: Bytes,
+        epoch: u64,
+    ) -> Result<bool, Error> {
+        Self::require_not_stopped(&env)?;
+        submitter.require_auth();
+
+        let quorum = Self::get_attestation_quorum(env.clone());
+        if quorum == 0 {
+            return Err(Error::QuorumNotConfigured);
+        }
+        if !submitters(&env).contains(&submitter) {
+            return Err(Error::UnauthorizedCaller);
+        }
+        Self::require_not_paused(&env)?;
+        if hash.len() != HASH_SIZE {
+            return Err(Error::InvalidHashSize);
+        }
+        if epoch == 0 {
+            return Err(Error::InvalidEpoch);
+        }
+
+        let current_latest = latest_epoch(&env);
+        if let Some(latest) = current_latest {
+            if epoch == latest {
+                return Err(Error::EpochAlreadyExists);
+            }
+            if epoch < latest {
+                return Err(Error::EpochMonotonicityViolated);
+            }
+        }
+
+        let key = DataKey::Attestations(epoch);
+        let mut attestations: Vec<Attestation> = env
+            .storage()
+            .temporary()
+            .get(&key)
+            .unwrap_or_else(|| Vec::new(&env));
+        if attestations.iter().any(|a| a.submitter == submitter) {
+            return Err(Error::AlreadyAttested);
+        }
+        attestations.push_back(Attestation {
+            submitter: submitter.clone(),
+            hash: hash.clone(),
+            timestamp: env.ledger().timestamp(),
+        });
+
+        // Only submitters still registered count, so a revoked key's attestations
+        // cannot help a hash reach quorum
+        let registry = submitters(&env);
+        let matching = attestations
+            .iter()
+            .filter(|a| a.hash == hash && registry.contains(&a.submitter))
+            .count() as u32;
+        env.events().publish(
+            (symbol_short!("ATTESTED"),),
+            SnapshotAttestedEvent {
+                epoch,
+                hash: hash.clone(),
+                submitter: submitter.clone(),
+                attestations: matching,
+                quorum,
+            },
+        );
+
+        if matching < quorum {
+            env.storage().temporary().set(&key, &attestations);
+            env.storage().temporary().extend_ttl(
+                &key,
+                PENDING_ATTESTATION_TTL,
+                PENDING_ATTESTATION_TTL,
+            );
+            return Ok(false);
+        }
+        // Attestations that made the snapshot canonical are kept with it
+        env.storage().temporary().remove(&key);
+        env.storage().persistent().set(&key, &attestations);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+        commit_snapshot(&env, hash, epoch, current_latest, &submitter, None);
+        Ok(true)
+    }
+
+    /// Every attestation recorded for `epoch`, in the order they arrived. Pending ones
+    /// are only returned until they expire.
+    pub fn get_attestations(env: Env, epoch: u64) -> Vec<Attestation> {
+        let key = DataKey::Attestations(epoch);
+        env.storage()
+            .persistent()
+            .get(&key)
+            .or_else(|| env.storage().temporary().get(&key))
+            .unwrap_or_else(|| Vec::new(&env))
+    }
+
+    /// Pause snapshot submission and attestation, e.g. while a leaked submitter key is
+    /// rotated. Unlike `stop_contract`, reads and verification keep working. Pausing an
+    /// already paused contract does nothing and emits no event.
+    ///
+    /// # Errors
+    /// * `Error::Unauthorized` - If caller is not the admin
+    pub fn pause(env: Env, caller: Address) -> Result<(), Error> {
+        caller.require_auth();
+        let admin = Self::get_admin(&env)?;
+        if caller != admin {
+            return Err(Error::Unauthorized);
+        }
+        if Self::is_paused(env.clone()) {
+            return Ok(());
+        }
+        env.storage().instance().set(&DataKey::Paused, &true);
+        bump_instance(&env);
+        env.events().publish(
+            (symbol_short!("pause"), caller.clone()),
+            PauseEvent {
+                paused_by: caller,
+                timestamp: env.ledger().timestamp(),
+                ledger_sequence: env.ledger().sequence(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Resume submissions after `pause`. A no-op without an event if not paused.
+    ///
+    /// # Errors
+    /// * `Error::Unauthorized` - If caller is not the admin
+    pub fn unpause(env: Env, caller: Address) -> Result<(), Error> {
+        caller.require_auth();
+        let admin = Self::get_admin(&env)?;
+        if caller != admin {
+            return Err(Error::Unauthorized);
+        }
+        if !Self::is_paused(env.clone()) {
+            return Ok(());
+        }
+        env.storage().instance().set(&DataKey::Paused, &false);
+        bump_instance(&env);
+        env.events().publish(
+            (symbol_short!("unpause"), caller.clone()),
+            UnpauseEvent {
+                unpaused_by: caller,
+                timestamp: env.ledger().timestamp(),
+                ledger_sequence: env.ledger().sequence(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Check if contract is paused
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
+
+    /// Get public contract metadata
+    ///
+    /// Returns metadata information including name, version, author,
+    /// description, repository, and license.
+    pub fn get_metadata(env: Env) -> PublicMetadata {
+        PublicMetadata {
+            name: String::from_str(&env, "Stellar Insights Snapshot"),
+            version: String::from_str(&env, VERSION),
+            author: String::from_str(&env, "Stellar Insights Team"),
+            description: String::from_str(
+                &env,
+                "Snapshot management and verification contract for Stellar analytics",
+            ),
+            repository: String::from_str(&env, "https://github.com/stellar-insights/contracts"),
+            license: String::from_str(&env, "MIT"),
+        }
+    }
+
+    /// Get comprehensive contract information
+    ///
+    /// Returns both metadata and current runtime state including
+    /// initialization status, pause state, admin address, and snapshot count.
+    pub fn get_contract_info(env: Env) -> ContractInfo {
+        ContractInfo {
+            metadata: Self::get_metadata(env.clone()),
+            initialized: env.storage().instance().has(&DataKey::Admin),
+            paused: env
+                .storage()
+                .instance()
+                .get(&DataKey::Paused)
+                .unwrap_or(false),
+            admin: env.storage().instance().get(&DataKey::Admin),
+            total_snapshots: latest_epoch(&env).unwrap_or(0),
+        }
+    }
+
+    /// Snapshot count, epoch bounds and last submission time, read from the epoch
+    /// index and instance storage so monitoring never has to enumerate snapshots.
+    /// Available while stopped.
+    pub fn get_stats(env: Env) -> SnapshotStats {
+        let slots = epoch_slots(&env);
+        let latest = latest_epoch(&env);
+        // Deployments that predate `LastSubmissionAt` fall back to the latest snapshot
+        let last_submission_at = env
+            .storage()
+            .instance()
+            .get(&DataKey::LastSubmissionAt)
+            .or_else(|| {
+                latest
+                    .and_then(|epoch| load_snapshot(&env, epoch))
+                    .map(|s| s.timestamp)
+            });
+        SnapshotStats {
+            total_snapshots: slots.len(),
+            earliest_epoch: if slots.len() > 0 {
+                epoch_at(&env, slots.first)
+            } else {
+                None
+            },
+            latest_epoch: latest,
+            last_submission_at,
+        }
+    }
+
+    pub fn initialize_multisig(
+        env: Env,
+        admins: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), Error> {
+        if threshold == 0 || threshold > admins.len() as u32 {
+            return Err(Error::InvalidThreshold);
+        }
+
+        let config = MultiSigConfig { admins, threshold };
+        env.storage()
+            .instance()
+            .set(&DataKey::MultiSigConfig, &config);
+        bump_instance(&env);
+
+        Ok(())
+    }
+
+    pub fn propose_action(
+        env: Env,
+        proposer: Address,
+        action_type: String,
+        _action_data: BytesN<32>,
+    ) -> Result<u64, Error> {
+        proposer.require_auth();
 
         let config: MultiSigConfig = env
             .storage()
@@ -773,7 +1982,7 @@ mod test {
     use soroban_sdk::{
         bytes,
         testutils::{Address as _, Events},
-        vec, Env, TryIntoVal,
+        vec, Env, TryFromVal, TryIntoVal,
     };
 
     #[test]
@@ -914,6 +2123,8 @@ mod test {
 
         let client =
             SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
 
         let hash = bytes!(
             &env,
@@ -921,7 +2132,7 @@ mod test {
         );
         let epoch = 42u64;
 
-        let _timestamp = client.submit_snapshot(&hash, &epoch);
+        let _timestamp = client.submit_snapshot(&hash, &epoch, &admin);
 
         let retrieved_hash = client.get_snapshot(&epoch);
         assert_eq!(retrieved_hash, hash);
@@ -934,6 +2145,8 @@ mod test {
 
         let client =
             SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
 
         let hash = bytes!(
             &env,
@@ -941,18 +2154,39 @@ mod test {
         );
         let epoch = 100u64;
 
-        client.submit_snapshot(&hash, &epoch);
-
-        let events = env.events().all();
-        let snap_event = events.iter().find(|e| {
-            if !e.1.is_empty() {
-                let topic: soroban_sdk::Symbol = e.1.get_unchecked(0).try_into_val(&env).unwrap();
-                topic == symbol_short!("SNAP_SUB")
-            } else {
-                false
-            }
-        });
-        assert!(snap_event.is_some());
+        client.submit_snapshot(&hash, &epoch, &admin);
+
+        let submitted = |env: &Env| {
+            env.events()
+                .all()
+                .iter()
+                .filter(|e| {
+                    !e.1.is_empty() && {
+                        let topic: soroban_sdk::Symbol =
+                            e.1.get_unchecked(0).try_into_val(env).unwrap();
+                        topic == symbol_short!("SNAP_SUB")
+                    }
+                })
+                .map(|e| SnapshotSubmitted::try_from_val(env, &e.2).unwrap())
+                .last()
+        };
+        let event = submitted(&env).unwrap();
+        assert_eq!((event.epoch, event.hash), (epoch, hash));
+        assert_eq!(event.submitter, admin);
+        assert_eq!(event.schema_version, None);
+
+        let metadata = SnapshotMetadata {
+            schema_version: Some(3),
+            content_uri: None,
+        };
+        client.submit_snapshot_with_metadata(
+            &Bytes::from_array(&env, &[5; 32]),
+            &101,
+            &admin,
+            &metadata,
+        );
+        let event = submitted(&env).unwrap();
+        assert_eq!((event.previous_epoch, event.schema_version), (100, Some(3)));
     }
 
     #[test]
@@ -962,278 +2196,1347 @@ mod test {
 
         let client =
             SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
 
         let short_hash = bytes!(&env, 0x1234);
-        let result = client.try_submit_snapshot(&short_hash, &1);
+        let result = client.try_submit_snapshot(&short_hash, &1, &admin);
         assert_eq!(result, Err(Ok(Error::InvalidHashSize)));
     }
 
     #[test]
-    fn test_invalid_epoch_zero() {
+    fn test_invalid_epoch_zero() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        let hash = bytes!(
+            &env,
+            0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef
+        );
+        let result = client.try_submit_snapshot(&hash, &0, &admin);
+        assert_eq!(result, Err(Ok(Error::InvalidEpoch)));
+    }
+
+    #[test]
+    fn test_older_epoch_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        let hash1 = bytes!(
+            &env,
+            0x1111111111111111111111111111111111111111111111111111111111111111
+        );
+        let hash2 = bytes!(
+            &env,
+            0x2222222222222222222222222222222222222222222222222222222222222222
+        );
+
+        client.submit_snapshot(&hash1, &10, &admin);
+        let latest = client.latest_snapshot();
+        assert_eq!(latest.epoch, 10);
+
+        let result = client.try_submit_snapshot(&hash2, &5, &admin);
+        assert_eq!(result, Err(Ok(Error::EpochMonotonicityViolated)));
+    }
+
+    #[test]
+    fn test_multiple_snapshots() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        let hash1 = bytes!(
+            &env,
+            0x1111111111111111111111111111111111111111111111111111111111111111
+        );
+        let epoch1 = 1u64;
+        client.submit_snapshot(&hash1, &epoch1, &admin);
+
+        let hash2 = bytes!(
+            &env,
+            0x2222222222222222222222222222222222222222222222222222222222222222
+        );
+        let epoch2 = 2u64;
+        client.submit_snapshot(&hash2, &epoch2, &admin);
+
+        assert_eq!(client.get_snapshot(&epoch1), hash1);
+        assert_eq!(client.get_snapshot(&epoch2), hash2);
+    }
+
+    #[test]
+    fn test_latest_snapshot() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        client.submit_snapshot(
+            &bytes!(
+                &env,
+                0x1111111111111111111111111111111111111111111111111111111111111111
+            ),
+            &1,
+            &admin,
+        );
+        client.submit_snapshot(
+            &bytes!(
+                &env,
+                0x2222222222222222222222222222222222222222222222222222222222222222
+            ),
+            &3,
+            &admin,
+        );
+        client.submit_snapshot(
+            &bytes!(
+                &env,
+                0x3333333333333333333333333333333333333333333333333333333333333333
+            ),
+            &7,
+            &admin,
+        );
+
+        let snapshot = client.latest_snapshot();
+        assert_eq!(snapshot.epoch, 7);
+        assert_eq!(
+            snapshot.hash,
+            bytes!(
+                &env,
+                0x3333333333333333333333333333333333333333333333333333333333333333
+            )
+        );
+    }
+
+    #[test]
+    fn test_latest_snapshot_empty() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+
+        let result = client.try_latest_snapshot();
+        assert_eq!(result, Err(Ok(Error::SnapshotNotFound)));
+    }
+
+    #[test]
+    fn test_verify_found() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        let hash = bytes!(
+            &env,
+            0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890
+        );
+        client.submit_snapshot(&hash, &100, &admin);
+
+        assert!(client.verify_snapshot(&hash));
+    }
+
+    #[test]
+    fn test_verify_not_found() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        client.submit_snapshot(
+            &bytes!(
+                &env,
+                0x1111111111111111111111111111111111111111111111111111111111111111
+            ),
+            &5,
+            &admin,
+        );
+
+        assert!(!client.verify_snapshot(&bytes!(
+            &env,
+            0x9999999999999999999999999999999999999999999999999999999999999999
+        )));
+    }
+
+    #[test]
+    fn test_version_without_init() {
+        let env = Env::default();
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+
+        assert_eq!(client.version(), CONTRACT_VERSION);
+    }
+
+    #[test]
+    fn test_upgrade_mechanism_with_snapshots() {
+        let env = Env::default();
+        let admin = Address::generate(&env);
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+
+        client.initialize(&admin);
+
+        let hash1 = bytes!(
+            &env,
+            0x1111111111111111111111111111111111111111111111111111111111111111
+        );
+        client.submit_snapshot(&hash1, &1, &admin);
+
+        let wasm_hash = bytes!(
+            &env,
+            0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890
+        );
+        client.prepare_upgrade(&wasm_hash);
+
+        assert_eq!(client.get_snapshot(&1), hash1);
+        assert!(client.verify_snapshot(&hash1));
+    }
+
+    #[test]
+    fn test_upgrade_allowed_while_stopped() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        assert_eq!(
+            client.try_upgrade(&Bytes::from_array(&env, &[7; 31])),
+            Err(Ok(Error::InvalidHashSize))
+        );
+
+        // No WASM is uploaded under this hash, so the host rejects the swap, but the
+        // stop flag must not be what blocks it
+        client.stop_contract();
+        let result = client.try_upgrade(&Bytes::from_array(&env, &[7; 32]));
+        assert!(matches!(result, Err(Err(_))));
+        assert_eq!(client.version(), CONTRACT_VERSION);
+    }
+
+    #[test]
+    fn test_verify_snapshot_at_epoch() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        let hash1 = bytes!(
+            &env,
+            0x1111111111111111111111111111111111111111111111111111111111111111
+        );
+        let hash2 = bytes!(
+            &env,
+            0x2222222222222222222222222222222222222222222222222222222222222222
+        );
+
+        client.submit_snapshot(&hash1, &1, &admin);
+        client.submit_snapshot(&hash2, &2, &admin);
+
+        assert!(client.verify_snapshot_at_epoch(&hash1, &1));
+        assert!(!client.verify_snapshot_at_epoch(&hash1, &2));
+        assert!(client.verify_snapshot_at_epoch(&hash2, &2));
+    }
+
+    #[test]
+    fn test_verify_latest_snapshot() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        let hash1 = bytes!(
+            &env,
+            0x1111111111111111111111111111111111111111111111111111111111111111
+        );
+        let hash2 = bytes!(
+            &env,
+            0x2222222222222222222222222222222222222222222222222222222222222222
+        );
+
+        client.submit_snapshot(&hash1, &1, &admin);
+        assert!(client.verify_latest_snapshot(&hash1));
+
+        client.submit_snapshot(&hash2, &2, &admin);
+        assert!(!client.verify_latest_snapshot(&hash1));
+        assert!(client.verify_latest_snapshot(&hash2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Reentrancy detected")]
+    fn test_reentrancy_protection() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        let hash = bytes!(
+            &env,
+            0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890
+        );
+
+        // Manually set the reentrancy guard to simulate a reentrant call
+        env.storage()
+            .instance()
+            .set(&DataKey::ReentrancyGuard, &true);
+
+        // This should panic due to reentrancy detection
+        client.submit_snapshot(&hash, &1, &admin);
+    }
+
+    #[test]
+    fn test_submit_requires_admin() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        let intruder = Address::generate(&env);
+        let hash = bytes!(
+            &env,
+            0x1111111111111111111111111111111111111111111111111111111111111111
+        );
+
+        assert_eq!(
+            client.try_submit_snapshot(&hash, &1, &admin),
+            Err(Ok(Error::NotInitialized))
+        );
+
+        client.initialize(&admin);
+        assert_eq!(
+            client.try_submit_snapshot(&hash, &1, &intruder),
+            Err(Ok(Error::UnauthorizedCaller))
+        );
+        assert_eq!(
+            client.try_latest_snapshot(),
+            Err(Ok(Error::SnapshotNotFound))
+        );
+
+        client.submit_snapshot(&hash, &1, &admin);
+        assert_eq!(client.get_snapshot(&1), hash);
+    }
+
+    #[test]
+    fn test_submit_records_caller_auth() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register_contract(None, SnapshotContract);
+        let client = SnapshotContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        let hash = bytes!(
+            &env,
+            0x1111111111111111111111111111111111111111111111111111111111111111
+        );
+        client.submit_snapshot(&hash, &1, &admin);
+
+        let auths = env.auths();
+        assert_eq!(auths.len(), 1);
+        assert_eq!(auths[0].0, admin);
+    }
+
+    #[test]
+    fn test_set_admin_rotates_submitter() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        let new_admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        assert_eq!(
+            client.try_set_admin(&new_admin, &new_admin),
+            Err(Ok(Error::UnauthorizedCaller))
+        );
+
+        client.set_admin(&admin, &new_admin);
+        assert_eq!(client.get_admin_addr(), Some(new_admin.clone()));
+
+        let changed = env.events().all().iter().find_map(|e| {
+            let topic: Symbol = e.1.get_unchecked(0).try_into_val(&env).unwrap();
+            (topic == symbol_short!("ADM_XFER"))
+                .then(|| AdminChangedEvent::try_from_val(&env, &e.2).unwrap())
+        });
+        assert_eq!(
+            changed,
+            Some(AdminChangedEvent {
+                previous_admin: admin.clone(),
+                new_admin: new_admin.clone(),
+            })
+        );
+
+        let hash = bytes!(
+            &env,
+            0x1111111111111111111111111111111111111111111111111111111111111111
+        );
+        assert_eq!(
+            client.try_submit_snapshot(&hash, &1, &admin),
+            Err(Ok(Error::UnauthorizedCaller))
+        );
+        client.submit_snapshot(&hash, &1, &new_admin);
+        assert_eq!(client.get_snapshot(&1), hash);
+    }
+
+    /// Serialized size of every snapshot entry plus its epoch slot.
+    fn stored_snapshots_size(env: &Env, contract_id: &Address) -> u32 {
+        use soroban_sdk::xdr::ToXdr;
+        env.as_contract(contract_id, || {
+            indexed_epochs(env).iter().fold(0, |size, epoch| {
+                let snapshot: Snapshot = env
+                    .storage()
+                    .persistent()
+                    .get(&DataKey::Snapshot(epoch))
+                    .unwrap();
+                size + snapshot.to_xdr(env).len() + epoch.to_xdr(env).len()
+            })
+        })
+    }
+
+    #[test]
+    fn test_snapshots_stored_per_epoch() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register_contract(None, SnapshotContract);
+        let client = SnapshotContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        for epoch in 1..=3u8 {
+            client.submit_snapshot(
+                &Bytes::from_array(&env, &[epoch; 32]),
+                &u64::from(epoch),
+                &admin,
+            );
+        }
+
+        env.as_contract(&contract_id, || {
+            assert!(!env.storage().persistent().has(&DataKey::Snapshots));
+            assert_eq!(indexed_epochs(&env), vec![&env, 1u64, 2, 3]);
+            let second: Snapshot = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Snapshot(2))
+                .unwrap();
+            assert_eq!(second.hash, Bytes::from_array(&env, &[2; 32]));
+        });
+        assert!(client.verify_snapshot_at_epoch(&Bytes::from_array(&env, &[2; 32]), &2));
+        assert!(!client.verify_snapshot_at_epoch(&Bytes::from_array(&env, &[2; 32]), &3));
+        assert!(client.verify_snapshot(&Bytes::from_array(&env, &[3; 32])));
+    }
+
+    #[test]
+    fn test_find_epoch_by_hash() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register_contract(None, SnapshotContract);
+        let client = SnapshotContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        let repeated = Bytes::from_array(&env, &[7; 32]);
+        client.submit_snapshot(&repeated, &1, &admin);
+        client.submit_snapshot(&Bytes::from_array(&env, &[2; 32]), &2, &admin);
+        client.submit_snapshot(&repeated, &3, &admin);
+
+        assert_eq!(client.find_epoch_by_hash(&repeated), 3);
+        assert_eq!(
+            client.find_epoch_by_hash(&Bytes::from_array(&env, &[2; 32])),
+            2
+        );
+        assert_eq!(
+            client.try_find_epoch_by_hash(&Bytes::from_array(&env, &[9; 32])),
+            Err(Ok(Error::SnapshotNotFound))
+        );
+
+        // Pruning the older copy of a repeated hash keeps it verifiable
+        client.prune_before(&admin, &3);
+        assert!(client.verify_snapshot(&repeated));
+        assert!(!client.verify_snapshot(&Bytes::from_array(&env, &[2; 32])));
+        env.as_contract(&contract_id, || {
+            assert!(!env
+                .storage()
+                .persistent()
+                .has(&DataKey::HashEpoch(Bytes::from_array(&env, &[2; 32]))));
+        });
+    }
+
+    #[test]
+    fn test_retiring_one_copy_of_a_repeated_hash_keeps_the_others() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        let repeated = Bytes::from_array(&env, &[7; 32]);
+        client.submit_snapshot(&repeated, &1, &admin);
+        client.submit_snapshot(&Bytes::from_array(&env, &[2; 32]), &2, &admin);
+        client.submit_snapshot(&repeated, &3, &admin);
+        client.submit_snapshot(&repeated, &4, &admin);
+
+        // Epoch 1 still holds the hash after the later copies are withdrawn
+        client.revoke_snapshot(&admin, &4);
+        assert!(client.verify_snapshot(&repeated));
+        assert_eq!(client.find_epoch_by_hash(&repeated), 3);
+        client.supersede_snapshot(&admin, &3, &Bytes::from_array(&env, &[3; 32]));
+        assert!(client.verify_snapshot(&repeated));
+        assert_eq!(client.find_epoch_by_hash(&repeated), 1);
+
+        client.revoke_snapshot(&admin, &1);
+        assert!(!client.verify_snapshot(&repeated));
+        assert_eq!(
+            client.try_find_epoch_by_hash(&repeated),
+            Err(Ok(Error::SnapshotNotFound))
+        );
+    }
+
+    #[test]
+    fn test_pause_blocks_submissions_only() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register_contract(None, SnapshotContract);
+        let client = SnapshotContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+        let hash = Bytes::from_array(&env, &[1; 32]);
+        client.submit_snapshot(&hash, &1, &admin);
+
+        let outsider = Address::generate(&env);
+        assert_eq!(client.try_pause(&outsider), Err(Ok(Error::Unauthorized)));
+
+        client.pause(&admin);
+        assert!(client.is_paused());
+        let events = env.events().all().len();
+        // Repeating the call changes nothing and stays silent
+        client.pause(&admin);
+        assert_eq!(env.events().all().len(), events);
+
+        assert_eq!(
+            client.try_submit_snapshot(&Bytes::from_array(&env, &[2; 32]), &2, &admin),
+            Err(Ok(Error::ContractPaused))
+        );
+        assert_eq!(client.get_snapshot(&1), hash);
+        assert!(client.verify_snapshot(&hash));
+        assert_eq!(client.latest_snapshot().epoch, 1);
+
+        client.unpause(&admin);
+        assert!(!client.is_paused());
+        client.submit_snapshot(&Bytes::from_array(&env, &[2; 32]), &2, &admin);
+        assert_eq!(client.latest_snapshot().epoch, 2);
+    }
+
+    #[test]
+    fn test_snapshot_metadata() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register_contract(None, SnapshotContract);
+        let client = SnapshotContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        let metadata = SnapshotMetadata {
+            schema_version: Some(2),
+            content_uri: Some(String::from_str(
+                &env,
+                "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+            )),
+        };
+        client.submit_snapshot_with_metadata(
+            &Bytes::from_array(&env, &[1; 32]),
+            &1,
+            &admin,
+            &metadata,
+        );
+        assert_eq!(client.get_snapshot_metadata(&1), metadata);
+
+        // Snapshots without metadata read back as empty rather than failing
+        client.submit_snapshot(&Bytes::from_array(&env, &[2; 32]), &2, &admin);
+        let empty = SnapshotMetadata {
+            schema_version: None,
+            content_uri: None,
+        };
+        assert_eq!(client.get_snapshot_metadata(&2), empty);
+        assert_eq!(
+            client.try_get_snapshot_metadata(&3),
+            Err(Ok(Error::SnapshotNotFound))
+        );
+
+        let too_long = SnapshotMetadata {
+            schema_version: Some(2),
+            content_uri: Some(String::from_str(&env, &"a".repeat(129))),
+        };
+        assert_eq!(
+            client.try_submit_snapshot_with_metadata(
+                &Bytes::from_array(&env, &[3; 32]),
+                &3,
+                &admin,
+                &too_long,
+            ),
+            Err(Ok(Error::InvalidMetadata))
+        );
+        assert_eq!(
+            client.try_get_snapshot(&3),
+            Err(Ok(Error::SnapshotNotFound))
+        );
+
+        // A corrected hash no longer claims the old blob
+        client.supersede_snapshot(&admin, &1, &Bytes::from_array(&env, &[9; 32]));
+        assert_eq!(client.get_snapshot_metadata(&1), empty);
+    }
+
+    #[test]
+    fn test_extend_ttl_keeps_snapshots_live() {
+        use soroban_sdk::testutils::{storage::Persistent as _, Ledger as _};
+
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register_contract(None, SnapshotContract);
+        let client = SnapshotContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        let hash = Bytes::from_array(&env, &[4; 32]);
+        client.submit_snapshot(&hash, &1, &admin);
+        let ttl = |key: &DataKey| {
+            env.as_contract(&contract_id, || env.storage().persistent().get_ttl(key))
+        };
+
+        // Reads push the latest-epoch entry's expiry forward again
+        env.ledger().with_mut(|li| li.sequence_number += 10_000);
+        assert!(ttl(&DataKey::LatestEpoch) < LEDGERS_TO_EXTEND);
+        client.latest_snapshot();
+        assert_eq!(ttl(&DataKey::LatestEpoch), LEDGERS_TO_EXTEND);
+
+        let max_ttl = env.as_contract(&contract_id, || env.storage().max_ttl());
+        client.extend_ttl(&1, &max_ttl);
+        assert_eq!(ttl(&DataKey::Snapshot(1)), max_ttl);
+        assert_eq!(ttl(&DataKey::HashEpoch(hash)), max_ttl);
+
+        assert_eq!(client.try_extend_ttl(&1, &0), Err(Ok(Error::InvalidTtl)));
+        assert_eq!(
+            client.try_extend_ttl(&1, &(max_ttl + 1)),
+            Err(Ok(Error::InvalidTtl))
+        );
+        assert_eq!(
+            client.try_extend_ttl(&2, &max_ttl),
+            Err(Ok(Error::SnapshotNotFound))
+        );
+    }
+
+    #[test]
+    fn test_verify_snapshot_range() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        for epoch in [10u8, 11, 12, 14] {
+            client.submit_snapshot(
+                &Bytes::from_array(&env, &[epoch; 32]),
+                &u64::from(epoch),
+                &admin,
+            );
+        }
+        client.revoke_snapshot(&admin, &12);
+
+        let hashes = vec![
+            &env,
+            Bytes::from_array(&env, &[10; 32]),
+            Bytes::from_array(&env, &[99; 32]),
+            Bytes::from_array(&env, &[12; 32]),
+            Bytes::from_array(&env, &[13; 32]),
+            Bytes::from_array(&env, &[14; 32]),
+        ];
+        // Wrong hash at 11, revoked 12, nothing stored at 13
+        assert_eq!(
+            client.verify_snapshot_range(&hashes, &10),
+            vec![&env, true, false, false, false, true]
+        );
+        assert_eq!(
+            client.verify_snapshot_range(&Vec::new(&env), &10),
+            Vec::<bool>::new(&env)
+        );
+
+        let mut too_many = Vec::new(&env);
+        for _ in 0..=MAX_VERIFY_RANGE {
+            too_many.push_back(Bytes::from_array(&env, &[10; 32]));
+        }
+        assert_eq!(
+            client.try_verify_snapshot_range(&too_many, &10),
+            Err(Ok(Error::RangeTooLarge))
+        );
+        assert_eq!(
+            client.try_verify_snapshot_range(&hashes, &(u64::MAX - 2)),
+            Err(Ok(Error::InvalidEpoch))
+        );
+    }
+
+    /// Root and per-leaf proofs for `leaves`, built as the README describes
+    fn merkle_tree(env: &Env, leaves: &[Bytes]) -> (Bytes, std::vec::Vec<Vec<Bytes>>) {
+        let mut level: std::vec::Vec<Bytes> =
+            leaves.iter().map(|leaf| merkle_leaf(env, leaf)).collect();
+        let mut positions: std::vec::Vec<usize> = (0..leaves.len()).collect();
+        let mut proofs: std::vec::Vec<Vec<Bytes>> = leaves.iter().map(|_| Vec::new(env)).collect();
+        while level.len() > 1 {
+            for (proof, position) in proofs.iter_mut().zip(positions.iter_mut()) {
+                // An odd node out has no sibling and moves up unchanged
+                if let Some(sibling) = level.get(*position ^ 1) {
+                    proof.push_back(sibling.clone());
+                }
+                *position /= 2;
+            }
+            level = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => merkle_parent(env, a, b),
+                    _ => pair[0].clone(),
+                })
+                .collect();
+        }
+        (level[0].clone(), proofs)
+    }
+
+    #[test]
+    fn test_merkle_root_anchoring() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let client =
+            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        let leaves: std::vec::Vec<Bytes> = (1u8..=5)
+            .map(|corridor| Bytes::from_array(&env, &[corridor; 32]))
+            .collect();
+        let (root, proofs) = merkle_tree(&env, &leaves);
+        assert_eq!(
+            client.try_submit_merkle_root(&root, &1, &0, &admin),
+            Err(Ok(Error::InvalidLeafCount))
+        );
+        client.submit_merkle_root(&root, &1, &5, &admin);
+        assert!(client.verify_snapshot(&root));
+
+        for (leaf, proof) in leaves.iter().zip(proofs.iter()) {
+            assert!(client.verify_merkle_proof(&1, leaf, proof));
+        }
+        assert!(!client.verify_merkle_proof(&1, &leaves[1], &proofs[0]));
+        assert!(!client.verify_merkle_proof(&1, &Bytes::from_array(&env, &[9; 32]), &proofs[0]));
+
+        // Malformed proofs and leaves
+        let mut short_node = proofs[0].clone();
+        short_node.set(0, Bytes::from_array(&env, &[0; 31]));
+        assert_eq!(
+            client.try_verify_merkle_proof(&1, &leaves[0], &short_node),
+            Err(Ok(Error::InvalidProof))
+        );
+        let mut too_long = proofs[0].clone();
+        too_long.push_back(Bytes::from_array(&env, &[0; 32]));
+        assert_eq!(
+            client.try_verify_merkle_proof(&1, &leaves[0], &too_long),
+            Err(Ok(Error::InvalidProof))
+        );
+        assert_eq!(
+            client.try_verify_merkle_proof(&1, &Bytes::from_array(&env, &[1; 31]), &proofs[0]),
+            Err(Ok(Error::InvalidHashSize))
+        );
+
+        // Plain snapshots and missing epochs
+        client.submit_snapshot(&Bytes::from_array(&env, &[7; 32]), &2, &admin);
+        assert_eq!(
+            client.try_verify_merkle_proof(&2, &leaves[0], &proofs[0]),
+            Err(Ok(Error::NotMerkleRoot))
+        );
+        assert_eq!(
+            client.try_verify_merkle_proof(&9, &leaves[0], &proofs[0]),
+            Err(Ok(Error::SnapshotNotFound))
+        );
+    }
+
+    #[test]
+    fn test_migrate_moves_legacy_snapshot_map() {
         let env = Env::default();
         env.mock_all_auths();
 
-        let client =
-            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let contract_id = env.register_contract(None, SnapshotContract);
+        let client = SnapshotContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
 
-        let hash = bytes!(
-            &env,
-            0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef
+        env.as_contract(&contract_id, || {
+            let mut legacy: Map<u64, Snapshot> = Map::new(&env);
+            for epoch in 1..=2u8 {
+                legacy.set(
+                    u64::from(epoch),
+                    Snapshot {
+                        hash: Bytes::from_array(&env, &[epoch; 32]),
+                        epoch: u64::from(epoch),
+                        timestamp: 0,
+                    },
+                );
+            }
+            env.storage().persistent().set(&DataKey::Snapshots, &legacy);
+            env.storage().persistent().set(&DataKey::LatestEpoch, &2u64);
+        });
+
+        client.migrate(&0);
+
+        assert_eq!(client.get_snapshot(&1), Bytes::from_array(&env, &[1; 32]));
+        assert_eq!(client.latest_snapshot().epoch, 2);
+        assert_eq!(
+            client.find_epoch_by_hash(&Bytes::from_array(&env, &[1; 32])),
+            1
         );
-        let result = client.try_submit_snapshot(&hash, &0);
-        assert_eq!(result, Err(Ok(Error::InvalidEpoch)));
+        client.submit_snapshot(&Bytes::from_array(&env, &[3; 32]), &3, &admin);
+        env.as_contract(&contract_id, || {
+            assert!(!env.storage().persistent().has(&DataKey::Snapshots));
+            assert_eq!(indexed_epochs(&env), vec![&env, 1u64, 2, 3]);
+        });
     }
 
     #[test]
-    fn test_older_epoch_rejected() {
+    fn test_migrate_moves_legacy_epoch_index() {
         let env = Env::default();
         env.mock_all_auths();
 
-        let client =
-            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let contract_id = env.register_contract(None, SnapshotContract);
+        let client = SnapshotContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
 
-        let hash1 = bytes!(
-            &env,
-            0x1111111111111111111111111111111111111111111111111111111111111111
-        );
-        let hash2 = bytes!(
-            &env,
-            0x2222222222222222222222222222222222222222222222222222222222222222
-        );
+        // Epochs 1 and 2 indexed by the single vector, before the upgrade
+        env.as_contract(&contract_id, || {
+            for epoch in 1..=2u8 {
+                env.storage().persistent().set(
+                    &DataKey::Snapshot(u64::from(epoch)),
+                    &Snapshot {
+                        hash: Bytes::from_array(&env, &[epoch; 32]),
+                        epoch: u64::from(epoch),
+                        timestamp: 0,
+                    },
+                );
+            }
+            env.storage()
+                .persistent()
+                .set(&DataKey::EpochIndex, &vec![&env, 1u64, 2]);
+            env.storage().persistent().set(&DataKey::LatestEpoch, &2u64);
+        });
+        client.submit_snapshot(&Bytes::from_array(&env, &[3; 32]), &3, &admin);
 
-        client.submit_snapshot(&hash1, &10);
-        let latest = client.latest_snapshot();
-        assert_eq!(latest.epoch, 10);
+        client.migrate(&0);
 
-        let result = client.try_submit_snapshot(&hash2, &5);
-        assert_eq!(result, Err(Ok(Error::EpochMonotonicityViolated)));
+        env.as_contract(&contract_id, || {
+            assert!(!env.storage().persistent().has(&DataKey::EpochIndex));
+            assert_eq!(indexed_epochs(&env), vec![&env, 1u64, 2, 3]);
+        });
+        let stats = client.get_stats();
+        assert_eq!(stats.total_snapshots, 3);
+        assert_eq!(stats.earliest_epoch, Some(1));
+        assert_eq!(
+            client.find_epoch_by_hash(&Bytes::from_array(&env, &[1; 32])),
+            1
+        );
+
+        assert_eq!(client.prune_before(&admin, &3), 2);
+        assert_eq!(client.get_stats().earliest_epoch, Some(3));
+        assert_eq!(
+            client.try_get_snapshot(&2),
+            Err(Ok(Error::SnapshotNotFound))
+        );
     }
 
     #[test]
-    fn test_multiple_snapshots() {
+    fn test_prune_before_shrinks_snapshot_entry() {
         let env = Env::default();
         env.mock_all_auths();
 
-        let client =
-            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let contract_id = env.register_contract(None, SnapshotContract);
+        let client = SnapshotContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
 
-        let hash1 = bytes!(
-            &env,
-            0x1111111111111111111111111111111111111111111111111111111111111111
+        for epoch in 1..=10u8 {
+            client.submit_snapshot(
+                &Bytes::from_array(&env, &[epoch; 32]),
+                &u64::from(epoch),
+                &admin,
+            );
+        }
+        let size_before = stored_snapshots_size(&env, &contract_id);
+
+        assert_eq!(
+            client.try_prune_before(&Address::generate(&env), &8),
+            Err(Ok(Error::UnauthorizedCaller))
         );
-        let epoch1 = 1u64;
-        client.submit_snapshot(&hash1, &epoch1);
+        assert_eq!(client.prune_before(&admin, &8), MAX_PRUNE_PER_CALL);
 
-        let hash2 = bytes!(
-            &env,
-            0x2222222222222222222222222222222222222222222222222222222222222222
+        let pruned = env.events().all().iter().find_map(|e| {
+            let topic: Symbol = e.1.get_unchecked(0).try_into_val(&env).unwrap();
+            (topic == Symbol::new(&env, "SNAP_PRUNED"))
+                .then(|| SnapshotsPrunedEvent::try_from_val(&env, &e.2).unwrap())
+        });
+        assert_eq!(
+            pruned,
+            Some(SnapshotsPrunedEvent {
+                before_epoch: 8,
+                removed: 3,
+                remaining: 7,
+                ledger_sequence: env.ledger().sequence(),
+            })
         );
-        let epoch2 = 2u64;
-        client.submit_snapshot(&hash2, &epoch2);
+        // The rest of the range is removed by further calls
+        assert_eq!(client.prune_before(&admin, &8), 3);
+        assert_eq!(client.prune_before(&admin, &8), 1);
+        assert_eq!(client.prune_before(&admin, &8), 0);
 
-        assert_eq!(client.get_snapshot(&epoch1), hash1);
-        assert_eq!(client.get_snapshot(&epoch2), hash2);
+        let size_after = stored_snapshots_size(&env, &contract_id);
+        assert!(size_after * 3 < size_before);
+        assert_eq!(
+            client.try_get_snapshot(&7),
+            Err(Ok(Error::SnapshotNotFound))
+        );
+        assert!(!client.verify_snapshot(&Bytes::from_array(&env, &[1; 32])));
+        assert!(client.verify_snapshot(&Bytes::from_array(&env, &[8; 32])));
+
+        // Pruning past the latest epoch still keeps the latest snapshot
+        assert_eq!(client.prune_before(&admin, &100), 2);
+        assert_eq!(client.latest_snapshot().epoch, 10);
+        assert_eq!(client.prune_before(&admin, &100), 0);
     }
 
     #[test]
-    fn test_latest_snapshot() {
+    fn test_retention_prunes_on_submit() {
         let env = Env::default();
         env.mock_all_auths();
 
         let client =
             SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+        assert_eq!(client.get_retention(), 0);
 
-        client.submit_snapshot(
-            &bytes!(
-                &env,
-                0x1111111111111111111111111111111111111111111111111111111111111111
-            ),
-            &1,
-        );
-        client.submit_snapshot(
-            &bytes!(
-                &env,
-                0x2222222222222222222222222222222222222222222222222222222222222222
-            ),
-            &3,
-        );
-        client.submit_snapshot(
-            &bytes!(
-                &env,
-                0x3333333333333333333333333333333333333333333333333333333333333333
-            ),
-            &7,
+        assert_eq!(
+            client.try_set_retention(&Address::generate(&env), &3),
+            Err(Ok(Error::UnauthorizedCaller))
         );
+        client.set_retention(&admin, &3);
+        assert_eq!(client.get_retention(), 3);
+
+        for epoch in 1..=5u8 {
+            client.submit_snapshot(
+                &Bytes::from_array(&env, &[epoch; 32]),
+                &u64::from(epoch),
+                &admin,
+            );
+        }
 
-        let snapshot = client.latest_snapshot();
-        assert_eq!(snapshot.epoch, 7);
         assert_eq!(
-            snapshot.hash,
-            bytes!(
-                &env,
-                0x3333333333333333333333333333333333333333333333333333333333333333
-            )
+            client.try_get_snapshot(&2),
+            Err(Ok(Error::SnapshotNotFound))
         );
+        for epoch in 3..=5u8 {
+            assert_eq!(
+                client.get_snapshot(&u64::from(epoch)),
+                Bytes::from_array(&env, &[epoch; 32])
+            );
+        }
+
+        // Disabling retention stops pruning
+        client.set_retention(&admin, &0);
+        client.submit_snapshot(&Bytes::from_array(&env, &[6; 32]), &6, &admin);
+        assert!(client.verify_snapshot_at_epoch(&Bytes::from_array(&env, &[3; 32]), &3));
     }
 
     #[test]
-    fn test_latest_snapshot_empty() {
+    fn test_shrinking_retention_prunes_a_long_history_gradually() {
         let env = Env::default();
         env.mock_all_auths();
 
         let client =
             SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
 
-        let result = client.try_latest_snapshot();
-        assert_eq!(result, Err(Ok(Error::SnapshotNotFound)));
+        for epoch in 1..=60u8 {
+            env.budget().reset_default();
+            client.submit_snapshot(
+                &Bytes::from_array(&env, &[epoch; 32]),
+                &u64::from(epoch),
+                &admin,
+            );
+        }
+        client.set_retention(&admin, &2);
+
+        // Each submission removes a bounded number of snapshots and still succeeds
+        let mut epoch = 60u8;
+        let mut stored = client.get_stats().total_snapshots;
+        while stored > 2 {
+            epoch += 1;
+            env.budget().reset_default();
+            client.submit_snapshot(
+                &Bytes::from_array(&env, &[epoch; 32]),
+                &u64::from(epoch),
+                &admin,
+            );
+            let now = client.get_stats().total_snapshots;
+            assert_eq!(now, (stored + 1 - MAX_PRUNE_PER_CALL).max(2));
+            stored = now;
+        }
+
+        assert_eq!(
+            client.get_stats().earliest_epoch,
+            Some(u64::from(epoch) - 1)
+        );
+        assert!(client
+            .verify_snapshot_at_epoch(&Bytes::from_array(&env, &[epoch; 32]), &u64::from(epoch)));
+        assert_eq!(
+            client.try_get_snapshot(&(u64::from(epoch) - 2)),
+            Err(Ok(Error::SnapshotNotFound))
+        );
     }
 
     #[test]
-    fn test_verify_found() {
+    fn test_get_stats() {
+        use soroban_sdk::testutils::Ledger as _;
+
         let env = Env::default();
         env.mock_all_auths();
 
         let client =
             SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+        assert_eq!(
+            client.get_stats(),
+            SnapshotStats {
+                total_snapshots: 0,
+                earliest_epoch: None,
+                latest_epoch: None,
+                last_submission_at: None,
+            }
+        );
 
-        let hash = bytes!(
-            &env,
-            0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890
+        client.set_retention(&admin, &2);
+        for epoch in 1..=3u8 {
+            env.ledger()
+                .with_mut(|li| li.timestamp = 1_000 * u64::from(epoch));
+            client.submit_snapshot(
+                &Bytes::from_array(&env, &[epoch; 32]),
+                &u64::from(epoch),
+                &admin,
+            );
+        }
+
+        // Epoch 1 was pruned by retention
+        assert_eq!(
+            client.get_stats(),
+            SnapshotStats {
+                total_snapshots: 2,
+                earliest_epoch: Some(2),
+                latest_epoch: Some(3),
+                last_submission_at: Some(3_000),
+            }
         );
-        client.submit_snapshot(&hash, &100);
 
-        assert!(client.verify_snapshot(&hash));
+        // Still readable while stopped
+        client.stop_contract();
+        assert_eq!(client.get_stats().latest_epoch, Some(3));
     }
 
     #[test]
-    fn test_verify_not_found() {
+    fn test_revoke_and_supersede_keep_audit_trail() {
         let env = Env::default();
         env.mock_all_auths();
 
         let client =
             SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
 
-        client.submit_snapshot(
-            &bytes!(
-                &env,
-                0x1111111111111111111111111111111111111111111111111111111111111111
-            ),
-            &5,
-        );
+        let bad = Bytes::from_array(&env, &[1; 32]);
+        let fixed = Bytes::from_array(&env, &[2; 32]);
+        client.submit_snapshot(&bad, &1, &admin);
+        client.submit_snapshot(&Bytes::from_array(&env, &[9; 32]), &2, &admin);
 
-        assert!(!client.verify_snapshot(&bytes!(
-            &env,
-            0x9999999999999999999999999999999999999999999999999999999999999999
-        )));
-    }
+        assert_eq!(
+            client.try_revoke_snapshot(&Address::generate(&env), &1),
+            Err(Ok(Error::UnauthorizedCaller))
+        );
+        client.revoke_snapshot(&admin, &1);
+        assert!(client.is_snapshot_revoked(&1));
+        assert_eq!(client.try_get_snapshot(&1), Err(Ok(Error::SnapshotRevoked)));
+        assert_eq!(
+            client.try_get_snapshot(&5),
+            Err(Ok(Error::SnapshotNotFound))
+        );
+        assert!(!client.verify_snapshot(&bad));
+        assert!(!client.verify_snapshot_at_epoch(&bad, &1));
+        assert_eq!(
+            client.try_revoke_snapshot(&admin, &1),
+            Err(Ok(Error::SnapshotRevoked))
+        );
 
-    #[test]
-    fn test_version_without_init() {
-        let env = Env::default();
-        let client =
-            SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let revoked = env.events().all().iter().find_map(|e| {
+            let topic: Symbol = e.1.get_unchecked(0).try_into_val(&env).unwrap();
+            (topic == symbol_short!("SNAP_REV"))
+                .then(|| SnapshotRevokedEvent::try_from_val(&env, &e.2).unwrap())
+        });
+        assert_eq!(revoked.map(|e| e.hash), Some(bad.clone()));
+
+        client.supersede_snapshot(&admin, &1, &fixed);
+        assert!(!client.is_snapshot_revoked(&1));
+        assert_eq!(client.get_snapshot(&1), fixed);
+        assert_eq!(client.find_epoch_by_hash(&fixed), 1);
+
+        // Superseding a live snapshot records it too
+        let fixed_again = Bytes::from_array(&env, &[3; 32]);
+        client.supersede_snapshot(&admin, &1, &fixed_again);
+        let history = client.get_snapshot_history(&1);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.get(0).unwrap().hash, bad);
+        assert_eq!(history.get(0).unwrap().kind, RevisionKind::Revoked);
+        assert_eq!(history.get(1).unwrap().hash, fixed);
+        assert_eq!(history.get(1).unwrap().kind, RevisionKind::Superseded);
+        assert!(!client.verify_snapshot(&fixed));
+
+        // A hash recorded for an epoch cannot be moved onto another
+        assert_eq!(
+            client.try_supersede_snapshot(&admin, &2, &fixed_again),
+            Err(Ok(Error::HashAlreadyIndexed))
+        );
+        assert_eq!(client.find_epoch_by_hash(&fixed_again), 1);
+        assert_eq!(client.get_snapshot_history(&2).len(), 0);
 
-        assert_eq!(client.version(), CONTRACT_VERSION);
+        assert_eq!(
+            client.try_supersede_snapshot(&admin, &7, &fixed),
+            Err(Ok(Error::SnapshotNotFound))
+        );
+        assert_eq!(client.latest_snapshot().epoch, 2);
     }
 
     #[test]
-    fn test_upgrade_mechanism_with_snapshots() {
+    fn test_attestation_quorum_makes_snapshot_canonical() {
         let env = Env::default();
-        let admin = Address::generate(&env);
         env.mock_all_auths();
 
         let client =
             SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
-
+        let admin = Address::generate(&env);
         client.initialize(&admin);
 
-        let hash1 = bytes!(
-            &env,
-            0x1111111111111111111111111111111111111111111111111111111111111111
+        let oracles = [
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ];
+        let hash = Bytes::from_array(&env, &[4; 32]);
+        assert_eq!(
+            client.try_attest_snapshot(&oracles[0], &hash, &1),
+            Err(Ok(Error::QuorumNotConfigured))
+        );
+        assert_eq!(
+            client.try_set_attestation_quorum(&admin, &2),
+            Err(Ok(Error::InvalidThreshold))
         );
-        client.submit_snapshot(&hash1, &1);
+        for oracle in &oracles {
+            client.add_submitter(&admin, oracle);
+        }
+        client.set_attestation_quorum(&admin, &2);
+        assert_eq!(client.get_submitters().len(), 3);
 
-        let wasm_hash = bytes!(
-            &env,
-            0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890
+        assert_eq!(
+            client.try_attest_snapshot(&Address::generate(&env), &hash, &1),
+            Err(Ok(Error::UnauthorizedCaller))
         );
-        client.prepare_upgrade(&wasm_hash);
 
-        assert_eq!(client.get_snapshot(&1), hash1);
-        assert!(client.verify_snapshot(&hash1));
+        // A dissenting hash does not count toward the majority hash
+        assert!(!client.attest_snapshot(&oracles[0], &hash, &1));
+        assert_eq!(
+            client.try_attest_snapshot(&oracles[0], &hash, &1),
+            Err(Ok(Error::AlreadyAttested))
+        );
+        assert!(!client.attest_snapshot(&oracles[1], &Bytes::from_array(&env, &[5; 32]), &1));
+        assert_eq!(
+            client.try_get_snapshot(&1),
+            Err(Ok(Error::SnapshotNotFound))
+        );
+
+        assert!(client.attest_snapshot(&oracles[2], &hash, &1));
+        assert_eq!(client.get_snapshot(&1), hash);
+        assert_eq!(client.latest_snapshot().epoch, 1);
+
+        let attestations = client.get_attestations(&1);
+        assert_eq!(attestations.len(), 3);
+        assert_eq!(attestations.get(2).unwrap().submitter, oracles[2]);
+
+        // The epoch is settled, so late attestations are rejected
+        assert_eq!(
+            client.try_attest_snapshot(&oracles[1], &hash, &1),
+            Err(Ok(Error::EpochAlreadyExists))
+        );
+
+        // Cannot drop below the quorum
+        client.remove_submitter(&admin, &oracles[0]);
+        assert_eq!(
+            client.try_remove_submitter(&admin, &oracles[1]),
+            Err(Ok(Error::InvalidThreshold))
+        );
     }
 
     #[test]
-    fn test_verify_snapshot_at_epoch() {
+    fn test_quorum_counts_current_submitters_only() {
         let env = Env::default();
         env.mock_all_auths();
 
         let client =
             SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
 
-        let hash1 = bytes!(
-            &env,
-            0x1111111111111111111111111111111111111111111111111111111111111111
-        );
-        let hash2 = bytes!(
-            &env,
-            0x2222222222222222222222222222222222222222222222222222222222222222
+        let oracles = [
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        ];
+        for oracle in &oracles {
+            client.add_submitter(&admin, oracle);
+        }
+        client.set_attestation_quorum(&admin, &2);
+
+        // The admin cannot record an epoch past the quorum
+        let hash = Bytes::from_array(&env, &[6; 32]);
+        assert_eq!(
+            client.try_submit_snapshot(&hash, &1, &admin),
+            Err(Ok(Error::QuorumRequired))
         );
 
-        client.submit_snapshot(&hash1, &1);
-        client.submit_snapshot(&hash2, &2);
+        // A revoked submitter's attestation no longer counts
+        assert!(!client.attest_snapshot(&oracles[0], &hash, &1));
+        client.remove_submitter(&admin, &oracles[0]);
+        assert!(!client.attest_snapshot(&oracles[1], &hash, &1));
+        assert_eq!(
+            client.try_get_snapshot(&1),
+            Err(Ok(Error::SnapshotNotFound))
+        );
+        assert!(client.attest_snapshot(&oracles[2], &hash, &1));
+        assert_eq!(client.get_snapshot(&1), hash);
 
-        assert!(client.verify_snapshot_at_epoch(&hash1, &1));
-        assert!(!client.verify_snapshot_at_epoch(&hash1, &2));
-        assert!(client.verify_snapshot_at_epoch(&hash2, &2));
+        // Recovering from a stalled quorum takes turning it off first
+        client.set_attestation_quorum(&admin, &0);
+        client.submit_snapshot(&Bytes::from_array(&env, &[7; 32]), &2, &admin);
+        assert_eq!(client.latest_snapshot().epoch, 2);
     }
 
     #[test]
-    fn test_verify_latest_snapshot() {
+    fn test_quorum_attested_snapshot_cannot_be_superseded() {
         let env = Env::default();
         env.mock_all_auths();
 
         let client =
             SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
 
-        let hash1 = bytes!(
-            &env,
-            0x1111111111111111111111111111111111111111111111111111111111111111
-        );
-        let hash2 = bytes!(
-            &env,
-            0x2222222222222222222222222222222222222222222222222222222222222222
-        );
+        let oracles = [Address::generate(&env), Address::generate(&env)];
+        for oracle in &oracles {
+            client.add_submitter(&admin, oracle);
+        }
+        client.set_attestation_quorum(&admin, &2);
 
-        client.submit_snapshot(&hash1, &1);
-        assert!(client.verify_latest_snapshot(&hash1));
+        let hash = Bytes::from_array(&env, &[8; 32]);
+        for oracle in &oracles {
+            client.attest_snapshot(oracle, &hash, &1);
+        }
+        assert_eq!(client.get_snapshot(&1), hash);
 
-        client.submit_snapshot(&hash2, &2);
-        assert!(!client.verify_latest_snapshot(&hash1));
-        assert!(client.verify_latest_snapshot(&hash2));
+        assert_eq!(
+            client.try_supersede_snapshot(&admin, &1, &Bytes::from_array(&env, &[9; 32])),
+            Err(Ok(Error::QuorumRequired))
+        );
+        assert_eq!(client.get_snapshot(&1), hash);
+        assert_eq!(client.get_attestations(&1).len(), 2);
     }
 
     #[test]
-    #[should_panic(expected = "Reentrancy detected")]
-    fn test_reentrancy_protection() {
+    fn test_attestations_for_a_passed_over_epoch_expire() {
+        use soroban_sdk::testutils::Ledger as _;
+
         let env = Env::default();
         env.mock_all_auths();
 
         let client =
             SnapshotContractClient::new(&env, &env.register_contract(None, SnapshotContract));
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
 
-        let hash = bytes!(
-            &env,
-            0xabcdef1234567890abcdef1234567890abcdef1234567890abcdef1234567890
-        );
-
-        // Manually set the reentrancy guard to simulate a reentrant call
-        env.storage()
-            .instance()
-            .set(&DataKey::ReentrancyGuard, &true);
+        let oracles = [Address::generate(&env), Address::generate(&env)];
+        for oracle in &oracles {
+            client.add_submitter(&admin, oracle);
+        }
+        client.set_attestation_quorum(&admin, &2);
 
-        // This should panic due to reentrancy detection
-        client.submit_snapshot(&hash, &1);
+        // Epoch 1 never reaches quorum before epoch 2 is committed
+        client.attest_snapshot(&oracles[0], &Bytes::from_array(&env, &[1; 32]), &1);
+        let hash = Bytes::from_array(&env, &[2; 32]);
+        for oracle in &oracles {
+            client.attest_snapshot(oracle, &hash, &2);
+        }
+        assert_eq!(client.get_attestations(&1).len(), 1);
+
+        env.ledger()
+            .with_mut(|li| li.sequence_number += PENDING_ATTESTATION_TTL + 1);
+        assert_eq!(client.get_attestations(&1).len(), 0);
+        // Attestations behind a canonical snapshot are kept
+        assert_eq!(client.get_attestations(&2).len(), 2);
+        assert_eq!(client.get_stats().total_snapshots, 1);
     }
 
     #[test]
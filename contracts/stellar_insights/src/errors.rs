@@ -49,6 +49,42 @@ pub enum Error {
     UnauthorizedCaller = 21,
     /// Invalid hash size (must be 32 bytes)
     InvalidHashSize = 22,
+    /// A delta needs an earlier snapshot to apply to
+    DeltaBaseMissing = 23,
+    /// The delta chain is as long as the interval allows; submit a full snapshot
+    FullSnapshotRequired = 24,
+    /// Full snapshot interval must be at least 1
+    InvalidFullSnapshotInterval = 25,
+    /// Address is already an authorized submitter
+    SubmitterAlreadyExists = 26,
+    /// Address is not an authorized submitter
+    SubmitterNotFound = 27,
+    /// A batch must contain at least one entry
+    EmptyBatch = 28,
+    /// A batch holds more entries than one call may record
+    BatchTooLarge = 29,
+    /// The dispute window is 0, so snapshots cannot be disputed
+    DisputesDisabled = 30,
+    /// The snapshot is older than the dispute window
+    DisputeWindowClosed = 31,
+    /// The snapshot has already been disputed
+    SnapshotAlreadyDisputed = 32,
+    /// A snapshot cannot be disputed by the address that submitted it
+    CannotDisputeOwnSnapshot = 33,
+    /// The snapshot has no open dispute
+    SnapshotNotDisputed = 34,
+    /// The epoch's time span is further from the ledger time than the allowed skew
+    EpochTimestampMismatch = 35,
+    /// Epoch clock duration must be at least 1 second
+    InvalidEpochClock = 36,
+    /// No commitment is open for the epoch
+    CommitmentNotFound = 37,
+    /// The reveal delay since the commitment has not passed yet
+    RevealTooEarly = 38,
+    /// The revealed hash and salt do not match the commitment
+    CommitmentMismatch = 39,
+    /// The epoch has an open commitment that only its reveal can fill
+    EpochCommitted = 40,
 }
 
 impl Error {
@@ -84,6 +120,26 @@ impl Error {
             Error::ActionAlreadyExecuted => "Governance action has already been executed",
             Error::UnauthorizedCaller => "Caller is not authorized to perform this action",
             Error::InvalidHashSize => "Invalid hash size (must be 32 bytes)",
+            Error::DeltaBaseMissing => "No earlier snapshot for the delta to apply to",
+            Error::FullSnapshotRequired => "A full snapshot is due for this epoch",
+            Error::InvalidFullSnapshotInterval => "Full snapshot interval must be at least 1",
+            Error::SubmitterAlreadyExists => "Address is already an authorized submitter",
+            Error::SubmitterNotFound => "Address is not an authorized submitter",
+            Error::EmptyBatch => "Batch must contain at least one entry",
+            Error::BatchTooLarge => "Batch exceeds the maximum number of entries",
+            Error::DisputesDisabled => "Snapshot disputes are disabled",
+            Error::DisputeWindowClosed => "The dispute window for this snapshot has closed",
+            Error::SnapshotAlreadyDisputed => "Snapshot has already been disputed",
+            Error::CannotDisputeOwnSnapshot => "Submitter cannot dispute their own snapshot",
+            Error::SnapshotNotDisputed => "Snapshot has no open dispute",
+            Error::EpochTimestampMismatch => {
+                "Epoch does not match the ledger time within the allowed skew"
+            }
+            Error::InvalidEpochClock => "Epoch clock duration must be at least 1 second",
+            Error::CommitmentNotFound => "No commitment is open for this epoch",
+            Error::RevealTooEarly => "The reveal delay has not passed yet",
+            Error::CommitmentMismatch => "Revealed hash and salt do not match the commitment",
+            Error::EpochCommitted => "Epoch has an open commitment",
         }
     }
 
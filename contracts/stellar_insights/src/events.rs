@@ -1,5 +1,7 @@
 use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol};
 
+use crate::SnapshotStatus;
+
 // ============================================================================
 // Event Topics - Short symbols for efficient on-chain storage
 // ============================================================================
@@ -7,6 +9,27 @@ use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol};
 /// Topic for snapshot submission events
 pub const SNAPSHOT_SUBMITTED: Symbol = symbol_short!("SNAP_SUB");
 
+/// Topic for delta snapshot submission events
+pub const DELTA_SUBMITTED: Symbol = symbol_short!("SNAP_DLT");
+
+/// Topic for snapshot dispute events
+pub const SNAPSHOT_DISPUTED: Symbol = symbol_short!("SNAP_DSP");
+
+/// Topic for dispute resolution events
+pub const DISPUTE_RESOLVED: Symbol = symbol_short!("SNAP_RSV");
+
+/// Topic for admin changes
+pub const ADMIN_SET: Symbol = symbol_short!("ADMIN_SET");
+
+/// Topic for submitter additions; longer than a short symbol, so built at publish time
+pub const SUBMITTER_ADD: &str = "SUBMITTER_ADD";
+
+/// Topic for submitter removals; longer than a short symbol, so built at publish time
+pub const SUBMITTER_REM: &str = "SUBMITTER_REM";
+
+/// Topic for authorization changes (for filtering)
+pub const GOVERNANCE: Symbol = symbol_short!("GOV");
+
 /// Topic for snapshot lifecycle events (for filtering)
 pub const SNAPSHOT_LIFECYCLE: Symbol = symbol_short!("SNAP_LFE");
 
@@ -25,7 +48,7 @@ pub const SNAPSHOT_LIFECYCLE: Symbol = symbol_short!("SNAP_LFE");
 /// - `hash`: The 32-byte SHA-256 hash of the analytics snapshot data
 /// - `epoch`: The epoch identifier for this snapshot (positive integer)
 /// - `timestamp`: Ledger timestamp when the snapshot was recorded on-chain
-/// - `submitter`: Address of the admin who submitted the snapshot
+/// - `submitter`: Address of the admin or submitter who submitted the snapshot
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct SnapshotSubmitted {
@@ -35,7 +58,7 @@ pub struct SnapshotSubmitted {
     pub epoch: u64,
     /// Ledger timestamp when the snapshot was recorded
     pub timestamp: u64,
-    /// Address of the admin who submitted the snapshot
+    /// Address of the admin or submitter who submitted the snapshot
     pub submitter: Address,
 }
 
@@ -67,6 +90,135 @@ impl SnapshotSubmitted {
     }
 }
 
+/// Event emitted when a delta snapshot is submitted.
+///
+/// `base_epoch` is the epoch the delta applies to, and `depth` counts deltas
+/// back to the last full snapshot (1 for the first delta after a full one).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeltaSnapshotSubmitted {
+    /// SHA-256 hash of the changes since `base_epoch`
+    pub hash: BytesN<32>,
+    /// SHA-256 hash of the full snapshot the delta materializes to
+    pub snapshot_hash: BytesN<32>,
+    /// Epoch identifier for this delta
+    pub epoch: u64,
+    /// Epoch the delta applies to
+    pub base_epoch: u64,
+    /// Number of deltas since the last full snapshot, including this one
+    pub depth: u32,
+    /// Ledger timestamp when the delta was recorded
+    pub timestamp: u64,
+    /// Address of the admin or submitter who submitted the delta
+    pub submitter: Address,
+}
+
+impl DeltaSnapshotSubmitted {
+    /// Publish under (DELTA_SUBMITTED, SNAPSHOT_LIFECYCLE)
+    pub fn publish(self, env: &Env) {
+        env.events()
+            .publish((DELTA_SUBMITTED, SNAPSHOT_LIFECYCLE), self);
+    }
+}
+
+/// Event emitted when a snapshot is flagged as disputed.
+///
+/// Until the admin resolves it, `latest_snapshot` skips the disputed epoch.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SnapshotDisputed {
+    /// Hash that was disputed
+    pub hash: BytesN<32>,
+    /// Epoch of the disputed snapshot
+    pub epoch: u64,
+    /// Address that raised the dispute
+    pub disputer: Address,
+    /// Ledger timestamp when the dispute was raised
+    pub timestamp: u64,
+}
+
+impl SnapshotDisputed {
+    /// Publish under (SNAPSHOT_DISPUTED, SNAPSHOT_LIFECYCLE)
+    pub fn publish(self, env: &Env) {
+        env.events()
+            .publish((SNAPSHOT_DISPUTED, SNAPSHOT_LIFECYCLE), self);
+    }
+}
+
+/// Event emitted when the admin resolves a dispute.
+///
+/// `status` is `Accepted` when the hash was upheld and `Rejected` otherwise.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeResolved {
+    /// Epoch of the disputed snapshot
+    pub epoch: u64,
+    /// Status the snapshot was resolved to
+    pub status: SnapshotStatus,
+    /// Admin that resolved the dispute
+    pub resolver: Address,
+    /// Ledger timestamp when the dispute was resolved
+    pub timestamp: u64,
+}
+
+impl DisputeResolved {
+    /// Publish under (DISPUTE_RESOLVED, SNAPSHOT_LIFECYCLE)
+    pub fn publish(self, env: &Env) {
+        env.events()
+            .publish((DISPUTE_RESOLVED, SNAPSHOT_LIFECYCLE), self);
+    }
+}
+
+/// Event emitted when the admin is set.
+///
+/// `previous` is `None` when the contract is initialized. Together with the
+/// submitter events this lets an indexer reconstruct who could submit at any ledger.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminSet {
+    /// Admin before the change, if any
+    pub previous: Option<Address>,
+    /// New admin
+    pub admin: Address,
+    /// Ledger timestamp of the change
+    pub timestamp: u64,
+}
+
+impl AdminSet {
+    /// Publish under (ADMIN_SET, GOVERNANCE)
+    pub fn publish(self, env: &Env) {
+        env.events().publish((ADMIN_SET, GOVERNANCE), self);
+    }
+}
+
+/// Event emitted when a submitter is added or removed.
+///
+/// Published under `SUBMITTER_ADD` or `SUBMITTER_REM` with the same payload.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SubmitterChanged {
+    /// Submitter that was added or removed
+    pub submitter: Address,
+    /// Admin that made the change
+    pub admin: Address,
+    /// Ledger timestamp of the change
+    pub timestamp: u64,
+}
+
+impl SubmitterChanged {
+    /// Publish under (SUBMITTER_ADD, GOVERNANCE)
+    pub fn publish_added(self, env: &Env) {
+        env.events()
+            .publish((Symbol::new(env, SUBMITTER_ADD), GOVERNANCE), self);
+    }
+
+    /// Publish under (SUBMITTER_REM, GOVERNANCE)
+    pub fn publish_removed(self, env: &Env) {
+        env.events()
+            .publish((Symbol::new(env, SUBMITTER_REM), GOVERNANCE), self);
+    }
+}
+
 /// Legacy event structure for backwards compatibility
 ///
 /// This event emitted when an analytics snapshot is successfully submitted.
@@ -6,8 +6,14 @@ mod errors;
 mod events;
 
 use errors::Error;
-use events::emit_snapshot_submitted;
-use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, Map, String};
+use events::{
+    emit_snapshot_submitted, AdminSet, DeltaSnapshotSubmitted, DisputeResolved, SnapshotDisputed,
+    SubmitterChanged,
+};
+use soroban_sdk::{
+    contract, contractimpl, contracttype, vec, Address, Bytes, BytesN, Env, IntoVal, Map, String,
+    Symbol, Vec,
+};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -16,6 +22,15 @@ const LEDGERS_TO_EXTEND: u32 = 518_400;
 const INSTANCE_TTL_THRESHOLD: u32 = 100_000;
 const INSTANCE_TTL_EXTEND: u32 = 518_400;
 
+/// Most entries `submit_snapshots` records in one call
+pub const MAX_BATCH_SIZE: u32 = 100;
+
+/// Most snapshots `get_snapshots` returns in one call
+pub const MAX_RANGE_SIZE: u32 = 200;
+
+/// Ledgers a commitment stays open after the reveal delay has passed (~1 day)
+const COMMITMENT_REVEAL_WINDOW: u32 = 17_280;
+
 fn bump_instance(env: &Env) {
     env.storage()
         .instance()
@@ -36,6 +51,79 @@ pub enum DataKey {
     Paused,
     /// Contract package version at initialization
     Version,
+    /// Delta snapshot recorded for an epoch
+    Delta(u64),
+    /// Newest delta in the chain starting at the full snapshot of this epoch
+    DeltaHead(u64),
+    /// A full snapshot is required at least every this many epochs
+    FullSnapshotInterval,
+    /// Legacy SnapshotContract consulted by `verify_snapshot` for epochs not stored here
+    LegacyContract,
+    /// Addresses besides the admin allowed to submit snapshots and deltas
+    Submitters,
+    /// Which epochs `submit_snapshot` accepts
+    EpochPolicy,
+    /// Seconds after submission during which a snapshot can be disputed (0 = disabled)
+    DisputeWindow,
+    /// Address that submitted the snapshot at an epoch, kept while disputes are enabled
+    SubmittedBy(u64),
+    /// Dispute status of the snapshot at an epoch, once it has been disputed
+    Status(u64),
+    /// Epoch schedule and allowed skew for checking submissions against ledger time
+    EpochClock,
+    /// Commitment waiting to be revealed, by epoch and committer
+    Commitment(u64, Address),
+    /// Address holding the open commitment for an epoch
+    CommittedBy(u64),
+    /// Ledgers that must pass between a commitment and its reveal
+    RevealDelay,
+}
+
+/// Which epochs `submit_snapshot` accepts
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EpochPolicy {
+    /// Each epoch must be greater than the latest recorded one (the default)
+    StrictIncreasing,
+    /// Any epoch not recorded yet, so gaps left earlier can be filled in
+    AnyUnique,
+}
+
+/// Dispute state of a full snapshot
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SnapshotStatus {
+    /// Never disputed, or upheld by the admin after a dispute
+    Accepted,
+    /// Flagged by a second authorized party and waiting for the admin
+    Disputed,
+    /// Found invalid by the admin; never returned by `latest_snapshot`
+    Rejected,
+}
+
+/// Fixed-length epochs counted from `genesis`: epoch `n` covers
+/// `[genesis + n * duration, genesis + (n + 1) * duration)`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EpochClock {
+    /// Unix timestamp epoch 0 starts at
+    pub genesis: u64,
+    /// Length of each epoch in seconds
+    pub duration: u64,
+    /// How far outside the epoch's span the ledger time may be, in seconds
+    pub max_skew: u64,
+}
+
+/// A committed snapshot hash, hidden until revealed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Commitment {
+    /// SHA-256 of the snapshot hash followed by a 32-byte salt
+    pub commitment: BytesN<32>,
+    /// Address that committed, the only one allowed to reveal
+    pub committer: Address,
+    /// Ledger sequence the commitment was made in
+    pub ledger: u32,
 }
 
 /// Analytics snapshot data structure
@@ -50,6 +138,292 @@ pub struct Snapshot {
     pub timestamp: u64,
 }
 
+/// Outcome of one entry passed to `submit_snapshots`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchEntryResult {
+    /// Epoch of the entry
+    pub epoch: u64,
+    /// Whether the snapshot was recorded
+    pub accepted: bool,
+    /// `Error` code the entry was rejected with, if any
+    pub error: Option<u32>,
+}
+
+/// Hash of the changes since the previous epoch, recorded between full snapshots
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DeltaSnapshot {
+    /// SHA-256 hash of the changes since `base_epoch`
+    pub hash: BytesN<32>,
+    /// SHA-256 hash of the full snapshot the delta materializes to
+    pub snapshot_hash: BytesN<32>,
+    /// Epoch identifier
+    pub epoch: u64,
+    /// Epoch this delta applies to, either a full snapshot or another delta
+    pub base_epoch: u64,
+    /// Full snapshot at the start of the chain
+    pub full_epoch: u64,
+    /// Deltas back to the last full snapshot, including this one
+    pub depth: u32,
+    /// Ledger timestamp when recorded
+    pub timestamp: u64,
+}
+
+fn load_snapshots(env: &Env) -> Map<u64, Snapshot> {
+    if env.storage().persistent().has(&DataKey::Snapshots) {
+        env.storage().persistent().extend_ttl(
+            &DataKey::Snapshots,
+            LEDGERS_TO_EXTEND,
+            LEDGERS_TO_EXTEND,
+        );
+    }
+    env.storage()
+        .persistent()
+        .get(&DataKey::Snapshots)
+        .unwrap_or_else(|| Map::new(env))
+}
+
+fn load_delta(env: &Env, epoch: u64) -> Option<DeltaSnapshot> {
+    let key = DataKey::Delta(epoch);
+    let delta = env.storage().persistent().get(&key)?;
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+    Some(delta)
+}
+
+fn has_delta(env: &Env, epoch: u64) -> bool {
+    env.storage().persistent().has(&DataKey::Delta(epoch))
+}
+
+/// Store a delta under its own epoch and move its chain's head to it
+fn save_delta(env: &Env, delta: &DeltaSnapshot) {
+    let key = DataKey::Delta(delta.epoch);
+    env.storage().persistent().set(&key, delta);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+
+    let head = DataKey::DeltaHead(delta.full_epoch);
+    env.storage().persistent().set(&head, &delta.epoch);
+    env.storage()
+        .persistent()
+        .extend_ttl(&head, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+}
+
+fn load_submitters(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Submitters)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// The admin and any address in the submitter list may record snapshots
+fn require_submitter(env: &Env, caller: &Address) -> Result<(), Error> {
+    let admin: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .ok_or(Error::AdminNotSet)?;
+    if *caller == admin || load_submitters(env).contains(caller) {
+        Ok(())
+    } else {
+        Err(Error::Unauthorized)
+    }
+}
+
+fn load_snapshot_status(env: &Env, epoch: u64) -> Option<SnapshotStatus> {
+    let key = DataKey::Status(epoch);
+    let status = env.storage().persistent().get(&key)?;
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+    Some(status)
+}
+
+fn save_snapshot_status(env: &Env, epoch: u64, status: SnapshotStatus) {
+    let key = DataKey::Status(epoch);
+    env.storage().persistent().set(&key, &status);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+}
+
+/// Disputed and rejected snapshots are neither returned as latest nor verified
+fn is_blocked(env: &Env, epoch: u64) -> bool {
+    matches!(
+        load_snapshot_status(env, epoch),
+        Some(SnapshotStatus::Disputed | SnapshotStatus::Rejected)
+    )
+}
+
+fn load_snapshot_submitter(env: &Env, epoch: u64) -> Option<Address> {
+    env.storage().persistent().get(&DataKey::SubmittedBy(epoch))
+}
+
+/// Remember who submitted each epoch so they cannot dispute it themselves
+fn record_snapshot_submitters(env: &Env, epochs: &Vec<u64>, submitter: &Address) {
+    let window: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::DisputeWindow)
+        .unwrap_or(0);
+    if window == 0 || epochs.is_empty() {
+        return;
+    }
+    for epoch in epochs.iter() {
+        let key = DataKey::SubmittedBy(epoch);
+        env.storage().persistent().set(&key, submitter);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGERS_TO_EXTEND, LEDGERS_TO_EXTEND);
+    }
+}
+
+fn has_commitment(env: &Env, epoch: u64) -> bool {
+    env.storage().temporary().has(&DataKey::CommittedBy(epoch))
+}
+
+fn load_commitment(env: &Env, epoch: u64) -> Option<Commitment> {
+    let committer: Address = env
+        .storage()
+        .temporary()
+        .get(&DataKey::CommittedBy(epoch))?;
+    env.storage()
+        .temporary()
+        .get(&DataKey::Commitment(epoch, committer))
+}
+
+/// Commitments live in temporary storage, so one that is never revealed or
+/// cancelled expires a reveal window after it could first have been revealed
+fn save_commitment(env: &Env, epoch: u64, commitment: &Commitment) {
+    let ttl = StellarInsightsContract::get_reveal_delay(env.clone())
+        .saturating_add(COMMITMENT_REVEAL_WINDOW)
+        .min(LEDGERS_TO_EXTEND);
+    let owner = DataKey::CommittedBy(epoch);
+    let key = DataKey::Commitment(epoch, commitment.committer.clone());
+    env.storage().temporary().set(&owner, &commitment.committer);
+    env.storage().temporary().set(&key, commitment);
+    env.storage().temporary().extend_ttl(&owner, ttl, ttl);
+    env.storage().temporary().extend_ttl(&key, ttl, ttl);
+}
+
+fn remove_commitment(env: &Env, epoch: u64, committer: &Address) {
+    env.storage()
+        .temporary()
+        .remove(&DataKey::CommittedBy(epoch));
+    env.storage()
+        .temporary()
+        .remove(&DataKey::Commitment(epoch, committer.clone()));
+}
+
+/// Checks a new full snapshot's epoch against what is recorded so far
+fn check_snapshot_epoch(
+    env: &Env,
+    snapshots: &Map<u64, Snapshot>,
+    epoch: u64,
+    latest: u64,
+    policy: EpochPolicy,
+) -> Result<(), Error> {
+    if epoch == 0 {
+        return Err(Error::InvalidEpochZero);
+    }
+    if snapshots.contains_key(epoch) || has_delta(env, epoch) {
+        return Err(Error::DuplicateEpoch);
+    }
+    // Under the strict policy, enforce monotonic epoch increase to prevent
+    // rollback attacks
+    if epoch <= latest && policy == EpochPolicy::StrictIncreasing {
+        return Err(Error::EpochMonotonicityViolated);
+    }
+    Ok(())
+}
+
+/// Rejects an epoch whose span is more than the allowed skew away from `now`
+fn check_epoch_time(clock: Option<&EpochClock>, epoch: u64, now: u64) -> Result<(), Error> {
+    let Some(clock) = clock else {
+        return Ok(());
+    };
+    let start = clock
+        .genesis
+        .saturating_add(epoch.saturating_mul(clock.duration));
+    let end = start.saturating_add(clock.duration);
+    if now.saturating_add(clock.max_skew) < start || now > end.saturating_add(clock.max_skew) {
+        return Err(Error::EpochTimestampMismatch);
+    }
+    Ok(())
+}
+
+/// Validate and store a full snapshot for an authorized caller, returning its timestamp
+fn record_snapshot(env: &Env, epoch: u64, hash: BytesN<32>, caller: Address) -> Result<u64, Error> {
+    // Get existing snapshots map or create new one
+    let mut snapshots: Map<u64, Snapshot> = env
+        .storage()
+        .persistent()
+        .get(&DataKey::Snapshots)
+        .unwrap_or_else(|| Map::new(env));
+
+    // Reject a zero, duplicate or (under the strict policy) out-of-order epoch
+    let current_latest: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::LatestEpoch)
+        .unwrap_or(0);
+    check_snapshot_epoch(
+        env,
+        &snapshots,
+        epoch,
+        current_latest,
+        StellarInsightsContract::get_epoch_policy(env.clone()),
+    )?;
+
+    // Get current ledger timestamp
+    let timestamp = env.ledger().timestamp();
+
+    // Reject an epoch that does not line up with the ledger clock
+    check_epoch_time(
+        StellarInsightsContract::get_epoch_clock(env.clone()).as_ref(),
+        epoch,
+        timestamp,
+    )?;
+
+    // Create snapshot entry
+    let snapshot = Snapshot {
+        hash: hash.clone(),
+        epoch,
+        timestamp,
+    };
+
+    // Store snapshot
+    snapshots.set(epoch, snapshot);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Snapshots, &snapshots);
+
+    // Extend storage TTL (~30 days at 5s per ledger)
+    env.storage().persistent().extend_ttl(
+        &DataKey::Snapshots,
+        LEDGERS_TO_EXTEND,
+        LEDGERS_TO_EXTEND,
+    );
+
+    if epoch > current_latest {
+        env.storage().instance().set(&DataKey::LatestEpoch, &epoch);
+    }
+    record_snapshot_submitters(env, &vec![env, epoch], &caller);
+
+    // Emit structured event for off-chain indexing
+    // Event payload matches stored data exactly:
+    // - hash: same as snapshot.hash
+    // - epoch: same as snapshot.epoch
+    // - timestamp: same as snapshot.timestamp
+    // - submitter: the authenticated caller
+    emit_snapshot_submitted(env, hash, epoch, timestamp, caller);
+
+    Ok(timestamp)
+}
+
 /// Extended contract metadata for public disclosure
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -94,6 +468,12 @@ impl StellarInsightsContract {
 
         // Store the admin address
         env.storage().instance().set(&DataKey::Admin, &admin);
+        AdminSet {
+            previous: None,
+            admin,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
 
         // Initialize latest epoch to 0
         env.storage().instance().set(&DataKey::LatestEpoch, &0u64);
@@ -118,7 +498,7 @@ impl StellarInsightsContract {
 
     /// Submit a cryptographic hash of an analytics snapshot on-chain
     ///
-    /// Only the authorized admin can call this function. Each epoch can only
+    /// Only the admin or an authorized submitter can call this function. Each epoch can only
     /// have one snapshot submitted. Upon successful submission, an event is
     /// emitted for verification purposes.
     ///
@@ -131,10 +511,15 @@ impl StellarInsightsContract {
     /// # Errors
     /// * `Error::ContractPaused` - If contract is in emergency pause state
     /// * `Error::AdminNotSet` - If admin was not initialized
-    /// * `Error::UnauthorizedCaller` - If caller is not the admin
+    /// * `Error::Unauthorized` - If caller is neither the admin nor a submitter
     /// * `Error::InvalidEpoch` - If epoch is 0
     /// * `Error::DuplicateEpoch` - If snapshot already exists for this epoch
-    /// * `Error::EpochMonotonicityViolated` - If epoch <= latest (out-of-order submission)
+    /// * `Error::EpochMonotonicityViolated` - If epoch <= latest under
+    ///   `EpochPolicy::StrictIncreasing` (out-of-order submission)
+    /// * `Error::EpochTimestampMismatch` - If an epoch clock is set and the epoch is
+    ///   further from the ledger time than its `max_skew`
+    /// * `Error::EpochCommitted` - If the epoch has an open commitment; see
+    ///   `commit_snapshot`
     ///
     /// # Returns
     /// * Ledger timestamp when the snapshot was recorded
@@ -157,159 +542,931 @@ impl StellarInsightsContract {
         // Verify caller is authenticated
         caller.require_auth();
 
-        // Get admin address from storage
-        let admin: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::Admin)
-            .ok_or(Error::AdminNotSet)?;
+        // Verify caller is the admin or an authorized submitter
+        require_submitter(&env, &caller)?;
 
-        // Verify caller is the admin
-        if caller != admin {
-            return Err(Error::Unauthorized);
+        // An epoch with an open commitment can only be recorded by revealing it
+        if has_commitment(&env, epoch) {
+            return Err(Error::EpochCommitted);
         }
 
-        // Validate epoch is not zero
-        if epoch == 0 {
-            return Err(Error::InvalidEpochZero);
-        }
+        record_snapshot(&env, epoch, hash, caller)
+    }
 
-        // Get existing snapshots map or create new one
-        let mut snapshots: Map<u64, Snapshot> = env
+    /// Commit to a snapshot hash for `epoch` without revealing it
+    ///
+    /// `commitment` is the SHA-256 of the 32-byte snapshot hash followed by a
+    /// 32-byte secret salt. Until it is revealed with `reveal_snapshot` or
+    /// cancelled, nobody can submit a snapshot for the epoch directly, so an
+    /// observer who sees the hash during the reveal cannot get a competing
+    /// submission in first. A commitment that is not revealed within
+    /// `COMMITMENT_REVEAL_WINDOW` ledgers after the reveal delay expires and frees
+    /// the epoch again.
+    ///
+    /// # Errors
+    /// * `Error::ContractPaused` - If contract is in emergency pause state
+    /// * `Error::AdminNotSet` - If admin was not initialized
+    /// * `Error::Unauthorized` - If caller is neither the admin nor a submitter
+    /// * `Error::InvalidEpochZero` - If epoch is 0
+    /// * `Error::DuplicateEpoch` - If a snapshot or delta already exists for this epoch
+    /// * `Error::EpochMonotonicityViolated` - If the epoch policy would reject it
+    /// * `Error::EpochCommitted` - If the epoch already has an open commitment
+    pub fn commit_snapshot(
+        env: Env,
+        caller: Address,
+        epoch: u64,
+        commitment: BytesN<32>,
+    ) -> Result<(), Error> {
+        let is_paused: bool = env
             .storage()
-            .persistent()
-            .get(&DataKey::Snapshots)
-            .unwrap_or_else(|| Map::new(&env));
-
-        // Check for duplicate epoch
-        if snapshots.contains_key(epoch) {
-            return Err(Error::DuplicateEpoch);
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false);
+        if is_paused {
+            return Err(Error::ContractPaused);
         }
 
-        // Enforce monotonic epoch increase to prevent rollback attacks
-        let current_latest: u64 = env
+        caller.require_auth();
+        require_submitter(&env, &caller)?;
+
+        let latest: u64 = env
             .storage()
             .instance()
             .get(&DataKey::LatestEpoch)
             .unwrap_or(0);
-        if epoch <= current_latest {
-            return Err(Error::EpochMonotonicityViolated);
-        }
-
-        // Get current ledger timestamp
-        let timestamp = env.ledger().timestamp();
-
-        // Create snapshot entry
-        let snapshot = Snapshot {
-            hash: hash.clone(),
+        check_snapshot_epoch(
+            &env,
+            &load_snapshots(&env),
             epoch,
-            timestamp,
-        };
-
-        // Store snapshot
-        snapshots.set(epoch, snapshot);
-        env.storage()
-            .persistent()
-            .set(&DataKey::Snapshots, &snapshots);
+            latest,
+            Self::get_epoch_policy(env.clone()),
+        )?;
 
-        // Extend storage TTL (~30 days at 5s per ledger)
-        const LEDGERS_TO_EXTEND: u32 = 518_400;
-        env.storage().persistent().extend_ttl(
-            &DataKey::Snapshots,
-            LEDGERS_TO_EXTEND,
-            LEDGERS_TO_EXTEND,
+        if has_commitment(&env, epoch) {
+            return Err(Error::EpochCommitted);
+        }
+        save_commitment(
+            &env,
+            epoch,
+            &Commitment {
+                commitment,
+                committer: caller,
+                ledger: env.ledger().sequence(),
+            },
         );
-
-        env.storage().instance().set(&DataKey::LatestEpoch, &epoch);
-
-        // Emit structured event for off-chain indexing
-        // Event payload matches stored data exactly:
-        // - hash: same as snapshot.hash
-        // - epoch: same as snapshot.epoch
-        // - timestamp: same as snapshot.timestamp
-        // - submitter: the authenticated caller
-        emit_snapshot_submitted(&env, hash, epoch, timestamp, caller);
-
-        Ok(timestamp)
+        Ok(())
     }
 
-    /// Retrieve a snapshot hash for a specific epoch
+    /// Reveal a committed hash and record it as the snapshot for `epoch`
     ///
-    /// # Arguments
-    /// * `env` - Contract environment
-    /// * `epoch` - Epoch to retrieve
+    /// Only the committer can reveal, and only once `get_reveal_delay()` ledgers
+    /// have passed since the commitment. The snapshot is then checked and recorded
+    /// as `submit_snapshot` would.
     ///
     /// # Errors
-    /// * `Error::SnapshotNotFound` - If no snapshot exists for the epoch
+    /// * `Error::ContractPaused` - If contract is in emergency pause state
+    /// * `Error::AdminNotSet` - If admin was not initialized
+    /// * `Error::Unauthorized` - If caller is not an authorized submitter
+    /// * `Error::UnauthorizedCaller` - If caller did not make the commitment
+    /// * `Error::CommitmentNotFound` - If there is no commitment for `epoch`
+    /// * `Error::RevealTooEarly` - If the reveal delay has not passed
+    /// * `Error::CommitmentMismatch` - If `hash` and `salt` do not match the commitment
+    /// * Any error `submit_snapshot` returns for the epoch
     ///
     /// # Returns
-    /// * The 32-byte hash stored for that epoch
-    pub fn get_snapshot(env: Env, epoch: u64) -> Result<BytesN<32>, Error> {
-        // Extend TTL on read to keep data alive
-        const LEDGERS_TO_EXTEND: u32 = 518_400;
-        if env.storage().persistent().has(&DataKey::Snapshots) {
-            env.storage().persistent().extend_ttl(
-                &DataKey::Snapshots,
-                LEDGERS_TO_EXTEND,
-                LEDGERS_TO_EXTEND,
-            );
-        }
-        let snapshots: Map<u64, Snapshot> = env
+    /// * Ledger timestamp when the snapshot was recorded
+    pub fn reveal_snapshot(
+        env: Env,
+        caller: Address,
+        epoch: u64,
+        hash: BytesN<32>,
+        salt: BytesN<32>,
+    ) -> Result<u64, Error> {
+        let is_paused: bool = env
             .storage()
-            .persistent()
-            .get(&DataKey::Snapshots)
-            .unwrap_or_else(|| Map::new(&env));
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false);
+        if is_paused {
+            return Err(Error::ContractPaused);
+        }
 
-        snapshots
-            .get(epoch)
-            .map(|s| s.hash)
-            .ok_or(Error::SnapshotNotFound)
+        caller.require_auth();
+        require_submitter(&env, &caller)?;
+
+        let commitment = load_commitment(&env, epoch).ok_or(Error::CommitmentNotFound)?;
+        if commitment.committer != caller {
+            return Err(Error::UnauthorizedCaller);
+        }
+        let reveal_at = commitment
+            .ledger
+            .saturating_add(Self::get_reveal_delay(env.clone()));
+        if env.ledger().sequence() < reveal_at {
+            return Err(Error::RevealTooEarly);
+        }
+
+        let mut preimage = Bytes::from_array(&env, &hash.to_array());
+        preimage.append(&Bytes::from_array(&env, &salt.to_array()));
+        let digest: BytesN<32> = env.crypto().sha256(&preimage).into();
+        if digest != commitment.commitment {
+            return Err(Error::CommitmentMismatch);
+        }
+
+        let timestamp = record_snapshot(&env, epoch, hash, caller.clone())?;
+        remove_commitment(&env, epoch, &caller);
+        Ok(timestamp)
     }
 
-    /// Get the most recent snapshot
-    ///
-    /// # Arguments
-    /// * `env` - Contract environment
+    /// Withdraw an unrevealed commitment so the epoch can be submitted again
     ///
     /// # Errors
-    /// * `Error::SnapshotNotFound` - If no snapshots exist
-    ///
-    /// # Returns
-    /// * Tuple of (hash, epoch, timestamp) for the latest snapshot
-    pub fn latest_snapshot(env: Env) -> Result<(BytesN<32>, u64, u64), Error> {
-        let latest_epoch: u64 = env
+    /// * `Error::AdminNotSet` - If admin was not initialized
+    /// * `Error::CommitmentNotFound` - If there is no commitment for `epoch`
+    /// * `Error::UnauthorizedCaller` - If caller is neither the committer nor the admin
+    pub fn cancel_commitment(env: Env, caller: Address, epoch: u64) -> Result<(), Error> {
+        caller.require_auth();
+
+        let admin: Address = env
             .storage()
             .instance()
-            .get(&DataKey::LatestEpoch)
-            .unwrap_or(0);
-
-        if latest_epoch == 0 {
-            return Err(Error::SnapshotNotFound);
-        }
-
-        if env.storage().persistent().has(&DataKey::Snapshots) {
-            env.storage().persistent().extend_ttl(
-                &DataKey::Snapshots,
-                LEDGERS_TO_EXTEND,
-                LEDGERS_TO_EXTEND,
-            );
+            .get(&DataKey::Admin)
+            .ok_or(Error::AdminNotSet)?;
+        let commitment = load_commitment(&env, epoch).ok_or(Error::CommitmentNotFound)?;
+        if caller != commitment.committer && caller != admin {
+            return Err(Error::UnauthorizedCaller);
         }
+        remove_commitment(&env, epoch, &commitment.committer);
+        Ok(())
+    }
 
-        let snapshots: Map<u64, Snapshot> = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Snapshots)
-            .unwrap_or_else(|| Map::new(&env));
-
-        let snapshot = snapshots.get(latest_epoch).ok_or(Error::SnapshotNotFound)?;
-
-        Ok((snapshot.hash, snapshot.epoch, snapshot.timestamp))
+    /// Open commitment for `epoch`, if any
+    pub fn get_commitment(env: Env, epoch: u64) -> Option<Commitment> {
+        load_commitment(&env, epoch)
     }
 
-    /// Get the current admin address
-    ///
-    /// # Arguments
-    /// * `env` - Contract environment
+    /// Set how many ledgers must pass between `commit_snapshot` and `reveal_snapshot`
+    ///
+    /// # Errors
+    /// * `Error::AdminNotSet` - If admin was not initialized
+    /// * `Error::UnauthorizedCaller` - If caller is not the admin
+    pub fn set_reveal_delay(env: Env, caller: Address, ledgers: u32) -> Result<(), Error> {
+        caller.require_auth();
+
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::AdminNotSet)?;
+        if caller != admin {
+            return Err(Error::UnauthorizedCaller);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::RevealDelay, &ledgers);
+        bump_instance(&env);
+        Ok(())
+    }
+
+    /// Ledgers between a commitment and its reveal, 1 unless the admin changed it
+    pub fn get_reveal_delay(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::RevealDelay)
+            .unwrap_or(1)
+    }
+
+    /// Submit several snapshots in one call, e.g. when a backfill anchors many epochs
+    ///
+    /// Each entry is checked as `submit_snapshot` would check it, in order, so under
+    /// `EpochPolicy::StrictIncreasing` the entries must be in ascending epoch order.
+    /// Rejected entries are reported rather than failing the call, and the rest are
+    /// still recorded. Pausing, authorization and batch size apply to the whole call.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `entries` - `(epoch, hash)` pairs, at most `MAX_BATCH_SIZE`
+    /// * `caller` - Address attempting to submit the snapshots
+    ///
+    /// # Errors
+    /// * `Error::ContractPaused` - If contract is in emergency pause state
+    /// * `Error::AdminNotSet` - If admin was not initialized
+    /// * `Error::Unauthorized` - If caller is neither the admin nor a submitter
+    /// * `Error::EmptyBatch` - If `entries` is empty
+    /// * `Error::BatchTooLarge` - If `entries` holds more than `MAX_BATCH_SIZE` entries
+    ///
+    /// # Returns
+    /// * One result per entry, in the order given
+    pub fn submit_snapshots(
+        env: Env,
+        entries: Vec<(u64, BytesN<32>)>,
+        caller: Address,
+    ) -> Result<Vec<BatchEntryResult>, Error> {
+        let is_paused: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false);
+        if is_paused {
+            return Err(Error::ContractPaused);
+        }
+
+        caller.require_auth();
+        require_submitter(&env, &caller)?;
+
+        if entries.is_empty() {
+            return Err(Error::EmptyBatch);
+        }
+        if entries.len() > MAX_BATCH_SIZE {
+            return Err(Error::BatchTooLarge);
+        }
+
+        let mut snapshots = load_snapshots(&env);
+        let policy = Self::get_epoch_policy(env.clone());
+        let clock = Self::get_epoch_clock(env.clone());
+        let mut latest: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LatestEpoch)
+            .unwrap_or(0);
+        let timestamp = env.ledger().timestamp();
+
+        let mut results = Vec::new(&env);
+        let mut recorded = Vec::new(&env);
+        for (epoch, hash) in entries.iter() {
+            let checked = check_snapshot_epoch(&env, &snapshots, epoch, latest, policy)
+                .and_then(|()| check_epoch_time(clock.as_ref(), epoch, timestamp))
+                .and_then(|()| {
+                    if has_commitment(&env, epoch) {
+                        Err(Error::EpochCommitted)
+                    } else {
+                        Ok(())
+                    }
+                });
+            match checked {
+                Ok(()) => {
+                    snapshots.set(
+                        epoch,
+                        Snapshot {
+                            hash: hash.clone(),
+                            epoch,
+                            timestamp,
+                        },
+                    );
+                    latest = latest.max(epoch);
+                    recorded.push_back(epoch);
+                    emit_snapshot_submitted(&env, hash, epoch, timestamp, caller.clone());
+                    results.push_back(BatchEntryResult {
+                        epoch,
+                        accepted: true,
+                        error: None,
+                    });
+                }
+                Err(e) => results.push_back(BatchEntryResult {
+                    epoch,
+                    accepted: false,
+                    error: Some(e.code()),
+                }),
+            }
+        }
+
+        if !recorded.is_empty() {
+            env.storage()
+                .persistent()
+                .set(&DataKey::Snapshots, &snapshots);
+            env.storage().persistent().extend_ttl(
+                &DataKey::Snapshots,
+                LEDGERS_TO_EXTEND,
+                LEDGERS_TO_EXTEND,
+            );
+            env.storage().instance().set(&DataKey::LatestEpoch, &latest);
+            record_snapshot_submitters(&env, &recorded, &caller);
+        }
+
+        Ok(results)
+    }
+
+    /// Submit the hash of the changes since the previous epoch instead of a full snapshot
+    ///
+    /// The delta applies to the latest recorded epoch, so its epoch must be greater
+    /// than the latest whatever the epoch policy. Once the chain back to the
+    /// last full snapshot spans the configured interval, the next epoch must be a
+    /// full snapshot again, which bounds how far verification has to walk.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `epoch` - Epoch identifier (must be greater than the latest)
+    /// * `hash` - 32-byte SHA-256 hash of the changes since the previous epoch
+    /// * `snapshot_hash` - 32-byte SHA-256 hash of the full snapshot after applying
+    ///   the changes, which `latest_snapshot` and `verify_snapshot` use for this epoch
+    /// * `caller` - Address attempting to submit the delta
+    ///
+    /// # Errors
+    /// * `Error::ContractPaused` - If contract is in emergency pause state
+    /// * `Error::AdminNotSet` - If admin was not initialized
+    /// * `Error::Unauthorized` - If caller is neither the admin nor a submitter
+    /// * `Error::InvalidEpochZero` - If epoch is 0
+    /// * `Error::DuplicateEpoch` - If a snapshot or delta already exists for this epoch
+    /// * `Error::EpochCommitted` - If the epoch has an open commitment
+    /// * `Error::EpochMonotonicityViolated` - If epoch <= latest
+    /// * `Error::DeltaBaseMissing` - If nothing has been recorded yet
+    /// * `Error::FullSnapshotRequired` - If a full snapshot is due
+    /// * `Error::EpochTimestampMismatch` - If the epoch is too far from the ledger time
+    ///
+    /// # Returns
+    /// * Ledger timestamp when the delta was recorded
+    pub fn submit_delta_snapshot(
+        env: Env,
+        epoch: u64,
+        hash: BytesN<32>,
+        snapshot_hash: BytesN<32>,
+        caller: Address,
+    ) -> Result<u64, Error> {
+        let is_paused: bool = env
+            .storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false);
+        if is_paused {
+            return Err(Error::ContractPaused);
+        }
+
+        caller.require_auth();
+        require_submitter(&env, &caller)?;
+
+        if epoch == 0 {
+            return Err(Error::InvalidEpochZero);
+        }
+
+        let snapshots = load_snapshots(&env);
+        if snapshots.contains_key(epoch) || has_delta(&env, epoch) {
+            return Err(Error::DuplicateEpoch);
+        }
+        // An epoch with an open commitment can only be recorded by revealing it
+        if has_commitment(&env, epoch) {
+            return Err(Error::EpochCommitted);
+        }
+
+        let base_epoch: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LatestEpoch)
+            .unwrap_or(0);
+        if base_epoch == 0 {
+            return Err(Error::DeltaBaseMissing);
+        }
+        if epoch <= base_epoch {
+            return Err(Error::EpochMonotonicityViolated);
+        }
+        check_epoch_time(
+            Self::get_epoch_clock(env.clone()).as_ref(),
+            epoch,
+            env.ledger().timestamp(),
+        )?;
+
+        let (base_depth, full_epoch) = if snapshots.contains_key(base_epoch) {
+            (0, base_epoch)
+        } else {
+            load_delta(&env, base_epoch)
+                .map(|delta| (delta.depth, delta.full_epoch))
+                .ok_or(Error::DeltaBaseMissing)?
+        };
+        let depth = base_depth + 1;
+        if depth >= Self::get_full_snapshot_interval(env.clone()) {
+            return Err(Error::FullSnapshotRequired);
+        }
+
+        let timestamp = env.ledger().timestamp();
+        save_delta(
+            &env,
+            &DeltaSnapshot {
+                hash: hash.clone(),
+                snapshot_hash: snapshot_hash.clone(),
+                epoch,
+                base_epoch,
+                full_epoch,
+                depth,
+                timestamp,
+            },
+        );
+        env.storage().instance().set(&DataKey::LatestEpoch, &epoch);
+
+        DeltaSnapshotSubmitted {
+            hash,
+            snapshot_hash,
+            epoch,
+            base_epoch,
+            depth,
+            timestamp,
+            submitter: caller,
+        }
+        .publish(&env);
+
+        Ok(timestamp)
+    }
+
+    /// Require a full snapshot at least every `interval` epochs
+    ///
+    /// An interval of 1 (the default) disables deltas; with 24, a full snapshot
+    /// can be followed by up to 23 deltas.
+    ///
+    /// # Errors
+    /// * `Error::AdminNotSet` - If admin was not initialized
+    /// * `Error::UnauthorizedCaller` - If caller is not the admin
+    /// * `Error::InvalidFullSnapshotInterval` - If interval is 0
+    pub fn set_full_snapshot_interval(
+        env: Env,
+        caller: Address,
+        interval: u32,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::AdminNotSet)?;
+        if caller != admin {
+            return Err(Error::UnauthorizedCaller);
+        }
+        if interval == 0 {
+            return Err(Error::InvalidFullSnapshotInterval);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::FullSnapshotInterval, &interval);
+        bump_instance(&env);
+        Ok(())
+    }
+
+    /// Choose which epochs `submit_snapshot` accepts
+    ///
+    /// `AnyUnique` lets a generator fill in epochs below the latest, but an epoch
+    /// that is already recorded is still rejected, so history cannot be rewritten.
+    ///
+    /// # Errors
+    /// * `Error::AdminNotSet` - If admin was not initialized
+    /// * `Error::UnauthorizedCaller` - If caller is not the admin
+    pub fn set_epoch_policy(env: Env, caller: Address, policy: EpochPolicy) -> Result<(), Error> {
+        caller.require_auth();
+
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::AdminNotSet)?;
+        if caller != admin {
+            return Err(Error::UnauthorizedCaller);
+        }
+
+        env.storage().instance().set(&DataKey::EpochPolicy, &policy);
+        bump_instance(&env);
+        Ok(())
+    }
+
+    /// Current epoch policy, `StrictIncreasing` unless the admin changed it
+    pub fn get_epoch_policy(env: Env) -> EpochPolicy {
+        env.storage()
+            .instance()
+            .get(&DataKey::EpochPolicy)
+            .unwrap_or(EpochPolicy::StrictIncreasing)
+    }
+
+    /// Set how long after submission a full snapshot can be disputed
+    ///
+    /// Submitters are only recorded while the window is non-zero, so snapshots
+    /// submitted with disputes disabled can never be disputed.
+    ///
+    /// # Errors
+    /// * `Error::AdminNotSet` - If admin was not initialized
+    /// * `Error::UnauthorizedCaller` - If caller is not the admin
+    pub fn set_dispute_window(env: Env, caller: Address, seconds: u64) -> Result<(), Error> {
+        caller.require_auth();
+
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::AdminNotSet)?;
+        if caller != admin {
+            return Err(Error::UnauthorizedCaller);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::DisputeWindow, &seconds);
+        bump_instance(&env);
+        Ok(())
+    }
+
+    /// Seconds after submission during which a snapshot can be disputed (0 = disabled)
+    pub fn get_dispute_window(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::DisputeWindow)
+            .unwrap_or(0)
+    }
+
+    /// Flag a full snapshot as disputed
+    ///
+    /// Any authorized address other than the one that submitted the snapshot may
+    /// dispute it within the dispute window. A disputed snapshot is skipped by
+    /// `latest_snapshot` until the admin calls `resolve_dispute`.
+    ///
+    /// # Errors
+    /// * `Error::AdminNotSet` - If admin was not initialized
+    /// * `Error::Unauthorized` - If caller is neither the admin nor a submitter
+    /// * `Error::DisputesDisabled` - If the dispute window is 0
+    /// * `Error::SnapshotNotFound` - If no full snapshot exists for `epoch`
+    /// * `Error::DisputeWindowClosed` - If the window has passed or the snapshot
+    ///   was submitted while disputes were disabled
+    /// * `Error::CannotDisputeOwnSnapshot` - If caller submitted the snapshot
+    /// * `Error::SnapshotAlreadyDisputed` - If the snapshot was disputed before
+    pub fn dispute_snapshot(env: Env, caller: Address, epoch: u64) -> Result<(), Error> {
+        caller.require_auth();
+        require_submitter(&env, &caller)?;
+
+        let window = Self::get_dispute_window(env.clone());
+        if window == 0 {
+            return Err(Error::DisputesDisabled);
+        }
+        let snapshot = load_snapshots(&env)
+            .get(epoch)
+            .ok_or(Error::SnapshotNotFound)?;
+        let now = env.ledger().timestamp();
+        let submitter = load_snapshot_submitter(&env, epoch).ok_or(Error::DisputeWindowClosed)?;
+        if now > snapshot.timestamp.saturating_add(window) {
+            return Err(Error::DisputeWindowClosed);
+        }
+        if caller == submitter {
+            return Err(Error::CannotDisputeOwnSnapshot);
+        }
+
+        if load_snapshot_status(&env, epoch).is_some() {
+            return Err(Error::SnapshotAlreadyDisputed);
+        }
+        save_snapshot_status(&env, epoch, SnapshotStatus::Disputed);
+
+        SnapshotDisputed {
+            hash: snapshot.hash,
+            epoch,
+            disputer: caller,
+            timestamp: now,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Settle an open dispute, either upholding the hash or rejecting it
+    ///
+    /// An upheld snapshot is returned by `latest_snapshot` again. A rejected one
+    /// stays recorded, so its epoch cannot be reused, but is never returned.
+    ///
+    /// # Errors
+    /// * `Error::AdminNotSet` - If admin was not initialized
+    /// * `Error::UnauthorizedCaller` - If caller is not the admin
+    /// * `Error::SnapshotNotDisputed` - If the snapshot has no open dispute
+    ///
+    /// # Returns
+    /// * The status the snapshot was resolved to
+    pub fn resolve_dispute(
+        env: Env,
+        caller: Address,
+        epoch: u64,
+        uphold: bool,
+    ) -> Result<SnapshotStatus, Error> {
+        caller.require_auth();
+
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::AdminNotSet)?;
+        if caller != admin {
+            return Err(Error::UnauthorizedCaller);
+        }
+
+        if load_snapshot_status(&env, epoch) != Some(SnapshotStatus::Disputed) {
+            return Err(Error::SnapshotNotDisputed);
+        }
+        let status = if uphold {
+            SnapshotStatus::Accepted
+        } else {
+            SnapshotStatus::Rejected
+        };
+        save_snapshot_status(&env, epoch, status);
+
+        DisputeResolved {
+            epoch,
+            status,
+            resolver: caller,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish(&env);
+        Ok(status)
+    }
+
+    /// Dispute state of the full snapshot at `epoch`
+    ///
+    /// # Errors
+    /// * `Error::SnapshotNotFound` - If no full snapshot exists for `epoch`
+    pub fn get_snapshot_status(env: Env, epoch: u64) -> Result<SnapshotStatus, Error> {
+        if !load_snapshots(&env).contains_key(epoch) {
+            return Err(Error::SnapshotNotFound);
+        }
+        Ok(load_snapshot_status(&env, epoch).unwrap_or(SnapshotStatus::Accepted))
+    }
+
+    /// Reject snapshots and deltas whose epoch is far from the current ledger time
+    ///
+    /// With a clock set, an epoch is only accepted while the ledger time is within
+    /// `max_skew` seconds of its span, which stops a generator with a broken clock
+    /// or a bad epoch calculation from anchoring epochs far in the past or future.
+    /// `None` turns the check off, e.g. to backfill old epochs.
+    ///
+    /// # Errors
+    /// * `Error::AdminNotSet` - If admin was not initialized
+    /// * `Error::UnauthorizedCaller` - If caller is not the admin
+    /// * `Error::InvalidEpochClock` - If the clock's duration is 0
+    pub fn set_epoch_clock(
+        env: Env,
+        caller: Address,
+        clock: Option<EpochClock>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::AdminNotSet)?;
+        if caller != admin {
+            return Err(Error::UnauthorizedCaller);
+        }
+
+        match clock {
+            Some(clock) => {
+                if clock.duration == 0 {
+                    return Err(Error::InvalidEpochClock);
+                }
+                env.storage().instance().set(&DataKey::EpochClock, &clock);
+            }
+            None => env.storage().instance().remove(&DataKey::EpochClock),
+        }
+        bump_instance(&env);
+        Ok(())
+    }
+
+    /// Epoch clock submissions are checked against, if any
+    pub fn get_epoch_clock(env: Env) -> Option<EpochClock> {
+        env.storage().instance().get(&DataKey::EpochClock)
+    }
+
+    /// Maximum number of epochs between full snapshots
+    pub fn get_full_snapshot_interval(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::FullSnapshotInterval)
+            .unwrap_or(1)
+    }
+
+    /// Retrieve the delta recorded for an epoch
+    ///
+    /// # Errors
+    /// * `Error::SnapshotNotFound` - If no delta exists for the epoch
+    pub fn get_delta(env: Env, epoch: u64) -> Result<DeltaSnapshot, Error> {
+        load_delta(&env, epoch).ok_or(Error::SnapshotNotFound)
+    }
+
+    /// Hashes needed to reconstruct an epoch: the last full snapshot at or before
+    /// it, followed by every delta up to and including the epoch
+    ///
+    /// # Errors
+    /// * `Error::SnapshotNotFound` - If the epoch, or any link back to a full
+    ///   snapshot, is missing
+    pub fn get_delta_chain(env: Env, epoch: u64) -> Result<Vec<BytesN<32>>, Error> {
+        let snapshots = load_snapshots(&env);
+
+        let mut chain = Vec::new(&env);
+        let mut current = epoch;
+        loop {
+            if let Some(full) = snapshots.get(current) {
+                chain.push_front(full.hash);
+                return Ok(chain);
+            }
+            let delta = load_delta(&env, current).ok_or(Error::SnapshotNotFound)?;
+            chain.push_front(delta.hash);
+            current = delta.base_epoch;
+        }
+    }
+
+    /// Check an off-chain chain of hashes (full snapshot first, then deltas in
+    /// order) against what is recorded for `epoch`
+    pub fn verify_delta_chain(env: Env, epoch: u64, hashes: Vec<BytesN<32>>) -> bool {
+        Self::get_delta_chain(env, epoch).is_ok_and(|chain| chain == hashes)
+    }
+
+    /// Retrieve a snapshot hash for a specific epoch
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `epoch` - Epoch to retrieve
+    ///
+    /// # Errors
+    /// * `Error::SnapshotNotFound` - If no snapshot exists for the epoch
+    ///
+    /// # Returns
+    /// * The 32-byte hash stored for that epoch
+    pub fn get_snapshot(env: Env, epoch: u64) -> Result<BytesN<32>, Error> {
+        // Extend TTL on read to keep data alive
+        const LEDGERS_TO_EXTEND: u32 = 518_400;
+        if env.storage().persistent().has(&DataKey::Snapshots) {
+            env.storage().persistent().extend_ttl(
+                &DataKey::Snapshots,
+                LEDGERS_TO_EXTEND,
+                LEDGERS_TO_EXTEND,
+            );
+        }
+        let snapshots: Map<u64, Snapshot> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Snapshots)
+            .unwrap_or_else(|| Map::new(&env));
+
+        snapshots
+            .get(epoch)
+            .map(|s| s.hash)
+            .ok_or(Error::SnapshotNotFound)
+    }
+
+    /// Retrieve the full snapshots recorded between two epochs in one call
+    ///
+    /// Lets a mirror catch up after downtime without a `get_snapshot` call per
+    /// epoch. Epochs without a full snapshot, including deltas, are left out. To
+    /// page through a longer range, call again from the last epoch returned plus one.
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    /// * `from_epoch` - First epoch to include
+    /// * `to_epoch` - Last epoch to include
+    /// * `limit` - Most snapshots to return, capped at `MAX_RANGE_SIZE`
+    ///
+    /// # Errors
+    /// * `Error::InvalidEpoch` - If `from_epoch` is greater than `to_epoch`
+    ///
+    /// # Returns
+    /// * `(epoch, hash, timestamp)` for each snapshot, in ascending epoch order
+    pub fn get_snapshots(
+        env: Env,
+        from_epoch: u64,
+        to_epoch: u64,
+        limit: u32,
+    ) -> Result<Vec<(u64, BytesN<32>, u64)>, Error> {
+        if from_epoch > to_epoch {
+            return Err(Error::InvalidEpoch);
+        }
+        let limit = limit.min(MAX_RANGE_SIZE);
+
+        let mut range = Vec::new(&env);
+        for (epoch, snapshot) in load_snapshots(&env).iter() {
+            if range.len() >= limit || epoch > to_epoch {
+                break;
+            }
+            if epoch >= from_epoch {
+                range.push_back((epoch, snapshot.hash, snapshot.timestamp));
+            }
+        }
+        Ok(range)
+    }
+
+    /// Check a hash against the snapshot recorded for an epoch, here or in the
+    /// legacy contract
+    ///
+    /// Epochs with a full snapshot in this contract are checked locally, and
+    /// delta epochs against the snapshot hash they materialize to. A snapshot
+    /// that is disputed or rejected never verifies, and neither do the deltas
+    /// built on it. Other epochs fall through to the legacy SnapshotContract, if
+    /// one is configured, so during a migration consumers only need this
+    /// contract's address. A legacy contract that fails or is unreachable counts
+    /// as not verified.
+    ///
+    /// # Returns
+    /// * `true` if the hash matches the snapshot recorded for the epoch
+    pub fn verify_snapshot(env: Env, epoch: u64, hash: BytesN<32>) -> bool {
+        if let Some(snapshot) = load_snapshots(&env).get(epoch) {
+            return !is_blocked(&env, epoch) && snapshot.hash == hash;
+        }
+        if let Some(delta) = load_delta(&env, epoch) {
+            return !is_blocked(&env, delta.full_epoch) && delta.snapshot_hash == hash;
+        }
+        let Some(legacy) = Self::get_legacy_contract(env.clone()) else {
+            return false;
+        };
+
+        let args = vec![&env, Bytes::from(hash).into_val(&env), epoch.into_val(&env)];
+        matches!(
+            env.try_invoke_contract::<bool, soroban_sdk::Error>(
+                &legacy,
+                &Symbol::new(&env, "verify_snapshot_at_epoch"),
+                args,
+            ),
+            Ok(Ok(true))
+        )
+    }
+
+    /// Point `verify_snapshot` at the legacy SnapshotContract holding epochs
+    /// recorded under the old layout, or pass `None` once migration is done
+    ///
+    /// # Errors
+    /// * `Error::AdminNotSet` - If admin was not initialized
+    /// * `Error::UnauthorizedCaller` - If caller is not the admin
+    pub fn set_legacy_contract(
+        env: Env,
+        caller: Address,
+        legacy: Option<Address>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::AdminNotSet)?;
+        if caller != admin {
+            return Err(Error::UnauthorizedCaller);
+        }
+
+        match legacy {
+            Some(address) => env
+                .storage()
+                .instance()
+                .set(&DataKey::LegacyContract, &address),
+            None => env.storage().instance().remove(&DataKey::LegacyContract),
+        }
+        bump_instance(&env);
+        Ok(())
+    }
+
+    /// Legacy SnapshotContract consulted by `verify_snapshot`, if any
+    pub fn get_legacy_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::LegacyContract)
+    }
+
+    /// Get the most recent snapshot
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
+    ///
+    /// # Errors
+    /// * `Error::SnapshotNotFound` - If no snapshots exist
+    ///
+    /// # Returns
+    /// * Tuple of (hash, epoch, timestamp) for the latest snapshot. When the latest
+    ///   epoch is a delta this is the hash of the snapshot it materializes to. A
+    ///   full snapshot that is disputed or rejected is skipped, along with the
+    ///   deltas built on it, in favour of the last undisputed full snapshot before
+    ///   it and the newest delta on top of that.
+    pub fn latest_snapshot(env: Env) -> Result<(BytesN<32>, u64, u64), Error> {
+        let latest_epoch: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LatestEpoch)
+            .unwrap_or(0);
+        if latest_epoch == 0 {
+            return Err(Error::SnapshotNotFound);
+        }
+
+        let snapshots = load_snapshots(&env);
+
+        let mut epoch = latest_epoch;
+        let full_epoch = load_delta(&env, epoch).map_or(epoch, |delta| delta.full_epoch);
+        if is_blocked(&env, full_epoch) {
+            let fallback = snapshots
+                .keys()
+                .iter()
+                .filter(|candidate| *candidate < full_epoch && !is_blocked(&env, *candidate))
+                .max()
+                .ok_or(Error::SnapshotNotFound)?;
+            epoch = env
+                .storage()
+                .persistent()
+                .get(&DataKey::DeltaHead(fallback))
+                .unwrap_or(fallback);
+        }
+
+        if let Some(snapshot) = snapshots.get(epoch) {
+            return Ok((snapshot.hash, snapshot.epoch, snapshot.timestamp));
+        }
+        let delta = load_delta(&env, epoch).ok_or(Error::SnapshotNotFound)?;
+
+        Ok((delta.snapshot_hash, delta.epoch, delta.timestamp))
+    }
+
+    /// Get the current admin address
+    ///
+    /// # Arguments
+    /// * `env` - Contract environment
     ///
     /// # Errors
     /// * `Error::AdminNotSet` - If admin was not initialized
@@ -323,6 +1480,86 @@ impl StellarInsightsContract {
             .ok_or(Error::AdminNotSet)
     }
 
+    /// Allow `submitter` to call `submit_snapshot` and `submit_delta_snapshot`
+    ///
+    /// Lets automated submission run under a dedicated key instead of the admin's.
+    /// Submitters cannot pause, change settings or manage other submitters.
+    ///
+    /// # Errors
+    /// * `Error::AdminNotSet` - If admin was not initialized
+    /// * `Error::UnauthorizedCaller` - If caller is not the admin
+    /// * `Error::SubmitterAlreadyExists` - If `submitter` is already in the list
+    pub fn add_submitter(env: Env, caller: Address, submitter: Address) -> Result<(), Error> {
+        caller.require_auth();
+
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::AdminNotSet)?;
+        if caller != admin {
+            return Err(Error::UnauthorizedCaller);
+        }
+
+        let mut submitters = load_submitters(&env);
+        if submitters.contains(&submitter) {
+            return Err(Error::SubmitterAlreadyExists);
+        }
+        submitters.push_back(submitter.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::Submitters, &submitters);
+        bump_instance(&env);
+        SubmitterChanged {
+            submitter,
+            admin,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish_added(&env);
+        Ok(())
+    }
+
+    /// Revoke a submitter added with `add_submitter`
+    ///
+    /// # Errors
+    /// * `Error::AdminNotSet` - If admin was not initialized
+    /// * `Error::UnauthorizedCaller` - If caller is not the admin
+    /// * `Error::SubmitterNotFound` - If `submitter` is not in the list
+    pub fn remove_submitter(env: Env, caller: Address, submitter: Address) -> Result<(), Error> {
+        caller.require_auth();
+
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::AdminNotSet)?;
+        if caller != admin {
+            return Err(Error::UnauthorizedCaller);
+        }
+
+        let mut submitters = load_submitters(&env);
+        let index = submitters
+            .first_index_of(&submitter)
+            .ok_or(Error::SubmitterNotFound)?;
+        submitters.remove(index);
+        env.storage()
+            .instance()
+            .set(&DataKey::Submitters, &submitters);
+        bump_instance(&env);
+        SubmitterChanged {
+            submitter,
+            admin,
+            timestamp: env.ledger().timestamp(),
+        }
+        .publish_removed(&env);
+        Ok(())
+    }
+
+    /// Addresses allowed to submit snapshots besides the admin, in the order added
+    pub fn list_submitters(env: Env) -> Vec<Address> {
+        load_submitters(&env)
+    }
+
     /// Get the latest epoch number
     ///
     /// # Arguments
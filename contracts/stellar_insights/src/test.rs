@@ -4,7 +4,10 @@
 #![allow(clippy::panic)]
 
 use super::*;
-use crate::events::{SnapshotSubmitted, SNAPSHOT_LIFECYCLE, SNAPSHOT_SUBMITTED};
+use crate::events::{
+    AdminSet, SnapshotSubmitted, SubmitterChanged, ADMIN_SET, GOVERNANCE, SNAPSHOT_LIFECYCLE,
+    SNAPSHOT_SUBMITTED, SUBMITTER_ADD, SUBMITTER_REM,
+};
 use soroban_sdk::{
     testutils::{Address as _, Events},
     Address, BytesN, Env,
@@ -204,6 +207,98 @@ fn test_older_epoch_rejected() {
     assert!(client.try_get_snapshot(&5u64).is_err());
 }
 
+#[test]
+fn test_any_unique_epoch_policy_fills_gaps() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StellarInsightsContract);
+    let client = StellarInsightsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    assert_eq!(client.get_epoch_policy(), EpochPolicy::StrictIncreasing);
+    assert_eq!(
+        client.try_set_epoch_policy(&Address::generate(&env), &EpochPolicy::AnyUnique),
+        Err(Ok(Error::UnauthorizedCaller))
+    );
+
+    client.submit_snapshot(&10u64, &create_test_hash(&env, 10), &admin);
+    client.set_epoch_policy(&admin, &EpochPolicy::AnyUnique);
+
+    // A gap below the latest can be filled, but the latest epoch stays put
+    client.submit_snapshot(&5u64, &create_test_hash(&env, 5), &admin);
+    assert_eq!(client.get_snapshot(&5u64), create_test_hash(&env, 5));
+    assert_eq!(client.get_latest_epoch(), 10);
+
+    // Recorded epochs still cannot be rewritten
+    let result = client.try_submit_snapshot(&5u64, &create_test_hash(&env, 6), &admin);
+    assert_eq!(result, Err(Ok(Error::DuplicateEpoch)));
+
+    client.set_epoch_policy(&admin, &EpochPolicy::StrictIncreasing);
+    let result = client.try_submit_snapshot(&7u64, &create_test_hash(&env, 7), &admin);
+    assert_eq!(result, Err(Ok(Error::EpochMonotonicityViolated)));
+}
+
+#[test]
+fn test_submit_snapshots_reports_rejected_entries() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StellarInsightsContract);
+    let client = StellarInsightsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.submit_snapshot(&3u64, &create_test_hash(&env, 3), &admin);
+
+    let entries = soroban_sdk::vec![
+        &env,
+        (4u64, create_test_hash(&env, 4)),
+        (0u64, create_test_hash(&env, 0)),
+        (4u64, create_test_hash(&env, 40)),
+        (2u64, create_test_hash(&env, 2)),
+        (6u64, create_test_hash(&env, 6)),
+    ];
+    let results = client.submit_snapshots(&entries, &admin);
+
+    let outcome = |i: u32| {
+        let result = results.get(i).unwrap();
+        (result.epoch, result.accepted, result.error)
+    };
+    assert_eq!(outcome(0), (4, true, None));
+    assert_eq!(outcome(1), (0, false, Some(Error::InvalidEpochZero.code())));
+    assert_eq!(outcome(2), (4, false, Some(Error::DuplicateEpoch.code())));
+    assert_eq!(
+        outcome(3),
+        (2, false, Some(Error::EpochMonotonicityViolated.code()))
+    );
+    assert_eq!(outcome(4), (6, true, None));
+
+    assert_eq!(client.get_snapshot(&4u64), create_test_hash(&env, 4));
+    assert_eq!(client.get_snapshot(&6u64), create_test_hash(&env, 6));
+    assert!(client.try_get_snapshot(&2u64).is_err());
+    assert_eq!(client.get_latest_epoch(), 6);
+
+    // Whole-call checks fail the call outright
+    assert_eq!(
+        client.try_submit_snapshots(&Vec::new(&env), &admin),
+        Err(Ok(Error::EmptyBatch))
+    );
+    let mut oversized = Vec::new(&env);
+    for epoch in 0..=u64::from(MAX_BATCH_SIZE) {
+        oversized.push_back((100 + epoch, create_test_hash(&env, 7)));
+    }
+    assert_eq!(
+        client.try_submit_snapshots(&oversized, &admin),
+        Err(Ok(Error::BatchTooLarge))
+    );
+    assert_eq!(
+        client.try_submit_snapshots(&entries, &Address::generate(&env)),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
 #[test]
 fn test_snapshot_submitted_event() {
     let env = Env::default();
@@ -449,6 +544,24 @@ fn test_error_codes_are_unique() {
         Error::ActionAlreadyExecuted as u32,
         Error::UnauthorizedCaller as u32,
         Error::InvalidHashSize as u32,
+        Error::DeltaBaseMissing as u32,
+        Error::FullSnapshotRequired as u32,
+        Error::InvalidFullSnapshotInterval as u32,
+        Error::SubmitterAlreadyExists as u32,
+        Error::SubmitterNotFound as u32,
+        Error::EmptyBatch as u32,
+        Error::BatchTooLarge as u32,
+        Error::DisputesDisabled as u32,
+        Error::DisputeWindowClosed as u32,
+        Error::SnapshotAlreadyDisputed as u32,
+        Error::CannotDisputeOwnSnapshot as u32,
+        Error::SnapshotNotDisputed as u32,
+        Error::EpochTimestampMismatch as u32,
+        Error::InvalidEpochClock as u32,
+        Error::CommitmentNotFound as u32,
+        Error::RevealTooEarly as u32,
+        Error::CommitmentMismatch as u32,
+        Error::EpochCommitted as u32,
     ];
     codes.sort();
     let unique = codes.windows(2).all(|w| w[0] != w[1]);
@@ -480,6 +593,24 @@ fn test_error_descriptions_are_non_empty() {
         Error::ActionAlreadyExecuted,
         Error::UnauthorizedCaller,
         Error::InvalidHashSize,
+        Error::DeltaBaseMissing,
+        Error::FullSnapshotRequired,
+        Error::InvalidFullSnapshotInterval,
+        Error::SubmitterAlreadyExists,
+        Error::SubmitterNotFound,
+        Error::EmptyBatch,
+        Error::BatchTooLarge,
+        Error::DisputesDisabled,
+        Error::DisputeWindowClosed,
+        Error::SnapshotAlreadyDisputed,
+        Error::CannotDisputeOwnSnapshot,
+        Error::SnapshotNotDisputed,
+        Error::EpochTimestampMismatch,
+        Error::InvalidEpochClock,
+        Error::CommitmentNotFound,
+        Error::RevealTooEarly,
+        Error::CommitmentMismatch,
+        Error::EpochCommitted,
     ];
     for e in errors {
         assert!(
@@ -514,6 +645,24 @@ fn test_error_code_matches_repr() {
     assert_eq!(Error::ActionAlreadyExecuted.code(), 20);
     assert_eq!(Error::UnauthorizedCaller.code(), 21);
     assert_eq!(Error::InvalidHashSize.code(), 22);
+    assert_eq!(Error::DeltaBaseMissing.code(), 23);
+    assert_eq!(Error::FullSnapshotRequired.code(), 24);
+    assert_eq!(Error::InvalidFullSnapshotInterval.code(), 25);
+    assert_eq!(Error::SubmitterAlreadyExists.code(), 26);
+    assert_eq!(Error::SubmitterNotFound.code(), 27);
+    assert_eq!(Error::EmptyBatch.code(), 28);
+    assert_eq!(Error::BatchTooLarge.code(), 29);
+    assert_eq!(Error::DisputesDisabled.code(), 30);
+    assert_eq!(Error::DisputeWindowClosed.code(), 31);
+    assert_eq!(Error::SnapshotAlreadyDisputed.code(), 32);
+    assert_eq!(Error::CannotDisputeOwnSnapshot.code(), 33);
+    assert_eq!(Error::SnapshotNotDisputed.code(), 34);
+    assert_eq!(Error::EpochTimestampMismatch.code(), 35);
+    assert_eq!(Error::InvalidEpochClock.code(), 36);
+    assert_eq!(Error::CommitmentNotFound.code(), 37);
+    assert_eq!(Error::RevealTooEarly.code(), 38);
+    assert_eq!(Error::CommitmentMismatch.code(), 39);
+    assert_eq!(Error::EpochCommitted.code(), 40);
 }
 
 #[test]
@@ -621,3 +770,744 @@ fn test_error_log_context_returns_self() {
     // log_context must return the same error variant
     assert_eq!(err.log_context(&env, "test context"), Error::Unauthorized);
 }
+
+// ============================================================================
+// Delta snapshots
+// ============================================================================
+
+#[test]
+fn test_deltas_disabled_by_default() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StellarInsightsContract);
+    let client = StellarInsightsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    // Nothing to apply a delta to yet
+    let result = client.try_submit_delta_snapshot(
+        &1,
+        &create_test_hash(&env, 1),
+        &create_test_hash(&env, 1001),
+        &admin,
+    );
+    assert_eq!(result, Err(Ok(Error::DeltaBaseMissing)));
+
+    client.submit_snapshot(&1, &create_test_hash(&env, 1), &admin);
+    assert_eq!(client.get_full_snapshot_interval(), 1);
+    let result = client.try_submit_delta_snapshot(
+        &2,
+        &create_test_hash(&env, 2),
+        &create_test_hash(&env, 1002),
+        &admin,
+    );
+    assert_eq!(result, Err(Ok(Error::FullSnapshotRequired)));
+
+    assert_eq!(
+        client.try_set_full_snapshot_interval(&admin, &0),
+        Err(Ok(Error::InvalidFullSnapshotInterval))
+    );
+    assert_eq!(
+        client.try_set_full_snapshot_interval(&Address::generate(&env), &4),
+        Err(Ok(Error::UnauthorizedCaller))
+    );
+}
+
+#[test]
+fn test_delta_chain_walks_back_to_full_snapshot() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StellarInsightsContract);
+    let client = StellarInsightsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.set_full_snapshot_interval(&admin, &3);
+
+    let full = create_test_hash(&env, 100);
+    let first = create_test_hash(&env, 101);
+    let second = create_test_hash(&env, 102);
+    let materialized = create_test_hash(&env, 112);
+    client.submit_snapshot(&10, &full, &admin);
+    client.submit_delta_snapshot(&11, &first, &create_test_hash(&env, 111), &admin);
+    client.submit_delta_snapshot(&12, &second, &materialized, &admin);
+
+    // Two deltas after a full snapshot fill an interval of 3
+    let result = client.try_submit_delta_snapshot(
+        &13,
+        &create_test_hash(&env, 103),
+        &create_test_hash(&env, 1013),
+        &admin,
+    );
+    assert_eq!(result, Err(Ok(Error::FullSnapshotRequired)));
+
+    let delta = client.get_delta(&12);
+    assert_eq!(delta.base_epoch, 11);
+    assert_eq!(delta.depth, 2);
+    assert_eq!(delta.full_epoch, 10);
+    // The latest snapshot is what the delta materializes to, not the delta itself
+    assert_eq!(
+        client.latest_snapshot(),
+        (materialized.clone(), 12, delta.timestamp)
+    );
+    assert!(client.verify_snapshot(&12, &materialized));
+    assert!(!client.verify_snapshot(&12, &second));
+    assert!(client.try_get_snapshot(&12).is_err());
+
+    let chain = client.get_delta_chain(&12);
+    assert_eq!(
+        chain,
+        soroban_sdk::vec![&env, full.clone(), first.clone(), second.clone()]
+    );
+    assert_eq!(
+        client.get_delta_chain(&10),
+        soroban_sdk::vec![&env, full.clone()]
+    );
+    assert!(client.verify_delta_chain(&12, &chain));
+    assert!(!client.verify_delta_chain(&12, &soroban_sdk::vec![&env, full.clone(), second]));
+    assert!(!client.verify_delta_chain(&99, &chain));
+
+    // A full snapshot restarts the chain
+    let next_full = create_test_hash(&env, 200);
+    client.submit_snapshot(&13, &next_full, &admin);
+    client.submit_delta_snapshot(
+        &14,
+        &create_test_hash(&env, 201),
+        &create_test_hash(&env, 1014),
+        &admin,
+    );
+    assert_eq!(client.get_delta(&14).depth, 1);
+    assert_eq!(client.get_delta_chain(&14).get(0), Some(next_full));
+    assert_eq!(
+        client.try_submit_snapshot(&14, &full, &admin),
+        Err(Ok(Error::DuplicateEpoch))
+    );
+}
+
+/// Stands in for the legacy SnapshotContract, which recorded epoch 7 only
+#[soroban_sdk::contract]
+struct LegacySnapshotContract;
+
+#[soroban_sdk::contractimpl]
+impl LegacySnapshotContract {
+    pub fn verify_snapshot_at_epoch(env: Env, hash: soroban_sdk::Bytes, epoch: u64) -> bool {
+        epoch == 7 && hash == soroban_sdk::Bytes::from(create_test_hash(&env, 7))
+    }
+}
+
+#[test]
+fn test_verify_snapshot_falls_back_to_legacy_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StellarInsightsContract);
+    let client = StellarInsightsContractClient::new(&env, &contract_id);
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    client.submit_snapshot(&10, &create_test_hash(&env, 10), &admin);
+
+    assert!(client.verify_snapshot(&10, &create_test_hash(&env, 10)));
+    assert!(!client.verify_snapshot(&10, &create_test_hash(&env, 11)));
+    // No legacy contract configured yet
+    assert!(!client.verify_snapshot(&7, &create_test_hash(&env, 7)));
+
+    let legacy = env.register_contract(None, LegacySnapshotContract);
+    assert_eq!(
+        client.try_set_legacy_contract(&Address::generate(&env), &Some(legacy.clone())),
+        Err(Ok(Error::UnauthorizedCaller))
+    );
+    client.set_legacy_contract(&admin, &Some(legacy.clone()));
+    assert_eq!(client.get_legacy_contract(), Some(legacy));
+
+    assert!(client.verify_snapshot(&7, &create_test_hash(&env, 7)));
+    assert!(!client.verify_snapshot(&7, &create_test_hash(&env, 8)));
+    assert!(!client.verify_snapshot(&8, &create_test_hash(&env, 7)));
+
+    // A legacy address that is not a contract fails closed
+    client.set_legacy_contract(&admin, &Some(Address::generate(&env)));
+    assert!(!client.verify_snapshot(&7, &create_test_hash(&env, 7)));
+
+    client.set_legacy_contract(&admin, &None);
+    assert_eq!(client.get_legacy_contract(), None);
+    assert!(!client.verify_snapshot(&7, &create_test_hash(&env, 7)));
+}
+
+// ============================================================================
+// Submitter Role Tests
+// ============================================================================
+
+#[test]
+fn test_submitter_role() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StellarInsightsContract);
+    let client = StellarInsightsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let generator = Address::generate(&env);
+    client.initialize(&admin);
+    assert_eq!(client.list_submitters().len(), 0);
+
+    // Not a submitter yet
+    let result = client.try_submit_snapshot(&1, &create_test_hash(&env, 1), &generator);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    // Only the admin manages the list
+    assert_eq!(
+        client.try_add_submitter(&generator, &generator),
+        Err(Ok(Error::UnauthorizedCaller))
+    );
+    client.add_submitter(&admin, &generator);
+    assert_eq!(
+        client.try_add_submitter(&admin, &generator),
+        Err(Ok(Error::SubmitterAlreadyExists))
+    );
+    assert_eq!(
+        client.list_submitters(),
+        soroban_sdk::vec![&env, generator.clone()]
+    );
+
+    client.submit_snapshot(&1, &create_test_hash(&env, 1), &generator);
+    client.set_full_snapshot_interval(&admin, &4);
+    client.submit_delta_snapshot(
+        &2,
+        &create_test_hash(&env, 2),
+        &create_test_hash(&env, 1002),
+        &generator,
+    );
+    assert_eq!(client.get_latest_epoch(), 2);
+
+    // Submitters get no admin powers
+    assert_eq!(
+        client.try_pause(&generator),
+        Err(Ok(Error::UnauthorizedCaller))
+    );
+
+    assert_eq!(
+        client.try_remove_submitter(&generator, &generator),
+        Err(Ok(Error::UnauthorizedCaller))
+    );
+    client.remove_submitter(&admin, &generator);
+    assert_eq!(
+        client.try_remove_submitter(&admin, &generator),
+        Err(Ok(Error::SubmitterNotFound))
+    );
+    assert_eq!(client.list_submitters().len(), 0);
+    let result = client.try_submit_snapshot(&3, &create_test_hash(&env, 3), &generator);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    // The admin can always submit
+    client.submit_snapshot(&3, &create_test_hash(&env, 3), &admin);
+}
+
+#[test]
+fn test_dispute_window() {
+    use soroban_sdk::testutils::Ledger;
+
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StellarInsightsContract);
+    let client = StellarInsightsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let auditor = Address::generate(&env);
+    client.initialize(&admin);
+    client.add_submitter(&admin, &auditor);
+
+    // Disabled by default
+    client.submit_snapshot(&1, &create_test_hash(&env, 1), &admin);
+    assert_eq!(
+        client.try_dispute_snapshot(&auditor, &1),
+        Err(Ok(Error::DisputesDisabled))
+    );
+
+    client.set_dispute_window(&admin, &3600);
+    assert_eq!(client.get_dispute_window(), 3600);
+    // Submitted while disabled, so there is nothing to dispute against
+    assert_eq!(
+        client.try_dispute_snapshot(&auditor, &1),
+        Err(Ok(Error::DisputeWindowClosed))
+    );
+
+    client.submit_snapshot(&2, &create_test_hash(&env, 2), &admin);
+    assert_eq!(
+        client.try_dispute_snapshot(&admin, &2),
+        Err(Ok(Error::CannotDisputeOwnSnapshot))
+    );
+    assert_eq!(
+        client.try_dispute_snapshot(&Address::generate(&env), &2),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.dispute_snapshot(&auditor, &2);
+    assert_eq!(client.get_snapshot_status(&2), SnapshotStatus::Disputed);
+    assert_eq!(
+        client.try_dispute_snapshot(&auditor, &2),
+        Err(Ok(Error::SnapshotAlreadyDisputed))
+    );
+
+    // The disputed hash is held back until resolved
+    assert_eq!(client.latest_snapshot().1, 1);
+    assert_eq!(client.get_latest_epoch(), 2);
+
+    assert_eq!(
+        client.try_resolve_dispute(&auditor, &2, &true),
+        Err(Ok(Error::UnauthorizedCaller))
+    );
+    assert_eq!(
+        client.resolve_dispute(&admin, &2, &true),
+        SnapshotStatus::Accepted
+    );
+    assert_eq!(client.latest_snapshot().1, 2);
+    assert_eq!(
+        client.try_resolve_dispute(&admin, &2, &true),
+        Err(Ok(Error::SnapshotNotDisputed))
+    );
+
+    client.submit_snapshot(&3, &create_test_hash(&env, 3), &auditor);
+    client.dispute_snapshot(&admin, &3);
+    assert_eq!(
+        client.resolve_dispute(&admin, &3, &false),
+        SnapshotStatus::Rejected
+    );
+    assert_eq!(client.latest_snapshot().1, 2);
+    assert_eq!(client.get_snapshot_status(&3), SnapshotStatus::Rejected);
+
+    client.submit_snapshot(&4, &create_test_hash(&env, 4), &admin);
+    env.ledger().with_mut(|ledger| ledger.timestamp += 3601);
+    assert_eq!(
+        client.try_dispute_snapshot(&auditor, &4),
+        Err(Ok(Error::DisputeWindowClosed))
+    );
+    assert_eq!(
+        client.try_get_snapshot_status(&9),
+        Err(Ok(Error::SnapshotNotFound))
+    );
+}
+
+#[test]
+fn test_disputed_snapshot_holds_back_its_deltas() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StellarInsightsContract);
+    let client = StellarInsightsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let auditor = Address::generate(&env);
+    client.initialize(&admin);
+    client.add_submitter(&admin, &auditor);
+    client.set_dispute_window(&admin, &3600);
+    client.set_full_snapshot_interval(&admin, &4);
+
+    let materialized = create_test_hash(&env, 1002);
+    client.submit_snapshot(&1, &create_test_hash(&env, 1), &admin);
+    client.submit_delta_snapshot(&2, &create_test_hash(&env, 2), &materialized, &admin);
+    client.submit_snapshot(&3, &create_test_hash(&env, 3), &auditor);
+    client.submit_delta_snapshot(
+        &4,
+        &create_test_hash(&env, 4),
+        &create_test_hash(&env, 1004),
+        &auditor,
+    );
+    assert_eq!(client.latest_snapshot().1, 4);
+
+    // Delta 4 materializes on top of the disputed snapshot, so it is held back too
+    client.dispute_snapshot(&admin, &3);
+    assert_eq!(client.latest_snapshot().0, materialized);
+    assert_eq!(client.latest_snapshot().1, 2);
+
+    client.resolve_dispute(&admin, &3, &true);
+    assert_eq!(client.latest_snapshot().1, 4);
+}
+
+#[test]
+fn test_disputed_and_rejected_snapshots_do_not_verify() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StellarInsightsContract);
+    let client = StellarInsightsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let auditor = Address::generate(&env);
+    client.initialize(&admin);
+    client.add_submitter(&admin, &auditor);
+    client.set_dispute_window(&admin, &3600);
+    client.set_full_snapshot_interval(&admin, &4);
+
+    let disputed = create_test_hash(&env, 1);
+    let materialized = create_test_hash(&env, 1002);
+    let rejected = create_test_hash(&env, 3);
+    client.submit_snapshot(&1, &disputed, &admin);
+    client.submit_delta_snapshot(&2, &create_test_hash(&env, 2), &materialized, &admin);
+    client.submit_snapshot(&3, &rejected, &admin);
+    assert!(client.verify_snapshot(&1, &disputed));
+    assert!(client.verify_snapshot(&2, &materialized));
+
+    // A disputed snapshot and the deltas on top of it stop verifying until upheld
+    client.dispute_snapshot(&auditor, &1);
+    assert!(!client.verify_snapshot(&1, &disputed));
+    assert!(!client.verify_snapshot(&2, &materialized));
+    client.resolve_dispute(&admin, &1, &true);
+    assert!(client.verify_snapshot(&1, &disputed));
+    assert!(client.verify_snapshot(&2, &materialized));
+
+    client.dispute_snapshot(&auditor, &3);
+    assert!(!client.verify_snapshot(&3, &rejected));
+    client.resolve_dispute(&admin, &3, &false);
+    assert!(!client.verify_snapshot(&3, &rejected));
+}
+
+#[test]
+fn test_get_snapshots_range() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StellarInsightsContract);
+    let client = StellarInsightsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    assert_eq!(client.get_snapshots(&1, &10, &10).len(), 0);
+
+    for epoch in [1u64, 2, 4, 5, 7] {
+        client.submit_snapshot(&epoch, &create_test_hash(&env, epoch as u32), &admin);
+    }
+    client.set_full_snapshot_interval(&admin, &2);
+    client.submit_delta_snapshot(
+        &8,
+        &create_test_hash(&env, 8),
+        &create_test_hash(&env, 1008),
+        &admin,
+    );
+
+    let range = client.get_snapshots(&2, &8, &10);
+    let epochs: std::vec::Vec<u64> = range.iter().map(|(epoch, _, _)| epoch).collect();
+    assert_eq!(epochs, [2, 4, 5, 7]);
+    let (epoch, hash, timestamp) = range.get(1).unwrap();
+    assert_eq!((epoch, hash), (4, create_test_hash(&env, 4)));
+    assert_eq!(timestamp, env.ledger().timestamp());
+
+    // Limited, then paged from the last epoch returned
+    let first_page = client.get_snapshots(&1, &u64::MAX, &2);
+    assert_eq!(first_page.len(), 2);
+    assert_eq!(first_page.get(1).unwrap().0, 2);
+    let next_page = client.get_snapshots(&3, &u64::MAX, &2);
+    assert_eq!(next_page.get(0).unwrap().0, 4);
+
+    assert_eq!(
+        client.try_get_snapshots(&5, &4, &10),
+        Err(Ok(Error::InvalidEpoch))
+    );
+}
+
+#[test]
+fn test_epoch_clock_rejects_skewed_epochs() {
+    use soroban_sdk::testutils::Ledger;
+
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StellarInsightsContract);
+    let client = StellarInsightsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+    assert_eq!(client.get_epoch_clock(), None);
+
+    // Daily epochs from genesis 1_000_000, five minutes of skew allowed
+    let clock = EpochClock {
+        genesis: 1_000_000,
+        duration: 86_400,
+        max_skew: 300,
+    };
+    assert_eq!(
+        client.try_set_epoch_clock(
+            &admin,
+            &Some(EpochClock {
+                duration: 0,
+                ..clock.clone()
+            })
+        ),
+        Err(Ok(Error::InvalidEpochClock))
+    );
+    assert_eq!(
+        client.try_set_epoch_clock(&Address::generate(&env), &Some(clock.clone())),
+        Err(Ok(Error::UnauthorizedCaller))
+    );
+    client.set_epoch_clock(&admin, &Some(clock.clone()));
+    assert_eq!(client.get_epoch_clock(), Some(clock));
+
+    // Epoch 10 spans [1_864_000, 1_950_400); submitting just after it ends is fine
+    let epoch_10_end = 1_000_000 + 11 * 86_400;
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp = epoch_10_end + 200);
+    client.submit_snapshot(&10, &create_test_hash(&env, 10), &admin);
+
+    // A generator that thinks it is a week ahead
+    assert_eq!(
+        client.try_submit_snapshot(&18, &create_test_hash(&env, 18), &admin),
+        Err(Ok(Error::EpochTimestampMismatch))
+    );
+    client.set_full_snapshot_interval(&admin, &4);
+    assert_eq!(
+        client.try_submit_delta_snapshot(
+            &18,
+            &create_test_hash(&env, 18),
+            &create_test_hash(&env, 1018),
+            &admin
+        ),
+        Err(Ok(Error::EpochTimestampMismatch))
+    );
+
+    // Starting slightly early is within the skew
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp = 1_000_000 + 12 * 86_400 - 100);
+    client.submit_snapshot(&12, &create_test_hash(&env, 12), &admin);
+
+    // Far behind the ledger clock, e.g. a stale epoch counter
+    env.ledger()
+        .with_mut(|ledger| ledger.timestamp = 1_000_000 + 30 * 86_400);
+    let results = client.submit_snapshots(
+        &soroban_sdk::vec![
+            &env,
+            (13u64, create_test_hash(&env, 13)),
+            (30u64, create_test_hash(&env, 30)),
+        ],
+        &admin,
+    );
+    assert_eq!(
+        results.get(0).unwrap().error,
+        Some(Error::EpochTimestampMismatch.code())
+    );
+    assert!(results.get(1).unwrap().accepted);
+
+    // Turning the clock off allows backfilling again
+    client.set_epoch_clock(&admin, &None);
+    client.set_epoch_policy(&admin, &EpochPolicy::AnyUnique);
+    client.submit_snapshot(&13, &create_test_hash(&env, 13), &admin);
+}
+
+/// The most recent event, as a one-element list so it compares by value
+fn last_event(
+    env: &Env,
+) -> soroban_sdk::Vec<(
+    Address,
+    soroban_sdk::Vec<soroban_sdk::Val>,
+    soroban_sdk::Val,
+)> {
+    let events = env.events().all();
+    events.slice(events.len() - 1..)
+}
+
+#[test]
+fn test_governance_events() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StellarInsightsContract);
+    let client = StellarInsightsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let generator = Address::generate(&env);
+
+    client.initialize(&admin);
+    let admin_set = AdminSet {
+        previous: None,
+        admin: admin.clone(),
+        timestamp: env.ledger().timestamp(),
+    };
+    assert_eq!(
+        last_event(&env),
+        soroban_sdk::vec![
+            &env,
+            (
+                contract_id.clone(),
+                (ADMIN_SET, GOVERNANCE).into_val(&env),
+                admin_set.into_val(&env),
+            )
+        ]
+    );
+
+    let change = SubmitterChanged {
+        submitter: generator.clone(),
+        admin: admin.clone(),
+        timestamp: env.ledger().timestamp(),
+    };
+    client.add_submitter(&admin, &generator);
+    assert_eq!(
+        last_event(&env),
+        soroban_sdk::vec![
+            &env,
+            (
+                contract_id.clone(),
+                (Symbol::new(&env, SUBMITTER_ADD), GOVERNANCE).into_val(&env),
+                change.clone().into_val(&env),
+            )
+        ]
+    );
+
+    client.remove_submitter(&admin, &generator);
+    assert_eq!(
+        last_event(&env),
+        soroban_sdk::vec![
+            &env,
+            (
+                contract_id,
+                (Symbol::new(&env, SUBMITTER_REM), GOVERNANCE).into_val(&env),
+                change.into_val(&env),
+            )
+        ]
+    );
+}
+
+/// SHA-256 of `hash` followed by `salt`, as `commit_snapshot` expects
+fn commitment_for(env: &Env, hash: &BytesN<32>, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = soroban_sdk::Bytes::from_array(env, &hash.to_array());
+    preimage.append(&soroban_sdk::Bytes::from_array(env, &salt.to_array()));
+    env.crypto().sha256(&preimage).into()
+}
+
+#[test]
+fn test_commit_reveal_submission() {
+    use soroban_sdk::testutils::Ledger;
+
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StellarInsightsContract);
+    let client = StellarInsightsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let rival = Address::generate(&env);
+    client.initialize(&admin);
+    client.add_submitter(&admin, &rival);
+    client.set_reveal_delay(&admin, &3);
+    assert_eq!(client.get_reveal_delay(), 3);
+
+    let hash = create_test_hash(&env, 5);
+    let salt = create_test_hash(&env, 0xdead_beef);
+    client.commit_snapshot(&admin, &5, &commitment_for(&env, &hash, &salt));
+    assert_eq!(client.get_commitment(&5).unwrap().committer, admin);
+
+    // Nobody can take the epoch while the commitment is open
+    assert_eq!(
+        client.try_submit_snapshot(&5, &create_test_hash(&env, 66), &rival),
+        Err(Ok(Error::EpochCommitted))
+    );
+    let results = client.submit_snapshots(
+        &soroban_sdk::vec![&env, (5u64, create_test_hash(&env, 66))],
+        &rival,
+    );
+    assert_eq!(
+        results.get(0).unwrap().error,
+        Some(Error::EpochCommitted.code())
+    );
+    assert_eq!(
+        client.try_commit_snapshot(&rival, &5, &create_test_hash(&env, 1)),
+        Err(Ok(Error::EpochCommitted))
+    );
+
+    assert_eq!(
+        client.try_reveal_snapshot(&admin, &5, &hash, &salt),
+        Err(Ok(Error::RevealTooEarly))
+    );
+    env.ledger().with_mut(|ledger| ledger.sequence_number += 3);
+    assert_eq!(
+        client.try_reveal_snapshot(&rival, &5, &hash, &salt),
+        Err(Ok(Error::UnauthorizedCaller))
+    );
+    assert_eq!(
+        client.try_reveal_snapshot(&admin, &5, &create_test_hash(&env, 6), &salt),
+        Err(Ok(Error::CommitmentMismatch))
+    );
+
+    client.reveal_snapshot(&admin, &5, &hash, &salt);
+    assert_eq!(client.get_snapshot(&5), hash);
+    assert_eq!(client.get_latest_epoch(), 5);
+    assert_eq!(client.get_commitment(&5), None);
+    assert_eq!(
+        client.try_reveal_snapshot(&admin, &5, &hash, &salt),
+        Err(Ok(Error::CommitmentNotFound))
+    );
+
+    // An abandoned commitment can be withdrawn by the committer or the admin
+    client.commit_snapshot(&rival, &6, &create_test_hash(&env, 1));
+    assert_eq!(
+        client.try_cancel_commitment(&Address::generate(&env), &6),
+        Err(Ok(Error::UnauthorizedCaller))
+    );
+    client.cancel_commitment(&admin, &6);
+    client.submit_snapshot(&6, &create_test_hash(&env, 6), &admin);
+}
+
+#[test]
+fn test_unrevealed_commitment_expires() {
+    use soroban_sdk::testutils::Ledger;
+
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StellarInsightsContract);
+    let client = StellarInsightsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    client.initialize(&admin);
+
+    let hash = create_test_hash(&env, 7);
+    let salt = create_test_hash(&env, 0xdead_beef);
+    client.commit_snapshot(&admin, &7, &commitment_for(&env, &hash, &salt));
+    assert!(client.get_commitment(&7).is_some());
+
+    // Abandoned commitments do not hold the epoch forever
+    env.ledger()
+        .with_mut(|ledger| ledger.sequence_number += 1 + COMMITMENT_REVEAL_WINDOW + 1);
+    assert_eq!(client.get_commitment(&7), None);
+    assert_eq!(
+        client.try_reveal_snapshot(&admin, &7, &hash, &salt),
+        Err(Ok(Error::CommitmentNotFound))
+    );
+    client.submit_snapshot(&7, &hash, &admin);
+}
+
+#[test]
+fn test_commitment_blocks_delta_at_epoch() {
+    use soroban_sdk::testutils::Ledger;
+
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let contract_id = env.register_contract(None, StellarInsightsContract);
+    let client = StellarInsightsContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let rival = Address::generate(&env);
+    client.initialize(&admin);
+    client.add_submitter(&admin, &rival);
+    client.set_full_snapshot_interval(&admin, &4);
+    client.submit_snapshot(&10, &create_test_hash(&env, 10), &admin);
+
+    let hash = create_test_hash(&env, 11);
+    let salt = create_test_hash(&env, 0xdead_beef);
+    client.commit_snapshot(&admin, &11, &commitment_for(&env, &hash, &salt));
+
+    // A delta must not take the committed epoch ahead of the reveal
+    assert_eq!(
+        client.try_submit_delta_snapshot(
+            &11,
+            &create_test_hash(&env, 66),
+            &create_test_hash(&env, 67),
+            &rival,
+        ),
+        Err(Ok(Error::EpochCommitted))
+    );
+
+    env.ledger().with_mut(|ledger| ledger.sequence_number += 1);
+    client.reveal_snapshot(&admin, &11, &hash, &salt);
+    assert_eq!(client.get_snapshot(&11), hash);
+}